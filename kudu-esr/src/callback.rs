@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Payload sent to the callback of a signing request once it has been signed.
+//!
+//! See the callback section of the ESR spec:
+//! <https://github.com/eosio-eps/EEPs/blob/master/EEPS/eep-7.md#callbacks>
+//!
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+use snafu::prelude::*;
+
+use kudu::{
+    Checksum256, JsonValue, PermissionLevel, PublicKey, Signature, TimePointSec, Transaction,
+    TransactionId,
+};
+
+use crate::signing_request::{
+    EncodeOptions, SigningRequest, SigningRequestError, TransactionHeader,
+    InvalidSignatureSnafu, InvalidSnafu, TransactionMismatchSnafu, TransactionSnafu,
+};
+
+
+/// Payload of the callback of a signing request.
+///
+/// It can be built by the signer once the request has been resolved and signed,
+/// and parsed by the backend receiving it, which can then check the signatures
+/// using [`CallbackPayload::recover_keys()`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallbackPayload {
+    /// Signatures of the transaction (`sig`, then `sig0`, `sig1`, etc.)
+    pub signatures: Vec<Signature>,
+    /// ID of the resolved transaction (`tx`)
+    pub tx: TransactionId,
+    /// Reference block num used when resolving the request (`rbn`)
+    pub ref_block_num: u16,
+    /// Reference block prefix used when resolving the request (`rid`)
+    pub ref_block_prefix: u32,
+    /// Expiration time used when resolving the request (`ex`)
+    pub expiration: TimePointSec,
+    /// Originating signing request, as an URI (`req`)
+    pub request: String,
+    /// Account and permission of the signer (`sa` and `sp`)
+    pub signer: PermissionLevel,
+    /// ID of the chain the transaction was resolved for (`cid`)
+    pub chain_id: Checksum256,
+    /// Block number hint, only present if the transaction was broadcast (`bn`)
+    pub block_num: Option<u32>,
+}
+
+impl CallbackPayload {
+    /// Create the callback payload for `request` once it has been resolved into `tx`
+    /// and signed. `tx` needs to have its chain ID set.
+    pub fn new(request: &SigningRequest, tx: &Transaction, signer: PermissionLevel,
+               signatures: Vec<Signature>, block_num: Option<u32>) -> Result<Self, SigningRequestError> {
+        let chain_id = tx.chain_id.clone().context(InvalidSnafu {
+            message: "resolved transaction needs to have its chain ID set"
+        })?;
+        ensure!(!signatures.is_empty(), InvalidSnafu { message: "callback payload needs at least one signature" });
+
+        Ok(CallbackPayload {
+            signatures,
+            tx: tx.id(),
+            ref_block_num: tx.ref_block_num,
+            ref_block_prefix: tx.ref_block_prefix,
+            expiration: tx.expiration,
+            request: request.encode_uri(&EncodeOptions::default())?,
+            signer,
+            chain_id,
+            block_num,
+        })
+    }
+
+    /// Parse the JSON payload received by a callback.
+    pub fn from_json(payload: &JsonValue) -> Result<Self, SigningRequestError> {
+        let mut signatures = vec![parse_field(payload, "sig")?];
+        for n in 0.. {
+            let key = format!("sig{n}");
+            if payload.get(&key).is_none() { break; }
+            signatures.push(parse_field(payload, &key)?);
+        }
+
+        Ok(CallbackPayload {
+            signatures,
+            tx: parse_field(payload, "tx")?,
+            ref_block_num: parse_field(payload, "rbn")?,
+            ref_block_prefix: parse_field(payload, "rid")?,
+            expiration: parse_field(payload, "ex")?,
+            request: field_str(payload, "req")?,
+            signer: PermissionLevel {
+                actor: parse_field(payload, "sa")?,
+                permission: parse_field(payload, "sp")?,
+            },
+            chain_id: parse_field(payload, "cid")?,
+            block_num: match payload.get("bn") {
+                Some(_) => Some(parse_field(payload, "bn")?),
+                None => None,
+            },
+        })
+    }
+
+    /// Return the fields of the payload as they are sent to the callback, which
+    /// are also the variables available in the callback URL template.
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        let mut result = BTreeMap::from([
+            ("tx".to_string(), self.tx.to_hex()),
+            ("rbn".to_string(), self.ref_block_num.to_string()),
+            ("rid".to_string(), self.ref_block_prefix.to_string()),
+            ("ex".to_string(), self.expiration.to_string()),
+            ("req".to_string(), self.request.clone()),
+            ("sa".to_string(), self.signer.actor.to_string()),
+            ("sp".to_string(), self.signer.permission.to_string()),
+            ("cid".to_string(), self.chain_id.to_hex()),
+        ]);
+        for (n, sig) in self.signatures.iter().enumerate() {
+            let key = match n {
+                0 => "sig".to_string(),
+                n => format!("sig{}", n - 1),
+            };
+            result.insert(key, sig.to_string());
+        }
+        if let Some(block_num) = self.block_num {
+            result.insert("bn".to_string(), block_num.to_string());
+        }
+        result
+    }
+
+    pub fn header(&self) -> TransactionHeader {
+        TransactionHeader {
+            expiration: self.expiration,
+            ref_block_num: self.ref_block_num,
+            ref_block_prefix: self.ref_block_prefix,
+        }
+    }
+
+    /// Decode the originating request and resolve it again using the chain, signer
+    /// and transaction header in this payload.
+    pub fn resolve_transaction(&self) -> Result<Transaction, SigningRequestError> {
+        let request = SigningRequest::from_uri(&self.request)?;
+        request.resolve_transaction(&self.chain_id, &self.signer, &self.header())
+    }
+
+    /// Check that the originating request resolves to the transaction in this payload,
+    /// and return the public keys that produced its signatures.
+    ///
+    /// It is then up to the caller to check that these keys satisfy the authority
+    /// of the signer on the chain.
+    pub fn recover_keys(&self) -> Result<Vec<PublicKey>, SigningRequestError> {
+        let tx = self.resolve_transaction()?;
+        let id = tx.id();
+        ensure!(id == self.tx, TransactionMismatchSnafu { expected: self.tx.clone(), actual: id });
+
+        let context_free_data = b"";  // TODO: support this
+        let digest = tx.sig_digest(context_free_data).context(TransactionSnafu)?;
+        self.signatures.iter()
+            .map(|sig| PublicKey::recover_from_digest(&digest, sig).context(InvalidSignatureSnafu))
+            .collect()
+    }
+}
+
+impl Serialize for CallbackPayload {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer
+    {
+        self.to_map().serialize(serializer)
+    }
+}
+
+fn field_str(payload: &JsonValue, key: &str) -> Result<String, SigningRequestError> {
+    match payload.get(key) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(n @ JsonValue::Number(_)) => Ok(n.to_string()),
+        Some(value) => InvalidSnafu { message: format!("invalid callback payload field `{key}`: {value}") }.fail(),
+        None => InvalidSnafu { message: format!("missing callback payload field `{key}`") }.fail(),
+    }
+}
+
+fn parse_field<T>(payload: &JsonValue, key: &str) -> Result<T, SigningRequestError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = field_str(payload, key)?;
+    value.parse().map_err(|e| InvalidSnafu {
+        message: format!("invalid callback payload field `{key}` = {value:?}: {e}")
+    }.build())
+}
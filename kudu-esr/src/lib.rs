@@ -15,3 +15,4 @@
 #![cfg_attr(feature = "float128", feature(f128))]
 
 pub mod signing_request;
+pub mod callback;
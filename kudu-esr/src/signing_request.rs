@@ -3,32 +3,38 @@
 
 use std::{backtrace::Backtrace, string::FromUtf8Error};
 use std::io::prelude::*;
+use std::collections::BTreeMap;
 use std::sync::OnceLock;
 use std::fmt;
 
 use base64::prelude::*;
 use flagset::{flags, FlagSet};
-use hex;
 use snafu::prelude::*;
 
-use flate2::read::DeflateDecoder;
+use flate2::{Compression, read::{DeflateDecoder, DeflateEncoder}};
 use serde::{Serialize, Serializer, ser::SerializeStruct};
 
 use kudu::{
-    impl_auto_error_conversion, json, with_location,
-    ABI, ABIDefinition, ABIError, Action, ActionError, ByteStream, Bytes, Checksum256, JsonValue, Name,
-    PermissionLevel, SerializeEnum, SerializeError, Transaction, TransactionError
+    bytes_to_hex, impl_auto_error_conversion, json, to_bin, with_location, convert::FromHexError,
+    ABI, ABIDefinition, ABIError, AccountName, Action, ActionError, ByteStream, Bytes, Checksum256,
+    InvalidCryptoData, JsonValue, Name, PermissionLevel, PermissionName, SerializeEnum, SerializeError,
+    TimePointSec, Transaction, TransactionError, TransactionId,
 };
 
+use crate::callback::CallbackPayload;
+
 use tracing::{trace, debug};
 
-pub const SIGNER_NAME: Name = Name::from_u64(1);
-pub const SIGNER_PERMISSION: Name = Name::from_u64(2);
+pub const SIGNER_NAME: AccountName = AccountName::from_u64(1);
+pub const SIGNER_PERMISSION: PermissionName = PermissionName::from_u64(2);
 pub const SIGNER_AUTH: PermissionLevel = PermissionLevel {
     actor: SIGNER_NAME,
     permission: SIGNER_PERMISSION
 };
 
+/// Key of the info pair containing the list of chains of a multi-chain request.
+pub const CHAIN_IDS_KEY: &str = "chain_ids";
+
 pub static SIGNING_REQUEST_ABI: &str = include_str!("signing_request_abi.json");
 
 pub fn get_signing_request_abi() -> &'static ABI {
@@ -51,6 +57,86 @@ pub enum ChainId {
     Id(Box<Checksum256>),
 }
 
+/// Chain aliases defined in the ESR spec as `(alias, name, chain ID)`, alias 0 is
+/// reserved for multi-chain requests.
+pub const CHAIN_ALIASES: &[(u8, &str, &str)] = &[
+    (1,  "EOS",      "aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906"),
+    (2,  "TELOS",    "4667b205c6838ef70ff7988f6e8257e8be0e1284a2f59699054a018f743b1d11"),
+    (3,  "JUNGLE",   "e70aaab8997e1dfce58fbfac80cbbb8fecec7b99cf982a9444273cbc64c41473"),
+    (4,  "KYLIN",    "5fff1dae8dc8e2fc4d5b23b2c7665c97f9e9d8edf2b6485a86ba311c25639191"),
+    (5,  "WORBLI",   "73647cde120091e0a4b85bced2f3cfdb3041e266cbbe95cee59b73235a1b3b6f"),
+    (6,  "BOS",      "d5a3d18fbb3c084e3b1f3fa98c21014b5f3db536cc15d08f9f6479517c6a3d86"),
+    (7,  "MEETONE",  "cfe6486a83bad4962f232d48003b1824ab5665c36778141034d75e57b956e422"),
+    (8,  "INSIGHTS", "b042025541e25a472bffde2d62edd457b7e70cee943412b1ea0f044f88591664"),
+    (9,  "BEOS",     "b912d19a6abd2b1b05611ae5be473355d64d95aeff0c09bedc8c166cd6468fe4"),
+    (10, "WAX",      "1064487b3cd1a897ce03ae5b6a865651747e2e152090f99c1d19d44e01aea5a4"),
+    (11, "PROTON",   "384da888112027f0321850a169f737c33e53b388aad48b5adace4bab97f437e0"),
+    (12, "FIO",      "21dcae42c0182200e93f954a074011f9048a7624c6fe81d3c9541a614a88bd1c"),
+];
+
+impl ChainId {
+    /// Return the `ChainId` for the given chain, using its alias if it has one.
+    ///
+    /// ```
+    /// # use kudu::Checksum256;
+    /// # use kudu_esr::signing_request::ChainId;
+    /// let eos = Checksum256::from_hex("aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906")?;
+    /// assert_eq!(ChainId::from_id(eos.clone()), ChainId::Alias(1));
+    /// assert_eq!(ChainId::Alias(1).to_id(), Some(eos));
+    /// assert_eq!(ChainId::Alias(1).name(), Some("EOS"));
+    /// # Ok::<(), kudu::convert::FromHexError>(())
+    /// ```
+    pub fn from_id(id: Checksum256) -> Self {
+        let hex = id.to_hex();
+        match CHAIN_ALIASES.iter().find(|(_, _, chain_id)| *chain_id == hex) {
+            Some((alias, _, _)) => ChainId::Alias(*alias),
+            None => ChainId::Id(Box::new(id)),
+        }
+    }
+
+    /// Return the full chain ID, or `None` if this is an unknown alias or the
+    /// multi-chain placeholder.
+    pub fn to_id(&self) -> Option<Checksum256> {
+        match self {
+            ChainId::Alias(alias) => CHAIN_ALIASES.iter()
+                .find(|(a, _, _)| a == alias)
+                .map(|(_, _, chain_id)| Checksum256::from_hex(chain_id).unwrap()),  // safe unwrap
+            ChainId::Id(id) => Some((**id).clone()),
+        }
+    }
+
+    /// Return the name of the chain if it is a known alias.
+    pub fn name(&self) -> Option<&'static str> {
+        let ChainId::Alias(alias) = self else { return None; };
+        CHAIN_ALIASES.iter().find(|(a, _, _)| a == alias).map(|(_, name, _)| *name)
+    }
+
+    pub fn is_multi_chain(&self) -> bool {
+        *self == ChainId::Alias(0)
+    }
+
+    fn from_json(chain_id: &JsonValue) -> Result<Self, SigningRequestError> {
+        let chain_id_type = conv_str("chain_id type", &chain_id[0])?;
+
+        Ok(match chain_id_type {
+            "chain_id" => {
+                let data = conv_str("chain_id data", &chain_id[1])?;
+                ChainId::Id(Box::new(Checksum256::from_hex(data).context(HexDecodeSnafu)?))
+            },
+            "chain_alias" => {
+                let alias = chain_id[1].as_u64().with_context(|| InvalidSnafu {
+                    message: format!("cannot convert chain alias \"{}\" to integer", chain_id[1])
+                })?;
+                let alias = u8::try_from(alias).map_err(|_| InvalidSnafu {
+                    message: format!("chain alias value {alias} doesn't fit in a `u8`")
+                }.build())?;
+                ChainId::Alias(alias)
+            },
+            other => InvalidSnafu { message: format!("unknown chain id type: {other}") }.fail()?,
+        })
+    }
+}
+
 
 // -----------------------------------------------------------------------------
 //     Request data enum - contains the data in the request
@@ -123,6 +209,7 @@ impl Default for SigningRequest {
 // -----------------------------------------------------------------------------
 
 // TODO: use builder pattern to create instances of this struct
+#[derive(Clone, Debug)]
 pub struct EncodeOptions {
     pub version: u8,
     pub use_compression: bool,
@@ -138,6 +225,19 @@ impl Default for EncodeOptions {
 }
 
 
+// -----------------------------------------------------------------------------
+//     TransactionHeader
+// -----------------------------------------------------------------------------
+
+/// Header fields chosen by the signer when resolving a request into a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionHeader {
+    pub expiration: TimePointSec,
+    pub ref_block_num: u16,
+    pub ref_block_prefix: u32,
+}
+
+
 impl SigningRequest {
     pub fn from_action(action: Action) -> Self {
         SigningRequest {
@@ -170,9 +270,11 @@ impl SigningRequest {
         })
     }
 
+    /// Decode a signing request from its URI, with either the `esr:` or the
+    /// `esr://` scheme.
     pub fn from_uri(uri: &str) -> Result<Self, SigningRequestError> {
-        ensure!(uri.starts_with("esr://"), InvalidURISnafu { uri });
-        let payload = &uri[6..];
+        let payload = uri.strip_prefix("esr://").or_else(|| uri.strip_prefix("esr:"))
+            .context(InvalidURISnafu { uri })?;
         Self::decode(payload)
     }
 
@@ -198,9 +300,9 @@ impl SigningRequest {
 
         }
         else {
-            dec2 = dec;
+            dec2 = dec[1..].to_vec();
         }
-        trace!("decompressed payload = {}", hex::encode(&dec2));
+        trace!("decompressed payload = {}", bytes_to_hex(&dec2));
 
 
         let abi = get_signing_request_abi();
@@ -228,6 +330,78 @@ impl SigningRequest {
         req
     }
 
+    pub fn with_chain_id(self, chain_id: ChainId) -> Self {
+        SigningRequest { chain_id, ..self }
+    }
+
+    /// Make this a multi-chain request which can be resolved for any of the given
+    /// chains, or for any chain if `chain_ids` is empty.
+    ///
+    /// ```
+    /// # use kudu_esr::signing_request::{ChainId, SigningRequest};
+    /// let req = SigningRequest::from_actions(vec![])
+    ///     .with_chain_ids(vec![ChainId::Alias(1), ChainId::Alias(10)])?;
+    ///
+    /// assert!(req.is_multi_chain());
+    /// assert_eq!(req.chain_ids()?, vec![ChainId::Alias(1), ChainId::Alias(10)]);
+    /// assert!(req.check_chain(&ChainId::Alias(10).to_id().unwrap()).is_ok());
+    /// assert!(req.check_chain(&ChainId::Alias(2).to_id().unwrap()).is_err());
+    /// # Ok::<(), kudu_esr::signing_request::SigningRequestError>(())
+    /// ```
+    pub fn with_chain_ids(self, chain_ids: Vec<ChainId>) -> Result<Self, SigningRequestError> {
+        let mut req = self;
+        req.chain_id = ChainId::Alias(0);
+        req.info.retain(|pair| pair["key"] != CHAIN_IDS_KEY);
+        if !chain_ids.is_empty() {
+            let mut ds = Bytes::new();
+            let abi = get_signing_request_abi();
+            abi.encode_variant(&mut ds, "variant_id[]", &json!(chain_ids)).context(ABISnafu)?;
+            req.info.push(json!({"key": CHAIN_IDS_KEY, "value": ds}));
+        }
+        Ok(req)
+    }
+
+    /// Return whether this request can be resolved for more than one chain.
+    pub fn is_multi_chain(&self) -> bool {
+        self.chain_id.is_multi_chain()
+    }
+
+    /// Return the chains for which this request can be resolved. This is the chain
+    /// of the request for single-chain requests, and the list of chains in the
+    /// `chain_ids` info field for multi-chain requests, where an empty list means
+    /// that any chain is accepted.
+    pub fn chain_ids(&self) -> Result<Vec<ChainId>, SigningRequestError> {
+        if !self.is_multi_chain() {
+            return Ok(vec![self.chain_id.clone()]);
+        }
+
+        let Some(pair) = self.info.iter().find(|pair| pair["key"] == CHAIN_IDS_KEY) else {
+            return Ok(vec![]);
+        };
+        let data = conv_str("chain_ids value", &pair["value"])?;
+        let data = Bytes::from_hex(data).context(HexDecodeSnafu)?;
+        let abi = get_signing_request_abi();
+        let chain_ids = abi.decode_variant(&mut ByteStream::from(&data), "variant_id[]").context(ABISnafu)?;
+        conv_array("chain_ids", &chain_ids)?.iter().map(ChainId::from_json).collect()
+    }
+
+    /// Check that this request can be resolved for the chain with the given ID.
+    pub fn check_chain(&self, chain_id: &Checksum256) -> Result<(), SigningRequestError> {
+        let chain_ids = self.chain_ids()?;
+        if self.is_multi_chain() && chain_ids.is_empty() {
+            return Ok(());
+        }
+
+        for allowed in &chain_ids {
+            match allowed.to_id() {
+                Some(id) if id == *chain_id => return Ok(()),
+                Some(_) => {},
+                None => InvalidSnafu { message: format!("unknown chain alias: {allowed:?}") }.fail()?,
+            }
+        }
+        ChainMismatchSnafu { chain_id: chain_id.clone() }.fail()
+    }
+
     pub fn with_broadcast(self, broadcast: bool) -> Self {
         let mut req = self;
         if broadcast { req.flags |= RequestFlags::Broadcast }
@@ -245,6 +419,150 @@ impl SigningRequest {
         abi.encode_variant(&mut ds, "signing_request", &sr).context(ABISnafu)?;
         Ok(ds)
     }
+
+    /// Encode this request as an `esr:` URI.
+    ///
+    /// Multi-chain requests are always encoded using version 3 of the protocol.
+    pub fn encode_uri(&self, options: &EncodeOptions) -> Result<String, SigningRequestError> {
+        let data = self.encode()?;
+
+        let version = match self.is_multi_chain() {
+            true => options.version.max(3),
+            false => options.version,
+        };
+        let mut payload = vec![version];
+        if options.use_compression {
+            payload[0] |= 1 << 7;
+            let mut deflater = DeflateEncoder::new(&data[..], Compression::best());
+            deflater.read_to_end(&mut payload).context(CompressSnafu)?;
+        }
+        else {
+            payload.extend_from_slice(&data);
+        }
+
+        Ok(format!("esr:{}", BASE64_URL_SAFE_NO_PAD.encode(payload)))
+    }
+
+    /// Resolve this request into the transaction to be signed by `signer` on the chain
+    /// with the given ID, replacing the [`SIGNER_NAME`] and [`SIGNER_PERMISSION`]
+    /// placeholders in the actions.
+    ///
+    /// This fails if the request cannot be resolved for that chain (see
+    /// [`SigningRequest::check_chain()`]).
+    ///
+    /// The transaction header is set from `header`, unless the request already
+    /// contains a full transaction with its header set.
+    ///
+    /// **NOTE:** placeholders in the action data are resolved by decoding it, which
+    ///           requires the ABI of the contract to be available in the registry.
+    pub fn resolve_transaction(&self, chain_id: &Checksum256, signer: &PermissionLevel,
+                               header: &TransactionHeader) -> Result<Transaction, SigningRequestError> {
+        self.check_chain(chain_id)?;
+
+        let mut tx = match &self.request {
+            Request::Action(action) => Transaction::new(vec![action.clone()]),
+            Request::Actions(actions) => Transaction::new(actions.clone()),
+            Request::Transaction(tx) => tx.clone(),
+            Request::Identity => UnsupportedSnafu { message: "cannot resolve identity requests" }.fail()?,
+        };
+
+        if tx.expiration == TimePointSec::from(0) && tx.ref_block_num == 0 && tx.ref_block_prefix == 0 {
+            tx.expiration = header.expiration;
+            tx.ref_block_num = header.ref_block_num;
+            tx.ref_block_prefix = header.ref_block_prefix;
+        }
+
+        for action in tx.context_free_actions.iter_mut().chain(tx.actions.iter_mut()) {
+            resolve_action(action, signer)?;
+        }
+
+        tx.chain_id = Some(chain_id.clone());
+        Ok(tx)
+    }
+
+    /// Return the callback URL of this request with its template variables
+    /// (eg: `{{sig}}`, `{{tx}}`, `{{bn}}`) replaced by the values in `payload`,
+    /// or `None` if the request has no callback.
+    ///
+    /// Variables which are not in the payload are replaced by an empty string.
+    ///
+    /// ```
+    /// # use kudu::{json, AccountName, PermissionLevel, PermissionName, PrivateKey};
+    /// # use kudu_esr::{callback::CallbackPayload, signing_request::*};
+    /// let req = SigningRequest::from_action_json(&json!({
+    ///     "account": "eosio.token",
+    ///     "name": "transfer",
+    ///     "authorization": [SIGNER_AUTH],
+    ///     "data": {"from": SIGNER_NAME, "to": "bar", "quantity": "1.000 EOS", "memo": ""},
+    /// }))?.with_callback("https://example.com/?tx={{tx}}&bn={{bn}}", true);
+    ///
+    /// // resolve and sign the request
+    /// let signer = PermissionLevel { actor: AccountName::new("foo")?, permission: PermissionName::new("active")? };
+    /// let chain_id = ChainId::Alias(1).to_id().unwrap();
+    /// let tx = req.resolve_transaction(&chain_id, &signer, &TransactionHeader::default())?;
+    /// let sig = PrivateKey::eosio_dev().sign_digest(tx.sig_digest(b"")?);
+    ///
+    /// let payload = CallbackPayload::new(&req, &tx, signer, vec![sig], None)?;
+    /// assert_eq!(req.callback_url(&payload).unwrap(),
+    ///            format!("https://example.com/?tx={}&bn=", tx.id().to_hex()));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn callback_url(&self, payload: &CallbackPayload) -> Option<String> {
+        self.callback.as_ref().map(|url| fill_template(url, &payload.to_map()))
+    }
+}
+
+fn resolve_action(action: &mut Action, signer: &PermissionLevel) -> Result<(), SigningRequestError> {
+    for auth in action.authorization.iter_mut() {
+        if auth.actor == SIGNER_NAME { auth.actor = signer.actor; }
+        if auth.permission == SIGNER_PERMISSION { auth.permission = signer.permission; }
+    }
+
+    // only decode the action data if it might contain a placeholder
+    let placeholders = [to_bin(&SIGNER_NAME), to_bin(&SIGNER_PERMISSION)];
+    if placeholders.iter().any(|p| action.data.windows(p.len()).any(|w| w == &p[..])) {
+        let mut data = action.decode_data().context(ABISnafu)?;
+        resolve_placeholders(&mut data, signer);
+        *action = action.clone().with_data(&data).context(ActionSnafu)?;
+    }
+    Ok(())
+}
+
+fn resolve_placeholders(value: &mut JsonValue, signer: &PermissionLevel) {
+    match value {
+        JsonValue::String(s) => {
+            let name = s.parse::<Name>().ok();
+            if name == Some(SIGNER_NAME.as_name()) { *s = signer.actor.to_string(); }
+            else if name == Some(SIGNER_PERMISSION.as_name()) { *s = signer.permission.to_string(); }
+        },
+        JsonValue::Array(values) => values.iter_mut().for_each(|v| resolve_placeholders(v, signer)),
+        JsonValue::Object(fields) => fields.values_mut().for_each(|v| resolve_placeholders(v, signer)),
+        _ => {},
+    }
+}
+
+/// Replace the `{{key}}` variables in `template` with their value in `values`.
+fn fill_template(template: &str, values: &BTreeMap<String, String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let var = &rest[start + 2..];
+        match var.find("}}") {
+            Some(end) if end > 0 && var[..end].bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()) => {
+                if let Some(value) = values.get(&var[..end]) {
+                    result.push_str(value);
+                }
+                rest = &var[end + 2..];
+            },
+            _ => {
+                result.push_str("{{");
+                rest = var;
+            },
+        }
+    }
+    result.push_str(rest);
+    result
 }
 
 
@@ -283,26 +601,9 @@ impl SigningRequest {
 
     fn try_from_json(payload: JsonValue) -> Result<Self, SigningRequestError> {
         // FIXME: this would be better as `serde::Deserialize`, right?
-        let mut result = SigningRequest::default();
-
-        let chain_id = &payload["chain_id"];
-        let chain_id_type = conv_str("chain_id type", &chain_id[0])?;
-
-        result.chain_id = match chain_id_type {
-            "chain_id" => {
-                let data = conv_str("chain_id data", &chain_id[1])?;
-                ChainId::Id(Box::new(Checksum256::from_hex(data).context(HexDecodeSnafu)?))
-            },
-            "chain_alias" => {
-                let alias = chain_id[1].as_u64().with_context(|| InvalidSnafu {
-                    message: format!("cannot convert chain alias \"{}\" to integer", chain_id[1])
-                })?;
-                let alias = u8::try_from(alias).map_err(|_| InvalidSnafu {
-                    message: format!("chain alias value {alias} doesn't fit in a `u8`")
-                }.build())?;
-                ChainId::Alias(alias)
-            },
-            _ => unimplemented!(),
+        let mut result = SigningRequest {
+            chain_id: ChainId::from_json(&payload["chain_id"])?,
+            ..Default::default()
         };
 
         let req_type = conv_str("request type", &payload["req"][0])?;
@@ -318,7 +619,9 @@ impl SigningRequest {
                     .collect();
                 Request::Actions(actions.context(ActionSnafu)?)
             },
-            _ => unimplemented!(),
+            "transaction" => Request::Transaction(Transaction::from_json(req_data).context(TransactionSnafu)?),
+            "identity" => UnsupportedSnafu { message: "identity requests are not supported yet" }.fail()?,
+            other => InvalidSnafu { message: format!("unknown request type: {other}") }.fail()?,
         };
 
         let flags = payload["flags"].as_u64().with_context(|| InvalidSnafu {
@@ -344,6 +647,7 @@ impl SigningRequest {
 
 #[with_location]
 #[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
 pub enum SigningRequestError {
     #[snafu(display("{message}"))]
     Invalid {
@@ -367,6 +671,16 @@ pub enum SigningRequestError {
         source: std::io::Error,
     },
 
+    #[snafu(display("can not compress (deflate) payload data"))]
+    Compress {
+        source: std::io::Error,
+    },
+
+    #[snafu(display("unsupported request: {message}"))]
+    Unsupported {
+        message: String,
+    },
+
     #[snafu(display("error decoding base64 content: {content}"))]
     Base64Decode {
         content: String,
@@ -390,7 +704,7 @@ pub enum SigningRequestError {
 
     #[snafu(display("hex decoding error"))]
     HexDecode {
-        source: hex::FromHexError,
+        source: FromHexError,
     },
 
     #[snafu(display("ABI error"))]
@@ -398,6 +712,23 @@ pub enum SigningRequestError {
         source: ABIError,
     },
 
+    #[snafu(display("request cannot be resolved for chain {}", chain_id.to_hex()))]
+    ChainMismatch {
+        chain_id: Checksum256,
+    },
+
+    #[snafu(display("invalid signature"))]
+    InvalidSignature {
+        source: InvalidCryptoData,
+    },
+
+    #[snafu(display("callback payload is for transaction {} but request resolves to {}",
+                    expected.to_hex(), actual.to_hex()))]
+    TransactionMismatch {
+        expected: TransactionId,
+        actual: TransactionId,
+    },
+
     #[snafu(display("Invalid UTF-8 string"))]
     FromUtf8 {
         source: FromUtf8Error,
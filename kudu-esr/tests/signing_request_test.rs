@@ -10,8 +10,11 @@ use tracing_subscriber::{
 };
 use color_eyre::{Result, eyre::bail};
 
-use kudu::{Action, Name, abi, json};
-use kudu_esr::signing_request::*;
+use kudu::{
+    AccountName, Action, ActionName, Checksum256, Name, PermissionLevel, PermissionName,
+    PrivateKey, PublicKey, abi, json,
+};
+use kudu_esr::{callback::CallbackPayload, signing_request::*};
 
 
 //
@@ -59,8 +62,8 @@ fn encode() -> Result<()> {
 
     let abi = abi::registry::get_abi("eosio")?;
     let actions2 = vec![Action {
-        account: Name::constant("eosio"),
-        name: Name::constant("voteproducer"),
+        account: AccountName::constant("eosio"),
+        name: ActionName::constant("voteproducer"),
         authorization: vec![SIGNER_AUTH],
         data: abi.variant_to_binary("voteproducer", &json!({
             "voter": SIGNER_NAME,
@@ -349,3 +352,138 @@ fn create_from_uri() -> Result<()> {
 
     Ok(())
 }
+
+
+#[test]
+fn encode_uri() -> Result<()> {
+    init();
+
+    let uri = "esr://gmNgZGBY1mTC_MoglIGBIVzX5uxZRqAQGMBoExgDAjRi4fwAVz93ICUckpGYl12skJZfpFCSkaqQllmcwczAAAA";
+    let req = SigningRequest::from_uri(uri)?;
+
+    for use_compression in [true, false] {
+        let options = EncodeOptions { use_compression, ..Default::default() };
+        let encoded = req.encode_uri(&options)?;
+        assert!(encoded.starts_with("esr:"));
+        assert_eq!(json!(SigningRequest::from_uri(&encoded)?), json!(req));
+    }
+
+    assert!(SigningRequest::from_uri("https://example.com").is_err());
+
+    Ok(())
+}
+
+
+#[test]
+fn callback() -> Result<()> {
+    init();
+
+    let req = SigningRequest::from_action_json(&json!({
+        "account": "eosio.token",
+        "name": "transfer",
+        "authorization": [SIGNER_AUTH],
+        "data": {"from": SIGNER_NAME, "to": "bar", "quantity": "1.000 EOS", "memo": "hello there"},
+    }))?.with_callback("https://example.com/cb?sig={{sig}}&sig0={{sig0}}&tx={{tx}}&x={{foo}}&y={{Z}}", true);
+
+    // resolve the request, placeholders should be replaced by the signer
+    let signer = PermissionLevel {
+        actor: AccountName::new("foo")?,
+        permission: PermissionName::new("active")?,
+    };
+    let header = TransactionHeader {
+        expiration: "2020-07-10T08:40:20".parse()?,
+        ref_block_num: 1234,
+        ref_block_prefix: 56789,
+    };
+    let chain_id = Checksum256::from_hex("aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906")?;
+    let tx = req.resolve_transaction(&chain_id, &signer, &header)?;
+    assert_eq!(tx.ref_block_num, 1234);
+    assert_eq!(tx.actions[0].authorization, vec![signer]);
+    assert_eq!(tx.actions[0].decode_data()?["from"], "foo");
+
+    // sign it twice and build the callback payload
+    let keys = [PrivateKey::eosio_dev(), PrivateKey::new("5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss")?];
+    let sigs = keys.iter().map(|k| Ok(k.sign_digest(tx.sig_digest(b"")?))).collect::<Result<Vec<_>>>()?;
+    let payload = CallbackPayload::new(&req, &tx, signer, sigs.clone(), Some(42))?;
+
+    let url = req.callback_url(&payload).unwrap();
+    assert_eq!(url, format!("https://example.com/cb?sig={}&sig0={}&tx={}&x=&y={{{{Z}}}}",
+                            sigs[0], sigs[1], tx.id().to_hex()));
+
+    // a backend receiving the payload can check who signed it
+    let received = json!(payload);
+    assert_eq!(received["bn"], "42");
+    assert_eq!(received["sa"], "foo");
+    let received = CallbackPayload::from_json(&received)?;
+    assert_eq!(received, payload);
+    assert_eq!(received.recover_keys()?, keys.iter().map(PublicKey::from_private_key).collect::<Vec<_>>());
+
+    // tampering with the payload is detected
+    let mut tampered = payload.clone();
+    tampered.signer.actor = AccountName::new("bar")?;
+    assert!(matches!(tampered.recover_keys(), Err(SigningRequestError::TransactionMismatch { .. })));
+
+    assert!(CallbackPayload::from_json(&json!({"sig": sigs[0]})).is_err());
+
+    Ok(())
+}
+
+
+#[test]
+fn chain_ids() -> Result<()> {
+    init();
+
+    let eos = Checksum256::from_hex("aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906")?;
+    let wax = Checksum256::from_hex("1064487b3cd1a897ce03ae5b6a865651747e2e152090f99c1d19d44e01aea5a4")?;
+    let other = Checksum256::from_hex("73e4385a2708e6d7048834fbc1079f2fabb17b3c125b146af438971e90716c4d")?;
+
+    assert_eq!(ChainId::from_id(wax.clone()), ChainId::Alias(10));
+    assert_eq!(ChainId::from_id(other.clone()), ChainId::Id(Box::new(other.clone())));
+    assert_eq!(ChainId::Alias(10).name(), Some("WAX"));
+    assert_eq!(ChainId::Alias(0).to_id(), None);
+
+    let signer = PermissionLevel {
+        actor: AccountName::new("foo")?,
+        permission: PermissionName::new("active")?,
+    };
+    let header = TransactionHeader::default();
+
+    // single-chain requests, with an alias or a full chain ID
+    let req = SigningRequest::from_actions(vec![]);
+    assert!(!req.is_multi_chain());
+    assert_eq!(req.chain_ids()?, vec![ChainId::Alias(1)]);
+    assert_eq!(req.resolve_transaction(&eos, &signer, &header)?.chain_id, Some(eos.clone()));
+    assert!(matches!(req.resolve_transaction(&wax, &signer, &header),
+                     Err(SigningRequestError::ChainMismatch { .. })));
+
+    let req = req.with_chain_id(ChainId::Id(Box::new(other.clone())));
+    assert!(req.check_chain(&other).is_ok());
+    assert!(req.check_chain(&eos).is_err());
+
+    let req = req.with_chain_id(ChainId::Alias(42));
+    assert!(matches!(req.check_chain(&eos), Err(SigningRequestError::Invalid { .. })));
+
+    // multi-chain request for any chain
+    let req = SigningRequest::from_actions(vec![]).with_chain_ids(vec![])?;
+    assert!(req.is_multi_chain());
+    assert!(req.chain_ids()?.is_empty());
+    assert!(req.check_chain(&other).is_ok());
+
+    // multi-chain request for a list of chains, which survives encoding
+    let chain_ids = vec![ChainId::Alias(1), ChainId::Id(Box::new(other.clone()))];
+    let req = SigningRequest::from_actions(vec![]).with_chain_ids(chain_ids.clone())?;
+    let uri = req.encode_uri(&EncodeOptions::default())?;
+    let decoded = SigningRequest::from_uri(&uri)?;
+    assert_eq!(decoded.chain_ids()?, chain_ids);
+    assert!(decoded.check_chain(&eos).is_ok());
+    assert!(decoded.check_chain(&other).is_ok());
+    assert!(matches!(decoded.resolve_transaction(&wax, &signer, &header),
+                     Err(SigningRequestError::ChainMismatch { .. })));
+
+    // setting the chains again replaces the previous list
+    let req = decoded.with_chain_ids(vec![ChainId::Alias(10)])?;
+    assert_eq!(req.info.len(), 1);
+    assert_eq!(req.chain_ids()?, vec![ChainId::Alias(10)]);
+
+    Ok(())
+}
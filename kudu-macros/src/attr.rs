@@ -3,7 +3,7 @@
 
 use heck::ToSnakeCase;
 use proc_macro2::Ident;
-use syn::{Attribute, LitStr, Result, Variant};
+use syn::{Attribute, Field, LitStr, Meta, Path, Result, Token, Variant, punctuated::Punctuated};
 
 /// Find the value of a #[serde(rename = "...")] attribute.
 fn attr_rename(attrs: &[Attribute]) -> Result<Option<String>> {
@@ -31,6 +31,72 @@ fn attr_rename(attrs: &[Attribute]) -> Result<Option<String>> {
     Ok(rename)
 }
 
+/// Find the path to the `kudu` crate given by a `#[kudu(crate = "...")]` container
+/// attribute, defaulting to `kudu`.
+pub fn crate_path(attrs: &[Attribute]) -> Result<Path> {
+    let mut path = None;
+
+    for attr in attrs {
+        if !attr.path().is_ident("kudu") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let s: LitStr = meta.value()?.parse()?;
+                if path.is_some() {
+                    return Err(meta.error("duplicate crate attribute"));
+                }
+                path = Some(s.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+    }
+
+    Ok(path.unwrap_or_else(|| syn::parse_quote!(kudu)))
+}
+
+/// Return whether a field is marked with the `#[kudu(base)]` attribute.
+pub fn is_base_field(field: &Field) -> Result<bool> {
+    let mut base = false;
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident("kudu") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("base") {
+                if base {
+                    return Err(meta.error("duplicate base attribute"));
+                }
+                base = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+    }
+
+    Ok(base)
+}
+
+/// Return whether a field is marked with the `#[serde(flatten)]` attribute.
+pub fn has_serde_flatten(field: &Field) -> Result<bool> {
+    for attr in &field.attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if metas.iter().any(|meta| meta.path().is_ident("flatten")) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Determine the name of a variant, respecting a rename attribute.
 pub fn snake_name_of_variant(var: &Variant) -> Result<String> {
     let rename = attr_rename(&var.attrs)?;
@@ -3,7 +3,7 @@
 
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Expr, ItemStruct, Lit, MetaNameValue, punctuated::Punctuated};
+use syn::{Expr, ItemStruct, Lit, MetaNameValue, Path, punctuated::Punctuated};
 
 /// control whether we want to have debugging information for the macro when compiling
 const DEBUG: bool = false;
@@ -15,9 +15,10 @@ macro_rules! debug {
 
 type KVPairs = Punctuated::<MetaNameValue, syn::Token![,]>;
 
-fn parse_contract_args(attrs: KVPairs) -> (String, String) {
+fn parse_contract_args(attrs: KVPairs) -> (String, String, Path) {
     let mut account = None;
     let mut name = None;
+    let mut krate = None;
 
     for kv in attrs {
         if kv.path.is_ident("account") {
@@ -41,10 +42,21 @@ fn parse_contract_args(attrs: KVPairs) -> (String, String) {
             } else {
                 panic!("'name' value should be a string");
             }
+        } else if kv.path.is_ident("crate") {
+            if krate.is_some() {
+                panic!("'crate' provided more than once");
+            }
+            let Expr::Lit(lit) = kv.value else { panic!("'crate' value should be a string"); };
+            if let Lit::Str(v) = lit.lit {
+                krate = Some(v.parse().expect("'crate' value should be a path"));
+            } else {
+                panic!("'crate' value should be a string");
+            }
         }
     }
+    let krate = krate.unwrap_or_else(|| syn::parse_quote!(kudu));
     match (account, name) {
-        (Some(account), Some(name)) => (account.value(), name.value()),
+        (Some(account), Some(name)) => (account.value(), name.value(), krate),
         (None, None) => panic!("missing both 'account' and 'name' attributes"),
         (None, _) => panic!("missing 'account' attribute"),
         (_, None) => panic!("missing 'name' attribute"),
@@ -54,7 +66,7 @@ fn parse_contract_args(attrs: KVPairs) -> (String, String) {
 pub fn add_contract_trait_impl(attrs: KVPairs, contract_struct: ItemStruct) -> TokenStream {
     debug!("attr: {:?}", &attrs);
 
-    let (account, name) = parse_contract_args(attrs);
+    let (account, name, krate) = parse_contract_args(attrs);
 
     let struct_name = &contract_struct.ident;
 
@@ -65,12 +77,12 @@ pub fn add_contract_trait_impl(attrs: KVPairs, contract_struct: ItemStruct) -> T
 
         #[doc(hidden)]
         const _: () = {
-            impl kudu::Contract for #struct_name {
-                fn account() -> kudu::AccountName {
-                    const { kudu::AccountName::constant(#account) }
+            impl #krate::Contract for #struct_name {
+                fn account() -> #krate::AccountName {
+                    const { #krate::AccountName::constant(#account) }
                 }
-                fn name() -> kudu::ActionName {
-                    const { kudu:: ActionName::constant(#name) }
+                fn name() -> #krate::ActionName {
+                    const { #krate::ActionName::constant(#name) }
                 }
             }
         };
@@ -53,21 +53,21 @@ pub fn contract(attr: TokenStream, annotated_item: TokenStream) -> TokenStream {
 }
 
 // the `kudu` crate re-exports this macro and adds documentation to it
-#[proc_macro_derive(ABISerializable)]
+#[proc_macro_derive(ABISerializable, attributes(kudu))]
 pub fn derive_abiserializable(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     serde::derive(&input).into()
 }
 
 // the `kudu` crate re-exports this macro and adds documentation to it
-#[proc_macro_derive(SerializeEnum, attributes(serde))]
+#[proc_macro_derive(SerializeEnum, attributes(serde, kudu))]
 pub fn derive_serialize_enum(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     serde::derive_serialize_enum(&input, false).into()
 }
 
 // the `kudu` crate re-exports this macro and adds documentation to it
-#[proc_macro_derive(SerializeEnumPrefixed, attributes(serde))]
+#[proc_macro_derive(SerializeEnumPrefixed, attributes(serde, kudu))]
 pub fn derive_serialize_enum_prefixed(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     serde::derive_serialize_enum(&input, true).into()
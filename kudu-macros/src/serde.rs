@@ -6,6 +6,7 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
     Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Ident, Result, Variant, Field,
+    GenericArgument, PathArguments, Type,
 };
 
 
@@ -26,7 +27,7 @@ macro_rules! debug {
 pub fn derive(input: &DeriveInput) -> TokenStream {
     match try_expand(input) {
         Ok(expanded) => expanded,
-        Err(error) => panic!("Error while using derive(ABISerializable): {}", error),
+        Err(error) => error.to_compile_error(),
     }
 }
 
@@ -46,6 +47,9 @@ fn try_expand(input: &DeriveInput) -> Result<TokenStream> {
 
 fn derive_abiserializable_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStream> {
     let ident = &input.ident;
+    let krate = attr::crate_path(&input.attrs)?;
+    // generic parameters need to have the `ABISerializable` bound declared on the struct itself
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     let fieldname = &fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
     let fieldtype = &fields.named.iter().map(|f| &f.ty).collect::<Vec<_>>();
@@ -53,19 +57,44 @@ fn derive_abiserializable_struct(input: &DeriveInput, fields: &FieldsNamed) -> R
     debug!("field names: {:?}", &fieldname);
     debug!("field types: {:?}", &fieldtype);
 
+    for ty in fieldtype {
+        check_abi_type(ty)?;
+    }
+
+    // the fields of the base struct are serialized first, so it can only be the first field,
+    // and they need to be inlined in JSON too, which only serde can do
+    for (i, field) in fields.named.iter().enumerate() {
+        if !attr::is_base_field(field)? {
+            continue;
+        }
+        if i > 0 {
+            return Err(Error::new_spanned(
+                field,
+                "only the first field of a struct can be marked with `#[kudu(base)]`",
+            ));
+        }
+        if !attr::has_serde_flatten(field)? {
+            return Err(Error::new_spanned(
+                field,
+                "a field marked with `#[kudu(base)]` also needs `#[serde(flatten)]` \
+                 so that the fields of the base struct are inlined in JSON",
+            ));
+        }
+    }
+
     Ok(quote! {
         #[doc(hidden)]
         const _: () = {
-            impl kudu::ABISerializable for #ident {
-                fn to_bin(&self, s: &mut kudu::Bytes) {
+            impl #impl_generics #krate::ABISerializable for #ident #ty_generics #where_clause {
+                fn to_bin(&self, s: &mut #krate::Bytes) {
                     #(
-                        self.#fieldname.to_bin(s);
+                        #krate::ABISerializable::to_bin(&self.#fieldname, s);
                     )*
                 }
-                fn from_bin(s: &mut kudu::ByteStream) -> ::core::result::Result<Self, kudu::SerializeError> {
-                    Ok(Self {
+                fn from_bin(s: &mut #krate::ByteStream) -> ::core::result::Result<Self, #krate::SerializeError> {
+                    ::core::result::Result::Ok(Self {
                         #(
-                            #fieldname: <#fieldtype>::from_bin(s)?,
+                            #fieldname: <#fieldtype as #krate::ABISerializable>::from_bin(s)?,
                         )*
                     })
                 }
@@ -74,6 +103,56 @@ fn derive_abiserializable_struct(input: &DeriveInput, fields: &FieldsNamed) -> R
     })
 }
 
+/// Check that a field type can be expressed as an ABI type.
+///
+/// `Option<T>` maps to the `T?` ABI type, which is encoded with a presence byte
+/// followed by the value. The ABI does not allow nesting optionals (`T??`), so
+/// `Option<Option<T>>` is rejected here, also when the inner `Option` is boxed
+/// or when it appears inside a `Vec`, an array or a `Box`.
+fn check_abi_type(ty: &Type) -> Result<()> {
+    match ty {
+        Type::Array(array) => check_abi_type(&array.elem),
+        Type::Group(group) => check_abi_type(&group.elem),
+        Type::Paren(paren) => check_abi_type(&paren.elem),
+        Type::Path(_) => {
+            let Some((wrapper, inner)) = generic_wrapper(ty) else { return Ok(()) };
+            if wrapper == "Option" && is_optional(inner) {
+                return Err(Error::new_spanned(
+                    ty,
+                    "nested optionals (`T??`) cannot be expressed in an ABI, \
+                     use a struct or a variant to wrap the inner `Option`",
+                ));
+            }
+            check_abi_type(inner)
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Return whether the type is an `Option`, possibly behind a `Box`.
+fn is_optional(ty: &Type) -> bool {
+    match generic_wrapper(ty) {
+        Some((wrapper, _)) if wrapper == "Option" => true,
+        Some((wrapper, inner)) if wrapper == "Box" => is_optional(inner),
+        _ => false,
+    }
+}
+
+/// If the type is one of the single-parameter wrappers known to the ABI
+/// (`Option`, `Vec` and `Box`), return its name and its type parameter.
+fn generic_wrapper(ty: &Type) -> Option<(String, &Type)> {
+    let Type::Path(path) = ty else { return None };
+    if path.qself.is_some() { return None; }
+    let segment = path.path.segments.last()?;
+    let wrapper = segment.ident.to_string();
+    if !matches!(wrapper.as_str(), "Option" | "Vec" | "Box") { return None; }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+    match args.args.first() {
+        Some(GenericArgument::Type(inner)) if args.args.len() == 1 => Some((wrapper, inner)),
+        _ => None,
+    }
+}
+
 fn derive_abiserializable_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
     if input.generics.lt_token.is_some() || input.generics.where_clause.is_some() {
         return Err(Error::new(
@@ -84,6 +163,7 @@ fn derive_abiserializable_enum(input: &DeriveInput, enumeration: &DataEnum) -> R
 
     let ident = &input.ident;
     let ident_str = ident.to_string();
+    let krate = attr::crate_path(&input.attrs)?;
 
     let variants = enumeration
         .variants
@@ -109,28 +189,32 @@ fn derive_abiserializable_enum(input: &DeriveInput, enumeration: &DataEnum) -> R
     debug!("field types: {:?}", &var_type);
     // debug!("field names: {:?}", &names);
 
+    for ty in &var_type {
+        check_abi_type(ty)?;
+    }
+
     let index: Vec<_> = (0..(var_idents.len() as u32)).collect();
 
     Ok(quote! {
         #[doc(hidden)]
         const _: () = {
-            impl kudu::ABISerializable for #ident {
-                fn to_bin(&self, s: &mut kudu::Bytes) {
+            impl #krate::ABISerializable for #ident {
+                fn to_bin(&self, s: &mut #krate::Bytes) {
                     match *self {
                         #(
                             #ident::#var_idents(ref __field0) => {
-                                kudu::VarUint32(#index).to_bin(s);
-                                __field0.to_bin(s);
+                                #krate::ABISerializable::to_bin(&#krate::VarUint32(#index), s);
+                                #krate::ABISerializable::to_bin(__field0, s);
                             }
                         )*
                     }
                 }
-                fn from_bin(s: &mut kudu::ByteStream) -> ::core::result::Result<Self, kudu::SerializeError> {
-                    Ok(match kudu::VarUint32::from_bin(s)?.0 {
+                fn from_bin(s: &mut #krate::ByteStream) -> ::core::result::Result<Self, #krate::SerializeError> {
+                    ::core::result::Result::Ok(match <#krate::VarUint32 as #krate::ABISerializable>::from_bin(s)?.0 {
                         #(
-                            #index => #ident::#var_idents(<#var_type>::from_bin(s)?),
+                            #index => #ident::#var_idents(<#var_type as #krate::ABISerializable>::from_bin(s)?),
                         )*
-                        t => kudu::abiserializable::InvalidTagSnafu { tag: t, variant: #ident_str }.fail()?,
+                        t => #krate::abiserializable::InvalidTagSnafu { tag: t, variant: #ident_str }.fail()?,
                     })
                 }
             }
@@ -172,6 +256,8 @@ fn derive_enum(input: &DeriveInput, enumeration: &DataEnum, prefixed: bool) -> R
 
     let ident = &input.ident;
     let ident_str = ident.to_string();
+    let krate = attr::crate_path(&input.attrs)?;
+    let serde = quote! { #krate::__private::serde };
 
     let _valid = enumeration.variants.iter().map(|variant| match variant.fields {
         Fields::Unit => Ok(()),
@@ -242,58 +328,77 @@ fn derive_enum(input: &DeriveInput, enumeration: &DataEnum, prefixed: bool) -> R
     Ok(quote! {
         #[doc(hidden)]
         const _: () = {
-            impl serde::Serialize for #ident {
-                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-                where S: serde::Serializer
+            impl #serde::Serialize for #ident {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where S: #serde::Serializer
                 {
                     match *self {
                         #(
                             #ident::#var_idents(ref __field0) => {
-                                let mut state = serde::Serializer::serialize_tuple(serializer, 2)?;
-                                serde::ser::SerializeTuple::serialize_element(&mut state, #names)?;
-                                serde::ser::SerializeTuple::serialize_element(&mut state, __field0)?;
-                                serde::ser::SerializeTuple::end(state)
+                                let mut state = #serde::Serializer::serialize_tuple(serializer, 2)?;
+                                #serde::ser::SerializeTuple::serialize_element(&mut state, #names)?;
+                                #serde::ser::SerializeTuple::serialize_element(&mut state, __field0)?;
+                                #serde::ser::SerializeTuple::end(state)
                             },
                         )*
                         #(
                             #ident::#unit_var_idents => {
-                                let mut state = serde::Serializer::serialize_tuple(serializer, 2)?;
-                                serde::ser::SerializeTuple::serialize_element(&mut state, #unit_names)?;
+                                let mut state = #serde::Serializer::serialize_tuple(serializer, 2)?;
+                                #serde::ser::SerializeTuple::serialize_element(&mut state, #unit_names)?;
                                 // FIXME: is this the correct behavior?
-                                serde::ser::SerializeTuple::serialize_element(&mut state, "")?;
-                                serde::ser::SerializeTuple::end(state)
+                                #serde::ser::SerializeTuple::serialize_element(&mut state, "")?;
+                                #serde::ser::SerializeTuple::end(state)
                             },
                         )*
                     }
                 }
             }
 
-            impl<'de> serde::Deserialize<'de> for #ident {
-                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            impl<'de> #serde::Deserialize<'de> for #ident {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
                 where
-                    D: serde::Deserializer<'de>,
+                    D: #serde::Deserializer<'de>,
                 {
-                    let tup: serde_json::Value = serde_json::Value::deserialize(deserializer)?;
-                    // let tag: Option<&str> = tup[0].as_str();
-                    // let tag: &str = tag.ok_or(serde::de::Error::custom("Tag (discriminant) needs to be a string"))?;
-                    let tag: &str = tup[0].as_str()
-                        .ok_or(serde::de::Error::custom("Tag (discriminant) needs to be a string"))?;
-                    Ok(match tag {
-                        #(
-                            #names => {
-                                let v: #var_type = serde_json::from_str(&tup[1].to_string())
-                                    .map_err(|e| serde::de::Error::custom(e.to_string()))?;
-                                #ident::#var_idents(v)
-                            },
-                        )*
-                        #(
-                            #unit_names => #ident::#unit_var_idents,
-                        )*
-                        _ => {
-                            let msg = format!("Invalid tag (discriminant) for type {}: {}", #ident_str, tag);
-                            return Err(serde::de::Error::custom(msg));
+                    // use a visitor on a tuple instead of going through a `serde_json::Value`
+                    // so that this works for all data formats, not only JSON
+                    struct __Visitor;
+
+                    impl<'de> #serde::de::Visitor<'de> for __Visitor {
+                        type Value = #ident;
+
+                        fn expecting(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                            ::core::write!(f, "a (tag, value) pair for type {}", #ident_str)
                         }
-                    })
+
+                        fn visit_seq<A>(self, mut seq: A) -> ::core::result::Result<Self::Value, A::Error>
+                        where
+                            A: #serde::de::SeqAccess<'de>,
+                        {
+                            let tag: ::std::string::String = seq.next_element()?
+                                .ok_or_else(|| #serde::de::Error::custom("Tag (discriminant) needs to be a string"))?;
+                            ::core::result::Result::Ok(match tag.as_str() {
+                                #(
+                                    #names => {
+                                        let v: #var_type = seq.next_element()?
+                                            .ok_or_else(|| #serde::de::Error::invalid_length(1, &self))?;
+                                        #ident::#var_idents(v)
+                                    },
+                                )*
+                                #(
+                                    #unit_names => {
+                                        let _: ::core::option::Option<#serde::de::IgnoredAny> = seq.next_element()?;
+                                        #ident::#unit_var_idents
+                                    },
+                                )*
+                                _ => {
+                                    let msg = ::std::format!("Invalid tag (discriminant) for type {}: {}", #ident_str, tag);
+                                    return ::core::result::Result::Err(#serde::de::Error::custom(msg));
+                                }
+                            })
+                        }
+                    }
+
+                    deserializer.deserialize_tuple(2, __Visitor)
                 }
             }
         };
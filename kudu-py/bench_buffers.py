@@ -0,0 +1,44 @@
+# SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+# SPDX-License-Identifier: AGPL-3.0-or-later
+
+"""Compare the time needed to decode a large payload given as different buffer types.
+
+`bytes(view)` corresponds to the previous behavior, where only `bytes` objects were
+accepted and other buffers had to be copied first by the caller.
+
+Run with: `uv run python bench_buffers.py`
+"""
+
+import json
+import timeit
+
+from kudu.abi import ABI
+
+ABI_DEF = json.dumps({
+    'version': 'eosio::abi/1.2',
+    'structs': [{'name': 'blob', 'base': '', 'fields': [{'name': 'data', 'type': 'bytes'},
+                                                         {'name': 'values', 'type': 'uint64[]'}]}],
+})
+
+SIZES = [1_000, 100_000, 10_000_000]
+
+
+def main():
+    abi = ABI(ABI_DEF)
+    for size in SIZES:
+        data = abi.encode('blob', {'data': '00' * size, 'values': list(range(size // 1000))})
+        buffer = bytearray(data)
+        view = memoryview(buffer)
+        number = max(1, 1_000_000 // size)
+
+        print(f'payload of {len(data)} bytes, {number} iterations')
+        for name, payload in [('bytes', lambda: data),
+                              ('bytearray', lambda: buffer),
+                              ('memoryview', lambda: view),
+                              ('bytes(view)', lambda: bytes(view))]:
+            t = timeit.timeit(lambda: abi.decode('blob', payload()), number=number)
+            print(f'  {name:<12} {t / number * 1e6:>10.1f} µs')
+
+
+if __name__ == '__main__':
+    main()
@@ -6,7 +6,7 @@ import shlex
 import duct
 
 from .kudu import *  # noqa: F403
-from kudu.api import APIClient
+from kudu.api import APIClient, Chain, ChainRegistry
 from kudu.chain import *  # noqa: F403
 from kudu.crypto import *  # noqa: F403
 from kudu.time import *  # noqa: F403
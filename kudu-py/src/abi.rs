@@ -7,26 +7,262 @@ use pyo3::prelude::*;
 /// A Python module implemented in Rust.
 #[pymodule(name = "abi", submodule)]
 pub mod kudu_abi {
+    use std::sync::Arc;
+
+    use pyo3::buffer::PyBuffer;
+    use pyo3::exceptions::PyValueError;
     use pyo3::prelude::*;
+    use pyo3::types::{IntoPyDict, PyBytes, PyDict, PyList, PyTuple};
+    use pythonize::depythonize;
+
+    use kudu::{ByteStream, JsonMap, JsonValue};
+    use kudu::abi::{ABI, TypeName};
+
+    use crate::util::{json_to_py, value_err, with_buffer};
+
+
+    // -----------------------------------------------------------------------------
+    //     Variant
+    // -----------------------------------------------------------------------------
+
+    /// Value of a variant type, ie: the decoded form of a `[type, value]` pair.
+    #[pyclass(name = "Variant", module = "kudu.abi", frozen)]
+    pub struct PyVariant {
+        #[pyo3(get, name = "type")]
+        type_: String,
+        #[pyo3(get)]
+        value: Py<PyAny>,
+    }
+
+    #[pymethods]
+    impl PyVariant {
+        #[new]
+        fn new(type_: String, value: Py<PyAny>) -> Self {
+            PyVariant { type_, value }
+        }
 
-    use kudu::abi::ABI;
+        fn __repr__<'py>(&self, py: Python<'py>) -> PyResult<String> {
+            Ok(format!("<kudu.abi.Variant: {} {}>", self.type_, self.value.bind(py).repr()?))
+        }
+
+        fn __eq__<'py>(&self, other: &Bound<'py, PyAny>) -> PyResult<bool> {
+            match other.cast::<PyVariant>() {
+                Ok(v) => {
+                    let v = v.get();
+                    Ok(self.type_ == v.type_ && self.value.bind(other.py()).eq(v.value.bind(other.py()))?)
+                },
+                Err(_) => Ok(false),
+            }
+        }
+
+        /// Return the `[type, value]` list corresponding to this variant.
+        fn to_list<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+            PyList::new(py, [self.type_.clone().into_pyobject(py)?.into_any(), self.value.bind(py).clone()])
+        }
+    }
 
-    use crate::util::value_err;
 
+    // -----------------------------------------------------------------------------
+    //     ABI
+    // -----------------------------------------------------------------------------
 
-    #[pyclass(name = "ABI", module = "kudu.abi")]
-    pub struct PyABI(pub ABI);
+    /// A parsed ABI, kept in Rust memory and shared (not copied) between all the
+    /// objects referencing it, eg: the ABIs returned by `Chain.get_abi()`.
+    ///
+    /// Instances are immutable and can be used concurrently from several threads.
+    /// Encoding and decoding release the GIL while converting data, so that large
+    /// conversions can run in parallel.
+    #[pyclass(name = "ABI", module = "kudu.abi", frozen)]
+    pub struct PyABI(pub Arc<ABI>);
 
     #[pymethods]
     impl PyABI {
         #[new]
-        fn new(abi_definition: &str) -> PyResult<Self> {
-            Ok(PyABI(ABI::from_str(abi_definition).map_err(value_err)?))
+        fn new(py: Python<'_>, abi_definition: &str) -> PyResult<Self> {
+            let abi = py.detach(|| ABI::from_str(abi_definition)).map_err(value_err)?;
+            Ok(PyABI(Arc::new(abi)))
         }
 
         fn __repr__(&self) -> String {
             format!("<kudu.api.ABI: {:?}>", self.0)
         }
 
+        /// Encode the given value as `typename`. Variant values can be given as
+        /// `[type, value]` lists, `Variant` instances or instances of the classes
+        /// returned by `variant_classes()`.
+        fn encode<'py>(&self, py: Python<'py>, typename: &str, value: &Bound<'py, PyAny>)
+                       -> PyResult<Bound<'py, PyBytes>> {
+            let value = to_json(value)?;
+            let bin = py.detach(|| self.0.variant_to_binary(typename, &value)).map_err(value_err)?;
+            Ok(PyBytes::new(py, &bin))
+        }
+
+        /// Decode the given data as `typename`. The data can be any object implementing
+        /// the buffer protocol (eg: `bytes`, `bytearray` or `memoryview`), and is read
+        /// without being copied.
+        ///
+        /// Variants are returned as `[type, value]` lists, unless `variant_classes`
+        /// is given, see `convert_variants()`.
+        #[pyo3(signature = (typename, data, variant_classes=None))]
+        fn decode<'py>(&self, py: Python<'py>, typename: &str, data: PyBuffer<u8>,
+                       variant_classes: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+            let value = with_buffer(py, &data, |data| {
+                self.0.decode_variant(&mut ByteStream::from(data), typename)
+            })?.map_err(value_err)?;
+            match variant_classes {
+                Some(classes) => self.convert(py, TypeName(typename), &value, Some(classes)),
+                None => json_to_py(py, &value),
+            }
+        }
+
+        /// Convert all the `[type, value]` lists of variants found in `value` (a
+        /// decoded instance of `typename`, eg: as returned by the node API).
+        ///
+        /// A variant whose type is a key of `variant_classes` is converted to an
+        /// instance of the corresponding class, built with the fields of its value
+        /// as keyword arguments (or with the value itself if it is not a struct).
+        /// Other variants are converted to `Variant` instances.
+        #[pyo3(signature = (typename, value, variant_classes=None))]
+        fn convert_variants<'py>(&self, py: Python<'py>, typename: &str, value: &Bound<'py, PyAny>,
+                                 variant_classes: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+            let value = to_json(value)?;
+            self.convert(py, TypeName(typename), &value, variant_classes)
+        }
+
+        /// Return a dict of dataclasses generated for all the struct types that are
+        /// alternatives of a variant, indexed by type name.
+        ///
+        /// Each class has an `__abi_type__` attribute containing its type name, so
+        /// its instances can be passed back to `encode()`.
+        fn variant_classes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+            let dataclasses = py.import("dataclasses")?;
+            let any = py.import("typing")?.getattr("Any")?;
+            let classes = PyDict::new(py);
+            for variant in self.0.variants() {
+                for alternative in &variant.types {
+                    let name = alternative.as_str();
+                    let rtype = self.0.resolve_type(TypeName(name)).map_err(value_err)?;
+                    if classes.contains(name)? || !self.0.has_struct(rtype.as_str()) {
+                        continue;
+                    }
+                    let struct_def = self.0.flattened_struct(name).map_err(value_err)?;
+                    let fields = struct_def.fields.iter().map(|field| {
+                        let default = dataclasses.call_method("field", (), Some(&[("default", py.None())].into_py_dict(py)?))?;
+                        PyTuple::new(py, [field.name.clone().into_pyobject(py)?.into_any(),
+                                          any.clone(),
+                                          default])
+                    }).collect::<PyResult<Vec<_>>>()?;
+                    let namespace = [("__abi_type__", name)].into_py_dict(py)?;
+                    let cls = dataclasses.call_method("make_dataclass", (name, fields),
+                                                      Some(&[("namespace", namespace)].into_py_dict(py)?))?;
+                    classes.set_item(name, cls)?;
+                }
+            }
+            Ok(classes)
+        }
+    }
+
+    impl PyABI {
+        fn convert<'py>(&self, py: Python<'py>, typename: TypeName, value: &JsonValue,
+                        classes: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyAny>> {
+            let abi = &self.0;
+            let rtype = abi.resolve_type(typename).map_err(value_err)?.remove_bin_extension();
+            let rtype = abi.resolve_type(rtype).map_err(value_err)?;
+
+            if rtype.is_optional() {
+                return match value {
+                    JsonValue::Null => Ok(py.None().into_bound(py)),
+                    _ => self.convert(py, rtype.fundamental_type(), value, classes),
+                };
+            }
+
+            if rtype.is_array() {
+                let items = value.as_array().ok_or_else(|| invalid(rtype, value))?;
+                let items = items.iter()
+                    .map(|item| self.convert(py, rtype.fundamental_type(), item, classes))
+                    .collect::<PyResult<Vec<_>>>()?;
+                return Ok(PyList::new(py, items)?.into_any());
+            }
+
+            if let Some(variant_def) = abi.variants().find(|v| v.name == rtype.as_str()) {
+                let (tag, inner) = match value.as_array().map(|v| v.as_slice()) {
+                    Some([JsonValue::String(tag), inner]) => (tag.as_str(), inner),
+                    _ => return Err(invalid(rtype, value)),
+                };
+                if !variant_def.types.iter().any(|t| t == tag) {
+                    return Err(PyValueError::new_err(
+                        format!("invalid type `{tag}` for variant `{rtype}`")));
+                }
+                let inner = self.convert(py, TypeName(tag), inner, classes)?;
+                if let Some(cls) = classes.map(|c| c.get_item(tag)).transpose()?.flatten() {
+                    return match inner.cast::<PyDict>() {
+                        Ok(fields) => cls.call((), Some(fields)),
+                        Err(_) => cls.call1((inner,)),
+                    };
+                }
+                return Ok(Bound::new(py, PyVariant::new(tag.to_string(), inner.unbind()))?.into_any());
+            }
+
+            if abi.has_struct(rtype.as_str()) {
+                let obj = value.as_object().ok_or_else(|| invalid(rtype, value))?;
+                let struct_def = abi.flattened_struct(rtype).map_err(value_err)?;
+                let result = PyDict::new(py);
+                for field in &struct_def.fields {
+                    // missing fields are binary extensions, keep them missing
+                    if let Some(field_value) = obj.get(field.name.as_str()) {
+                        let field_type = TypeName(field.type_.as_str());
+                        result.set_item(field.name.as_str(), self.convert(py, field_type, field_value, classes)?)?;
+                    }
+                }
+                return Ok(result.into_any());
+            }
+
+            json_to_py(py, value)
+        }
+    }
+
+    fn invalid(typename: TypeName, value: &JsonValue) -> PyErr {
+        PyValueError::new_err(format!("invalid value for type `{typename}`: {value}"))
+    }
+
+    /// Convert a python value to JSON, turning `Variant` instances and instances of
+    /// classes with an `__abi_type__` attribute into `[type, value]` lists.
+    fn to_json(value: &Bound<'_, PyAny>) -> PyResult<JsonValue> {
+        if let Ok(variant) = value.cast::<PyVariant>() {
+            let variant = variant.get();
+            let inner = to_json(variant.value.bind(value.py()))?;
+            return Ok(JsonValue::Array(vec![JsonValue::String(variant.type_.clone()), inner]));
+        }
+        if let Ok(abi_type) = value.get_type().getattr("__abi_type__") {
+            let abi_type: String = abi_type.extract()?;
+            let mut fields = JsonMap::new();
+            for (name, field_value) in value.getattr("__dict__")?.cast::<PyDict>()?.iter() {
+                // fields left to `None` are missing binary extensions
+                if !field_value.is_none() {
+                    fields.insert(name.extract()?, to_json(&field_value)?);
+                }
+            }
+            return Ok(JsonValue::Array(vec![JsonValue::String(abi_type), JsonValue::Object(fields)]));
+        }
+        if let Ok(dict) = value.cast::<PyDict>() {
+            return dict.iter()
+                .map(|(k, v)| Ok((k.extract::<String>()?, to_json(&v)?)))
+                .collect::<PyResult<_>>()
+                .map(JsonValue::Object);
+        }
+        if let Ok(list) = value.cast::<PyList>() {
+            return list.iter().map(|v| to_json(&v)).collect::<PyResult<_>>().map(JsonValue::Array);
+        }
+        if let Ok(tuple) = value.cast::<PyTuple>() {
+            return tuple.iter().map(|v| to_json(&v)).collect::<PyResult<_>>().map(JsonValue::Array);
+        }
+        Ok(depythonize(value)?)
+    }
+
+    /// Convert a value returned by `ABI.decode()` or `ABI.convert_variants()` back
+    /// to plain python objects, with variants as `[type, value]` lists.
+    #[pyfunction]
+    fn unwrap_variants<'py>(py: Python<'py>, value: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        json_to_py(py, &to_json(value)?)
     }
 }
@@ -14,10 +14,11 @@ pub mod kudu_api {
     use pythonize::{depythonize, pythonize};
     use serde::Serialize;
 
-    use kudu::api::{APIClient, HttpError};
-    use kudu::JsonValue;
+    use kudu::api::{APIClient, Chain, ChainRegistry, HttpError};
+    use kudu::{AccountName, ChainId, JsonValue};
 
-    use crate::util::value_err;
+    use crate::abi::kudu_abi::PyABI;
+    use crate::util::{runtime_err, value_err};
 
     fn wrap_for_python<'py, T>(py: Python<'py>, value: Result<&T, &HttpError>) -> PyResult<Bound<'py, PyAny>>
     where
@@ -29,6 +30,11 @@ pub mod kudu_api {
                 HttpError::HttpError { .. } => PyRuntimeError::new_err(format!("HTTP error: {}", e)),
                 HttpError::ConnectionError { source: _ } => PyRuntimeError::new_err(format!("HTTP error: {}", e)),
                 HttpError::JsonError { source: _ } => PyValueError::new_err(format!("JSON error: {}", e)),
+                HttpError::InvalidConfig { .. } => PyValueError::new_err(format!("{}", e)),
+                HttpError::InvalidResponse { .. } => PyValueError::new_err(format!("{}", e)),
+                HttpError::UnsupportedReadMode { .. } => PyRuntimeError::new_err(format!("{}", e)),
+                HttpError::TransactionFailed { .. } => PyRuntimeError::new_err(format!("{}", e)),
+                HttpError::ConfirmationTimeout { .. } => PyRuntimeError::new_err(format!("{}", e)),
             })
         }
     }
@@ -62,4 +68,89 @@ pub mod kudu_api {
             wrap_for_python(py, result.as_ref())
         }
     }
+
+    #[pyclass(name = "Chain", module = "kudu.api")]
+    pub struct PyChain(pub Arc<Chain>);
+
+    #[pymethods]
+    impl PyChain {
+        #[new]
+        #[pyo3(signature = (name, endpoint, chain_id=None))]
+        fn new(name: &str, endpoint: &str, chain_id: Option<&str>) -> PyResult<Self> {
+            let mut chain = Chain::new(name, Arc::new(APIClient::new(endpoint)));
+            if let Some(chain_id) = chain_id {
+                chain = chain.with_chain_id(ChainId::from_hex(chain_id).map_err(value_err)?);
+            }
+            Ok(PyChain(Arc::new(chain)))
+        }
+
+        fn __repr__(&self) -> String {
+            format!("<kudu.api.Chain: {} ({})>", self.0.name, self.0.client.endpoint)
+        }
+
+        #[getter]
+        fn name(&self) -> &str {
+            &self.0.name
+        }
+
+        #[getter]
+        fn client(&self) -> PyAPIClient {
+            PyAPIClient(self.0.client.clone())
+        }
+
+        #[getter]
+        fn chain_id(&self) -> PyResult<String> {
+            Ok(self.0.chain_id().map_err(runtime_err)?.to_hex())
+        }
+
+        fn get_abi(&self, account: &str) -> PyResult<PyABI> {
+            let account = AccountName::new(account).map_err(value_err)?;
+            let abi = self.0.get_abi(account).map_err(runtime_err)?;
+            Ok(PyABI(abi))
+        }
+
+        fn clear_abi_cache(&self) {
+            self.0.clear_abi_cache()
+        }
+    }
+
+    #[pyclass(name = "ChainRegistry", module = "kudu.api")]
+    pub struct PyChainRegistry(pub ChainRegistry);
+
+    #[pymethods]
+    impl PyChainRegistry {
+        #[new]
+        #[pyo3(signature = (defaults=true))]
+        fn new(defaults: bool) -> Self {
+            PyChainRegistry(match defaults {
+                true => ChainRegistry::with_defaults(),
+                false => ChainRegistry::new(),
+            })
+        }
+
+        fn __repr__(&self) -> String {
+            format!("<kudu.api.ChainRegistry: {:?}>", self.0.names())
+        }
+
+        fn __getitem__(&self, name: &str) -> PyResult<PyChain> {
+            self.get(name)
+        }
+
+        fn get(&self, name: &str) -> PyResult<PyChain> {
+            Ok(PyChain(self.0.get(name).map_err(value_err)?))
+        }
+
+        fn add(&mut self, chain: &PyChain) -> PyResult<()> {
+            self.0.add(chain.0.clone()).map_err(value_err)?;
+            Ok(())
+        }
+
+        fn add_alias(&mut self, alias: &str, name: &str) -> PyResult<()> {
+            self.0.add_alias(alias, name).map_err(value_err)
+        }
+
+        fn names(&self) -> Vec<String> {
+            self.0.names()
+        }
+    }
 }
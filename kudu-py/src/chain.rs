@@ -9,13 +9,14 @@ pub mod kudu_chain {
     use std::string::ToString;
 
     use pyo3::prelude::*;
+    use pyo3::buffer::PyBuffer;
     use pyo3::exceptions::PyValueError;
     use pyo3::types::{PyBytes, PyDict, PyList, PyString, PyTuple};
     use pythonize::{depythonize, pythonize};
 
     use kudu::chain::{Action, PermissionLevel, SignedTransaction, Transaction};
     use kudu::{
-        ABISerializable, AccountName, ActionName, Bytes, JsonValue, Name, PermissionName,
+        ABISerializable, AccountName, ActionName, Bytes, JsonValue, PermissionName,
     };
 
     use crate::api::kudu_api::PyAPIClient;
@@ -123,12 +124,13 @@ pub mod kudu_chain {
                 return Err(PyValueError::new_err(format!("invalid value for PermissionLevel: {}", 23)));
             }
 
-            let action = if let Ok(data) = data.cast::<PyBytes>() {
+            // binary data can be given as any object implementing the buffer protocol
+            let action = if let Ok(buffer) = PyBuffer::<u8>::get(data) {
                 Action {
                     account: AccountName::new(account).map_err(value_err)?,
                     name: ActionName::new(name).map_err(value_err)?,
                     authorization: auth,
-                    data: data.as_bytes().into(),
+                    data: buffer.to_vec(data.py())?.into(),
                 }
             }
             else {
@@ -222,10 +224,10 @@ pub mod kudu_chain {
         let args: JsonValue = depythonize(args)?;
         kudu::chain::push_action(
             client.borrow().0.clone(),
-            Name::new(actor).map_err(value_err)?,
+            AccountName::new(actor).map_err(value_err)?,
             &signing_key.borrow().0,
-            Name::new(contract).map_err(value_err)?,
-            Name::new(action).map_err(value_err)?,
+            AccountName::new(contract).map_err(value_err)?,
+            ActionName::new(action).map_err(value_err)?,
             &args,
         ).map_err(value_err)
     }
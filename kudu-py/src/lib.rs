@@ -118,6 +118,7 @@ mod kudu {
         // m.add("vaulta", api_client.call1(("https://api.eos.detroitledger.tech",))?)?;
         m.add("vaulta", api_client.call1(("https://vaulta.greymass.com",))?)?;
         m.add("jungle", api_client.call1(("https://jungle4.greymass.com",))?)?;
+        m.add("chains", m.getattr("api")?.getattr("ChainRegistry")?.call0()?)?;
 
         Ok(())
     }
@@ -2,9 +2,14 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::error::Error;
+use std::slice;
 
 use pyo3::prelude::*;
+use pyo3::buffer::PyBuffer;
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::types::{PyBool, PyDict, PyList, PyString};
+
+use kudu::JsonValue;
 
 fn full_error_message<T: Error>(e: T) -> String {
     let mut message = vec![e.to_string()];
@@ -26,6 +31,63 @@ pub fn runtime_err<T: Error>(e: T) -> PyErr {
     PyRuntimeError::new_err(full_error_message(e))
 }
 
+/// Convert a JSON value to a python object.
+///
+/// This should be used instead of `pythonize` for values that can contain numbers,
+/// as we enable the `arbitrary_precision` feature of `serde_json` (through the
+/// `arbitrary-precision` feature of `kudu`) which `pythonize` doesn't know about. Integers that do not fit in 64 bits are
+/// converted to python ints too.
+pub fn json_to_py<'py>(py: Python<'py>, value: &JsonValue) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        JsonValue::Null => py.None().into_bound(py),
+        JsonValue::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() { i.into_pyobject(py)?.into_any() }
+            else if let Some(u) = n.as_u64() { u.into_pyobject(py)?.into_any() }
+            else if let Ok(i) = n.as_str().parse::<i128>() { i.into_pyobject(py)?.into_any() }
+            else if let Ok(u) = n.as_str().parse::<u128>() { u.into_pyobject(py)?.into_any() }
+            else { n.as_str().parse::<f64>().map_err(value_err)?.into_pyobject(py)?.into_any() }
+        },
+        JsonValue::String(s) => PyString::new(py, s).into_any(),
+        JsonValue::Array(items) => {
+            let items = items.iter().map(|v| json_to_py(py, v)).collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_any()
+        },
+        JsonValue::Object(obj) => {
+            let dict = PyDict::new(py);
+            for (k, v) in obj {
+                dict.set_item(k, json_to_py(py, v)?)?;
+            }
+            dict.into_any()
+        },
+    })
+}
+
+/// Call `f` with the contents of a python object implementing the buffer protocol
+/// (eg: `bytes`, `bytearray` or `memoryview`), without copying them if possible.
+///
+/// The GIL is released while calling `f` if the buffer is read-only (eg: `bytes`),
+/// otherwise it is kept so that the data cannot be modified by another thread in
+/// the meantime (eg: `bytearray`). Non-contiguous buffers are copied first.
+pub fn with_buffer<T, F>(py: Python<'_>, buffer: &PyBuffer<u8>, f: F) -> PyResult<T>
+where
+    T: Send,
+    F: FnOnce(&[u8]) -> T + Send,
+{
+    if !buffer.is_c_contiguous() {
+        let data = buffer.to_vec(py)?;
+        return Ok(py.detach(|| f(&data)));
+    }
+
+    // SAFETY: the buffer is contiguous, and the object exporting it is kept alive
+    //         (and cannot be resized) until `buffer` is released
+    let data = unsafe { slice::from_raw_parts(buffer.buf_ptr() as *const u8, buffer.len_bytes()) };
+    Ok(match buffer.readonly() {
+        true => py.detach(|| f(data)),
+        false => f(data),
+    })
+}
+
 #[crabtime::function]
 fn _gen_default_repr(struct_name: String) {
     crabtime::output! {
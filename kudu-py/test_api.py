@@ -22,6 +22,19 @@ def test_api_client():
     assert isinstance(kudu.jungle, kudu.APIClient)
 
 
+def test_chain_registry():
+    assert kudu.chains['mainnet'].name == 'vaulta'
+    assert kudu.chains.get('testnet').client.__repr__() == '<kudu.api.APIClient: https://jungle4.greymass.com>'
+
+    registry = kudu.ChainRegistry(defaults=False)
+    registry.add(kudu.Chain('devnet', 'http://127.0.0.1:18888'))
+    registry.add_alias('dev', 'devnet')
+    assert registry.names() == ['devnet']
+    assert registry['dev'].name == 'devnet'
+    with pytest.raises(ValueError):
+        registry.get('mainnet')
+
+
 def test_get_info(chain):
     info = chain.get_info()
     assert info['head_block_num'] > 0
@@ -1,10 +1,13 @@
 # SPDX-FileCopyrightText: 2026 DigiGaia SCCL
 # SPDX-License-Identifier: AGPL-3.0-or-later
 
+from concurrent.futures import ThreadPoolExecutor
 from datetime import datetime, timezone
+import json
 
 import pytest
 
+from kudu.abi import ABI, Variant, unwrap_variants
 from kudu.chain import Action, PermissionLevel, Transaction
 from kudu.crypto import PrivateKey, PublicKey
 import kudu
@@ -182,6 +185,10 @@ def test_action():
     assert str(action) == '<kudu.Action: eosio.token::transfer(...) [eosio@active]>'
     assert bytes(action).hex() == '00a6823403ea3055000000572d3ccdcd010000000000ea305500000000a8ed32322a608c31c6187315d6708c31c6187315d6010000000000000004535953000000000974657374206d656d6f'
 
+    # binary data can be given as any object implementing the buffer protocol
+    assert Action('eosio.token', 'transfer', PermissionLevel('eosio', 'active'), bytearray(action.data)) == action
+    assert Action('eosio.token', 'transfer', PermissionLevel('eosio', 'active'), memoryview(action.data)) == action
+
     assert action.account == 'eosio.token'
     assert action.name == 'transfer'
     assert action.authorization == [('eosio', 'active')]
@@ -242,3 +249,74 @@ def test_transaction():
 
     with pytest.raises(ValueError):
         Transaction('this should fail gracefully')
+
+
+SHAPES_ABI = json.dumps({
+    'version': 'eosio::abi/1.2',
+    'types': [{'new_type_name': 'amount', 'type': 'uint32'}],
+    'structs': [
+        {'name': 'point', 'base': '', 'fields': [{'name': 'x', 'type': 'int32'}, {'name': 'y', 'type': 'int32'}]},
+        {'name': 'shapes', 'base': '', 'fields': [{'name': 'items', 'type': 'shape[]'}, {'name': 'extra', 'type': 'shape?'}]},
+    ],
+    'variants': [{'name': 'shape', 'types': ['point', 'amount']}],
+})
+
+
+def test_abi_variants():
+    abi = ABI(SHAPES_ABI)
+    value = {'items': [['point', {'x': 1, 'y': -2}], ['amount', 3]], 'extra': None}
+    data = abi.encode('shapes', value)
+
+    assert data.hex() == '020001000000feffffff010300000000'
+    assert abi.decode('shapes', data) == value
+
+    # generic variant values
+    converted = abi.convert_variants('shapes', value)
+    assert converted['items'] == [Variant('point', {'x': 1, 'y': -2}), Variant('amount', 3)]
+    assert converted['items'][1].type == 'amount'
+    assert converted['items'][1].value == 3
+    assert converted['items'][1].to_list() == ['amount', 3]
+    assert repr(converted['items'][1]) == '<kudu.abi.Variant: amount 3>'
+    assert abi.encode('shapes', converted) == data
+    assert unwrap_variants(converted) == value
+
+    # generated classes
+    classes = abi.variant_classes()
+    assert list(classes) == ['point']
+    Point = classes['point']
+    decoded = abi.decode('shapes', data, variant_classes=classes)
+    assert decoded['items'] == [Point(x=1, y=-2), Variant('amount', 3)]
+    assert abi.encode('shapes', decoded) == data
+    assert abi.encode('shapes', {'items': [Point(x=1, y=-2), Variant('amount', 3)], 'extra': None}) == data
+
+    with pytest.raises(ValueError, match='invalid type `foo` for variant `shape`'):
+        abi.convert_variants('shapes', {'items': [['foo', 1]]})
+
+
+def test_abi_buffers():
+    abi = ABI(SHAPES_ABI)
+    value = {'items': [['point', {'x': 1, 'y': -2}], ['amount', 3]], 'extra': None}
+    data = abi.encode('shapes', value)
+
+    # data can be decoded from any object implementing the buffer protocol
+    assert abi.decode('shapes', bytearray(data)) == value
+    assert abi.decode('shapes', memoryview(data)) == value
+    assert abi.decode('shapes', memoryview(b'xx' + data)[2:]) == value
+
+    # non-contiguous buffers are supported too
+    interleaved = bytes(b for byte in data for b in (byte, 0))
+    assert abi.decode('shapes', memoryview(interleaved)[::2]) == value
+
+    with pytest.raises(TypeError):
+        abi.decode('shapes', data.hex())
+
+
+def test_abi_shared_between_threads():
+    abi = ABI(SHAPES_ABI)
+    values = [{'items': [['point', {'x': i, 'y': -i}], ['amount', i]], 'extra': None} for i in range(100)]
+
+    def roundtrip(value):
+        return abi.decode('shapes', abi.encode('shapes', value))
+
+    with ThreadPoolExecutor(max_workers=4) as executor:
+        assert list(executor.map(roundtrip, values)) == values
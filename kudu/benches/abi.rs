@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Benchmarks of the parsing of ABIs, from a small test ABI to the
+//! `eosio` system contract ABI, which is one of the biggest ABIs found on chain.
+//!
+//! This shows the cost of building an `ABI` instance, which should be reused as
+//! much as possible, and the speedup brought by the cache of parsed ABIs.
+//!
+//! Run with:
+//! ```sh
+//! cargo bench -p kudu --bench abi
+//! ```
+//!
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use kudu::{ABI, ABIDefinition, Bytes};
+use kudu::abi::data::{CORE_VAULTA_ABI, EOSIO_ABI, EOSIO_TOKEN_ABI, TEST_ABI};
+use kudu::abi::hash_packed_abi;
+
+
+/// Run `f` `iterations` times and print the average time per call.
+fn bench<T>(label: &str, iterations: u32, mut f: impl FnMut() -> T) {
+    // warm up
+    for _ in 0..iterations / 10 {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    let elapsed: Duration = start.elapsed();
+    println!("{label:<50} {:>10.1} µs/iter", elapsed.as_nanos() as f64 / iterations as f64 / 1000.0);
+}
+
+fn main() {
+    for (name, json_abi, iterations) in [
+        ("test", TEST_ABI, 10_000),
+        ("eosio.token", EOSIO_TOKEN_ABI, 10_000),
+        ("core.vaulta", CORE_VAULTA_ABI, 1_000),
+        ("eosio", EOSIO_ABI, 1_000),
+    ] {
+        let mut packed = Bytes::new();
+        ABIDefinition::from_str(json_abi).unwrap().encode(&mut packed).unwrap();
+        let hex_abi = packed.to_hex();
+        println!("\n----==== {name} ABI: {} bytes packed ====----\n", packed.len());
+
+        bench("ABI::from_str()", iterations, || ABI::from_str(json_abi).unwrap());
+        bench("ABI::from_hex_abi()", iterations, || ABI::from_hex_abi(&hex_abi).unwrap());
+        bench("ABI::from_bin_abi()", iterations, || ABI::from_bin_abi(&packed).unwrap());
+        bench("ABIDefinition::decode() (no validation)", iterations, || {
+            ABIDefinition::decode(&mut (&packed).into()).unwrap()
+        });
+        bench("hex::decode()", iterations, || hex::decode(&hex_abi).unwrap());
+        bench("hash_packed_abi()", iterations, || hash_packed_abi(&packed));
+        bench("ABI::from_hex_abi_cached()", iterations, || ABI::from_hex_abi_cached(&hex_abi).unwrap());
+        bench("ABI::from_bin_abi_cached()", iterations, || ABI::from_bin_abi_cached(&packed).unwrap());
+    }
+}
@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Benchmarks of the conversions of `Name` to its string representation.
+//!
+//! Run with:
+//! ```sh
+//! cargo bench -p kudu --bench name
+//! ```
+//!
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use kudu::{json, AntelopeValue, Name, NameCache};
+
+
+const ITERATIONS: u32 = 1_000_000;
+
+/// Run `f` `ITERATIONS` times and print the average time per call.
+fn bench<T>(label: &str, mut f: impl FnMut(u32) -> T) {
+    // warm up
+    for i in 0..ITERATIONS / 10 {
+        black_box(f(i));
+    }
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        black_box(f(i));
+    }
+    let elapsed: Duration = start.elapsed();
+    println!("{label:<40} {:>8.1} ns/iter", elapsed.as_nanos() as f64 / ITERATIONS as f64);
+}
+
+fn main() {
+    // a small set of hot names, as typically seen by an indexer
+    let names: Vec<Name> = ["eosio", "eosio.token", "transfer", "active", "owner", "core.vaulta",
+                            "alice", "bob", "eosio.ram", "eosio.stake", "delegatebw", "buyrambytes"]
+        .iter().map(|s| Name::new(s).unwrap()).collect();
+    let name = |i: u32| names[i as usize % names.len()];
+
+    bench("Name::to_string()", |i| name(i).to_string());
+    bench("Name::to_name_str()", |i| name(i).to_name_str());
+    bench("Name::as_display_cache()", |i| name(i).as_display_cache());
+
+    let cache = NameCache::new(names.len());
+    bench("NameCache::get() (hot names)", |i| cache.get(name(i)));
+    let cache = NameCache::new(1024);
+    bench("NameCache::get() (no hits)", |i| cache.get(Name::from_u64(i as u64)));
+
+    bench("json!(name)", |i| json!(name(i)));
+    bench("AntelopeValue::Name.to_variant()", |i| AntelopeValue::Name(name(i)).to_variant());
+    bench("serde_json::to_string(&name)", |i| serde_json::to_string(&name(i)).unwrap());
+}
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Benchmark of the ABI encoding cache (see `ABI::with_encode_cache()`).
+//!
+//! Run it with: `cargo run --release --example encode_cache [ABI_FILE TYPENAME JSONL_FILE]`
+//!
+//! If no arguments are given, a synthetic corpus of transactions is used. Otherwise,
+//! each line of `JSONL_FILE` is encoded as `TYPENAME` using the ABI in `ABI_FILE`,
+//! eg: traces dumped from a state history node.
+//!
+
+use std::env;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{bail, Result};
+use serde_json::json;
+
+use kudu::{ABI, JsonValue, abi::data::TRANSACTION_ABI};
+
+
+const ROUNDS: usize = 5;
+const CACHE_CAPACITY: usize = 10_000;
+
+fn synthetic_corpus() -> Vec<JsonValue> {
+    let actors = ["alice", "bob", "carol", "dave"];
+    (0..10_000).map(|i| {
+        let actor = actors[i % actors.len()];
+        json!({
+            "expiration": "2009-02-13T23:31:31.000",
+            "ref_block_num": i % 65536,
+            "ref_block_prefix": 5678,
+            "max_net_usage_words": 0,
+            "max_cpu_usage_ms": 0,
+            "delay_sec": 0,
+            "context_free_actions": [],
+            "actions": (0..4).map(|_| json!({
+                "account": "eosio.token",
+                "name": "transfer",
+                "authorization": [{"actor": actor, "permission": "active"}],
+                "data": "608c31c6187315d6708c31c6187315d60100000000000000045359530000000000",
+            })).collect::<Vec<_>>(),
+            "transaction_extensions": [],
+        })
+    }).collect()
+}
+
+fn bench(abi: &ABI, typename: &str, corpus: &[JsonValue]) -> Result<Duration> {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for value in corpus {
+            abi.variant_to_binary(typename, value)?;
+        }
+    }
+    Ok(start.elapsed() / ROUNDS as u32)
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (abi, typename, corpus) = match args.as_slice() {
+        [] => (ABI::from_str(TRANSACTION_ABI)?, "transaction".to_string(), synthetic_corpus()),
+        [abi_file, typename, corpus_file] => {
+            let corpus = fs::read_to_string(corpus_file)?.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<_, _>>()?;
+            (ABI::from_str(&fs::read_to_string(abi_file)?)?, typename.clone(), corpus)
+        },
+        _ => bail!("usage: encode_cache [ABI_FILE TYPENAME JSONL_FILE]"),
+    };
+
+    println!("Encoding {} values of type `{typename}`, {ROUNDS} rounds", corpus.len());
+
+    let uncached = bench(&abi, &typename, &corpus)?;
+    println!("without cache: {uncached:?} per round");
+
+    let cached_abi = abi.with_encode_cache(CACHE_CAPACITY);
+    let cached = bench(&cached_abi, &typename, &corpus)?;
+    println!("with cache:    {cached:?} per round ({:.2}x)", uncached.as_secs_f64() / cached.as_secs_f64());
+
+    if let Some(stats) = cached_abi.encode_cache_stats() {
+        println!("cache stats:   {} hits, {} misses ({:.1}% hit ratio), {} evictions",
+                 stats.hits, stats.misses, 100.0 * stats.hit_ratio(), stats.evictions);
+    }
+
+    Ok(())
+}
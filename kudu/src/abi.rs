@@ -5,14 +5,36 @@
 //! This module provides tools to encode/decode `Antelope` types into/from an ABI.
 //!
 
+// ABIs and the data they decode usually come from untrusted sources, make sure
+// we return errors instead of panicking on malformed input
+#![deny(clippy::unwrap_used)]
+
+mod builtin;
+mod cache;
 mod definition;
+mod describe;
+mod diff;
 mod error;
+mod expand;
+mod graph;
+mod offsets;
 pub mod registry;
+mod ricardian;
 mod serializer;
 mod typename;
 pub mod data;
 
-pub use definition::{ABIDefinition, abi_schema};
+pub use builtin::BuiltinTypeError;
+pub use cache::CacheStats;
+pub use definition::{
+    ABIDefinition, Field, MergePolicy, Struct, Variant, VersionPolicy, abi_schema, hash_packed_abi,
+};
+pub use describe::{FieldDescription, TypeDescription};
+pub use diff::{Difference, ValueDiff};
 pub use error::ABIError;
-pub use serializer::ABI;
+pub use expand::{ABIResolver, Expander, FieldDecoder};
+pub use graph::{DependencyGraph, TypeKind};
+pub use offsets::{ByteOffsets, ByteSpan};
+pub use ricardian::RicardianContract;
+pub use serializer::{ABI, ABIAction, ABITable, DecodeOptions, EncodeOptions};
 pub use typename::TypeName;
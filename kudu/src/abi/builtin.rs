@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::Value as JsonValue;
+
+use crate::{ByteStream, Bytes};
+
+/// Error returned by the encode/decode functions of a custom builtin type.
+pub type BuiltinTypeError = Box<dyn Error + Send + Sync>;
+
+type EncodeFn = dyn Fn(&JsonValue, &mut Bytes) -> Result<(), BuiltinTypeError> + Send + Sync;
+type DecodeFn = dyn Fn(&mut ByteStream) -> Result<JsonValue, BuiltinTypeError> + Send + Sync;
+
+/// Encode/decode functions of a builtin type registered on an [`ABI`] with
+/// [`ABI::with_builtin_type()`].
+///
+/// [`ABI`]: crate::ABI
+/// [`ABI::with_builtin_type()`]: crate::ABI::with_builtin_type
+#[derive(Clone)]
+pub(super) struct BuiltinType {
+    encode: Arc<EncodeFn>,
+    decode: Arc<DecodeFn>,
+}
+
+impl BuiltinType {
+    pub fn new<E, D>(encode: E, decode: D) -> Self
+    where
+        E: Fn(&JsonValue, &mut Bytes) -> Result<(), BuiltinTypeError> + Send + Sync + 'static,
+        D: Fn(&mut ByteStream) -> Result<JsonValue, BuiltinTypeError> + Send + Sync + 'static,
+    {
+        BuiltinType { encode: Arc::new(encode), decode: Arc::new(decode) }
+    }
+
+    pub fn encode(&self, object: &JsonValue, ds: &mut Bytes) -> Result<(), BuiltinTypeError> {
+        (self.encode)(object, ds)
+    }
+
+    pub fn decode(&self, ds: &mut ByteStream) -> Result<JsonValue, BuiltinTypeError> {
+        (self.decode)(ds)
+    }
+}
+
+impl fmt::Debug for BuiltinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuiltinType").finish_non_exhaustive()
+    }
+}
+
+
+type HookFn = dyn Fn(JsonValue) -> Result<JsonValue, BuiltinTypeError> + Send + Sync;
+
+/// Function transforming the decoded values of a type, registered on an [`ABI`]
+/// with [`ABI::with_decode_hook()`].
+///
+/// [`ABI`]: crate::ABI
+/// [`ABI::with_decode_hook()`]: crate::ABI::with_decode_hook
+#[derive(Clone)]
+pub(super) struct DecodeHook(Arc<HookFn>);
+
+impl DecodeHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(JsonValue) -> Result<JsonValue, BuiltinTypeError> + Send + Sync + 'static,
+    {
+        DecodeHook(Arc::new(hook))
+    }
+
+    pub fn apply(&self, value: JsonValue) -> Result<JsonValue, BuiltinTypeError> {
+        (self.0)(value)
+    }
+}
+
+impl fmt::Debug for DecodeHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodeHook").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,271 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use serde_json::Value as JsonValue;
+
+use crate::{ABI, Bytes, Checksum256, TypeName};
+
+// the cache is keyed by the hash of `(typename, value)` but stores the value too
+// so that we can check for equality on lookup and never return the encoding of
+// another value in case of a hash collision
+//
+// entries are evicted in FIFO order once the cache is full
+
+/// Statistics of the encoding cache of an [`ABI`], as returned by
+/// [`ABI::encode_cache_stats()`], or of the cache of parsed ABIs, as returned by
+/// [`ABI::parsed_cache_stats()`].
+///
+/// [`ABI`]: crate::ABI
+/// [`ABI::encode_cache_stats()`]: crate::ABI::encode_cache_stats
+/// [`ABI::parsed_cache_stats()`]: crate::ABI::parsed_cache_stats
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Number of encoded values currently stored in the cache
+    pub entries: usize,
+    /// Maximum number of encoded values that can be stored in the cache
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// Return the ratio of lookups that were found in the cache, between 0 and 1.
+    pub fn hit_ratio(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+struct Entry {
+    typename: String,
+    value: JsonValue,
+    encoded: Bytes,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<u64, Vec<Entry>>,
+    order: VecDeque<u64>,
+    stats: CacheStats,
+}
+
+/// Bounded cache of the binary encoding of struct values.
+pub(super) struct EncodeCache {
+    inner: Mutex<CacheInner>,
+}
+
+impl EncodeCache {
+    pub fn new(capacity: usize) -> Self {
+        let mut inner = CacheInner::default();
+        inner.stats.capacity = capacity;
+        EncodeCache { inner: Mutex::new(inner) }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// If the given value is in the cache, append its encoding to `ds` and return `true`.
+    pub fn encode_cached(&self, typename: TypeName, value: &JsonValue, ds: &mut Bytes) -> bool {
+        let key = cache_key(typename, value);
+        let mut inner = self.lock();
+        let found = inner.entries.get(&key).and_then(|bucket| {
+            bucket.iter().find(|e| e.typename == typename.as_str() && e.value == *value)
+        });
+        #[cfg(feature = "metrics")]
+        crate::metrics::cache_lookup("abi_encode", found.is_some());
+        match found {
+            Some(entry) => {
+                ds.write_bytes(&entry.encoded);
+                inner.stats.hits += 1;
+                true
+            },
+            None => {
+                inner.stats.misses += 1;
+                false
+            },
+        }
+    }
+
+    pub fn insert(&self, typename: TypeName, value: &JsonValue, encoded: &[u8]) {
+        let key = cache_key(typename, value);
+        let mut inner = self.lock();
+        if inner.stats.capacity == 0 { return; }
+
+        if inner.stats.entries == inner.stats.capacity {
+            if let Some(oldest) = inner.order.pop_front() {
+                if let Some(bucket) = inner.entries.get_mut(&oldest) {
+                    bucket.remove(0);
+                    if bucket.is_empty() {
+                        inner.entries.remove(&oldest);
+                    }
+                }
+                inner.stats.entries -= 1;
+                inner.stats.evictions += 1;
+            }
+        }
+
+        inner.entries.entry(key).or_default().push(Entry {
+            typename: typename.to_string(),
+            value: value.clone(),
+            encoded: Bytes::from(encoded),
+        });
+        inner.order.push_back(key);
+        inner.stats.entries += 1;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Remove all entries from the cache and reset its statistics.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        let capacity = inner.stats.capacity;
+        *inner = CacheInner::default();
+        inner.stats.capacity = capacity;
+    }
+}
+
+impl Clone for EncodeCache {
+    /// Return a new empty cache with the same capacity.
+    fn clone(&self) -> Self {
+        EncodeCache::new(self.stats().capacity)
+    }
+}
+
+impl fmt::Debug for EncodeCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncodeCache")
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Cache of parsed ABIs
+// -----------------------------------------------------------------------------
+
+#[derive(Default)]
+struct ParsedCacheInner {
+    entries: HashMap<Checksum256, Arc<ABI>>,
+    order: VecDeque<Checksum256>,
+    stats: CacheStats,
+}
+
+/// Bounded cache of parsed ABIs, keyed by the hash of their packed definition.
+pub(super) struct ParsedCache {
+    inner: Mutex<ParsedCacheInner>,
+}
+
+impl ParsedCache {
+    pub fn new(capacity: usize) -> Self {
+        let mut inner = ParsedCacheInner::default();
+        inner.stats.capacity = capacity;
+        ParsedCache { inner: Mutex::new(inner) }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ParsedCacheInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Return the ABI with the given hash if it is in the cache, otherwise build it
+    /// with `parse` and store it.
+    ///
+    /// The lock is not held while parsing so that other threads can still use the
+    /// cache, which means that the same ABI might be parsed more than once at first.
+    pub fn get_or_try_insert<E>(&self, hash: &Checksum256,
+                                parse: impl FnOnce() -> Result<ABI, E>) -> Result<Arc<ABI>, E> {
+        {
+            let mut inner = self.lock();
+            let found = inner.entries.get(hash).cloned();
+            #[cfg(feature = "metrics")]
+            crate::metrics::cache_lookup("abi_parsed", found.is_some());
+            if let Some(abi) = found {
+                inner.stats.hits += 1;
+                return Ok(abi);
+            }
+            inner.stats.misses += 1;
+        }
+
+        let abi = Arc::new(parse()?);
+
+        let mut inner = self.lock();
+        if inner.stats.capacity == 0 { return Ok(abi); }
+        if let Some(existing) = inner.entries.get(hash) {
+            return Ok(existing.clone());
+        }
+
+        if inner.stats.entries == inner.stats.capacity {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.entries.remove(&oldest);
+                inner.stats.entries -= 1;
+                inner.stats.evictions += 1;
+            }
+        }
+        inner.entries.insert(hash.clone(), abi.clone());
+        inner.order.push_back(hash.clone());
+        inner.stats.entries += 1;
+        Ok(abi)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Remove all entries from the cache and reset its statistics.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        let capacity = inner.stats.capacity;
+        *inner = ParsedCacheInner::default();
+        inner.stats.capacity = capacity;
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Hashing of cache keys
+// -----------------------------------------------------------------------------
+
+fn cache_key(typename: TypeName, value: &JsonValue) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    typename.as_str().hash(&mut hasher);
+    hash_json(value, &mut hasher);
+    hasher.finish()
+}
+
+/// Hash a JSON value consistently with its `Eq` implementation, ie: the order
+/// of the keys of an object doesn't matter.
+fn hash_json<H: Hasher>(value: &JsonValue, state: &mut H) {
+    match value {
+        JsonValue::Null => 0u8.hash(state),
+        JsonValue::Bool(b) => (1u8, b).hash(state),
+        JsonValue::Number(n) => (2u8, n.to_string()).hash(state),
+        JsonValue::String(s) => (3u8, s).hash(state),
+        JsonValue::Array(items) => {
+            (4u8, items.len()).hash(state);
+            for item in items {
+                hash_json(item, state);
+            }
+        },
+        JsonValue::Object(obj) => {
+            // combine the hashes of the entries with a commutative operation
+            let combined = obj.iter().fold(0u64, |acc, (k, v)| {
+                let mut hasher = DefaultHasher::new();
+                k.hash(&mut hasher);
+                hash_json(v, &mut hasher);
+                acc.wrapping_add(hasher.finish())
+            });
+            (5u8, obj.len(), combined).hash(state);
+        },
+    }
+}
@@ -5,15 +5,19 @@ use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use snafu::{ensure, ResultExt};
+use tracing::warn;
 
 
 use crate::abi::error::LeftoverDataInStreamSnafu;
 use crate::abiserializable::{ABISerializable, ABISnafu};
 use crate::{
-    Bytes, ByteStream, SerializeError, JsonValue, ActionName, TableName,
+    Bytes, ByteStream, Checksum256, SerializeError, JsonValue, ActionName, TableName,
     abi::serializer::ABI,
-    abi::error::{ABIError, JsonSnafu, DeserializeSnafu, VersionSnafu, IncompatibleVersionSnafu},
+    abi::error::{
+        ABIError, JsonSnafu, DeserializeSnafu, VersionSnafu, IncompatibleVersionSnafu, MergeConflictSnafu,
+    },
     abi::data::{ABI_SCHEMA, CONTRACT_ABI}
 };
 
@@ -30,6 +34,53 @@ type Result<T, E = ABIError> = core::result::Result<T, E>;
 pub type TypeName = String;
 pub type FieldName = String;
 
+/// Prefix of the ABI versions that can be parsed, ie: `eosio::abi/1.x`
+const SUPPORTED_VERSION_PREFIX: &str = "eosio::abi/1.";
+
+
+/// What to do when loading an ABI with a version that is not supported (ie: not
+/// `eosio::abi/1.x`), see [`ABIDefinition::from_str_with_options()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VersionPolicy {
+    /// Fail with an [`ABIError::VersionError`]
+    #[default]
+    Strict,
+    /// Log a warning and try to parse the ABI as if it was an `eosio::abi/1.x` one.
+    /// The version still needs to start with `eosio::abi/`.
+    ///
+    /// This is useful for tooling that needs to inspect an ABI coming from a newer
+    /// chain, but the result is not guaranteed to be correct.
+    BestEffort,
+}
+
+impl VersionPolicy {
+    /// Check whether an ABI with the given version can be loaded under this policy.
+    pub(crate) fn check(&self, version: &str) -> Result<()> {
+        if version.starts_with(SUPPORTED_VERSION_PREFIX) {
+            return Ok(());
+        }
+        ensure!(*self == VersionPolicy::BestEffort && version.starts_with("eosio::abi/"),
+                VersionSnafu { version });
+        warn!(r#"unsupported ABI version "{version}", trying to parse it anyway"#);
+        Ok(())
+    }
+}
+
+/// What to do when merging two ABIs which both define an item with the same name
+/// but a different definition, see [`ABIDefinition::merge()`].
+///
+/// Items with identical definitions are always kept only once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Fail with an [`ABIError::MergeConflictError`]
+    #[default]
+    Error,
+    /// Keep the definition from the ABI being merged into
+    KeepOurs,
+    /// Keep the definition from the ABI being merged in
+    KeepTheirs,
+}
+
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct TypeDef {
@@ -129,15 +180,63 @@ impl ABIDefinition {
         serde_json::from_str(s).context(JsonSnafu)
     }
 
+    /// Parse an ABI which might not be strict JSON, as sometimes produced by CDT or
+    /// edited by hand: a leading BOM, `//` and `/* */` comments and trailing commas
+    /// in objects and arrays are accepted.
+    ///
+    /// This should only be used for loading ABI files from users, use the strict
+    /// [`ABIDefinition::from_str()`] whenever the ABI needs to match what the chain
+    /// would accept.
+    ///
+    /// ```
+    /// # use kudu::{ABIDefinition, ABIError};
+    /// let abi = ABIDefinition::from_str_lenient(r#"{
+    ///     // generated by hand
+    ///     "version": "eosio::abi/1.2",
+    ///     "types": [{"new_type_name": "account", "type": "name"},],
+    /// }"#)?;
+    /// assert_eq!(abi.types[0].new_type_name, "account");
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn from_str_lenient(s: &str) -> Result<Self> {
+        Self::from_str(&strip_non_strict_json(s))
+    }
+
+    /// Parse an ABI and check that its version is supported according to the given
+    /// [`VersionPolicy`].
+    ///
+    /// ```
+    /// # use kudu::{ABIDefinition, ABIError, abi::VersionPolicy};
+    /// let abi = r#"{"version": "eosio::abi/2.0", "structs": []}"#;
+    /// assert!(ABIDefinition::from_str_with_options(abi, VersionPolicy::Strict).is_err());
+    ///
+    /// let abi = ABIDefinition::from_str_with_options(abi, VersionPolicy::BestEffort)?;
+    /// assert_eq!(abi.version, "eosio::abi/2.0");
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn from_str_with_options(s: &str, policy: VersionPolicy) -> Result<Self> {
+        let abi = Self::from_str(s)?;
+        policy.check(&abi.version)?;
+        Ok(abi)
+    }
+
     pub fn from_variant(v: &JsonValue) -> Result<Self> {
         ABIDefinition::from_str(&v.to_string())
     }
 
     pub fn decode(data: &mut ByteStream) -> Result<Self> {
+        Self::decode_with_options(data, VersionPolicy::Strict)
+    }
+
+    /// Decode a binary ABI, checking its version according to the given [`VersionPolicy`].
+    ///
+    /// With [`VersionPolicy::BestEffort`], ABIs with an unknown version are decoded
+    /// using the `eosio::abi/1.x` layout.
+    pub fn decode_with_options(data: &mut ByteStream, policy: VersionPolicy) -> Result<Self> {
         // FIXME: check how to deserialize properly the different versions: 1.0, 1.1, 1.2, ...
         let version = String::from_bin(data).context(DeserializeSnafu { what: "version" })?;
 
-        ensure!(version.starts_with("eosio::abi/1."), VersionSnafu { version });
+        policy.check(&version)?;
 
         let parser = bin_abi_parser();
         let abi = json!({
@@ -177,7 +276,7 @@ impl ABIDefinition {
         parser.encode_variant(stream, "action[]", &json!(self.actions))?;
         parser.encode_variant(stream, "table[]", &json!(self.tables))?;
         parser.encode_variant(stream, "ricardian_clause[]", &json!(self.ricardian_clauses))?;
-        parser.encode_variant(stream, "error_messages[]", &json!(self.error_messages))?;
+        parser.encode_variant(stream, "error_message[]", &json!(self.error_messages))?;
         // TODO: decide whether to encode this or not depending on version number
         parser.encode_variant(stream, "variant[]", &json!(self.variants))?;
         parser.encode_variant(stream, "action_result[]", &json!(self.action_results))?;
@@ -185,6 +284,30 @@ impl ABIDefinition {
         Ok(())
     }
 
+    /// Return the sha256 hash of the packed ABI, which is what the system contract
+    /// stores in its `abihash` table when the ABI is deployed using `setabi`.
+    ///
+    /// **NOTE:** the packed ABI always contains the `variants` and `action_results`
+    ///           sections, so this only matches the hash of a deployed ABI if it was
+    ///           packed with them too, which is the case for ABIs deployed by recent
+    ///           tools.
+    ///
+    /// ```
+    /// # use kudu::{ABIDefinition, ABIError, Bytes, ByteStream};
+    /// let abi = ABIDefinition::from_str(r#"{"version": "eosio::abi/1.2", "types": []}"#)?;
+    ///
+    /// let mut packed = Bytes::new();
+    /// abi.encode(&mut packed)?;
+    /// let deployed = ABIDefinition::decode(&mut ByteStream::from(&packed))?;
+    /// assert_eq!(deployed.hash()?, abi.hash()?);
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn hash(&self) -> Result<Checksum256> {
+        let mut packed = Bytes::new();
+        self.encode(&mut packed)?;
+        Ok(hash_packed_abi(&packed))
+    }
+
 
 
     pub fn update(&mut self, other: &ABIDefinition) -> Result<()> {
@@ -204,6 +327,49 @@ impl ABIDefinition {
         Ok(())
     }
 
+    /// Return a new ABI containing the items of both `self` and `other`, eg: to decode
+    /// data with a contract ABI extended with custom types.
+    ///
+    /// Items are matched by name (or id for ricardian clauses, and error code for
+    /// error messages) and conflicts between different definitions are resolved
+    /// according to `policy`. The items of `self` come first, in their original order,
+    /// and the version of the result is the highest of both.
+    ///
+    /// ```
+    /// # use kudu::{ABIDefinition, ABIError, abi::MergePolicy};
+    /// let contract = ABIDefinition::from_str(r#"{
+    ///     "version": "eosio::abi/1.1",
+    ///     "structs": [{"name": "hi", "base": "", "fields": [{"name": "user", "type": "name"}]}],
+    ///     "actions": [{"name": "hi", "type": "hi", "ricardian_contract": ""}]
+    /// }"#)?;
+    /// let extension = ABIDefinition::from_str(r#"{
+    ///     "version": "eosio::abi/1.2",
+    ///     "structs": [{"name": "greeting", "base": "", "fields": [{"name": "text", "type": "string"}]}],
+    ///     "action_results": [{"name": "hi", "result_type": "greeting"}]
+    /// }"#)?;
+    /// let merged = contract.merge(&extension, MergePolicy::Error)?;
+    /// assert_eq!(merged.version, "eosio::abi/1.2");
+    /// assert_eq!(merged.structs.len(), 2);
+    /// assert_eq!(merged.action_results[0].result_type, "greeting");
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn merge(&self, other: &ABIDefinition, policy: MergePolicy) -> Result<ABIDefinition> {
+        Ok(ABIDefinition {
+            version: self.version.clone().max(other.version.clone()),
+            types: merge_items("type", &self.types, &other.types, |t| t.new_type_name.clone(), policy)?,
+            structs: merge_items("struct", &self.structs, &other.structs, |s| s.name.clone(), policy)?,
+            actions: merge_items("action", &self.actions, &other.actions, |a| a.name.to_string(), policy)?,
+            tables: merge_items("table", &self.tables, &other.tables, |t| t.name.to_string(), policy)?,
+            ricardian_clauses: merge_items("ricardian clause", &self.ricardian_clauses, &other.ricardian_clauses,
+                                           |c| c.id.clone(), policy)?,
+            error_messages: merge_items("error message", &self.error_messages, &other.error_messages,
+                                        |e| e.error_code.to_string(), policy)?,
+            variants: merge_items("variant", &self.variants, &other.variants, |v| v.name.clone(), policy)?,
+            action_results: merge_items("action result", &self.action_results, &other.action_results,
+                                        |r| r.name.to_string(), policy)?,
+        })
+    }
+
     // FIXME: do we really need this? we should remove it
     pub fn with_contract_abi(mut self) -> Result<Self> {
         // ref impl: `spring/libraries/chain/eosio_contract_abi.cpp`
@@ -230,6 +396,8 @@ impl Default for ABIDefinition {
 
 // FIXME: review this, can't we have a single `to_bin`/`encode` method instead of this duplication?
 impl ABISerializable for ABIDefinition {
+    // `to_bin()` cannot fail, use `ABIDefinition::encode()` to get an error on invalid definitions
+    #[allow(clippy::unwrap_used)]
     fn to_bin(&self, stream: &mut Bytes) {
         self.encode(stream).unwrap()  // safe unwrap
     }
@@ -239,16 +407,99 @@ impl ABISerializable for ABIDefinition {
 }
 
 
+/// Merge two lists of ABI items identified by the given key, see [`ABIDefinition::merge()`].
+fn merge_items<T, F>(kind: &'static str, ours: &[T], theirs: &[T], key: F, policy: MergePolicy)
+                     -> Result<Vec<T>>
+where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> String,
+{
+    let mut result = ours.to_vec();
+    for item in theirs {
+        let name = key(item);
+        match result.iter().position(|existing| key(existing) == name) {
+            None => result.push(item.clone()),
+            Some(pos) if result[pos] == *item => {},
+            Some(pos) => match policy {
+                MergePolicy::Error => MergeConflictSnafu { kind, name }.fail()?,
+                MergePolicy::KeepOurs => {},
+                MergePolicy::KeepTheirs => result[pos] = item.clone(),
+            },
+        }
+    }
+    Ok(result)
+}
+
+/// Return the sha256 hash of an already packed ABI, see [`ABIDefinition::hash()`].
+pub fn hash_packed_abi(packed: &[u8]) -> Checksum256 {
+    let hash: [u8; 32] = Sha256::digest(packed).into();
+    hash.into()
+}
+
+#[allow(clippy::unwrap_used)]  // safe unwrap, the ABI schema is embedded and known to be valid
 pub fn abi_schema() -> &'static ABIDefinition {
     static ABI_SCHEMA_ONCE: OnceLock<ABIDefinition> = OnceLock::new();
     ABI_SCHEMA_ONCE.get_or_init(|| { ABIDefinition::from_str(ABI_SCHEMA).unwrap() })
 }
 
+/// Remove a leading BOM, comments and trailing commas from a JSON document, leaving
+/// the contents of strings untouched.
+fn strip_non_strict_json(s: &str) -> String {
+    let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            match c {
+                '\\' => result.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {},
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                result.push(c);
+            },
+            ('/', Some('/')) => {
+                // keep the newline so that error positions stay meaningful
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            },
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' { break; }
+                    prev = c;
+                }
+                result.push(' ');
+            },
+            ('}' | ']', _) => {
+                // comments have already been removed, so a trailing comma is the
+                // last non-whitespace character
+                let len = result.trim_end().len();
+                if result[..len].ends_with(',') {
+                    result.remove(len - 1);
+                }
+                result.push(c);
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
 // TODO: check if this is still needed once we have Serde de/serialization to/from a binary stream
+#[allow(clippy::unwrap_used)]  // safe unwrap, the ABI schema is embedded and known to be valid
 fn bin_abi_parser() -> &'static ABI {
     static BIN_ABI_PARSER: OnceLock<ABI> = OnceLock::new();
     BIN_ABI_PARSER.get_or_init(|| {
-        ABI::from_definition(abi_schema()).unwrap()  // safe unwrap
+        ABI::from_definition(abi_schema()).unwrap()
     })
 }
 
@@ -302,9 +553,9 @@ mod tests {
             "four": ['f', 'o', 'u', 'r'],
         });
 
-        let abi = ABI::from_definition(&abi).unwrap();  // safe unwrap
+        let abi = ABI::from_definition(&abi)?;
         let mut ds = Bytes::new();
-        abi.encode_variant(&mut ds, "bar", &obj).unwrap();
+        abi.encode_variant(&mut ds, "bar", &obj)?;
 
         assert_eq!(&ds.to_hex().to_uppercase(), "036F6E65020100000000000028CF01040166016F01750172");
 
@@ -313,4 +564,138 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_lenient_parsing() -> Result<(), ABIError> {
+        let abi = "\u{feff}{
+            /* generated by cdt */
+            \"version\": \"eosio::abi/1.2\",
+            \"structs\": [{
+                \"name\": \"hi\",  // say hi
+                \"base\": \"\",
+                \"fields\": [
+                    {\"name\": \"user\", \"type\": \"name\"},
+                ],
+            }],
+            \"ricardian_clauses\": [{
+                \"id\": \"// not a comment, /* nor this */\",
+                \"body\": \"trailing ,] and escaped \\\" ,}\"
+            }],
+        }";
+
+        // the strict parser rejects non-strict JSON
+        assert!(ABIDefinition::from_str(abi).is_err());
+
+        let lenient = ABIDefinition::from_str_lenient(abi)?;
+        assert_eq!(lenient.structs[0].fields[0], Field { name: "user".to_owned(), type_: "name".to_owned() });
+        assert_eq!(lenient.ricardian_clauses[0].id, "// not a comment, /* nor this */");
+        assert_eq!(lenient.ricardian_clauses[0].body, "trailing ,] and escaped \" ,}");
+
+        // strict JSON is parsed the same by both
+        let strict = serde_json::to_string(&lenient)?;
+        assert_eq!(ABIDefinition::from_str_lenient(&strict)?, ABIDefinition::from_str(&strict)?);
+
+        // only one trailing comma is allowed
+        assert!(ABIDefinition::from_str_lenient(r#"{"version": "eosio::abi/1.2",, }"#).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash() -> Result<(), ABIError> {
+        let abi = ABIDefinition::from_str(ABI_EXAMPLE)?;
+
+        let mut packed = Bytes::new();
+        abi.encode(&mut packed)?;
+        let hash: [u8; 32] = Sha256::digest(&packed[..]).into();
+        assert_eq!(abi.hash()?, hash.into());
+        assert_eq!(abi.hash()?, hash_packed_abi(&packed));
+
+        // the hash doesn't depend on the JSON formatting, only on the contents
+        let reformatted = ABIDefinition::from_str(&serde_json::to_string_pretty(&abi)?)?;
+        assert_eq!(reformatted.hash()?, abi.hash()?);
+        assert_eq!(ABIDefinition::decode(&mut packed.view())?.hash()?, abi.hash()?);
+
+        let mut modified = abi.clone();
+        modified.structs.pop();
+        assert_ne!(modified.hash()?, abi.hash()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_policy() -> Result<(), ABIError> {
+        let abi = r#"{"version": "eosio::abi/2.0", "types": [{"new_type_name": "account", "type": "name"}]}"#;
+
+        assert!(matches!(ABIDefinition::from_str_with_options(abi, VersionPolicy::Strict),
+                         Err(ABIError::VersionError { .. })));
+        let def = ABIDefinition::from_str_with_options(abi, VersionPolicy::BestEffort)?;
+        assert_eq!(def.types[0].new_type_name, "account");
+
+        // supported versions are accepted by both policies
+        let mut supported = def.clone();
+        supported.version = "eosio::abi/1.2".to_owned();
+        let supported = serde_json::to_string(&supported)?;
+        assert!(ABIDefinition::from_str_with_options(&supported, VersionPolicy::Strict).is_ok());
+        assert!(ABIDefinition::from_str_with_options(&supported, VersionPolicy::BestEffort).is_ok());
+
+        // versions which are not ABI versions are always rejected
+        let invalid = r#"{"version": "not_an_abi/1.0"}"#;
+        assert!(ABIDefinition::from_str_with_options(invalid, VersionPolicy::BestEffort).is_err());
+
+        // binary ABIs
+        let mut bin = Bytes::new();
+        def.encode(&mut bin)?;
+        assert!(ABIDefinition::decode(&mut bin.view()).is_err());
+        assert_eq!(ABIDefinition::decode_with_options(&mut bin.view(), VersionPolicy::BestEffort)?, def);
+
+        // ABIs can be loaded from the definition
+        assert!(ABI::from_definition(&def).is_err());
+        let abi = ABI::new().with_version_policy(VersionPolicy::BestEffort).with_definition(&def)?;
+        assert!(abi.has_struct("account"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge() -> Result<(), ABIError> {
+        let ours = ABIDefinition::from_str(r#"{
+            "version": "eosio::abi/1.1",
+            "types": [{"new_type_name": "account", "type": "name"}],
+            "structs": [
+                {"name": "a", "base": "", "fields": [{"name": "x", "type": "uint8"}]},
+                {"name": "b", "base": "", "fields": []}
+            ],
+            "error_messages": [{"error_code": 1, "error_msg": "oops"}]
+        }"#)?;
+        let theirs = ABIDefinition::from_str(r#"{
+            "version": "eosio::abi/1.1",
+            "types": [{"new_type_name": "account", "type": "name"}],
+            "structs": [
+                {"name": "c", "base": "", "fields": []},
+                {"name": "a", "base": "", "fields": [{"name": "x", "type": "uint16"}]}
+            ],
+            "error_messages": [{"error_code": 2, "error_msg": "ouch"}]
+        }"#)?;
+        let names = |abi: &ABIDefinition| abi.structs.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+
+        // identical items are kept once, and different ones are a conflict by default
+        let result = ours.merge(&theirs, MergePolicy::Error);
+        assert!(matches!(result, Err(ABIError::MergeConflictError { kind: "struct", ref name, .. }) if name == "a"));
+
+        let merged = ours.merge(&theirs, MergePolicy::KeepOurs)?;
+        assert_eq!(names(&merged), ["a", "b", "c"]);
+        assert_eq!(merged.structs[0].fields[0].type_, "uint8");
+        assert_eq!(merged.types.len(), 1);
+        assert_eq!(merged.error_messages.len(), 2);
+
+        let merged = ours.merge(&theirs, MergePolicy::KeepTheirs)?;
+        assert_eq!(names(&merged), ["a", "b", "c"]);
+        assert_eq!(merged.structs[0].fields[0].type_, "uint16");
+
+        // merging an ABI with itself is a no-op
+        assert_eq!(ours.merge(&ours, MergePolicy::Error)?, ours);
+
+        Ok(())
+    }
 }
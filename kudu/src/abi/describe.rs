@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt;
+
+use serde::Serialize;
+
+/// Recursive description of a type defined in an ABI, as returned by
+/// [`ABI::describe()`].
+///
+/// Typedefs are resolved, so the description only contains builtin types,
+/// structs (including the fields of their base structs), variants and the
+/// array/optional/binary extension wrappers around them.
+///
+/// Its `Display` implementation shows the type as an indented tree.
+///
+/// [`ABI::describe()`]: crate::ABI::describe
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeDescription {
+    /// A builtin Antelope type, eg: `name`, `asset`, `uint64`, etc.
+    Builtin { name: String },
+    Array { items: Box<TypeDescription> },
+    Optional { value: Box<TypeDescription> },
+    /// A field that can be omitted if it is at the end of a struct, ie: its type
+    /// has a `$` suffix
+    BinaryExtension { value: Box<TypeDescription> },
+    Struct { name: String, fields: Vec<FieldDescription> },
+    Variant { name: String, alternatives: Vec<TypeDescription> },
+    /// A struct or variant that is already being described higher up in the
+    /// type graph, this is used to describe recursive types
+    Recursive { name: String },
+}
+
+/// A field of a struct, as found in a [`TypeDescription`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FieldDescription {
+    pub name: String,
+    /// Type of the field as declared in the ABI, before typedefs are resolved
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub description: TypeDescription,
+}
+
+impl TypeDescription {
+    /// Return a one-line summary of the type, eg: `array of struct permission_level`
+    pub fn summary(&self) -> String {
+        match self {
+            TypeDescription::Builtin { name } => name.clone(),
+            TypeDescription::Array { items } => format!("array of {}", items.summary()),
+            TypeDescription::Optional { value } => format!("optional {}", value.summary()),
+            TypeDescription::BinaryExtension { value } => format!("{} (binary extension)", value.summary()),
+            TypeDescription::Struct { name, .. } => format!("struct {name}"),
+            TypeDescription::Variant { name, .. } => format!("variant {name}"),
+            TypeDescription::Recursive { name } => format!("{name} (recursive)"),
+        }
+    }
+
+    fn fmt_children(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        match self {
+            TypeDescription::Array { items: inner }
+            | TypeDescription::Optional { value: inner }
+            | TypeDescription::BinaryExtension { value: inner } => inner.fmt_children(f, depth),
+            TypeDescription::Struct { fields, .. } => {
+                for field in fields {
+                    writeln!(f, "{indent}{}: {}", field.name, field.description.summary())?;
+                    field.description.fmt_children(f, depth + 1)?;
+                }
+                Ok(())
+            },
+            TypeDescription::Variant { alternatives, .. } => {
+                for alt in alternatives {
+                    writeln!(f, "{indent}| {}", alt.summary())?;
+                    alt.fmt_children(f, depth + 1)?;
+                }
+                Ok(())
+            },
+            TypeDescription::Builtin { .. } | TypeDescription::Recursive { .. } => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for TypeDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.summary())?;
+        self.fmt_children(f, 1)
+    }
+}
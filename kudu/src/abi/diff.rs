@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+
+use crate::Bytes;
+
+/// A single difference between two values, as found by [`ABI::diff_binary()`].
+///
+/// [`ABI::diff_binary()`]: crate::ABI::diff_binary
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difference {
+    /// Path of the differing value in the decoded data, eg: `actions[0].data.memo`.
+    /// Empty if the root values differ.
+    pub path: String,
+    /// Value in the first payload, `None` if it is missing.
+    pub a: Option<JsonValue>,
+    /// Value in the second payload, `None` if it is missing.
+    pub b: Option<JsonValue>,
+}
+
+/// Result of comparing two binary payloads decoded using an ABI, as returned
+/// by [`ABI::diff_binary()`].
+///
+/// Its `Display` implementation shows one difference per line.
+///
+/// [`ABI::diff_binary()`]: crate::ABI::diff_binary
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueDiff {
+    /// Decoded value of the first payload
+    pub a: JsonValue,
+    /// Decoded value of the second payload
+    pub b: JsonValue,
+    /// Field-level differences between both decoded values, in depth-first order
+    pub differences: Vec<Difference>,
+    /// Data left in the first payload after decoding the value
+    pub leftover_a: Bytes,
+    /// Data left in the second payload after decoding the value
+    pub leftover_b: Bytes,
+}
+
+impl ValueDiff {
+    pub fn new(a: JsonValue, b: JsonValue, leftover_a: Bytes, leftover_b: Bytes) -> Self {
+        let mut differences = vec![];
+        diff_values(&mut String::new(), &a, &b, &mut differences);
+        ValueDiff { a, b, differences, leftover_a, leftover_b }
+    }
+
+    /// Return `true` if both payloads are identical once decoded.
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty() && self.leftover_a == self.leftover_b
+    }
+}
+
+fn diff_values(path: &mut String, a: &JsonValue, b: &JsonValue, result: &mut Vec<Difference>) {
+    let mut diff_child = |path: &mut String, child: &str, a: Option<&JsonValue>, b: Option<&JsonValue>| {
+        let len = path.len();
+        path.push_str(child);
+        match (a, b) {
+            (Some(a), Some(b)) => diff_values(path, a, b, result),
+            (a, b) => result.push(Difference { path: path.clone(), a: a.cloned(), b: b.cloned() }),
+        }
+        path.truncate(len);
+    };
+
+    match (a, b) {
+        (JsonValue::Object(obj_a), JsonValue::Object(obj_b)) => {
+            let sep = if path.is_empty() { "" } else { "." };
+            for (key, value) in obj_a {
+                diff_child(path, &format!("{sep}{key}"), Some(value), obj_b.get(key));
+            }
+            for (key, value) in obj_b.iter().filter(|(key, _)| !obj_a.contains_key(*key)) {
+                diff_child(path, &format!("{sep}{key}"), None, Some(value));
+            }
+        },
+        (JsonValue::Array(arr_a), JsonValue::Array(arr_b)) => {
+            for i in 0..arr_a.len().max(arr_b.len()) {
+                diff_child(path, &format!("[{i}]"), arr_a.get(i), arr_b.get(i));
+            }
+        },
+        (a, b) => {
+            if a != b {
+                result.push(Difference { path: path.clone(), a: Some(a.clone()), b: Some(b.clone()) });
+            }
+        },
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "<root>" } else { &self.path };
+        match (&self.a, &self.b) {
+            (Some(a), Some(b)) => write!(f, "{path}: {a} != {b}"),
+            (Some(a), None) => write!(f, "{path}: {a} (missing in b)"),
+            (None, Some(b)) => write!(f, "{path}: {b} (missing in a)"),
+            (None, None) => write!(f, "{path}: missing in both"),
+        }
+    }
+}
+
+impl fmt::Display for ValueDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diff in &self.differences {
+            writeln!(f, "{diff}")?;
+        }
+        if self.leftover_a != self.leftover_b {
+            writeln!(f, "leftover data: \"{}\" != \"{}\"", self.leftover_a.hex(), self.leftover_b.hex())?;
+        }
+        Ok(())
+    }
+}
@@ -10,7 +10,7 @@ use snafu::Snafu;
 
 use kudu_macros::with_location;
 
-use crate::{InvalidValue, impl_auto_error_conversion, SerializeError};
+use crate::{abi::BuiltinTypeError, InvalidValue, impl_auto_error_conversion, SerializeError};
 
 #[with_location]
 #[derive(Debug, Snafu)]
@@ -25,9 +25,15 @@ pub enum ABIError {
     #[snafu(display(r#"incompatible versions: "{a}" vs. "{b}""#))]
     IncompatibleVersionError { a: String, b: String },
 
+    #[snafu(display("conflicting definitions for {kind} '{name}' while merging ABIs"))]
+    MergeConflictError { kind: &'static str, name: String },
+
     #[snafu(display("integrity error: {message}"))]
     IntegrityError { message: String },
 
+    #[snafu(display("cannot resolve type `{typename}`: more than {max_depth} nested typedefs"))]
+    TypedefDepthError { typename: String, max_depth: usize },
+
     #[snafu(display("encode error: {message}"))]
     EncodeError { message: String },
 
@@ -46,12 +52,28 @@ pub enum ABIError {
     #[snafu(display("no matching ABI for typename: '{name}'"))]
     NoMatchingABIError { name: String },
 
+    #[snafu(display("no action result defined for action: '{action}'"))]
+    UnknownActionResultError { action: String },
+
     #[snafu(display("leftover data in stream"))]
     LeftoverDataInStream,
 
+    #[snafu(display("cannot expand binary field `{path}`"))]
+    ExpandError {
+        path: String,
+        #[snafu(source(from(ABIError, Box::new)))]
+        source: Box<ABIError>,
+    },
+
     #[snafu(display("cannot convert variant to AntelopeValue: {v}"))]
     VariantConversionError { v: Box<JsonValue>, source: InvalidValue },
 
+    #[snafu(display("error in custom builtin type `{typename}`"))]
+    BuiltinTypeError { typename: String, source: BuiltinTypeError },
+
+    #[snafu(display("error in decode hook for type `{typename}`"))]
+    DecodeHookError { typename: String, source: BuiltinTypeError },
+
     #[snafu(display(r#"cannot convert given variant {value} to Antelope type "{typename}""#))]
     IncompatibleVariantTypes {
         typename: String,
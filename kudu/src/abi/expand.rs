@@ -0,0 +1,280 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use snafu::{OptionExt, ResultExt};
+use tracing::debug;
+
+use crate::{
+    ABI, ABIError, Bytes, abi::registry,
+    abi::error::{DecodeSnafu, DeserializeSnafu, ExpandSnafu, HexABISnafu},
+    chain::unpack_data,
+};
+
+
+/// Resolve the ABI of a contract given its account name, see [`Expander::action_data_with()`].
+pub type ABIResolver = Arc<dyn Fn(&str) -> Option<Arc<ABI>> + Send + Sync>;
+
+/// How to decode the binary fields matched by a rule of an [`Expander`].
+#[derive(Clone)]
+pub enum FieldDecoder {
+    /// Decode the field as `typename` using the given ABI.
+    Type { abi: Arc<ABI>, typename: String },
+    /// Decode the field as the data of an action, using the `account` and `name`
+    /// fields next to it to find the ABI and the type of the action.
+    ActionData { resolver: ABIResolver },
+    /// Decompress the field if needed, according to the `compression` field next
+    /// to it, and decode it as a transaction.
+    PackedTransaction,
+}
+
+impl fmt::Debug for FieldDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldDecoder::Type { typename, .. } => write!(f, "Type({typename})"),
+            FieldDecoder::ActionData { .. } => f.write_str("ActionData"),
+            FieldDecoder::PackedTransaction => f.write_str("PackedTransaction"),
+        }
+    }
+}
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    /// `*`: any field of an object or item of an array
+    Any,
+    /// `**`: any number of levels, including none
+    AnyDepth,
+}
+
+fn parse_path(path: &str) -> Vec<Segment> {
+    path.split('.').filter(|s| !s.is_empty()).map(|s| match s {
+        "*" => Segment::Any,
+        "**" => Segment::AnyDepth,
+        key => Segment::Key(key.to_string()),
+    }).collect()
+}
+
+
+/// Post-processing pass on decoded JSON data, further decoding the binary fields
+/// it contains (encoded as hex strings) in place.
+///
+/// Data decoded from an ABI often embeds other binary data: the `data` of actions,
+/// the `packed_trx` of transactions in a block, etc. An `Expander` holds a list of
+/// rules mapping a field path to a [`FieldDecoder`] and applies them in order, so
+/// that a rule can match fields which have been expanded by a previous one.
+///
+/// Paths are made of field names separated by `.`, where `*` matches any field or
+/// array item and `**` matches any number of levels. Fields which are not strings
+/// are left untouched, so expanding a value twice is harmless.
+///
+/// By default, fields that cannot be decoded (eg: because the ABI of an action is
+/// unknown) are left as is; use [`Expander::strict()`] to return an error instead.
+///
+/// # Example
+///
+/// ```
+/// # use kudu::{ABIError, json};
+/// # use kudu::abi::Expander;
+/// let mut trx = json!({
+///     "actions": [{
+///         "account": "eosio.token",
+///         "name": "transfer",
+///         "authorization": [],
+///         "data": "608c31c6187315d6708c31c6187315d60100000000000000045359530000000000",
+///     }],
+/// });
+/// Expander::conventional().expand(&mut trx)?;
+/// assert_eq!(trx["actions"][0]["data"]["quantity"], "0.0001 SYS");
+/// # Ok::<(), ABIError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Expander {
+    rules: Vec<(Vec<Segment>, FieldDecoder)>,
+    strict: bool,
+}
+
+impl Expander {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return an expander following the usual conventions of nodeos data:
+    ///  - `**.packed_trx` fields are packed transactions
+    ///  - `**.actions.*.data`, `**.context_free_actions.*.data` and `**.act.data`
+    ///    fields (the latter as found in action traces) are action data, decoded
+    ///    using the ABIs in the [registry]
+    pub fn conventional() -> Self {
+        Self::new()
+            .packed_transaction("**.packed_trx")
+            .action_data("**.actions.*.data")
+            .action_data("**.context_free_actions.*.data")
+            .action_data("**.act.data")
+    }
+
+    /// Add a rule decoding the fields matching `path` as `typename` using `abi`.
+    pub fn field(self, path: &str, abi: Arc<ABI>, typename: &str) -> Self {
+        self.rule(path, FieldDecoder::Type { abi, typename: typename.to_string() })
+    }
+
+    /// Add a rule decoding the fields matching `path` as action data, using the
+    /// ABIs in the [registry].
+    pub fn action_data(self, path: &str) -> Self {
+        self.action_data_with(path, Arc::new(|account| registry::get_abi(account).ok()))
+    }
+
+    /// Add a rule decoding the fields matching `path` as action data, using the
+    /// given function to find the ABI of a contract.
+    pub fn action_data_with(self, path: &str, resolver: ABIResolver) -> Self {
+        self.rule(path, FieldDecoder::ActionData { resolver })
+    }
+
+    /// Add a rule decoding the fields matching `path` as packed transactions.
+    pub fn packed_transaction(self, path: &str) -> Self {
+        self.rule(path, FieldDecoder::PackedTransaction)
+    }
+
+    pub fn rule(mut self, path: &str, decoder: FieldDecoder) -> Self {
+        self.rules.push((parse_path(path), decoder));
+        self
+    }
+
+    /// Set whether fields that cannot be decoded result in an error.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Apply all the rules in order to the given value, replacing the binary fields
+    /// they match with their decoded value.
+    pub fn expand(&self, value: &mut JsonValue) -> Result<(), ABIError> {
+        for (path, decoder) in &self.rules {
+            let mut expand_field = |obj: &mut JsonMap<String, JsonValue>, key: &str, field_path: &str| {
+                match decode_field(decoder, obj, key) {
+                    Ok(Some(decoded)) => { obj.insert(key.to_string(), decoded); },
+                    Ok(None) => {},
+                    Err(e) if self.strict => return Err(e).context(ExpandSnafu { path: field_path }),
+                    Err(e) => debug!("cannot expand field `{field_path}`: {e}"),
+                }
+                Ok(())
+            };
+            walk(value, path, &mut String::new(), &mut expand_field)?;
+        }
+        Ok(())
+    }
+
+    /// Return the given value with all its binary fields expanded, see [`Expander::expand()`].
+    pub fn expanded(&self, mut value: JsonValue) -> Result<JsonValue, ABIError> {
+        self.expand(&mut value)?;
+        Ok(value)
+    }
+}
+
+
+type FieldCallback<'a> = dyn FnMut(&mut JsonMap<String, JsonValue>, &str, &str) -> Result<(), ABIError> + 'a;
+
+/// Call `f` on all the object fields matching `path`, with the object containing
+/// the field, the field name and its full path.
+fn walk(value: &mut JsonValue, path: &[Segment], current: &mut String, f: &mut FieldCallback) -> Result<(), ABIError> {
+    let Some((segment, rest)) = path.split_first() else { return Ok(()) };
+
+    if *segment == Segment::AnyDepth {
+        walk(value, rest, current, f)?;
+        return for_each_child(value, current, &mut |child, current| walk(child, path, current, f));
+    }
+
+    if rest.is_empty() {
+        let JsonValue::Object(obj) = value else { return Ok(()) };
+        let keys: Vec<String> = match segment {
+            Segment::Key(key) if obj.contains_key(key) => vec![key.clone()],
+            Segment::Any => obj.keys().cloned().collect(),
+            _ => vec![],
+        };
+        for key in keys {
+            let len = current.len();
+            push_key(current, &key);
+            f(obj, &key, current)?;
+            current.truncate(len);
+        }
+        return Ok(());
+    }
+
+    match segment {
+        Segment::Key(key) => match value {
+            JsonValue::Object(obj) => match obj.get_mut(key) {
+                Some(child) => {
+                    let len = current.len();
+                    push_key(current, key);
+                    walk(child, rest, current, f)?;
+                    current.truncate(len);
+                    Ok(())
+                },
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        },
+        _ => for_each_child(value, current, &mut |child, current| walk(child, rest, current, f)),
+    }
+}
+
+type ChildCallback<'a> = dyn FnMut(&mut JsonValue, &mut String) -> Result<(), ABIError> + 'a;
+
+fn for_each_child(value: &mut JsonValue, current: &mut String, f: &mut ChildCallback) -> Result<(), ABIError> {
+    let len = current.len();
+    match value {
+        JsonValue::Object(obj) => for (key, child) in obj.iter_mut() {
+            push_key(current, key);
+            f(child, current)?;
+            current.truncate(len);
+        },
+        JsonValue::Array(arr) => for (i, child) in arr.iter_mut().enumerate() {
+            current.push_str(&format!("[{i}]"));
+            f(child, current)?;
+            current.truncate(len);
+        },
+        _ => {},
+    }
+    Ok(())
+}
+
+fn push_key(path: &mut String, key: &str) {
+    if !path.is_empty() { path.push('.'); }
+    path.push_str(key);
+}
+
+/// Decode the field `key` of `obj`, return `None` if it is not a binary field.
+fn decode_field(decoder: &FieldDecoder, obj: &JsonMap<String, JsonValue>, key: &str) -> Result<Option<JsonValue>, ABIError> {
+    let Some(JsonValue::String(hex)) = obj.get(key) else { return Ok(None) };
+    let data = Bytes::from_hex(hex).context(HexABISnafu)?;
+
+    let sibling = |name: &str| obj.get(name).and_then(JsonValue::as_str).with_context(|| DecodeSnafu {
+        message: format!("missing `{name}` field next to `{key}`"),
+    });
+
+    Ok(Some(match decoder {
+        FieldDecoder::Type { abi, typename } => abi.binary_to_variant(typename.as_str(), data)?,
+        FieldDecoder::ActionData { resolver } => {
+            let account = sibling("account")?;
+            let abi = resolver(account).with_context(|| DecodeSnafu {
+                message: format!("no ABI for contract `{account}`"),
+            })?;
+            abi.binary_to_variant(sibling("name")?, data)?
+        },
+        FieldDecoder::PackedTransaction => {
+            let compression = match obj.get("compression") {
+                None => 0,
+                Some(JsonValue::String(c)) if c == "none" => 0,
+                Some(JsonValue::String(c)) if c == "zlib" => 1,
+                Some(c) => c.as_u64().and_then(|c| u8::try_from(c).ok()).with_context(|| DecodeSnafu {
+                    message: format!("invalid compression: {c}"),
+                })?,
+            };
+            let data = unpack_data(compression, &data).context(DeserializeSnafu { what: "packed transaction" })?;
+            registry::get_abi("transaction")?.binary_to_variant("transaction", Bytes::from(data))?
+        },
+    }))
+}
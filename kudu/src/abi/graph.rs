@@ -0,0 +1,170 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::TypeName;
+
+/// Kind of a type defined in an ABI, see [`DependencyGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeKind {
+    Struct,
+    Variant,
+    Typedef,
+}
+
+/// Graph of the dependencies between the types defined in an ABI, as returned by
+/// [`ABI::dependency_graph()`].
+///
+/// Nodes are the structs, variants and typedefs of the ABI, and there is an edge
+/// from a type to each of the other ABI types it references directly: base struct,
+/// field types, variant alternatives and aliased type for typedefs. Array, optional
+/// and binary extension wrappers are looked through, and builtin types are not
+/// part of the graph.
+///
+/// The types used by actions, action results and tables are the roots of the graph,
+/// types that cannot be reached from them are never used by the contract.
+///
+/// [`ABI::dependency_graph()`]: crate::ABI::dependency_graph
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyGraph {
+    pub types: BTreeMap<String, TypeKind>,
+    pub edges: BTreeMap<String, BTreeSet<String>>,
+    pub roots: BTreeSet<String>,
+}
+
+impl DependencyGraph {
+    /// Add a type to the graph, with the (possibly wrapped) types it references.
+    pub(super) fn add_type<'a>(&mut self, name: &str, kind: TypeKind,
+                               references: impl IntoIterator<Item = TypeName<'a>>) {
+        self.types.insert(name.to_string(), kind);
+        let edges = self.edges.entry(name.to_string()).or_default();
+        edges.extend(references.into_iter().map(|t| base_type(t).to_string()));
+    }
+
+    pub(super) fn add_root(&mut self, typename: TypeName) {
+        self.roots.insert(base_type(typename).to_string());
+    }
+
+    /// Remove references to types that are not defined in the ABI, ie: builtin types.
+    pub(super) fn finish(mut self) -> Self {
+        let types = &self.types;
+        for edges in self.edges.values_mut() {
+            edges.retain(|t| types.contains_key(t));
+        }
+        self.roots.retain(|t| types.contains_key(t));
+        self
+    }
+
+    /// Return the ABI types directly referenced by the given type.
+    pub fn dependencies(&self, typename: &str) -> impl Iterator<Item = &str> {
+        self.edges.get(typename).into_iter().flatten().map(String::as_str)
+    }
+
+    /// Return the ABI types directly referencing the given type.
+    pub fn dependents<'a>(&'a self, typename: &'a str) -> impl Iterator<Item = &'a str> {
+        self.edges.iter()
+            .filter(move |(_, edges)| edges.contains(typename))
+            .map(|(t, _)| t.as_str())
+    }
+
+    /// Return all the types that can be reached from the roots of the graph,
+    /// ie: the types actually used by the actions and tables of the contract.
+    pub fn used_types(&self) -> BTreeSet<&str> {
+        let mut result = BTreeSet::new();
+        let mut stack: Vec<&str> = self.roots.iter().map(String::as_str).collect();
+        while let Some(t) = stack.pop() {
+            if result.insert(t) {
+                stack.extend(self.dependencies(t));
+            }
+        }
+        result
+    }
+
+    /// Return the types that are defined in the ABI but never used by its actions
+    /// or tables, directly or indirectly.
+    pub fn unused_types(&self) -> Vec<&str> {
+        let used = self.used_types();
+        self.types.keys().map(String::as_str).filter(|t| !used.contains(t)).collect()
+    }
+
+    /// Return a representation of the graph in the [DOT language] of Graphviz.
+    ///
+    /// Roots are drawn with a double border and unused types are greyed out.
+    ///
+    /// [DOT language]: https://graphviz.org/doc/info/lang.html
+    pub fn to_dot(&self) -> String {
+        let used = self.used_types();
+        let mut result = String::from("digraph abi {\n    rankdir=LR;\n    node [fontname=\"monospace\"];\n");
+        for (name, kind) in &self.types {
+            let shape = match kind {
+                TypeKind::Struct => "box",
+                TypeKind::Variant => "diamond",
+                TypeKind::Typedef => "ellipse",
+            };
+            let mut attrs = format!("shape={shape}");
+            if self.roots.contains(name) { attrs += ", peripheries=2"; }
+            if !used.contains(name.as_str()) { attrs += ", style=dashed, color=grey, fontcolor=grey"; }
+            let _ = writeln!(result, "    {name:?} [{attrs}];");
+        }
+        for (name, edges) in &self.edges {
+            for dep in edges {
+                let _ = writeln!(result, "    {name:?} -> {dep:?};");
+            }
+        }
+        result.push_str("}\n");
+        result
+    }
+}
+
+/// Remove all the array, optional and binary extension wrappers around a type.
+fn base_type<'a>(typename: TypeName<'a>) -> &'a str {
+    let mut t = typename.remove_bin_extension();
+    loop {
+        let ft = t.fundamental_type();
+        if ft == t { return t.as_str(); }
+        t = ft;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use crate::ABI;
+
+    #[test]
+    fn test_dependency_graph() -> Result<()> {
+        let abi = ABI::from_str(r#"{
+            "version": "eosio::abi/1.2",
+            "types": [{"new_type_name": "levels", "type": "level[]"}],
+            "structs": [
+                {"name": "level", "base": "", "fields": [{"name": "actor", "type": "name"}]},
+                {"name": "base", "base": "", "fields": [{"name": "auth", "type": "levels?"}]},
+                {"name": "doit", "base": "base", "fields": [{"name": "value", "type": "value$"}]},
+                {"name": "unused", "base": "", "fields": [{"name": "level", "type": "level"}]}
+            ],
+            "variants": [{"name": "value", "types": ["uint64", "level[]"]}],
+            "actions": [{"name": "doit", "type": "doit", "ricardian_contract": ""}]
+        }"#)?;
+
+        let graph = abi.dependency_graph();
+        assert_eq!(graph.types.len(), 6);
+        assert_eq!(graph.dependencies("doit").collect::<Vec<_>>(), ["base", "value"]);
+        assert_eq!(graph.dependencies("base").collect::<Vec<_>>(), ["levels"]);
+        assert_eq!(graph.dependencies("value").collect::<Vec<_>>(), ["level"]);
+        assert_eq!(graph.dependents("level").collect::<Vec<_>>(), ["levels", "unused", "value"]);
+        assert_eq!(graph.unused_types(), ["unused"]);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"doit\" [shape=box, peripheries=2];"));
+        assert!(dot.contains("\"unused\" [shape=box, style=dashed, color=grey, fontcolor=grey];"));
+        assert!(dot.contains("\"levels\" -> \"level\";"));
+        Ok(())
+    }
+}
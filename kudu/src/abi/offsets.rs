@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+use serde::Serialize;
+
+/// Location of a decoded value in the binary input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct ByteSpan {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl ByteSpan {
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+}
+
+impl fmt::Display for ByteSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.offset, self.offset + self.len)
+    }
+}
+
+/// Location of all the values decoded by [`ABI::decode_variant_with_offsets()`],
+/// indexed by their [JSON pointer](https://www.rfc-editor.org/rfc/rfc6901) in the
+/// decoded value (the root value has an empty pointer).
+///
+/// [`ABI::decode_variant_with_offsets()`]: crate::ABI::decode_variant_with_offsets
+pub type ByteOffsets = BTreeMap<String, ByteSpan>;
+
+
+/// Keep track of the JSON pointer of the value being decoded, and of the offsets
+/// of the values decoded so far. A disabled recorder does nothing.
+pub(super) struct OffsetRecorder {
+    pointer: String,
+    offsets: Option<ByteOffsets>,
+}
+
+impl OffsetRecorder {
+    pub fn disabled() -> Self {
+        OffsetRecorder { pointer: String::new(), offsets: None }
+    }
+
+    pub fn enabled() -> Self {
+        OffsetRecorder { pointer: String::new(), offsets: Some(ByteOffsets::new()) }
+    }
+
+    /// Append `token` to the current pointer while calling `f`.
+    pub fn enter<T>(&mut self, token: impl fmt::Display, f: impl FnOnce(&mut Self) -> T) -> T {
+        if self.offsets.is_none() {
+            return f(self);
+        }
+        let len = self.pointer.len();
+        self.pointer.push('/');
+        // escape the token as per RFC 6901
+        for c in token.to_string().chars() {
+            match c {
+                '~' => self.pointer.push_str("~0"),
+                '/' => self.pointer.push_str("~1"),
+                c => self.pointer.push(c),
+            }
+        }
+        let result = f(self);
+        self.pointer.truncate(len);
+        result
+    }
+
+    /// Record the location of the value at the current pointer.
+    pub fn record(&mut self, start: usize, end: usize) {
+        if let Some(offsets) = &mut self.offsets {
+            offsets.insert(self.pointer.clone(), ByteSpan { offset: start, len: end - start });
+        }
+    }
+
+    pub fn into_offsets(self) -> ByteOffsets {
+        self.offsets.unwrap_or_default()
+    }
+}
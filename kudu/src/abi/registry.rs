@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::collections::HashMap;
-use std::sync::{Arc, LazyLock, Mutex};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError};
 
 use snafu::OptionExt;
 
@@ -18,6 +18,7 @@ use crate::{
 // TODO: make abi name a kudu::Name instead of a String
 //       at the very least, we should be able to query using either a kudu::Name or a &str
 // TODO: could we get rid of the `Arc` here since we have a 'static lifetime?
+#[allow(clippy::unwrap_used)]  // safe unwrap, the builtin ABIs are known to be valid
 static REGISTRY: LazyLock<Mutex<HashMap<String, Arc<ABI>>>> = LazyLock::new(|| {
     let mut reg = HashMap::new();
     reg.insert("eosio".to_string(), Arc::new(ABI::from_str(abi::data::EOSIO_ABI).unwrap()));
@@ -27,18 +28,31 @@ static REGISTRY: LazyLock<Mutex<HashMap<String, Arc<ABI>>>> = LazyLock::new(|| {
     Mutex::new(reg)
 });
 
+/// Lock the registry. A panic while holding the lock cannot leave the map in an
+/// inconsistent state, so it is fine to ignore the lock poisoning.
+fn registry() -> MutexGuard<'static, HashMap<String, Arc<ABI>>> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 pub fn load_abi(abi_name: &str, abi: &str) -> Result<(), ABIError> {
-    let mut reg = REGISTRY.lock().unwrap();
+    let mut reg = registry();
     reg.insert(abi_name.to_string(), Arc::new(ABI::from_str(abi)?));
     Ok(())
 }
 
 pub fn get_abi(abi_name: &str) -> Result<Arc<ABI>, ABIError> {
-    REGISTRY.lock().unwrap().get(abi_name).cloned().context(UnknownABISnafu { name: abi_name.to_string() })
+    registry().get(abi_name).cloned().context(UnknownABISnafu { name: abi_name.to_string() })
+}
+
+/// Return the names of all the ABIs in the registry, sorted alphabetically.
+pub fn abi_names() -> Vec<String> {
+    let mut result: Vec<_> = registry().keys().cloned().collect();
+    result.sort();
+    result
 }
 
 pub fn find_abi_for(typename: &str) -> Result<Arc<ABI>, ABIError> {
-    for (_name, abi) in REGISTRY.lock().unwrap().iter() {
+    for (_name, abi) in registry().iter() {
         if abi.has_struct(typename) { return Ok(abi.clone()); }
     }
     NoMatchingABISnafu { name: typename }.fail()
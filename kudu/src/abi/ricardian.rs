@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde_json::Value as JsonValue;
+
+// from: https://github.com/eosnetworkfoundation/ricardian-spec
+//
+// a ricardian contract is a markdown document with an optional YAML front matter
+// (`spec_version`, `title`, `summary`, `icon`), which both contain handlebars-like
+// templates where `{{param}}` is replaced by the value of the action parameter
+//
+// we only support the subset of handlebars that is used in practice by the system
+// contracts: variables (with an optional `nowrap` helper), dotted paths into
+// structs and arrays, and `{{#if}}...{{else}}...{{/if}}` blocks
+
+/// Ricardian contract of an action, as returned by [`ABI::ricardian_for_action()`].
+///
+/// The `title`, `summary` and `body` have the action parameters substituted in
+/// their templates. Variables that cannot be resolved are left untouched.
+///
+/// [`ABI::ricardian_for_action()`]: crate::ABI::ricardian_for_action
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RicardianContract {
+    pub spec_version: Option<String>,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub icon: Option<String>,
+    /// Markdown text of the contract, without its front matter
+    pub body: String,
+}
+
+impl RicardianContract {
+    /// Parse the given ricardian `template` and render it using the action parameters
+    /// given in `args`.
+    pub fn render(template: &str, args: &JsonValue) -> Self {
+        let mut result = RicardianContract::default();
+
+        let (front_matter, body) = split_front_matter(template);
+        for line in front_matter.lines() {
+            let Some((key, value)) = line.split_once(':') else { continue; };
+            let value = unquote(value.trim());
+            match key.trim() {
+                "spec_version" => result.spec_version = Some(value.to_string()),
+                "title" => result.title = Some(render_template(value, args)),
+                "summary" => result.summary = Some(render_template(value, args)),
+                "icon" => result.icon = Some(value.to_string()),
+                _ => {},
+            }
+        }
+
+        result.body = render_template(body.trim_start_matches(['\r', '\n']), args);
+        result
+    }
+}
+
+fn split_front_matter(template: &str) -> (&str, &str) {
+    let Some(rest) = template.trim_start().strip_prefix("---") else {
+        return ("", template);
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let body = &rest[end + 4..];
+            (&rest[..end], body.split_once('\n').map_or("", |(_, body)| body))
+        },
+        None => ("", template),
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(s) = s.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)) {
+            return s;
+        }
+    }
+    s
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    /// Content of a `{{...}}` tag, and its full text
+    Tag(&'a str, &'a str),
+}
+
+fn tokenize(mut template: &str) -> Vec<Token<'_>> {
+    let mut result = vec![];
+    while let Some(start) = template.find("{{") {
+        let Some(len) = template[start..].find("}}") else { break; };
+        let end = start + len + 2;
+        if start > 0 {
+            result.push(Token::Text(&template[..start]));
+        }
+        result.push(Token::Tag(template[start + 2..end - 2].trim(), &template[start..end]));
+        template = &template[end..];
+    }
+    if !template.is_empty() {
+        result.push(Token::Text(template));
+    }
+    result
+}
+
+/// Render the given template, replacing `{{param}}` variables with the
+/// corresponding values in `args`.
+fn render_template(template: &str, args: &JsonValue) -> String {
+    let mut result = String::new();
+    let mut tokens = tokenize(template).into_iter();
+    // an unmatched `{{else}}` or `{{/if}}` stops the rendering of the first block,
+    // so keep going until we have consumed all the tokens
+    while tokens.len() > 0 {
+        render_block(&mut tokens, args, &mut result, true);
+    }
+    result
+}
+
+/// Render tokens until the end of the current block, ie: until a `{{else}}` or
+/// `{{/if}}` tag, and return that tag. If `emit` is false, tokens are consumed
+/// without being output.
+fn render_block<'a>(tokens: &mut impl Iterator<Item = Token<'a>>,
+                    args: &JsonValue,
+                    out: &mut String,
+                    emit: bool) -> Option<&'a str> {
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => if emit { out.push_str(text) },
+            Token::Tag(tag @ ("else" | "/if"), _) => return Some(tag),
+            Token::Tag(tag, raw) => {
+                if let Some(cond) = tag.strip_prefix("#if ") {
+                    let truthy = lookup(args, cond.trim()).is_some_and(is_truthy);
+                    if render_block(tokens, args, out, emit && truthy) == Some("else") {
+                        render_block(tokens, args, out, emit && !truthy);
+                    }
+                }
+                else if emit {
+                    let path = tag.strip_prefix("nowrap ").unwrap_or(tag).trim();
+                    match lookup(args, path) {
+                        Some(JsonValue::String(s)) => out.push_str(s),
+                        Some(value) => out.push_str(&value.to_string()),
+                        None => out.push_str(raw),
+                    }
+                }
+            },
+        }
+    }
+    None
+}
+
+fn lookup<'a>(args: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    path.split('.').try_fold(args, |value, key| match value {
+        JsonValue::Object(obj) => obj.get(key),
+        JsonValue::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(b) => *b,
+        JsonValue::Number(n) => n.as_f64() != Some(0.0),
+        JsonValue::String(s) => !s.is_empty(),
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Object(_) => true,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_render_ricardian() {
+        // from the `transfer` action of the `eosio.token` contract
+        let template = concat!(
+            "---\n",
+            "spec_version: \"0.2.0\"\n",
+            "title: Transfer Tokens\n",
+            "summary: 'Send {{nowrap quantity}} from {{nowrap from}} to {{nowrap to}}'\n",
+            "icon: http://127.0.0.1/token.png#207ff68b\n",
+            "---\n",
+            "\n",
+            "{{from}} agrees to send {{quantity}} to {{to}}.\n",
+            "\n",
+            "{{#if memo}}There is a memo attached to the transfer stating:\n",
+            "{{memo}}\n",
+            "{{else}}No memo.\n",
+            "{{/if}}",
+            "Signed by {{$action.authorization.0.actor}}.",
+        );
+
+        let args = json!({"from": "alice", "to": "bob", "quantity": "1.0000 EOS", "memo": "thanks"});
+        let contract = RicardianContract::render(template, &args);
+        assert_eq!(contract.spec_version.as_deref(), Some("0.2.0"));
+        assert_eq!(contract.title.as_deref(), Some("Transfer Tokens"));
+        assert_eq!(contract.summary.as_deref(), Some("Send 1.0000 EOS from alice to bob"));
+        assert_eq!(contract.icon.as_deref(), Some("http://127.0.0.1/token.png#207ff68b"));
+        assert_eq!(contract.body, concat!(
+            "alice agrees to send 1.0000 EOS to bob.\n\n",
+            "There is a memo attached to the transfer stating:\nthanks\n",
+            "Signed by {{$action.authorization.0.actor}}.",
+        ));
+
+        let args = json!({"from": "alice", "to": "bob", "quantity": "1.0000 EOS", "memo": ""});
+        let contract = RicardianContract::render(template, &args);
+        assert!(contract.body.contains("No memo.\n"));
+        assert!(!contract.body.contains("thanks"));
+
+        // templates without front matter are rendered as a whole
+        let contract = RicardianContract::render("{{a.b.1}} and {{c}}", &json!({"a": {"b": [1, 2]}, "c": true}));
+        assert_eq!(contract.title, None);
+        assert_eq!(contract.body, "2 and true");
+    }
+}
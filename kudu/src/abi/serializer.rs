@@ -1,29 +1,48 @@
 // SPDX-FileCopyrightText: 2023-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock};
 
 use serde_json::{
     json,
     Map as JsonMap,
     Value as JsonValue,
 };
-use snafu::{ensure, ResultExt};
+use hex::FromHexError;
+use sha2::{Digest, Sha256};
+use snafu::{ensure, OptionExt, ResultExt};
 use strum::VariantNames;
 use tracing::{debug, warn, instrument};
 
 use crate::{
-    AntelopeType, AntelopeValue, Bytes, Name, VarUint32, TypeName,
-    ABIDefinition, ByteStream, ABISerializable,
+    ActionName, AntelopeType, AntelopeValue, Bytes, Checksum256, TableName, VarUint32, TypeName,
+    ABIDefinition, ByteStream, ABISerializable, SerializeError,
     abi::error::*,
+    abi::builtin::{BuiltinType, BuiltinTypeError, DecodeHook},
+    abi::cache::{CacheStats, EncodeCache, ParsedCache},
+    abi::describe::{FieldDescription, TypeDescription},
+    abi::diff::ValueDiff,
+    abi::graph::{DependencyGraph, TypeKind},
+    abi::offsets::{ByteOffsets, OffsetRecorder},
+    abi::ricardian::RicardianContract,
     abi::definition::{
-        TypeName as TypeNameOwned, Struct, Variant
+        TypeName as TypeNameOwned, Field, Struct, Variant, VersionPolicy
     },
-    config,
+    config::{self, SerializationConfig},
 };
 
 type Result<T, E = ABIError> = core::result::Result<T, E>;
 
+/// Maximum number of ABIs kept in the cache used by [`ABI::from_hex_abi_cached()`]
+/// and [`ABI::from_bin_abi_cached()`].
+const PARSED_CACHE_CAPACITY: usize = 32;
+
+static PARSED_CACHE: LazyLock<ParsedCache> = LazyLock::new(|| ParsedCache::new(PARSED_CACHE_CAPACITY));
+
+/// Size of the chunks of hex data decoded at once by [`decode_hex_and_hash()`].
+const HEX_CHUNK_SIZE: usize = 8192;
+
 // TODO: make sure that we can (de)serialize an ABI (ABIDefinition?) itself (eg, see: https://github.com/wharfkit/antelope/blob/master/src/chain/abi.ts, which implements ABISerializableObject)
 
 #[derive(Default, Clone, Debug)]
@@ -31,10 +50,84 @@ pub struct ABI {
     // ABI-related fields
     typedefs: HashMap<TypeNameOwned, TypeNameOwned>,
     structs: HashMap<TypeNameOwned, Struct>,
-    actions: HashMap<Name, TypeNameOwned>,
-    tables: HashMap<Name, TypeNameOwned>,
+    actions: HashMap<ActionName, TypeNameOwned>,
+    tables: HashMap<TableName, TypeNameOwned>,
     variants: HashMap<TypeNameOwned, Variant>,
-    action_results: HashMap<Name, TypeNameOwned>,
+    action_results: HashMap<ActionName, TypeNameOwned>,
+    ricardian_contracts: HashMap<ActionName, String>,
+
+    // custom builtin types, registered by the user
+    builtin_types: HashMap<TypeNameOwned, BuiltinType>,
+
+    // functions transforming decoded values, registered by the user
+    decode_hooks: HashMap<TypeNameOwned, DecodeHook>,
+
+    // strictness of the encoding of input objects
+    encode_options: EncodeOptions,
+
+    // leniency of the decoding of binary data
+    decode_options: DecodeOptions,
+
+    // whether to accept definitions with an unsupported version
+    version_policy: VersionPolicy,
+
+    // opt-in cache of encoded structs
+    encode_cache: Option<EncodeCache>,
+}
+
+/// Options controlling how strictly input objects are checked when encoding them
+/// with an [`ABI`], see [`ABI::with_encode_options()`].
+///
+/// The default values match the behavior of Spring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Fail if an input object contains a field that is not defined in its struct,
+    /// or if an input array has more items than its struct has fields (default: `false`)
+    pub deny_unknown_fields: bool,
+    /// Encode missing optional fields as null instead of failing (default: `true`)
+    pub allow_missing_optionals: bool,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions { deny_unknown_fields: false, allow_missing_optionals: true }
+    }
+}
+
+/// Options controlling how lenient the decoding of binary data with an [`ABI`] is,
+/// see [`ABI::with_decode_options()`].
+///
+/// The default values match the behavior of Spring.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Instead of failing on a variant tag which is out of range for its variant
+    /// (eg: because it has been added in a newer revision of the ABI), decode it
+    /// as `["__unknown_<tag>", "<hex of the remaining data>"]` (default: `false`)
+    ///
+    /// As the size of the unknown value cannot be known, all the remaining data
+    /// of the stream is consumed, so the values following it cannot be decoded.
+    pub tolerate_unknown_variants: bool,
+    /// Representation of the decoded values (default: `None`, which uses the process
+    /// default, see [`config::set_default_serialization()`])
+    pub serialization: Option<SerializationConfig>,
+}
+
+/// An action defined in an [`ABI`], as returned by [`ABI::actions()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ABIAction<'a> {
+    pub name: ActionName,
+    /// Type of the action data
+    pub type_: TypeName<'a>,
+    /// Type of the value returned by the action, if declared in the ABI
+    pub result_type: Option<TypeName<'a>>,
+}
+
+/// A table defined in an [`ABI`], as returned by [`ABI::tables()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ABITable<'a> {
+    pub name: TableName,
+    /// Type of the rows stored in the table
+    pub type_: TypeName<'a>,
 }
 
 
@@ -47,6 +140,13 @@ impl ABI {
             tables: HashMap::new(),
             variants: HashMap::new(),
             action_results: HashMap::new(),
+            ricardian_contracts: HashMap::new(),
+            builtin_types: HashMap::new(),
+            decode_hooks: HashMap::new(),
+            encode_options: EncodeOptions::default(),
+            decode_options: DecodeOptions::default(),
+            version_policy: VersionPolicy::default(),
+            encode_cache: None,
         }
     }
 
@@ -54,32 +154,224 @@ impl ABI {
         self.structs.contains_key(name) || self.typedefs.contains_key(name) || self.variants.contains_key(name)
     }
 
+    // -----------------------------------------------------------------------------
+    //     Enumerating the ABI contents
+    // -----------------------------------------------------------------------------
+
+    // NOTE: all the following methods return their items sorted by name, so that
+    //       the output is deterministic
+
+    /// Return the actions defined in this ABI.
+    pub fn actions(&self) -> impl Iterator<Item = ABIAction<'_>> {
+        let mut result: Vec<_> = self.actions.iter().map(|(name, type_)| ABIAction {
+            name: *name,
+            type_: TypeName(type_),
+            result_type: self.action_results.get(name).map(|t| TypeName(t)),
+        }).collect();
+        result.sort_by_key(|a| a.name.to_string());
+        result.into_iter()
+    }
+
+    /// Return the tables defined in this ABI.
+    pub fn tables(&self) -> impl Iterator<Item = ABITable<'_>> {
+        let mut result: Vec<_> = self.tables.iter()
+            .map(|(name, type_)| ABITable { name: *name, type_: TypeName(type_) })
+            .collect();
+        result.sort_by_key(|t| t.name.to_string());
+        result.into_iter()
+    }
+
+    /// Return the structs defined in this ABI.
+    pub fn structs(&self) -> impl Iterator<Item = &Struct> {
+        let mut result: Vec<_> = self.structs.values().collect();
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        result.into_iter()
+    }
+
+    /// Return the variants defined in this ABI.
+    pub fn variants(&self) -> impl Iterator<Item = &Variant> {
+        let mut result: Vec<_> = self.variants.values().collect();
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        result.into_iter()
+    }
+
+    /// Return the type definitions of this ABI as `(new_type_name, type)` pairs.
+    pub fn typedefs(&self) -> impl Iterator<Item = (TypeName<'_>, TypeName<'_>)> {
+        let mut result: Vec<_> = self.typedefs.iter()
+            .map(|(new_type, type_)| (TypeName(new_type), TypeName(type_)))
+            .collect();
+        result.sort();
+        result.into_iter()
+    }
+
+    /// Return the graph of the dependencies between the types defined in this ABI,
+    /// which can be used to find types that are never used or to visualize big ABIs.
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, abi::data::EOSIO_TOKEN_ABI};
+    /// let abi = ABI::from_str(EOSIO_TOKEN_ABI)?;
+    /// let graph = abi.dependency_graph();
+    /// assert!(graph.roots.contains("transfer"));
+    /// assert!(graph.unused_types().is_empty());
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        for (name, type_) in &self.typedefs {
+            graph.add_type(name, TypeKind::Typedef, [TypeName(type_)]);
+        }
+        for (name, s) in &self.structs {
+            let base = (!s.base.is_empty()).then_some(TypeName(&s.base));
+            let fields = s.fields.iter().map(|f| TypeName(&f.type_));
+            graph.add_type(name, TypeKind::Struct, base.into_iter().chain(fields));
+        }
+        for (name, v) in &self.variants {
+            graph.add_type(name, TypeKind::Variant, v.types.iter().map(TypeName::from));
+        }
+        for type_ in self.actions.values().chain(self.tables.values()).chain(self.action_results.values()) {
+            graph.add_root(TypeName(type_));
+        }
+        graph.finish()
+    }
+
+    /// Return the ricardian contract of the given action, with the action parameters
+    /// given in `args` substituted in its template, or `None` if the ABI doesn't
+    /// define a ricardian contract for this action.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kudu::{ABI, ActionName, abi::ABIError};
+    /// # use serde_json::json;
+    /// let abi = ABI::from_str(r#"{
+    ///     "version": "eosio::abi/1.1",
+    ///     "structs": [{"name": "hi", "base": "", "fields": [{"name": "user", "type": "name"}]}],
+    ///     "actions": [{"name": "hi", "type": "hi", "ricardian_contract":
+    ///                  "---\ntitle: Say hi\nsummary: '{{nowrap user}} says hi'\n---\nHello {{user}}!"}]
+    /// }"#)?;
+    /// let contract = abi.ricardian_for_action(ActionName::constant("hi"), &json!({"user": "alice"})).unwrap();
+    /// assert_eq!(contract.title.as_deref(), Some("Say hi"));
+    /// assert_eq!(contract.summary.as_deref(), Some("alice says hi"));
+    /// assert_eq!(contract.body, "Hello alice!");
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn ricardian_for_action(&self, action: ActionName, args: &JsonValue) -> Option<RicardianContract> {
+        self.ricardian_contracts.get(&action)
+            .map(|template| RicardianContract::render(template, args))
+    }
+
     // -----------------------------------------------------------------------------
     //     Constructors and validation of ABI
     // -----------------------------------------------------------------------------
 
     pub fn from_definition(abi: &ABIDefinition) -> Result<Self> {
-        let mut result = Self::new();
-        result.set_abi(abi)?;
-        Ok(result)
+        Self::new().with_definition(abi)
+    }
+
+    /// Load the given definition into this ABI, replacing the previous one if any.
+    ///
+    /// The custom builtin types registered with [`ABI::with_builtin_type()`] are kept,
+    /// so they can be used in the definition.
+    pub fn with_definition(mut self, abi: &ABIDefinition) -> Result<Self> {
+        self.set_abi(abi)?;
+        Ok(self)
+    }
+
+    /// Set the policy for loading definitions with an unsupported version, this
+    /// needs to be called before [`ABI::with_definition()`].
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIDefinition, ABIError, abi::VersionPolicy};
+    /// let def = ABIDefinition { version: "eosio::abi/2.0".to_owned(), ..Default::default() };
+    /// assert!(ABI::from_definition(&def).is_err());
+    ///
+    /// let abi = ABI::new().with_version_policy(VersionPolicy::BestEffort).with_definition(&def)?;
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn with_version_policy(mut self, policy: VersionPolicy) -> Self {
+        self.version_policy = policy;
+        self
+    }
+
+    pub fn version_policy(&self) -> VersionPolicy {
+        self.version_policy
     }
 
     pub fn from_str(abi: &str) -> Result<Self> {
         Self::from_definition(&ABIDefinition::from_str(abi)?)
     }
 
+    /// Build an `ABI` from the hex representation of a packed ABI definition, as
+    /// returned by the `get_raw_abi` API endpoint.
+    ///
+    /// Note that parsing an ABI is costly: besides decoding the definition, all its
+    /// types are resolved and validated, which takes in the order of a few milliseconds
+    /// for big ABIs such as the one of the `eosio` system contract (run the `abi`
+    /// benchmark to get numbers for your machine). Services decoding data for a given
+    /// contract should keep and reuse the `ABI` instance instead of parsing it again
+    /// for each request, or use [`ABI::from_hex_abi_cached()`] to do so transparently.
     pub fn from_hex_abi(abi: &str) -> Result<Self> {
         Self::from_bin_abi(&hex::decode(abi)?)
     }
 
+    /// Build an `ABI` from a packed ABI definition, see [`ABI::from_hex_abi()`].
     pub fn from_bin_abi(abi: &[u8]) -> Result<Self> {
         let mut data = ByteStream::from(abi);
         let abi_def = ABIDefinition::decode(&mut data)?;
         Self::from_definition(&abi_def)
     }
 
+    /// Same as [`ABI::from_hex_abi()`], but return a shared instance from a process-wide
+    /// cache of parsed ABIs, keyed by the hash of the packed ABI definition (see
+    /// [`hash_packed_abi()`]).
+    ///
+    /// On a cache hit, the only remaining costs are decoding the hex data, which is done
+    /// in chunks while hashing it so that the input is only traversed once, and the
+    /// hashing itself.
+    ///
+    /// The cache keeps the 32 most recently inserted ABIs. Its ABIs are built with the
+    /// default settings, use [`ABI::with_definition()`] instead if you need custom
+    /// builtin types or a specific version policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use kudu::{ABI, ABIDefinition, ABIError, Bytes, abi::data::EOSIO_TOKEN_ABI};
+    /// let mut packed = Bytes::new();
+    /// ABIDefinition::from_str(EOSIO_TOKEN_ABI)?.encode(&mut packed)?;
+    /// let hex_abi = packed.to_hex();
+    /// let abi = ABI::from_hex_abi_cached(&hex_abi)?;
+    /// assert!(Arc::ptr_eq(&abi, &ABI::from_hex_abi_cached(&hex_abi)?));
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    ///
+    /// [`hash_packed_abi()`]: crate::abi::hash_packed_abi
+    pub fn from_hex_abi_cached(abi: &str) -> Result<Arc<Self>> {
+        let (packed, hash) = decode_hex_and_hash(abi)?;
+        PARSED_CACHE.get_or_try_insert(&hash, || Self::from_bin_abi(&packed))
+    }
+
+    /// Same as [`ABI::from_bin_abi()`], but return a shared instance from a process-wide
+    /// cache of parsed ABIs, see [`ABI::from_hex_abi_cached()`].
+    pub fn from_bin_abi_cached(abi: &[u8]) -> Result<Arc<Self>> {
+        let hash = crate::abi::hash_packed_abi(abi);
+        PARSED_CACHE.get_or_try_insert(&hash, || Self::from_bin_abi(abi))
+    }
+
+    /// Return the statistics of the process-wide cache used by [`ABI::from_hex_abi_cached()`]
+    /// and [`ABI::from_bin_abi_cached()`].
+    pub fn parsed_cache_stats() -> CacheStats {
+        PARSED_CACHE.stats()
+    }
+
+    /// Remove all entries from the cache of parsed ABIs and reset its statistics.
+    pub fn clear_parsed_cache() {
+        PARSED_CACHE.clear()
+    }
+
     fn set_abi(&mut self, abi: &ABIDefinition) -> Result<()> {
-        ensure!(abi.version.starts_with("eosio::abi/1."), VersionSnafu { version: &abi.version });
+        self.version_policy.check(&abi.version)?;
 
         self.typedefs.clear();
         self.structs.clear();
@@ -87,6 +379,8 @@ impl ABI {
         self.tables.clear();
         self.variants.clear();
         self.action_results.clear();
+        self.ricardian_contracts.clear();
+        self.clear_encode_cache();
 
         self.structs.extend(abi.structs.iter().map(|s| (s.name.to_string(), s.clone())));
 
@@ -108,6 +402,9 @@ impl ABI {
                              .map(|v| (v.name.clone(), v.clone())));
         self.action_results.extend(abi.action_results.iter()
                                    .map(|a| (a.name, a.result_type.clone())));
+        self.ricardian_contracts.extend(abi.actions.iter()
+                                        .filter(|a| !a.ricardian_contract.is_empty())
+                                        .map(|a| (a.name, a.ricardian_contract.clone())));
 
         // The ABIDefinition vectors may contain duplicates which would make it an invalid ABI
         ensure!(self.typedefs.len() == abi.types.len(),
@@ -126,34 +423,165 @@ impl ABI {
         self.validate()
     }
 
+    /// Return whether the given type name refers to a known type, ie: a native
+    /// Antelope type, a struct, a variant, a custom builtin type or a typedef
+    /// resolving to one of these.
+    ///
+    /// Typedef chains longer than [`config::MAX_TYPEDEF_DEPTH`] are not considered
+    /// valid types, so that this is bounded even for ABIs that have not been validated.
     pub fn is_type(&self, t: TypeName) -> bool {
-        // NOTE: this would be a better behavior IMO but it doesn't match the C++ code
-        //       for Antelope Spring; keep the latter for better compatibility
-
         let mut t = t;
-        let mut ft = t.fundamental_type();
-        while ft != t {
-            t = ft;
-            ft = t.fundamental_type();
-        }
+        for _ in 0..=config::MAX_TYPEDEF_DEPTH {
+            // NOTE: this would be a better behavior IMO but it doesn't match the C++ code
+            //       for Antelope Spring; keep the latter for better compatibility
+            let mut ft = t.fundamental_type();
+            while ft != t {
+                t = ft;
+                ft = t.fundamental_type();
+            }
 
-        // NOTE: this is the C++ Antelope Spring behavior
-        // let t = t.fundamental_type();
-        AntelopeValue::VARIANTS.contains(&t)
-            || (self.typedefs.contains_key(t.as_str()) &&
-               self.is_type(TypeName(self.typedefs.get(t.as_str()).unwrap())))  // safe unwrap
-            || self.structs.contains_key(t.as_str())
-            || self.variants.contains_key(t.as_str())
+            // NOTE: this is the C++ Antelope Spring behavior
+            // let t = t.fundamental_type();
+            if AntelopeValue::VARIANTS.contains(&t)
+                || self.structs.contains_key(t.as_str())
+                || self.variants.contains_key(t.as_str())
+                || self.builtin_types.contains_key(t.as_str()) {
+                return true;
+            }
+            match self.typedefs.get(t.as_str()) {
+                Some(next) => t = TypeName(next),
+                None => return false,
+            }
+        }
+        false
     }
 
-    pub fn resolve_type<'a>(&'a self, t: TypeName<'a>) -> TypeName<'a> {
+    /// Follow the typedefs starting from the given type name and return the type
+    /// it resolves to.
+    ///
+    /// Return an error if more than [`config::MAX_TYPEDEF_DEPTH`] typedefs need to
+    /// be followed, which also protects against circular typedefs in ABIs that
+    /// have not been validated.
+    pub fn resolve_type<'a>(&'a self, t: TypeName<'a>) -> Result<TypeName<'a>> {
         let mut rtype = t;
-        loop {
+        for _ in 0..=config::MAX_TYPEDEF_DEPTH {
             match self.typedefs.get(rtype.as_str()) {
-                Some(t) => rtype = TypeName(t),
-                None => return rtype,
+                Some(next) => rtype = TypeName(next),
+                None => return Ok(rtype),
             }
         }
+        TypedefDepthSnafu { typename: t.to_string(), max_depth: config::MAX_TYPEDEF_DEPTH }.fail()
+    }
+
+    /// Return the definition of the given struct where the fields of all its base
+    /// structs have been included, in serialization order (ie: the fields of a base
+    /// struct come before the fields of the struct deriving from it).
+    ///
+    /// The returned struct has an empty `base`.
+    pub fn flattened_struct<'a, T>(&self, typename: T) -> Result<Struct>
+    where
+        T: Into<TypeName<'a>>
+    {
+        let rtype = self.resolve_type(typename.into())?;
+        let Some(struct_def) = self.structs.get(rtype.as_str()) else {
+            return IntegritySnafu { message: format!("not a struct type: `{}`", rtype) }.fail();
+        };
+        Ok(Struct {
+            name: struct_def.name.clone(),
+            base: TypeNameOwned::new(),
+            fields: self.struct_fields(struct_def)?.into_iter().cloned().collect(),
+        })
+    }
+
+    /// Return a recursive description of the given type, with typedefs resolved.
+    ///
+    /// This can be used to generate forms for entering the data of an action,
+    /// or to explain the layout of a type to a user.
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, abi::data::EOSIO_TOKEN_ABI};
+    /// let abi = ABI::from_str(EOSIO_TOKEN_ABI)?;
+    /// let description = abi.describe("transfer")?;
+    /// assert_eq!(description.to_string(), "\
+    /// struct transfer
+    ///   from: name
+    ///   to: name
+    ///   quantity: asset
+    ///   memo: string
+    /// ");
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn describe<'a, T>(&self, typename: T) -> Result<TypeDescription>
+    where
+        T: Into<TypeName<'a>>
+    {
+        self.describe_(typename.into(), &mut vec![])
+    }
+
+    fn describe_<'a>(&'a self, typename: TypeName<'a>, visiting: &mut Vec<&'a str>) -> Result<TypeDescription> {
+        if typename.has_bin_extension() {
+            let value = Box::new(self.describe_(typename.remove_bin_extension(), visiting)?);
+            return Ok(TypeDescription::BinaryExtension { value });
+        }
+
+        let rtype = self.resolve_type(typename)?;
+        let name = rtype.as_str();
+
+        Ok(if rtype.is_array() || rtype.is_sized_array() {
+            TypeDescription::Array { items: Box::new(self.describe_(rtype.fundamental_type(), visiting)?) }
+        }
+        else if rtype.is_optional() {
+            TypeDescription::Optional { value: Box::new(self.describe_(rtype.fundamental_type(), visiting)?) }
+        }
+        else if AntelopeType::try_from(rtype).is_ok() {
+            TypeDescription::Builtin { name: name.to_string() }
+        }
+        else if visiting.contains(&name) {
+            TypeDescription::Recursive { name: name.to_string() }
+        }
+        else if let Some(variant_def) = self.variants.get(name) {
+            visiting.push(name);
+            let alternatives = variant_def.types.iter()
+                .map(|t| self.describe_(TypeName(t), visiting))
+                .collect::<Result<_>>()?;
+            visiting.pop();
+            TypeDescription::Variant { name: name.to_string(), alternatives }
+        }
+        else if let Some(struct_def) = self.structs.get(name) {
+            visiting.push(name);
+            let fields = self.struct_fields(struct_def)?.into_iter()
+                .map(|field| Ok(FieldDescription {
+                    name: field.name.clone(),
+                    type_: field.type_.clone(),
+                    description: self.describe_(TypeName(&field.type_), visiting)?,
+                }))
+                .collect::<Result<_>>()?;
+            visiting.pop();
+            TypeDescription::Struct { name: name.to_string(), fields }
+        }
+        else if self.builtin_types.contains_key(name) {
+            TypeDescription::Builtin { name: name.to_string() }
+        }
+        else {
+            IntegritySnafu { message: format!("unknown type: `{}`", rtype) }.fail()?
+        })
+    }
+
+    /// Return the list of all fields of a struct, including those of its base structs.
+    fn struct_fields<'a>(&'a self, struct_def: &'a Struct) -> Result<Vec<&'a Field>> {
+        let mut chain = vec![struct_def];
+        let mut current = struct_def;
+        while !current.base.is_empty() {
+            let Some(base) = self.structs.get(&current.base) else {
+                return IntegritySnafu { message: format!("invalid type used in '{}::base': `{}`",
+                                                         &current.name, &current.base) }.fail();
+            };
+            ensure!(!chain.iter().any(|s| s.name == base.name),
+                    IntegritySnafu { message: format!("circular reference in struct '{}'", &struct_def.name) });
+            chain.push(base);
+            current = base;
+        }
+        Ok(chain.iter().rev().flat_map(|s| s.fields.iter()).collect())
     }
 
     pub fn validate(&self) -> Result<(), ABIError> {
@@ -161,10 +589,11 @@ impl ABI {
         for t in &self.typedefs {
             let mut types_seen = vec![t.0, t.1];
             let mut itr = self.typedefs.get(&t.1[..]);
-            while itr.is_some() {
-                let it = itr.unwrap();
+            while let Some(it) = itr {
                 ensure!(!types_seen.contains(&it),
                         IntegritySnafu { message: format!("circular reference in type `{}`", t.0) });
+                ensure!(types_seen.len() <= config::MAX_TYPEDEF_DEPTH,
+                        TypedefDepthSnafu { typename: t.0, max_depth: config::MAX_TYPEDEF_DEPTH });
                 types_seen.push(it);
                 itr = self.typedefs.get(it);
             }
@@ -181,20 +610,31 @@ impl ABI {
         // check there are no circular references in the structs definition
         for s in self.structs.values() {
             ensure!(!s.name.is_empty(), IntegritySnafu { message: "empty name in struct definition" });
+            let mut bases = vec![];
             if !s.base.is_empty() {
                 let mut current = s;
                 let mut types_seen = vec![&current.name];
                 while !current.base.is_empty() {
-                    ensure!(self.structs.contains_key(&current.base),
-                            IntegritySnafu { message: format!("invalid type used in '{}::base': `{}`", &s.name, &current.base) });
-                    let base = self.structs.get(&current.base).unwrap();  // safe unwrap
+                    let base = self.structs.get(&current.base).with_context(|| IntegritySnafu {
+                        message: format!("invalid type used in '{}::base': `{}`", &s.name, &current.base)
+                    })?;
                     ensure!(!types_seen.contains(&&base.name),
                             IntegritySnafu { message: format!("circular reference in struct '{}'", &s.name) });
                     types_seen.push(&base.name);
+                    bases.push(base);
                     current = base;
                 }
             }
 
+            // check field names are unique, including the ones of the base structs, as
+            // they would otherwise map to the same key of the JSON object
+            let mut field_names = HashSet::new();
+            for field in bases.iter().rev().chain([&s]).flat_map(|b| &b.fields) {
+                ensure!(field_names.insert(&field.name),
+                        IntegritySnafu { message: format!("duplicate field name '{}::{}'",
+                                                          &s.name, &field.name) });
+            }
+
             // check all field types are valid types
             for field in &s.fields {
                 ensure!(self.is_type(TypeName(&field.type_[..]).remove_bin_extension()),
@@ -252,6 +692,154 @@ impl ABI {
         Ok(ds)
     }
 
+    /// Set the options controlling how strictly input objects are checked when encoding.
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, abi::{EncodeOptions, data::EOSIO_TOKEN_ABI}, json};
+    /// let transfer = json!({"from": "alice", "to": "bob", "quantity": "1.0000 A", "memo": "", "extra": 1});
+    /// let abi = ABI::from_str(EOSIO_TOKEN_ABI)?;
+    /// assert!(abi.variant_to_binary("transfer", &transfer).is_ok());
+    ///
+    /// let strict = abi.with_encode_options(EncodeOptions { deny_unknown_fields: true, ..Default::default() });
+    /// assert!(strict.variant_to_binary("transfer", &transfer).is_err());
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn with_encode_options(mut self, options: EncodeOptions) -> Self {
+        self.encode_options = options;
+        self
+    }
+
+    pub fn encode_options(&self) -> EncodeOptions {
+        self.encode_options
+    }
+
+    /// Enable caching of the binary encoding of struct values, as an optimization for
+    /// when the same values are encoded repeatedly (eg: permission levels in traces).
+    ///
+    /// Only structs whose fields are all scalar values are cached, as hashing and cloning
+    /// bigger values costs more than encoding them. At most `capacity` encoded values
+    /// are kept, older ones are evicted first. Check [`ABI::encode_cache_stats()`] to
+    /// find out whether this pays off for your data (see also the `encode_cache` example).
+    ///
+    /// Note that cloning the `ABI` creates a new empty cache for the clone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, abi::data::EOSIO_TOKEN_ABI, json};
+    /// let abi = ABI::from_str(EOSIO_TOKEN_ABI)?.with_encode_cache(1000);
+    /// let transfer = json!({"from": "alice", "to": "bob", "quantity": "1.0000 A", "memo": ""});
+    /// for _ in 0..10 {
+    ///     abi.variant_to_binary("transfer", &transfer)?;
+    /// }
+    /// let stats = abi.encode_cache_stats().unwrap();
+    /// assert_eq!((stats.hits, stats.misses, stats.entries), (9, 1, 1));
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn with_encode_cache(mut self, capacity: usize) -> Self {
+        self.encode_cache = Some(EncodeCache::new(capacity));
+        self
+    }
+
+    /// Return the statistics of the encoding cache, or `None` if it is not enabled.
+    pub fn encode_cache_stats(&self) -> Option<CacheStats> {
+        self.encode_cache.as_ref().map(EncodeCache::stats)
+    }
+
+    /// Remove all entries from the encoding cache (if enabled) and reset its statistics.
+    pub fn clear_encode_cache(&self) {
+        if let Some(cache) = &self.encode_cache {
+            cache.clear();
+        }
+    }
+
+    // -----------------------------------------------------------------------------
+    //     Custom builtin types
+    // -----------------------------------------------------------------------------
+
+    /// Register a custom builtin type with the functions used to encode it to and
+    /// decode it from its binary representation.
+    ///
+    /// This allows handling types that are not (yet) natively supported, eg: a new
+    /// intrinsic type added by a fork of the chain. Registered types can be used in
+    /// the definition loaded with [`ABI::with_definition()`], and are only looked up
+    /// after the native types, structs and variants of the same name.
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIDefinition, ABIError, ByteStream, json};
+    /// let definition = ABIDefinition::from_str(r#"{
+    ///     "version": "eosio::abi/1.1",
+    ///     "structs": [{"name": "color", "base": "", "fields": [{"name": "rgb", "type": "uint24"}]}]
+    /// }"#)?;
+    /// let abi = ABI::new()
+    ///     .with_builtin_type("uint24",
+    ///         |obj, ds| {
+    ///             let n = obj.as_u64().filter(|&n| n < 1 << 24).ok_or("not a uint24")?;
+    ///             ds.write_bytes(&n.to_le_bytes()[..3]);
+    ///             Ok(())
+    ///         },
+    ///         |ds: &mut ByteStream| {
+    ///             let b = ds.read_bytes(3)?;
+    ///             Ok(json!(u32::from_le_bytes([b[0], b[1], b[2], 0])))
+    ///         })
+    ///     .with_definition(&definition)?;
+    /// let color = json!({"rgb": 0x123456});
+    /// let encoded = abi.variant_to_binary("color", &color)?;
+    /// assert_eq!(encoded.to_hex(), "563412");
+    /// assert_eq!(abi.binary_to_variant("color", encoded)?, color);
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn with_builtin_type<E, D>(mut self, name: &str, encode: E, decode: D) -> Self
+    where
+        E: Fn(&JsonValue, &mut Bytes) -> Result<(), BuiltinTypeError> + Send + Sync + 'static,
+        D: Fn(&mut ByteStream) -> Result<JsonValue, BuiltinTypeError> + Send + Sync + 'static,
+    {
+        self.builtin_types.insert(name.to_string(), BuiltinType::new(encode, decode));
+        self
+    }
+
+    /// Return whether a custom builtin type has been registered with the given name.
+    pub fn has_builtin_type(&self, name: &str) -> bool {
+        self.builtin_types.contains_key(name)
+    }
+
+    /// Register a function transforming the decoded values of the given type, eg:
+    /// to change their JSON representation.
+    ///
+    /// The hook is applied to all the values of that type, wherever they appear in
+    /// the decoded data (struct fields, array items, etc.). A hook registered for an
+    /// alias defined in the `types` section takes precedence over the one registered
+    /// for the type it resolves to.
+    ///
+    /// Note that the values are not transformed back when encoding them.
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, abi::data::EOSIO_TOKEN_ABI, json};
+    /// let abi = ABI::from_str(EOSIO_TOKEN_ABI)?
+    ///     .with_decode_hook("asset", |asset| {
+    ///         let asset = asset.as_str().unwrap_or_default();
+    ///         let (amount, symbol) = asset.split_once(' ').ok_or("invalid asset")?;
+    ///         Ok(json!({"amount": amount, "symbol": symbol}))
+    ///     });
+    /// let transfer = json!({"from": "alice", "to": "bob", "quantity": "1.0000 A", "memo": ""});
+    /// let data = abi.variant_to_binary("transfer", &transfer)?;
+    /// assert_eq!(abi.binary_to_variant("transfer", data)?["quantity"],
+    ///            json!({"amount": "1.0000", "symbol": "A"}));
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn with_decode_hook<F>(mut self, typename: &str, hook: F) -> Self
+    where
+        F: Fn(JsonValue) -> Result<JsonValue, BuiltinTypeError> + Send + Sync + 'static,
+    {
+        self.decode_hooks.insert(typename.to_string(), DecodeHook::new(hook));
+        self
+    }
+
+    /// Return whether a decode hook has been registered for the given type.
+    pub fn has_decode_hook(&self, typename: &str) -> bool {
+        self.decode_hooks.contains_key(typename)
+    }
+
     #[inline]
     pub fn encode<T: ABISerializable>(&self, stream: &mut Bytes, obj: &T) {
         obj.to_bin(stream)
@@ -271,7 +859,7 @@ impl ABI {
                        typename: TypeName, object: &JsonValue)
                        -> Result<(), ABIError> {
         // see C++ implementation here: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/abi_serializer.cpp#L493
-        let rtype = self.resolve_type(typename);
+        let rtype = self.resolve_type(typename)?;
         let ftype = rtype.fundamental_type();
 
         debug!(rtype=rtype.0, ftype=ftype.0);
@@ -280,9 +868,14 @@ impl ABI {
         if rtype.is_optional() {
             ensure!(!ftype.is_optional() && !ftype.has_bin_extension(),
                     IntegritySnafu { message: format!("invalid optional nesting for type {rtype}") }); }
-        if rtype.is_array() {
+        if rtype.is_array() || rtype.is_sized_array() {
             ensure!(!ftype.is_optional() && !ftype.has_bin_extension(),
                     IntegritySnafu { message: format!("invalid array nesting for type {rtype}") }); }
+        if let (Some(size), Some(a)) = (rtype.sized_array_len(), object.as_array()) {
+            ensure!(a.len() == size, EncodeSnafu { message: format!(
+                "wrong number of items for fixed-size array `{rtype}`: got {}, expected {size}", a.len()
+            )});
+        }
         if rtype.has_bin_extension() {
             ensure!(!ftype.has_bin_extension(),
                     IntegritySnafu { message: format!("invalid extension nesting for type {rtype}") }); }
@@ -293,11 +886,10 @@ impl ABI {
             value: Box::new(object.clone())
         }.build() };
 
-        if AntelopeValue::VARIANTS.contains(&ftype) {
+        if let Ok(inner_type) = AntelopeType::try_from(ftype) {
             // if our fundamental type is a builtin type, we can serialize it directly
             // to the stream
-            let inner_type: AntelopeType = ftype.try_into().unwrap();  // safe unwrap
-            if rtype.is_array() {
+            if rtype.is_array() || rtype.is_sized_array() {
                 let a = object.as_array().ok_or_else(incompatible_types)?;
                 VarUint32::from(a.len()).to_bin(ds);
                 for v in a {
@@ -326,7 +918,7 @@ impl ABI {
         else {
             // not a builtin type, we have to recurse down
 
-            if rtype.is_array() {
+            if rtype.is_array() || rtype.is_sized_array() {
                 let a = object.as_array().ok_or_else(incompatible_types)?;
                 VarUint32::from(a.len()).to_bin(ds);
                 for v in a {
@@ -344,17 +936,14 @@ impl ABI {
             }
             else if let Some(variant_def) = self.variants.get(rtype.as_str()) {
                 debug!("serializing type {:?} with variant: {:?}", rtype, object);
-                ensure!(object.is_array() && object.as_array().unwrap().len() == 2,
+                ensure!(object.as_array().is_some_and(|a| a.len() == 2),
                         EncodeSnafu {
                             message: format!("expected input to be an array of 2 elements while processing variant: {}",
                                              &object)
                         });
-                ensure!(object[0].is_string(),
-                        EncodeSnafu {
-                            message: format!("expected variant typename to be a string: {}",
-                                             object[0])
-                        });
-                let variant_type = TypeName(object[0].as_str().unwrap());
+                let variant_type = TypeName(object[0].as_str().with_context(|| EncodeSnafu {
+                    message: format!("expected variant typename to be a string: {}", object[0])
+                })?);
                 if let Some(vpos) = variant_def.types.iter().position(|v| *v == variant_type) {
                     VarUint32::from(vpos).to_bin(ds);
                     self.encode_variant_(ctx, ds, variant_type, &object[1])?;
@@ -367,7 +956,24 @@ impl ABI {
                 }
             }
             else if let Some(struct_def) = self.structs.get(rtype.as_str()) {
-                self.encode_struct(ctx, ds, struct_def, object)?;
+                // only flat structs are cached, as hashing and cloning large values can
+                // be more expensive than encoding them. Also, the encoding of a struct can
+                // only depend on the context if extensions are disallowed, don't use the
+                // cache in that case
+                let cacheable = ctx.allow_extensions && is_flat(object);
+                match self.encode_cache.as_ref().filter(|_| cacheable) {
+                    Some(cache) => {
+                        if !cache.encode_cached(rtype, object, ds) {
+                            let start = ds.len();
+                            self.encode_struct(ctx, ds, struct_def, object)?;
+                            cache.insert(rtype, object, &ds[start..]);
+                        }
+                    },
+                    None => self.encode_struct(ctx, ds, struct_def, object)?,
+                }
+            }
+            else if let Some(builtin) = self.builtin_types.get(rtype.as_str()) {
+                builtin.encode(object, ds).context(BuiltinTypeSnafu { typename: rtype.to_string() })?;
             }
             else {
                 EncodeSnafu { message: format!("unknown ABI type: `{}`", rtype) }.fail()?;
@@ -382,24 +988,29 @@ impl ABI {
                      -> Result<(), ABIError> {
         // we want to serialize a struct...
         if let Some(obj) = object.as_object() {
-            // ...and we are given an object -> serialize fields using their name
-            if !struct_def.base.is_empty() {
-                let _ = ctx.disallow_extensions_unless(false);
-                self.encode_variant(ds, TypeName(&struct_def.base), object)?;
+            // ...and we are given an object -> serialize fields using their name,
+            // starting with the fields from the base structs
+            let fields = self.struct_fields(struct_def)?;
+            let nfields = fields.len();
+
+            if self.encode_options.deny_unknown_fields {
+                if let Some(name) = obj.keys().find(|k| !fields.iter().any(|f| &f.name == *k)) {
+                    EncodeSnafu { message: format!(
+                        "unknown field '{}' found in input object while processing struct '{}'",
+                        name, &struct_def.name) }.fail()?;
+                }
             }
 
             let mut allow_additional_fields = true;
-            for (i, field) in struct_def.fields.iter().enumerate() {
+            for (i, field) in fields.into_iter().enumerate() {
                 let ftype = TypeName(&field.type_);
-                let nfields = struct_def.fields.len();
                 let present: bool = obj.contains_key(&field.name);
-                if present || ftype.is_optional() {
+                if present || (ftype.is_optional() && self.encode_options.allow_missing_optionals) {
                     ensure!(allow_additional_fields,
                             EncodeSnafu { message: format!(
                                 "Unexpected field '{}' found in input object while processing struct '{}'",
                                 &field.name, &struct_def.name) });
-                    let value = if present { obj.get(&field.name).unwrap() }  // safe unwrap
-                    else                   { &JsonValue::Null };
+                    let value = obj.get(&field.name).unwrap_or(&JsonValue::Null);
                     // TODO: ctx.push_to_path
                     ctx.disallow_extensions_unless(i == nfields-1); // disallow except for the last field
                     self.encode_variant(ds, ftype.remove_bin_extension(), value)?;
@@ -426,6 +1037,10 @@ impl ABI {
                         "using input array to specify the fields of the derived struct '{}'; ",
                         "input arrays are currently only allowed for structs without a base"
                     ), struct_def.name) });
+            ensure!(!self.encode_options.deny_unknown_fields || arr.len() <= struct_def.fields.len(),
+                    EncodeSnafu { message: format!(
+                        "too many items in input array for struct '{}': got {}, expected at most {}",
+                        struct_def.name, arr.len(), struct_def.fields.len()) });
 
             for (i, field) in struct_def.fields.iter().enumerate() {
                 // let field = &struct_def.fields[i];
@@ -461,42 +1076,181 @@ impl ABI {
     //     Decoding of binary data -> variant
     // -----------------------------------------------------------------------------
 
+    /// Set the options controlling how lenient the decoding of binary data is.
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, Bytes, abi::DecodeOptions, json};
+    /// let abi = ABI::from_str(r#"{
+    ///     "version": "eosio::abi/1.1",
+    ///     "variants": [{"name": "v", "types": ["uint8", "string"]}]
+    /// }"#)?;
+    /// let data = Bytes::from_hex("02abcd")?;
+    /// assert!(abi.binary_to_variant("v", data.clone()).is_err());
+    ///
+    /// let tolerant = abi.with_decode_options(DecodeOptions { tolerate_unknown_variants: true, ..Default::default() });
+    /// assert_eq!(tolerant.binary_to_variant("v", data)?, json!(["__unknown_2", "abcd"]));
+    /// # Ok::<(), color_eyre::Report>(())
+    /// ```
+    pub fn with_decode_options(mut self, options: DecodeOptions) -> Self {
+        self.decode_options = options;
+        self
+    }
+
+    pub fn decode_options(&self) -> DecodeOptions {
+        self.decode_options
+    }
+
     pub fn binary_to_variant<'a, T>(&self, typename: T, bytes: Bytes)
                                     -> Result<JsonValue>
     where
         T: Into<TypeName<'a>>
     {
-        self.decode_variant_(&mut bytes.view(), typename.into())
+        self.decode_variant_(&mut OffsetRecorder::disabled(), &self.serialization_config(),
+                             &mut bytes.view(), typename.into())
     }
 
 
+    /// Decode both binary payloads as `typename` and return their field-level
+    /// differences, eg: to compare an action packed locally with the one produced
+    /// by a wallet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, abi::data::EOSIO_TOKEN_ABI, json};
+    /// let abi = ABI::from_str(EOSIO_TOKEN_ABI)?;
+    /// let transfer = |memo| json!({"from": "alice", "to": "bob", "quantity": "1.0000 A", "memo": memo});
+    /// let a = abi.variant_to_binary("transfer", &transfer("hello"))?;
+    /// let b = abi.variant_to_binary("transfer", &transfer("world"))?;
+    /// let diff = abi.diff_binary("transfer", &a, &b)?;
+    /// assert_eq!(diff.to_string(), "memo: \"hello\" != \"world\"\n");
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn diff_binary<'a, T>(&self, typename: T, a: &[u8], b: &[u8]) -> Result<ValueDiff>
+    where
+        T: Into<TypeName<'a>>
+    {
+        let typename = typename.into();
+        let config = self.serialization_config();
+        let decode = |data: &[u8]| -> Result<(JsonValue, Bytes)> {
+            let mut ds = ByteStream::from(data);
+            let value = self.decode_variant_(&mut OffsetRecorder::disabled(), &config, &mut ds, typename)?;
+            Ok((value, Bytes::from(ds.leftover().to_vec())))
+        };
+        let (a, leftover_a) = decode(a)?;
+        let (b, leftover_b) = decode(b)?;
+        Ok(ValueDiff::new(a, b, leftover_a, leftover_b))
+    }
+
+    /// Decode the return value of an action, using the type declared for it in
+    /// the `action_results` section of the ABI.
+    ///
+    /// This is typically used on the `return_value_data` field of action traces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, ActionName, json};
+    /// let abi = ABI::from_str(r#"{
+    ///     "version": "eosio::abi/1.2",
+    ///     "structs": [{"name": "hi", "base": "", "fields": [{"name": "user", "type": "name"}]}],
+    ///     "actions": [{"name": "hi", "type": "hi", "ricardian_contract": ""}],
+    ///     "action_results": [{"name": "hi", "result_type": "string"}]
+    /// }"#)?;
+    /// let hi = ActionName::constant("hi");
+    /// let data = abi.encode_action_result(hi, &json!("hello alice"))?;
+    /// assert_eq!(abi.decode_action_result(hi, &data)?, json!("hello alice"));
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn decode_action_result(&self, action: ActionName, data: &[u8]) -> Result<JsonValue> {
+        let result_type = self.action_result_type(action)?;
+        let mut ds = ByteStream::from(data);
+        let result = self.decode_variant(&mut ds, result_type)?;
+        ensure!(ds.leftover().is_empty(), LeftoverDataInStreamSnafu);
+        Ok(result)
+    }
+
+    /// Encode the return value of an action, see [`ABI::decode_action_result()`].
+    pub fn encode_action_result(&self, action: ActionName, value: &JsonValue) -> Result<Bytes> {
+        self.variant_to_binary(self.action_result_type(action)?, value)
+    }
+
+    fn action_result_type(&self, action: ActionName) -> Result<TypeName<'_>> {
+        self.action_results.get(&action)
+            .map(|t| TypeName(t))
+            .context(UnknownActionResultSnafu { action: action.to_string() })
+    }
+
     #[inline]
     pub fn decode_variant<'a, T>(&self, ds: &mut ByteStream, typename: T) -> Result<JsonValue, ABIError>
     where
         T: Into<TypeName<'a>>
     {
-        self.decode_variant_(ds, typename.into())
+        self.decode_variant_(&mut OffsetRecorder::disabled(), &self.serialization_config(), ds, typename.into())
+    }
+
+    /// Decode a value of the given type, and also return the location in the stream
+    /// of all the values that have been decoded (struct fields, array items, etc.).
+    ///
+    /// This allows to find which bytes of the input correspond to a given value,
+    /// eg: to debug a value that is decoded incorrectly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kudu::{ABI, ABIError, Bytes, abi::data::EOSIO_TOKEN_ABI, json};
+    /// let abi = ABI::from_str(EOSIO_TOKEN_ABI)?;
+    /// let transfer = json!({"from": "alice", "to": "bob", "quantity": "1.0000 A", "memo": "hi"});
+    /// let data = abi.variant_to_binary("transfer", &transfer)?;
+    ///
+    /// let (value, offsets) = abi.decode_variant_with_offsets(&mut data.view(), "transfer")?;
+    /// assert_eq!(value, transfer);
+    /// assert_eq!(offsets[""].range(), 0..35);
+    /// assert_eq!(offsets["/to"].range(), 8..16);
+    /// assert_eq!(&data[offsets["/memo"].range()], b"\x02hi");
+    /// # Ok::<(), ABIError>(())
+    /// ```
+    pub fn decode_variant_with_offsets<'a, T>(&self, ds: &mut ByteStream, typename: T)
+                                              -> Result<(JsonValue, ByteOffsets), ABIError>
+    where
+        T: Into<TypeName<'a>>
+    {
+        let mut offsets = OffsetRecorder::enabled();
+        let value = self.decode_variant_(&mut offsets, &self.serialization_config(), ds, typename.into())?;
+        Ok((value, offsets.into_offsets()))
+    }
+
+    /// Return the serialization config used for decoding, read only once per decoded
+    /// value so that it is consistent even if the process default changes meanwhile.
+    fn serialization_config(&self) -> SerializationConfig {
+        self.decode_options.serialization.unwrap_or_else(config::default_serialization)
     }
 
     #[allow(clippy::collapsible_else_if)]
-    fn decode_variant_(&self, ds: &mut ByteStream, typename: TypeName) -> Result<JsonValue, ABIError> {
-        let rtype = self.resolve_type(typename);
+    fn decode_variant_(&self, offsets: &mut OffsetRecorder, config: &SerializationConfig,
+                       ds: &mut ByteStream, typename: TypeName) -> Result<JsonValue, ABIError> {
+        let rtype = self.resolve_type(typename)?;
         let ftype = rtype.fundamental_type();
+        let start = ds.read_pos();
 
-        Ok(if AntelopeValue::VARIANTS.contains(&ftype) {
-            let type_ = ftype.try_into().unwrap();  // safe unwrap
-
+        let value = if let Ok(type_) = AntelopeType::try_from(ftype) {
             // if our fundamental type is a builtin type, we can deserialize it directly
             // from the stream
-            if rtype.is_array() {
-                let item_count = decode_usize(ds, "item_count (as varuint32)")?;
+            if rtype.is_array() || rtype.is_sized_array() {
+                let item_count = decode_array_len(ds, rtype)?;
                 debug!(r#"reading array of {item_count} elements of type "{ftype}""#);
                 // limit the maximum size that can be reserved before data is read
                 let initial_capacity = item_count.min(1024);
                 let mut a = Vec::with_capacity(initial_capacity);
                 // loop {}
-                for _ in 0..item_count {
-                    a.push(read_value(ds, type_, "array item")?);
+                for i in 0..item_count {
+                    a.push(offsets.enter(i, |offsets| {
+                        let item_start = ds.read_pos();
+                        let item = read_value(ds, config, type_, "array item")
+                            .and_then(|item| self.apply_decode_hook(ftype, item));
+                        offsets.record(item_start, ds.read_pos());
+                        item
+                    })?);
                 }
                 JsonValue::Array(a)
             }
@@ -504,25 +1258,25 @@ impl ABI {
                 let non_null = bool::from_bin(ds)
                     .context(DeserializeSnafu { what: "optional discriminant" })?;
                 match non_null {
-                    true => read_value(ds, type_, "optional value")?,
+                    true => self.apply_decode_hook(ftype, read_value(ds, config, type_, "optional value")?)?,
                     false => JsonValue::Null,
                 }
             }
             else {
-                read_value(ds, type_, "single `AntelopeValue`")?
+                read_value(ds, config, type_, "single `AntelopeValue`")?
             }
         }
         else {
-            if rtype.is_array() {
+            if rtype.is_array() || rtype.is_sized_array() {
                 // not a builtin type, we have to recurse down
-                let item_count = decode_usize(ds, "item_count (as varuint32)")?;
+                let item_count = decode_array_len(ds, rtype)?;
                 debug!(r#"reading array of {item_count} elements of type "{ftype}""#);
                 // limit the maximum size that can be reserved before data is read
                 let initial_capacity = item_count.min(1024);
                 let mut a = Vec::with_capacity(initial_capacity);
                 // loop {}
-                for _ in 0..item_count {
-                    a.push(self.decode_variant(ds, ftype)?);
+                for i in 0..item_count {
+                    a.push(offsets.enter(i, |offsets| self.decode_variant_(offsets, config, ds, ftype))?);
                 }
                 JsonValue::Array(a)
             }
@@ -530,46 +1284,73 @@ impl ABI {
                 let non_null = bool::from_bin(ds)
                     .context(DeserializeSnafu { what: "optional discriminant" })?;
                 match non_null {
-                    true => self.decode_variant(ds, ftype)?,
+                    true => self.decode_variant_(offsets, config, ds, ftype)?,
                     false => JsonValue::Null,
                 }
             }
             else if let Some(variant_def) = self.variants.get(rtype.as_str()) {
                 let variant_tag: usize = decode_usize(ds, "variant tag (as varuint32)")?;
-                ensure!(variant_tag < variant_def.types.len(),
-                        DecodeSnafu { message: format!("deserialized invalid tag {} for variant {}",
-                                                       variant_tag, rtype)
-                        });
-                let variant_type = TypeName(&variant_def.types[variant_tag]);
-                json!([variant_type, self.decode_variant(ds, variant_type)?])
+                let tag_end = ds.read_pos();
+                offsets.enter(0, |offsets| offsets.record(start, tag_end));
+                if variant_tag >= variant_def.types.len() && self.decode_options.tolerate_unknown_variants {
+                    // the size of the unknown value is unknown, consume all the remaining data
+                    let data = config.hex(ds.leftover());
+                    let remaining = ds.leftover().len();
+                    ds.read_bytes(remaining).map_err(SerializeError::from)
+                        .context(DeserializeSnafu { what: "unknown variant data" })?;
+                    offsets.enter(1, |offsets| offsets.record(tag_end, ds.read_pos()));
+                    json!([format!("__unknown_{variant_tag}"), data])
+                }
+                else {
+                    ensure!(variant_tag < variant_def.types.len(),
+                            DecodeSnafu { message: format!("deserialized invalid tag {} for variant {}",
+                                                           variant_tag, rtype)
+                            });
+                    let variant_type = TypeName(&variant_def.types[variant_tag]);
+                    let value = offsets.enter(1, |offsets| self.decode_variant_(offsets, config, ds, variant_type))?;
+                    json!([variant_type, value])
+                }
             }
             else if let Some(struct_def) = self.structs.get(rtype.as_str()) {
-                self.decode_struct(ds, struct_def)?
+                self.decode_struct(offsets, config, ds, struct_def)?
+            }
+            else if let Some(builtin) = self.builtin_types.get(rtype.as_str()) {
+                builtin.decode(ds).context(BuiltinTypeSnafu { typename: rtype.to_string() })?
             }
             else {
                 DecodeSnafu { message: format!("do not know how to deserialize type: {}", rtype) }.fail()?
             }
-        })
+        };
+
+        offsets.record(start, ds.read_pos());
+
+        // a hook on an alias takes precedence over the one on the type it resolves to
+        match self.decode_hooks.contains_key(typename.as_str()) {
+            true => self.apply_decode_hook(typename, value),
+            false => self.apply_decode_hook(rtype, value),
+        }
     }
 
-    fn decode_struct(&self, ds: &mut ByteStream, struct_def: &Struct) -> Result<JsonValue, ABIError> {
+    fn apply_decode_hook(&self, typename: TypeName, value: JsonValue) -> Result<JsonValue, ABIError> {
+        match self.decode_hooks.get(typename.as_str()) {
+            Some(hook) => hook.apply(value).context(DecodeHookSnafu { typename: typename.to_string() }),
+            None => Ok(value),
+        }
+    }
+
+    fn decode_struct(&self, offsets: &mut OffsetRecorder, config: &SerializationConfig,
+                     ds: &mut ByteStream, struct_def: &Struct) -> Result<JsonValue, ABIError> {
         debug!(r#"reading struct with name "{}" and base "{}""#, struct_def.name, struct_def.base);
 
         let mut result: JsonMap<String, JsonValue> = JsonMap::new();
 
-        if !struct_def.base.is_empty() {
-            // result.insert("base".to_owned(), json!(struct_def.base));
-            let base_def = self.structs.get(&struct_def.base).unwrap();
-            let mut base = self.decode_struct(ds, base_def)?;
-            debug!("base {base:?}");
-            // array(&mut result, "fields").append(array(&mut base, "fields"));
-            result.append(base.as_object_mut().unwrap());
-        }
+        // fields from the base structs come first
+        let fields = self.struct_fields(struct_def)?;
 
         let mut encountered_extension = false;
-        let nfields = struct_def.fields.len();
+        let nfields = fields.len();
         debug!("reading {nfields} fields");
-        for field in &struct_def.fields {
+        for field in fields {
             let fname = &field.name;
             let ftype = TypeName(&field.type_);
             encountered_extension |= ftype.has_bin_extension();
@@ -587,8 +1368,9 @@ impl ABI {
                     fname, struct_def.name) }.fail()?
             }
 
-            let rtype = self.resolve_type(ftype.remove_bin_extension());
-            let value = self.decode_variant(ds, rtype)?;
+            // the type is resolved when decoding, keep its name for looking up decode hooks
+            let ftype = ftype.remove_bin_extension();
+            let value = offsets.enter(fname, |offsets| self.decode_variant_(offsets, config, ds, ftype))?;
             debug!(r#"decoded field '{fname}' with type `{ftype}`: {value}"#);
             result.insert(fname.to_string(), value);
         }
@@ -598,9 +1380,37 @@ impl ABI {
     }
 }
 
-fn read_value(stream: &mut ByteStream, type_: AntelopeType, what: &str) ->  Result<JsonValue, ABIError> {
+/// Decode the given hex data and compute the sha256 hash of the decoded bytes at the
+/// same time, by chunks, so that big ABIs are only traversed once.
+fn decode_hex_and_hash(hex_data: &str) -> Result<(Vec<u8>, Checksum256), FromHexError> {
+    let hex_data = hex_data.as_bytes();
+    if !hex_data.len().is_multiple_of(2) {
+        return Err(FromHexError::OddLength);
+    }
+
+    let mut result = vec![0u8; hex_data.len() / 2];
+    let mut hasher = Sha256::new();
+    for (n, (src, dst)) in hex_data.chunks(HEX_CHUNK_SIZE)
+        .zip(result.chunks_mut(HEX_CHUNK_SIZE / 2))
+        .enumerate()
+    {
+        hex::decode_to_slice(src, dst).map_err(|e| match e {
+            // report the position of the invalid character in the whole input
+            FromHexError::InvalidHexCharacter { c, index } => FromHexError::InvalidHexCharacter {
+                c, index: index + n * HEX_CHUNK_SIZE
+            },
+            e => e,
+        })?;
+        hasher.update(&*dst);
+    }
+    let hash: [u8; 32] = hasher.finalize().into();
+    Ok((result, hash.into()))
+}
+
+fn read_value(stream: &mut ByteStream, config: &SerializationConfig, type_: AntelopeType, what: &str)
+              -> Result<JsonValue, ABIError> {
     Ok(AntelopeValue::from_bin(type_, stream)
-       .context(DeserializeSnafu { what })?.to_variant())
+       .context(DeserializeSnafu { what })?.to_variant_with(config))
 }
 
 fn decode_usize(stream: &mut ByteStream, what: &str) -> Result<usize, ABIError> {
@@ -616,6 +1426,28 @@ fn decode_usize(stream: &mut ByteStream, what: &str) -> Result<usize, ABIError>
     Ok(n)
 }
 
+/// Return whether the given object or array only contains scalar values.
+fn is_flat(value: &JsonValue) -> bool {
+    let is_scalar = |v: &JsonValue| !v.is_array() && !v.is_object();
+    match value {
+        JsonValue::Object(obj) => obj.values().all(is_scalar),
+        JsonValue::Array(items) => items.iter().all(is_scalar),
+        _ => true,
+    }
+}
+
+/// Decode the number of items of an array, checking that it matches the size of the
+/// array type if it is a fixed-size array.
+fn decode_array_len(stream: &mut ByteStream, rtype: TypeName) -> Result<usize, ABIError> {
+    let item_count = decode_usize(stream, "item_count (as varuint32)")?;
+    if let Some(size) = rtype.sized_array_len() {
+        ensure!(item_count == size, DecodeSnafu { message: format!(
+            "wrong number of items for fixed-size array `{rtype}`: got {item_count}, expected {size}"
+        )});
+    }
+    Ok(item_count)
+}
+
 
 
 // TODO: rename this ScopeGuard?
@@ -28,6 +28,14 @@ impl<'a> TypeName<'a> {
         }
     }
 
+    /// Return the number of elements of a fixed-size array type, eg: `32` for `uint8[32]`,
+    /// or `None` if this is not a fixed-size array.
+    pub fn sized_array_len(&self) -> Option<usize> {
+        if !self.is_sized_array() { return None; }
+        let (_, size) = self.0.strip_suffix(']')?.rsplit_once('[')?;
+        size.parse().ok()
+    }
+
     pub fn is_optional(&self) -> bool {
         self.0.ends_with('?')
     }
@@ -53,6 +61,7 @@ impl<'a> TypeName<'a> {
     /// assert_eq!(ftype("int8[][]"), TypeName("int8[]"));
     /// assert_eq!(ftype("int8[][]?"), TypeName("int8[][]"));
     /// ```
+    #[allow(clippy::unwrap_used)]
     pub fn fundamental_type(&self) -> TypeName<'a> {
         if self.is_array() {
             TypeName(&self.0[..self.0.len() - 2])
@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::collections::{BTreeMap, BTreeSet};
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
 use std::str::{from_utf8, Utf8Error};
 
 use bytemuck::{cast_ref, pod_read_unaligned};
+use serde::{de, Deserialize, Deserializer, Serializer};
 use snafu::{Snafu, ResultExt, ensure};
 
 use kudu_macros::with_location;
@@ -42,7 +44,7 @@ pub enum SerializeError {
     #[snafu(display("invalid tag (discriminant): {tag} for variant type `{variant}`"), visibility(pub))]
     InvalidTag { tag: u32, variant: String },
 
-    #[snafu(display("{message}"))]
+    #[snafu(display("{message}"), visibility(pub(crate)))]
     InvalidData { message: String },  // acts as a generic error type with a given message
 
     #[snafu(display("ABI error"), visibility(pub(crate)))]
@@ -85,6 +87,77 @@ pub fn from_bin<T: ABISerializable>(bin: impl AsRef<[u8]>) -> Result<T, Serializ
     T::from_bin(&mut s)
 }
 
+// -----------------------------------------------------------------------------
+//     Serde helpers for non human-readable formats
+// -----------------------------------------------------------------------------
+
+// types that have a string representation (keys, checksums, etc.) use these when
+// the serde format is not human-readable (eg: bincode, MessagePack) so that they
+// are stored as the raw bytes of their binary representation instead
+
+pub(crate) fn serialize_bin<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ABISerializable,
+    S: Serializer,
+{
+    serializer.serialize_bytes(to_bin(value).as_ref())
+}
+
+pub(crate) fn deserialize_bin<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: ABISerializable,
+    D: Deserializer<'de>,
+{
+    let data = Bytes::deserialize(deserializer)?;
+    let mut stream = ByteStream::from(data.as_ref());
+    let result = T::from_bin(&mut stream).map_err(|e| de::Error::custom(e.to_string()))?;
+    if !stream.leftover().is_empty() {
+        return Err(de::Error::custom(format!("{} bytes left in stream after deserializing",
+                                             stream.leftover().len())));
+    }
+    Ok(result)
+}
+
+// types with a string representation use this in human-readable formats, so that
+// the string can be parsed directly if it is borrowed from the input (eg: JSON text)
+// and still be accepted if the format can only provide an owned one
+
+pub(crate) fn deserialize_str<'de, T, E, D>(deserializer: D, expecting: &'static str,
+                                            parse: impl FnOnce(&str) -> Result<T, E>) -> Result<T, D::Error>
+where
+    E: ToString,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(StrVisitor { expecting, parse, _marker: PhantomData })
+}
+
+struct StrVisitor<T, F> {
+    expecting: &'static str,
+    parse: F,
+    _marker: PhantomData<T>,
+}
+
+impl<T, E, F> de::Visitor<'_> for StrVisitor<T, F>
+where
+    E: ToString,
+    F: FnOnce(&str) -> Result<T, E>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<DE: de::Error>(self, v: &str) -> Result<T, DE> {
+        (self.parse)(v).map_err(|e| DE::custom(e.to_string()))
+    }
+
+    fn visit_string<DE: de::Error>(self, v: String) -> Result<T, DE> {
+        self.visit_str(&v)
+    }
+}
+
+
 // -----------------------------------------------------------------------------
 //     Boilerplate macros
 // -----------------------------------------------------------------------------
@@ -249,7 +322,7 @@ impl ABISerializable for &[u8] {
         stream.write_bytes(self);
     }
     fn from_bin(_stream: &mut ByteStream) -> Result<Self, SerializeError> {
-        unimplemented!();
+        InvalidDataSnafu { message: "cannot deserialize to a borrowed `&[u8]`, use `Bytes` instead" }.fail()
     }
 }
 
@@ -271,7 +344,7 @@ impl ABISerializable for &str {
         stream.write_bytes(self.as_bytes());
     }
     fn from_bin(_stream: &mut ByteStream) -> Result<Self, SerializeError> {
-        unimplemented!()
+        InvalidDataSnafu { message: "cannot deserialize to a borrowed `&str`, use `String` instead" }.fail()
     }
 }
 
@@ -286,12 +359,14 @@ impl_wrapped_serialization!(BlockTimestamp, u32);
 
 
 // -----------------------------------------------------------------------------
-//     Serialization of checksum types
+//     Serialization of checksum and BLS types
 // -----------------------------------------------------------------------------
 
 impl_array_serialization!(Checksum160, 20);
 impl_array_serialization!(Checksum256, 32);
 impl_array_serialization!(Checksum512, 64);
+impl_array_serialization!(BlsPublicKey, 96);
+impl_array_serialization!(BlsSignature, 192);
 
 
 // -----------------------------------------------------------------------------
@@ -311,6 +386,28 @@ impl ABISerializable for Name {
     }
 }
 
+macro_rules! impl_name_serialization {
+    ($typ:ty) => {
+        impl ABISerializable for $typ {
+            #[inline]
+            fn to_bin(&self, stream: &mut Bytes) {
+                self.as_u64().to_bin(stream)
+            }
+
+            #[inline]
+            fn from_bin(stream: &mut ByteStream) -> Result<Self, SerializeError> {
+                Ok(<$typ>::from_u64(u64::from_bin(stream)?))
+            }
+        }
+    }
+}
+
+impl_name_serialization!(AccountName);
+impl_name_serialization!(ActionName);
+impl_name_serialization!(PermissionName);
+impl_name_serialization!(TableName);
+impl_name_serialization!(ScopeName);
+
 impl ABISerializable for Symbol {
     #[inline]
     fn to_bin(&self, stream: &mut Bytes) {
@@ -358,7 +455,7 @@ impl ABISerializable for ExtendedAsset {
 
     fn from_bin(stream: &mut ByteStream) -> Result<Self, SerializeError> {
         let quantity = Asset::from_bin(stream)?;
-        let contract = Name::from_bin(stream)?;
+        let contract = AccountName::from_bin(stream)?;
         Ok(ExtendedAsset { quantity, contract })
     }
 }
@@ -370,23 +467,24 @@ impl<T: CryptoDataType, const DATA_SIZE: usize> ABISerializable for CryptoData<T
     }
 
     fn from_bin(stream: &mut ByteStream) -> Result<Self, SerializeError> {
-        let key_type = KeyType::from_index(stream.read_byte()?)?;
+        let key_type = KeyType::fixed_size_from_index(stream.read_byte()?)?;
         let data = stream.read_bytes(DATA_SIZE)?.try_into().unwrap();  // safe unwrap
         Ok(Self::with_key_type(key_type, data))
     }
 }
 
 // this, coupled with the blanket impl for Vec, gives us the impl for the `Extensions` type
-impl ABISerializable for (u16, Bytes) {
+// (`Vec<(u16, Bytes)>`), and it is also used for `std::pair` in general
+impl<A: ABISerializable, B: ABISerializable> ABISerializable for (A, B) {
     fn to_bin(&self, stream: &mut Bytes) {
         self.0.to_bin(stream);
         self.1.to_bin(stream);
     }
 
     fn from_bin(stream: &mut ByteStream) -> Result<Self, SerializeError> {
-        let id = u16::from_bin(stream)?;
-        let data = Bytes::from_bin(stream)?;
-        Ok((id, data))
+        let first = A::from_bin(stream)?;
+        let second = B::from_bin(stream)?;
+        Ok((first, second))
     }
 }
 
@@ -401,7 +499,9 @@ impl ABISerializable for (u16, Bytes) {
 //     impl for fixed-size arrays [T; N]
 // -----------------------------------------------------------------------------
 
-impl<T: ABISerializable + Debug, const N: usize> ABISerializable for [T; N] {
+// NOTE: fixed-size arrays are serialized with their length prepended, the same way
+//       as `std::array` in the CDT and `T[N]` types in ABIs
+impl<T: ABISerializable, const N: usize> ABISerializable for [T; N] {
     fn to_bin(&self, stream: &mut Bytes) {
         stream.write_var_u32(self.len() as u32);
         for elem in self {
@@ -419,7 +519,8 @@ impl<T: ABISerializable + Debug, const N: usize> ABISerializable for [T; N] {
         for _ in 0..len {
             result.push(T::from_bin(stream)?);
         }
-        Ok(result.try_into().unwrap())  // safe unwrap
+        let Ok(result) = result.try_into() else { unreachable!("array length has been checked") };
+        Ok(result)
     }
 }
 
@@ -447,7 +548,9 @@ impl<T: ABISerializable> ABISerializable for Vec<T> {
         ensure!(len < config::MAX_ARRAY_SIZE, InvalidDataSnafu {
             message: format!("deserializing vector with size over max allowed size: {} > {}", len, config::MAX_ARRAY_SIZE)
         });
-        let mut result = Vec::with_capacity(len);
+        // each element takes at least one byte, so do not trust `len` further than that
+        // as it could make us allocate a lot of memory for a short invalid input
+        let mut result = Vec::with_capacity(len.min(stream.leftover().len()));
         for _ in 0..len {
             result.push(T::from_bin(stream)?);
         }
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    APIClient, BlockId, BlockNum, Extensions, JsonValue, SignedBlock, SignedBlockHeader, json,
+    api::{HttpError, InvalidResponseSnafu},
+};
+
+
+/// Reference to a block, either by number or by id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockNumOrId {
+    Num(BlockNum),
+    Id(BlockId),
+}
+
+impl From<BlockNum> for BlockNumOrId {
+    fn from(block_num: BlockNum) -> Self {
+        BlockNumOrId::Num(block_num)
+    }
+}
+
+impl From<BlockId> for BlockNumOrId {
+    fn from(block_id: BlockId) -> Self {
+        BlockNumOrId::Id(block_id)
+    }
+}
+
+impl BlockNumOrId {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            BlockNumOrId::Num(num) => json!(num),
+            BlockNumOrId::Id(id) => json!(id.to_hex()),
+        }
+    }
+}
+
+
+/// Header of a block, as returned by [`APIClient::get_block_header()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHeaderInfo {
+    pub id: BlockId,
+    pub header: SignedBlockHeader,
+    /// The block extensions, only present if they have been requested
+    pub block_extensions: Option<Extensions>,
+}
+
+
+impl APIClient {
+    /// Return a block using the `/v1/chain/get_raw_block` endpoint of Spring nodes.
+    ///
+    /// As opposed to `/v1/chain/get_block`, the node returns the block as stored,
+    /// without decoding the action data using the contract ABIs, which makes it
+    /// much faster to serve. This is the preferred way to fetch blocks in bulk,
+    /// eg: when backfilling a database.
+    pub fn get_raw_block(&self, block: impl Into<BlockNumOrId>) -> Result<SignedBlock, HttpError> {
+        let mut result = self.call("/v1/chain/get_raw_block", &json!({"block_num_or_id": block.into().to_json()}))?;
+        normalize_block(&mut result);
+        from_response("signed_block", result)
+    }
+
+    /// Return the header of a block using the `/v1/chain/get_block_header` endpoint
+    /// of Spring nodes, and its block extensions if `include_extensions` is `true`.
+    pub fn get_block_header(&self, block: impl Into<BlockNumOrId>, include_extensions: bool)
+                            -> Result<BlockHeaderInfo, HttpError> {
+        let result = self.call("/v1/chain/get_block_header", &json!({
+            "block_num_or_id": block.into().to_json(),
+            "include_extensions": include_extensions,
+        }))?;
+        Ok(BlockHeaderInfo {
+            id: from_response("block id", result["id"].clone())?,
+            header: from_response("signed_block_header", result["signed_block_header"].clone())?,
+            block_extensions: match &result["block_extensions"] {
+                JsonValue::Null => None,
+                extensions => Some(from_response("block_extensions", extensions.clone())?),
+            },
+        })
+    }
+}
+
+fn from_response<T: DeserializeOwned>(what: &str, value: JsonValue) -> Result<T, HttpError> {
+    serde_json::from_value(value)
+        .map_err(|e| InvalidResponseSnafu { message: format!("invalid {what}: {e}") }.build())
+}
+
+/// Convert the representation of a block used by nodeos when not going through an
+/// ABI (enums as strings, variants as `[index, value]`) to the one used by kudu.
+fn normalize_block(block: &mut JsonValue) {
+    let Some(transactions) = block["transactions"].as_array_mut() else { return; };
+    for receipt in transactions.iter_mut().filter(|r| r.is_object()) {
+        let status = match receipt["status"].as_str() {
+            Some("executed") => Some(0),
+            Some("soft_fail") => Some(1),
+            Some("hard_fail") => Some(2),
+            Some("delayed") => Some(3),
+            Some("expired") => Some(4),
+            _ => None,
+        };
+        if let Some(status) = status {
+            receipt["status"] = json!(status);
+        }
+
+        let trx = &mut receipt["trx"];
+        match trx[0].as_u64() {
+            Some(0) => trx[0] = json!("transaction_id"),
+            Some(1) => trx[0] = json!("packed_transaction"),
+            _ => {},
+        }
+        if let Some(compression) = trx.get_mut(1).and_then(|t| t.get_mut("compression")) {
+            match compression.as_str() {
+                Some("none") => *compression = json!(0),
+                Some("zlib") => *compression = json!(1),
+                _ => {},
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use color_eyre::eyre::Result;
+
+    use crate::{ReceiptTransaction, api::MockTransport};
+    use super::*;
+
+    const BLOCK_ID: &str = "0000000267f3e2284b482f3afc2e724be1d6cbc1804532ec62d4e7af47c30693";
+    const SIGNATURE: &str = "SIG_K1_K18qEA2qTqVj153ZKriMnnRwHpLuENX7bp9UYs5AJsRWhgD6diPgMeoebwRRFQuvyicDsgwVYTt3g4GsG5FxCXM3WNZVN7";
+
+    fn header() -> JsonValue {
+        json!({
+            "timestamp": "2024-01-01T00:00:00.500",
+            "producer": "eosio",
+            "confirmed": 0,
+            "previous": BLOCK_ID,
+            "transaction_mroot": "0000000000000000000000000000000000000000000000000000000000000000",
+            "action_mroot": "0000000000000000000000000000000000000000000000000000000000000000",
+            "schedule_version": 0,
+            "new_producers": null,
+            "header_extensions": [],
+            "producer_signature": SIGNATURE,
+        })
+    }
+
+    #[test]
+    fn test_get_raw_block() -> Result<()> {
+        let mut block = header();
+        block["transactions"] = json!([{
+            "status": "executed",
+            "cpu_usage_us": 150,
+            "net_usage_words": 16,
+            "trx": [1, {
+                "signatures": [SIGNATURE],
+                "compression": "none",
+                "packed_context_free_data": "",
+                "packed_trx": "d3029649d2042e160000000000000100a6823403ea3055000000572d3ccdcd01608c31c6187315d600000000a8ed323221608c31c6187315d6708c31c6187315d6010000000000000004535953000000000000",
+            }],
+        }, {
+            "status": "hard_fail",
+            "cpu_usage_us": 100,
+            "net_usage_words": 0,
+            "trx": [0, BLOCK_ID],
+        }]);
+        block["block_extensions"] = json!([]);
+
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_raw_block", block);
+        let client = APIClient::with_transport("mock://", mock.clone());
+
+        let block = client.get_raw_block(3)?;
+        mock.assert_called_with("/v1/chain/get_raw_block", &json!({"block_num_or_id": 3}));
+        assert_eq!(block.block_num(), 3);
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!((block.transactions[0].status, block.transactions[1].status), (0, 2));
+        let ReceiptTransaction::Packed(trx) = &block.transactions[0].trx else { panic!("not a packed trx") };
+        assert_eq!(trx.unpack()?.ref_block_num, 1234);
+        assert!(matches!(&block.transactions[1].trx, ReceiptTransaction::Id(id) if id.to_hex() == BLOCK_ID));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_block_header() -> Result<()> {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_block_header", json!({
+            "id": BLOCK_ID,
+            "signed_block_header": header(),
+            "block_extensions": [[2, "0102"]],
+        }));
+        let client = APIClient::with_transport("mock://", mock.clone());
+
+        let id = BlockId::from_hex(BLOCK_ID)?;
+        let info = client.get_block_header(id.clone(), true)?;
+        mock.assert_called_with("/v1/chain/get_block_header",
+                                &json!({"block_num_or_id": BLOCK_ID, "include_extensions": true}));
+        assert_eq!(info.id, id);
+        assert_eq!(info.header.header.producer.to_string(), "eosio");
+        assert_eq!(info.block_extensions.unwrap()[0].0, 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Registry of the chains an application talks to, each with its own [`APIClient`]
+//! and cache of contract ABIs.
+//!
+//! This is useful for cross-chain tooling (bridges, monitors, etc.) which would
+//! otherwise need to juggle clients and ABI caches manually.
+//!
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use serde_json::json;
+use snafu::{Snafu, OptionExt, ResultExt, ensure};
+
+use kudu_macros::with_location;
+
+use crate::{ABI, ABIDefinition, ABIError, APIClient, AccountName, ChainId, api::HttpError, config};
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum ChainError {
+    #[snafu(display("unknown chain: '{name}'"))]
+    UnknownChain { name: String },
+
+    #[snafu(display("chain already exists: '{name}'"))]
+    DuplicateChain { name: String },
+
+    #[snafu(display("account '{account}' has no ABI on chain '{chain}'"))]
+    NoABI { account: AccountName, chain: String },
+
+    #[snafu(display("invalid chain id returned by node: {chain_id}"))]
+    InvalidChainId { chain_id: String },
+
+    #[snafu(display("error while querying chain '{chain}'"))]
+    Http { chain: String, source: HttpError },
+
+    #[snafu(display("invalid ABI for account '{account}' on chain '{chain}'"))]
+    InvalidABI { account: AccountName, chain: String, source: ABIError },
+}
+
+
+// -----------------------------------------------------------------------------
+//     Chain
+// -----------------------------------------------------------------------------
+
+/// A chain with the client used to query it and a cache of the ABIs of the
+/// contracts deployed on it.
+#[derive(Debug)]
+pub struct Chain {
+    pub name: String,
+    pub client: Arc<APIClient>,
+    chain_id: Mutex<Option<ChainId>>,
+    abis: Mutex<HashMap<AccountName, Arc<ABI>>>,
+}
+
+impl Chain {
+    pub fn new(name: &str, client: Arc<APIClient>) -> Self {
+        Chain {
+            name: name.to_string(),
+            client,
+            chain_id: Mutex::new(None),
+            abis: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the chain id, avoiding to query the node when it is needed.
+    pub fn with_chain_id(self, chain_id: ChainId) -> Self {
+        *self.chain_id.lock().unwrap_or_else(PoisonError::into_inner) = Some(chain_id);
+        self
+    }
+
+    /// Return the chain id if it is known, without querying the node.
+    pub fn known_chain_id(&self) -> Option<ChainId> {
+        self.chain_id.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Return the chain id, querying the node for it the first time if it
+    /// was not given at construction.
+    pub fn chain_id(&self) -> Result<ChainId, ChainError> {
+        if let Some(chain_id) = self.known_chain_id() {
+            return Ok(chain_id);
+        }
+        let info = self.client.get("/v1/chain/get_info").context(HttpSnafu { chain: &self.name })?;
+        let repr = info["chain_id"].to_string();
+        let chain_id = info["chain_id"].as_str()
+            .and_then(|id| ChainId::from_hex(id).ok())
+            .context(InvalidChainIdSnafu { chain_id: repr })?;
+        *self.chain_id.lock().unwrap_or_else(PoisonError::into_inner) = Some(chain_id.clone());
+        Ok(chain_id)
+    }
+
+    /// Return the ABI of the contract deployed on `account`, fetching it from the
+    /// node if it is not in the cache yet.
+    pub fn get_abi(&self, account: AccountName) -> Result<Arc<ABI>, ChainError> {
+        if let Some(abi) = self.cached_abi(account) {
+            return Ok(abi);
+        }
+
+        let result = self.client.call("/v1/chain/get_abi", &json!({"account_name": account}))
+            .context(HttpSnafu { chain: &self.name })?;
+        ensure!(result["abi"].is_object(), NoABISnafu { account, chain: &self.name });
+
+        let abi = ABIDefinition::from_variant(&result["abi"])
+            .and_then(|def| ABI::from_definition(&def))
+            .context(InvalidABISnafu { account, chain: &self.name })?;
+        Ok(self.set_abi(account, abi))
+    }
+
+    /// Return the ABI of the contract deployed on `account` if it is in the cache.
+    pub fn cached_abi(&self, account: AccountName) -> Option<Arc<ABI>> {
+        self.abis.lock().unwrap_or_else(PoisonError::into_inner).get(&account).cloned()
+    }
+
+    /// Insert the given ABI in the cache, replacing the previous one if any.
+    pub fn set_abi(&self, account: AccountName, abi: ABI) -> Arc<ABI> {
+        let abi = Arc::new(abi);
+        self.abis.lock().unwrap_or_else(PoisonError::into_inner).insert(account, abi.clone());
+        abi
+    }
+
+    /// Remove all ABIs from the cache, eg: after a contract has been updated.
+    pub fn clear_abi_cache(&self) {
+        self.abis.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     ChainRegistry
+// -----------------------------------------------------------------------------
+
+/// Collection of named [`Chain`]s, that can be looked up by name, alias or chain id.
+///
+/// # Example
+///
+/// ```
+/// # use kudu::api::{ChainRegistry, ChainError};
+/// let registry = ChainRegistry::with_defaults();
+/// let mainnet = registry.get("mainnet")?;
+/// assert_eq!(mainnet.name, "vaulta");
+/// assert_eq!(mainnet.client.endpoint, "https://vaulta.greymass.com");
+/// # Ok::<(), ChainError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct ChainRegistry {
+    chains: HashMap<String, Arc<Chain>>,
+    aliases: HashMap<String, String>,
+}
+
+impl ChainRegistry {
+    /// Return an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a registry containing the well-known chains: `local`, `vaulta`
+    /// (aliased as `mainnet`) and `jungle` (aliased as `testnet`).
+    pub fn with_defaults() -> Self {
+        let mut result = ChainRegistry::new();
+        let mut add = |name: &str, alias: Option<&str>, client: Arc<APIClient>, chain_id: Option<&str>| {
+            let mut chain = Chain::new(name, client);
+            if let Some(chain_id) = chain_id {
+                chain = chain.with_chain_id(ChainId::from_hex(chain_id).unwrap());  // safe unwrap
+            }
+            result.chains.insert(name.to_string(), Arc::new(chain));
+            if let Some(alias) = alias {
+                result.aliases.insert(alias.to_string(), name.to_string());
+            }
+        };
+        add("local", None, APIClient::local(), None);
+        add("vaulta", Some("mainnet"), APIClient::vaulta(), Some(config::VAULTA_CHAIN_ID));
+        add("jungle", Some("testnet"), APIClient::jungle(), Some(config::JUNGLE_CHAIN_ID));
+        result
+    }
+
+    /// Add a new chain to the registry and return it.
+    pub fn add(&mut self, chain: impl Into<Arc<Chain>>) -> Result<Arc<Chain>, ChainError> {
+        let chain = chain.into();
+        let name = chain.name.clone();
+        ensure!(!self.chains.contains_key(&name) && !self.aliases.contains_key(&name),
+                DuplicateChainSnafu { name });
+        self.chains.insert(name, chain.clone());
+        Ok(chain)
+    }
+
+    /// Add an alias for the chain with the given name.
+    pub fn add_alias(&mut self, alias: &str, name: &str) -> Result<(), ChainError> {
+        ensure!(self.chains.contains_key(name), UnknownChainSnafu { name });
+        ensure!(!self.chains.contains_key(alias), DuplicateChainSnafu { name: alias });
+        self.aliases.insert(alias.to_string(), name.to_string());
+        Ok(())
+    }
+
+    /// Remove the chain with the given name (or alias) from the registry, as
+    /// well as all its aliases.
+    pub fn remove(&mut self, name: &str) -> Option<Arc<Chain>> {
+        let name = self.aliases.get(name).cloned().unwrap_or_else(|| name.to_string());
+        self.aliases.retain(|_, target| *target != name);
+        self.chains.remove(&name)
+    }
+
+    /// Return the chain with the given name or alias, or whose chain id matches the
+    /// given hex representation.
+    ///
+    /// Note that lookup by chain id only considers chains whose id is already known.
+    pub fn get(&self, name: &str) -> Result<Arc<Chain>, ChainError> {
+        let target = self.aliases.get(name).map(String::as_str).unwrap_or(name);
+        if let Some(chain) = self.chains.get(target) {
+            return Ok(chain.clone());
+        }
+        ChainId::from_hex(name).ok()
+            .and_then(|id| self.by_chain_id(&id))
+            .context(UnknownChainSnafu { name })
+    }
+
+    /// Return the chain with the given chain id, if it is known.
+    pub fn by_chain_id(&self, chain_id: &ChainId) -> Option<Arc<Chain>> {
+        self.chains.values()
+            .find(|c| c.known_chain_id().as_ref() == Some(chain_id))
+            .cloned()
+    }
+
+    /// Return the names of all the chains in the registry, sorted alphabetically.
+    pub fn names(&self) -> Vec<String> {
+        let mut result: Vec<_> = self.chains.keys().cloned().collect();
+        result.sort();
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use super::*;
+    use crate::abi;
+
+    #[test]
+    fn test_chain_registry() -> Result<()> {
+        let mut registry = ChainRegistry::with_defaults();
+        assert_eq!(registry.names(), ["jungle", "local", "vaulta"]);
+        assert_eq!(registry.get("testnet")?.name, "jungle");
+        assert_eq!(registry.get(config::JUNGLE_CHAIN_ID)?.name, "jungle");
+        assert!(registry.get("unknown").is_err());
+
+        let chain = registry.add(Chain::new("devnet", Arc::new(APIClient::new("http://127.0.0.1:18888"))))?;
+        assert!(registry.add(Chain::new("mainnet", APIClient::local())).is_err());
+        registry.add_alias("dev", "devnet")?;
+        assert!(Arc::ptr_eq(&registry.get("dev")?, &chain));
+
+        // ABIs are cached per chain, so no request is made to the node here
+        let token = AccountName::constant("eosio.token");
+        chain.set_abi(token, ABI::from_str(abi::data::EOSIO_TOKEN_ABI)?);
+        assert!(chain.get_abi(token)?.has_struct("transfer"));
+        assert!(registry.get("local")?.cached_abi(token).is_none());
+
+        assert!(registry.remove("dev").is_some());
+        assert!(registry.get("devnet").is_err());
+        Ok(())
+    }
+}
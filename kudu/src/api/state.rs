@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+use snafu::OptionExt;
+
+use crate::{BlockNum, api::{HttpError, InvalidResponseSnafu}};
+
+
+/// State of the chain that read requests are expected to be served from.
+///
+/// Nodes serve either the speculative state at their head block, which might still
+/// be undone by a fork, or only irreversible state if they run with the
+/// `read-mode = irreversible` option. This is a setting of the node and not of
+/// the request, so a client in [`ReadMode::Irreversible`] checks that the node
+/// it talks to does serve irreversible state and fails otherwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ReadMode {
+    /// Speculative state at the head block (default)
+    #[default]
+    Head,
+    /// Only irreversible state
+    Irreversible,
+}
+
+impl fmt::Display for ReadMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReadMode::Head => "head",
+            ReadMode::Irreversible => "irreversible",
+        })
+    }
+}
+
+
+/// Block numbers describing the state of the chain as seen by a node, as returned
+/// by the `/v1/chain/get_info` endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainState {
+    pub head_block_num: BlockNum,
+    pub last_irreversible_block_num: BlockNum,
+}
+
+impl ChainState {
+    pub fn from_info(info: &JsonValue) -> Result<Self, HttpError> {
+        let block_num = |field: &str| -> Result<BlockNum, HttpError> {
+            info[field].as_u64()
+                .and_then(|n| BlockNum::try_from(n).ok())
+                .with_context(|| InvalidResponseSnafu { message: format!("invalid `{field}` in chain info: {}", info[field]) })
+        };
+        Ok(ChainState {
+            head_block_num: block_num("head_block_num")?,
+            last_irreversible_block_num: block_num("last_irreversible_block_num")?,
+        })
+    }
+
+    /// Return whether the node only serves irreversible state, ie: its head block
+    /// is irreversible.
+    pub fn is_irreversible(&self) -> bool {
+        self.head_block_num <= self.last_irreversible_block_num
+    }
+
+    /// Return whether the given block is irreversible.
+    pub fn is_block_irreversible(&self, block_num: BlockNum) -> bool {
+        block_num <= self.last_irreversible_block_num
+    }
+}
+
+
+/// The result of a read request, together with the state of the chain on the node
+/// that served it.
+///
+/// The chain state is fetched right before the request itself, so the returned
+/// value reflects a state at least as recent as `state.head_block_num`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadResult<T> {
+    pub value: T,
+    pub state: ChainState,
+    pub read_mode: ReadMode,
+}
+
+impl<T> ReadResult<T> {
+    /// Transform the value of the result, keeping the chain state.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ReadResult<U> {
+        ReadResult { value: f(self.value), state: self.state, read_mode: self.read_mode }
+    }
+}
@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::{
+    APIClient, BlockId, BlockNum, TimePoint, TimePointSec, TransactionId,
+    api::{ConfirmationTimeoutSnafu, HttpError, TransactionFailedSnafu},
+};
+
+
+/// State of a transaction as tracked by a node, see [`TransactionStatus`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionState {
+    /// Executed by the node, but not part of a block yet
+    LocallyApplied,
+    /// Part of a block that is not irreversible yet
+    InBlock,
+    /// Part of an irreversible block
+    Irreversible,
+    /// The block containing the transaction has been forked out, it may still be
+    /// included in another block before its expiration
+    ForkedOut,
+    /// The transaction failed and will not be included in a block
+    Failed,
+    /// The transaction is not known by the node
+    Unknown,
+}
+
+impl fmt::Display for TransactionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransactionState::LocallyApplied => "LOCALLY_APPLIED",
+            TransactionState::InBlock => "IN_BLOCK",
+            TransactionState::Irreversible => "IRREVERSIBLE",
+            TransactionState::ForkedOut => "FORKED_OUT",
+            TransactionState::Failed => "FAILED",
+            TransactionState::Unknown => "UNKNOWN",
+        })
+    }
+}
+
+
+/// Status of a transaction, as returned by [`APIClient::get_transaction_status()`].
+///
+/// The block fields are only present if the transaction is part of a block.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TransactionStatus {
+    pub state: TransactionState,
+    #[serde(default)]
+    pub block_number: Option<BlockNum>,
+    #[serde(default)]
+    pub block_id: Option<BlockId>,
+    #[serde(default)]
+    pub block_timestamp: Option<TimePoint>,
+    #[serde(default)]
+    pub expiration: Option<TimePointSec>,
+    pub head_number: BlockNum,
+    pub head_id: BlockId,
+    pub head_timestamp: TimePoint,
+    pub irreversible_number: BlockNum,
+    pub irreversible_id: BlockId,
+    pub irreversible_timestamp: TimePoint,
+    pub earliest_tracked_block_id: BlockId,
+    pub earliest_tracked_block_number: BlockNum,
+}
+
+impl TransactionStatus {
+    /// Return the number of blocks confirming the transaction, counting the block
+    /// it is part of, or 0 if it is not part of a block.
+    pub fn confirmations(&self) -> u32 {
+        match (self.state, self.block_number) {
+            (TransactionState::InBlock | TransactionState::Irreversible, Some(block_num)) =>
+                (self.head_number + 1).saturating_sub(block_num),
+            _ => 0,
+        }
+    }
+
+    /// Return whether the transaction has expired without being part of a block,
+    /// in which case it will never be.
+    pub fn is_expired(&self) -> bool {
+        match (self.state, self.expiration) {
+            (TransactionState::Unknown | TransactionState::ForkedOut | TransactionState::LocallyApplied,
+             Some(expiration)) => self.head_timestamp.to_datetime() >= expiration.to_datetime(),
+            _ => false,
+        }
+    }
+
+    /// Return whether the transaction has reached the given confirmation depth.
+    pub fn is_confirmed(&self, depth: ConfirmationDepth) -> bool {
+        match depth {
+            ConfirmationDepth::Blocks(n) => self.confirmations() >= n.max(1),
+            ConfirmationDepth::Irreversible => self.state == TransactionState::Irreversible,
+        }
+    }
+}
+
+
+/// Depth at which a transaction is considered confirmed, see [`ConfirmationWatcher`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfirmationDepth {
+    /// The transaction is part of a block, followed by at least `n - 1` other blocks.
+    /// `Blocks(1)` means that the transaction is part of a block.
+    Blocks(u32),
+    /// The transaction is part of an irreversible block
+    Irreversible,
+}
+
+
+/// Wait for a transaction to be confirmed by polling the `/v1/chain/get_transaction_status`
+/// endpoint, as returned by [`APIClient::watch_confirmation()`].
+///
+/// Transient errors while polling are ignored. Waiting fails if the transaction
+/// failed or expired without being included in a block, or if it is not confirmed
+/// before the timeout.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use kudu::{APIClient, TransactionId};
+/// # use kudu::api::{ConfirmationDepth, HttpError};
+/// # let id = TransactionId::from_hex("4ab8a7f3c3b5dd01b1c3c2bb5e4e2bc5e1e8a2e17d8fc2f1b6fc3f62e2d8cd1f").unwrap();
+/// let client = APIClient::jungle();
+/// let status = client.watch_confirmation(&id, ConfirmationDepth::Irreversible)
+///     .timeout(Duration::from_secs(300))
+///     .wait()?;
+/// println!("transaction is irreversible in block {:?}", status.block_number);
+/// # Ok::<(), HttpError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfirmationWatcher<'a> {
+    client: &'a APIClient,
+    id: TransactionId,
+    depth: ConfirmationDepth,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl<'a> ConfirmationWatcher<'a> {
+    pub fn new(client: &'a APIClient, id: &TransactionId, depth: ConfirmationDepth) -> Self {
+        ConfirmationWatcher {
+            client,
+            id: id.clone(),
+            depth,
+            timeout: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Set the maximum time to wait for, 60 seconds by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the delay between two status requests, 500 ms (one block) by default.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Wait until the transaction reaches the required depth and return its status.
+    pub fn wait(self) -> Result<TransactionStatus, HttpError> {
+        let start = Instant::now();
+        let transaction_id = self.id.to_hex();
+        loop {
+            match self.client.get_transaction_status(&self.id) {
+                Ok(status) if status.is_confirmed(self.depth) => return Ok(status),
+                Ok(status) if status.state == TransactionState::Failed => {
+                    return TransactionFailedSnafu { transaction_id, reason: "execution failed" }.fail();
+                },
+                Ok(status) if status.is_expired() => {
+                    return TransactionFailedSnafu { transaction_id, reason: "expired before being included in a block" }.fail();
+                },
+                Ok(status) => debug!("transaction {transaction_id} not confirmed yet: {} ({} confirmations)",
+                                     status.state, status.confirmations()),
+                Err(e) if e.is_transient() => warn!("could not get transaction status, retrying: {e}"),
+                Err(e) => return Err(e),
+            }
+
+            if start.elapsed() + self.poll_interval > self.timeout {
+                return ConfirmationTimeoutSnafu { transaction_id, timeout: self.timeout }.fail();
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
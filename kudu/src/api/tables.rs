@@ -0,0 +1,238 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::marker::PhantomData;
+
+use serde_json::{json, Value as JsonValue};
+use snafu::{Snafu, OptionExt, ResultExt};
+
+use kudu_macros::with_location;
+
+use crate::{APIClient, Bytes, SerializeError, TableName, TableRow, api::{HttpError, ReadMode, ReadResult}};
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum TableError {
+    #[snafu(display("could not fetch table `{table}` from node"))]
+    Fetch { table: TableName, source: HttpError },
+
+    #[snafu(display("invalid response for table `{table}`: {message}"))]
+    InvalidResponse { table: TableName, message: String },
+
+    #[snafu(display("cannot decode row from table `{table}`"))]
+    Decode { table: TableName, source: SerializeError },
+}
+
+
+/// Rows returned by a [`TableQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRows<T> {
+    pub rows: Vec<T>,
+    /// Whether there are more rows to be fetched after these ones
+    pub more: bool,
+    /// Lower bound to use to fetch the next rows, if there are more
+    pub next_key: String,
+}
+
+
+/// Builder for a `/v1/chain/get_table_rows` query returning typed rows, as
+/// returned by [`APIClient::table()`].
+///
+/// Rows are requested in binary form and decoded using their [`ABISerializable`]
+/// implementation, so no ABI is needed. The scope defaults to the account of the
+/// contract owning the table.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[cfg(feature = "contracts-system")] {
+/// # use kudu::{APIClient, contracts::system::ProducerInfo};
+/// let client = APIClient::vaulta();
+/// let producers = client.table::<ProducerInfo>().limit(50).fetch()?;
+/// for producer in producers.rows {
+///     println!("{}: {}", producer.owner, producer.total_votes);
+/// }
+/// # }
+/// # Ok::<(), kudu::api::TableError>(())
+/// ```
+///
+/// [`ABISerializable`]: crate::ABISerializable
+#[derive(Debug, Clone)]
+pub struct TableQuery<'a, T> {
+    client: &'a APIClient,
+    scope: Option<String>,
+    lower_bound: Option<String>,
+    upper_bound: Option<String>,
+    index_position: Option<String>,
+    key_type: Option<String>,
+    limit: u32,
+    reverse: bool,
+    read_mode: Option<ReadMode>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: TableRow> TableQuery<'a, T> {
+    pub fn new(client: &'a APIClient) -> Self {
+        TableQuery {
+            client,
+            scope: None,
+            lower_bound: None,
+            upper_bound: None,
+            index_position: None,
+            key_type: None,
+            limit: 10,
+            reverse: false,
+            read_mode: None,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn scope(mut self, scope: impl ToString) -> Self {
+        self.scope = Some(scope.to_string());
+        self
+    }
+
+    pub fn lower_bound(mut self, lower_bound: impl ToString) -> Self {
+        self.lower_bound = Some(lower_bound.to_string());
+        self
+    }
+
+    pub fn upper_bound(mut self, upper_bound: impl ToString) -> Self {
+        self.upper_bound = Some(upper_bound.to_string());
+        self
+    }
+
+    /// Set the lower bound of a query on a `float64` index, see [`TableQuery::upper_bound_f64()`].
+    pub fn lower_bound_f64(mut self, lower_bound: f64) -> Self {
+        self.lower_bound = Some(lower_bound.to_string());
+        self.key_type.get_or_insert_with(|| "float64".to_string());
+        self
+    }
+
+    /// Set the upper bound of a query on a `float64` index.
+    ///
+    /// The bound is formatted as the shortest decimal that `nodeos` parses back to
+    /// the exact same value, and the key type is set to `"float64"` if it has not
+    /// been set already (`"float128"` indices also take `f64` bounds). As bounds are
+    /// inclusive, use [`f64_to_ordered_bits()`] to compute the neighbouring values
+    /// of a key.
+    ///
+    /// [`f64_to_ordered_bits()`]: crate::f64_to_ordered_bits
+    pub fn upper_bound_f64(mut self, upper_bound: f64) -> Self {
+        self.upper_bound = Some(upper_bound.to_string());
+        self.key_type.get_or_insert_with(|| "float64".to_string());
+        self
+    }
+
+    /// Set the index to use for the query, eg: `"primary"`, `"secondary"` or `"2"`.
+    pub fn index_position(mut self, index_position: impl ToString) -> Self {
+        self.index_position = Some(index_position.to_string());
+        self
+    }
+
+    /// Set the type of the index keys, eg: `"name"`, `"i64"`, `"float64"`, `"sha256"`, etc.
+    pub fn key_type(mut self, key_type: impl ToString) -> Self {
+        self.key_type = Some(key_type.to_string());
+        self
+    }
+
+    /// Set the max number of rows to return (default: 10).
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Override the read mode of the client for this query, see [`ReadMode`].
+    pub fn read_mode(mut self, read_mode: ReadMode) -> Self {
+        self.read_mode = Some(read_mode);
+        self
+    }
+
+    /// Return the params of the `get_table_rows` request.
+    pub fn params(&self) -> JsonValue {
+        let mut params = json!({
+            "code": T::code().to_string(),
+            "scope": self.scope.clone().unwrap_or_else(|| T::code().to_string()),
+            "table": T::table().to_string(),
+            "json": false,
+            "limit": self.limit,
+            "reverse": self.reverse,
+        });
+        let optional = [
+            ("lower_bound", &self.lower_bound),
+            ("upper_bound", &self.upper_bound),
+            ("index_position", &self.index_position),
+            ("key_type", &self.key_type),
+        ];
+        for (field, value) in optional {
+            if let Some(value) = value {
+                params[field] = json!(value);
+            }
+        }
+        params
+    }
+
+    /// Execute the query and decode the rows.
+    ///
+    /// In [`ReadMode::Irreversible`], this first checks that the node serves
+    /// irreversible state. Use [`TableQuery::fetch_with_state()`] to also get the
+    /// block numbers of the chain state.
+    pub fn fetch(&self) -> Result<TableRows<T>, TableError> {
+        match self.effective_read_mode() {
+            ReadMode::Head => {
+                let response = self.client.call("/v1/chain/get_table_rows", &self.params())
+                    .context(FetchSnafu { table: T::table() })?;
+                self.decode(&response)
+            },
+            ReadMode::Irreversible => Ok(self.fetch_with_state()?.value),
+        }
+    }
+
+    /// Execute the query and decode the rows, returning them together with the
+    /// state of the chain on the node.
+    pub fn fetch_with_state(&self) -> Result<ReadResult<TableRows<T>>, TableError> {
+        let table = T::table();
+        let result = self.client.read_with_mode("/v1/chain/get_table_rows", &self.params(), self.effective_read_mode())
+            .context(FetchSnafu { table })?;
+        let rows = self.decode(&result.value)?;
+        Ok(result.map(|_| rows))
+    }
+
+    fn effective_read_mode(&self) -> ReadMode {
+        self.read_mode.unwrap_or_else(|| self.client.read_mode())
+    }
+
+    fn decode(&self, response: &JsonValue) -> Result<TableRows<T>, TableError> {
+        let table = T::table();
+
+        let rows = response["rows"].as_array()
+            .context(InvalidResponseSnafu { table, message: "missing `rows` array" })?;
+        let rows = rows.iter().map(|row| {
+            let row = row.as_str().with_context(|| InvalidResponseSnafu {
+                table, message: format!("expected hex string, got: {row}")
+            })?;
+            let data = Bytes::from_hex(row).map_err(|e| InvalidResponseSnafu {
+                table, message: e.to_string()
+            }.build())?;
+            crate::from_bin(&data).context(DecodeSnafu { table })
+        }).collect::<Result<_, _>>()?;
+
+        Ok(TableRows {
+            rows,
+            more: response["more"].as_bool().unwrap_or(false),
+            next_key: response["next_key"].as_str().unwrap_or_default().to_owned(),
+        })
+    }
+
+    /// Execute the query and return the first row, if any. This is useful for
+    /// singleton tables.
+    pub fn first(self) -> Result<Option<T>, TableError> {
+        Ok(self.limit(1).fetch()?.rows.into_iter().next())
+    }
+}
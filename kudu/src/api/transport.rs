@@ -0,0 +1,189 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+
+use serde_json::{json, Value as JsonValue};
+use snafu::ResultExt;
+
+use super::{ConnectionSnafu, HttpError, JsonSnafu};
+
+
+/// Response to a request sent through an [`ApiTransport`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: JsonValue,
+}
+
+impl ApiResponse {
+    pub fn ok(body: JsonValue) -> Self {
+        ApiResponse { status: 200, body }
+    }
+}
+
+/// Low-level transport used by an [`APIClient`] to send requests to a node.
+///
+/// The default implementation ([`HttpTransport`]) sends them over HTTP, but this
+/// can be replaced (eg: by a [`MockTransport`] in tests).
+///
+/// [`APIClient`]: super::APIClient
+pub trait ApiTransport: fmt::Debug + Send + Sync {
+    /// Send a request to the given path: a POST request with `body` as JSON if it
+    /// is given, a GET request otherwise.
+    ///
+    /// Responses with an HTTP error status should be returned as `Ok`, checking
+    /// the status is left to the caller.
+    fn send(&self, path: &str, body: Option<&JsonValue>) -> Result<ApiResponse, HttpError>;
+}
+
+
+// -----------------------------------------------------------------------------
+//     HTTP transport
+// -----------------------------------------------------------------------------
+
+/// Transport sending requests over HTTP, as built by an [`APIClientBuilder`].
+///
+/// [`APIClientBuilder`]: super::APIClientBuilder
+#[derive(Clone, Debug)]
+pub struct HttpTransport {
+    pub endpoint: String,
+    pub agent: ureq::Agent,
+    /// headers sent with each request
+    pub headers: Vec<(String, String)>,
+}
+
+impl ApiTransport for HttpTransport {
+    fn send(&self, path: &str, body: Option<&JsonValue>) -> Result<ApiResponse, HttpError> {
+        let url = format!("{}{}", &self.endpoint, path);
+        let mut response = match body {
+            Some(body) => {
+                let mut request = self.agent.post(url);
+                for (name, value) in &self.headers {
+                    request = request.header(name, value);
+                }
+                request.send_json(body)
+            },
+            None => {
+                let mut request = self.agent.get(url);
+                for (name, value) in &self.headers {
+                    request = request.header(name, value);
+                }
+                request.call()
+            },
+        }.context(ConnectionSnafu)?;
+
+        Ok(ApiResponse {
+            status: response.status().as_u16(),
+            body: response.body_mut().read_json().context(JsonSnafu)?,
+        })
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Mock transport
+// -----------------------------------------------------------------------------
+
+/// A request received by a [`MockTransport`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockRequest {
+    pub path: String,
+    /// JSON body of the request, `None` for GET requests
+    pub body: Option<JsonValue>,
+}
+
+/// Transport returning canned responses and recording the requests it receives,
+/// so that code using an [`APIClient`] can be tested without a running node.
+///
+/// Responses are registered for a given path and returned in order, the last one
+/// being repeated for all subsequent requests. Requests to a path without a
+/// registered response get a `404` error.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use kudu::{APIClient, json};
+/// # use kudu::api::{HttpError, MockTransport};
+/// let mock = Arc::new(MockTransport::new());
+/// mock.respond("/v1/chain/get_info", json!({"head_block_num": 42}));
+///
+/// let client = APIClient::with_transport("mock://", mock.clone());
+/// assert_eq!(client.get("/v1/chain/get_info")?["head_block_num"], 42);
+/// assert!(client.get("/v1/chain/get_block").is_err());
+///
+/// mock.assert_called("/v1/chain/get_info");
+/// # Ok::<(), HttpError>(())
+/// ```
+///
+/// [`APIClient`]: super::APIClient
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<HashMap<String, VecDeque<ApiResponse>>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a successful response with the given JSON body for requests to `path`.
+    pub fn respond(&self, path: &str, body: JsonValue) -> &Self {
+        self.respond_with(path, ApiResponse::ok(body))
+    }
+
+    /// Add a response for requests to `path`.
+    pub fn respond_with(&self, path: &str, response: ApiResponse) -> &Self {
+        self.responses.lock().unwrap_or_else(PoisonError::into_inner)
+            .entry(path.to_string()).or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Return all the requests received so far, in order.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Return the requests received so far for the given path, in order.
+    pub fn requests_to(&self, path: &str) -> Vec<MockRequest> {
+        self.requests().into_iter().filter(|r| r.path == path).collect()
+    }
+
+    /// Panic if no request has been received for the given path.
+    #[track_caller]
+    pub fn assert_called(&self, path: &str) {
+        assert!(!self.requests_to(path).is_empty(),
+                "no request received for path {path}, received: {:?}", self.requests());
+    }
+
+    /// Panic if no request with the given JSON body has been received for the given path.
+    #[track_caller]
+    pub fn assert_called_with(&self, path: &str, body: &JsonValue) {
+        let requests = self.requests_to(path);
+        assert!(requests.iter().any(|r| r.body.as_ref() == Some(body)),
+                "no request received for path {path} with body {body}, received: {requests:?}");
+    }
+}
+
+impl ApiTransport for MockTransport {
+    fn send(&self, path: &str, body: Option<&JsonValue>) -> Result<ApiResponse, HttpError> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner)
+            .push(MockRequest { path: path.to_string(), body: body.cloned() });
+
+        let mut responses = self.responses.lock().unwrap_or_else(PoisonError::into_inner);
+        let response = match responses.get_mut(path) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+        Ok(response.unwrap_or_else(|| ApiResponse {
+            status: 404,
+            body: json!({"error": format!("no mock response for path: {path}")}),
+        }))
+    }
+}
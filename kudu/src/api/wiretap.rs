@@ -0,0 +1,65 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value as JsonValue;
+
+use super::HttpError;
+
+
+/// A request about to be sent by an [`APIClient`], as given to its
+/// [`on_request`](super::APIClient::on_request) hook.
+///
+/// [`APIClient`]: super::APIClient
+#[derive(Clone, Debug)]
+pub struct WireRequest<'a> {
+    /// `GET` or `POST`
+    pub method: &'static str,
+    pub url: String,
+    /// JSON body of the request, `None` for `GET` requests
+    pub body: Option<&'a JsonValue>,
+}
+
+/// The response to a request sent by an [`APIClient`], as given to its
+/// [`on_response`](super::APIClient::on_response) hook.
+///
+/// [`APIClient`]: super::APIClient
+#[derive(Debug)]
+pub struct WireResponse<'a> {
+    pub request: &'a WireRequest<'a>,
+    /// HTTP status, `None` if no response could be received
+    pub status: Option<u16>,
+    /// JSON body of the response, `None` if no response could be received
+    pub body: Option<&'a JsonValue>,
+    /// Error that occurred while sending the request or reading the response
+    pub error: Option<&'a HttpError>,
+    /// Time elapsed between sending the request and receiving the response
+    pub elapsed: Duration,
+}
+
+pub type RequestHook = Arc<dyn Fn(&WireRequest) + Send + Sync>;
+pub type ResponseHook = Arc<dyn Fn(&WireResponse) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub(super) struct Hooks {
+    pub on_request: Option<RequestHook>,
+    pub on_response: Option<ResponseHook>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.on_request.is_none() && self.on_response.is_none()
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("on_request", &self.on_request.is_some())
+            .field("on_response", &self.on_response.is_some())
+            .finish()
+    }
+}
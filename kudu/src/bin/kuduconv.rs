@@ -1,15 +1,26 @@
 // SPDX-FileCopyrightText: 2025, 2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use std::sync::Arc;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
 
-use clap::{Parser, Subcommand};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use clap::{Parser, Subcommand, ValueEnum};
 use color_eyre::{Result, eyre::{eyre, OptionExt, WrapErr}};
-use serde_json::Value;
+use serde_json::{json, Value};
 
-use kudu::{abi, tracing_init, Bytes, ABI};
+use kudu::{
+    abi, abi::{Expander, ValueDiff, hash_packed_abi}, tracing_init,
+    APIClient, Bytes, ABI, ABIDefinition, ByteStream, Transaction,
+    chain::ship::{BlocksRequest, ShipBlock, ShipClient},
+};
 
 
 #[derive(Parser)]
@@ -55,6 +66,216 @@ enum Commands {
         /// an hex representation of the object we want to decode
         hex: String,
     },
+
+    /// Decode the return value of an action using the `action_results` of its ABI
+    DecodeResult {
+        /// the name of a preloaded ABI or a filename of an ABI to load
+        #[arg(short, long)]
+        abi: String,
+
+        /// the name of the action
+        action: String,
+
+        /// the hex representation of the return value, eg: the `return_value_hex_data`
+        /// field of an action trace
+        hex: String,
+    },
+
+    /// Describe the layout of a type, with all its fields and nested types
+    Explain {
+        /// the name of a preloaded ABI or a filename of an ABI to load
+        #[arg(short, long)]
+        abi: Option<String>,
+
+        /// output the description as JSON instead of a tree
+        #[arg(long)]
+        json: bool,
+
+        /// hex data to decode, showing which bytes correspond to each decoded value
+        #[arg(long)]
+        hex: Option<String>,
+
+        /// the typename (or action name) to describe
+        typename: String,
+    },
+
+    /// Operations on whole ABIs
+    Abi {
+        #[command(subcommand)]
+        command: AbiCommands,
+    },
+
+    /// Convert eosio.msig proposals to/from a human-readable form
+    Msig {
+        #[command(subcommand)]
+        command: MsigCommands,
+    },
+
+    /// Start an interactive session where conversions reuse the same ABI
+    Repl {
+        /// the name of a preloaded ABI or the filename of an ABI to load.
+        /// If not specified, a matching ABI will be looked for in the preloaded ones
+        /// for each conversion
+        #[arg(short, long)]
+        abi: Option<String>,
+    },
+
+    /// Run as a service answering JSON-RPC 2.0 requests, one per line
+    ///
+    /// The available methods are `to-hex` (params: `abi`, `typename`, `value`),
+    /// `to-json` (params: `abi`, `typename`, `hex`) and `abi-validate` (params: `abi`,
+    /// either an ABI definition or the name of a preloaded ABI or ABI file).
+    /// ABIs are only loaded once and kept for the lifetime of the service.
+    ///
+    /// The `abi` param can be the name of a preloaded ABI or, if `--abi-dir` is given,
+    /// the filename of an ABI inside that directory. No other file can be read.
+    Serve {
+        /// read requests from stdin and write responses to stdout
+        #[arg(long, conflicts_with = "listen", required_unless_present = "listen")]
+        stdio: bool,
+
+        /// listen for connections on the given address, eg: `127.0.0.1:9000`
+        #[arg(long)]
+        listen: Option<String>,
+
+        /// directory from which requests are allowed to load ABI files
+        #[arg(long)]
+        abi_dir: Option<PathBuf>,
+    },
+
+    /// Dump blocks from a state history (SHiP) endpoint as newline-delimited JSON
+    ///
+    /// Each line is an object with the `block_num`, the `type` of the data and the
+    /// decoded `data` itself. Blocks are only requested from the node as fast as
+    /// they can be written.
+    ShipDump {
+        /// websocket URL of the state history endpoint
+        #[arg(long, default_value = "ws://127.0.0.1:8080")]
+        url: String,
+
+        /// first block to dump
+        #[arg(long)]
+        from: u32,
+
+        /// last block to dump (included)
+        #[arg(long)]
+        to: u32,
+
+        /// types of data to dump, comma-separated
+        #[arg(long, value_delimiter = ',', default_value = "transaction_trace")]
+        types: Vec<ShipType>,
+
+        /// only dump irreversible blocks
+        #[arg(long)]
+        irreversible_only: bool,
+
+        /// write to the given file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// Typenames are completed dynamically using the ABI given with `--abi`, eg,
+    /// for bash: `source <(kuduconv completions bash)`
+    Completions {
+        shell: Shell,
+    },
+
+    /// List the types and actions that can be converted, used for shell completion
+    #[command(hide = true)]
+    ListTypes {
+        #[arg(short, long)]
+        abi: Option<String>,
+    },
+
+    /// List the names of the preloaded ABIs, used for shell completion
+    #[command(hide = true)]
+    ListAbis,
+}
+
+#[derive(Subcommand, Debug)]
+enum AbiCommands {
+    /// Check that the ABI deployed on an account matches a local ABI file
+    ///
+    /// The hash of the binary ABI on chain is compared to the one of the local file.
+    /// If they differ, the differences between both ABIs are shown and the command
+    /// exits with an error.
+    Verify {
+        /// the account on which the contract is deployed
+        #[arg(long)]
+        account: String,
+
+        /// the local ABI file
+        #[arg(long)]
+        file: String,
+
+        /// the URL of the API endpoint of the chain
+        #[arg(long)]
+        url: String,
+    },
+
+    /// Show which types of an ABI reference which other types
+    ///
+    /// Types that are not used by any action or table, directly or indirectly,
+    /// are listed as unused. With `--dot`, the output can be rendered with Graphviz,
+    /// eg: `kuduconv abi graph --abi eosio --dot | dot -Tsvg > eosio.svg`
+    Graph {
+        /// the name of a preloaded ABI or a filename of an ABI to load
+        #[arg(short, long)]
+        abi: String,
+
+        /// output the graph in the DOT language of Graphviz
+        #[arg(long, conflicts_with = "json")]
+        dot: bool,
+
+        /// output the graph as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum MsigCommands {
+    /// Pack a transaction given as JSON, with decoded action data, into the hex of
+    /// the `packed_transaction` field of an eosio.msig proposal
+    PackProposal {
+        /// ABI to use for the actions of a contract, as `ACCOUNT=FILE`, in addition
+        /// to the preloaded ones. Can be given multiple times
+        #[arg(short, long = "abi", value_name = "ACCOUNT=FILE")]
+        abis: Vec<String>,
+
+        /// the JSON representation of the proposed transaction
+        json: String,
+    },
+
+    /// Decode the packed transaction of an eosio.msig proposal as JSON, also
+    /// decoding the data of its actions when their ABI is known
+    UnpackProposal {
+        /// ABI to use for the actions of a contract, as `ACCOUNT=FILE`, in addition
+        /// to the preloaded ones. Can be given multiple times
+        #[arg(short, long = "abi", value_name = "ACCOUNT=FILE")]
+        abis: Vec<String>,
+
+        /// the hex of the `packed_transaction` field of a proposal, or the JSON of a
+        /// whole proposal row as returned by `get_table_rows`
+        proposal: String,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[value(rename_all = "snake_case")]
+enum ShipType {
+    Block,
+    TransactionTrace,
+    TableDelta,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
 }
 
 /// Return an `ABI` object given its name or filename. If none is given, try to find one that
@@ -64,8 +285,7 @@ fn get_abi(abi_name: Option<String>, typename: &str) -> Result<Arc<ABI>> {
         // if abi_name is an existing file, load it
         // do this first to avoid pre-loading ABIs in the registry if that is not needed
         if Path::new(&abi_name).is_file() {
-            let abi_str = fs::read_to_string(&abi_name).unwrap();  // safe unwrap
-            return Ok(Arc::new(ABI::from_str(&abi_str)?))
+            return load_abi_file(Path::new(&abi_name));
         }
 
         // if it isn't a file, try to look for a pre-loaded ABI in our registry with that name
@@ -83,6 +303,681 @@ fn get_abi(abi_name: Option<String>, typename: &str) -> Result<Arc<ABI>> {
     }
 }
 
+/// Load an ABI from a JSON file.
+fn load_abi_file(path: &Path) -> Result<Arc<ABI>> {
+    let abi_str = fs::read_to_string(path)
+        .wrap_err_with(|| format!("Could not read ABI file: {}", path.display()))?;
+    // ABI files written by users or CDT are not always strict JSON
+    Ok(Arc::new(ABI::from_definition(&ABIDefinition::from_str_lenient(&abi_str)?)?))
+}
+
+/// Return the type to use for converting `typename`, which can also be the name
+/// of an action defined in the ABI.
+fn resolve_typename(abi: &ABI, typename: &str) -> String {
+    match abi.has_struct(typename) {
+        true => typename.to_string(),
+        false => abi.actions()
+            .find(|a| a.name == typename)
+            .map_or_else(|| typename.to_string(), |a| a.type_.as_str().to_string()),
+    }
+}
+
+fn to_hex(abi: &ABI, typename: &str, json: &str) -> Result<String> {
+    value_to_hex(abi, typename, &kudu::json::parse_strict(json)?)
+}
+
+fn value_to_hex(abi: &ABI, typename: &str, value: &Value) -> Result<String> {
+    // create a byte stream for storing the bin representation
+    let mut ds = Bytes::new();
+
+    // perform the json->hex conversion
+    abi.encode_variant(&mut ds, resolve_typename(abi, typename).as_str(), value)?;
+
+    Ok(ds.to_hex())
+}
+
+fn from_hex(abi: &ABI, typename: &str, hex: &str) -> Result<Value> {
+    // create a byte stream from the given hex representation
+    let bin = Bytes::from_hex(hex)?;
+    let mut view = bin.view();
+
+    // perform the hex->json conversion
+    let v = abi.decode_variant(&mut view, resolve_typename(abi, typename).as_str())?;
+
+    if !view.leftover().is_empty() {
+        return Err(eyre!("Trailing input, {} bytes haven't been consumed. Decoded object: {:?}",
+                         view.leftover().len(), &v));
+    }
+
+    Ok(v)
+}
+
+fn decode_result(abi: &ABI, action: &str, hex: &str) -> Result<Value> {
+    let action = action.parse().wrap_err_with(|| format!("Invalid action name: {action}"))?;
+    Ok(abi.decode_action_result(action, &Bytes::from_hex(hex)?)?)
+}
+
+fn explain(abi: &ABI, typename: &str, json: bool) -> Result<String> {
+    let description = abi.describe(resolve_typename(abi, typename).as_str())?;
+    Ok(match json {
+        true => serde_json::to_string_pretty(&description)?,
+        false => description.to_string().trim_end().to_string(),
+    })
+}
+
+/// Decode the given hex data and list the bytes corresponding to each decoded value.
+fn explain_data(abi: &ABI, typename: &str, hex: &str, json: bool) -> Result<String> {
+    let bin = Bytes::from_hex(hex)?;
+    let mut view = bin.view();
+    let (value, offsets) = abi.decode_variant_with_offsets(&mut view, resolve_typename(abi, typename).as_str())?;
+    let trailing = view.leftover().len();
+
+    if json {
+        return Ok(serde_json::to_string_pretty(&serde_json::json!({"value": value, "offsets": offsets}))?);
+    }
+
+    // list values in the order they appear in the input, parents before their children
+    let mut offsets: Vec<_> = offsets.into_iter().collect();
+    offsets.sort_by_key(|(pointer, span)| (span.offset, pointer.len()));
+
+    let width = offsets.iter().map(|(pointer, _)| pointer.len())
+        .chain((trailing > 0).then_some("(trailing)".len()))
+        .max().unwrap_or(0).max(1);
+    let lines: Vec<_> = offsets.iter().map(|(pointer, span)| {
+        let pointer = if pointer.is_empty() { "/" } else { pointer };
+        let value = value.pointer(pointer).filter(|v| !v.is_object() && !v.is_array());
+        let value = value.map(|v| format!("  {v}")).unwrap_or_default();
+        format!("{:<12} {pointer:<width$}  {}{value}", span.to_string(), hex::encode(&bin[span.range()]))
+    }).collect();
+    let mut result = lines.join("\n");
+    if trailing > 0 {
+        let span = format!("{}..{}", bin.len() - trailing, bin.len());
+        result += &format!("\n{span:<12} {:<width$}  {}", "(trailing)", hex::encode(view.leftover()));
+    }
+    Ok(result)
+}
+
+/// Compare a local ABI with the binary ABI deployed on chain, and return their
+/// differences if their hashes do not match.
+fn compare_abis(local: &ABIDefinition, deployed: &[u8]) -> Result<Option<ValueDiff>> {
+    if local.hash()? == hash_packed_abi(deployed) {
+        return Ok(None);
+    }
+
+    let deployed = ABIDefinition::decode(&mut ByteStream::from(deployed))
+        .wrap_err("Could not decode the ABI deployed on chain")?;
+    Ok(Some(ValueDiff::new(json!(local), json!(deployed), Bytes::new(), Bytes::new())))
+}
+
+fn verify_abi(account: &str, file: &str, url: &str) -> Result<()> {
+    let local = fs::read_to_string(file).wrap_err_with(|| format!("Could not read ABI file: {file}"))?;
+    let local = ABIDefinition::from_str_lenient(&local)?;
+
+    let client = APIClient::new(url);
+    let result = client.call("/v1/chain/get_raw_abi", &json!({"account_name": account}))?;
+    let raw_abi = result["abi"].as_str().ok_or_eyre("Invalid response from get_raw_abi")?;
+    let deployed = STANDARD_NO_PAD.decode(raw_abi.trim_end_matches('='))?;
+    if deployed.is_empty() {
+        return Err(eyre!("There is no ABI deployed on account: {account}"));
+    }
+
+    match compare_abis(&local, &deployed)? {
+        None => {
+            println!("ABI deployed on {account} matches {file} (hash: {})", hash_packed_abi(&deployed).to_hex());
+            Ok(())
+        },
+        Some(diff) => {
+            if diff.is_empty() {
+                println!("Both ABIs define the same contents but their binary encodings differ");
+            }
+            else {
+                println!("Differences between {file} (a) and the ABI deployed on {account} (b):");
+                print!("{diff}");
+            }
+            Err(eyre!("ABI deployed on {account} does not match {file}"))
+        },
+    }
+}
+
+/// Return the dependency graph of the given ABI as a list of types with the types
+/// they reference, followed by the list of unused types.
+fn abi_graph(abi: &ABI, dot: bool, json: bool) -> Result<String> {
+    let graph = abi.dependency_graph();
+    if dot {
+        return Ok(graph.to_dot().trim_end().to_string());
+    }
+    if json {
+        return Ok(serde_json::to_string_pretty(&json!({
+            "graph": graph,
+            "unused": graph.unused_types(),
+        }))?);
+    }
+
+    let mut lines: Vec<_> = graph.types.iter().map(|(name, kind)| {
+        let mut line = format!("{name}: {}", format!("{kind:?}").to_lowercase());
+        if graph.roots.contains(name) {
+            line += " (root)";
+        }
+        let deps: Vec<_> = graph.dependencies(name).collect();
+        if !deps.is_empty() {
+            line += &format!(" -> {}", deps.join(", "));
+        }
+        line
+    }).collect();
+    let unused = graph.unused_types();
+    if !unused.is_empty() {
+        lines.push(format!("\nunused types: {}", unused.join(", ")));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Load the ABIs given as `ACCOUNT=FILE` in the registry, so that they are used
+/// to encode and decode the data of the actions of these accounts.
+fn load_contract_abis(abis: &[String]) -> Result<()> {
+    for spec in abis {
+        let (account, file) = spec.split_once('=')
+            .ok_or_else(|| eyre!("Invalid ABI specification, expected ACCOUNT=FILE: {spec}"))?;
+        let abi = fs::read_to_string(file).wrap_err_with(|| format!("Could not read ABI file: {file}"))?;
+        let abi = ABIDefinition::from_str_lenient(&abi)?;
+        abi::registry::load_abi(account, &serde_json::to_string(&abi)?)?;
+    }
+    Ok(())
+}
+
+fn pack_proposal(json: &str) -> Result<String> {
+    let tx = Transaction::from_json_str(json)?;
+    Ok(kudu::to_hex(&tx))
+}
+
+fn unpack_proposal(proposal: &str) -> Result<Value> {
+    // accept either the hex of the packed transaction or the whole proposal row
+    let proposal = proposal.trim();
+    let hex = match proposal.starts_with('{') {
+        true => {
+            let row: Value = proposal.parse()?;
+            row["packed_transaction"].as_str()
+                .ok_or_eyre("Missing `packed_transaction` field in proposal")?
+                .to_string()
+        },
+        false => proposal.to_string(),
+    };
+    let tx = abi::registry::get_abi("transaction")?.binary_to_variant("transaction", Bytes::from_hex(hex)?)?;
+
+    // action data is left as hex for the contracts with no known ABI
+    Ok(Expander::new()
+       .action_data("actions.*.data")
+       .action_data("context_free_actions.*.data")
+       .expanded(tx)?)
+}
+
+/// Return the names of all the actions and types defined in the given ABI.
+fn abi_types(abi: &ABI) -> Vec<String> {
+    let mut result: Vec<_> = abi.actions().map(|a| a.name.to_string())
+        .chain(abi.structs().map(|s| s.name.clone()))
+        .chain(abi.variants().map(|v| v.name.clone()))
+        .chain(abi.typedefs().map(|(t, _)| t.as_str().to_string()))
+        .collect();
+    result.sort();
+    result.dedup();
+    result
+}
+
+/// Return the names of all the actions and types defined in the given ABI or, if
+/// none is given, in all the preloaded ABIs.
+fn list_types(abi_name: Option<String>) -> Result<Vec<String>> {
+    let abis = match abi_name {
+        Some(_) => vec![get_abi(abi_name, "")?],
+        None => abi::registry::abi_names().iter()
+            .map(|name| abi::registry::get_abi(name))
+            .collect::<Result<_, _>>()?,
+    };
+
+    let mut result: Vec<_> = abis.iter().flat_map(|abi| abi_types(abi)).collect();
+    result.sort();
+    result.dedup();
+    Ok(result)
+}
+
+
+// =============================================================================
+//
+//     Interactive mode
+//
+// =============================================================================
+
+const REPL_HELP: &str = "\
+Available commands:
+  to-hex <typename> <json>    convert a JSON object to its hex representation
+  from-hex <typename> <hex>   decode hex data as a JSON object
+  explain <typename>          describe the layout of a type
+  abi [<name or filename>]    set the ABI to use (or unset it if no name is given)
+  types                       list the types and actions of the current ABI
+  help                        show this message
+  quit                        exit the interactive session";
+
+fn repl(abi_name: Option<String>) -> Result<()> {
+    let mut abi = abi_name.map(|name| get_abi(Some(name), "")).transpose()?;
+    let interactive = io::stdin().is_terminal();
+
+    if interactive {
+        println!("kuduconv {} - type `help` for a list of commands", kudu::config::VERSION);
+    }
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("kuduconv> ");
+            io::stdout().flush()?;
+        }
+        let Some(line) = lines.next() else { break; };
+        let line = line?;
+        let line = line.trim();
+
+        let (cmd, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args = args.trim();
+        let split_args = || {
+            args.split_once(char::is_whitespace)
+                .map(|(typename, data)| (typename, data.trim()))
+                .ok_or_eyre("Needs 2 arguments: typename and data to convert")
+        };
+        let current_abi = |abi: &Option<Arc<ABI>>, typename: &str| match abi {
+            Some(abi) => Ok(abi.clone()),
+            None => get_abi(None, typename),
+        };
+
+        let result: Result<()> = match cmd {
+            "" => Ok(()),
+            "quit" | "exit" => break,
+            "help" => { println!("{REPL_HELP}"); Ok(()) },
+            "abi" if args.is_empty() => { abi = None; Ok(()) },
+            "abi" => get_abi(Some(args.to_string()), "").map(|a| { abi = Some(a); }),
+            "types" => {
+                let types = match &abi {
+                    Some(abi) => Ok(abi_types(abi)),
+                    None => list_types(None),
+                };
+                types.map(|types| println!("{}", types.join(" ")))
+            },
+            "to-hex" => split_args().and_then(|(typename, json)| {
+                let abi: Arc<ABI> = current_abi(&abi, typename)?;
+                println!("{}", to_hex(&abi, typename, json)?);
+                Ok(())
+            }),
+            "from-hex" => split_args().and_then(|(typename, hex)| {
+                let abi: Arc<ABI> = current_abi(&abi, typename)?;
+                println!("{}", from_hex(&abi, typename, hex)?);
+                Ok(())
+            }),
+            "explain" => current_abi(&abi, args).and_then(|abi| {
+                println!("{}", explain(&abi, args, false)?);
+                Ok(())
+            }),
+            _ => Err(eyre!("Unknown command: `{cmd}`, type `help` for a list of commands")),
+        };
+
+        // errors are displayed but do not end the session
+        if let Err(e) = result {
+            eprintln!("Error: {e:#}");
+        }
+    }
+
+    Ok(())
+}
+
+
+// =============================================================================
+//
+//     JSON-RPC service
+//
+// =============================================================================
+
+// see: https://www.jsonrpc.org/specification#error_object
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const CONVERSION_ERROR: i64 = -32000;
+
+/// ABIs loaded by the service, indexed by the name or filename used to load them.
+///
+/// As the names come from the clients, files are only loaded if they are inside
+/// `abi_dir`, otherwise only the ABIs preloaded in the registry can be used.
+struct AbiCache {
+    abis: Mutex<HashMap<String, Arc<ABI>>>,
+    abi_dir: Option<PathBuf>,
+}
+
+impl AbiCache {
+    fn new(abi_dir: Option<PathBuf>) -> Result<Self> {
+        let abi_dir = abi_dir.map(|dir| dir.canonicalize()
+            .wrap_err_with(|| format!("Invalid ABI directory: {}", dir.display())))
+            .transpose()?;
+        Ok(AbiCache { abis: Mutex::default(), abi_dir })
+    }
+
+    fn get(&self, abi_name: Option<&str>, typename: &str) -> Result<Arc<ABI>> {
+        let Some(abi_name) = abi_name else {
+            return get_abi(None, typename);
+        };
+        if let Some(abi) = self.abis.lock().unwrap_or_else(PoisonError::into_inner).get(abi_name) {
+            return Ok(abi.clone());
+        }
+        let abi = self.load(abi_name)?;
+        self.abis.lock().unwrap_or_else(PoisonError::into_inner).insert(abi_name.to_string(), abi.clone());
+        Ok(abi)
+    }
+
+    fn load(&self, abi_name: &str) -> Result<Arc<ABI>> {
+        if let Ok(abi) = abi::registry::get_abi(abi_name) {
+            return Ok(abi);
+        }
+        // resolve symlinks and `..` before checking that the file is inside `abi_dir`
+        let path = self.abi_dir.as_ref()
+            .and_then(|dir| dir.join(abi_name).canonicalize().ok().filter(|path| path.starts_with(dir)))
+            .filter(|path| path.is_file())
+            .ok_or_else(|| eyre!("Could not find ABI with name: {}", abi_name))?;
+        load_abi_file(&path)
+    }
+}
+
+/// Handle a single JSON-RPC request and return its response, or `None` if it
+/// is a notification.
+fn handle_rpc_request(cache: &AbiCache, request: &str) -> Option<Value> {
+    let error = |id: &Value, code: i64, message: String| Some(json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message},
+    }));
+
+    let request = match kudu::json::parse_strict(request) {
+        Ok(request) => request,
+        Err(e) => return error(&Value::Null, PARSE_ERROR, e.to_string()),
+    };
+    let Some(method) = request["method"].as_str() else {
+        return error(request.get("id").unwrap_or(&Value::Null), INVALID_REQUEST, "missing method".to_string());
+    };
+    let params = request.get("params").unwrap_or(&Value::Null);
+
+    let result = match method {
+        "to-hex" | "to-json" | "abi-validate" => rpc_call(cache, method, params),
+        _ => Err(RpcError::MethodNotFound(format!("unknown method: `{method}`"))),
+    };
+
+    let id = request.get("id")?;  // notifications do not get a response
+    match result {
+        Ok(result) => Some(json!({"jsonrpc": "2.0", "id": id, "result": result})),
+        Err(RpcError::MethodNotFound(message)) => error(id, METHOD_NOT_FOUND, message),
+        Err(RpcError::InvalidParams(message)) => error(id, INVALID_PARAMS, message),
+        Err(RpcError::Conversion(e)) => error(id, CONVERSION_ERROR, format!("{e:#}")),
+    }
+}
+
+enum RpcError {
+    MethodNotFound(String),
+    InvalidParams(String),
+    Conversion(color_eyre::Report),
+}
+
+impl From<color_eyre::Report> for RpcError {
+    fn from(e: color_eyre::Report) -> Self {
+        RpcError::Conversion(e)
+    }
+}
+
+fn rpc_call(cache: &AbiCache, method: &str, params: &Value) -> Result<Value, RpcError> {
+    let param = |name: &str| params.get(name).ok_or_else(|| {
+        RpcError::InvalidParams(format!("missing param: `{name}`"))
+    });
+    let str_param = |name: &str| param(name)?.as_str().ok_or_else(|| {
+        RpcError::InvalidParams(format!("param `{name}` should be a string"))
+    });
+    let abi_name = || match params.get("abi") {
+        None => Ok(None),
+        Some(_) => str_param("abi").map(Some),
+    };
+
+    Ok(match method {
+        "to-hex" => {
+            let (typename, value) = (str_param("typename")?, param("value")?);
+            let abi = cache.get(abi_name()?, typename)?;
+            json!(value_to_hex(&abi, typename, value)?)
+        },
+        "to-json" => {
+            let (typename, hex) = (str_param("typename")?, str_param("hex")?);
+            let abi = cache.get(abi_name()?, typename)?;
+            from_hex(&abi, typename, hex)?
+        },
+        "abi-validate" => {
+            let result = match param("abi")? {
+                Value::String(name) => cache.get(Some(name), "").map(|_| ()),
+                def => ABIDefinition::from_variant(def)
+                    .and_then(|def| ABI::from_definition(&def))
+                    .map(|_| ()).map_err(Into::into),
+            };
+            match result {
+                Ok(()) => json!({"valid": true}),
+                Err(e) => json!({"valid": false, "error": format!("{e:#}")}),
+            }
+        },
+        _ => unreachable!(),
+    })
+}
+
+/// Answer the requests read from `input`, one per line, until the end of the input.
+fn serve_lines(cache: &AbiCache, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_rpc_request(cache, &line) {
+            writeln!(output, "{response}")?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+fn serve(listen: Option<String>, abi_dir: Option<PathBuf>) -> Result<()> {
+    let cache = Arc::new(AbiCache::new(abi_dir)?);
+    let Some(addr) = listen else {
+        return serve_lines(&cache, io::stdin().lock(), io::stdout().lock());
+    };
+
+    let listener = TcpListener::bind(&addr).wrap_err_with(|| format!("Could not listen on: {addr}"))?;
+    eprintln!("kuduconv listening on {}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        // a failed connection should not stop the service
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Error: could not accept connection: {e}");
+                continue;
+            },
+        };
+        let cache = cache.clone();
+        thread::spawn(move || {
+            let input = BufReader::new(stream.try_clone()?);
+            serve_lines(&cache, input, stream)
+        });
+    }
+    Ok(())
+}
+
+
+// =============================================================================
+//
+//     SHiP dump
+//
+// =============================================================================
+
+/// Write the contents of a block from a state history endpoint as NDJSON lines.
+fn write_ship_block(out: &mut impl Write, block: &ShipBlock, types: &[ShipType]) -> Result<()> {
+    let mut write_line = |type_: &str, data: &Value| -> Result<()> {
+        serde_json::to_writer(&mut *out, &json!({"block_num": block.block_num, "type": type_, "data": data}))?;
+        Ok(writeln!(out)?)
+    };
+
+    if types.contains(&ShipType::Block) && let Some(data) = &block.block {
+        write_line("block", data)?;
+    }
+    if types.contains(&ShipType::TransactionTrace) {
+        for trace in &block.traces {
+            write_line("transaction_trace", trace)?;
+        }
+    }
+    if types.contains(&ShipType::TableDelta) {
+        for delta in &block.deltas {
+            write_line("table_delta", delta)?;
+        }
+    }
+    Ok(())
+}
+
+fn ship_dump(url: &str, range: RangeInclusive<u32>, types: &[ShipType],
+             irreversible_only: bool, output: Option<String>) -> Result<()> {
+    let out: Box<dyn Write> = match output {
+        Some(path) => Box::new(fs::File::create(&path).wrap_err_with(|| format!("Could not create file: {path}"))?),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut out = io::BufWriter::new(out);
+
+    let request = BlocksRequest {
+        irreversible_only,
+        fetch_block: types.contains(&ShipType::Block),
+        fetch_traces: types.contains(&ShipType::TransactionTrace),
+        fetch_deltas: types.contains(&ShipType::TableDelta),
+        ..BlocksRequest::new(*range.start()..range.end().saturating_add(1))
+    };
+
+    let mut client = ShipClient::connect(url)?;
+    for block in client.blocks(&request)? {
+        write_ship_block(&mut out, &block?, types)?;
+        // flush before requesting the next block, so that a slow consumer slows
+        // down the node instead of having blocks accumulate in memory
+        out.flush()?;
+    }
+    Ok(())
+}
+
+
+// =============================================================================
+//
+//     Shell completion
+//
+// =============================================================================
+
+const BASH_COMPLETION: &str = r#"
+_kuduconv() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    if [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=( $(compgen -W "to-hex from-hex decode-result explain abi msig repl serve ship-dump completions help --help --version" -- "$cur") )
+        return
+    fi
+
+    case "$prev" in
+        -a|--abi)
+            COMPREPLY=( $(compgen -W "$(kuduconv list-abis 2>/dev/null)" -- "$cur") $(compgen -f -- "$cur") )
+            return ;;
+    esac
+
+    case "${COMP_WORDS[1]}" in
+        completions)
+            COMPREPLY=( $(compgen -W "bash zsh fish" -- "$cur") ) ;;
+        abi)
+            if [[ $COMP_CWORD -eq 2 ]]; then
+                COMPREPLY=( $(compgen -W "verify graph" -- "$cur") )
+            elif [[ "${COMP_WORDS[2]}" == "graph" ]]; then
+                COMPREPLY=( $(compgen -W "--abi --dot --json" -- "$cur") )
+            else
+                COMPREPLY=( $(compgen -W "--account --file --url" -- "$cur") $(compgen -f -- "$cur") )
+            fi ;;
+        msig)
+            if [[ $COMP_CWORD -eq 2 ]]; then
+                COMPREPLY=( $(compgen -W "pack-proposal unpack-proposal" -- "$cur") )
+            else
+                COMPREPLY=( $(compgen -W "--abi" -- "$cur") )
+            fi ;;
+        to-hex|from-hex|decode-result|explain)
+            # complete the typename using the ABI given with `--abi`, if any
+            local abi_args=() i
+            for (( i=2; i < COMP_CWORD; i++ )); do
+                if [[ "${COMP_WORDS[i]}" == "-a" || "${COMP_WORDS[i]}" == "--abi" ]]; then
+                    abi_args=(--abi "${COMP_WORDS[i+1]}")
+                fi
+            done
+            COMPREPLY=( $(compgen -W "--abi $(kuduconv list-types "${abi_args[@]}" 2>/dev/null)" -- "$cur") ) ;;
+        repl)
+            COMPREPLY=( $(compgen -W "--abi" -- "$cur") ) ;;
+        serve)
+            COMPREPLY=( $(compgen -W "--stdio --listen --abi-dir" -- "$cur") ) ;;
+        ship-dump)
+            COMPREPLY=( $(compgen -W "--url --from --to --types --irreversible-only --output" -- "$cur") ) ;;
+    esac
+}
+complete -F _kuduconv kuduconv
+"#;
+
+const FISH_COMPLETION: &str = r#"
+function __kuduconv_types
+    set -l tokens (commandline -opc)
+    set -l abi_args
+    for i in (seq (count $tokens))
+        if contains -- $tokens[$i] -a --abi
+            set abi_args --abi $tokens[(math $i + 1)]
+        end
+    end
+    kuduconv list-types $abi_args 2>/dev/null
+end
+
+complete -c kuduconv -f
+complete -c kuduconv -n __fish_use_subcommand -a to-hex -d 'Convert a JSON object to its hex representation'
+complete -c kuduconv -n __fish_use_subcommand -a from-hex -d 'Decode hex data as a JSON object'
+complete -c kuduconv -n __fish_use_subcommand -a decode-result -d 'Decode the return value of an action'
+complete -c kuduconv -n __fish_use_subcommand -a explain -d 'Describe the layout of a type'
+complete -c kuduconv -n __fish_use_subcommand -a abi -d 'Operations on whole ABIs'
+complete -c kuduconv -n __fish_use_subcommand -a msig -d 'Convert eosio.msig proposals'
+complete -c kuduconv -n __fish_use_subcommand -a repl -d 'Start an interactive session'
+complete -c kuduconv -n __fish_use_subcommand -a serve -d 'Run as a JSON-RPC service'
+complete -c kuduconv -n __fish_use_subcommand -a ship-dump -d 'Dump blocks from a SHiP endpoint as NDJSON'
+complete -c kuduconv -n __fish_use_subcommand -a completions -d 'Print a shell completion script'
+complete -c kuduconv -n '__fish_seen_subcommand_from to-hex from-hex decode-result explain repl' -s a -l abi -r -F -a '(kuduconv list-abis 2>/dev/null)' -d 'ABI name or filename'
+complete -c kuduconv -n '__fish_seen_subcommand_from to-hex from-hex decode-result explain' -a '(__kuduconv_types)'
+complete -c kuduconv -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish'
+complete -c kuduconv -n '__fish_seen_subcommand_from serve' -l stdio -d 'Serve requests on stdin/stdout'
+complete -c kuduconv -n '__fish_seen_subcommand_from serve' -l listen -r -d 'Address to listen on'
+complete -c kuduconv -n '__fish_seen_subcommand_from serve' -l abi-dir -r -a '(__fish_complete_directories)' -d 'Directory of the ABI files that can be loaded'
+complete -c kuduconv -n '__fish_seen_subcommand_from ship-dump' -l url -r -d 'State history websocket URL'
+complete -c kuduconv -n '__fish_seen_subcommand_from ship-dump' -l from -r -d 'First block'
+complete -c kuduconv -n '__fish_seen_subcommand_from ship-dump' -l to -r -d 'Last block'
+complete -c kuduconv -n '__fish_seen_subcommand_from ship-dump' -l types -r -a 'block transaction_trace table_delta' -d 'Types of data to dump'
+complete -c kuduconv -n '__fish_seen_subcommand_from ship-dump' -l irreversible-only -d 'Only dump irreversible blocks'
+complete -c kuduconv -n '__fish_seen_subcommand_from ship-dump' -s o -l output -r -F -d 'Output file'
+complete -c kuduconv -n '__fish_seen_subcommand_from abi; and not __fish_seen_subcommand_from verify graph' -a verify -d 'Check that a deployed ABI matches a local file'
+complete -c kuduconv -n '__fish_seen_subcommand_from abi; and not __fish_seen_subcommand_from verify graph' -a graph -d 'Show the dependencies between the types of an ABI'
+complete -c kuduconv -n '__fish_seen_subcommand_from graph' -s a -l abi -r -F -a '(kuduconv list-abis 2>/dev/null)' -d 'ABI name or filename'
+complete -c kuduconv -n '__fish_seen_subcommand_from graph' -l dot -d 'Output in the DOT language'
+complete -c kuduconv -n '__fish_seen_subcommand_from graph' -l json -d 'Output as JSON'
+complete -c kuduconv -n '__fish_seen_subcommand_from msig; and not __fish_seen_subcommand_from pack-proposal unpack-proposal' -a pack-proposal -d 'Pack a proposed transaction'
+complete -c kuduconv -n '__fish_seen_subcommand_from msig; and not __fish_seen_subcommand_from pack-proposal unpack-proposal' -a unpack-proposal -d 'Decode a proposed transaction'
+complete -c kuduconv -n '__fish_seen_subcommand_from pack-proposal unpack-proposal' -s a -l abi -r -d 'ABI of a contract, as ACCOUNT=FILE'
+complete -c kuduconv -n '__fish_seen_subcommand_from verify' -l account -r -d 'Account of the contract'
+complete -c kuduconv -n '__fish_seen_subcommand_from verify' -l file -r -F -d 'Local ABI file'
+complete -c kuduconv -n '__fish_seen_subcommand_from verify' -l url -r -d 'API endpoint URL'
+"#;
+
+fn completion_script(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => BASH_COMPLETION.trim_start().to_string(),
+        // zsh can reuse the bash completion function
+        Shell::Zsh => format!("autoload -U +X bashcompinit && bashcompinit\n{}", BASH_COMPLETION.trim_start()),
+        Shell::Fish => FISH_COMPLETION.trim_start().to_string(),
+    }
+}
+
+
 pub fn main() -> Result<()> {
     color_eyre::install()?;
     tracing_init();
@@ -94,35 +989,69 @@ pub fn main() -> Result<()> {
     match cmd {
         Commands::ToHex { abi, typename, json } => {
             let abi = get_abi(abi, &typename)?;
+            println!("{}", to_hex(&abi, &typename, &json)?);
+        }
 
-            // create a byte stream for storing the bin representation
-            let mut ds = Bytes::new();
-
-            // perform the json->hex conversion
-            let v: Value = json.parse()?;
-            abi.encode_variant(&mut ds, &typename,  &v)?;
+        Commands::FromHex { abi, typename, hex } => {
+            let abi = get_abi(abi, &typename)?;
+            println!("{}", from_hex(&abi, &typename, &hex)?);
+        }
 
-            println!("{}", ds.to_hex());
+        Commands::DecodeResult { abi, action, hex } => {
+            let abi = get_abi(Some(abi), "")?;
+            println!("{}", decode_result(&abi, &action, &hex)?);
         }
 
-        Commands::FromHex { abi, typename, hex } => {
+        Commands::Explain { abi, json, hex, typename } => {
             let abi = get_abi(abi, &typename)?;
+            match hex {
+                Some(hex) => println!("{}", explain_data(&abi, &typename, &hex, json)?),
+                None => println!("{}", explain(&abi, &typename, json)?),
+            }
+        }
 
-            // create a byte stream from the given hex representation
-            let bin = Bytes::from_hex(&hex)?;
-            let mut view = bin.view();
+        Commands::Abi { command: AbiCommands::Verify { account, file, url } } => {
+            verify_abi(&account, &file, &url)?;
+        }
 
-            // perform the hex->json conversion
-            let v = abi.decode_variant(&mut view, &typename)?;
+        Commands::Abi { command: AbiCommands::Graph { abi, dot, json } } => {
+            let abi = get_abi(Some(abi), "")?;
+            println!("{}", abi_graph(&abi, dot, json)?);
+        }
 
-            if !view.leftover().is_empty() {
-                return Err(eyre!("Trailing input, {} bytes haven't been consumed. Decoded object: {:?}",
-                                 view.leftover().len(), &v));
-            }
+        Commands::Msig { command: MsigCommands::PackProposal { abis, json } } => {
+            load_contract_abis(&abis)?;
+            println!("{}", pack_proposal(&json)?);
+        }
+
+        Commands::Msig { command: MsigCommands::UnpackProposal { abis, proposal } } => {
+            load_contract_abis(&abis)?;
+            println!("{}", serde_json::to_string_pretty(&unpack_proposal(&proposal)?)?);
+        }
+
+        Commands::Repl { abi } => {
+            repl(abi)?;
+        }
 
-            println!("{}", v);
+        Commands::Serve { listen, abi_dir, .. } => {
+            serve(listen, abi_dir)?;
         }
 
+        Commands::ShipDump { url, from, to, types, irreversible_only, output } => {
+            ship_dump(&url, from..=to, &types, irreversible_only, output)?;
+        }
+
+        Commands::Completions { shell } => {
+            print!("{}", completion_script(shell));
+        }
+
+        Commands::ListTypes { abi } => {
+            println!("{}", list_types(abi)?.join("\n"));
+        }
+
+        Commands::ListAbis => {
+            println!("{}", abi::registry::abi_names().join("\n"));
+        }
     }
 
     Ok(())
@@ -1,8 +1,15 @@
 // SPDX-FileCopyrightText: 2023-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::fmt;
 use std::num::ParseIntError;
+use std::ops::Deref;
 
+use base64::{
+    Engine,
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
 use hex;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use snafu::{ensure, Snafu};
@@ -54,6 +61,11 @@ impl<'a> ByteStream<'a> {
         &self.data[self.read_pos..]
     }
 
+    /// Return the number of bytes that have been read so far.
+    pub fn read_pos(&self) -> usize {
+        self.read_pos
+    }
+
     pub fn read_byte(&mut self) -> Result<u8, StreamError> {
         let pos = self.read_pos;
         ensure!(pos != self.data.len(), EndedSnafu { wanted: 1_usize, available: 0_usize });
@@ -100,13 +112,13 @@ impl<'a> ByteStream<'a> {
 //        actually: use Bytes instead of ByteStream for writing data, and only
 //                  ByteStream (rename to ByteStream then) for reading
 
-/// Provide access to a byte stream along with a cursor to read into it.
-/// This is an owning type, the string type equivalent would be `String`.
-///
-/// This is different that both `std::io::Read`/`std::io::Write` and the `bytes`
-/// crate as this is supposed to be used for reading from files/streams that have
-/// an end, so the `read` operation is fallible, but when writing we assume everything
-/// is fine (we usually write into memory) so the `write` operation is infallible.
+/// Base64 engine using the standard alphabet, which accepts input with or without padding.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+
 // #[derive(Default)]
 // pub struct ByteStream {
 //     data: Vec<u8>,
@@ -155,6 +167,22 @@ impl<'a> ByteStream<'a> {
 
 
 
+/// Provide access to a byte stream along with a cursor to read into it.
+/// This is an owning type, the string type equivalent would be `String`.
+///
+/// This is different that both `std::io::Read`/`std::io::Write` and the `bytes`
+/// crate as this is supposed to be used for reading from files/streams that have
+/// an end, so the `read` operation is fallible, but when writing we assume everything
+/// is fine (we usually write into memory) so the `write` operation is infallible.
+///
+/// When serialized with `serde` it is represented as a hex string for human-readable
+/// formats (eg: JSON) and as raw bytes otherwise (eg: MessagePack, CBOR).
+///
+/// When deserializing from a human-readable format, a hex string is expected. A base64
+/// string is also accepted if it cannot be mistaken for hex, i.e. if it contains a
+/// character that is not a hex digit (eg: `+`, `/`, `=`). Strings made only of hex
+/// digits are always decoded as hex and rejected if they are not valid hex (eg: `"abc"`),
+/// use [`serde_base64`] on the field if you know your input is always base64-encoded.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
 pub struct Bytes(Vec<u8>);
 
@@ -162,13 +190,47 @@ impl Bytes {
     pub fn new() -> Self { Bytes(vec![]) }
 
     pub fn from_hex<T: AsRef<[u8]>>(data: T) -> Result<Bytes, hex::FromHexError> {
-        Ok(Bytes(hex::decode(data)?))
+        let mut result = Bytes(Vec::with_capacity(data.as_ref().len() / 2));
+        result.extend_from_hex(data)?;
+        Ok(result)
+    }
+
+    /// Decode the given hex string and append the result to this buffer, without
+    /// allocating an intermediate buffer.
+    ///
+    /// On error, the buffer is left unchanged.
+    pub fn extend_from_hex<T: AsRef<[u8]>>(&mut self, data: T) -> Result<(), hex::FromHexError> {
+        let data = data.as_ref();
+        if data.len() % 2 != 0 {
+            return Err(hex::FromHexError::OddLength);
+        }
+        let start = self.0.len();
+        self.0.resize(start + data.len() / 2, 0);
+        hex::decode_to_slice(data, &mut self.0[start..]).inspect_err(|_| self.0.truncate(start))
     }
 
     pub fn to_hex(&self) -> String {
         hex::encode(&self.0)
     }
 
+    /// Return a value that displays as the hex representation of these bytes,
+    /// without building the full string in memory, eg: `write!(f, "{}", bytes.hex())`.
+    ///
+    /// It also serializes as a hex string with `serde`.
+    pub fn hex(&self) -> HexDisplay<'_> {
+        HexDisplay(&self.0)
+    }
+
+    /// Decode a base64 string (standard alphabet, padding is optional).
+    pub fn from_base64<T: AsRef<[u8]>>(data: T) -> Result<Bytes, base64::DecodeError> {
+        Ok(Bytes(BASE64.decode(data)?))
+    }
+
+    /// Return the base64 representation (standard alphabet, with padding).
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(&self.0)
+    }
+
     pub fn clear(&mut self) {
         self.0.clear()
     }
@@ -213,6 +275,32 @@ impl Bytes {
 
 }
 
+
+/// Hex representation of a slice of bytes, written out in chunks when displayed
+/// instead of being allocated as a whole. See [`Bytes::hex()`].
+#[derive(Clone, Copy, Debug)]
+pub struct HexDisplay<'a>(pub &'a [u8]);
+
+impl fmt::Display for HexDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK_SIZE: usize = 256;
+        let mut buf = [0u8; 2 * CHUNK_SIZE];
+        for chunk in self.0.chunks(CHUNK_SIZE) {
+            let out = &mut buf[..2 * chunk.len()];
+            hex::encode_to_slice(chunk, out).map_err(|_| fmt::Error)?;
+            // hex digits are always valid utf-8
+            f.write_str(std::str::from_utf8(out).map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for HexDisplay<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl From<Vec<u8>> for Bytes {
     fn from(v: Vec<u8>) -> Bytes {
         Bytes(v)
@@ -259,12 +347,75 @@ impl<'a> From<&'a Bytes> for ByteStream<'a> {
 }
 
 
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromIterator<u8> for Bytes {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        Bytes(iter.into_iter().collect())
+    }
+}
+
+
 impl Serialize for Bytes {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer
     {
-        self.to_hex().serialize(serializer)
+        if serializer.is_human_readable() {
+            self.hex().serialize(serializer)
+        }
+        else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+struct BytesVisitor {
+    base64_only: bool,
+}
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.base64_only {
+            true => f.write_str("a base64 string"),
+            false => f.write_str("a hex or base64 string, or a byte array"),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Bytes, E> {
+        if self.base64_only {
+            return Bytes::from_base64(v).map_err(|e| E::custom(format!("invalid base64 string: {e}")));
+        }
+        // only fall back to base64 if the input cannot be hex, so that malformed
+        // hex strings are rejected instead of being silently decoded as base64
+        if v.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Bytes::from_hex(v).map_err(|e| E::custom(format!("invalid hex string: {e}")));
+        }
+        Bytes::from_base64(v).map_err(|e| E::custom(format!("invalid base64 string: {e}")))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Bytes, E> {
+        Ok(Bytes::from(v))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Bytes, E> {
+        Ok(Bytes(v))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Bytes, A::Error> {
+        let mut result = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(b) = seq.next_element::<u8>()? {
+            result.push(b);
+        }
+        Ok(Bytes(result))
     }
 }
 
@@ -273,7 +424,92 @@ impl<'de> Deserialize<'de> for Bytes {
     where
         D: Deserializer<'de>,
     {
-        let hex_repr: &str = <&str>::deserialize(deserializer)?;
-        Bytes::from_hex(hex_repr).map_err(|e| de::Error::custom(e.to_string()))
+        let visitor = BytesVisitor { base64_only: false };
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(visitor)
+        }
+        else {
+            deserializer.deserialize_bytes(visitor)
+        }
+    }
+}
+
+/// Serialize and deserialize a [`Bytes`] field as a base64 string for human-readable
+/// formats, to be used as `#[serde(with = "kudu::serde_base64")]`.
+///
+/// Non human-readable formats use raw bytes, same as the default implementation.
+pub mod serde_base64 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => bytes.to_base64().serialize(serializer),
+            false => serializer.serialize_bytes(bytes),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+        let visitor = BytesVisitor { base64_only: true };
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_str(visitor),
+            false => deserializer.deserialize_bytes(BytesVisitor { base64_only: false }),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_bytes_serde() -> Result<()> {
+        let b = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(json!(b), json!("deadbeef"));
+        assert_eq!(serde_json::from_value::<Bytes>(json!("deadbeef"))?, b);
+        assert_eq!(serde_json::from_value::<Bytes>(json!("3q2+7w=="))?, b);  // base64
+        assert_eq!(serde_json::from_value::<Bytes>(json!("3q2+7w"))?, b);    // base64, no padding
+        assert!(serde_json::from_value::<Bytes>(json!("not valid!")).is_err());
+        // malformed hex is an error, not valid base64
+        assert!(serde_json::from_value::<Bytes>(json!("abc")).is_err());
+        assert!(serde_json::from_value::<Bytes>(json!("abcd")).is_ok_and(|b| b.as_bytes() == [0xab, 0xcd]));
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct WithBase64 {
+            #[serde(with = "serde_base64")]
+            data: Bytes,
+        }
+        let value = WithBase64 { data: Bytes::from_base64("abcd")? };
+        assert_eq!(json!(value), json!({"data": "abcd"}));
+        assert_eq!(serde_json::from_value::<WithBase64>(json!({"data": "abcd"}))?, value);
+
+        assert_eq!(b.len(), 4);  // through `Deref`
+        assert_eq!(&b[1..3], &[0xad, 0xbe]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hex() -> Result<()> {
+        let mut b = Bytes::from_hex("dead")?;
+        b.extend_from_hex("BEEF")?;
+        assert_eq!(b, Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]));
+
+        // buffer is left untouched on error
+        assert!(b.extend_from_hex("abc").is_err());
+        assert!(b.extend_from_hex("abcz").is_err());
+        assert_eq!(b.len(), 4);
+
+        // output is written in chunks
+        let large: Bytes = (0..1000).map(|i| i as u8).collect();
+        assert_eq!(large.hex().to_string(), large.to_hex());
+        assert_eq!(format!("[{}]", b.hex()), "[deadbeef]");
+        assert_eq!(serde_json::to_string(&b.hex())?, r#""deadbeef""#);
+        assert_eq!(Bytes::new().hex().to_string(), "");
+
+        Ok(())
     }
 }
@@ -8,8 +8,23 @@
 //!
 
 mod action;
+mod authority;
+mod block;
+pub mod blocklog;
+pub mod extensions;
+mod finality;
+pub mod history;
+mod multisig;
+#[cfg(feature = "contracts-system")]
+pub mod ops;
+mod preflight;
+pub mod resources;
+#[cfg(feature = "ship")]
+pub mod ship;
+mod submit;
 mod trace;
 mod transaction;
+pub mod wasm;
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
@@ -19,7 +34,8 @@ use serde_json;
 use tracing::{debug, trace, warn};
 
 use crate::{
-    contract, ABISerializable, APIClient, AccountName, ActionName, Asset, Bytes, JsonValue, Name, PrivateKey
+    contract, ABISerializable, APIClient, AccountName, ActionName, Bytes, JsonValue, PermissionName, PrivateKey,
+    TableName,
 };
 
 // this is needed to be able to call the `ABISerializable` derive macro, which needs
@@ -55,7 +71,26 @@ pub trait Contract: ABISerializable {
     fn name() -> ActionName;
 }
 
+/// A row of a contract table, which can be queried with [`APIClient::table()`].
+pub trait TableRow: ABISerializable {
+    /// Account of the contract owning the table
+    fn code() -> AccountName;
+    fn table() -> TableName;
+}
+
 pub use action::{Action, ActionError, IntoPermissionVec, PermissionLevel};
+pub use authority::{
+    Authority, AuthorityChecker, AuthorityError, KeyWeight, PermissionLevelWeight, WaitWeight,
+};
+pub use block::{
+    BlockHeader, BlockSigningAuthority, BlockSigningAuthorityV0, PackedTransaction,
+    ProducerAuthority, ProducerAuthoritySchedule, ProducerKey, ProducerSchedule, ReceiptTransaction,
+    SignedBlock, SignedBlockHeader, TransactionReceipt,
+};
+pub use finality::{
+    FinalizerAuthority, FinalizerPolicy, FinalizerPolicyDiff, OrderedDiff, ProposerPolicyDiff,
+    Qc, QcClaim, QcSig,
+};
 pub use trace::{
     AccountAuthSequence, AccountDelta,
     ActionReceipt, ActionReceiptV0,
@@ -64,32 +99,27 @@ pub use trace::{
     Trace,
     TransactionTrace, TransactionTraceV0, TransactionTraceException, TransactionTraceMsg,
 };
-pub use transaction::{SignedTransaction, Transaction, TransactionError};
+pub use multisig::{SignatureCollector, SignatureError};
+pub(crate) use block::unpack_data;
+pub use preflight::{ResourceEstimate, TransactionLimits};
+pub use submit::{SubmitOptions, Submission};
+pub use transaction::{DEFAULT_EXPIRATION_DELAY, SignedTransaction, Transaction, TransactionError};
 
 
-/// not a native Antelope type but normally defined through an ABI
-/// It is provided here for convenience
+/// Deploy the given WebAssembly code on an account, see [`wasm::WasmCode`] to
+/// inspect it.
+///
+/// **NOTE:** the optional `memo` field (a binary extension) is not decoded.
 #[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
-#[contract(account="eosio.token", name="transfer")]
-pub struct Transfer {
-    pub from: Name,
-    pub to: Name,
-    pub quantity: Asset,
-    pub memo: String,
+#[contract(account="eosio", name="setcode")]
+pub struct SetCode {
+    pub account: AccountName,
+    pub vmtype: u8,
+    pub vmversion: u8,
+    pub code: Bytes,
 }
 
 
-// impl Contract for Transfer {
-//     fn account() -> AccountName {
-//         const { AccountName::constant("eosio.token") }
-//      }
-//     fn name() -> ActionName {
-//         const { ActionName::constant("transfer") }
-//     }
-// }
-
-
-
 // TODO: move this to api.rs (or not?)
 pub const DEBUG: usize = 0;
 pub const WARN: usize = 1;
@@ -173,10 +203,10 @@ pub fn log_tx_trace(response: &JsonValue) -> Result<(), TransactionError> {
 
 pub fn push_action(
     client: Arc<APIClient>,
-    actor: Name,
+    actor: AccountName,
     signing_key: &PrivateKey,
-    contract: Name,
-    action: Name,
+    contract: AccountName,
+    action: ActionName,
     args: &JsonValue,
 ) -> Result<(), TransactionError>
 {
@@ -186,7 +216,7 @@ pub fn push_action(
     let action = Action {
         account: contract,
         name: action,
-        authorization: vec![PermissionLevel { actor, permission: Name::constant("active") }],
+        authorization: vec![PermissionLevel { actor, permission: PermissionName::constant("active") }],
         data: Bytes::new(),
     }
     .with_data(args)?;
@@ -207,7 +237,7 @@ mod tests {
     use kudu::{PrivateKey, json};
 
     #[allow(non_snake_case)]
-    fn N(name: &str) -> Name { Name::constant(name) }
+    fn N(name: &str) -> AccountName { AccountName::constant(name) }
 
 
     #[test]
@@ -218,7 +248,7 @@ mod tests {
         let client = APIClient::local();
         let key = PrivateKey::eosio_dev();
 
-        push_action(client, N("eosio"), &key, N("eosio.token"), N("transfer"), &json!({
+        push_action(client, N("eosio"), &key, N("eosio.token"), ActionName::constant("transfer"), &json!({
             "from": "eosio",
             "to": "eosio.token",
             "quantity": "1.0000 SYS",
@@ -10,7 +10,7 @@ use snafu::{Snafu, OptionExt};
 
 use crate::{
     AccountName, ActionName, Contract,
-    PermissionName, Name, ABISerializable,
+    PermissionName, ABISerializable,
     abiserializable::to_bin, Bytes, JsonValue,
     ByteStream, ABI, ABIError, InvalidName,
     abi, with_location, impl_auto_error_conversion,
@@ -24,7 +24,8 @@ extern crate self as kudu;
 // from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/action.hpp
 
 
-#[derive(Eq, Hash, PartialEq, Copy, Clone, Default, Deserialize, Serialize, ABISerializable)]
+/// Permission levels are ordered by actor first, then by permission, as on-chain.
+#[derive(Eq, Hash, PartialEq, PartialOrd, Ord, Copy, Clone, Default, Deserialize, Serialize, ABISerializable)]
 pub struct PermissionLevel {
     pub actor: AccountName,
     pub permission: PermissionName,
@@ -171,8 +172,8 @@ impl Action {
         };
 
         Ok(Action {
-            account: Name::new(account)?,
-            name: Name::new(action_name)?,
+            account: AccountName::new(account)?,
+            name: ActionName::new(action_name)?,
             authorization,
             data,
         })
@@ -221,7 +222,7 @@ impl Action {
 
 impl fmt::Debug for Action {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Action({}::{} {:?} data={}", self.account, self.name, self.authorization, self.data.to_hex())
+        write!(f, "Action({}::{} {:?} data={}", self.account, self.name, self.authorization, self.data.hex())
     }
 }
 
@@ -0,0 +1,554 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, de};
+use snafu::{OptionExt, ResultExt, Snafu};
+
+use crate::{
+    ABISerializable, AccountName, ActionName, InvalidCryptoData, InvalidName, JsonValue,
+    PermissionLevel, PermissionName, PublicKey, Weight, with_location,
+};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/authority.hpp
+
+// NOTE: the ordering of the following types is the one of their first field, which
+//       is how they need to be sorted inside an `Authority`
+
+#[derive(Eq, Hash, PartialEq, PartialOrd, Ord, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct KeyWeight {
+    pub key: PublicKey,
+    pub weight: Weight,
+}
+
+#[derive(Eq, Hash, PartialEq, PartialOrd, Ord, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct PermissionLevelWeight {
+    pub permission: PermissionLevel,
+    pub weight: Weight,
+}
+
+#[derive(Eq, Hash, PartialEq, PartialOrd, Ord, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct WaitWeight {
+    pub wait_sec: u32,
+    pub weight: Weight,
+}
+
+/// An authority, ie: the keys, accounts and waits that can satisfy a permission.
+///
+/// When deserializing, a public key can be given instead of the full authority
+/// object, as `cleos` allows, which is equivalent to [`Authority::from_simple()`].
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, ABISerializable)]
+pub struct Authority {
+    pub threshold: u32,
+    pub keys: Vec<KeyWeight>,
+    pub accounts: Vec<PermissionLevelWeight>,
+    pub waits: Vec<WaitWeight>,
+}
+
+impl Authority {
+    /// Return an authority satisfied by a single key, with a threshold of 1.
+    ///
+    /// ```
+    /// # use kudu::Authority;
+    /// let auth = Authority::from_simple("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV")?;
+    /// assert_eq!((auth.threshold, auth.keys[0].weight), (1, 1));
+    /// # Ok::<(), kudu::AuthorityError>(())
+    /// ```
+    pub fn from_simple(key: &str) -> Result<Self, AuthorityError> {
+        Ok(Self::from_key(PublicKey::new(key).context(InvalidKeySnafu)?))
+    }
+
+    /// Return an authority satisfied by the given key, with a threshold of 1.
+    pub fn from_key(key: PublicKey) -> Self {
+        Authority {
+            threshold: 1,
+            keys: vec![KeyWeight { key, weight: 1 }],
+            ..Default::default()
+        }
+    }
+
+    /// Return an authority delegated to the given `(account, permission, weight)`
+    /// permission levels, which are sorted as required by nodeos.
+    ///
+    /// ```
+    /// # use kudu::Authority;
+    /// let auth = Authority::from_accounts(&[("bob", "active", 1), ("alice", "active", 1)], 2)?;
+    /// assert_eq!(auth.accounts[0].permission.to_string(), "alice@active");
+    /// assert!(auth.is_valid());
+    /// # Ok::<(), kudu::AuthorityError>(())
+    /// ```
+    pub fn from_accounts(accounts: &[(&str, &str, Weight)], threshold: u32) -> Result<Self, AuthorityError> {
+        let mut result = Authority {
+            threshold,
+            accounts: accounts.iter().map(|&(actor, permission, weight)| Ok(PermissionLevelWeight {
+                permission: PermissionLevel {
+                    actor: AccountName::new(actor).context(InvalidNameSnafu)?,
+                    permission: PermissionName::new(permission).context(InvalidNameSnafu)?,
+                },
+                weight,
+            })).collect::<Result<_, _>>()?,
+            ..Default::default()
+        };
+        result.sort();
+        Ok(result)
+    }
+
+    /// Return whether this authority is valid, using the same rules as nodeos:
+    ///  - keys, accounts and waits are sorted in strictly increasing order of
+    ///    (respectively) public key, permission level and wait time, which also
+    ///    means there cannot be any duplicates
+    ///  - the sum of all the weights reaches the threshold
+    pub fn is_valid(&self) -> bool {
+        fn strictly_increasing<T: Ord>(values: impl Iterator<Item = T>) -> bool {
+            values.is_sorted_by(|a, b| a < b)
+        }
+
+        let total_weight: u64 = self.keys.iter().map(|k| k.weight as u64)
+            .chain(self.accounts.iter().map(|a| a.weight as u64))
+            .chain(self.waits.iter().map(|w| w.weight as u64))
+            .sum();
+
+        strictly_increasing(self.keys.iter().map(|k| &k.key))
+            && strictly_increasing(self.accounts.iter().map(|a| &a.permission))
+            && strictly_increasing(self.waits.iter().map(|w| w.wait_sec))
+            && total_weight >= self.threshold as u64
+    }
+
+    /// Sort the keys, accounts and waits of this authority in the order required
+    /// by nodeos.
+    pub fn sort(&mut self) {
+        self.keys.sort();
+        self.accounts.sort();
+        self.waits.sort();
+    }
+}
+
+/// Mirror of `Authority` used to derive its deserialization from an object.
+#[derive(Deserialize)]
+#[serde(rename = "Authority")]
+struct AuthorityFields {
+    threshold: u32,
+    keys: Vec<KeyWeight>,
+    accounts: Vec<PermissionLevelWeight>,
+    waits: Vec<WaitWeight>,
+}
+
+impl From<AuthorityFields> for Authority {
+    fn from(a: AuthorityFields) -> Self {
+        Authority { threshold: a.threshold, keys: a.keys, accounts: a.accounts, waits: a.waits }
+    }
+}
+
+impl<'de> Deserialize<'de> for Authority {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AuthorityVisitor;
+
+        impl<'de> de::Visitor<'de> for AuthorityVisitor {
+            type Value = Authority;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an authority or a public key")
+            }
+
+            fn visit_str<E: de::Error>(self, key: &str) -> Result<Authority, E> {
+                Authority::from_simple(key).map_err(|e| E::custom(e.to_string()))
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Authority, A::Error> {
+                AuthorityFields::deserialize(de::value::MapAccessDeserializer::new(map)).map(Into::into)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Authority, A::Error> {
+                AuthorityFields::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Into::into)
+            }
+        }
+
+        deserializer.deserialize_any(AuthorityVisitor)
+    }
+}
+
+
+// =============================================================================
+//
+//     Offline authority checker
+//
+// =============================================================================
+
+/// Default value for the max recursion depth when evaluating authorities, this
+/// is the value of `max_authority_depth` in the default chain config.
+pub const DEFAULT_MAX_AUTHORITY_DEPTH: u16 = 6;
+
+const ACTIVE: PermissionName = PermissionName::constant("active");
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum AuthorityError {
+    #[snafu(display("missing or invalid field `{field}` in account data"))]
+    InvalidAccountData { field: String },
+
+    #[snafu(display("cannot parse field `{field}` in account data"))]
+    InvalidJson { field: String, source: serde_json::Error },
+
+    #[snafu(display("invalid public key"))]
+    InvalidKey { source: InvalidCryptoData },
+
+    #[snafu(display("invalid account or permission name"))]
+    InvalidName { source: InvalidName },
+}
+
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+struct PermissionInfo {
+    parent: Option<PermissionName>,
+    authority: Authority,
+}
+
+/// Evaluate offline whether a given `PermissionLevel` can be satisfied with a set
+/// of public keys.
+///
+/// The checker needs to know about the permissions of all the accounts involved,
+/// including the accounts to which a permission is delegated. These can be added
+/// using the [`AuthorityChecker::add_account()`] method with the result of the
+/// `/v1/chain/get_account` API endpoint, or individually with
+/// [`AuthorityChecker::add_permission()`].
+///
+/// A permission level is satisfied if the sum of the weights of the available keys,
+/// satisfied delegated permissions and waits (if their `wait_sec` is lower or equal
+/// to the provided delay) reaches the threshold of its authority.
+///
+/// As in nodeos, a permission level is only checked against its own authority, never
+/// against the one of its parent: the permission hierarchy (e.g. `owner` being allowed
+/// wherever `active` is required) only applies when choosing which permission to use
+/// for a linked action, see [`AuthorityChecker::permission_satisfies()`].
+#[derive(Debug, Clone)]
+pub struct AuthorityChecker {
+    permissions: HashMap<PermissionLevel, PermissionInfo>,
+    links: HashMap<(AccountName, AccountName, ActionName), PermissionName>,
+    max_depth: u16,
+    provided_delay: u32,
+}
+
+impl Default for AuthorityChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuthorityChecker {
+    pub fn new() -> Self {
+        AuthorityChecker {
+            permissions: HashMap::new(),
+            links: HashMap::new(),
+            max_depth: DEFAULT_MAX_AUTHORITY_DEPTH,
+            provided_delay: 0,
+        }
+    }
+
+    pub fn with_max_depth(self, max_depth: u16) -> Self {
+        AuthorityChecker { max_depth, ..self }
+    }
+
+    /// Set the delay (in seconds) that satisfies the `waits` of an authority.
+    pub fn with_provided_delay(self, provided_delay: u32) -> Self {
+        AuthorityChecker { provided_delay, ..self }
+    }
+
+    /// Register a permission for an account, along with its parent permission.
+    pub fn add_permission(&mut self, account: AccountName, permission: PermissionName,
+                          parent: Option<PermissionName>, authority: Authority) {
+        let level = PermissionLevel { actor: account, permission };
+        self.permissions.insert(level, PermissionInfo { parent, authority });
+    }
+
+    /// Register a link (as created by `eosio::linkauth`) so that the given permission
+    /// is required for `code::action`. An empty `action` means all actions from `code`.
+    pub fn add_link(&mut self, account: AccountName, code: AccountName, action: ActionName,
+                    permission: PermissionName) {
+        self.links.insert((account, code, action), permission);
+    }
+
+    /// Register all the permissions (and their linked actions) of an account, given
+    /// the JSON response of the `/v1/chain/get_account` API endpoint.
+    pub fn add_account(&mut self, account_info: &JsonValue) -> Result<(), AuthorityError> {
+        fn parse<'a, T: Deserialize<'a>>(value: &'a JsonValue, field: &str) -> Result<T, AuthorityError> {
+            T::deserialize(value).context(InvalidJsonSnafu { field })
+        }
+
+        let account: AccountName = parse(&account_info["account_name"], "account_name")?;
+        let permissions = account_info["permissions"].as_array()
+            .context(InvalidAccountDataSnafu { field: "permissions" })?;
+
+        for perm in permissions {
+            let name: PermissionName = parse(&perm["perm_name"], "perm_name")?;
+            let parent: PermissionName = match &perm["parent"] {
+                JsonValue::String(s) if s.is_empty() => PermissionName::default(),
+                value => parse(value, "parent")?,
+            };
+            let parent = (parent != PermissionName::default()).then_some(parent);
+            let authority: Authority = parse(&perm["required_auth"], "required_auth")?;
+            self.add_permission(account, name, parent, authority);
+
+            if let Some(links) = perm.get("linked_actions").and_then(|l| l.as_array()) {
+                for link in links {
+                    let code: AccountName = parse(&link["account"], "linked_actions.account")?;
+                    let action: ActionName = match &link["action"] {
+                        JsonValue::Null => ActionName::default(),
+                        value => parse(value, "linked_actions.action")?,
+                    };
+                    self.add_link(account, code, action, name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the minimum permission of `account` required to execute `code::action`,
+    /// taking into account the registered links. Defaults to `active` if no link exists.
+    pub fn required_permission(&self, account: AccountName, code: AccountName, action: ActionName) -> PermissionName {
+        self.links.get(&(account, code, action))
+            .or_else(|| self.links.get(&(account, code, ActionName::default())))
+            .copied()
+            .unwrap_or(ACTIVE)
+    }
+
+    /// Return whether `provided` can be used where `required` is the minimum permission
+    /// of `account`, i.e. if `provided` is `required` itself or one of its ancestors.
+    pub fn permission_satisfies(&self, account: AccountName, required: PermissionName,
+                                provided: PermissionName) -> bool {
+        let mut current = Some(required);
+        let mut depth = 0;
+        while let Some(permission) = current {
+            if permission == provided {
+                return true;
+            }
+            if depth >= self.max_depth {
+                return false;
+            }
+            depth += 1;
+            current = self.permissions.get(&PermissionLevel { actor: account, permission })
+                .and_then(|info| info.parent);
+        }
+        false
+    }
+
+    /// Return whether the given permission level can be satisfied using the given keys.
+    pub fn satisfies(&self, level: &PermissionLevel, keys: &[PublicKey]) -> bool {
+        self.required_keys(level, keys).is_some()
+    }
+
+    /// If the permission level can be satisfied with the given keys, return the subset
+    /// of keys that is needed to do so, otherwise return `None`.
+    pub fn required_keys(&self, level: &PermissionLevel, keys: &[PublicKey]) -> Option<Vec<PublicKey>> {
+        let available: HashSet<&PublicKey> = keys.iter().collect();
+        let mut visiting = HashSet::new();
+        let mut used = HashSet::new();
+        if !self.check_level(level, &available, 0, &mut visiting, &mut used) {
+            return None;
+        }
+        // keep the keys in the same order as they were given
+        Some(keys.iter().filter(|k| used.contains(k)).cloned().collect())
+    }
+
+    fn check_level(&self, level: &PermissionLevel, keys: &HashSet<&PublicKey>, depth: u16,
+                   visiting: &mut HashSet<PermissionLevel>, used: &mut HashSet<PublicKey>) -> bool {
+        if depth > self.max_depth || visiting.contains(level) {
+            return false;
+        }
+        let Some(info) = self.permissions.get(level) else {
+            return false;
+        };
+
+        visiting.insert(*level);
+        let satisfied = self.check_authority(&info.authority, keys, depth, visiting, used);
+        visiting.remove(level);
+        satisfied
+    }
+
+    fn check_authority(&self, authority: &Authority, keys: &HashSet<&PublicKey>, depth: u16,
+                       visiting: &mut HashSet<PermissionLevel>, used: &mut HashSet<PublicKey>) -> bool {
+        let mut total: u32 = 0;
+        let mut used_keys = vec![];
+
+        // add the highest weights first so we use the minimum set of keys possible
+        let mut key_weights: Vec<_> = authority.keys.iter().filter(|kw| keys.contains(&kw.key)).collect();
+        key_weights.sort_by_key(|kw| std::cmp::Reverse(kw.weight));
+
+        for kw in key_weights {
+            if total >= authority.threshold { break; }
+            total += kw.weight as u32;
+            used_keys.push(kw.key.clone());
+        }
+
+        for wait in authority.waits.iter() {
+            if total >= authority.threshold { break; }
+            if wait.wait_sec <= self.provided_delay {
+                total += wait.weight as u32;
+            }
+        }
+
+        for plw in authority.accounts.iter() {
+            if total >= authority.threshold { break; }
+            let mut account_used = HashSet::new();
+            if self.check_level(&plw.permission, keys, depth + 1, visiting, &mut account_used) {
+                total += plw.weight as u32;
+                used_keys.extend(account_used);
+            }
+        }
+
+        let satisfied = total >= authority.threshold;
+        if satisfied {
+            used.extend(used_keys);
+        }
+        satisfied
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use super::*;
+    use crate::{PrivateKey, json};
+
+    const OWNER: PermissionName = PermissionName::constant("owner");
+
+    #[allow(non_snake_case)]
+    fn N(name: &str) -> AccountName { AccountName::constant(name) }
+
+    fn key(i: u8) -> PublicKey {
+        let mut data = [0u8; 32];
+        data[31] = i;
+        PublicKey::from_private_key(&PrivateKey::with_key_type(crate::KeyType::K1, data))
+    }
+
+    #[test]
+    fn test_authority_checker() -> Result<(), AuthorityError> {
+        let (k1, k2, k3, k4) = (key(1), key(2), key(3), key(4));
+        let mut checker = AuthorityChecker::new();
+
+        checker.add_account(&json!({
+            "account_name": "alice",
+            "permissions": [
+                { "perm_name": "owner", "parent": "", "required_auth": {
+                    "threshold": 1, "keys": [{ "key": k1.to_string(), "weight": 1 }],
+                    "accounts": [], "waits": []
+                }, "linked_actions": [{ "account": "eosio.token", "action": "transfer" }]},
+                { "perm_name": "active", "parent": "owner", "required_auth": {
+                    "threshold": 2,
+                    "keys": [{ "key": k2.to_string(), "weight": 1 }],
+                    "accounts": [{ "permission": { "actor": "bob", "permission": "active" }, "weight": 1 }],
+                    "waits": []
+                }},
+            ]
+        }))?;
+
+        checker.add_permission(N("bob"), OWNER, None, Authority {
+            threshold: 1,
+            keys: vec![KeyWeight { key: k4.clone(), weight: 1 }],
+            ..Default::default()
+        });
+        checker.add_permission(N("bob"), ACTIVE, Some(OWNER), Authority {
+            threshold: 1,
+            keys: vec![KeyWeight { key: k3.clone(), weight: 1 }],
+            ..Default::default()
+        });
+
+        let alice_active = PermissionLevel { actor: N("alice"), permission: ACTIVE };
+        assert!(!checker.satisfies(&alice_active, std::slice::from_ref(&k1)));  // parent permission doesn't count
+        assert!(!checker.satisfies(&alice_active, std::slice::from_ref(&k2)));
+        assert!(checker.satisfies(&alice_active, &[k2.clone(), k3.clone()]));  // through delegation
+        assert!(!checker.satisfies(&alice_active, &[k2.clone(), k4.clone()]));  // bob@owner doesn't satisfy bob@active
+        assert_eq!(checker.required_keys(&alice_active, &[k3.clone(), k2.clone()]), Some(vec![k3, k2]));
+
+        assert_eq!(checker.required_permission(N("alice"), N("eosio.token"), ActionName::constant("transfer")), OWNER);
+        assert_eq!(checker.required_permission(N("alice"), N("eosio"), ActionName::constant("buyram")), ACTIVE);
+
+        // the parent relation only matters for the minimum permission of linked actions
+        assert!(checker.permission_satisfies(N("alice"), ACTIVE, OWNER));
+        assert!(checker.permission_satisfies(N("alice"), ACTIVE, ACTIVE));
+        assert!(!checker.permission_satisfies(N("alice"), OWNER, ACTIVE));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_authority_validation() {
+        let r1_key = PublicKey::with_key_type(crate::KeyType::R1, [2; 33]);
+        let mut auth = Authority {
+            threshold: 3,
+            keys: [r1_key, key(1), key(2), key(3)].into_iter()
+                .map(|key| KeyWeight { key, weight: 1 }).collect(),
+            accounts: vec![
+                PermissionLevelWeight { permission: PermissionLevel { actor: N("bob"), permission: OWNER }, weight: 1 },
+                PermissionLevelWeight { permission: PermissionLevel { actor: N("bob"), permission: ACTIVE }, weight: 1 },
+                PermissionLevelWeight { permission: PermissionLevel { actor: N("alice"), permission: OWNER }, weight: 1 },
+            ],
+            waits: vec![WaitWeight { wait_sec: 10, weight: 1 }, WaitWeight { wait_sec: 5, weight: 1 }],
+        };
+        assert!(!auth.is_valid());
+
+        auth.sort();
+        assert!(auth.is_valid());
+
+        // keys are sorted the same way as nodeos does, ie: by key type then by data,
+        // which is the order of their binary representation
+        assert_eq!(auth.keys.last().unwrap().key.key_type(), crate::KeyType::R1);
+        let bin_keys: Vec<_> = auth.keys.iter().map(|k| crate::to_bin(&k.key).to_vec()).collect();
+        assert!(bin_keys.is_sorted());
+
+        let accounts: Vec<_> = auth.accounts.iter().map(|a| a.permission.to_string()).collect();
+        assert_eq!(accounts, ["alice@owner", "bob@active", "bob@owner"]);
+
+        // duplicates are not allowed
+        let mut dup = auth.clone();
+        dup.keys.push(dup.keys[3].clone());
+        dup.sort();
+        assert!(!dup.is_valid());
+
+        // threshold needs to be reachable
+        auth.threshold = 10;
+        assert!(!auth.is_valid());
+    }
+
+    #[test]
+    fn test_simplified_authority() -> Result<()> {
+        let k = "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+        let auth = Authority::from_simple(k)?;
+        assert_eq!(auth, Authority::from_key(PublicKey::new(k)?));
+        assert!(auth.is_valid());
+        assert!(Authority::from_simple("not a key").is_err());
+
+        // the shorthand form is accepted when deserializing
+        let full = json!({
+            "threshold": 1,
+            "keys": [{"key": "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63", "weight": 1}],
+            "accounts": [],
+            "waits": [],
+        });
+        assert_eq!(json!(auth), full);
+        assert_eq!(serde_json::from_value::<Authority>(json!(k))?, auth);
+        assert_eq!(serde_json::from_value::<Authority>(full)?, auth);
+        assert!(serde_json::from_value::<Authority>(json!(1)).is_err());
+        assert!(serde_json::from_value::<Authority>(json!({"threshold": 1})).is_err());
+
+        let auth = Authority::from_accounts(&[("bob", "active", 1), ("alice", "owner", 1)], 2)?;
+        let accounts: Vec<_> = auth.accounts.iter().map(|a| a.permission.to_string()).collect();
+        assert_eq!(accounts, ["alice@owner", "bob@active"]);
+        assert!(auth.is_valid());
+        assert!(Authority::from_accounts(&[("Bob", "active", 1)], 1).is_err());
+
+        Ok(())
+    }
+}
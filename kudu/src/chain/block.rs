@@ -0,0 +1,343 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    ABISerializable, AccountName, BlockId, BlockNum, BlockTimestamp, ByteStream, Bytes,
+    Checksum256, Extensions, KeyWeight, PublicKey, SerializeError, SerializeEnum, Signature,
+    Transaction, TransactionId, VarUint32,
+    abiserializable::InvalidDataSnafu,
+    chain::extensions::{BlockExtension, BlockHeaderExtension, ExtensionType, get_extension},
+};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/block_header.hpp
+//       https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/block.hpp
+
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct ProducerKey {
+    pub producer_name: AccountName,
+    pub block_signing_key: PublicKey,
+}
+
+/// Legacy producer schedule, as found in the `new_producers` field of a block header.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct ProducerSchedule {
+    pub version: u32,
+    pub producers: Vec<ProducerKey>,
+}
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/producer_schedule.hpp
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct BlockSigningAuthorityV0 {
+    /// Minimum threshold of accumulated weights from component keys that satisfies this authority
+    pub threshold: u32,
+    pub keys: Vec<KeyWeight>,
+}
+
+/// The authority a producer uses to sign blocks. For now only a weighted
+/// threshold multi-sig (`v0`) exists.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, SerializeEnum, ABISerializable)]
+pub enum BlockSigningAuthority {
+    #[serde(rename="block_signing_authority_v0")]
+    V0(BlockSigningAuthorityV0),
+}
+
+impl BlockSigningAuthority {
+    /// Return a block signing authority that is satisfied by the given key only.
+    pub fn from_key(key: PublicKey) -> Self {
+        BlockSigningAuthority::V0(BlockSigningAuthorityV0 {
+            threshold: 1,
+            keys: vec![KeyWeight { key, weight: 1 }],
+        })
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct ProducerAuthority {
+    pub producer_name: AccountName,
+    pub authority: BlockSigningAuthority,
+}
+
+/// Producer schedule where each producer can use a multi-sig authority to sign
+/// blocks, as opposed to the legacy [`ProducerSchedule`] with only one key.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct ProducerAuthoritySchedule {
+    pub version: u32,
+    pub producers: Vec<ProducerAuthority>,
+}
+
+impl From<ProducerSchedule> for ProducerAuthoritySchedule {
+    fn from(schedule: ProducerSchedule) -> Self {
+        ProducerAuthoritySchedule {
+            version: schedule.version,
+            producers: schedule.producers.into_iter().map(|p| ProducerAuthority {
+                producer_name: p.producer_name,
+                authority: BlockSigningAuthority::from_key(p.block_signing_key),
+            }).collect(),
+        }
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct BlockHeader {
+    pub timestamp: BlockTimestamp,
+    pub producer: AccountName,
+    /// By signing this block this producer is confirming blocks `[block_num() - confirmed, blocknum())`
+    /// as being the best blocks for that range and that he has not signed any other
+    /// statements that would contradict.
+    pub confirmed: u16,
+    pub previous: BlockId,
+    pub transaction_mroot: Checksum256,
+    pub action_mroot: Checksum256,
+    /// The producer schedule version that should validate this block, this is used to
+    /// indicate that the prior block which included new_producers->version has been marked
+    /// irreversible and that it the new producer schedule takes effect this block.
+    pub schedule_version: u32,
+    pub new_producers: Option<ProducerSchedule>,
+    pub header_extensions: Extensions,
+}
+
+impl BlockHeader {
+    /// Return the block number of a block given its ID. The block number is encoded
+    /// in big-endian order in the first 4 bytes of the ID.
+    pub fn num_from_id(id: &BlockId) -> BlockNum {
+        u32::from_be_bytes(id.0[..4].try_into().unwrap())  // safe unwrap
+    }
+
+    pub fn block_num(&self) -> BlockNum {
+        Self::num_from_id(&self.previous) + 1
+    }
+
+    /// Return the header extension of type `T`, if this block header has one.
+    pub fn extension<T: ExtensionType>(&self) -> Result<Option<T>, SerializeError> {
+        get_extension(&self.header_extensions)
+    }
+
+    /// Decode all the header extensions of this block header, unknown ones being
+    /// kept as raw bytes.
+    pub fn decode_extensions(&self) -> Result<Vec<BlockHeaderExtension>, SerializeError> {
+        BlockHeaderExtension::decode_all(&self.header_extensions)
+    }
+
+    pub fn digest(&self) -> Checksum256 {
+        let mut data = Bytes::new();
+        self.to_bin(&mut data);
+        let hash: [u8; 32] = Sha256::digest(&data).into();
+        hash.into()
+    }
+
+    /// Return the ID of this block, which is its digest where the first 4 bytes
+    /// have been replaced with the block number.
+    pub fn id(&self) -> BlockId {
+        let mut result = self.digest();
+        result.0[..4].copy_from_slice(&self.block_num().to_be_bytes());
+        result
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct SignedBlockHeader {
+    #[kudu(base)]
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    pub producer_signature: Signature,
+}
+
+
+// -----------------------------------------------------------------------------
+//     Transaction receipts
+// -----------------------------------------------------------------------------
+
+/// A packed transaction as it is stored inside a block.
+///
+/// The `packed_trx` field contains the binary representation of the transaction, possibly
+/// compressed using zlib (if `compression == 1`).
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct PackedTransaction {
+    pub signatures: Vec<Signature>,
+    pub compression: u8,
+    pub packed_context_free_data: Bytes,
+    pub packed_trx: Bytes,
+}
+
+impl PackedTransaction {
+    pub const COMPRESSION_NONE: u8 = 0;
+    pub const COMPRESSION_ZLIB: u8 = 1;
+
+    /// Decompress (if needed) and decode the `Transaction` contained in this packed transaction.
+    pub fn unpack(&self) -> Result<Transaction, SerializeError> {
+        let data = unpack_data(self.compression, self.packed_trx.as_ref())?;
+        let mut stream = ByteStream::from(&data);
+        Transaction::from_bin(&mut stream)
+    }
+
+    pub fn id(&self) -> Result<TransactionId, SerializeError> {
+        Ok(self.unpack()?.id())
+    }
+}
+
+/// Return the uncompressed data given the compression type (`0`: none, `1`: zlib)
+pub(crate) fn unpack_data(compression: u8, data: &[u8]) -> Result<Vec<u8>, SerializeError> {
+    match compression {
+        PackedTransaction::COMPRESSION_NONE => Ok(data.to_vec()),
+        PackedTransaction::COMPRESSION_ZLIB => {
+            let mut result = Vec::new();
+            ZlibDecoder::new(data).read_to_end(&mut result).map_err(|e| {
+                InvalidDataSnafu { message: format!("cannot decompress zlib data: {e}") }.build()
+            })?;
+            Ok(result)
+        },
+        c => InvalidDataSnafu { message: format!("unknown compression type: {c}") }.fail(),
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, SerializeEnum, ABISerializable)]
+pub enum ReceiptTransaction {
+    #[serde(rename="transaction_id")]
+    Id(TransactionId),
+
+    #[serde(rename="packed_transaction")]
+    Packed(PackedTransaction),
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct TransactionReceipt {
+    // -----------------------------------------------------------------------------
+    //     TransactionReceiptHeader fields
+    // -----------------------------------------------------------------------------
+
+    /// One of: `0` (executed), `1` (soft_fail), `2` (hard_fail), `3` (delayed), `4` (expired)
+    pub status: u8,
+    /// Total billed CPU usage (microseconds)
+    pub cpu_usage_us: u32,
+    /// Total billed NET usage, so we can reconstruct resource state when skipping context free data... hard failures...
+    pub net_usage_words: VarUint32,
+
+    // -----------------------------------------------------------------------------
+    //     TransactionReceipt fields
+    // -----------------------------------------------------------------------------
+
+    pub trx: ReceiptTransaction,
+}
+
+
+// -----------------------------------------------------------------------------
+//     Signed block
+// -----------------------------------------------------------------------------
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct SignedBlock {
+    #[kudu(base)]
+    #[serde(flatten)]
+    pub header: SignedBlockHeader,
+    pub transactions: Vec<TransactionReceipt>,
+    pub block_extensions: Extensions,
+}
+
+impl SignedBlock {
+    pub fn block_num(&self) -> BlockNum {
+        self.header.header.block_num()
+    }
+
+    /// Return the block extension of type `T`, if this block has one.
+    ///
+    /// Note that this is different from the header extensions, which can be accessed
+    /// with [`BlockHeader::extension()`].
+    pub fn extension<T: ExtensionType>(&self) -> Result<Option<T>, SerializeError> {
+        get_extension(&self.block_extensions)
+    }
+
+    /// Decode all the block extensions of this block, unknown ones being kept as
+    /// raw bytes.
+    ///
+    /// Use [`SignedBlock::decode_header_extensions()`] for the header extensions.
+    pub fn decode_extensions(&self) -> Result<Vec<BlockExtension>, SerializeError> {
+        BlockExtension::decode_all(&self.block_extensions)
+    }
+
+    /// Decode all the header extensions of this block, unknown ones being kept as
+    /// raw bytes.
+    pub fn decode_header_extensions(&self) -> Result<Vec<BlockHeaderExtension>, SerializeError> {
+        self.header.header.decode_extensions()
+    }
+
+    pub fn id(&self) -> BlockId {
+        self.header.header.id()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_num_from_id() {
+        let id = BlockId::from_hex("0000000267f3e2284b482f3afc2e724be1d6cbc1804532ec62d4e7af47c30693").unwrap();
+        assert_eq!(BlockHeader::num_from_id(&id), 2);
+
+        let id = BlockId::from_hex("0fd0d8ae1d1c58fd07e8a8d59a0272a44b8bf9f1156ebfa3b539daa0215f7177").unwrap();
+        assert_eq!(BlockHeader::num_from_id(&id), 0x0fd0d8ae);
+    }
+
+    #[test]
+    fn test_producer_authority_schedule() -> Result<(), SerializeError> {
+        let key = PublicKey::new("PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63").unwrap();
+        let schedule = ProducerAuthoritySchedule::from(ProducerSchedule {
+            version: 2,
+            producers: vec![ProducerKey { producer_name: AccountName::constant("eosio"), block_signing_key: key }],
+        });
+
+        assert_eq!(crate::json!(schedule), crate::json!({
+            "version": 2,
+            "producers": [{
+                "producer_name": "eosio",
+                "authority": ["block_signing_authority_v0", {
+                    "threshold": 1,
+                    "keys": [{"key": "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63", "weight": 1}],
+                }],
+            }],
+        }));
+
+        let bin = crate::to_bin(&schedule);
+        assert_eq!(&bin.to_hex()[..38], "02000000010000000000ea3055000100000001");
+        assert_eq!(ProducerAuthoritySchedule::from_bin(&mut ByteStream::from(&bin))?, schedule);
+        Ok(())
+    }
+
+    #[test]
+    fn test_signed_block_from_str() {
+        let json = r#"{
+            "timestamp": "2024-01-01T00:00:00.500",
+            "producer": "eosio",
+            "confirmed": 0,
+            "previous": "0000000267f3e2284b482f3afc2e724be1d6cbc1804532ec62d4e7af47c30693",
+            "transaction_mroot": "0000000000000000000000000000000000000000000000000000000000000000",
+            "action_mroot": "0000000000000000000000000000000000000000000000000000000000000000",
+            "schedule_version": 0,
+            "new_producers": null,
+            "header_extensions": [],
+            "producer_signature": "SIG_K1_K18qEA2qTqVj153ZKriMnnRwHpLuENX7bp9UYs5AJsRWhgD6diPgMeoebwRRFQuvyicDsgwVYTt3g4GsG5FxCXM3WNZVN7",
+            "transactions": [],
+            "block_extensions": []
+        }"#;
+
+        // the flattened headers only contain integers, which `serde_json` can read
+        // directly from a string even with the `arbitrary-precision` feature
+        let block = serde_json::from_str::<SignedBlock>(json).unwrap();
+        assert_eq!(block.block_num(), 3);
+        assert_eq!(crate::json::from_str::<SignedBlock>(json).unwrap(), block);
+    }
+}
@@ -0,0 +1,601 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Reader for the `blocks.log` and `blocks.index` files written by `nodeos`.
+//!
+//! This allows accessing the blocks of an archived block log without the need for a
+//! running node, e.g. for offline analytics.
+//!
+//! The format of a block log is as follows:
+//!  - a header containing the version of the log, the number of the first block in
+//!    the log and either the genesis state or the chain ID
+//!  - a sequence of entries, one per block, each of them followed by the position
+//!    (as an `u64`) of the start of the entry in the file
+//!
+//! The `blocks.index` file contains the position of each block entry in the log
+//! file, as a sequence of `u64`. If it is missing, it will be reconstructed in
+//! memory by walking the log file backwards.
+//!
+//! Supported versions:
+//!  - `1`: complete block log from genesis
+//!  - `2`: adds the number of the first block in the log, allowing partial logs
+//!  - `3`: contains either the genesis state or only the chain ID for partial logs
+//!  - `4`: entries are prefixed with some metadata (compression, size) and
+//!    contain blocks where the transactions can be pruned
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::chain::blocklog::{BlockLogReader, BlockLogError};
+//! # fn main() -> Result<(), BlockLogError> {
+//! let mut log = BlockLogReader::open("/path/to/nodeos/data/blocks")?;
+//! for block in log.blocks() {
+//!     let block = block?;
+//!     println!("block #{}: {} transactions", block.block_num(), block.transactions.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use snafu::{ResultExt, Snafu, ensure};
+
+use crate::{
+    ABISerializable, BlockNum, ByteStream, Bytes, ChainId, Digest, Extensions,
+    PublicKey, SerializeError, SerializeEnum, Signature, TimePoint, TransactionId,
+    VarUint32, with_location,
+};
+use crate::chain::block::{
+    PackedTransaction, ReceiptTransaction, SignedBlock, SignedBlockHeader,
+    TransactionReceipt, unpack_data,
+};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+const MIN_SUPPORTED_VERSION: u32 = 1;
+const MAX_SUPPORTED_VERSION: u32 = 4;
+
+/// value written after the header for versions >= 2
+const TOTEM: u64 = u64::MAX;
+
+/// max size of a block log header, this is more than enough to hold the version,
+/// first block num, genesis state and totem
+const MAX_HEADER_SIZE: u64 = 256;
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum BlockLogError {
+    #[snafu(display("I/O error while accessing file: {path:?}"))]
+    Io { path: PathBuf, source: io::Error },
+
+    #[snafu(display("unsupported block log version: {version}"))]
+    UnsupportedVersion { version: u32 },
+
+    #[snafu(display("invalid block log: {message}"))]
+    InvalidBlockLog { message: String },
+
+    #[snafu(display("block #{block_num} is not in the block log"))]
+    BlockNotFound { block_num: BlockNum },
+
+    #[snafu(display("cannot decode block #{block_num}"))]
+    Decode { block_num: BlockNum, source: SerializeError },
+}
+
+
+// -----------------------------------------------------------------------------
+//     Block log header
+// -----------------------------------------------------------------------------
+
+/// Chain configuration parameters, as they can be found in a genesis state.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct ChainConfig {
+    pub max_block_net_usage: u64,
+    pub target_block_net_usage_pct: u32,
+    pub max_transaction_net_usage: u32,
+    pub base_per_transaction_net_usage: u32,
+    pub net_usage_leeway: u32,
+    pub context_free_discount_net_usage_num: u32,
+    pub context_free_discount_net_usage_den: u32,
+    pub max_block_cpu_usage: u32,
+    pub target_block_cpu_usage_pct: u32,
+    pub max_transaction_cpu_usage: u32,
+    pub min_transaction_cpu_usage: u32,
+    pub max_transaction_lifetime: u32,
+    pub deferred_trx_expiration_window: u32,
+    pub max_transaction_delay: u32,
+    pub max_inline_action_size: u32,
+    pub max_inline_action_depth: u16,
+    pub max_authority_depth: u16,
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct GenesisState {
+    pub initial_timestamp: TimePoint,
+    pub initial_key: PublicKey,
+    pub initial_configuration: ChainConfig,
+}
+
+impl GenesisState {
+    pub fn chain_id(&self) -> ChainId {
+        let mut data = Bytes::new();
+        self.to_bin(&mut data);
+        let hash: [u8; 32] = Sha256::digest(&data).into();
+        hash.into()
+    }
+}
+
+/// A block log contains the genesis state if it starts at the first block of the
+/// chain, otherwise only the chain ID.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, SerializeEnum, ABISerializable)]
+pub enum ChainContext {
+    #[serde(rename="genesis_state")]
+    Genesis(GenesisState),
+
+    #[serde(rename="chain_id")]
+    ChainId(ChainId),
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+pub struct BlockLogHeader {
+    pub version: u32,
+    pub first_block_num: BlockNum,
+    pub chain_context: ChainContext,
+}
+
+impl BlockLogHeader {
+    pub fn chain_id(&self) -> ChainId {
+        match &self.chain_context {
+            ChainContext::Genesis(genesis) => genesis.chain_id(),
+            ChainContext::ChainId(chain_id) => chain_id.clone(),
+        }
+    }
+
+    /// Decode the header from the given stream, also returning its size in bytes.
+    fn decode(data: &[u8]) -> Result<(Self, usize), BlockLogError> {
+        let mut stream = ByteStream::from(data);
+        let decode_err = |source| InvalidBlockLogSnafu {
+            message: format!("cannot decode header: {source}")
+        }.build();
+
+        let version = u32::from_bin(&mut stream).map_err(decode_err)?;
+        ensure!((MIN_SUPPORTED_VERSION..=MAX_SUPPORTED_VERSION).contains(&version),
+                UnsupportedVersionSnafu { version });
+
+        let first_block_num = match version {
+            1 => 1,
+            _ => u32::from_bin(&mut stream).map_err(decode_err)?,
+        };
+
+        let chain_context = match version {
+            1 | 2 => ChainContext::Genesis(GenesisState::from_bin(&mut stream).map_err(decode_err)?),
+            _ => ChainContext::from_bin(&mut stream).map_err(decode_err)?,
+        };
+
+        if version > 1 {
+            let totem = u64::from_bin(&mut stream).map_err(decode_err)?;
+            ensure!(totem == TOTEM, InvalidBlockLogSnafu {
+                message: format!("invalid totem after header: {totem:#x}")
+            });
+        }
+
+        let size = data.len() - stream.leftover().len();
+        Ok((BlockLogHeader { version, first_block_num, chain_context }, size))
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Pruned blocks (block log version 4)
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, ABISerializable)]
+struct PrunableFullLegacy {
+    signatures: Vec<Signature>,
+    packed_context_free_data: Bytes,
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+struct PrunableNone {
+    #[allow(dead_code)]
+    prunable_digest: Digest,
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+enum ContextFreeSegment {
+    Digest(#[allow(dead_code)] Digest),
+    Bytes(Bytes),
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+struct PrunablePartial {
+    signatures: Vec<Signature>,
+    context_free_segments: Vec<ContextFreeSegment>,
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+struct PrunableFull {
+    signatures: Vec<Signature>,
+    context_free_segments: Vec<Bytes>,
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+enum PrunableData {
+    FullLegacy(PrunableFullLegacy),
+    None(PrunableNone),
+    Partial(PrunablePartial),
+    Full(PrunableFull),
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+struct PrunablePackedTransaction {
+    compression: u8,
+    prunable_data: PrunableData,
+    packed_trx: Bytes,
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+enum PrunableReceiptTransaction {
+    Id(TransactionId),
+    Packed(PrunablePackedTransaction),
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+struct PrunableTransactionReceipt {
+    status: u8,
+    cpu_usage_us: u32,
+    net_usage_words: VarUint32,
+    trx: PrunableReceiptTransaction,
+}
+
+#[derive(Debug, Clone, ABISerializable)]
+struct PrunableSignedBlock {
+    header: SignedBlockHeader,
+    #[allow(dead_code)]
+    prune_state: u8,
+    transactions: Vec<PrunableTransactionReceipt>,
+    block_extensions: Extensions,
+}
+
+impl From<PrunablePackedTransaction> for PackedTransaction {
+    /// Convert to the legacy packed transaction format. Pruned signatures and/or
+    /// context-free data will be missing from the result.
+    fn from(trx: PrunablePackedTransaction) -> PackedTransaction {
+        let pack_segments = |segments: Vec<Bytes>| {
+            if segments.is_empty() { return Bytes::new(); }
+            let mut data = Bytes::new();
+            segments.to_bin(&mut data);
+            data
+        };
+
+        let (signatures, packed_context_free_data) = match trx.prunable_data {
+            PrunableData::FullLegacy(data) => (data.signatures, data.packed_context_free_data),
+            PrunableData::None(_) => (vec![], Bytes::new()),
+            PrunableData::Partial(data) => {
+                // we can only restore the context-free data if none of the segments have been pruned
+                let segments: Option<Vec<Bytes>> = data.context_free_segments.into_iter()
+                    .map(|s| match s {
+                        ContextFreeSegment::Bytes(b) => Some(b),
+                        ContextFreeSegment::Digest(_) => None,
+                    })
+                    .collect();
+                (data.signatures, segments.map(pack_segments).unwrap_or_default())
+            },
+            PrunableData::Full(data) => (data.signatures, pack_segments(data.context_free_segments)),
+        };
+
+        PackedTransaction {
+            signatures,
+            compression: trx.compression,
+            packed_context_free_data,
+            packed_trx: trx.packed_trx,
+        }
+    }
+}
+
+impl From<PrunableSignedBlock> for SignedBlock {
+    fn from(block: PrunableSignedBlock) -> SignedBlock {
+        SignedBlock {
+            header: block.header,
+            transactions: block.transactions.into_iter().map(|r| TransactionReceipt {
+                status: r.status,
+                cpu_usage_us: r.cpu_usage_us,
+                net_usage_words: r.net_usage_words,
+                trx: match r.trx {
+                    PrunableReceiptTransaction::Id(id) => ReceiptTransaction::Id(id),
+                    PrunableReceiptTransaction::Packed(trx) => ReceiptTransaction::Packed(trx.into()),
+                },
+            }).collect(),
+            block_extensions: block.block_extensions,
+        }
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Block log reader
+// -----------------------------------------------------------------------------
+
+enum BlockIndex {
+    File { file: File, path: PathBuf, len: u32 },
+    Memory(Vec<u64>),
+}
+
+/// Read-only access to a `nodeos` block log.
+pub struct BlockLogReader {
+    log: File,
+    log_path: PathBuf,
+    log_size: u64,
+    header: BlockLogHeader,
+    index: BlockIndex,
+}
+
+impl BlockLogReader {
+    /// Open the `blocks.log` file (and `blocks.index` if it exists) in the given directory.
+    pub fn open<P: AsRef<Path>>(blocks_dir: P) -> Result<Self, BlockLogError> {
+        let blocks_dir = blocks_dir.as_ref();
+        let index_path = blocks_dir.join("blocks.index");
+        let index_path = if index_path.exists() { Some(index_path) } else { None };
+        Self::from_files(blocks_dir.join("blocks.log"), index_path)
+    }
+
+    /// Open the given block log file, with an optional index file. If no index file
+    /// is given, the index will be reconstructed in memory.
+    pub fn from_files<P: AsRef<Path>>(log_path: P, index_path: Option<P>) -> Result<Self, BlockLogError> {
+        let log_path = log_path.as_ref().to_path_buf();
+        let mut log = File::open(&log_path).context(IoSnafu { path: &log_path })?;
+        let log_size = log.metadata().context(IoSnafu { path: &log_path })?.len();
+
+        let mut data = vec![];
+        (&mut log).take(MAX_HEADER_SIZE).read_to_end(&mut data).context(IoSnafu { path: &log_path })?;
+        let (header, header_size) = BlockLogHeader::decode(&data)?;
+
+        let index = match index_path {
+            Some(index_path) => {
+                let path = index_path.as_ref().to_path_buf();
+                let file = File::open(&path).context(IoSnafu { path: &path })?;
+                let size = file.metadata().context(IoSnafu { path: &path })?.len();
+                ensure!(size % 8 == 0, InvalidBlockLogSnafu {
+                    message: format!("size of index file is not a multiple of 8: {size}")
+                });
+                BlockIndex::File { file, path, len: (size / 8) as u32 }
+            },
+            None => {
+                let positions = reconstruct_index(&mut log, &log_path, header_size as u64, log_size)?;
+                BlockIndex::Memory(positions)
+            },
+        };
+
+        Ok(BlockLogReader { log, log_path, log_size, header, index })
+    }
+
+    pub fn header(&self) -> &BlockLogHeader {
+        &self.header
+    }
+
+    pub fn version(&self) -> u32 {
+        self.header.version
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.header.chain_id()
+    }
+
+    pub fn first_block_num(&self) -> BlockNum {
+        self.header.first_block_num
+    }
+
+    /// Return the number of the last block in the log, or `None` if it doesn't contain any block.
+    pub fn last_block_num(&self) -> Option<BlockNum> {
+        match self.num_blocks() {
+            0 => None,
+            n => Some(self.first_block_num() + n - 1),
+        }
+    }
+
+    pub fn num_blocks(&self) -> u32 {
+        match &self.index {
+            BlockIndex::File { len, .. } => *len,
+            BlockIndex::Memory(positions) => positions.len() as u32,
+        }
+    }
+
+    /// Return the position of the `n`-th entry in the log file.
+    fn position(&mut self, n: u32) -> Result<u64, BlockLogError> {
+        match &mut self.index {
+            BlockIndex::File { file, path, .. } => {
+                let mut pos = [0u8; 8];
+                file.seek(SeekFrom::Start(n as u64 * 8)).context(IoSnafu { path: &*path })?;
+                file.read_exact(&mut pos).context(IoSnafu { path: &*path })?;
+                Ok(u64::from_le_bytes(pos))
+            },
+            BlockIndex::Memory(positions) => Ok(positions[n as usize]),
+        }
+    }
+
+    /// Return the raw bytes of the log entry for the given block, without the
+    /// trailing position.
+    pub fn read_entry_bytes(&mut self, block_num: BlockNum) -> Result<Vec<u8>, BlockLogError> {
+        ensure!(block_num >= self.first_block_num() && block_num - self.first_block_num() < self.num_blocks(),
+                BlockNotFoundSnafu { block_num });
+
+        let n = block_num - self.first_block_num();
+        let start = self.position(n)?;
+        let end = match n + 1 == self.num_blocks() {
+            true => self.log_size,
+            false => self.position(n + 1)?,
+        };
+        ensure!(start < end && end - start >= 8 && end <= self.log_size, InvalidBlockLogSnafu {
+            message: format!("invalid entry position for block #{block_num}: [{start}..{end}]")
+        });
+
+        let mut data = vec![0u8; (end - start - 8) as usize];
+        let path = &self.log_path;
+        self.log.seek(SeekFrom::Start(start)).context(IoSnafu { path })?;
+        self.log.read_exact(&mut data).context(IoSnafu { path })?;
+        Ok(data)
+    }
+
+    /// Read and decode the given block.
+    pub fn read_block(&mut self, block_num: BlockNum) -> Result<SignedBlock, BlockLogError> {
+        let data = self.read_entry_bytes(block_num)?;
+        decode_entry(self.version(), &data).context(DecodeSnafu { block_num })
+    }
+
+    /// Return an iterator over all the blocks in the log.
+    pub fn blocks(&mut self) -> Blocks<'_> {
+        let start = self.first_block_num();
+        self.blocks_from(start)
+    }
+
+    /// Return an iterator over all the blocks in the log, starting at the given block.
+    pub fn blocks_from(&mut self, block_num: BlockNum) -> Blocks<'_> {
+        Blocks { reader: self, next: block_num }
+    }
+}
+
+/// Decode a block log entry (without the trailing position) according to the
+/// given block log version.
+fn decode_entry(version: u32, data: &[u8]) -> Result<SignedBlock, SerializeError> {
+    let mut stream = ByteStream::from(data);
+    if version < 4 {
+        return SignedBlock::from_bin(&mut stream);
+    }
+
+    let compression = u8::from_bin(&mut stream)?;
+    let _size = u32::from_bin(&mut stream)?;
+    let block = unpack_data(compression, stream.leftover())?;
+    let mut stream = ByteStream::from(&block);
+    Ok(PrunableSignedBlock::from_bin(&mut stream)?.into())
+}
+
+/// Reconstruct the index of a block log by walking it backwards, following the
+/// position written after each entry.
+fn reconstruct_index(log: &mut File, path: &Path, first_entry_pos: u64, log_size: u64) -> Result<Vec<u64>, BlockLogError> {
+    let mut positions = vec![];
+    let mut end = log_size;
+
+    while end > first_entry_pos {
+        ensure!(end >= first_entry_pos + 8, InvalidBlockLogSnafu {
+            message: format!("truncated entry at position: {end}")
+        });
+        let mut pos = [0u8; 8];
+        log.seek(SeekFrom::Start(end - 8)).context(IoSnafu { path })?;
+        log.read_exact(&mut pos).context(IoSnafu { path })?;
+        let pos = u64::from_le_bytes(pos);
+        ensure!(pos >= first_entry_pos && pos < end - 8, InvalidBlockLogSnafu {
+            message: format!("invalid entry position {pos} found at offset {}", end - 8)
+        });
+        positions.push(pos);
+        end = pos;
+    }
+
+    positions.reverse();
+    Ok(positions)
+}
+
+/// Iterator over the blocks of a [`BlockLogReader`].
+pub struct Blocks<'a> {
+    reader: &'a mut BlockLogReader,
+    next: BlockNum,
+}
+
+impl Iterator for Blocks<'_> {
+    type Item = Result<SignedBlock, BlockLogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.reader.last_block_num()?;
+        if self.next > last { return None; }
+        let result = self.reader.read_block(self.next);
+        self.next += 1;
+        Some(result)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use color_eyre::eyre::Result;
+
+    use super::*;
+    use crate::{AccountName, BlockHeader, BlockId, PrivateKey};
+
+    fn make_block(num: BlockNum) -> SignedBlock {
+        let mut previous = BlockId::default();
+        previous.0[..4].copy_from_slice(&(num - 1).to_be_bytes());
+        let header = BlockHeader {
+            producer: AccountName::constant("eosio"),
+            previous,
+            ..Default::default()
+        };
+        let producer_signature = PrivateKey::eosio_dev().sign_digest(header.digest());
+        SignedBlock {
+            header: SignedBlockHeader { header, producer_signature },
+            transactions: vec![TransactionReceipt {
+                status: 0,
+                cpu_usage_us: 100,
+                net_usage_words: VarUint32(12),
+                trx: ReceiptTransaction::Id(TransactionId::default()),
+            }],
+            block_extensions: vec![],
+        }
+    }
+
+    fn write_block_log(dir: &Path, first_block_num: BlockNum, blocks: &[SignedBlock]) -> Result<()> {
+        let mut log = Bytes::new();
+        let mut index = Bytes::new();
+        3u32.to_bin(&mut log);
+        first_block_num.to_bin(&mut log);
+        ChainContext::ChainId(ChainId::default()).to_bin(&mut log);
+        TOTEM.to_bin(&mut log);
+
+        for block in blocks {
+            let pos = log.as_bytes().len() as u64;
+            block.to_bin(&mut log);
+            pos.to_bin(&mut log);
+            pos.to_bin(&mut index);
+        }
+
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("blocks.log"), log.as_bytes())?;
+        fs::write(dir.join("blocks.index"), index.as_bytes())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_block_log() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("kudu_blocklog_test_{}", std::process::id()));
+        let blocks: Vec<_> = (10..15).map(make_block).collect();
+        write_block_log(&dir, 10, &blocks)?;
+
+        let mut log = BlockLogReader::open(&dir)?;
+        assert_eq!(log.version(), 3);
+        assert_eq!(log.first_block_num(), 10);
+        assert_eq!(log.last_block_num(), Some(14));
+        assert_eq!(log.read_block(12)?, blocks[2]);
+        assert!(log.read_block(15).is_err());
+
+        let read: Vec<_> = log.blocks().collect::<Result<_, _>>()?;
+        assert_eq!(read, blocks);
+
+        // remove the index file, it should be reconstructed when opening the log
+        fs::remove_file(dir.join("blocks.index"))?;
+        let mut log = BlockLogReader::open(&dir)?;
+        assert_eq!(log.num_blocks(), 5);
+        let read: Vec<_> = log.blocks_from(13).collect::<Result<_, _>>()?;
+        assert_eq!(read, blocks[3..]);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
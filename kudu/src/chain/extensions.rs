@@ -0,0 +1,439 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Typed support for transaction and block header extensions
+//!
+//! Extensions are stored as a list of `(id, bytes)` pairs (see [`Extensions`]) whose
+//! content depends on the id and on where the extension is found (transaction, block
+//! header or block). The types defined here can be extracted from such a list using
+//! [`get_extension()`], or the whole list can be decoded using an [`ExtensionRegistry`],
+//! in which case unknown extensions are kept as raw bytes so they can round-trip.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use snafu::{ResultExt, Snafu, ensure};
+
+use crate::{
+    ABISerializable, AccountName, Bytes, ByteStream, Checksum256, Extensions, FinalizerPolicyDiff,
+    JsonValue, ProducerAuthoritySchedule, ProposerPolicyDiff, Qc, QcClaim, SerializeError,
+    Signature, TransactionId, with_location,
+    abiserializable::InvalidDataSnafu, json,
+};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+/// A type which can be stored in an [`Extensions`] list under the given `ID`.
+pub trait ExtensionType: ABISerializable + Serialize + DeserializeOwned {
+    const ID: u16;
+    const NAME: &'static str;
+}
+
+
+// -----------------------------------------------------------------------------
+//     Transaction extensions
+// -----------------------------------------------------------------------------
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/transaction.hpp
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct DeferredTransactionGenerationContext {
+    pub sender_trx_id: TransactionId,
+    #[serde(with = "crate::convert::serde_int128")]
+    pub sender_id: u128,
+    pub sender: AccountName,
+}
+
+impl ExtensionType for DeferredTransactionGenerationContext {
+    const ID: u16 = 0;
+    const NAME: &'static str = "deferred_transaction_generation_context";
+}
+
+/// Specify an account other than the authorizers of a transaction that pays for its
+/// resources, along with the maximum amounts of resources it is willing to pay for.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct ResourcePayer {
+    pub payer: AccountName,
+    pub max_net_bytes: u64,
+    pub max_cpu_us: u64,
+    pub max_memory_bytes: u64,
+}
+
+impl ExtensionType for ResourcePayer {
+    const ID: u16 = 1;
+    const NAME: &'static str = "resource_payer";
+}
+
+
+// -----------------------------------------------------------------------------
+//     Block header extensions
+// -----------------------------------------------------------------------------
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/protocol_feature_activation.hpp
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct ProtocolFeatureActivation {
+    pub protocol_features: Vec<Checksum256>,
+}
+
+impl ExtensionType for ProtocolFeatureActivation {
+    const ID: u16 = 0;
+    const NAME: &'static str = "protocol_feature_activation";
+}
+
+/// New producer schedule, replacing the legacy `new_producers` field of the block header.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct ProducerScheduleChangeExtension {
+    #[kudu(base)]
+    #[serde(flatten)]
+    pub schedule: ProducerAuthoritySchedule,
+}
+
+impl ExtensionType for ProducerScheduleChangeExtension {
+    const ID: u16 = 1;
+    const NAME: &'static str = "producer_schedule_change_extension";
+}
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/finality/finality_extension.hpp
+
+/// Finality information contained in the header of each block once Savanna is active.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct FinalityExtension {
+    pub qc_claim: QcClaim,
+    pub new_finalizer_policy_diff: Option<FinalizerPolicyDiff>,
+    pub new_proposer_policy_diff: Option<ProposerPolicyDiff>,
+}
+
+impl ExtensionType for FinalityExtension {
+    const ID: u16 = 2;
+    const NAME: &'static str = "finality_extension";
+}
+
+
+// -----------------------------------------------------------------------------
+//     Block extensions
+// -----------------------------------------------------------------------------
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/block.hpp
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct AdditionalBlockSignaturesExtension {
+    pub signatures: Vec<Signature>,
+}
+
+impl ExtensionType for AdditionalBlockSignaturesExtension {
+    const ID: u16 = 2;
+    const NAME: &'static str = "additional_block_signatures_extension";
+}
+
+/// Quorum certificate for the block claimed in the [`FinalityExtension`] of the block header.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct QuorumCertificateExtension {
+    pub qc: Qc,
+}
+
+impl ExtensionType for QuorumCertificateExtension {
+    const ID: u16 = 3;
+    const NAME: &'static str = "quorum_certificate_extension";
+}
+
+
+// -----------------------------------------------------------------------------
+//     Accessing typed extensions
+// -----------------------------------------------------------------------------
+
+/// Return the first extension of type `T` in the given list, or `None` if there is none.
+pub fn get_extension<T: ExtensionType>(extensions: &Extensions) -> Result<Option<T>, SerializeError> {
+    extensions.iter()
+        .find(|(id, _)| *id == T::ID)
+        .map(|(_, data)| decode_exact(data.as_ref()))
+        .transpose()
+}
+
+/// Set the extension of type `T` in the given list, replacing a previous one if it
+/// exists. Extensions are kept sorted by id, as required by `nodeos`.
+pub fn set_extension<T: ExtensionType>(extensions: &mut Extensions, value: &T) {
+    let data = kudu::to_bin(value);
+    match extensions.iter_mut().find(|(id, _)| *id == T::ID) {
+        Some(ext) => ext.1 = data,
+        None => {
+            let pos = extensions.partition_point(|(id, _)| *id <= T::ID);
+            extensions.insert(pos, (T::ID, data));
+        },
+    }
+}
+
+fn decode_exact<T: ABISerializable>(data: &[u8]) -> Result<T, SerializeError> {
+    let mut stream = ByteStream::from(data);
+    let result = T::from_bin(&mut stream)?;
+    ensure!(stream.leftover().is_empty(), InvalidDataSnafu {
+        message: format!("{} bytes left in stream after decoding extension", stream.leftover().len())
+    });
+    Ok(result)
+}
+
+
+// -----------------------------------------------------------------------------
+//     Typed extension lists
+// -----------------------------------------------------------------------------
+
+macro_rules! typed_extensions {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident($typ:ty)),* $(,)? }) => {
+        $(#[$meta])*
+        ///
+        /// Known extensions are boxed, as some of them are quite big.
+        #[derive(Eq, PartialEq, Debug, Clone)]
+        pub enum $name {
+            $( $variant(Box<$typ>), )*
+            /// An extension with an unknown id, kept as is so it can round-trip.
+            Unknown { id: u16, data: Bytes },
+        }
+
+        impl $name {
+            /// Decode the extension with the given id and data.
+            pub fn decode(id: u16, data: &Bytes) -> Result<Self, SerializeError> {
+                Ok(match id {
+                    $( <$typ>::ID => Self::$variant(Box::new(decode_exact(data.as_ref())?)), )*
+                    _ => Self::Unknown { id, data: data.clone() },
+                })
+            }
+
+            /// Decode all the extensions of the given list, in order.
+            pub fn decode_all(extensions: &Extensions) -> Result<Vec<Self>, SerializeError> {
+                extensions.iter().map(|(id, data)| Self::decode(*id, data)).collect()
+            }
+
+            pub fn id(&self) -> u16 {
+                match self {
+                    $( Self::$variant(_) => <$typ>::ID, )*
+                    Self::Unknown { id, .. } => *id,
+                }
+            }
+
+            /// Return the `(id, data)` pair representing this extension in an [`Extensions`] list.
+            pub fn encode(&self) -> (u16, Bytes) {
+                match self {
+                    $( Self::$variant(ext) => (<$typ>::ID, kudu::to_bin(ext.as_ref())), )*
+                    Self::Unknown { id, data } => (*id, data.clone()),
+                }
+            }
+        }
+    }
+}
+
+typed_extensions! {
+    /// A block header extension, as returned by [`BlockHeader::decode_extensions()`].
+    ///
+    /// [`BlockHeader::decode_extensions()`]: crate::BlockHeader::decode_extensions
+    BlockHeaderExtension {
+        ProtocolFeatureActivation(ProtocolFeatureActivation),
+        ProducerScheduleChange(ProducerScheduleChangeExtension),
+        Finality(FinalityExtension),
+    }
+}
+
+typed_extensions! {
+    /// A block extension, as returned by [`SignedBlock::decode_extensions()`].
+    ///
+    /// [`SignedBlock::decode_extensions()`]: crate::SignedBlock::decode_extensions
+    BlockExtension {
+        AdditionalBlockSignatures(AdditionalBlockSignaturesExtension),
+        QuorumCertificate(QuorumCertificateExtension),
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Extension registry
+// -----------------------------------------------------------------------------
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum ExtensionError {
+    #[snafu(display("cannot decode extension `{name}` with id {id}"))]
+    Decode { id: u16, name: String, source: SerializeError },
+
+    #[snafu(display("cannot convert JSON value to extension `{name}` with id {id}"))]
+    FromJson { id: u16, name: String, source: serde_json::Error },
+
+    #[snafu(display("no registered extension with id {id} and name `{name}`"))]
+    Unregistered { id: u16, name: String },
+}
+
+/// An extension as decoded by an [`ExtensionRegistry`].
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DecodedExtension {
+    /// An extension with a registered type, with its value as JSON.
+    Known { id: u16, name: String, value: JsonValue },
+    /// An extension without a registered type, kept as is.
+    Raw { id: u16, data: Bytes },
+}
+
+impl DecodedExtension {
+    pub fn id(&self) -> u16 {
+        match self {
+            DecodedExtension::Known { id, .. } => *id,
+            DecodedExtension::Raw { id, .. } => *id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ExtensionCodec {
+    name: &'static str,
+    decode: fn(&[u8]) -> Result<JsonValue, SerializeError>,
+    encode: fn(&JsonValue) -> Result<Bytes, serde_json::Error>,
+}
+
+/// Registry of the known extension types for a given extensions list, keyed by id.
+///
+/// Use [`ExtensionRegistry::transaction()`] or [`ExtensionRegistry::block_header()`]
+/// to get a registry with the extensions known to `nodeos`, and [`ExtensionRegistry::register()`]
+/// to add your own.
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionRegistry {
+    codecs: HashMap<u16, ExtensionCodec>,
+}
+
+impl ExtensionRegistry {
+    /// Return an empty registry, which decodes all extensions as raw.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a registry with all known transaction extensions.
+    pub fn transaction() -> Self {
+        let mut result = Self::new();
+        result.register::<DeferredTransactionGenerationContext>();
+        result.register::<ResourcePayer>();
+        result
+    }
+
+    /// Return a registry with all known block header extensions.
+    pub fn block_header() -> Self {
+        let mut result = Self::new();
+        result.register::<ProtocolFeatureActivation>();
+        result.register::<ProducerScheduleChangeExtension>();
+        result.register::<FinalityExtension>();
+        result
+    }
+
+    /// Return a registry with all known block extensions.
+    pub fn block() -> Self {
+        let mut result = Self::new();
+        result.register::<AdditionalBlockSignaturesExtension>();
+        result.register::<QuorumCertificateExtension>();
+        result
+    }
+
+    /// Register the type `T`, replacing any type previously registered with the same id.
+    pub fn register<T: ExtensionType>(&mut self) -> &mut Self {
+        self.codecs.insert(T::ID, ExtensionCodec {
+            name: T::NAME,
+            decode: |data| Ok(json!(decode_exact::<T>(data)?)),
+            encode: |value| Ok(kudu::to_bin(&T::deserialize(value)?)),
+        });
+        self
+    }
+
+    pub fn name(&self, id: u16) -> Option<&'static str> {
+        self.codecs.get(&id).map(|c| c.name)
+    }
+
+    pub fn decode(&self, extensions: &Extensions) -> Result<Vec<DecodedExtension>, ExtensionError> {
+        extensions.iter().map(|(id, data)| {
+            Ok(match self.codecs.get(id) {
+                Some(codec) => DecodedExtension::Known {
+                    id: *id,
+                    name: codec.name.to_string(),
+                    value: (codec.decode)(data.as_ref()).context(DecodeSnafu { id: *id, name: codec.name })?,
+                },
+                None => DecodedExtension::Raw { id: *id, data: data.clone() },
+            })
+        }).collect()
+    }
+
+    pub fn encode(&self, extensions: &[DecodedExtension]) -> Result<Extensions, ExtensionError> {
+        extensions.iter().map(|ext| {
+            Ok(match ext {
+                DecodedExtension::Known { id, name, value } => {
+                    let codec = self.codecs.get(id).filter(|c| c.name == name)
+                        .ok_or_else(|| UnregisteredSnafu { id: *id, name }.build())?;
+                    (*id, (codec.encode)(value).context(FromJsonSnafu { id: *id, name })?)
+                },
+                DecodedExtension::Raw { id, data } => (*id, data.clone()),
+            })
+        }).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_extension_registry() -> Result<()> {
+        let payer = ResourcePayer {
+            payer: AccountName::new("alice")?,
+            max_net_bytes: 4096,
+            max_cpu_us: 400,
+            max_memory_bytes: 0,
+        };
+
+        let mut extensions: Extensions = vec![(42, Bytes::from(&[1, 2, 3]))];
+        set_extension(&mut extensions, &payer);
+        assert_eq!(extensions.iter().map(|e| e.0).collect::<Vec<_>>(), [1, 42]);
+        assert_eq!(get_extension::<ResourcePayer>(&extensions)?, Some(payer));
+        assert_eq!(get_extension::<DeferredTransactionGenerationContext>(&extensions)?, None);
+
+        let registry = ExtensionRegistry::transaction();
+        let decoded = registry.decode(&extensions)?;
+        assert_eq!(json!(decoded), json!([
+            {"id": 1, "name": "resource_payer", "value": {
+                "payer": "alice", "max_net_bytes": 4096, "max_cpu_us": 400, "max_memory_bytes": 0
+            }},
+            {"id": 42, "data": "010203"},
+        ]));
+        assert_eq!(registry.encode(&decoded)?, extensions);
+
+        // unknown extensions are kept raw
+        let decoded = ExtensionRegistry::new().decode(&extensions)?;
+        assert!(decoded.iter().all(|ext| matches!(ext, DecodedExtension::Raw { .. })));
+        assert_eq!(ExtensionRegistry::new().encode(&decoded)?, extensions);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_extensions() -> Result<()> {
+        let activation = ProtocolFeatureActivation { protocol_features: vec![Checksum256::default()] };
+        let finality = FinalityExtension::default();
+
+        let mut extensions: Extensions = vec![(42, Bytes::from(&[1, 2, 3]))];
+        set_extension(&mut extensions, &activation);
+        set_extension(&mut extensions, &finality);
+
+        let decoded = BlockHeaderExtension::decode_all(&extensions)?;
+        assert_eq!(decoded, [
+            BlockHeaderExtension::ProtocolFeatureActivation(Box::new(activation)),
+            BlockHeaderExtension::Finality(Box::new(finality)),
+            BlockHeaderExtension::Unknown { id: 42, data: Bytes::from(&[1, 2, 3]) },
+        ]);
+        assert_eq!(decoded.iter().map(BlockHeaderExtension::id).collect::<Vec<_>>(), [0, 2, 42]);
+        assert_eq!(decoded.iter().map(BlockHeaderExtension::encode).collect::<Extensions>(), extensions);
+
+        // the same ids have a different meaning in block extensions
+        let decoded = BlockExtension::decode_all(&extensions[..1].to_vec())?;
+        assert_eq!(decoded, [BlockExtension::Unknown { id: 0, data: extensions[0].1.clone() }]);
+        assert!(BlockExtension::decode_all(&extensions).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Types used by the Savanna instant finality algorithm, introduced in Spring 1.0
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ABISerializable, BlockNum, BlockTimestamp, BlsPublicKey, BlsSignature, ProducerAuthority};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+// -----------------------------------------------------------------------------
+//     Finalizer policy
+// -----------------------------------------------------------------------------
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/finalizer_authority.hpp
+//       https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/finalizer_policy.hpp
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct FinalizerAuthority {
+    /// Arbitrary description of the finalizer, for informational purposes only
+    pub description: String,
+    pub weight: u64,
+    pub public_key: BlsPublicKey,
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct FinalizerPolicy {
+    /// Sequentially incrementing version number
+    pub generation: u32,
+    /// Vote weight threshold to finalize blocks
+    pub threshold: u64,
+    pub finalizers: Vec<FinalizerAuthority>,
+}
+
+/// Difference between two ordered lists, as computed by `fc::ordered_diff`: first
+/// remove the elements at `remove_indexes`, then insert the elements of
+/// `insert_indexes` at the given positions.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct OrderedDiff<T: ABISerializable> {
+    pub remove_indexes: Vec<u16>,
+    pub insert_indexes: Vec<(u16, T)>,
+}
+
+impl<T: ABISerializable> Default for OrderedDiff<T> {
+    fn default() -> Self {
+        OrderedDiff { remove_indexes: vec![], insert_indexes: vec![] }
+    }
+}
+
+impl<T: ABISerializable + Clone> OrderedDiff<T> {
+    /// Apply this diff to the given list and return the resulting list, or `None`
+    /// if an index is out of bounds.
+    pub fn apply(&self, container: &[T]) -> Option<Vec<T>> {
+        let mut result = container.to_vec();
+        // indexes to remove are sorted in increasing order, remove from the end
+        for &idx in self.remove_indexes.iter().rev() {
+            let idx = idx as usize;
+            if idx >= result.len() { return None; }
+            result.remove(idx);
+        }
+        for (idx, value) in self.insert_indexes.iter() {
+            let idx = *idx as usize;
+            if idx > result.len() { return None; }
+            result.insert(idx, value.clone());
+        }
+        Some(result)
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct FinalizerPolicyDiff {
+    pub generation: u32,
+    pub threshold: u64,
+    pub finalizers_diff: OrderedDiff<FinalizerAuthority>,
+}
+
+impl FinalizerPolicyDiff {
+    /// Return the new finalizer policy obtained by applying this diff to `policy`.
+    pub fn apply(&self, policy: &FinalizerPolicy) -> Option<FinalizerPolicy> {
+        Some(FinalizerPolicy {
+            generation: self.generation,
+            threshold: self.threshold,
+            finalizers: self.finalizers_diff.apply(&policy.finalizers)?,
+        })
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Proposer policy
+// -----------------------------------------------------------------------------
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/proposer_policy.hpp
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct ProposerPolicyDiff {
+    pub version: u32,
+    /// Time when the proposer policy was proposed
+    pub proposal_time: BlockTimestamp,
+    pub producer_auth_diff: OrderedDiff<ProducerAuthority>,
+}
+
+
+// -----------------------------------------------------------------------------
+//     Quorum certificates
+// -----------------------------------------------------------------------------
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/finality/qc.hpp
+
+/// Claim made by a block about the best QC it has seen.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, Default, Serialize, Deserialize, ABISerializable)]
+pub struct QcClaim {
+    pub block_num: BlockNum,
+    pub is_strong_qc: bool,
+}
+
+/// Aggregated signature of the finalizers of a given policy.
+///
+/// The votes are bitsets (`boost::dynamic_bitset<uint32_t>`) giving which finalizers
+/// voted, represented by their underlying blocks of 32 bits.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct QcSig {
+    pub strong_votes: Option<Vec<u32>>,
+    pub weak_votes: Option<Vec<u32>>,
+    pub sig: BlsSignature,
+}
+
+impl QcSig {
+    pub fn is_strong(&self) -> bool {
+        self.weak_votes.is_none()
+    }
+}
+
+/// Quorum certificate for a given block, signed by the finalizers of the active
+/// policy and, during a policy transition, of the pending policy.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct Qc {
+    pub block_num: BlockNum,
+    pub active_policy_sig: QcSig,
+    pub pending_policy_sig: Option<QcSig>,
+}
+
+impl Qc {
+    pub fn is_strong(&self) -> bool {
+        self.active_policy_sig.is_strong()
+            && self.pending_policy_sig.as_ref().is_none_or(|sig| sig.is_strong())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use super::*;
+    use crate::to_bin;
+
+    fn finalizer(n: u8) -> FinalizerAuthority {
+        FinalizerAuthority {
+            description: format!("finalizer{n}"),
+            weight: 1,
+            public_key: BlsPublicKey([n; 96]),
+        }
+    }
+
+    #[test]
+    fn test_finalizer_policy_diff() -> Result<()> {
+        let policy = FinalizerPolicy {
+            generation: 1,
+            threshold: 2,
+            finalizers: vec![finalizer(1), finalizer(2), finalizer(3)],
+        };
+        let diff = FinalizerPolicyDiff {
+            generation: 2,
+            threshold: 3,
+            finalizers_diff: OrderedDiff {
+                remove_indexes: vec![0, 2],
+                insert_indexes: vec![(0, finalizer(4)), (2, finalizer(5)), (3, finalizer(6))],
+            },
+        };
+        let new_policy = diff.apply(&policy).unwrap();
+        assert_eq!(new_policy.finalizers, [finalizer(4), finalizer(2), finalizer(5), finalizer(6)]);
+
+        // binary format is the one of `fc::ordered_diff`
+        let diff = OrderedDiff { remove_indexes: vec![1], insert_indexes: vec![(258, 7u8)] };
+        assert_eq!(to_bin(&diff).to_hex(), "01010001020107");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Backfilling of chain history over a range of blocks, with checkpointing.
+//!
+//! [`HistorySync`] drives a [`BlockSource`] (eg: a state history (SHiP) client, or
+//! a [`BlockLogReader`] for archived blocks) across a block range and delivers the
+//! blocks in batches to a handler, which is the scaffolding every indexer needs:
+//!  - progress is saved in a [`CheckpointStore`] after each batch has been handled,
+//!    so that a sync can be resumed after a crash without losing or re-processing
+//!    any batch
+//!  - blocks are fetched in a background thread, but only a bounded number of
+//!    batches can be pending at any given time, so that fetching is paused when
+//!    the handler cannot keep up (backpressure)
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::chain::blocklog::BlockLogReader;
+//! # use kudu::chain::history::{FileCheckpointStore, HistorySync};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let log = BlockLogReader::open("/path/to/nodeos/data/blocks")?;
+//! let store = FileCheckpointStore::new("/path/to/indexer/checkpoint.json");
+//!
+//! let mut sync = HistorySync::new(log, store, 1..1_000_000).batch_size(500);
+//! sync.run(|batch| {
+//!     println!("indexing blocks #{} to #{}", batch.first_block_num(), batch.last_block_num());
+//!     Ok::<(), std::io::Error>(())
+//! })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BlockLogReader`]: crate::chain::blocklog::BlockLogReader
+//!
+
+use std::convert::Infallible;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+use snafu::{Snafu, ResultExt, ensure};
+use tracing::debug;
+
+use kudu_macros::with_location;
+
+use crate::{BlockNum, SignedBlock};
+use crate::chain::blocklog::{BlockLogError, BlockLogReader};
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum HistoryError {
+    #[snafu(display("invalid block range: {start}..{end}"))]
+    InvalidRange { start: BlockNum, end: BlockNum },
+
+    #[snafu(display("cannot fetch block #{block_num}"))]
+    Source { block_num: BlockNum, source: Box<dyn Error + Send + Sync> },
+
+    #[snafu(display("cannot access checkpoint store"))]
+    Checkpoint { source: Box<dyn Error + Send + Sync> },
+
+    #[snafu(display("error while handling blocks #{first_block_num} to #{last_block_num}"))]
+    Handler {
+        first_block_num: BlockNum,
+        last_block_num: BlockNum,
+        source: Box<dyn Error + Send + Sync>,
+    },
+}
+
+
+// -----------------------------------------------------------------------------
+//     Block sources
+// -----------------------------------------------------------------------------
+
+/// A source of blocks that can be fetched by block number.
+pub trait BlockSource {
+    type Block;
+    type Error: Error + Send + Sync + 'static;
+
+    fn fetch_block(&mut self, block_num: BlockNum) -> Result<Self::Block, Self::Error>;
+}
+
+impl BlockSource for BlockLogReader {
+    type Block = SignedBlock;
+    type Error = BlockLogError;
+
+    fn fetch_block(&mut self, block_num: BlockNum) -> Result<SignedBlock, BlockLogError> {
+        self.read_block(block_num)
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Checkpoint stores
+// -----------------------------------------------------------------------------
+
+/// Persistent storage of the last block that has been handled by a [`HistorySync`].
+pub trait CheckpointStore {
+    type Error: Error + Send + Sync + 'static;
+
+    /// Return the number of the last block that has been handled, if any.
+    fn load(&mut self) -> Result<Option<BlockNum>, Self::Error>;
+
+    /// Save the number of the last block that has been handled.
+    fn save(&mut self, block_num: BlockNum) -> Result<(), Self::Error>;
+}
+
+/// Checkpoint store keeping the checkpoint in memory, mostly useful for tests.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryCheckpointStore {
+    pub checkpoint: Option<BlockNum>,
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    type Error = Infallible;
+
+    fn load(&mut self) -> Result<Option<BlockNum>, Infallible> {
+        Ok(self.checkpoint)
+    }
+
+    fn save(&mut self, block_num: BlockNum) -> Result<(), Infallible> {
+        self.checkpoint = Some(block_num);
+        Ok(())
+    }
+}
+
+/// Checkpoint store keeping the checkpoint in a JSON file.
+///
+/// The file is written atomically, so that it is never left in a corrupted
+/// state if the process crashes while saving a checkpoint.
+#[derive(Clone, Debug)]
+pub struct FileCheckpointStore {
+    pub path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileCheckpointStore { path: path.into() }
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    type Error = io::Error;
+
+    fn load(&mut self) -> Result<Option<BlockNum>, io::Error> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let checkpoint: serde_json::Value = serde_json::from_str(&contents)?;
+        checkpoint["block_num"].as_u64()
+            .and_then(|n| BlockNum::try_from(n).ok())
+            .map(Some)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData,
+                                          format!("invalid checkpoint file: {contents}")))
+    }
+
+    fn save(&mut self, block_num: BlockNum) -> Result<(), io::Error> {
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::json!({ "block_num": block_num }).to_string())?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     HistorySync
+// -----------------------------------------------------------------------------
+
+/// A batch of consecutive blocks, as delivered by a [`HistorySync`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Batch<B> {
+    pub blocks: Vec<B>,
+    first_block_num: BlockNum,
+}
+
+impl<B> Batch<B> {
+    pub fn first_block_num(&self) -> BlockNum {
+        self.first_block_num
+    }
+
+    /// Return the number of the last block in this batch, batches are never empty.
+    pub fn last_block_num(&self) -> BlockNum {
+        self.first_block_num + self.blocks.len() as BlockNum - 1
+    }
+
+    /// Iterate over the blocks of this batch with their block number.
+    pub fn iter(&self) -> impl Iterator<Item = (BlockNum, &B)> {
+        (self.first_block_num..).zip(&self.blocks)
+    }
+}
+
+/// Fetch all the blocks in a given range from a [`BlockSource`] and deliver them
+/// in batches to a handler, saving progress in a [`CheckpointStore`].
+///
+/// See the [module documentation](self) for more details.
+pub struct HistorySync<S, C> {
+    source: S,
+    store: C,
+    range: Range<BlockNum>,
+    batch_size: usize,
+    max_pending_batches: usize,
+}
+
+impl<S, C> HistorySync<S, C>
+where
+    S: BlockSource + Send,
+    S::Block: Send,
+    C: CheckpointStore,
+{
+    /// Create a new `HistorySync` for the given block range (end block excluded).
+    pub fn new(source: S, store: C, range: Range<BlockNum>) -> Self {
+        HistorySync { source, store, range, batch_size: 100, max_pending_batches: 4 }
+    }
+
+    /// Set the max number of blocks delivered in a batch (default: 100).
+    pub fn batch_size(self, batch_size: usize) -> Self {
+        HistorySync { batch_size: batch_size.max(1), ..self }
+    }
+
+    /// Set the max number of batches that can be fetched in advance while the
+    /// handler is still busy with a previous one (default: 4).
+    pub fn max_pending_batches(self, max_pending_batches: usize) -> Self {
+        HistorySync { max_pending_batches, ..self }
+    }
+
+    pub fn store(&self) -> &C {
+        &self.store
+    }
+
+    /// Return the number of the first block that still needs to be handled,
+    /// taking into account the last saved checkpoint.
+    pub fn resume_block_num(&mut self) -> Result<BlockNum, HistoryError> {
+        let checkpoint = self.store.load().boxed().context(CheckpointSnafu)?;
+        Ok(match checkpoint {
+            Some(n) => self.range.start.max(n.saturating_add(1)),
+            None => self.range.start,
+        })
+    }
+
+    /// Fetch and deliver all the remaining blocks in the range to the handler,
+    /// starting after the last saved checkpoint.
+    ///
+    /// A checkpoint is saved after each batch that was successfully handled, so
+    /// calling this again after an error resumes the sync where it stopped.
+    ///
+    /// Return the number of blocks that have been handled.
+    pub fn run<F, E>(&mut self, mut handler: F) -> Result<usize, HistoryError>
+    where
+        F: FnMut(&Batch<S::Block>) -> Result<(), E>,
+        E: Error + Send + Sync + 'static,
+    {
+        let Range { start, end } = self.range;
+        ensure!(start <= end, InvalidRangeSnafu { start, end });
+
+        let first = self.resume_block_num()?;
+        debug!("syncing history from block #{first} to #{end} (excluded)");
+
+        let batch_size = self.batch_size;
+        let (tx, rx) = mpsc::sync_channel(self.max_pending_batches);
+        let source = &mut self.source;
+        let store = &mut self.store;
+
+        thread::scope(|s| {
+            let fetcher = s.spawn(move || -> Result<(), HistoryError> {
+                let mut block_num = first;
+                while block_num < end {
+                    let batch_end = end.min(block_num.saturating_add(batch_size as BlockNum));
+                    let mut blocks = Vec::with_capacity((batch_end - block_num) as usize);
+                    for n in block_num..batch_end {
+                        blocks.push(source.fetch_block(n).boxed().context(SourceSnafu { block_num: n })?);
+                    }
+                    // if sending fails, the handler stopped and we have nothing more to do
+                    if tx.send(Batch { blocks, first_block_num: block_num }).is_err() {
+                        break;
+                    }
+                    block_num = batch_end;
+                }
+                Ok(())
+            });
+
+            let mut handled = 0;
+            // blocks/sec is measured between the end of consecutive batches, so that it
+            // includes the time spent fetching blocks and not only in the handler
+            #[cfg(feature = "metrics")]
+            let mut last_batch_end = std::time::Instant::now();
+            let handled_result = rx.into_iter().try_for_each(|batch| {
+                let (first_block_num, last_block_num) = (batch.first_block_num(), batch.last_block_num());
+                #[cfg(feature = "metrics")]
+                let start = std::time::Instant::now();
+                handler(&batch).boxed().context(HandlerSnafu { first_block_num, last_block_num })?;
+                #[cfg(feature = "metrics")]
+                {
+                    use crate::metrics::{HISTORY_BATCH_DURATION, HISTORY_BLOCKS_HANDLED, HISTORY_BLOCKS_PER_SECOND};
+                    let now = std::time::Instant::now();
+                    let num_blocks = batch.blocks.len();
+                    ::metrics::histogram!(HISTORY_BATCH_DURATION).record(now - start);
+                    ::metrics::counter!(HISTORY_BLOCKS_HANDLED).increment(num_blocks as u64);
+                    let elapsed = (now - last_batch_end).as_secs_f64();
+                    if elapsed > 0.0 {
+                        ::metrics::gauge!(HISTORY_BLOCKS_PER_SECOND).set(num_blocks as f64 / elapsed);
+                    }
+                    last_batch_end = now;
+                }
+                store.save(last_block_num).boxed().context(CheckpointSnafu)?;
+                handled += batch.blocks.len();
+                Ok(())
+            });
+
+            // if the handler failed, the channel is now closed and the fetcher will stop
+            let fetched_result = fetcher.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+            handled_result.and(fetched_result).map(|_| handled)
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use color_eyre::eyre::Result;
+
+    use super::*;
+
+    /// block source returning the block number as block, failing once on a given block
+    #[derive(Clone)]
+    struct TestSource {
+        fail_on: Arc<Mutex<Option<BlockNum>>>,
+    }
+
+    impl BlockSource for TestSource {
+        type Block = BlockNum;
+        type Error = io::Error;
+
+        fn fetch_block(&mut self, block_num: BlockNum) -> Result<BlockNum, io::Error> {
+            let mut fail_on = self.fail_on.lock().unwrap();
+            if *fail_on == Some(block_num) {
+                *fail_on = None;
+                return Err(io::Error::other("connection lost"));
+            }
+            Ok(block_num)
+        }
+    }
+
+    #[test]
+    fn test_history_sync_resume() -> Result<()> {
+        let source = TestSource { fail_on: Arc::new(Mutex::new(Some(25))) };
+        let mut sync = HistorySync::new(source, MemoryCheckpointStore::default(), 10..50)
+            .batch_size(7)
+            .max_pending_batches(1);
+
+        let mut received = vec![];
+        let mut handler = |batch: &Batch<BlockNum>| {
+            assert!(batch.iter().all(|(n, b)| n == *b));
+            received.extend_from_slice(&batch.blocks);
+            Ok::<(), io::Error>(())
+        };
+
+        // the sync stops on the failing block, with the checkpoint on the last full batch
+        assert!(sync.run(&mut handler).is_err());
+        assert_eq!(sync.store().checkpoint, Some(23));
+        assert_eq!(sync.resume_block_num()?, 24);
+
+        // resuming delivers all the remaining blocks exactly once
+        assert_eq!(sync.run(&mut handler)?, 26);
+        assert_eq!(sync.run(&mut handler)?, 0);
+        assert_eq!(received, (10..50).collect::<Vec<_>>());
+
+        // a file store keeps the checkpoint across instances
+        let path = std::env::temp_dir().join(format!("kudu_history_test_{}.json", std::process::id()));
+        let mut store = FileCheckpointStore::new(&path);
+        assert_eq!(store.load()?, None);
+        store.save(42)?;
+        assert_eq!(FileCheckpointStore::new(&path).load()?, Some(42));
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+}
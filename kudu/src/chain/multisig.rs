@@ -0,0 +1,237 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::HashMap;
+
+use snafu::{ensure, ResultExt, Snafu};
+
+use crate::{
+    ABISerializable, Authority, Bytes, Digest, InvalidCryptoData, KeyWeight, PackedTransaction,
+    PublicKey, Signature, Transaction, TransactionError, Weight, with_location,
+};
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum SignatureError {
+    #[snafu(display("cannot compute the digest of the transaction"))]
+    Digest {
+        #[snafu(source(from(TransactionError, Box::new)))]
+        source: Box<TransactionError>,
+    },
+
+    #[snafu(display("cannot recover public key from signature {signature}"))]
+    Recover { signature: Signature, source: InvalidCryptoData },
+
+    #[snafu(display("signature {signature} is not canonical and would be refused by nodeos"))]
+    NonCanonical { signature: Signature },
+
+    #[snafu(display("signature {signature} has been made by {key} which is not a required key"))]
+    UnknownKey { signature: Signature, key: PublicKey },
+
+    #[snafu(display("threshold not reached: signed weight is {weight}, threshold is {threshold}"))]
+    ThresholdNotReached { weight: u32, threshold: u32 },
+}
+
+
+/// Collect the signatures of several parties for the same transaction, as needed
+/// when coordinating a multisig transaction.
+///
+/// Each signature is validated by recovering the public key that made it and
+/// checking that this key is one of the required keys. Signatures coming from the
+/// same key are only counted once.
+///
+/// Once the sum of the weights of the signing keys reaches the threshold, the
+/// final [`PackedTransaction`] can be obtained with [`SignatureCollector::finalize()`].
+///
+/// ## Example
+/// ```
+/// # use kudu::{config, Checksum256, KeyWeight, PrivateKey, PublicKey, SignatureCollector, Transaction};
+/// # let mut tx = Transaction::new(vec![]);
+/// # tx.chain_id = Some(Checksum256::from_hex(config::JUNGLE_CHAIN_ID)?);
+/// let key = PrivateKey::eosio_dev();
+/// let required = [KeyWeight { key: PublicKey::from_private_key(&key), weight: 1 }];
+/// let mut collector = SignatureCollector::new(tx, required, 1)?;
+///
+/// // the signature would normally come from another party
+/// let sig = key.sign_digest(collector.digest().clone());
+/// assert!(collector.add_signature(sig)?);
+/// assert!(collector.is_complete());
+/// let packed_tx = collector.finalize()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct SignatureCollector {
+    tx: Transaction,
+    digest: Digest,
+    threshold: u32,
+    weights: HashMap<PublicKey, Weight>,
+    signatures: Vec<(PublicKey, Signature)>,
+}
+
+impl SignatureCollector {
+    /// Create a collector for the given transaction, which needs to have its chain ID
+    /// set in order to compute its signing digest.
+    pub fn new(tx: Transaction, keys: impl IntoIterator<Item = KeyWeight>, threshold: u32)
+               -> Result<Self, SignatureError> {
+        let context_free_data = b"";  // TODO: support this
+        let digest = tx.sig_digest(context_free_data).context(DigestSnafu)?;
+        Ok(SignatureCollector {
+            tx,
+            digest,
+            threshold,
+            weights: keys.into_iter().map(|kw| (kw.key, kw.weight)).collect(),
+            signatures: vec![],
+        })
+    }
+
+    /// Create a collector using the keys and threshold of the given authority.
+    ///
+    /// **NOTE:** only the keys of the authority are taken into account, delegated
+    ///           permissions and waits are ignored.
+    pub fn from_authority(tx: Transaction, authority: &Authority) -> Result<Self, SignatureError> {
+        Self::new(tx, authority.keys.iter().cloned(), authority.threshold)
+    }
+
+    pub fn transaction(&self) -> &Transaction { &self.tx }
+
+    /// Return the digest that all parties need to sign.
+    pub fn digest(&self) -> &Digest { &self.digest }
+
+    pub fn threshold(&self) -> u32 { self.threshold }
+
+    /// Return the weight of the given key, or `None` if it is not a required key.
+    pub fn key_weight(&self, key: &PublicKey) -> Option<Weight> {
+        self.weights.get(key).copied()
+    }
+
+    /// Add a signature to the collector after validating it. Signatures which are
+    /// not canonical are refused, see [`Signature::normalize_s()`].
+    ///
+    /// Return `true` if the signature has been added, or `false` if a signature from
+    /// the same key had already been collected.
+    pub fn add_signature(&mut self, signature: Signature) -> Result<bool, SignatureError> {
+        ensure!(signature.is_canonical(), NonCanonicalSnafu { signature });
+        let key = PublicKey::recover_from_digest(&self.digest, &signature)
+            .context(RecoverSnafu { signature: signature.clone() })?;
+        ensure!(self.weights.contains_key(&key), UnknownKeySnafu { signature, key });
+
+        if self.signatures.iter().any(|(k, _)| *k == key) {
+            return Ok(false);
+        }
+        self.signatures.push((key, signature));
+        Ok(true)
+    }
+
+    /// Return the keys that have signed so far, in the order their signatures were added.
+    pub fn signers(&self) -> impl Iterator<Item = &PublicKey> {
+        self.signatures.iter().map(|(key, _)| key)
+    }
+
+    /// Return the required keys that have not signed yet, sorted.
+    pub fn missing_keys(&self) -> Vec<&PublicKey> {
+        let mut result: Vec<_> = self.weights.keys()
+            .filter(|key| !self.signers().any(|k| k == *key))
+            .collect();
+        result.sort();
+        result
+    }
+
+    /// Return the sum of the weights of the keys that have signed so far.
+    pub fn signed_weight(&self) -> u32 {
+        self.signers().map(|key| self.weights[key] as u32).sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.signed_weight() >= self.threshold
+    }
+
+    /// Build the final packed transaction containing all the collected signatures,
+    /// failing if the threshold hasn't been reached.
+    pub fn finalize(&self) -> Result<PackedTransaction, SignatureError> {
+        let weight = self.signed_weight();
+        let threshold = self.threshold;
+        ensure!(weight >= threshold, ThresholdNotReachedSnafu { weight, threshold });
+
+        let mut packed_trx = Bytes::new();
+        self.tx.to_bin(&mut packed_trx);
+
+        Ok(PackedTransaction {
+            signatures: self.signatures.iter().map(|(_, sig)| sig.clone()).collect(),
+            compression: PackedTransaction::COMPRESSION_NONE,
+            packed_context_free_data: Bytes::new(),
+            packed_trx,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use crate::{config, Checksum256, PrivateKey};
+    use super::*;
+
+    fn linked_tx() -> Result<Transaction> {
+        let mut tx = Transaction::new(vec![]);
+        tx.chain_id = Some(Checksum256::from_hex(config::JUNGLE_CHAIN_ID)?);
+        Ok(tx)
+    }
+
+    fn key(i: u8) -> PrivateKey {
+        PrivateKey::with_key_type(crate::KeyType::K1, [i; 32])
+    }
+
+    #[test]
+    fn test_collect_signatures() -> Result<()> {
+        let (k1, k2, k3) = (key(1), key(2), key(3));
+        let authority = Authority {
+            threshold: 3,
+            keys: [(&k1, 1), (&k2, 2), (&k3, 1)].into_iter()
+                .map(|(k, weight)| KeyWeight { key: PublicKey::from_private_key(k), weight })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut collector = SignatureCollector::from_authority(linked_tx()?, &authority)?;
+        let digest = collector.digest().clone();
+
+        assert!(collector.add_signature(k1.sign_digest(digest.clone()))?);
+        assert!(!collector.add_signature(k1.sign_digest(digest.clone()))?);  // duplicate
+        assert_eq!(collector.signed_weight(), 1);
+        assert!(matches!(collector.finalize(), Err(SignatureError::ThresholdNotReached { weight: 1, threshold: 3, .. })));
+
+        // signature from a key that isn't required
+        assert!(matches!(collector.add_signature(key(4).sign_digest(digest.clone())),
+                         Err(SignatureError::UnknownKey { .. })));
+        // signature over another digest recovers a different key
+        assert!(matches!(collector.add_signature(k2.sign_bytes(b"other")),
+                         Err(SignatureError::UnknownKey { .. })));
+
+        // signature which is not canonical
+        let mut data = *k2.sign_digest(digest.clone()).data();
+        data[1] |= 0x80;
+        let sig = Signature::with_key_type(crate::KeyType::K1, data);
+        assert!(matches!(collector.add_signature(sig), Err(SignatureError::NonCanonical { .. })));
+
+        let mut missing = [PublicKey::from_private_key(&k2), PublicKey::from_private_key(&k3)];
+        missing.sort();
+        assert_eq!(collector.missing_keys(), missing.iter().collect::<Vec<_>>());
+
+        assert!(collector.add_signature(k2.sign_digest(digest.clone()))?);
+        assert!(collector.is_complete());
+
+        let packed = collector.finalize()?;
+        assert_eq!(packed.signatures, vec![k1.sign_digest(digest.clone()), k2.sign_digest(digest)]);
+        assert_eq!(packed.id()?, collector.transaction().id());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlinked_transaction() {
+        assert!(matches!(SignatureCollector::new(Transaction::new(vec![]), [], 1),
+                         Err(SignatureError::Digest { .. })));
+    }
+}
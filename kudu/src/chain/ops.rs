@@ -0,0 +1,297 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! High-level operations composing several actions of the system contract in
+//! a single transaction.
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::{APIClient, Asset, PrivateKey};
+//! # use kudu::chain::ops::{create_account, CreatorKeys, NewAccountSpec, ResourcePurchase};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = APIClient::local();
+//! let creator = CreatorKeys::new("eosio".parse()?, PrivateKey::eosio_dev());
+//! let spec = NewAccountSpec {
+//!     ram_bytes: 16 * 1024,
+//!     resources: ResourcePurchase::Stake {
+//!         net: "1.0000 SYS".parse()?,
+//!         cpu: "10.0000 SYS".parse()?,
+//!         transfer: false,
+//!     },
+//!     ..NewAccountSpec::new("alice".parse()?, "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV")
+//! };
+//! let account = create_account(client, &creator, &spec)?;
+//! println!("created account {} in tx {}", account.name, account.transaction_id.to_hex());
+//! # Ok(())
+//! # }
+//! ```
+//!
+
+use std::sync::Arc;
+
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+use crate::{
+    APIClient, AccountName, Action, Asset, Authority, InvalidCryptoData, JsonValue, PermissionLevel,
+    PermissionName, PrivateKey, PublicKey, Transaction, TransactionError, TransactionId, json,
+    api::HttpError,
+    contracts::system::{BuyRamBytes, DelegateBw, NewAccount, Powerup},
+    with_location,
+};
+
+
+/// Amount of RAM bought for a new account by default, enough for its permissions
+/// and a few token balances.
+pub const DEFAULT_RAM_BYTES: u32 = 8192;
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum OpsError {
+    #[snafu(display("invalid {what} key `{key}`"))]
+    InvalidKey { what: &'static str, key: String, source: InvalidCryptoData },
+
+    #[snafu(display("{what} key is a private key, expected a public key"))]
+    PrivateKeyGiven { what: &'static str },
+
+    #[snafu(display("no signing key given for creator account `{account}`"))]
+    NoSigningKey { account: AccountName },
+
+    #[snafu(display("could not create account `{account}`"))]
+    Transaction { account: AccountName, source: TransactionError },
+
+    #[snafu(display("could not fetch info for account `{account}`"))]
+    AccountInfo { account: AccountName, source: HttpError },
+}
+
+
+/// The account paying for the creation of new accounts, along with the keys
+/// needed to sign for its permission.
+#[derive(Clone, Debug)]
+pub struct CreatorKeys {
+    pub account: AccountName,
+    /// Permission of `account` authorizing the actions, `active` by default
+    pub permission: PermissionName,
+    pub keys: Vec<PrivateKey>,
+}
+
+impl CreatorKeys {
+    pub fn new(account: AccountName, key: PrivateKey) -> Self {
+        CreatorKeys { account, permission: PermissionName::constant("active"), keys: vec![key] }
+    }
+
+    pub fn with_permission(self, permission: PermissionName) -> Self {
+        CreatorKeys { permission, ..self }
+    }
+}
+
+/// How the CPU and NET resources of a new account are paid for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResourcePurchase {
+    /// Do not buy any CPU or NET, the account will need to be powered up later
+    None,
+    /// Stake tokens for the account using `delegatebw`
+    Stake { net: Asset, cpu: Asset, transfer: bool },
+    /// Rent resources for the account using `powerup`
+    Powerup { days: u32, net_frac: i64, cpu_frac: i64, max_payment: Asset },
+}
+
+/// Description of an account to be created by [`create_account()`].
+///
+/// Keys are given as strings in any of the formats accepted by [`PublicKey::new()`],
+/// they are validated before any transaction is sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewAccountSpec {
+    pub name: AccountName,
+    pub owner_key: String,
+    /// Key for the `active` permission, same as `owner_key` if `None`
+    pub active_key: Option<String>,
+    /// Amount of RAM bought for the account, nothing is bought if `0`
+    pub ram_bytes: u32,
+    pub resources: ResourcePurchase,
+}
+
+impl NewAccountSpec {
+    /// Return a spec for an account with the same key for its `owner` and `active`
+    /// permissions, [`DEFAULT_RAM_BYTES`] of RAM and no CPU nor NET.
+    pub fn new(name: AccountName, owner_key: &str) -> Self {
+        NewAccountSpec {
+            name,
+            owner_key: owner_key.to_string(),
+            active_key: None,
+            ram_bytes: DEFAULT_RAM_BYTES,
+            resources: ResourcePurchase::None,
+        }
+    }
+}
+
+/// An account created by [`create_account()`].
+#[derive(Clone, Debug)]
+pub struct CreatedAccount {
+    pub name: AccountName,
+    pub transaction_id: TransactionId,
+    /// Response of the `/v1/chain/get_account` endpoint for the new account
+    pub info: JsonValue,
+}
+
+
+fn parse_public_key(what: &'static str, key: &str) -> Result<PublicKey, OpsError> {
+    // do not leak a private key in the error message if one has been given by mistake
+    ensure!(PrivateKey::new(key).is_err(), PrivateKeyGivenSnafu { what });
+    PublicKey::new(key).context(InvalidKeySnafu { what, key })
+}
+
+/// Return the actions creating the account described by `spec`: `newaccount`,
+/// followed by `buyrambytes` and `delegatebw` or `powerup` if needed.
+pub fn create_account_actions(creator: &CreatorKeys, spec: &NewAccountSpec) -> Result<Vec<Action>, OpsError> {
+    let owner = parse_public_key("owner", &spec.owner_key)?;
+    let active = match &spec.active_key {
+        Some(key) => parse_public_key("active", key)?,
+        None => owner.clone(),
+    };
+
+    let auth = PermissionLevel { actor: creator.account, permission: creator.permission };
+    let (payer, receiver) = (creator.account, spec.name);
+
+    let mut actions = vec![Action::new(auth, &NewAccount {
+        creator: payer,
+        name: receiver,
+        owner: Authority::from_key(owner),
+        active: Authority::from_key(active),
+    })];
+
+    if spec.ram_bytes > 0 {
+        actions.push(Action::new(auth, &BuyRamBytes { payer, receiver, bytes: spec.ram_bytes }));
+    }
+
+    match &spec.resources {
+        ResourcePurchase::None => {},
+        ResourcePurchase::Stake { net, cpu, transfer } => {
+            actions.push(Action::new(auth, &DelegateBw {
+                from: payer,
+                receiver,
+                stake_net_quantity: *net,
+                stake_cpu_quantity: *cpu,
+                transfer: *transfer,
+            }));
+        },
+        ResourcePurchase::Powerup { days, net_frac, cpu_frac, max_payment } => {
+            actions.push(Action::new(auth, &Powerup {
+                payer,
+                receiver,
+                days: *days,
+                net_frac: *net_frac,
+                cpu_frac: *cpu_frac,
+                max_payment: *max_payment,
+            }));
+        },
+    }
+
+    Ok(actions)
+}
+
+/// Create a new account and buy its resources in a single transaction signed by
+/// the creator, and return the info of the created account.
+///
+/// The keys of the new account are validated before anything is sent to the node.
+pub fn create_account(client: Arc<APIClient>, creator: &CreatorKeys, spec: &NewAccountSpec)
+                      -> Result<CreatedAccount, OpsError> {
+    let account = spec.name;
+    let actions = create_account_actions(creator, spec)?;
+    let (first_key, other_keys) = creator.keys.split_first()
+        .context(NoSigningKeySnafu { account: creator.account })?;
+
+    let mut tx = Transaction::new(actions);
+    tx.link(client.clone()).context(TransactionSnafu { account })?;
+    let mut signed_tx = tx.sign(first_key).context(TransactionSnafu { account })?;
+    if !other_keys.is_empty() {
+        let digest = tx.sig_digest(b"").context(TransactionSnafu { account })?;
+        signed_tx.signatures.extend(other_keys.iter().map(|key| key.sign_digest(digest.clone())));
+    }
+    let submission = signed_tx.submit().context(TransactionSnafu { account })?;
+
+    let info = client.call("/v1/chain/get_account", &json!({"account_name": account}))
+        .context(AccountInfoSnafu { account })?;
+
+    Ok(CreatedAccount { name: account, transaction_id: submission.transaction_id, info })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use crate::{ActionName, api::MockTransport, config};
+    use super::*;
+
+    const KEY: &str = "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+
+    fn creator() -> Result<CreatorKeys> {
+        Ok(CreatorKeys::new("eosio".parse()?, PrivateKey::eosio_dev()))
+    }
+
+    #[test]
+    fn test_create_account_actions() -> Result<()> {
+        let spec = NewAccountSpec::new("alice".parse()?, KEY);
+        let actions = create_account_actions(&creator()?, &spec)?;
+        let names: Vec<_> = actions.iter().map(|a| a.name.to_string()).collect();
+        assert_eq!(names, ["newaccount", "buyrambytes"]);
+
+        let spec = NewAccountSpec {
+            ram_bytes: 0,
+            resources: ResourcePurchase::Powerup {
+                days: 1, net_frac: 1000, cpu_frac: 2000, max_payment: "1.0000 SYS".parse()?,
+            },
+            ..spec
+        };
+        let actions = create_account_actions(&creator()?, &spec)?;
+        assert_eq!(actions.iter().map(|a| a.name).collect::<Vec<_>>(),
+                   [ActionName::constant("newaccount"), ActionName::constant("powerup")]);
+        assert_eq!(actions[1].decode_data()?["cpu_frac"], 2000);
+
+        // invalid keys are rejected, without leaking private keys
+        let bad = NewAccountSpec { active_key: Some("EOS123".to_string()), ..spec.clone() };
+        assert!(matches!(create_account_actions(&creator()?, &bad), Err(OpsError::InvalidKey { what: "active", .. })));
+
+        let private = PrivateKey::eosio_dev().to_string();
+        let bad = NewAccountSpec { owner_key: private.clone(), ..spec };
+        let err = create_account_actions(&creator()?, &bad).unwrap_err();
+        assert!(matches!(err, OpsError::PrivateKeyGiven { what: "owner", .. }));
+        assert!(!err.to_string().contains(&private));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_account() -> Result<()> {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_info", json!({
+            "chain_id": config::JUNGLE_CHAIN_ID,
+            "last_irreversible_block_id": "0eeb31a70905138203051bf848fc7176336a0eb41d078338460af949d8cf2abd",
+            "head_block_time": "2024-01-01T00:00:00.000",
+        }))
+        .respond("/v1/chain/push_transaction", json!({"transaction_id": "abcd"}))
+        .respond("/v1/chain/get_account", json!({"account_name": "alice", "ram_quota": 8192}));
+        let client = Arc::new(APIClient::with_transport("mock://", mock.clone()));
+
+        let spec = NewAccountSpec {
+            resources: ResourcePurchase::Stake {
+                net: "1.0000 SYS".parse()?, cpu: "1.0000 SYS".parse()?, transfer: true,
+            },
+            ..NewAccountSpec::new("alice".parse()?, KEY)
+        };
+        let account = create_account(client.clone(), &creator()?, &spec)?;
+        assert_eq!(account.name.to_string(), "alice");
+        assert_eq!(account.info["ram_quota"], 8192);
+        assert_eq!(mock.requests_to("/v1/chain/push_transaction").len(), 1);
+        mock.assert_called_with("/v1/chain/get_account", &json!({"account_name": "alice"}));
+
+        // nothing is sent without a signing key
+        let no_keys = CreatorKeys { keys: vec![], ..creator()? };
+        assert!(matches!(create_account(client, &no_keys, &spec), Err(OpsError::NoSigningKey { .. })));
+        assert_eq!(mock.requests_to("/v1/chain/push_transaction").len(), 1);
+
+        Ok(())
+    }
+}
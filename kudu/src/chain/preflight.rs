@@ -0,0 +1,289 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use snafu::{OptionExt, ResultExt, ensure};
+use tracing::debug;
+
+use crate::{
+    ABISerializable, APIClient, Bytes, SignedTransaction, TimePointSec, Transaction, TransactionError,
+    json,
+};
+
+use super::blocklog::ChainConfig;
+use super::transaction::{
+    InvalidExpirationSnafu, NetworkSnafu, NodeosSnafu,
+    ResourceLimitExceededSnafu, TransactionTooLargeSnafu, UnlinkedTransactionSnafu,
+    get_info, head_block_time,
+};
+
+
+/// Limits enforced by nodeos on transactions, checked locally by
+/// [`Transaction::check_limits()`] before a transaction is sent.
+///
+/// The default values are the ones of a chain with the default configuration,
+/// use [`TransactionLimits::fetch()`] to get the actual ones from a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionLimits {
+    /// Max size of the packed transaction, in bytes
+    pub max_transaction_size: u32,
+    /// Max delay between the head block time and the expiration of a transaction
+    pub max_lifetime: Duration,
+    /// Max CPU usage of a transaction, in microseconds
+    pub max_cpu_usage_us: u32,
+}
+
+impl Default for TransactionLimits {
+    fn default() -> Self {
+        TransactionLimits {
+            max_transaction_size: 512 * 1024,
+            max_lifetime: Duration::from_secs(3600),
+            max_cpu_usage_us: 150_000,
+        }
+    }
+}
+
+impl From<&ChainConfig> for TransactionLimits {
+    fn from(config: &ChainConfig) -> Self {
+        TransactionLimits {
+            max_transaction_size: config.max_transaction_net_usage,
+            max_lifetime: Duration::from_secs(config.max_transaction_lifetime as u64),
+            max_cpu_usage_us: config.max_transaction_cpu_usage,
+        }
+    }
+}
+
+impl TransactionLimits {
+    /// Fetch the chain configuration from the `/v1/chain/get_consensus_parameters`
+    /// endpoint and return the corresponding limits.
+    pub fn fetch(client: &APIClient) -> Result<TransactionLimits, TransactionError> {
+        let params = client.get("/v1/chain/get_consensus_parameters")
+            .context(NetworkSnafu { message: "cannot get consensus parameters" })?;
+        let config = ChainConfig::deserialize(&params["chain_config"]).ok()
+            .context(NodeosSnafu { message: "invalid `chain_config` in consensus parameters" })?;
+        Ok(TransactionLimits::from(&config))
+    }
+}
+
+
+/// Resources used by a transaction, as computed by a node without applying it,
+/// see [`Transaction::estimate_resources()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceEstimate {
+    pub cpu_usage_us: u32,
+    pub net_usage_words: u32,
+}
+
+
+impl Transaction {
+    /// Check the transaction against the given limits, and return a descriptive
+    /// error for the first one that is exceeded.
+    ///
+    /// This only checks what can be known locally: the size of the transaction, its
+    /// expiration delay and its resource usage limits.
+    ///
+    /// Note that the data of top-level actions is only bounded by the size of the
+    /// transaction, the `max_inline_action_size` of the chain config only applies
+    /// to inline actions sent by contracts.
+    /// See [`Transaction::preflight()`] for checks that need to contact a node.
+    pub fn check_limits(&self, limits: &TransactionLimits) -> Result<(), TransactionError> {
+        let mut packed = Bytes::new();
+        self.to_bin(&mut packed);
+        ensure!(packed.len() <= limits.max_transaction_size as usize,
+                TransactionTooLargeSnafu { size: packed.len(), max: limits.max_transaction_size });
+
+        if let Some(delay) = self.expiration_delay {
+            ensure!(delay <= limits.max_lifetime, InvalidExpirationSnafu {
+                message: format!("expiration delay of {delay:?} exceeds the max of {:?}", limits.max_lifetime),
+            });
+        }
+
+        let max_cpu_us = self.max_cpu_usage_ms as u64 * 1000;
+        ensure!(max_cpu_us <= limits.max_cpu_usage_us as u64, ResourceLimitExceededSnafu {
+            resource: "max CPU (us)", usage: max_cpu_us, max: limits.max_cpu_usage_us,
+        });
+        let max_net = self.max_net_usage_words.0 as u64 * 8;
+        ensure!(max_net <= limits.max_transaction_size as u64, ResourceLimitExceededSnafu {
+            resource: "max NET (bytes)", usage: max_net, max: limits.max_transaction_size,
+        });
+
+        Ok(())
+    }
+
+    /// Check that the expiration of the transaction is in the future and within
+    /// `max_lifetime` of the given head block time.
+    pub fn check_expiration(&self, head_block_time: TimePointSec, max_lifetime: Duration)
+                            -> Result<(), TransactionError> {
+        ensure!(!self.is_expired(head_block_time), InvalidExpirationSnafu {
+            message: format!("transaction expired at {}, head block time is {head_block_time}", self.expiration),
+        });
+        let max_expiration = head_block_time + max_lifetime.as_secs() as u32;
+        ensure!(self.expiration <= max_expiration, InvalidExpirationSnafu {
+            message: format!("transaction expires at {}, more than {max_lifetime:?} after head block time {head_block_time}",
+                             self.expiration),
+        });
+        Ok(())
+    }
+
+    /// Compute the resources used by the transaction using the
+    /// `/v1/chain/compute_transaction` endpoint, which executes it on the node
+    /// without applying it. The transaction does not need to be signed.
+    pub fn estimate_resources(&self) -> Result<ResourceEstimate, TransactionError> {
+        let client = self.client.as_ref()
+            .with_context(|| UnlinkedTransactionSnafu { message: "cannot estimate resources" })?;
+        let unsigned = SignedTransaction {
+            tx: self.clone(),
+            signatures: vec![],
+            compression: false,
+            packed_content_free_data: Bytes::new(),
+        };
+        let result = client.call("/v1/chain/compute_transaction", &json!({"transaction": unsigned}))
+            .context(NetworkSnafu { message: "could not compute transaction" })?;
+
+        let receipt = &result["processed"]["receipt"];
+        let usage = |field: &str| receipt[field].as_u64().and_then(|v| u32::try_from(v).ok())
+            .with_context(|| NodeosSnafu { message: format!("invalid `{field}` in computed transaction receipt") });
+        Ok(ResourceEstimate {
+            cpu_usage_us: usage("cpu_usage_us")?,
+            net_usage_words: usage("net_usage_words")?,
+        })
+    }
+
+    /// Run all the checks that can be done before sending the linked transaction:
+    /// [`Transaction::check_limits()`], [`Transaction::check_expiration()`] against the
+    /// current head block, and the estimation of its resources by the node, which
+    /// also reports errors in the actions themselves (eg: failed assertions).
+    ///
+    /// Return the estimated resources if they are within the given limits.
+    pub fn preflight(&self, limits: &TransactionLimits) -> Result<ResourceEstimate, TransactionError> {
+        self.check_limits(limits)?;
+
+        let client = self.client.as_ref()
+            .with_context(|| UnlinkedTransactionSnafu { message: "cannot run preflight checks" })?;
+        self.check_expiration(head_block_time(&get_info(client)?)?, limits.max_lifetime)?;
+
+        let estimate = self.estimate_resources()?;
+        debug!("estimated resources for transaction {}: {estimate:?}", self.id().to_hex());
+        ensure!(estimate.cpu_usage_us <= limits.max_cpu_usage_us, ResourceLimitExceededSnafu {
+            resource: "CPU (us)", usage: estimate.cpu_usage_us, max: limits.max_cpu_usage_us,
+        });
+        let net_usage = estimate.net_usage_words as u64 * 8;
+        ensure!(net_usage <= limits.max_transaction_size as u64, ResourceLimitExceededSnafu {
+            resource: "NET (bytes)", usage: net_usage, max: limits.max_transaction_size,
+        });
+        Ok(estimate)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use color_eyre::eyre::Result;
+
+    use crate::{Action, IntoPermissionVec, api::MockTransport};
+    use super::*;
+
+    fn action(data_size: usize) -> Result<Action> {
+        Ok(Action {
+            account: "eosio.token".parse()?,
+            name: "transfer".parse()?,
+            authorization: ("alice", "active").into_permission_vec(),
+            data: Bytes::from(vec![0u8; data_size]),
+        })
+    }
+
+    #[test]
+    fn test_check_limits() -> Result<()> {
+        let limits = TransactionLimits { max_transaction_size: 300, ..Default::default() };
+
+        let tx = Transaction::new(vec![action(50)?]);
+        tx.check_limits(&limits)?;
+
+        let tx = Transaction::new(vec![action(80)?, action(80)?, action(80)?]);
+        assert!(matches!(tx.check_limits(&limits), Err(TransactionError::TransactionTooLarge { max: 300, .. })));
+
+        let tx = Transaction::new(vec![]).expires_in(Duration::from_secs(7200));
+        assert!(matches!(tx.check_limits(&limits), Err(TransactionError::InvalidExpiration { .. })));
+
+        let tx = Transaction { max_cpu_usage_ms: 200, ..Transaction::new(vec![]) };
+        assert!(matches!(tx.check_limits(&limits), Err(TransactionError::ResourceLimitExceeded { usage: 200_000, .. })));
+
+        // expiration window relative to the head block
+        let head = "2024-01-01T00:00:00".parse()?;
+        let tx = Transaction { expiration: "2024-01-01T00:02:00".parse()?, ..Transaction::new(vec![]) };
+        tx.check_expiration(head, limits.max_lifetime)?;
+        assert!(tx.check_expiration("2024-01-01T00:02:00".parse()?, limits.max_lifetime).is_err());
+        assert!(tx.check_expiration(head, Duration::from_secs(60)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_limits_top_level_action_data() -> Result<()> {
+        // `max_inline_action_size` does not apply to top-level actions (eg: `setcode`)
+        let config = ChainConfig {
+            max_transaction_net_usage: 512 * 1024,
+            max_inline_action_size: 4096,
+            max_transaction_lifetime: 3600,
+            max_transaction_cpu_usage: 150_000,
+            ..Default::default()
+        };
+        let limits = TransactionLimits::from(&config);
+
+        let tx = Transaction::new(vec![action(100_000)?]);
+        tx.check_limits(&limits)?;
+
+        let tx = Transaction::new(vec![action(600_000)?]);
+        assert!(matches!(tx.check_limits(&limits), Err(TransactionError::TransactionTooLarge { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preflight() -> Result<()> {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_info", json!({
+            "chain_id": crate::config::JUNGLE_CHAIN_ID,
+            "last_irreversible_block_id": "0eeb31a70905138203051bf848fc7176336a0eb41d078338460af949d8cf2abd",
+            "head_block_time": "2024-01-01T00:00:00.000",
+        }))
+        .respond("/v1/chain/get_consensus_parameters", json!({"chain_config": {
+            "max_block_net_usage": 1048576, "target_block_net_usage_pct": 1000,
+            "max_transaction_net_usage": 524288, "base_per_transaction_net_usage": 12,
+            "net_usage_leeway": 500, "context_free_discount_net_usage_num": 20,
+            "context_free_discount_net_usage_den": 100, "max_block_cpu_usage": 200000,
+            "target_block_cpu_usage_pct": 1000, "max_transaction_cpu_usage": 1000,
+            "min_transaction_cpu_usage": 100, "max_transaction_lifetime": 3600,
+            "deferred_trx_expiration_window": 600, "max_transaction_delay": 3888000,
+            "max_inline_action_size": 524288, "max_inline_action_depth": 4, "max_authority_depth": 6,
+        }}))
+        .respond("/v1/chain/compute_transaction", json!({
+            "transaction_id": "abcd",
+            "processed": {"receipt": {"status": "executed", "cpu_usage_us": 800, "net_usage_words": 16}},
+        }))
+        .respond("/v1/chain/compute_transaction", json!({
+            "transaction_id": "abcd",
+            "processed": {"receipt": {"status": "executed", "cpu_usage_us": 1200, "net_usage_words": 16}},
+        }));
+        let client = Arc::new(APIClient::with_transport("mock://", mock.clone()));
+
+        let limits = TransactionLimits::fetch(&client)?;
+        assert_eq!(limits.max_cpu_usage_us, 1000);
+
+        let mut tx = Transaction::new(vec![action(10)?]);
+        tx.link(client)?;
+        let estimate = tx.preflight(&limits)?;
+        assert_eq!(estimate, ResourceEstimate { cpu_usage_us: 800, net_usage_words: 16 });
+        let request = mock.requests_to("/v1/chain/compute_transaction").remove(0).body.unwrap_or_default();
+        assert_eq!(request["transaction"]["signatures"], json!([]));
+
+        let result = tx.preflight(&limits);
+        assert!(matches!(result, Err(TransactionError::ResourceLimitExceeded { usage: 1200, max: 1000, .. })));
+
+        Ok(())
+    }
+}
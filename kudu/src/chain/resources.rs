@@ -0,0 +1,460 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Helpers to estimate the cost of resources (CPU, NET) on an Antelope chain.
+//!
+//! This implements the same math as the system contract for:
+//!  - powerup: <https://github.com/VaultaFoundation/system-contracts/blob/main/contracts/eosio.system/src/powerup.cpp>
+//!  - REX: <https://github.com/VaultaFoundation/system-contracts/blob/main/contracts/eosio.system/src/rex.cpp>
+//!
+//! The state of the system contract can be read from a node using [`PowerupState::fetch()`]
+//! and [`RexPool::fetch()`].
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::{APIClient, TimePointSec};
+//! # use kudu::chain::resources::{PowerupState, ResourcesError, UsageSample};
+//! # fn main() -> Result<(), ResourcesError> {
+//! let client = APIClient::vaulta();
+//! let state = PowerupState::fetch(&client)?;
+//! let now = TimePointSec::from_datetime(chrono::Utc::now());
+//! let cpu = state.cpu.adjusted(now);
+//!
+//! // from `get_account`: an account with 1000 CPU weight can use 4ms of CPU
+//! let sample = UsageSample { max: 4_000, weight: 1000 };
+//! let frac = cpu.frac_for_usage(10_000, &sample);  // 10ms of CPU
+//! println!("Cost for powering up 10ms CPU: {}", cpu.fee(frac));
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu};
+
+use crate::{
+    ABISerializable, APIClient, Asset, Bytes, InvalidAsset, SerializeError, TimePointSec,
+    api::HttpError,
+    json, with_location,
+};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+/// `frac` values are expressed as a fraction of this number, e.g. `POWERUP_FRAC / 100` is 1%.
+pub const POWERUP_FRAC: i64 = 1_000_000_000_000_000;
+
+/// ratio between REX and the core token when the REX pool is empty
+pub const REX_RATIO: i64 = 10_000;
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum ResourcesError {
+    #[snafu(display("could not fetch table `{table}` from node"))]
+    Fetch { table: String, source: HttpError },
+
+    #[snafu(display("table `{table}` is empty or not initialized"))]
+    EmptyTable { table: String },
+
+    #[snafu(display("invalid row in table `{table}`: {message}"))]
+    InvalidRow { table: String, message: String },
+
+    #[snafu(display("cannot decode row from table `{table}`"))]
+    Decode { table: String, source: SerializeError },
+
+    #[snafu(display("fee overflows when adding NET and CPU fees"))]
+    FeeOverflow,
+
+    #[snafu(display("fee is not a valid asset amount"))]
+    InvalidFee { source: InvalidAsset },
+}
+
+
+/// Fetch the first row of the given `eosio` table in the given scope, decoded from
+/// its binary representation.
+fn fetch_singleton<T: ABISerializable>(client: &APIClient, scope: &str, table: &str) -> Result<T, ResourcesError> {
+    let response = client.call("/v1/chain/get_table_rows", &json!({
+        "code": "eosio",
+        "scope": scope,
+        "table": table,
+        "json": false,
+        "limit": 1,
+    })).context(FetchSnafu { table })?;
+
+    let row = response["rows"].get(0).context(EmptyTableSnafu { table })?;
+    let row = row.as_str().context(InvalidRowSnafu { table, message: format!("expected hex string, got: {row}") })?;
+    let data = Bytes::from_hex(row).map_err(|e| InvalidRowSnafu { table, message: e.to_string() }.build())?;
+    kudu::from_bin(&data).context(DecodeSnafu { table })
+}
+
+/// Integer division rounding towards positive infinity, for a positive divisor.
+fn div_ceil(a: i128, b: i128) -> i128 {
+    let (q, r) = (a / b, a % b);
+    if r > 0 { q + 1 } else { q }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Powerup
+// -----------------------------------------------------------------------------
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct PowerupStateResource {
+    pub version: u8,
+    /// resource market weight. calculated; varies over time.
+    /// 1 represents the same amount of resources as 1 satoshi of SYS staked.
+    pub weight: i64,
+    /// `weight` is `weight_ratio` of `weight + assumed_stake_weight`.
+    /// calculated; varies over time. 1x = `POWERUP_FRAC`.
+    pub weight_ratio: i64,
+    /// Assumed stake weight for ratio calculations.
+    pub assumed_stake_weight: i64,
+    /// Initial `weight_ratio` used for linear shrinkage.
+    pub initial_weight_ratio: i64,
+    /// Desired `weight_ratio`
+    pub target_weight_ratio: i64,
+    /// When `weight_ratio` shrinks from `initial_weight_ratio` to `target_weight_ratio`
+    pub initial_timestamp: TimePointSec,
+    pub target_timestamp: TimePointSec,
+    /// Exponent of resource price curve.
+    pub exponent: f64,
+    /// Number of seconds for the gap between `adjusted_utilization` and `utilization` to
+    /// shrink by 63%.
+    pub decay_secs: u32,
+    /// Fee needed to reserve the entire resource market weight at the minimum price.
+    pub min_price: Asset,
+    /// Fee needed to reserve the entire resource market weight at the maximum price.
+    pub max_price: Asset,
+    /// Instantaneous resource utilization. This is the current amount sold. `utilization <= weight`.
+    pub utilization: i64,
+    /// Adjusted resource utilization. This is `>= utilization` and `<= weight`. It grows
+    /// instantly but decays exponentially.
+    pub adjusted_utilization: i64,
+    /// When `adjusted_utilization` was last updated
+    pub utilization_timestamp: TimePointSec,
+}
+
+impl PowerupStateResource {
+    /// Return a copy of this state where the `adjusted_utilization` has been decayed
+    /// as it would be at time `now`.
+    pub fn adjusted(&self, now: TimePointSec) -> Self {
+        let mut result = self.clone();
+        let (now, last) = (u32::from(now), u32::from(self.utilization_timestamp));
+        if now <= last { return result; }
+
+        if result.utilization >= result.adjusted_utilization {
+            result.adjusted_utilization = result.utilization;
+        }
+        else {
+            let diff = (result.adjusted_utilization - result.utilization) as f64;
+            let decay = (-((now - last) as f64) / (result.decay_secs as f64)).exp();
+            result.adjusted_utilization = result.utilization + (diff * decay) as i64;
+        }
+        result.utilization_timestamp = now.into();
+        result
+    }
+
+    /// Return the price per unit of weight at the given utilization.
+    pub fn price_function(&self, utilization: i64) -> f64 {
+        let mut price = self.min_price.amount() as f64;
+        // do not want to compute exponent when it's 1
+        let new_exponent = self.exponent - 1.0;
+        if new_exponent <= 0.0 {
+            return self.max_price.amount() as f64;
+        }
+        price += (self.max_price.amount() - self.min_price.amount()) as f64
+            * (utilization as f64 / self.weight as f64).powf(new_exponent);
+        price
+    }
+
+    /// Return the integral of the price function between the given utilizations.
+    pub fn price_integral_delta(&self, start_utilization: i64, end_utilization: i64) -> f64 {
+        let min_price = self.min_price.amount() as f64;
+        let coefficient = (self.max_price.amount() - self.min_price.amount()) as f64 / self.exponent;
+        let start_u = start_utilization as f64 / self.weight as f64;
+        let end_u = end_utilization as f64 / self.weight as f64;
+        min_price * end_u - min_price * start_u
+            + coefficient * end_u.powf(self.exponent) - coefficient * start_u.powf(self.exponent)
+    }
+
+    /// Return the fee (in token units) to increase the utilization of this resource
+    /// by the given amount of weight.
+    pub fn utilization_fee(&self, utilization_increase: i64) -> i64 {
+        if utilization_increase <= 0 { return 0; }
+
+        let mut fee = 0.0;
+        let mut start_utilization = self.utilization;
+        let end_utilization = start_utilization + utilization_increase;
+
+        if start_utilization < self.adjusted_utilization {
+            fee += self.price_function(self.adjusted_utilization)
+                * (utilization_increase.min(self.adjusted_utilization - start_utilization) as f64)
+                / self.weight as f64;
+            start_utilization = self.adjusted_utilization;
+        }
+
+        if start_utilization < end_utilization {
+            fee += self.price_integral_delta(start_utilization, end_utilization);
+        }
+
+        fee.ceil() as i64
+    }
+
+    /// Return the amount of weight corresponding to the given `frac`.
+    pub fn weight_for_frac(&self, frac: i64) -> i64 {
+        (frac as i128 * self.weight as i128 / POWERUP_FRAC as i128) as i64
+    }
+
+    /// Return the `frac` needed to obtain the given amount of weight.
+    pub fn frac_for_weight(&self, weight: i64) -> i64 {
+        if self.weight == 0 { return 0; }
+        div_ceil(weight as i128 * POWERUP_FRAC as i128, self.weight as i128) as i64
+    }
+
+    /// Return the `frac` needed to obtain the given amount of resource (e.g. µs for CPU,
+    /// bytes for NET), using the given sample to convert between weight and resource.
+    pub fn frac_for_usage(&self, amount: i64, sample: &UsageSample) -> i64 {
+        self.frac_for_weight(sample.weight_for_usage(amount))
+    }
+
+    /// Return the fee (in token units) for a powerup of the given `frac` of this resource.
+    pub fn fee(&self, frac: i64) -> i64 {
+        self.utilization_fee(self.weight_for_frac(frac))
+    }
+}
+
+/// The state of the powerup market, as found in the `powup.state` table of the system contract.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct PowerupState {
+    pub version: u8,
+    pub net: PowerupStateResource,
+    pub cpu: PowerupStateResource,
+    /// `powerup` `days` argument must match this.
+    pub powerup_days: u32,
+    /// Fees below this amount are rejected
+    pub min_powerup_fee: Asset,
+}
+
+impl PowerupState {
+    /// Fetch the current state of the powerup market from the node.
+    pub fn fetch(client: &APIClient) -> Result<PowerupState, ResourcesError> {
+        // the powerup state singleton is stored with scope `0`
+        fetch_singleton(client, "", "powup.state")
+    }
+
+    /// Return the total fee that would be charged by the system contract for a
+    /// powerup with the given fracs, taking into account the minimum fee.
+    pub fn fee(&self, net_frac: i64, cpu_frac: i64) -> Result<Asset, ResourcesError> {
+        let fee = self.net.fee(net_frac).checked_add(self.cpu.fee(cpu_frac)).context(FeeOverflowSnafu)?;
+        let fee = fee.max(self.min_powerup_fee.amount());
+        Asset::new(fee, self.min_powerup_fee.symbol()).context(InvalidFeeSnafu)
+    }
+}
+
+/// A sample of the resources available to an account, used to convert between an
+/// amount of resource and the corresponding weight. This can be obtained from the
+/// `get_account` endpoint, e.g. for CPU: `max = cpu_limit.max`, `weight = cpu_weight`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct UsageSample {
+    pub max: i64,
+    pub weight: i64,
+}
+
+impl UsageSample {
+    pub fn weight_for_usage(&self, amount: i64) -> i64 {
+        if self.max == 0 { return 0; }
+        div_ceil(amount as i128 * self.weight as i128, self.max as i128) as i64
+    }
+
+    pub fn usage_for_weight(&self, weight: i64) -> i64 {
+        if self.weight == 0 { return 0; }
+        (weight as i128 * self.max as i128 / self.weight as i128) as i64
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     REX
+// -----------------------------------------------------------------------------
+
+/// The state of the REX pool, as found in the `rexpool` table of the system contract.
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct RexPool {
+    pub version: u8,
+    pub total_lent: Asset,
+    pub total_unlent: Asset,
+    pub total_rent: Asset,
+    pub total_lendable: Asset,
+    pub total_rex: Asset,
+    pub namebid_proceeds: Asset,
+    pub loan_num: u64,
+}
+
+impl RexPool {
+    /// Fetch the current state of the REX pool from the node.
+    pub fn fetch(client: &APIClient) -> Result<RexPool, ResourcesError> {
+        fetch_singleton(client, "eosio", "rexpool")
+    }
+
+    /// Return the amount of REX received when buying REX with the given amount of tokens.
+    pub fn tokens_to_rex(&self, tokens: i64) -> i64 {
+        let s0 = self.total_lendable.amount();
+        let r0 = self.total_rex.amount();
+        if s0 == 0 || r0 == 0 {
+            return REX_RATIO * tokens;
+        }
+        let s1 = s0 + tokens;
+        let r1 = (s1 as u128 * r0 as u128 / s0 as u128) as i64;
+        r1 - r0
+    }
+
+    /// Return the amount of tokens received when selling the given amount of REX.
+    pub fn rex_to_tokens(&self, rex: i64) -> i64 {
+        let s0 = self.total_lendable.amount();
+        let r0 = self.total_rex.amount();
+        if r0 == 0 { return 0; }
+        (rex as u128 * s0 as u128 / r0 as u128) as i64
+    }
+
+    /// Return the amount of tokens that would be staked to the receiver when renting
+    /// resources from REX with the given payment.
+    pub fn rent_output(&self, payment: i64) -> i64 {
+        bancor_output(self.total_rent.amount(), self.total_unlent.amount(), payment)
+    }
+}
+
+/// Bancor conversion, as used for renting resources from REX.
+pub fn bancor_output(inp_reserve: i64, out_reserve: i64, inp: i64) -> i64 {
+    let (ib, ob, inp) = (inp_reserve as f64, out_reserve as f64, inp as f64);
+    let out = ((inp * ob) / (ib + inp)) as i64;
+    out.max(0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{Symbol, api::MockTransport};
+
+    fn cpu_state() -> PowerupStateResource {
+        let sym: Symbol = "4,EOS".parse().unwrap();
+        PowerupStateResource {
+            version: 0,
+            weight: 100_000_000_000,
+            weight_ratio: POWERUP_FRAC,
+            assumed_stake_weight: 0,
+            initial_weight_ratio: POWERUP_FRAC,
+            target_weight_ratio: POWERUP_FRAC,
+            initial_timestamp: 0.into(),
+            target_timestamp: 0.into(),
+            exponent: 2.0,
+            decay_secs: 86400,
+            min_price: Asset::new(0, sym).unwrap(),
+            max_price: Asset::new(1_000_000_000, sym).unwrap(),
+            utilization: 0,
+            adjusted_utilization: 0,
+            utilization_timestamp: 1000.into(),
+        }
+    }
+
+    #[test]
+    fn test_powerup_fee() {
+        let state = cpu_state();
+        assert_eq!(state.fee(0), 0);
+        // with exponent 2 and min_price 0, the full market costs max_price / 2
+        assert_eq!(state.fee(POWERUP_FRAC), 500_000_000);
+        assert_eq!(state.weight_for_frac(POWERUP_FRAC / 100), 1_000_000_000);
+        assert_eq!(state.frac_for_weight(1_000_000_000), POWERUP_FRAC / 100);
+
+        let mut used = state.clone();
+        used.utilization = 10_000_000_000;
+        used.adjusted_utilization = 50_000_000_000;
+        assert!(used.fee(POWERUP_FRAC / 100) > state.fee(POWERUP_FRAC / 100));
+
+        // adjusted utilization decays over time
+        let later = used.adjusted((1000 + 86400).into());
+        assert!(later.adjusted_utilization < used.adjusted_utilization);
+        assert!(later.adjusted_utilization > used.utilization);
+    }
+
+    #[test]
+    fn test_powerup_state_fee() {
+        let sym: Symbol = "4,EOS".parse().unwrap();
+        let mut state = PowerupState {
+            version: 0,
+            net: cpu_state(),
+            cpu: cpu_state(),
+            powerup_days: 1,
+            min_powerup_fee: Asset::new(1, sym).unwrap(),
+        };
+        assert_eq!(state.fee(0, 0).unwrap(), Asset::new(1, sym).unwrap());
+        assert_eq!(state.fee(POWERUP_FRAC, POWERUP_FRAC).unwrap(), Asset::new(1_000_000_000, sym).unwrap());
+
+        // fees computed from the on-chain state are not bounded
+        state.net.max_price = Asset::new((1 << 62) - 2, sym).unwrap();
+        state.cpu.max_price = Asset::new((1 << 62) - 2, sym).unwrap();
+        assert!(matches!(state.fee(POWERUP_FRAC, POWERUP_FRAC), Err(ResourcesError::InvalidFee { .. })));
+    }
+
+    fn rex_pool() -> RexPool {
+        let sym: Symbol = "4,EOS".parse().unwrap();
+        let rex: Symbol = "4,REX".parse().unwrap();
+        RexPool {
+            version: 0,
+            total_lent: Asset::new(0, sym).unwrap(),
+            total_unlent: Asset::new(0, sym).unwrap(),
+            total_rent: Asset::new(0, sym).unwrap(),
+            total_lendable: Asset::new(20_000, sym).unwrap(),
+            total_rex: Asset::new(100_000_000, rex).unwrap(),
+            namebid_proceeds: Asset::new(0, sym).unwrap(),
+            loan_num: 0,
+        }
+    }
+
+    #[test]
+    fn test_fetch_rex_pool() -> Result<(), ResourcesError> {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_table_rows", json!({
+            "rows": [kudu::to_hex(&rex_pool())],
+            "more": false,
+        }));
+        let client = APIClient::with_transport("mock://", mock.clone());
+
+        assert_eq!(RexPool::fetch(&client)?, rex_pool());
+        mock.assert_called_with("/v1/chain/get_table_rows", &json!({
+            "code": "eosio",
+            "scope": "eosio",
+            "table": "rexpool",
+            "json": false,
+            "limit": 1,
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rex_conversion() {
+        let sym: Symbol = "4,EOS".parse().unwrap();
+        let rex: Symbol = "4,REX".parse().unwrap();
+        let mut pool = RexPool {
+            version: 0,
+            total_lent: Asset::new(0, sym).unwrap(),
+            total_unlent: Asset::new(0, sym).unwrap(),
+            total_rent: Asset::new(0, sym).unwrap(),
+            total_lendable: Asset::new(0, sym).unwrap(),
+            total_rex: Asset::new(0, rex).unwrap(),
+            namebid_proceeds: Asset::new(0, sym).unwrap(),
+            loan_num: 0,
+        };
+        assert_eq!(pool.tokens_to_rex(10_000), 100_000_000);
+
+        pool.total_lendable = Asset::new(20_000, sym).unwrap();
+        pool.total_rex = Asset::new(100_000_000, rex).unwrap();
+        assert_eq!(pool.tokens_to_rex(10_000), 50_000_000);
+        assert_eq!(pool.rex_to_tokens(50_000_000), 10_000);
+    }
+}
@@ -0,0 +1,388 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Client for the state history plugin (SHiP) of `nodeos`, which streams blocks,
+//! transaction traces and table deltas over a websocket.
+//!
+//! Messages are encoded using the ABI sent by the node when connecting, and
+//! decoded to JSON values.
+//!
+//! Blocks are requested with a max number of messages in flight, and each one is
+//! only acknowledged when the next one is requested from the [`ShipBlocks`] iterator,
+//! so the node stops sending blocks when the consumer cannot keep up (backpressure).
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::chain::ship::{BlocksRequest, ShipClient};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut client = ShipClient::connect("ws://127.0.0.1:8080")?;
+//! for block in client.blocks(&BlocksRequest::new(1000..2000))? {
+//!     let block = block?;
+//!     println!("block #{}: {} transaction traces", block.block_num, block.traces.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+
+use std::net::TcpStream;
+use std::ops::Range;
+
+use serde::Deserialize;
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use tracing::{debug, trace};
+use tungstenite::{Message, WebSocket, stream::MaybeTlsStream};
+
+use kudu_macros::with_location;
+
+use crate::{ABI, ABIDefinition, BlockId, BlockNum, ByteStream, Bytes, JsonValue, json};
+use crate::abi::ABIError;
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum ShipError {
+    #[snafu(display("cannot connect to state history endpoint: {url}"))]
+    Connect { url: String, source: Box<tungstenite::Error> },
+
+    #[snafu(display("error while communicating with state history endpoint"))]
+    WebSocket { source: Box<tungstenite::Error> },
+
+    #[snafu(display("connection closed by state history endpoint"))]
+    Closed,
+
+    #[snafu(display("invalid ABI sent by state history endpoint"))]
+    InvalidABI { source: ABIError },
+
+    #[snafu(display("cannot encode or decode state history `{typename}`"))]
+    Serialization { typename: String, source: ABIError },
+
+    #[snafu(display("unexpected message from state history endpoint: {message}"))]
+    UnexpectedMessage { message: String },
+}
+
+fn ws_error(source: tungstenite::Error) -> Box<tungstenite::Error> {
+    Box::new(source)
+}
+
+
+/// Position of a block in the chain, as sent by the state history plugin.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct BlockPosition {
+    pub block_num: BlockNum,
+    pub block_id: BlockId,
+}
+
+/// Parameters of a `get_blocks_request_v0` sent to the state history plugin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlocksRequest {
+    /// Range of blocks to fetch, end block excluded
+    pub range: Range<BlockNum>,
+    /// Max number of blocks sent by the node before waiting for them to be
+    /// acknowledged (default: 16)
+    pub max_messages_in_flight: u32,
+    pub irreversible_only: bool,
+    pub fetch_block: bool,
+    pub fetch_traces: bool,
+    pub fetch_deltas: bool,
+}
+
+impl BlocksRequest {
+    /// Return a request for the transaction traces of the blocks in the given range.
+    pub fn new(range: Range<BlockNum>) -> Self {
+        BlocksRequest {
+            range,
+            max_messages_in_flight: 16,
+            irreversible_only: false,
+            fetch_block: false,
+            fetch_traces: true,
+            fetch_deltas: false,
+        }
+    }
+}
+
+/// A block as sent by the state history plugin, with its contents decoded.
+///
+/// Traces and deltas are empty if they have not been requested.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShipBlock {
+    pub block_num: BlockNum,
+    pub block_id: BlockId,
+    pub head: BlockPosition,
+    pub last_irreversible: BlockPosition,
+    /// The `signed_block`, if it has been requested
+    pub block: Option<JsonValue>,
+    /// The `transaction_trace`s of the block
+    pub traces: Vec<JsonValue>,
+    /// The `table_delta`s of the block
+    pub deltas: Vec<JsonValue>,
+}
+
+
+/// A connection to the state history plugin of a node.
+pub struct ShipClient {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    abi: ABI,
+}
+
+impl ShipClient {
+    /// Connect to the given websocket URL (eg: `ws://127.0.0.1:8080`) and read
+    /// the ABI sent by the node.
+    pub fn connect(url: &str) -> Result<ShipClient, ShipError> {
+        let (socket, _) = tungstenite::connect(url).map_err(ws_error).context(ConnectSnafu { url })?;
+        let mut client = ShipClient { socket, abi: ABI::new() };
+
+        let abi = match client.read_message()? {
+            Message::Text(abi) => abi,
+            message => return UnexpectedMessageSnafu { message: format!("{message:?}") }.fail(),
+        };
+        let abi = ABIDefinition::from_str(&abi).and_then(|def| ABI::from_definition(&def));
+        client.abi = abi.context(InvalidABISnafu)?;
+        debug!("connected to state history endpoint: {url}");
+        Ok(client)
+    }
+
+    /// Return the ABI sent by the node, used to encode and decode messages.
+    pub fn abi(&self) -> &ABI {
+        &self.abi
+    }
+
+    /// Return the `get_status_result_v0` of the node.
+    pub fn get_status(&mut self) -> Result<JsonValue, ShipError> {
+        self.send_request(json!(["get_status_request_v0", {}]))?;
+        let (_, status) = self.read_result()?;
+        Ok(status)
+    }
+
+    /// Request the blocks described by `request` and return an iterator over them.
+    pub fn blocks(&mut self, request: &BlocksRequest) -> Result<ShipBlocks<'_>, ShipError> {
+        self.send_request(json!(["get_blocks_request_v0", {
+            "start_block_num": request.range.start,
+            "end_block_num": request.range.end,
+            "max_messages_in_flight": request.max_messages_in_flight,
+            "have_positions": [],
+            "irreversible_only": request.irreversible_only,
+            "fetch_block": request.fetch_block,
+            "fetch_traces": request.fetch_traces,
+            "fetch_deltas": request.fetch_deltas,
+        }]))?;
+        Ok(ShipBlocks {
+            client: self,
+            end: request.range.end,
+            pending_ack: false,
+            done: request.range.is_empty(),
+        })
+    }
+
+    fn send_request(&mut self, request: JsonValue) -> Result<(), ShipError> {
+        let bin = self.abi.variant_to_binary("request", &request)
+            .context(SerializationSnafu { typename: "request" })?;
+        trace!("sending state history request: {request}");
+        self.socket.send(Message::binary(Vec::from(bin))).map_err(ws_error).context(WebSocketSnafu)
+    }
+
+    fn read_message(&mut self) -> Result<Message, ShipError> {
+        loop {
+            match self.socket.read().map_err(ws_error).context(WebSocketSnafu)? {
+                // pings are answered automatically when reading
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {},
+                Message::Close(_) => return ClosedSnafu.fail(),
+                message => return Ok(message),
+            }
+        }
+    }
+
+    /// Read a `result` message and return its type and value.
+    fn read_result(&mut self) -> Result<(String, JsonValue), ShipError> {
+        let data = match self.read_message()? {
+            Message::Binary(data) => data,
+            message => return UnexpectedMessageSnafu { message: format!("{message:?}") }.fail(),
+        };
+        let result = self.abi.decode_variant(&mut ByteStream::from(&data[..]), "result")
+            .context(SerializationSnafu { typename: "result" })?;
+        match result {
+            JsonValue::Array(mut v) if v.len() == 2 => {
+                let value = v.pop().unwrap_or_default();
+                let tag = v.pop().and_then(|t| t.as_str().map(String::from)).unwrap_or_default();
+                Ok((tag, value))
+            },
+            result => UnexpectedMessageSnafu { message: result.to_string() }.fail(),
+        }
+    }
+
+    /// Decode a field of a `get_blocks_result`, which is either already decoded or
+    /// given as the hex representation of an instance of `typename`.
+    fn decode_field(&self, value: &JsonValue, typename: &str) -> Result<Option<JsonValue>, ShipError> {
+        match value {
+            JsonValue::Null => Ok(None),
+            JsonValue::String(hex) => {
+                let bin = Bytes::from_hex(hex).ok().context(UnexpectedMessageSnafu { message: hex })?;
+                if bin.is_empty() {
+                    return Ok(None);
+                }
+                let decoded = self.abi.binary_to_variant(typename, bin)
+                    .context(SerializationSnafu { typename })?;
+                Ok(Some(decoded))
+            },
+            decoded => Ok(Some(decoded.clone())),
+        }
+    }
+
+    fn decode_list(&self, value: &JsonValue, typename: &str) -> Result<Vec<JsonValue>, ShipError> {
+        match self.decode_field(value, typename)? {
+            Some(JsonValue::Array(items)) => Ok(items),
+            Some(other) => UnexpectedMessageSnafu { message: other.to_string() }.fail(),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+
+/// Iterator over the blocks sent by the state history plugin, see [`ShipClient::blocks()`].
+pub struct ShipBlocks<'a> {
+    client: &'a mut ShipClient,
+    end: BlockNum,
+    pending_ack: bool,
+    done: bool,
+}
+
+impl ShipBlocks<'_> {
+    fn next_block(&mut self) -> Result<Option<ShipBlock>, ShipError> {
+        loop {
+            if self.pending_ack {
+                self.client.send_request(json!(["get_blocks_ack_request_v0", {"num_messages": 1}]))?;
+                self.pending_ack = false;
+            }
+
+            let (tag, result) = self.client.read_result()?;
+            ensure!(tag.starts_with("get_blocks_result_"), UnexpectedMessageSnafu { message: tag });
+            self.pending_ack = true;
+
+            // results without a block can be sent while waiting for new blocks
+            if result["this_block"].is_null() {
+                continue;
+            }
+
+            let position = |field: &str| -> Result<BlockPosition, ShipError> {
+                serde_json::from_value(result[field].clone()).ok()
+                    .context(UnexpectedMessageSnafu { message: result[field].to_string() })
+            };
+            let this_block = position("this_block")?;
+            let block = ShipBlock {
+                block_num: this_block.block_num,
+                block_id: this_block.block_id,
+                head: position("head")?,
+                last_irreversible: position("last_irreversible")?,
+                block: self.client.decode_field(&result["block"], "signed_block")?,
+                traces: self.client.decode_list(&result["traces"], "transaction_trace[]")?,
+                deltas: self.client.decode_list(&result["deltas"], "table_delta[]")?,
+            };
+
+            #[cfg(feature = "metrics")]
+            {
+                use crate::metrics::{SHIP_BLOCKS_RECEIVED, SHIP_HEAD_LAG};
+                ::metrics::counter!(SHIP_BLOCKS_RECEIVED).increment(1);
+                ::metrics::gauge!(SHIP_HEAD_LAG).set(block.head.block_num.saturating_sub(block.block_num));
+            }
+
+            if block.block_num.saturating_add(1) >= self.end {
+                self.done = true;
+            }
+            return Ok(Some(block));
+        }
+    }
+}
+
+impl Iterator for ShipBlocks<'_> {
+    type Item = Result<ShipBlock, ShipError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_block();
+        if result.is_err() {
+            #[cfg(feature = "metrics")]
+            if matches!(result, Err(ShipError::Serialization { .. })) {
+                ::metrics::counter!(crate::metrics::SHIP_DECODE_ERRORS).increment(1);
+            }
+            self.done = true;
+        }
+        result.transpose()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use color_eyre::eyre::Result;
+
+    use crate::abi::data::STATE_HISTORY_PLUGIN_ABI;
+    use super::*;
+
+    const BLOCK_ID: &str = "0000000a3bdc1b3a4a3af7d7b8cd0e15e7b5b4f4d0f7a0ee1ba97ed8a8b4c1e2";
+
+    /// Run a state history server answering a single blocks request with the given
+    /// number of blocks, and return the `num_messages` of all the acks it received.
+    fn mock_server(listener: TcpListener, num_blocks: u32) -> Vec<u32> {
+        let abi = ABI::from_str(STATE_HISTORY_PLUGIN_ABI).unwrap();
+        let mut socket = tungstenite::accept(listener.accept().unwrap().0).unwrap();
+        socket.send(Message::text(STATE_HISTORY_PLUGIN_ABI)).unwrap();
+
+        let read_request = |socket: &mut WebSocket<TcpStream>| {
+            let data = socket.read().unwrap().into_data();
+            abi.decode_variant(&mut ByteStream::from(&data[..]), "request").unwrap()
+        };
+
+        let request = read_request(&mut socket);
+        assert_eq!(request[0], "get_blocks_request_v0");
+        assert_eq!(request[1]["max_messages_in_flight"], 1);
+        let start = request[1]["start_block_num"].as_u64().unwrap() as u32;
+
+        let mut acks = vec![];
+        for block_num in start..start + num_blocks {
+            if block_num > start {
+                // max 1 message in flight, wait for the ack of the previous block
+                let ack = read_request(&mut socket);
+                acks.push(ack[1]["num_messages"].as_u64().unwrap() as u32);
+            }
+            let position = json!({"block_num": block_num, "block_id": BLOCK_ID});
+            let result = json!(["get_blocks_result_v0", {
+                "head": position,
+                "last_irreversible": position,
+                "this_block": position,
+                "prev_block": null,
+                "block": null,
+                "traces": "00",  // empty `transaction_trace[]`
+                "deltas": null,
+            }]);
+            let bin = abi.variant_to_binary("result", &result).unwrap();
+            socket.send(Message::binary(Vec::from(bin))).unwrap();
+        }
+        acks
+    }
+
+    #[test]
+    fn test_ship_blocks() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        let server = thread::spawn(move || mock_server(listener, 3));
+
+        let mut client = ShipClient::connect(&url)?;
+        assert!(client.abi().has_struct("get_blocks_request_v0"));
+
+        let request = BlocksRequest { max_messages_in_flight: 1, ..BlocksRequest::new(10..13) };
+        let blocks = client.blocks(&request)?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(blocks.iter().map(|b| b.block_num).collect::<Vec<_>>(), [10, 11, 12]);
+        assert!(blocks.iter().all(|b| b.traces.is_empty() && b.block.is_none()));
+        assert_eq!(blocks[0].block_id.to_hex(), BLOCK_ID);
+
+        // the last block is not acked as no more blocks have been requested
+        assert_eq!(server.join().unwrap(), [1, 1]);
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use snafu::{OptionExt, ResultExt};
+use tracing::{debug, warn};
+
+use crate::{
+    APIClient, BlockNum, JsonValue, SignedTransaction, TransactionError, TransactionId, json,
+    api::{ConfirmationDepth, ConfirmationWatcher, HttpError, HttpSnafu},
+};
+
+use super::transaction::{
+    ConfirmationTimeoutSnafu, NetworkSnafu, NodeosSnafu, UnlinkedTransactionSnafu,
+};
+
+/// Name of the error returned by nodeos when it already knows a transaction.
+const TX_DUPLICATE: &str = "tx_duplicate";
+
+
+/// Options controlling how [`SignedTransaction::submit_with()`] retries pushing
+/// a transaction.
+///
+/// Only transient errors are retried, see [`HttpError::is_transient()`]. The delay
+/// between attempts starts at `initial_delay` and doubles after each attempt, up
+/// to `max_delay`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmitOptions {
+    /// Maximum number of times the transaction is pushed
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for SubmitOptions {
+    fn default() -> Self {
+        SubmitOptions {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+
+/// A transaction that has been accepted by a node, as returned by
+/// [`SignedTransaction::submit()`].
+///
+/// Being accepted does not mean that the transaction is part of a block yet, use
+/// [`Submission::wait_for_inclusion()`] to make sure it is.
+#[derive(Clone, Debug)]
+pub struct Submission {
+    pub transaction_id: TransactionId,
+    /// Whether the node reported the transaction as a duplicate, ie: it had already
+    /// been accepted, typically by a previous attempt whose response got lost.
+    pub duplicate: bool,
+    /// Number of times the transaction has been pushed
+    pub attempts: u32,
+    /// Response of the node to the push request, `None` if it was a duplicate
+    pub response: Option<JsonValue>,
+    client: Arc<APIClient>,
+}
+
+impl Submission {
+    /// Return a [`ConfirmationWatcher`] waiting until the transaction reaches the
+    /// given confirmation depth, see [`APIClient::watch_confirmation()`].
+    pub fn watch(&self, depth: ConfirmationDepth) -> ConfirmationWatcher<'_> {
+        self.client.watch_confirmation(&self.transaction_id, depth)
+    }
+
+    /// Poll the `/v1/chain/get_transaction_status` endpoint until the transaction
+    /// is included in a block and return the number of that block.
+    ///
+    /// This requires the node to run the `chain_api_plugin` with the
+    /// `transaction-finality-status-max-storage-size-gb` option enabled. Transient
+    /// errors while polling are ignored, and a [`TransactionError::ConfirmationTimeout`]
+    /// error is returned if the transaction is not included in time.
+    pub fn wait_for_inclusion(&self, timeout: Duration, poll_interval: Duration)
+                              -> Result<BlockNum, TransactionError> {
+        let status = match self.watch(ConfirmationDepth::Blocks(1))
+            .timeout(timeout)
+            .poll_interval(poll_interval)
+            .wait()
+        {
+            Ok(status) => status,
+            Err(HttpError::ConfirmationTimeout { transaction_id, timeout, .. }) => {
+                return ConfirmationTimeoutSnafu { transaction_id, timeout }.fail();
+            },
+            Err(e @ HttpError::TransactionFailed { .. }) => return NodeosSnafu { message: e.to_string() }.fail(),
+            Err(e) => return Err(e).context(NetworkSnafu { message: "cannot get transaction status" }),
+        };
+        status.block_number.with_context(|| NodeosSnafu {
+            message: format!("no block number in status of included transaction {}", self.transaction_id.to_hex())
+        })
+    }
+}
+
+
+impl SignedTransaction {
+    /// Push the transaction, retrying on transient errors, see [`SubmitOptions`].
+    ///
+    /// Pushing the same signed transaction several times is idempotent: if a previous
+    /// attempt went through but its response was lost, nodeos answers the next one
+    /// with a `tx_duplicate` error, which is treated as a success here.
+    pub fn submit(&self) -> Result<Submission, TransactionError> {
+        self.submit_with(&SubmitOptions::default())
+    }
+
+    /// Push the transaction with the given retry options, see [`SignedTransaction::submit()`].
+    pub fn submit_with(&self, options: &SubmitOptions) -> Result<Submission, TransactionError> {
+        let client = self.tx.client.clone()
+            .with_context(|| UnlinkedTransactionSnafu { message: "cannot send transaction" })?;
+        let signed_tx = json!(self);
+        let transaction_id = self.tx.id();
+
+        let mut delay = options.initial_delay;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let error = match client.send("/v1/chain/push_transaction", Some(&signed_tx)) {
+                Ok(response) if !(400..600).contains(&response.status) => {
+                    return Ok(Submission {
+                        transaction_id, duplicate: false, attempts, response: Some(response.body), client,
+                    });
+                },
+                Ok(response) if response.body["error"]["name"] == TX_DUPLICATE => {
+                    debug!("transaction {} already known by the node", transaction_id.to_hex());
+                    return Ok(Submission {
+                        transaction_id, duplicate: true, attempts, response: None, client,
+                    });
+                },
+                Ok(response) => HttpSnafu {
+                    code: response.status,
+                    message: response.body["error"].to_string(),
+                }.build(),
+                Err(e) => e,
+            };
+
+            if !error.is_transient() {
+                // errors coming from nodeos (as opposed to the network) are final
+                return match &error {
+                    HttpError::HttpError { message, .. } => NodeosSnafu { message: message.clone() }.fail(),
+                    _ => Err(error).context(NetworkSnafu { message: "could not push transaction" }),
+                };
+            }
+            if attempts >= options.max_attempts {
+                return Err(error).context(NetworkSnafu {
+                    message: format!("could not push transaction after {attempts} attempts"),
+                });
+            }
+
+            warn!("could not push transaction {} (attempt {attempts}/{}), retrying in {delay:?}: {error}",
+                  transaction_id.to_hex(), options.max_attempts);
+            thread::sleep(delay);
+            delay = (delay * 2).min(options.max_delay);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use crate::{PrivateKey, Transaction, api::{ApiResponse, MockTransport}};
+    use super::*;
+
+    fn mock_client() -> (Arc<MockTransport>, Arc<APIClient>) {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_info", json!({
+            "chain_id": crate::config::JUNGLE_CHAIN_ID,
+            "last_irreversible_block_id": "0eeb31a70905138203051bf848fc7176336a0eb41d078338460af949d8cf2abd",
+            "head_block_time": "2024-01-01T00:00:00.000",
+        }));
+        let client = Arc::new(APIClient::with_transport("mock://", mock.clone()));
+        (mock, client)
+    }
+
+    fn signed_tx(client: Arc<APIClient>) -> Result<SignedTransaction> {
+        let mut tx = Transaction::new(vec![]);
+        tx.link(client)?;
+        Ok(tx.sign(&PrivateKey::eosio_dev())?)
+    }
+
+    fn nodeos_error(name: &str) -> ApiResponse {
+        ApiResponse {
+            status: 500,
+            body: json!({"code": 500, "message": "Internal Service Error", "error": {"code": 0, "name": name}}),
+        }
+    }
+
+    const NO_DELAY: SubmitOptions = SubmitOptions {
+        max_attempts: 3,
+        initial_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    #[test]
+    fn test_submit() -> Result<()> {
+        // first attempt goes through
+        let (mock, client) = mock_client();
+        mock.respond("/v1/chain/push_transaction", json!({"transaction_id": "abcd"}));
+        let tx = signed_tx(client)?;
+        let submission = tx.submit_with(&NO_DELAY)?;
+        assert_eq!(submission.transaction_id, tx.tx.id());
+        assert_eq!((submission.duplicate, submission.attempts), (false, 1));
+        assert_eq!(submission.response.unwrap()["transaction_id"], "abcd");
+
+        // transient errors are retried, and a duplicate means the transaction went through
+        let (mock, client) = mock_client();
+        mock.respond_with("/v1/chain/push_transaction", ApiResponse { status: 503, body: json!({}) })
+            .respond_with("/v1/chain/push_transaction", nodeos_error("tx_duplicate"));
+        let submission = signed_tx(client)?.submit_with(&NO_DELAY)?;
+        assert_eq!((submission.duplicate, submission.attempts), (true, 2));
+        assert!(submission.response.is_none());
+
+        // nodeos errors are not retried
+        let (mock, client) = mock_client();
+        mock.respond_with("/v1/chain/push_transaction", nodeos_error("eosio_assert_message_exception"));
+        let result = signed_tx(client)?.submit_with(&NO_DELAY);
+        assert!(matches!(result, Err(TransactionError::NodeosError { .. })));
+        assert_eq!(mock.requests_to("/v1/chain/push_transaction").len(), 1);
+
+        // give up after the maximum number of attempts
+        let (mock, client) = mock_client();
+        mock.respond_with("/v1/chain/push_transaction", ApiResponse { status: 502, body: json!({}) });
+        let result = signed_tx(client)?.submit_with(&NO_DELAY);
+        assert!(matches!(result, Err(TransactionError::NetworkError { .. })));
+        assert_eq!(mock.requests_to("/v1/chain/push_transaction").len(), 3);
+
+        Ok(())
+    }
+
+    fn status(state: &str, block_number: Option<u32>) -> JsonValue {
+        let block_id = "0eeb31a70905138203051bf848fc7176336a0eb41d078338460af949d8cf2abd";
+        json!({
+            "state": state,
+            "block_number": block_number,
+            "block_id": block_number.map(|_| block_id),
+            "head_number": 100, "head_id": block_id, "head_timestamp": "2024-01-01T00:00:00.000",
+            "irreversible_number": 90, "irreversible_id": block_id, "irreversible_timestamp": "2024-01-01T00:00:00.000",
+            "earliest_tracked_block_number": 1, "earliest_tracked_block_id": block_id,
+        })
+    }
+
+    #[test]
+    fn test_wait_for_inclusion() -> Result<()> {
+        let (mock, client) = mock_client();
+        mock.respond("/v1/chain/push_transaction", json!({"transaction_id": "abcd"}))
+            .respond("/v1/chain/get_transaction_status", status("UNKNOWN", None))
+            .respond("/v1/chain/get_transaction_status", status("LOCALLY_APPLIED", None))
+            .respond("/v1/chain/get_transaction_status", status("IN_BLOCK", Some(42)));
+        let submission = signed_tx(client)?.submit_with(&NO_DELAY)?;
+        assert_eq!(submission.wait_for_inclusion(Duration::from_secs(1), Duration::ZERO)?, 42);
+        mock.assert_called_with("/v1/chain/get_transaction_status",
+                                &json!({"id": submission.transaction_id.to_hex()}));
+
+        let (mock, client) = mock_client();
+        mock.respond("/v1/chain/push_transaction", json!({"transaction_id": "abcd"}))
+            .respond("/v1/chain/get_transaction_status", status("UNKNOWN", None));
+        let submission = signed_tx(client)?.submit_with(&NO_DELAY)?;
+        let result = submission.wait_for_inclusion(Duration::from_millis(20), Duration::from_millis(5));
+        assert!(matches!(result, Err(TransactionError::ConfirmationTimeout { .. })));
+
+        Ok(())
+    }
+}
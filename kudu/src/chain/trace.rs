@@ -4,8 +4,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    AccountName, Action, ActionName, BlockId, BlockTimestamp, Digest, MicroSeconds,
-    TransactionId, VarUint32, Name, ABISerializable,
+    AccountName, Action, BlockId, BlockTimestamp, Digest, MicroSeconds,
+    TransactionId, VarUint32, ABISerializable,
     Bytes, Signature, SerializeEnumPrefixed, Transaction, Set,
 };
 
@@ -21,7 +21,7 @@ extern crate self as kudu;
 
 #[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
 pub struct AccountAuthSequence {
-    pub account: Name,
+    pub account: AccountName,
     pub sequence: u64,
 }
 
@@ -60,7 +60,7 @@ pub struct Trace {
     pub creator_action_ordinal: VarUint32,
     pub closest_unnotified_ancestor_action_ordinal: VarUint32,
     pub receipt: Option<ActionReceipt>,
-    pub receiver: ActionName,
+    pub receiver: AccountName,
     pub act: Action,
     pub context_free: bool, // = false;
     pub elapsed: MicroSeconds,
@@ -95,7 +95,7 @@ pub struct ActionTraceV0 {
     pub action_ordinal: VarUint32,
     pub creator_action_ordinal: VarUint32,
     pub receipt: Option<ActionReceipt>,
-    pub receiver: Name,
+    pub receiver: AccountName,
     pub act: Action,
     pub context_free: bool,
     pub elapsed: i64,
@@ -110,7 +110,7 @@ pub struct ActionTraceV1 {
     pub action_ordinal: VarUint32,
     pub creator_action_ordinal: VarUint32,
     pub receipt: Option<ActionReceipt>,
-    pub receiver: Name,
+    pub receiver: AccountName,
     pub act: Action,
     pub context_free: bool,
     pub elapsed: i64,
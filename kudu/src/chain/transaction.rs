@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytemuck::cast_ref;
 use chrono::ParseError as ChronoParseError;
@@ -15,9 +16,10 @@ use snafu::{OptionExt, ResultExt, Snafu, ensure};
 
 use crate::{
     ABISerializable, APIClient, Action, ActionError, BlockId, Bytes, ChainId,
-    Checksum256, Extensions, JsonValue, PrivateKey, Signature, TimePointSec, TransactionId,
-    VarUint32,
+    Checksum256, Extensions, JsonValue, PrivateKey, SerializeError, Signature, TimePointSec,
+    TransactionId, VarUint32,
     api::HttpError,
+    chain::extensions::{ExtensionType, get_extension},
     bitops::endian_reverse_u32,
     convert::{ConversionError,  variant_to_object, variant_to_str, variant_to_uint},
     impl_auto_error_conversion, json, with_location
@@ -42,10 +44,10 @@ pub enum TransactionError {
     #[snafu(display("invalid action"))]
     InvalidAction { source: ActionError },
 
-    #[snafu(display("unlinked transaction: {message}"))]
+    #[snafu(display("unlinked transaction: {message}"), visibility(pub(crate)))]
     UnlinkedTransaction { message: String },
 
-    #[snafu(display("network error: {message}\ndetails: {source}"))]
+    #[snafu(display("network error: {message}\ndetails: {source}"), visibility(pub(crate)))]
     NetworkError { message: String, source: HttpError  },
 
     #[snafu(display("invalid chain id: {chain_id}"))]
@@ -54,8 +56,20 @@ pub enum TransactionError {
     #[snafu(display("could not match JSON object to transaction"))]
     FromJson { source: serde_json::Error },
 
-    #[snafu(display("Nodeos error: {message}"))]
+    #[snafu(display("Nodeos error: {message}"), visibility(pub(crate)))]
     NodeosError { message: String },
+
+    #[snafu(display("transaction {transaction_id} not included in a block after {timeout:?}"), visibility(pub(crate)))]
+    ConfirmationTimeout { transaction_id: String, timeout: Duration },
+
+    #[snafu(display("packed transaction is {size} bytes, max allowed is {max}"), visibility(pub(crate)))]
+    TransactionTooLarge { size: usize, max: u32 },
+
+    #[snafu(display("invalid transaction expiration: {message}"), visibility(pub(crate)))]
+    InvalidExpiration { message: String },
+
+    #[snafu(display("{resource} usage of {usage} exceeds the max of {max} per transaction"), visibility(pub(crate)))]
+    ResourceLimitExceeded { resource: &'static str, usage: u64, max: u64 },
 }
 
 impl_auto_error_conversion!(ChronoParseError, TransactionError, DateTimeParseSnafu);
@@ -101,10 +115,19 @@ pub struct Transaction {
 
     #[serde(skip)]
     pub client: Option<Arc<APIClient>>,
+
+    /// Delay after the head block time at which the transaction expires when it is
+    /// linked, [`DEFAULT_EXPIRATION_DELAY`] if `None`.
+    #[serde(skip)]
+    pub expiration_delay: Option<Duration>,
 }
 
 type DigestType = Checksum256;
 
+/// Delay after the head block time at which a transaction expires, unless set
+/// using [`Transaction::expires_in()`].
+pub const DEFAULT_EXPIRATION_DELAY: Duration = Duration::from_secs(120);
+
 
 impl Transaction {
     pub fn new(actions: Vec<Action>) -> Transaction {
@@ -119,9 +142,26 @@ impl Transaction {
             actions,
             transaction_extensions: vec![],
             chain_id: None,
-            client: None
+            client: None,
+            expiration_delay: None,
         }
     }
+
+    /// Set the delay after which the transaction expires, counted from the head
+    /// block time when it is linked (see [`Transaction::link()`]).
+    ///
+    /// Note that nodeos refuses transactions expiring more than one hour after the
+    /// head block time.
+    pub fn expires_in(mut self, delay: Duration) -> Self {
+        self.expiration_delay = Some(delay);
+        self
+    }
+
+    /// Return whether this transaction is expired given the time of the head block.
+    pub fn is_expired(&self, head_block_time: TimePointSec) -> bool {
+        self.expiration <= head_block_time
+    }
+
     pub fn id(&self) -> TransactionId {
         let mut data = Bytes::new();
         self.to_bin(&mut data);
@@ -130,6 +170,11 @@ impl Transaction {
         r.into()
     }
 
+    /// Return the transaction extension of type `T`, if this transaction has one.
+    pub fn extension<T: ExtensionType>(&self) -> Result<Option<T>, SerializeError> {
+        get_extension(&self.transaction_extensions)
+    }
+
     /// Create a new `Transaction` from a JSON value containing the non-default fields.
     /// You should make sure that the necessary ABIs are properly loaded in the registry
     /// if the data fields for the `Actions` are not encoded yet, it is unnecessary otherwise.
@@ -152,6 +197,14 @@ impl Transaction {
         Ok(result)
     }
 
+    /// Create a new `Transaction` from a string of JSON text, see [`Transaction::from_json()`].
+    ///
+    /// As the transaction is meant to be signed, objects with duplicate keys are
+    /// rejected instead of silently keeping the last value, see [`json::parse_strict()`].
+    pub fn from_json_str(tx: &str) -> Result<Transaction, TransactionError> {
+        Transaction::from_json(&json::parse_strict(tx)?)
+    }
+
     pub fn sig_digest(&self, context_free_data: &[u8]) -> Result<DigestType, TransactionError> {
         let mut hasher = Sha256::new();
         match &self.chain_id {
@@ -182,8 +235,11 @@ impl Transaction {
     }
 
     pub fn link(&mut self, client: Arc<APIClient>) -> Result<&mut Self, TransactionError> {
-        let info = client.get("/v1/chain/get_info").context(NetworkSnafu { message: "cannot get chain info".to_string() })?;
+        let info = get_info(&client)?;
+        self.link_with_info(client, &info)
+    }
 
+    fn link_with_info(&mut self, client: Arc<APIClient>, info: &JsonValue) -> Result<&mut Self, TransactionError> {
         let block_id = info["last_irreversible_block_id"].as_str()
             .context(NodeosSnafu { message: "chain info 'last_irreversible_block_id' is not a string" })?;
         let block_id = BlockId::from_hex(block_id)
@@ -200,11 +256,8 @@ impl Transaction {
         self.chain_id = Some(ChainId::from_hex(chain_id).context(InvalidChainIdSnafu { chain_id })?);
 
         // set expiration time
-        let expiration_delay_seconds = 120;
-        let head_block_time: TimePointSec = info["head_block_time"].as_str()
-            .context(NodeosSnafu { message: "chain info 'head_block_time' is not a string" })?
-            .parse()?;
-        self.expiration = head_block_time + expiration_delay_seconds;
+        let expiration_delay = self.expiration_delay.unwrap_or(DEFAULT_EXPIRATION_DELAY);
+        self.expiration = head_block_time(info)? + expiration_delay.as_secs() as u32;
 
         // save client for sending later
         self.client = Some(client);
@@ -232,6 +285,16 @@ impl Transaction {
 
 }
 
+pub(super) fn get_info(client: &APIClient) -> Result<JsonValue, TransactionError> {
+    client.get("/v1/chain/get_info").context(NetworkSnafu { message: "cannot get chain info".to_string() })
+}
+
+pub(super) fn head_block_time(info: &JsonValue) -> Result<TimePointSec, TransactionError> {
+    Ok(info["head_block_time"].as_str()
+        .context(NodeosSnafu { message: "chain info 'head_block_time' is not a string" })?
+        .parse()?)
+}
+
 // TODO: we implement this manually as we don't have a way yet to ignore fields using the derive macro
 // TODO: implement this, using #[serde(skip)] to decide whether to skip fields
 impl kudu::ABISerializable for Transaction {
@@ -265,6 +328,7 @@ impl kudu::ABISerializable for Transaction {
             // FIXME!!: we need to give proper values here
             chain_id: None,
             client: None,
+            expiration_delay: None,
         })
     }
 }
@@ -288,6 +352,26 @@ impl SignedTransaction {
         Ok(result)
     }
 
+    /// If the transaction has expired, refresh its expiration and reference block
+    /// (see [`Transaction::link()`]) and sign it again with the given keys, replacing
+    /// the previous signatures.
+    ///
+    /// This is useful when a signed transaction is held for some time before being
+    /// sent, eg: in a queue. Return whether the transaction has been re-signed.
+    pub fn resign_if_expired(&mut self, client: Arc<APIClient>, signing_keys: &[PrivateKey])
+                             -> Result<bool, TransactionError> {
+        let info = get_info(&client)?;
+        if !self.tx.is_expired(head_block_time(&info)?) {
+            return Ok(false);
+        }
+
+        self.tx.link_with_info(client, &info)?;
+        self.signatures = signing_keys.iter()
+            .map(|key| self.tx.get_signature(key))
+            .collect::<Result<_, _>>()?;
+        Ok(true)
+    }
+
     pub fn send_unchecked(&self) -> Result<JsonValue, TransactionError> {
         let signed_tx = json!(self);
         let result = self.tx.client.as_ref()
@@ -314,7 +398,7 @@ impl Serialize for SignedTransaction {
 
         let mut s = Bytes::new();
         self.tx.to_bin(&mut s);
-        map.serialize_entry("packed_trx", &s.to_hex())?;
+        map.serialize_entry("packed_trx", &s.hex())?;
 
         map.end()
     }
@@ -325,9 +409,17 @@ impl Serialize for SignedTransaction {
 mod tests {
     use color_eyre::eyre::Result;
 
-    use crate::{chain::Transfer, Name};
+    use crate::{contract, AccountName, Asset, api::MockTransport};
     use super::*;
 
+    #[derive(ABISerializable)]
+    #[contract(account="eosio.token", name="transfer")]
+    struct Transfer {
+        from: AccountName,
+        to: AccountName,
+        quantity: Asset,
+        memo: String,
+    }
 
 
     #[test]
@@ -343,8 +435,8 @@ mod tests {
     fn test_sign_transaction() -> Result<()> {
 
         let transfer = Transfer {
-            from: Name::new("useraaaaaaaa")?,
-            to: Name::new("useraaaaaaab")?,
+            from: AccountName::new("useraaaaaaaa")?,
+            to: AccountName::new("useraaaaaaab")?,
             quantity: "0.0001 SYS".try_into()?,
             memo: "".into(),
         };
@@ -396,4 +488,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_link_and_send_with_mock() -> Result<()> {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_info", json!({
+            "chain_id": crate::config::JUNGLE_CHAIN_ID,
+            "last_irreversible_block_id": "0eeb31a70905138203051bf848fc7176336a0eb41d078338460af949d8cf2abd",
+            "head_block_time": "2024-01-01T00:00:00.000",
+        }));
+        mock.respond("/v1/chain/push_transaction", json!({"transaction_id": "abcd"}));
+        let client = Arc::new(APIClient::with_transport("mock://", mock.clone()));
+
+        let mut tx = Transaction::new(vec![]);
+        tx.link(client)?;
+        assert_eq!((tx.ref_block_num, tx.ref_block_prefix), (12711, 4162520323));
+        assert_eq!(tx.expiration, "2024-01-01T00:02:00".parse()?);
+
+        let signed_tx = tx.sign(&PrivateKey::eosio_dev())?;
+        assert_eq!(signed_tx.send()?["transaction_id"], "abcd");
+        mock.assert_called_with("/v1/chain/push_transaction", &json!(signed_tx));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resign_if_expired() -> Result<()> {
+        let info = |head_block_time: &str, lib: &str| json!({
+            "chain_id": crate::config::JUNGLE_CHAIN_ID,
+            "last_irreversible_block_id": lib,
+            "head_block_time": head_block_time,
+        });
+        let lib1 = "0eeb31a70905138203051bf848fc7176336a0eb41d078338460af949d8cf2abd";
+        let lib2 = "0fd0d8ae1d1c58fd07e8a8d59a0272a44b8bf9f1156ebfa3b539daa0215f7177";
+
+        // successive responses for linking, then for each call to `resign_if_expired()`
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_info", info("2024-01-01T00:00:00.000", lib1))
+            .respond("/v1/chain/get_info", info("2024-01-01T00:00:29.500", lib1))
+            .respond("/v1/chain/get_info", info("2024-01-01T00:00:30.000", lib2));
+        let client = Arc::new(APIClient::with_transport("mock://", mock.clone()));
+
+        let mut tx = Transaction::new(vec![]).expires_in(Duration::from_secs(30));
+        tx.link(client.clone())?;
+        assert_eq!(tx.expiration, "2024-01-01T00:00:30".parse()?);
+
+        let key = PrivateKey::eosio_dev();
+        let mut signed_tx = tx.sign(&key)?;
+        let original = signed_tx.clone();
+
+        // not expired yet
+        assert!(!signed_tx.resign_if_expired(client.clone(), std::slice::from_ref(&key))?);
+        assert_eq!(signed_tx, original);
+
+        // expired, the expiration delay is kept
+        assert!(signed_tx.resign_if_expired(client, std::slice::from_ref(&key))?);
+        assert_eq!(signed_tx.tx.expiration, "2024-01-01T00:01:00".parse()?);
+        assert_ne!(signed_tx.tx.ref_block_num, original.tx.ref_block_num);
+        assert_eq!(signed_tx.signatures, [key.sign_digest(signed_tx.tx.sig_digest(b"")?)]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,270 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Helpers to inspect the WebAssembly code deployed with a `setcode` action.
+//!
+//! This allows to track the provenance of the code of a contract: the hash of the
+//! code (which is the `code_hash` returned by the `/v1/chain/get_code_hash` API
+//! endpoint) and the metadata found in its custom sections, such as the
+//! [`producers`](https://github.com/WebAssembly/tool-conventions/blob/main/ProducersSection.md)
+//! section or an embedded ABI.
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::{Action, chain::wasm::{WasmCode, WasmError}};
+//! # fn main() -> Result<(), WasmError> {
+//! # let action = Action::default();
+//! let wasm = WasmCode::from_action(&action)?;
+//! println!("code hash: {}", wasm.hash().to_hex());
+//! if let Some(producers) = wasm.producers()? {
+//!     println!("compiled with: {:?}", producers.get("processed-by"));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::string::FromUtf8Error;
+
+use sha2::{Digest, Sha256};
+use snafu::{ensure, ResultExt, Snafu};
+
+use crate::{
+    ABIDefinition, ABIError, ABISerializable, AccountName, Action, ActionName, ByteStream, Bytes,
+    Checksum256, Contract, SerializeError, SetCode, StreamError, with_location,
+};
+
+
+/// Magic number found at the start of all wasm modules
+const WASM_MAGIC: &[u8; 4] = b"\0asm";
+
+/// Version of the wasm binary format supported
+const WASM_VERSION: u32 = 1;
+
+const CUSTOM_SECTION_ID: u8 = 0;
+
+/// Name of the custom section containing the ABI, if it has been embedded in the code
+pub const ABI_SECTION_NAME: &str = "abi";
+
+/// Name of the custom section containing the tools used to produce the code
+pub const PRODUCERS_SECTION_NAME: &str = "producers";
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum WasmError {
+    #[snafu(display("action {account}::{name} is not a `setcode` action"))]
+    NotSetCode { account: AccountName, name: ActionName },
+
+    #[snafu(display("cannot decode `setcode` action data"))]
+    ActionData { source: SerializeError },
+
+    #[snafu(display("not a wasm module: invalid magic number {magic:02x?}"))]
+    InvalidMagic { magic: Vec<u8> },
+
+    #[snafu(display("unsupported wasm version: {version}"))]
+    UnsupportedVersion { version: u32 },
+
+    #[snafu(display("truncated wasm module"))]
+    Truncated { source: StreamError },
+
+    #[snafu(display("invalid UTF-8 name in wasm custom section"))]
+    InvalidName { source: FromUtf8Error },
+
+    #[snafu(display("invalid ABI embedded in custom section `{section}`"))]
+    InvalidABI {
+        section: String,
+        #[snafu(source(from(ABIError, Box::new)))]
+        source: Box<ABIError>,
+    },
+}
+
+type Result<T, E = WasmError> = std::result::Result<T, E>;
+
+
+/// A custom section of a wasm module, which contains arbitrary data identified by a name.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct CustomSection {
+    pub name: String,
+    pub data: Bytes,
+}
+
+/// Content of the `producers` section, mapping a field name (`language`, `processed-by`
+/// or `sdk`) to a list of `(name, version)` pairs.
+pub type Producers = BTreeMap<String, Vec<(String, String)>>;
+
+
+/// The code of a contract, along with its hash and the custom sections it contains.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct WasmCode {
+    code: Bytes,
+    hash: Checksum256,
+    custom_sections: Vec<CustomSection>,
+}
+
+impl WasmCode {
+    /// Parse the given wasm module. Only the section headers and the custom sections
+    /// are decoded, the module itself is not validated.
+    pub fn new(code: impl Into<Bytes>) -> Result<Self> {
+        let code = code.into();
+        let hash: [u8; 32] = Sha256::digest(&code).into();
+        let custom_sections = parse_custom_sections(&code)?;
+        Ok(WasmCode { code, hash: hash.into(), custom_sections })
+    }
+
+    /// Extract the code deployed by a `setcode` action.
+    pub fn from_action(action: &Action) -> Result<Self> {
+        ensure!(action.account == SetCode::account() && action.name == SetCode::name(),
+                NotSetCodeSnafu { account: action.account, name: action.name });
+        let setcode = SetCode::from_bin(&mut ByteStream::from(&action.data)).context(ActionDataSnafu)?;
+        Self::new(setcode.code)
+    }
+
+    pub fn code(&self) -> &Bytes { &self.code }
+
+    /// Return the SHA-256 hash of the code, which is how the chain identifies it.
+    pub fn hash(&self) -> &Checksum256 { &self.hash }
+
+    pub fn custom_sections(&self) -> &[CustomSection] { &self.custom_sections }
+
+    /// Return the data of the first custom section with the given name.
+    pub fn custom_section(&self, name: &str) -> Option<&Bytes> {
+        self.custom_sections.iter().find(|s| s.name == name).map(|s| &s.data)
+    }
+
+    /// Return the content of the `producers` section, if present.
+    pub fn producers(&self) -> Result<Option<Producers>> {
+        self.custom_section(PRODUCERS_SECTION_NAME).map(|data| parse_producers(data)).transpose()
+    }
+
+    /// Return the ABI embedded in the `abi` custom section, if present. The ABI can be
+    /// stored either in its JSON or binary representation.
+    pub fn embedded_abi(&self) -> Result<Option<ABIDefinition>> {
+        let Some(data) = self.custom_section(ABI_SECTION_NAME) else { return Ok(None) };
+        let abi = match std::str::from_utf8(data) {
+            Ok(json) if json.trim_start().starts_with('{') => ABIDefinition::from_str(json),
+            _ => ABIDefinition::decode(&mut ByteStream::from(data)),
+        };
+        abi.map(Some).context(InvalidABISnafu { section: ABI_SECTION_NAME })
+    }
+}
+
+fn read_name(stream: &mut ByteStream) -> Result<String> {
+    let len = stream.read_var_u32().context(TruncatedSnafu)?;
+    let name = stream.read_bytes(len as usize).context(TruncatedSnafu)?;
+    String::from_utf8(name.to_vec()).context(InvalidNameSnafu)
+}
+
+fn parse_custom_sections(code: &[u8]) -> Result<Vec<CustomSection>> {
+    let mut stream = ByteStream::from(code);
+    let magic = stream.read_bytes(4).context(TruncatedSnafu)?;
+    ensure!(magic == WASM_MAGIC, InvalidMagicSnafu { magic });
+    let version = stream.read_bytes(4).context(TruncatedSnafu)?;
+    let version = u32::from_le_bytes(version.try_into().unwrap());  // safe unwrap
+    ensure!(version == WASM_VERSION, UnsupportedVersionSnafu { version });
+
+    let mut result = vec![];
+    while !stream.leftover().is_empty() {
+        let id = stream.read_byte().context(TruncatedSnafu)?;
+        let size = stream.read_var_u32().context(TruncatedSnafu)?;
+        let payload = stream.read_bytes(size as usize).context(TruncatedSnafu)?;
+        if id == CUSTOM_SECTION_ID {
+            let mut section = ByteStream::from(payload);
+            let name = read_name(&mut section)?;
+            result.push(CustomSection { name, data: section.leftover().into() });
+        }
+    }
+    Ok(result)
+}
+
+fn parse_producers(data: &[u8]) -> Result<Producers> {
+    let mut stream = ByteStream::from(data);
+    let mut result = Producers::new();
+    let field_count = stream.read_var_u32().context(TruncatedSnafu)?;
+    for _ in 0..field_count {
+        let field = read_name(&mut stream)?;
+        let value_count = stream.read_var_u32().context(TruncatedSnafu)?;
+        let values = (0..value_count)
+            .map(|_| Ok((read_name(&mut stream)?, read_name(&mut stream)?)))
+            .collect::<Result<_>>()?;
+        result.insert(field, values);
+    }
+    Ok(result)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use crate::{json, AccountName};
+    use super::*;
+
+    fn name(s: &str, out: &mut Vec<u8>) {
+        out.push(s.len() as u8);
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    fn custom_section(name_: &str, data: &[u8], out: &mut Vec<u8>) {
+        let mut payload = vec![];
+        name(name_, &mut payload);
+        payload.extend_from_slice(data);
+        out.push(CUSTOM_SECTION_ID);
+        out.push(payload.len() as u8);
+        out.extend(payload);
+    }
+
+    fn wasm_module() -> Vec<u8> {
+        let mut producers = vec![1];
+        name("processed-by", &mut producers);
+        producers.push(1);
+        name("clang", &mut producers);
+        name("18.1.2", &mut producers);
+
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        code.extend_from_slice(&[1, 4, 1, 0x60, 0, 0]);  // type section: one `() -> ()` function type
+        custom_section("producers", &producers, &mut code);
+        custom_section("abi", br#"{"version": "eosio::abi/1.2"}"#, &mut code);
+        code
+    }
+
+    #[test]
+    fn test_parse_wasm() -> Result<()> {
+        let code = wasm_module();
+        let wasm = WasmCode::new(code.clone())?;
+        assert_eq!(wasm.hash(), &Checksum256::from(<[u8; 32]>::from(Sha256::digest(&code))));
+        assert_eq!(wasm.custom_sections().len(), 2);
+        assert_eq!(wasm.producers()?, Some(Producers::from([
+            ("processed-by".to_string(), vec![("clang".to_string(), "18.1.2".to_string())])
+        ])));
+        assert_eq!(wasm.embedded_abi()?.map(|abi| abi.version), Some("eosio::abi/1.2".to_string()));
+        assert_eq!(wasm.custom_section("name"), None);
+
+        assert!(matches!(WasmCode::new(b"\0wasm".to_vec()), Err(WasmError::InvalidMagic { .. })));
+        assert!(matches!(WasmCode::new(b"\0asm\x02\0\0\0".to_vec()), Err(WasmError::UnsupportedVersion { .. })));
+        assert!(matches!(WasmCode::new(code[..code.len() - 1].to_vec()), Err(WasmError::Truncated { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_setcode_action() -> Result<()> {
+        let setcode = SetCode {
+            account: AccountName::new("alice")?,
+            vmtype: 0,
+            vmversion: 0,
+            code: wasm_module().into(),
+        };
+        let action = Action::new(("alice", "active"), &setcode);
+        let wasm = WasmCode::from_action(&action)?;
+        assert_eq!(wasm.code(), &setcode.code);
+
+        let action = Action::from_json(&json!({
+            "account": "eosio.token", "name": "transfer", "authorization": [], "data": ""
+        }))?;
+        assert!(matches!(WasmCode::from_action(&action), Err(WasmError::NotSetCode { .. })));
+
+        Ok(())
+    }
+}
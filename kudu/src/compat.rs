@@ -0,0 +1,40 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Compatibility layer easing the migration from the legacy `antelope` crates.
+//!
+//! This module is only available with the `antelope-compat` feature. It re-exports
+//! everything from the root of the `kudu` crate, and the items that have been renamed
+//! since the `antelope` crates under their old name, so that most code can be ported
+//! by replacing the `antelope` dependency with an alias to this module.
+//!
+//! | `antelope` name        | `kudu` name                |
+//! |------------------------|----------------------------|
+//! | `BinarySerializable`   | [`ABISerializable`] (trait and derive macro) |
+//!
+//! Note that the renamed items are the same as the `kudu` ones: the methods of
+//! [`BinarySerializable`] are those of [`ABISerializable`] (`to_bin()`/`from_bin()`).
+//!
+//! Example:
+//! ```
+//! use kudu::compat as antelope;
+//! use antelope::{AccountName, BinarySerializable};
+//!
+//! #[derive(BinarySerializable, Debug, PartialEq)]
+//! struct Account {
+//!     name: AccountName,
+//!     balance: u64,
+//! }
+//!
+//! let account = Account { name: AccountName::constant("alice"), balance: 42 };
+//! let bin = antelope::to_bin(&account);
+//! assert_eq!(antelope::from_bin::<Account>(&bin)?, account);
+//! # Ok::<(), antelope::SerializeError>(())
+//! ```
+//!
+
+pub use crate::*;
+
+/// Old name of [`ABISerializable`], both for the trait and its derive macro.
+pub use crate::ABISerializable as BinarySerializable;
@@ -1,6 +1,8 @@
 // SPDX-FileCopyrightText: 2024-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::sync::{PoisonError, RwLock};
+
 pub const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/version"));
 
 pub const BLOCK_INTERVAL_MS: u16 = 500;
@@ -46,3 +48,129 @@ pub const JUNGLE_CHAIN_ID: &str = "73e4385a2708e6d7048834fbc1079f2fabb17b3c125b1
 
 // max size for a vector/array that we deserialize from a binary stream
 pub const MAX_ARRAY_SIZE: usize = 1_000_000;
+
+// max number of typedefs followed when resolving a type name
+pub const MAX_TYPEDEF_DEPTH: usize = 32;
+
+
+// -----------------------------------------------------------------------------
+//     Runtime configuration
+// -----------------------------------------------------------------------------
+
+/// Default prefix of public keys in the legacy format, eg: `EOS6MRyAjQq8...`
+pub const DEFAULT_LEGACY_KEY_PREFIX: &str = "EOS";
+
+static LEGACY_KEY_PREFIX: RwLock<Option<String>> = RwLock::new(None);
+
+/// Return the prefix used to parse and format public keys in the legacy format.
+pub fn legacy_key_prefix() -> String {
+    LEGACY_KEY_PREFIX.read().unwrap_or_else(PoisonError::into_inner).clone()
+        .unwrap_or_else(|| DEFAULT_LEGACY_KEY_PREFIX.to_string())
+}
+
+/// Set the prefix used to parse and format public keys in the legacy format, for
+/// chains which historically used another prefix than `EOS` (eg: `FIO`).
+///
+/// This is a global setting. Keys with the default `EOS` prefix are still accepted
+/// when parsing, and keys in the `PUB_K1_` format are not affected.
+///
+/// ```
+/// # use kudu::{config, PublicKey};
+/// config::set_legacy_key_prefix("FIO");
+/// let key = PublicKey::new("FIO6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV")?;
+/// assert_eq!(key.to_old_format(), "FIO6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV");
+/// # Ok::<(), kudu::InvalidCryptoData>(())
+/// ```
+pub fn set_legacy_key_prefix(prefix: &str) {
+    *LEGACY_KEY_PREFIX.write().unwrap_or_else(PoisonError::into_inner) = Some(prefix.to_string());
+}
+
+
+// -----------------------------------------------------------------------------
+//     Serialization configuration
+// -----------------------------------------------------------------------------
+
+/// Case of the letters used when representing binary data as hex strings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HexCase {
+    #[default]
+    Lower,
+    Upper,
+}
+
+/// Representation of floating-point numbers when writing JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FloatMode {
+    /// Never use scientific notation, and do not write a trailing `.0` for
+    /// numbers without a fractional part, eg: `1` for `1.0`
+    #[default]
+    Plain,
+    /// Shortest representation that round-trips, as `serde_json` does: it uses
+    /// scientific notation for big and small numbers and always has a fractional
+    /// part, eg: `1.0`
+    Shortest,
+}
+
+/// Options controlling how values are represented when converted to JSON, in order
+/// to be compatible with different consumers.
+///
+/// A `SerializationConfig` can be given explicitly to functions that support it
+/// (eg: [`json::to_string_with()`](crate::json::to_string_with) or
+/// [`DecodeOptions`](crate::abi::DecodeOptions)), otherwise the process default
+/// is used, see [`set_default_serialization()`].
+///
+/// The default values match the historical behavior of `kudu`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SerializationConfig {
+    /// Case of hex strings representing bytes, checksums and `float128` values
+    pub hex_case: HexCase,
+    /// Represent `int64` and `uint64` values as strings, for consumers which
+    /// cannot represent all 64-bit integers exactly (eg: JavaScript)
+    pub quote_u64: bool,
+    /// Representation of `float32` and `float64` values when writing JSON
+    pub float_mode: FloatMode,
+}
+
+impl SerializationConfig {
+    const DEFAULT: SerializationConfig = SerializationConfig {
+        hex_case: HexCase::Lower,
+        quote_u64: false,
+        float_mode: FloatMode::Plain,
+    };
+
+    /// Return the hex representation of `data` using the configured case.
+    pub fn hex<T: AsRef<[u8]>>(&self, data: T) -> String {
+        match self.hex_case {
+            HexCase::Lower => hex::encode(data),
+            HexCase::Upper => hex::encode_upper(data),
+        }
+    }
+}
+
+static DEFAULT_SERIALIZATION: RwLock<SerializationConfig> = RwLock::new(SerializationConfig::DEFAULT);
+
+/// Return the [`SerializationConfig`] used when none is given explicitly.
+pub fn default_serialization() -> SerializationConfig {
+    *DEFAULT_SERIALIZATION.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Set the [`SerializationConfig`] used when none is given explicitly.
+///
+/// This is a global setting, which can be safely changed from any thread. Functions
+/// read it once when they are called, so a value being serialized while this is
+/// changed uses either the old or the new configuration, never a mix of both.
+///
+/// ```
+/// # use kudu::{ABI, ABIError, json};
+/// # use kudu::config::{self, HexCase, SerializationConfig};
+/// let abi = ABI::from_str(r#"{"version": "eosio::abi/1.1", "types": [{"new_type_name": "b", "type": "bytes"}]}"#)?;
+/// let data = abi.variant_to_binary("b", &json!("abcd"))?;
+///
+/// config::set_default_serialization(SerializationConfig { hex_case: HexCase::Upper, ..Default::default() });
+/// assert_eq!(abi.binary_to_variant("b", data)?, json!("ABCD"));
+/// # config::set_default_serialization(SerializationConfig::default());
+/// # Ok::<(), ABIError>(())
+/// ```
+pub fn set_default_serialization(config: SerializationConfig) {
+    *DEFAULT_SERIALIZATION.write().unwrap_or_else(PoisonError::into_inner) = config;
+}
@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Actions and tables of well-known contracts, which are normally defined through
+//! their ABI but are provided here for convenience. Each contract lives in its own
+//! module, enabled by the corresponding feature flag:
+//!
+//! - [`token`] (feature `contracts-token`): the `eosio.token` contract
+//! - [`system`] (feature `contracts-system`): the `eosio` system contract
+//!
+//! Actions implement the [`Contract`] trait and tables implement the [`TableRow`]
+//! trait, so they can be used directly with [`Action::new()`] and [`APIClient::table()`].
+//!
+//! Example:
+//! ```
+//! # #[cfg(feature = "contracts-token")] {
+//! use kudu::{Action, contracts::token::Transfer};
+//!
+//! let transfer = Transfer {
+//!     from: "alice".parse()?,
+//!     to: "bob".parse()?,
+//!     quantity: "1.0000 EOS".parse()?,
+//!     memo: "thanks for all the fish".to_string(),
+//! };
+//! let action = Action::new(("alice", "active"), &transfer);
+//! assert_eq!(action.account.to_string(), "eosio.token");
+//! # }
+//! # Ok::<(), color_eyre::Report>(())
+//! ```
+//!
+//! [`Contract`]: crate::Contract
+//! [`TableRow`]: crate::TableRow
+//! [`Action::new()`]: crate::Action::new
+//! [`APIClient::table()`]: crate::APIClient::table
+//!
+
+#[cfg(feature = "contracts-system")]
+pub mod system;
+
+#[cfg(feature = "contracts-token")]
+pub mod token;
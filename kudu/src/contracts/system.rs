@@ -0,0 +1,350 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Actions of the system contract (`eosio`), and its tables holding the chain
+//! configuration and global state, as well as the producers and voters.
+//!
+//! All tables implement [`TableRow`] so they can be queried with [`APIClient::table()`](crate::APIClient::table).
+//!
+//! See the definitions in: <https://github.com/VaultaFoundation/system-contracts/blob/main/contracts/eosio.system/include/eosio.system/eosio.system.hpp>
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::{APIClient, contracts::system::{GlobalState, VoterInfo}};
+//! # fn main() -> Result<(), kudu::api::TableError> {
+//! let client = APIClient::vaulta();
+//! let global = client.table::<GlobalState>().first()?.expect("system contract not initialized");
+//! println!("max RAM size: {}", global.max_ram_size);
+//!
+//! let voter = client.table::<VoterInfo>().lower_bound("alice").first()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    contract, ABISerializable, AccountName, Asset, Authority, BlockSigningAuthority, BlockTimestamp,
+    ByteStream, Bytes, Name, PublicKey, SerializeError, TableName, TableRow, TimePoint,
+};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+// -----------------------------------------------------------------------------
+//     Actions
+// -----------------------------------------------------------------------------
+
+/// Register a producer with a single block signing key. This is the legacy
+/// version of [`RegProducer2`].
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio", name="regproducer")]
+pub struct RegProducer {
+    pub producer: AccountName,
+    pub producer_key: PublicKey,
+    pub url: String,
+    pub location: u16,
+}
+
+/// Register a producer with a [`BlockSigningAuthority`], which allows it to sign
+/// blocks using a weighted threshold multi-sig.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio", name="regproducer2")]
+pub struct RegProducer2 {
+    pub producer: AccountName,
+    pub producer_authority: BlockSigningAuthority,
+    pub url: String,
+    pub location: u16,
+}
+
+/// Create a new account, with the given `owner` and `active` authorities.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio", name="newaccount")]
+pub struct NewAccount {
+    pub creator: AccountName,
+    pub name: AccountName,
+    pub owner: Authority,
+    pub active: Authority,
+}
+
+/// Buy RAM for `receiver`, paid by `payer`, given an amount of bytes.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio", name="buyrambytes")]
+pub struct BuyRamBytes {
+    pub payer: AccountName,
+    pub receiver: AccountName,
+    pub bytes: u32,
+}
+
+/// Stake tokens for NET and CPU bandwidth of `receiver`. If `transfer` is true,
+/// the staked tokens belong to the receiver.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio", name="delegatebw")]
+pub struct DelegateBw {
+    pub from: AccountName,
+    pub receiver: AccountName,
+    pub stake_net_quantity: Asset,
+    pub stake_cpu_quantity: Asset,
+    pub transfer: bool,
+}
+
+/// Rent NET and CPU for `receiver` for the given number of days, see
+/// [`PowerupState`](crate::chain::resources::PowerupState) to estimate its cost.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio", name="powerup")]
+pub struct Powerup {
+    pub payer: AccountName,
+    pub receiver: AccountName,
+    pub days: u32,
+    pub net_frac: i64,
+    pub cpu_frac: i64,
+    pub max_payment: Asset,
+}
+
+
+// -----------------------------------------------------------------------------
+//     Tables
+// -----------------------------------------------------------------------------
+
+macro_rules! impl_table_row {
+    ($typ:ty, $table:literal) => {
+        impl TableRow for $typ {
+            fn code() -> AccountName {
+                const { AccountName::constant("eosio") }
+            }
+            fn table() -> TableName {
+                const { TableName::constant($table) }
+            }
+        }
+    };
+}
+
+
+/// Chain configuration as set by the `setparams` action of the system contract.
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct BlockchainParameters {
+    pub max_block_net_usage: u64,
+    pub target_block_net_usage_pct: u32,
+    pub max_transaction_net_usage: u32,
+    pub base_per_transaction_net_usage: u32,
+    pub net_usage_leeway: u32,
+    pub context_free_discount_net_usage_num: u32,
+    pub context_free_discount_net_usage_den: u32,
+    pub max_block_cpu_usage: u32,
+    pub target_block_cpu_usage_pct: u32,
+    pub max_transaction_cpu_usage: u32,
+    pub min_transaction_cpu_usage: u32,
+    pub max_transaction_lifetime: u32,
+    pub deferred_trx_expiration_window: u32,
+    pub max_transaction_delay: u32,
+    pub max_inline_action_size: u32,
+    pub max_inline_action_depth: u16,
+    pub max_authority_depth: u16,
+}
+
+/// Row of the `eosio::global` singleton table.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct GlobalState {
+    #[kudu(base)]
+    #[serde(flatten)]
+    pub parameters: BlockchainParameters,
+    pub max_ram_size: u64,
+    pub total_ram_bytes_reserved: u64,
+    pub total_ram_stake: i64,
+    pub last_producer_schedule_update: BlockTimestamp,
+    pub last_pervote_bucket_fill: TimePoint,
+    pub pervote_bucket: i64,
+    pub perblock_bucket: i64,
+    pub total_unpaid_blocks: u32,
+    pub total_activated_stake: i64,
+    pub thresh_activated_stake_time: TimePoint,
+    pub last_producer_schedule_size: u16,
+    pub total_producer_vote_weight: f64,
+    pub last_name_close: BlockTimestamp,
+}
+
+/// Row of the `eosio::global2` singleton table.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct GlobalState2 {
+    pub new_ram_per_block: u16,
+    pub last_ram_increase: BlockTimestamp,
+    /// deprecated
+    pub last_block_num: BlockTimestamp,
+    pub total_producer_votepay_share: f64,
+    pub revision: u8,
+}
+
+/// Row of the `eosio::global3` singleton table.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct GlobalState3 {
+    pub last_vpay_state_update: TimePoint,
+    pub total_vpay_share_change_rate: f64,
+}
+
+/// Row of the `eosio::global4` singleton table, containing the inflation parameters.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize, ABISerializable)]
+pub struct GlobalState4 {
+    pub continuous_rate: f64,
+    pub inflation_pay_factor: i64,
+    pub votepay_factor: i64,
+}
+
+/// Row of the `eosio::producers` table.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct ProducerInfo {
+    pub owner: AccountName,
+    pub total_votes: f64,
+    pub producer_key: PublicKey,
+    pub is_active: bool,
+    pub url: String,
+    pub unpaid_blocks: u32,
+    pub last_claim_time: TimePoint,
+    pub location: u16,
+    /// binary extension, only present for producers registered with `regproducer2`
+    /// or updated since its introduction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer_authority: Option<BlockSigningAuthority>,
+}
+
+// implemented manually because of the `producer_authority` binary extension
+impl ABISerializable for ProducerInfo {
+    fn to_bin(&self, s: &mut Bytes) {
+        self.owner.to_bin(s);
+        self.total_votes.to_bin(s);
+        self.producer_key.to_bin(s);
+        self.is_active.to_bin(s);
+        self.url.to_bin(s);
+        self.unpaid_blocks.to_bin(s);
+        self.last_claim_time.to_bin(s);
+        self.location.to_bin(s);
+        if let Some(authority) = &self.producer_authority {
+            authority.to_bin(s);
+        }
+    }
+    fn from_bin(s: &mut ByteStream) -> Result<Self, SerializeError> {
+        Ok(Self {
+            owner: AccountName::from_bin(s)?,
+            total_votes: f64::from_bin(s)?,
+            producer_key: PublicKey::from_bin(s)?,
+            is_active: bool::from_bin(s)?,
+            url: String::from_bin(s)?,
+            unpaid_blocks: u32::from_bin(s)?,
+            last_claim_time: TimePoint::from_bin(s)?,
+            location: u16::from_bin(s)?,
+            producer_authority: match s.leftover().is_empty() {
+                true => None,
+                false => Some(BlockSigningAuthority::from_bin(s)?),
+            },
+        })
+    }
+}
+
+/// Row of the `eosio::voters` table.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct VoterInfo {
+    pub owner: AccountName,
+    pub proxy: AccountName,
+    pub producers: Vec<Name>,
+    pub staked: i64,
+    pub last_vote_weight: f64,
+    pub proxied_vote_weight: f64,
+    pub is_proxy: bool,
+    pub flags1: u32,
+    pub reserved2: u32,
+    pub reserved3: Asset,
+}
+
+/// Balance and weight of one side of a Bancor market.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct Connector {
+    pub balance: Asset,
+    pub weight: f64,
+}
+
+/// Row of the `eosio::rammarket` table, the Bancor market used to buy and sell RAM.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, ABISerializable)]
+pub struct ExchangeState {
+    pub supply: Asset,
+    pub base: Connector,
+    pub quote: Connector,
+}
+
+impl_table_row!(GlobalState, "global");
+impl_table_row!(GlobalState2, "global2");
+impl_table_row!(GlobalState3, "global3");
+impl_table_row!(GlobalState4, "global4");
+impl_table_row!(ProducerInfo, "producers");
+impl_table_row!(VoterInfo, "voters");
+impl_table_row!(ExchangeState, "rammarket");
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use color_eyre::eyre::Result;
+
+    use crate::{json, to_bin, APIClient, PrivateKey, api::MockTransport};
+    use super::*;
+
+    fn producer(authority: Option<BlockSigningAuthority>) -> Result<ProducerInfo> {
+        Ok(ProducerInfo {
+            owner: AccountName::new("alice")?,
+            total_votes: 1.5e18,
+            producer_key: PublicKey::from_private_key(&PrivateKey::eosio_dev()),
+            is_active: true,
+            url: "https://alice.example".to_string(),
+            unpaid_blocks: 12,
+            last_claim_time: "2025-01-01T00:00:00.000".parse()?,
+            location: 250,
+            producer_authority: authority,
+        })
+    }
+
+    #[test]
+    fn test_producer_binary_extension() -> Result<()> {
+        let legacy = producer(None)?;
+        let with_authority = producer(Some(BlockSigningAuthority::from_key(legacy.producer_key.clone())))?;
+
+        for p in [legacy, with_authority] {
+            assert_eq!(kudu::from_bin::<ProducerInfo>(to_bin(&p))?, p);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_tables() -> Result<()> {
+        let global4 = GlobalState4 { continuous_rate: 0.04879, inflation_pay_factor: 50000, votepay_factor: 40000 };
+        let producers = [producer(None)?, producer(None)?];
+
+        // responses are returned in the order they are registered
+        let mock = Arc::new(MockTransport::new());
+        mock.respond("/v1/chain/get_table_rows", json!({
+            "rows": producers.iter().map(|p| to_bin(p).to_hex()).collect::<Vec<_>>(),
+            "more": true,
+            "next_key": "bob",
+        }));
+        mock.respond("/v1/chain/get_table_rows", json!({
+            "rows": [to_bin(&global4).to_hex()], "more": false, "next_key": "",
+        }));
+        mock.respond("/v1/chain/get_table_rows", json!({"rows": ["zz"]}));
+        let client = APIClient::with_transport("mock://", mock.clone());
+
+        let rows = client.table::<ProducerInfo>().lower_bound("alice").limit(2).fetch()?;
+        assert_eq!(rows.rows, producers);
+        assert!(rows.more);
+        assert_eq!(rows.next_key, "bob");
+        mock.assert_called_with("/v1/chain/get_table_rows", &json!({
+            "code": "eosio", "scope": "eosio", "table": "producers", "json": false,
+            "limit": 2, "reverse": false, "lower_bound": "alice",
+        }));
+
+        assert_eq!(client.table::<GlobalState4>().first()?, Some(global4));
+        assert!(client.table::<GlobalState4>().first().is_err());
+
+        Ok(())
+    }
+}
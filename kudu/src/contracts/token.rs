@@ -0,0 +1,166 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Actions and tables of the token contract (`eosio.token`).
+//!
+//! See the definitions in: <https://github.com/VaultaFoundation/system-contracts/blob/main/contracts/eosio.token/include/eosio.token/eosio.token.hpp>
+//!
+//! Example:
+//! ```no_run
+//! # use kudu::{APIClient, contracts::token::Account};
+//! # fn main() -> Result<(), kudu::api::TableError> {
+//! let client = APIClient::vaulta();
+//! let balances = client.table::<Account>().scope("alice").fetch()?;
+//! for account in balances.rows {
+//!     println!("{}", account.balance);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A [`TokenRegistry`] of well-known tokens can be used to make sure that an asset
+//! was issued by the expected contract and not by an impostor.
+
+mod registry;
+
+pub use registry::{TokenInfo, TokenRegistry, TokenRegistryError};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{contract, ABISerializable, AccountName, Asset, Symbol, TableName, TableRow};
+
+// this is needed to be able to call the `ABISerializable` derive macro, which needs
+// access to the `kudu` crate
+extern crate self as kudu;
+
+
+// -----------------------------------------------------------------------------
+//     Actions
+// -----------------------------------------------------------------------------
+
+/// Create a new token with the given max supply, which can then be issued by `issuer`.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio.token", name="create")]
+pub struct Create {
+    pub issuer: AccountName,
+    pub maximum_supply: Asset,
+}
+
+/// Issue new tokens to the issuer of the token.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio.token", name="issue")]
+pub struct Issue {
+    pub to: AccountName,
+    pub quantity: Asset,
+    pub memo: String,
+}
+
+/// Remove tokens from the balance of the issuer and from the supply.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio.token", name="retire")]
+pub struct Retire {
+    pub quantity: Asset,
+    pub memo: String,
+}
+
+/// Transfer tokens from one account to another.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio.token", name="transfer")]
+pub struct Transfer {
+    pub from: AccountName,
+    pub to: AccountName,
+    pub quantity: Asset,
+    pub memo: String,
+}
+
+/// Create a zero balance for `owner`, with the RAM paid by `ram_payer`.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio.token", name="open")]
+pub struct Open {
+    pub owner: AccountName,
+    pub symbol: Symbol,
+    pub ram_payer: AccountName,
+}
+
+/// Delete the balance of `owner`, which needs to be zero.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+#[contract(account="eosio.token", name="close")]
+pub struct Close {
+    pub owner: AccountName,
+    pub symbol: Symbol,
+}
+
+
+// -----------------------------------------------------------------------------
+//     Tables
+// -----------------------------------------------------------------------------
+
+/// Row of the `eosio.token::accounts` table, scoped by account name.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+pub struct Account {
+    pub balance: Asset,
+}
+
+/// Row of the `eosio.token::stat` table, scoped by symbol code.
+#[derive(Clone, Debug, PartialEq, Eq, ABISerializable, Serialize, Deserialize)]
+pub struct CurrencyStats {
+    pub supply: Asset,
+    pub max_supply: Asset,
+    pub issuer: AccountName,
+}
+
+impl TableRow for Account {
+    fn code() -> AccountName {
+        const { AccountName::constant("eosio.token") }
+    }
+    fn table() -> TableName {
+        const { TableName::constant("accounts") }
+    }
+}
+
+impl TableRow for CurrencyStats {
+    fn code() -> AccountName {
+        const { AccountName::constant("eosio.token") }
+    }
+    fn table() -> TableName {
+        const { TableName::constant("stat") }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use crate::{json, to_bin, ABI, Contract, abi::data::EOSIO_TOKEN_ABI};
+    use super::*;
+
+    #[track_caller]
+    fn check_against_abi<T: Contract + Serialize>(abi: &ABI, action: &T) -> Result<()> {
+        let name = T::name().to_string();
+        assert_eq!(abi.variant_to_binary(name.as_str(), &json!(action))?, to_bin(action));
+        Ok(())
+    }
+
+    #[test]
+    fn test_actions_match_abi() -> Result<()> {
+        let abi = ABI::from_str(EOSIO_TOKEN_ABI)?;
+        let alice = AccountName::new("alice")?;
+        let bob = AccountName::new("bob")?;
+        let quantity: Asset = "1.0000 EOS".parse()?;
+        let symbol: Symbol = "4,EOS".parse()?;
+        let memo = "memo".to_string();
+
+        check_against_abi(&abi, &Create { issuer: alice, maximum_supply: quantity })?;
+        check_against_abi(&abi, &Issue { to: alice, quantity, memo: memo.clone() })?;
+        check_against_abi(&abi, &Retire { quantity, memo: memo.clone() })?;
+        check_against_abi(&abi, &Transfer { from: alice, to: bob, quantity, memo })?;
+        check_against_abi(&abi, &Open { owner: bob, symbol, ram_payer: alice })?;
+        check_against_abi(&abi, &Close { owner: bob, symbol })?;
+
+        let stats = CurrencyStats { supply: quantity, max_supply: quantity, issuer: alice };
+        assert_eq!(abi.variant_to_binary("currency_stats", &json!(stats))?, to_bin(&stats));
+        Ok(())
+    }
+}
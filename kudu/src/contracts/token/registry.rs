@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+use crate::{AccountName, Asset, ExtendedAsset, InvalidSymbol, Symbol, SymbolCode, with_location};
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum TokenRegistryError {
+    #[snafu(display("I/O error while reading token registry: {path:?}"))]
+    Io { path: PathBuf, source: io::Error },
+
+    #[snafu(display("invalid JSON for token registry"))]
+    FromJson { source: serde_json::Error },
+
+    #[snafu(display("token {code}@{contract} is already registered"))]
+    DuplicateToken { contract: AccountName, code: SymbolCode },
+
+    #[snafu(display("token {code}@{contract} has invalid decimals: {decimals}"))]
+    InvalidDecimals { contract: AccountName, code: SymbolCode, decimals: u8 },
+
+    #[snafu(display("unknown token {code}@{contract}"))]
+    UnknownToken { contract: AccountName, code: SymbolCode },
+
+    #[snafu(display("token {code}@{contract} has {expected} decimals, got {actual}"))]
+    DecimalsMismatch { contract: AccountName, code: SymbolCode, expected: u8, actual: u8 },
+}
+
+
+/// Metadata of a token, as registered in a [`TokenRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenInfo {
+    pub contract: AccountName,
+    pub symbol: SymbolCode,
+    pub decimals: u8,
+    /// Name of the token meant for display, eg: `"Vaulta"`
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo: Option<String>,
+}
+
+impl TokenInfo {
+    /// Return the full symbol (decimals and code) of this token.
+    pub fn full_symbol(&self) -> Result<Symbol, InvalidSymbol> {
+        Symbol::from_code(self.decimals, self.symbol)
+    }
+}
+
+
+/// A registry of well-known tokens, indexed by contract and symbol code.
+///
+/// As anyone can deploy a token contract issuing a token with an existing symbol
+/// code, the symbol of an asset alone is not enough to identify a token: wallets
+/// and bots should always check the contract it comes from against a registry.
+///
+/// The registry can be loaded from a JSON file containing a list of [`TokenInfo`].
+///
+/// ## Example
+/// ```
+/// # use kudu::{Asset, contracts::token::TokenRegistry};
+/// let registry = TokenRegistry::from_json(r#"[
+///     {"contract": "core.vaulta", "symbol": "A", "decimals": 4, "name": "Vaulta"},
+///     {"contract": "eosio.token", "symbol": "EOS", "decimals": 4, "name": "EOS",
+///      "logo": "https://example.com/eos.png"}
+/// ]"#)?;
+///
+/// let quantity: Asset = "1.0000 A".parse()?;
+/// assert_eq!(registry.validate("core.vaulta".parse()?, &quantity)?.name, "Vaulta");
+/// assert!(registry.validate("fake.vaulta".parse()?, &quantity).is_err());
+/// # Ok::<(), color_eyre::Report>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TokenRegistry {
+    tokens: BTreeMap<(AccountName, SymbolCode), TokenInfo>,
+}
+
+impl TokenRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, TokenRegistryError> {
+        let tokens: Vec<TokenInfo> = serde_json::from_str(s).context(FromJsonSnafu)?;
+        let mut registry = Self::new();
+        for token in tokens {
+            registry.add(token)?;
+        }
+        Ok(registry)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TokenRegistryError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).context(IoSnafu { path })?;
+        Self::from_json(&contents)
+    }
+
+    /// Add a token to the registry, it is an error to register the same token twice.
+    pub fn add(&mut self, token: TokenInfo) -> Result<(), TokenRegistryError> {
+        let (contract, code) = (token.contract, token.symbol);
+        ensure!(token.full_symbol().is_ok(),
+                InvalidDecimalsSnafu { contract, code, decimals: token.decimals });
+        ensure!(!self.tokens.contains_key(&(contract, code)), DuplicateTokenSnafu { contract, code });
+        self.tokens.insert((contract, code), token);
+        Ok(())
+    }
+
+    pub fn get(&self, contract: AccountName, code: SymbolCode) -> Option<&TokenInfo> {
+        self.tokens.get(&(contract, code))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Return an iterator over all the registered tokens, ordered by contract
+    /// and symbol code.
+    pub fn iter(&self) -> impl Iterator<Item = &TokenInfo> {
+        self.tokens.values()
+    }
+
+    /// Return all the registered tokens with the given symbol code, issued by
+    /// different contracts.
+    pub fn with_code(&self, code: SymbolCode) -> impl Iterator<Item = &TokenInfo> {
+        self.iter().filter(move |token| token.symbol == code)
+    }
+
+    /// Check that `quantity` is an amount of a registered token issued by `contract`
+    /// and that it has the expected number of decimals, and return its metadata.
+    pub fn validate(&self, contract: AccountName, quantity: &Asset) -> Result<&TokenInfo, TokenRegistryError> {
+        let code = quantity.symbol().code();
+        let token = self.get(contract, code).context(UnknownTokenSnafu { contract, code })?;
+        ensure!(token.decimals == quantity.decimals(), DecimalsMismatchSnafu {
+            contract, code, expected: token.decimals, actual: quantity.decimals()
+        });
+        Ok(token)
+    }
+
+    /// Same as [`TokenRegistry::validate()`] for an asset carrying its contract.
+    pub fn validate_extended(&self, asset: &ExtendedAsset) -> Result<&TokenInfo, TokenRegistryError> {
+        self.validate(asset.contract, &asset.quantity)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use super::*;
+
+    const TOKENS: &str = r#"[
+        {"contract": "core.vaulta", "symbol": "A", "decimals": 4, "name": "Vaulta"},
+        {"contract": "eosio.token", "symbol": "EOS", "decimals": 4, "name": "EOS",
+         "logo": "https://example.com/eos.png"},
+        {"contract": "tethertether", "symbol": "USDT", "decimals": 4, "name": "Tether USD"}
+    ]"#;
+
+    #[test]
+    fn test_registry() -> Result<()> {
+        let registry = TokenRegistry::from_json(TOKENS)?;
+        assert_eq!(registry.len(), 3);
+
+        let eos = registry.get("eosio.token".parse()?, "EOS".parse()?).unwrap();
+        assert_eq!(eos.full_symbol()?, "4,EOS".parse()?);
+        assert_eq!(eos.logo.as_deref(), Some("https://example.com/eos.png"));
+        assert_eq!(registry.with_code("USDT".parse()?).count(), 1);
+
+        // same symbol from another contract is not the same token
+        let usdt: Asset = "1.0000 USDT".parse()?;
+        assert_eq!(registry.validate("tethertether".parse()?, &usdt)?.name, "Tether USD");
+        assert!(matches!(registry.validate("fakefaketeth".parse()?, &usdt),
+                         Err(TokenRegistryError::UnknownToken { .. })));
+
+        let wrong_decimals: Asset = "1.00 USDT".parse()?;
+        assert!(matches!(registry.validate("tethertether".parse()?, &wrong_decimals),
+                         Err(TokenRegistryError::DecimalsMismatch { expected: 4, actual: 2, .. })));
+
+        let asset = ExtendedAsset { quantity: "2.5000 A".parse()?, contract: "core.vaulta".parse()? };
+        assert_eq!(registry.validate_extended(&asset)?.name, "Vaulta");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_registry() {
+        let duplicate = r#"[
+            {"contract": "eosio.token", "symbol": "EOS", "decimals": 4, "name": "EOS"},
+            {"contract": "eosio.token", "symbol": "EOS", "decimals": 4, "name": "Fake EOS"}
+        ]"#;
+        assert!(matches!(TokenRegistry::from_json(duplicate),
+                         Err(TokenRegistryError::DuplicateToken { .. })));
+
+        let bad_decimals = r#"[{"contract": "eosio.token", "symbol": "EOS", "decimals": 19, "name": "EOS"}]"#;
+        assert!(matches!(TokenRegistry::from_json(bad_decimals),
+                         Err(TokenRegistryError::InvalidDecimals { decimals: 19, .. })));
+
+        assert!(matches!(TokenRegistry::from_json(r#"{"not": "a list"}"#),
+                         Err(TokenRegistryError::FromJson { .. })));
+        assert!(matches!(TokenRegistry::load("/nonexistent/tokens.json"),
+                         Err(TokenRegistryError::Io { .. })));
+    }
+}
@@ -1,17 +1,52 @@
 // SPDX-FileCopyrightText: 2024-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+//! Conversion utilities between the different representations of Antelope data
+//!
+//! This module groups the low-level conversion functions used throughout the library
+//! so that they can also be used directly:
+//!  - hex <-> bytes: [`hex_to_bytes`], [`hex_to_array`] and [`bytes_to_hex`]
+//!  - variant (JSON value) -> native: [`variant_to_int`], [`variant_to_uint`],
+//!    [`variant_to_float`], [`variant_to_str`] and [`variant_to_object`]
+//!  - string -> native: [`str_to_int`] and [`str_to_float`]
+//!  - name/symbol <-> numeric (`u64`) values: [`name_to_u64`], [`u64_to_name`],
+//!    [`symbol_to_u64`], [`u64_to_symbol`], [`symbol_code_to_u64`] and [`u64_to_symbol_code`]
+//!
+//! All of these functions return a [`ConversionError`] on failure. [`FromHexError`] is
+//! also re-exported here for the types which expose it directly (eg: [`Checksum256::from_hex`]),
+//! so that you do not need to depend on the `hex` crate yourself.
+//!
+//! [`Checksum256::from_hex`]: crate::Checksum256::from_hex
+//!
+//! ## Example
+//! ```
+//! # use kudu::{json, convert::*};
+//! assert_eq!(hex_to_bytes("c0ffee")?, vec![0xc0, 0xff, 0xee]);
+//! assert_eq!(bytes_to_hex([0xc0, 0xff, 0xee]), "c0ffee");
+//!
+//! assert_eq!(variant_to_int::<i16>(&json!("0xfffe"))?, -2);
+//! assert_eq!(variant_to_uint::<u32>(&json!("42"))?, 42);
+//!
+//! assert_eq!(name_to_u64("eosio")?, 0x5530ea0000000000);
+//! assert_eq!(u64_to_name(0x5530ea0000000000), "eosio");
+//! assert_eq!(u64_to_symbol(symbol_to_u64("4,SYS")?)?, "4,SYS");
+//! # Ok::<(), ConversionError>(())
+//! ```
+
 use std::any::type_name;
 use std::str::FromStr;
 use std::num::{ParseFloatError, ParseIntError, TryFromIntError};
 
-use hex::FromHexError;
 use num::{Integer, Signed, Unsigned, Float};
 use serde_json::{Map, Value as JsonValue};
 use snafu::prelude::*;
 
 use kudu_macros::with_location;
 
+pub use hex::FromHexError;
+
+use crate::{InvalidName, InvalidSymbol, Name, Symbol, SymbolCode};
+
 
 // -----------------------------------------------------------------------------
 //     Error type for all possible conversion errors
@@ -40,6 +75,12 @@ pub enum ConversionError {
         source: FromHexError,
     },
 
+    #[snafu(display("invalid hex length: expected {expected} bytes, got {actual}"))]
+    HexLength {
+        expected: usize,
+        actual: usize,
+    },
+
     #[snafu(display("integer out of range: cannot fit {value} in a `{target_type}`"))]
     IntPrecision {
         value: i128,  // i128 allows to represent both i64 and u64
@@ -58,11 +99,38 @@ pub enum ConversionError {
         value: f64,
     },
 
+    #[snafu(display("integers wider than 64 bits must be quoted or enable the `arbitrary-precision` \
+                     feature, got unquoted number that was parsed as a float: {value}"))]
+    UnquotedBigInt {
+        value: f64,
+    },
+
     #[snafu(display(r#"cannot convert given variant {value} to type "{typename}""#))]
     IncompatibleVariantTypes {
         typename: &'static str,
         value: Box<JsonValue>
     },
+
+    #[snafu(display("invalid name: {repr}"))]
+    Name {
+        repr: String,
+        #[snafu(source(from(InvalidName, Box::new)))]
+        source: Box<InvalidName>,
+    },
+
+    #[snafu(display("invalid symbol: {repr}"))]
+    Symbol {
+        repr: String,
+        #[snafu(source(from(InvalidSymbol, Box::new)))]
+        source: Box<InvalidSymbol>,
+    },
+
+    #[snafu(display("invalid symbol value: {value:#x}"))]
+    SymbolValue {
+        value: u64,
+        #[snafu(source(from(InvalidSymbol, Box::new)))]
+        source: Box<InvalidSymbol>,
+    },
 }
 
 type Result<T, E = ConversionError> = std::result::Result<T, E>;
@@ -72,6 +140,25 @@ type Result<T, E = ConversionError> = std::result::Result<T, E>;
 //     Hex conversion functions
 // -----------------------------------------------------------------------------
 
+/// Decode a hex string into its bytes.
+pub fn hex_to_bytes<T: AsRef<[u8]>>(repr: T) -> Result<Vec<u8>> {
+    let repr = repr.as_ref();
+    hex::decode(repr).context(HexDecodeSnafu { repr: String::from_utf8_lossy(repr) })
+}
+
+/// Decode a hex string into a fixed-size array of bytes, failing if the decoded
+/// length doesn't match exactly.
+pub fn hex_to_array<const N: usize, T: AsRef<[u8]>>(repr: T) -> Result<[u8; N]> {
+    let bytes = hex_to_bytes(repr)?;
+    let actual = bytes.len();
+    bytes.try_into().map_err(|_| HexLengthSnafu { expected: N, actual }.build())
+}
+
+/// Encode bytes as a lowercase hex string.
+pub fn bytes_to_hex<T: AsRef<[u8]>>(data: T) -> String {
+    hex::encode(data)
+}
+
 /// Trait for signed integers that allows parsing negative integers
 /// from their hex representation
 pub trait NegativeHex : Integer + Signed {
@@ -134,6 +221,7 @@ where
     T: TryFromInt64 + FromStr<Err = ParseIntError> + NegativeHex,
 {
     if let Some(n) = v.as_i64()      { T::try_from_i64(n) }
+    else if let Some(n) = v.as_number().filter(|n| !n.is_f64()) { number_to_int(n) }
     else if let Some(s) = v.as_str() {
         if let Some(hex_repr) = s.strip_prefix("0x") {
             T::from_hex_str(hex_repr)
@@ -143,6 +231,7 @@ where
         }
     }
     else {
+        check_unquoted_big_int(v)?;
         IncompatibleVariantTypesSnafu { typename: type_name::<T>(), value: v.clone() }.fail()
     }
 }
@@ -152,6 +241,7 @@ where
     T: TryFromUint64 + FromStr<Err = ParseIntError>,
 {
     if let Some(n) = v.as_u64()      { T::try_from_u64(n) }
+    else if let Some(n) = v.as_number().filter(|n| !n.is_i64() && !n.is_f64()) { number_to_int(n) }
     else if let Some(s) = v.as_str() {
         if let Some(hex_repr) = s.strip_prefix("0x") {
             T::from_str_radix(hex_repr, 16).map_err(|_| HexIntSnafu { repr: s, target: type_name::<T>() }.build())
@@ -161,10 +251,32 @@ where
         }
     }
     else {
+        check_unquoted_big_int(v)?;
         IncompatibleVariantTypesSnafu { typename: type_name::<T>(), value: v.clone() }.fail()
     }
 }
 
+/// Without the `arbitrary_precision` feature of `serde_json`, unquoted integers that
+/// do not fit in 64 bits are parsed as floats and lose precision, return a specific
+/// error for those instead of a generic type mismatch.
+fn check_unquoted_big_int(v: &JsonValue) -> Result<()> {
+    match v.as_f64() {
+        Some(x) if x.fract() == 0.0 && x.abs() >= 2f64.powi(63) => UnquotedBigIntSnafu { value: x }.fail(),
+        _ => Ok(()),
+    }
+}
+
+/// Parse an integer JSON number that doesn't fit in the 64-bit type used to read it
+/// from its representation, so that quoted and unquoted big integers are handled the
+/// same way.
+fn number_to_int<T>(n: &serde_json::Number) -> Result<T>
+where
+    T: Integer + FromStr<Err = ParseIntError>,
+{
+    let repr = n.to_string();
+    repr.parse().context(IntSnafu { repr: &repr, target: type_name::<T>() })
+}
+
 pub fn variant_to_float<T>(v: &JsonValue) -> Result<T>
 where
     T: TryFromFloat64 + FromStr<Err = ParseFloatError>,
@@ -177,6 +289,45 @@ where
 }
 
 
+// -----------------------------------------------------------------------------
+//     Name and symbol numeric conversions
+// -----------------------------------------------------------------------------
+
+/// Return the `u64` representation of a name, eg: `"eosio"` -> `0x5530ea0000000000`.
+pub fn name_to_u64(repr: &str) -> Result<u64> {
+    Name::new(repr).map(|n| n.as_u64()).context(NameSnafu { repr })
+}
+
+/// Return the string representation of a name given as a `u64`.
+///
+/// All `u64` values map to a valid name, so this cannot fail.
+pub fn u64_to_name(value: u64) -> String {
+    Name::from_u64(value).to_string()
+}
+
+/// Return the `u64` representation of a symbol, eg: `"4,SYS"` -> `0x53595304`.
+pub fn symbol_to_u64(repr: &str) -> Result<u64> {
+    Symbol::new(repr).map(|s| s.as_u64()).context(SymbolSnafu { repr })
+}
+
+/// Return the string representation of a symbol given as a `u64`, eg: `"4,SYS"`.
+pub fn u64_to_symbol(value: u64) -> Result<String> {
+    Symbol::from_u64(value).map(|s| s.to_string()).context(SymbolValueSnafu { value })
+}
+
+/// Return the `u64` representation of a symbol code, eg: `"SYS"` -> `0x535953`.
+pub fn symbol_code_to_u64(repr: &str) -> Result<u64> {
+    SymbolCode::new(repr).map(|s| s.as_u64()).context(SymbolSnafu { repr })
+}
+
+/// Return the string representation of a symbol code given as a `u64`, eg: `"SYS"`.
+pub fn u64_to_symbol_code(value: u64) -> Result<String> {
+    let repr = SymbolCode::from_u64(value).to_string();
+    SymbolCode::new(&repr).context(SymbolValueSnafu { value })?;
+    Ok(repr)
+}
+
+
 // -----------------------------------------------------------------------------
 //     Trait definitions to convert an i64/u64 to any int and f64 to f32
 //
@@ -265,3 +416,134 @@ impl TryFromFloat64 for f32 {
         }
     }
 }
+
+
+// -----------------------------------------------------------------------------
+//     Serde helper for 128-bit integers
+// -----------------------------------------------------------------------------
+
+/// Serialize and deserialize 128-bit integers (`i128` / `u128`) as decimal strings for
+/// human-readable formats and natively for the other ones (eg: MessagePack, CBOR).
+///
+/// This matches the JSON representation of `nodeos`, and avoids the loss of precision
+/// when numbers do not fit in a `u64` (as most JSON parsers don't support them natively).
+/// Numbers are still accepted as input for human-readable formats.
+///
+/// Use it as `#[serde(with = "kudu::convert::serde_int128")]`.
+pub mod serde_int128 {
+    use std::fmt::{self, Display};
+    use std::marker::PhantomData;
+    use std::str::FromStr;
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub trait Int128: Copy + Display + FromStr + Serialize + for<'de> Deserialize<'de>
+        + TryFrom<i64> + TryFrom<u64> + TryFrom<i128> + TryFrom<u128> {}
+
+    impl Int128 for i128 {}
+    impl Int128 for u128 {}
+
+    pub fn serialize<T: Int128, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => serializer.collect_str(value),
+            false => value.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, T: Int128, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_any(Int128Visitor(PhantomData)),
+            false => T::deserialize(deserializer),
+        }
+    }
+
+    const SERDE_JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+    struct Int128Visitor<T>(PhantomData<T>);
+
+    impl<T: Int128> Int128Visitor<T> {
+        fn convert<E: de::Error, V: TryInto<T> + Display + Copy>(v: V) -> Result<T, E> {
+            v.try_into().map_err(|_| E::custom(format!("integer out of range: {v}")))
+        }
+    }
+
+    impl<'de, T: Int128> de::Visitor<'de> for Int128Visitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a 128-bit integer or its decimal representation as a string")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+            v.parse().map_err(|_| E::custom(format!("invalid 128-bit integer: {v:?}")))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> { Self::convert(v) }
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> { Self::convert(v) }
+        fn visit_i128<E: de::Error>(self, v: i128) -> Result<T, E> { Self::convert(v) }
+        fn visit_u128<E: de::Error>(self, v: u128) -> Result<T, E> { Self::convert(v) }
+
+        // with the `arbitrary_precision` feature of `serde_json`, numbers that do not fit
+        // in a `u64`/`i64` are given as a map with a single entry containing their representation
+        fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+            match map.next_entry::<String, String>()? {
+                Some((key, repr)) if key == SERDE_JSON_NUMBER_TOKEN => self.visit_str(&repr),
+                _ => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn hex_conversions() {
+        assert_eq!(hex_to_bytes("").unwrap(), Vec::<u8>::new());
+        assert_eq!(hex_to_bytes("00FFab").unwrap(), vec![0x00, 0xff, 0xab]);
+        assert_eq!(bytes_to_hex(hex_to_bytes("00FFab").unwrap()), "00ffab");
+        assert!(matches!(hex_to_bytes("abc"), Err(ConversionError::HexDecode { .. })));
+        assert!(matches!(hex_to_bytes("zz"), Err(ConversionError::HexDecode { .. })));
+
+        assert_eq!(hex_to_array::<2, _>("beef").unwrap(), [0xbe, 0xef]);
+        assert!(matches!(hex_to_array::<4, _>("beef"),
+                         Err(ConversionError::HexLength { expected: 4, actual: 2, .. })));
+    }
+
+    #[test]
+    fn big_int_conversions() {
+        assert_eq!(variant_to_uint::<u128>(&json!("340282366920938463463374607431768211455")).unwrap(), u128::MAX);
+        assert_eq!(variant_to_int::<i128>(&json!(-5)).unwrap(), -5);
+        assert!(matches!(variant_to_int::<i128>(&json!(1.5)),
+                         Err(ConversionError::IncompatibleVariantTypes { .. })));
+
+        // what an unquoted `uint128` gives without the `arbitrary_precision` feature of `serde_json`
+        let unquoted = json!(3.402823669209385e38);
+        assert!(matches!(variant_to_uint::<u128>(&unquoted), Err(ConversionError::UnquotedBigInt { .. })));
+        assert!(matches!(variant_to_int::<i128>(&json!(-1.7014118346046923e38)),
+                         Err(ConversionError::UnquotedBigInt { .. })));
+    }
+
+    #[test]
+    fn name_symbol_conversions() {
+        for name in ["", "eosio", "eosio.token", "a.b.c.d.e", "zzzzzzzzzzzzj"] {
+            assert_eq!(u64_to_name(name_to_u64(name).unwrap()), name);
+        }
+        assert!(matches!(name_to_u64("EOSIO"), Err(ConversionError::Name { .. })));
+
+        assert_eq!(symbol_to_u64("4,SYS").unwrap(), 0x53595304);
+        assert_eq!(u64_to_symbol(0x53595304).unwrap(), "4,SYS");
+        assert!(matches!(symbol_to_u64("SYS"), Err(ConversionError::Symbol { .. })));
+        assert!(matches!(u64_to_symbol(0x73797304), Err(ConversionError::SymbolValue { .. })));
+
+        assert_eq!(symbol_code_to_u64("SYS").unwrap(), 0x535953);
+        assert_eq!(u64_to_symbol_code(0x535953).unwrap(), "SYS");
+        for value in [0, 0x53005953, 0x5300, 0x737973] {
+            assert!(matches!(u64_to_symbol_code(value), Err(ConversionError::SymbolValue { .. })));
+        }
+    }
+}
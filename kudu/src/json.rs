@@ -4,14 +4,31 @@
 //! This module provides the same functionality as `serde_json` but uses a
 //! specific formatter for `to_string` that tries to match the way the
 //! the C++ Antelope code outputs JSON.
+//!
+//! ## Duplicate keys
+//!
+//! When parsing JSON text, `serde_json` (and [`from_str()`]) accepts objects
+//! containing the same key several times, in which case the last value wins:
+//! `{"to": "alice", "to": "bob"}` is parsed as `{"to": "bob"}`. As this can
+//! silently encode data that is different from what the user thinks they are
+//! signing, input that ends up being encoded and signed should be parsed with
+//! [`parse_strict()`] or [`from_str_strict()`] instead, which reject it.
 
+use std::collections::HashSet;
+use std::fmt;
 use std::io;
 
-use serde::Serialize;
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, DeserializeOwned, MapAccess, SeqAccess, Visitor},
+};
 
 use serde_json::Result;
 use serde_json::ser::{Formatter, CompactFormatter, Serializer};
 
+use crate::JsonValue;
+use crate::config::{self, FloatMode, SerializationConfig};
+
 /// JSON formatter with the following difference to `serde_json::Formatter`:
 ///  - `u128` and `i128` are implemented and are represented as strings (ie: double-quoted)
 ///  - although Antelope also quotes `u64` and `i64` types, we do not follow the same rule
@@ -19,14 +36,18 @@ use serde_json::ser::{Formatter, CompactFormatter, Serializer};
 ///    are internally represented as `i64` (even though they might be used to represent
 ///    smaller sized types such as `i8`, `i16`, etc.)
 ///  - `f32` and `f64` never use scientific notation, and floats that have a fractional
-///    part do not have a trailing ".0" (contrary to Antelope types)
+///    part do not have a trailing ".0" (contrary to Antelope types), unless the
+///    [`FloatMode::Shortest`] mode is used
+///  - integers that do not fit in a `u64`/`i64` in a `JsonValue` are written with all
+///    their digits (with the `arbitrary-precision` feature)
 pub struct VaultaFormatter {
     base: CompactFormatter,
+    float_mode: FloatMode,
 }
 
 impl VaultaFormatter {
-    fn new() -> Self {
-        VaultaFormatter { base: CompactFormatter {} }
+    fn new(config: &SerializationConfig) -> Self {
+        VaultaFormatter { base: CompactFormatter {}, float_mode: config.float_mode }
     }
 }
 
@@ -76,9 +97,12 @@ impl Formatter for VaultaFormatter {
     where
         W: ?Sized + io::Write,
     {
-        // use this instead of the default impl that uses Ryu in order to ensure
-        // that we never use scientific notation
-        write!(writer, "{}", value)
+        match self.float_mode {
+            // use this instead of the default impl that uses Ryu in order to ensure
+            // that we never use scientific notation
+            FloatMode::Plain => write!(writer, "{}", value),
+            FloatMode::Shortest => self.base.write_f32(writer, value),
+        }
     }
 
     #[inline]
@@ -86,20 +110,57 @@ impl Formatter for VaultaFormatter {
     where
         W: ?Sized + io::Write,
     {
-        // use this instead of the default impl that uses Ryu in order to ensure
-        // that we never use scientific notation
-        write!(writer, "{}", value)
+        match self.float_mode {
+            // use this instead of the default impl that uses Ryu in order to ensure
+            // that we never use scientific notation
+            FloatMode::Plain => write!(writer, "{}", value),
+            FloatMode::Shortest => self.base.write_f64(writer, value),
+        }
     }
 
+    #[inline]
+    fn write_number_str<W>(&mut self, writer: &mut W, value: &str) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        // with the `arbitrary_precision` feature of `serde_json`, numbers in a `JsonValue`
+        // are stored as strings and written directly instead of calling `write_f64`
+        // so make sure we format floats the same way here
+        match value.contains(['.', 'e', 'E']) {
+            true => match value.parse::<f64>() {
+                Ok(x) => self.write_f64(writer, x),
+                Err(_) => writer.write_all(value.as_bytes()),
+            },
+            false => writer.write_all(value.as_bytes()),
+        }
+    }
 }
 
 
-/// Serialize the given data structure as a String of JSON.
+/// Serialize the given data structure as a String of JSON, using the default
+/// [`SerializationConfig`], see [`config::set_default_serialization()`].
 pub fn to_string<T>(value: &T) -> Result<String>
 where
     T: ?Sized + Serialize,
 {
-    let fmt = VaultaFormatter::new();
+    to_string_with(value, &config::default_serialization())
+}
+
+/// Serialize the given data structure as a String of JSON, using the given
+/// [`SerializationConfig`].
+///
+/// ```
+/// # use kudu::{json, config::{FloatMode, SerializationConfig}};
+/// let config = SerializationConfig { float_mode: FloatMode::Shortest, ..Default::default() };
+/// assert_eq!(json::to_string(&1.0)?, "1");
+/// assert_eq!(json::to_string_with(&1.0, &config)?, "1.0");
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+pub fn to_string_with<T>(value: &T, config: &SerializationConfig) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    let fmt = VaultaFormatter::new(config);
     let mut vec = Vec::with_capacity(128);
     let mut ser = Serializer::with_formatter(&mut vec, fmt);
     value.serialize(&mut ser)?;
@@ -110,4 +171,145 @@ where
     Ok(string)
 }
 
-pub use serde_json::from_str;
+/// Deserialize an instance of type `T` from a string of JSON text.
+///
+/// Contrary to `serde_json::from_str`, this goes through a [`JsonValue`] first,
+/// which allows `#[serde(flatten)]` fields to contain numbers (this is otherwise
+/// not supported with the `arbitrary_precision` feature of `serde_json`, enabled
+/// by the `arbitrary-precision` feature of `kudu`).
+///
+/// [`JsonValue`]: crate::JsonValue
+pub fn from_str<T>(s: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_value(serde_json::from_str(s)?)
+}
+
+/// Parse a string of JSON text into a [`JsonValue`], returning an error if it
+/// contains an object with duplicate keys (at any nesting level).
+///
+/// ```
+/// # use kudu::json;
+/// assert!(json::parse_strict(r#"{"to": "alice", "quantity": "1.0000 EOS"}"#).is_ok());
+/// assert!(json::parse_strict(r#"{"to": "alice", "to": "bob"}"#).is_err());
+/// ```
+pub fn parse_strict(s: &str) -> Result<JsonValue> {
+    serde_json::from_str::<NoDuplicateKeys>(s)?;
+    serde_json::from_str(s)
+}
+
+/// Deserialize an instance of type `T` from a string of JSON text like
+/// [`from_str()`], but return an error if it contains an object with duplicate keys.
+pub fn from_str_strict<T>(s: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_value(parse_strict(s)?)
+}
+
+
+/// Value that can be deserialized from any JSON value, as long as none of the
+/// objects it contains have duplicate keys. Nothing is kept from the value itself.
+struct NoDuplicateKeys;
+
+impl<'de> Deserialize<'de> for NoDuplicateKeys {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NoDuplicateKeysVisitor)
+    }
+}
+
+struct NoDuplicateKeysVisitor;
+
+impl<'de> Visitor<'de> for NoDuplicateKeysVisitor {
+    type Value = NoDuplicateKeys;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value without duplicate object keys")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> std::result::Result<Self::Value, E> { Ok(NoDuplicateKeys) }
+    fn visit_i64<E>(self, _: i64) -> std::result::Result<Self::Value, E> { Ok(NoDuplicateKeys) }
+    fn visit_u64<E>(self, _: u64) -> std::result::Result<Self::Value, E> { Ok(NoDuplicateKeys) }
+    fn visit_f64<E>(self, _: f64) -> std::result::Result<Self::Value, E> { Ok(NoDuplicateKeys) }
+    fn visit_str<E>(self, _: &str) -> std::result::Result<Self::Value, E> { Ok(NoDuplicateKeys) }
+    fn visit_unit<E>(self) -> std::result::Result<Self::Value, E> { Ok(NoDuplicateKeys) }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        while seq.next_element::<NoDuplicateKeys>()?.is_some() {}
+        Ok(NoDuplicateKeys)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        // with the `arbitrary_precision` feature, numbers are also visited as a map
+        // with a single (private) key, so they go through here too without issue
+        let mut keys = HashSet::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if !keys.insert(key.clone()) {
+                return Err(de::Error::custom(format!("duplicate key `{key}` in JSON object")));
+            }
+            map.next_value::<NoDuplicateKeys>()?;
+        }
+        Ok(NoDuplicateKeys)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::json;
+    use super::*;
+
+    #[test]
+    fn test_parse_strict() {
+        let value = parse_strict(r#"{"a": [1, {"b": 2.5, "c": 18446744073709551615}], "d": null}"#).unwrap();
+        assert_eq!(value, json!({"a": [1, {"b": 2.5, "c": u64::MAX}], "d": null}));
+
+        // same key in different objects is fine
+        assert!(parse_strict(r#"[{"a": 1}, {"a": 2}]"#).is_ok());
+
+        // serde_json keeps the last value, we refuse to choose
+        assert_eq!(from_str::<JsonValue>(r#"{"a": 1, "a": 2}"#).unwrap(), json!({"a": 2}));
+        let err = parse_strict(r#"{"a": 1, "a": 2}"#).unwrap_err();
+        assert!(err.to_string().contains("duplicate key `a`"), "{err}");
+        assert!(parse_strict(r#"{"a": [{"b": {"c": 1, "c": 1}}]}"#).is_err());
+
+        assert!(from_str_strict::<Vec<u32>>("[1, 2, 3]").is_ok());
+        assert!(from_str_strict::<JsonValue>(r#"{"x": {"y": 1, "y": 1}}"#).is_err());
+    }
+
+    #[test]
+    fn test_from_str_flatten() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Base { ratio: f64, count: u32 }
+
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Derived { #[serde(flatten)] base: Base, name: String }
+
+        let repr = r#"{"ratio": 0.5, "count": 3, "name": "alice"}"#;
+        let expected = Derived { base: Base { ratio: 0.5, count: 3 }, name: "alice".to_string() };
+
+        // with its `arbitrary_precision` feature, `serde_json` cannot read floats
+        // inside `#[serde(flatten)]` fields directly from a string
+        let result = serde_json::from_str::<Derived>(repr);
+        #[cfg(feature = "arbitrary-precision")]
+        {
+            let err = result.unwrap_err().to_string();
+            assert!(err.contains("invalid type: map, expected f64"), "{err}");
+        }
+        #[cfg(not(feature = "arbitrary-precision"))]
+        assert_eq!(result.unwrap(), expected);
+
+        // going through a `JsonValue` always works
+        assert_eq!(from_str::<Derived>(repr).unwrap(), expected);
+    }
+}
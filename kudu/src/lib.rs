@@ -18,8 +18,28 @@
 //!
 //! - `cli`: whether to compile the command-line tools alongside the library.
 //!          This feature is enabled by default and currently installs the `kuduconv` tool.
+//! - `ship`: add a client for the state history plugin of `nodeos` in the `chain::ship`
+//!           module. Enabled by the `cli` feature.
 //! - `detailed-error`: activate this to enable the [`macro@with_location`] macro. If
 //!                     not enabled, the [`macro@with_location`] macro will be a no-op.
+//! - `contracts-token`: add the actions and tables of the `eosio.token` contract
+//!                      in the `contracts::token` module. Enabled by default.
+//! - `contracts-system`: add the actions and tables of the `eosio` system contract
+//!                       in the `contracts::system` module. Enabled by default.
+//! - `mnemonic`: add support for BIP39 mnemonics (seed phrases) to generate and recover
+//!               private keys, in the `mnemonic` module.
+//! - `antelope-compat`: add the `compat` module, which re-exports the `kudu` types
+//!                      under the names they had in the legacy `antelope` crates.
+//! - `arbitrary-precision`: enable the `arbitrary_precision` feature of `serde_json`, so
+//!                          that JSON integers that do not fit in 64 bits (eg: `uint128`
+//!                          values) can be given unquoted without losing precision. Without
+//!                          it, those need to be quoted and unquoted ones are rejected.
+//!                          **WARNING:** this also changes the behavior of `serde_json` for all
+//!                          the crates of your dependency tree: `serde_json::from_str()` fails
+//!                          for types having floats inside a `#[serde(flatten)]` field, use
+//!                          [`json::from_str()`] instead.
+//! - `metrics`: report metrics of the SHiP client, the history sync and the caches
+//!              through the `metrics` facade crate, see the `metrics` module.
 //! - `hardened`: implement safeguards to check for execution time and recursion depth
 //!               when validating ABIs. (NOT IMPLEMENTED YET!)
 //! - `float128`: add support for a native `float128` type. This currently needs a nightly Rust
@@ -91,6 +111,17 @@
 //!
 //! # or specify directly a file
 //! kuduconv to-hex --abi token_abi.json transfer '{"from": "useraaaaaaaa", "to": "useraaaaaaab", "quantity": "0.0001 SYS", "memo": ""}'
+//!
+//! # decode the proposed transaction of an eosio.msig proposal (a `proposal` table row or
+//! # its `packed_transaction` field), using the given ABI for the actions of `mycontract`
+//! kuduconv msig unpack-proposal --abi mycontract=mycontract.abi '{"proposal_name": ...}'
+//!
+//! # start an interactive session where all conversions use the same ABI
+//! kuduconv repl --abi token_abi.json
+//!
+//! # enable shell completion (also available for zsh and fish), typenames are completed
+//! # using the ABI given with `--abi`
+//! source <(kuduconv completions bash)
 //! ```
 //!
 //! # Differences between this library and the Antelope C++ library
@@ -115,10 +146,18 @@ pub mod abi;
 pub mod api;
 pub mod bitops;
 pub mod chain;
+#[cfg(feature = "antelope-compat")]
+pub mod compat;
 pub mod config;
+pub mod contracts;
 pub mod convert;
 pub mod macros;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
 pub mod json;
+pub mod testing;
 pub mod types;
 
 // FIXME: check whether we want those typedefs? Does it make it easier or
@@ -138,12 +177,20 @@ pub use chain::*;
 
 pub use abi::{ABI, ABIError, ABIDefinition, TypeName};
 
+pub use convert::{ConversionError, hex_to_bytes, bytes_to_hex};
+
 pub mod abiserializable;
 pub mod bytestream;
 
-pub use bytestream::{ByteStream, StreamError};
+pub use bytestream::{ByteStream, StreamError, serde_base64};
 pub use abiserializable::{ABISerializable, SerializeError, to_bin, to_hex, from_bin};
 
+// used by the code generated by the derive macros, not public API
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
+
 /// Add a `location` field to all variants of a `Snafu` error enum
 ///
 /// This will add the `location` field to all variants, which need to be either
@@ -172,12 +219,12 @@ pub use kudu_macros::with_location;
 /// # Example
 ///
 /// ```
-/// # use kudu::{Name, Asset, ABISerializable, contract};
+/// # use kudu::{AccountName, Asset, ABISerializable, contract};
 /// #[contract(account="eosio.token", name="transfer")]
 /// #[derive(ABISerializable)]
 /// pub struct Transfer {
-///     pub from: Name,
-///     pub to: Name,
+///     pub from: AccountName,
+///     pub to: AccountName,
 ///     pub quantity: Asset,
 ///     pub memo: String,
 /// }
@@ -188,6 +235,120 @@ pub use kudu_macros::contract;
 ///
 /// This calls [`ABISerializable::to_bin()`] and [`ABISerializable::from_bin()`]
 /// on all members sequentially.
+///
+/// Fields of type `Option<T>` map to the `T?` ABI type: they are encoded as a
+/// presence byte followed by the value if there is one. This works for all the
+/// builtin types as well as for arrays and other structs, so native structs can
+/// mirror ABIs that make heavy use of optionals:
+///
+/// ```
+/// # use kudu::{ABISerializable, Asset, Name};
+/// #[derive(ABISerializable)]
+/// struct Offer {
+///     owner: Name,
+///     price: Option<Asset>,       // asset?
+///     bidders: Option<Vec<Name>>, // name[]?
+/// }
+/// ```
+///
+/// The ABI does not allow nesting optionals, so `Option<Option<T>>` results in a
+/// compile error:
+///
+/// ```compile_fail
+/// # use kudu::{ABISerializable, Asset};
+/// #[derive(ABISerializable)]
+/// struct Offer {
+///     price: Option<Option<Asset>>,
+/// }
+/// ```
+///
+/// ABI structs can inherit the fields of a `base` struct, which are serialized
+/// before their own fields. This is mirrored by having the base struct as the first
+/// field, marked with `#[kudu(base)]` (which is rejected on any other field) and
+/// with `#[serde(flatten)]` so that its fields are also inlined in JSON. The derive
+/// macro cannot add the serde attribute itself, so a field marked with `#[kudu(base)]`
+/// without `#[serde(flatten)]` results in a compile error:
+///
+/// ```
+/// # use kudu::{ABISerializable, AccountName, Name};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(ABISerializable, Serialize, Deserialize)]
+/// struct Base {
+///     owner: AccountName,
+/// }
+///
+/// #[derive(ABISerializable, Serialize, Deserialize)]
+/// struct Derived {
+///     #[kudu(base)]
+///     #[serde(flatten)]
+///     base: Base,
+///     amount: u64,
+/// }
+///
+/// let json = r#"{"owner":"alice","amount":42}"#;
+/// let value: Derived = kudu::json::from_str(json)?;
+/// assert_eq!(value.base.owner, Name::constant("alice"));
+/// assert_eq!(kudu::json::to_string(&value)?, json);
+///
+/// let bin = kudu::to_bin(&value);
+/// assert_eq!(bin.to_hex(), format!("{}{}", kudu::to_hex(&value.base.owner), "2a00000000000000"));
+/// # Ok::<(), color_eyre::Report>(())
+/// ```
+///
+/// ```compile_fail
+/// # use kudu::{ABISerializable, AccountName};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(ABISerializable, Serialize, Deserialize)]
+/// struct Base {
+///     owner: AccountName,
+/// }
+///
+/// #[derive(ABISerializable, Serialize, Deserialize)]
+/// struct Derived {
+///     #[kudu(base)]
+///     base: Base,  // missing `#[serde(flatten)]`
+///     amount: u64,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use kudu::{ABISerializable, AccountName};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(ABISerializable, Serialize, Deserialize)]
+/// struct Base {
+///     owner: AccountName,
+/// }
+///
+/// #[derive(ABISerializable, Serialize, Deserialize)]
+/// struct Derived {
+///     amount: u64,
+///     #[kudu(base)]  // not the first field
+///     #[serde(flatten)]
+///     base: Base,
+/// }
+/// ```
+///
+/// Note that floats inside a `serde(flatten)` field are not supported with the
+/// `arbitrary_precision` feature of `serde_json` (enabled by the `arbitrary-precision`
+/// feature of `kudu`) when deserializing directly from a string, use
+/// [`json::from_str()`] which goes through a [`JsonValue`] instead.
+///
+/// The generated code refers to this crate as `kudu`. If it is available under
+/// another path (eg: re-exported by another crate or renamed in `Cargo.toml`),
+/// use the `#[kudu(crate = "...")]` attribute, which is also supported by the
+/// [`SerializeEnum`] and [`SerializeEnumPrefixed`] derive macros and, as a
+/// `crate = "..."` argument, by the [`contract`] attribute macro.
+///
+/// ```
+/// # mod my_lib { pub use ::kudu as antelope; }
+/// use my_lib::antelope::{ABISerializable, AccountName};
+///
+/// #[derive(ABISerializable)]
+/// #[kudu(crate = "my_lib::antelope")]
+/// struct Account {
+///     name: AccountName,
+/// }
+/// ```
 pub use kudu_macros::ABISerializable;
 
 /// Implement the `serde::Serialize` and `serde::Deserialize` trait
@@ -198,7 +359,8 @@ pub use kudu_macros::ABISerializable;
 /// `#[derive(Serialize, Deserialize)]` for enum types. By default the discriminant
 /// is serialized as a `snake_case` string.
 ///
-/// It exposes one attribute argument for fields which is `serde(rename)`.
+/// It exposes one attribute argument for fields which is `serde(rename)`, and
+/// the `kudu(crate = "...")` container attribute, see [`macro@ABISerializable`].
 pub use kudu_macros::SerializeEnum;
 
 /// Implement the `serde::Serialize` and `serde::Deserialize` trait
@@ -212,7 +374,8 @@ pub use kudu_macros::SerializeEnum;
 /// `#[derive(Serialize, Deserialize)]` for enum types. By default the discriminant
 /// is serialized as a `snake_case` string.
 ///
-/// It exposes one attribute argument for fields which is `serde(rename)`.
+/// It exposes one attribute argument for fields which is `serde(rename)`, and
+/// the `kudu(crate = "...")` container attribute, see [`macro@ABISerializable`].
 pub use kudu_macros::SerializeEnumPrefixed;
 
 
@@ -14,3 +14,52 @@ macro_rules! impl_auto_error_conversion {
         }
     };
 }
+
+/// Build an [`Asset`](crate::Asset) from its string representation, validated at
+/// compile time.
+///
+/// ## Example
+/// ```
+/// # use kudu::asset;
+/// let fee = asset!("1.2345 SYS");
+/// assert_eq!(fee.amount(), 12345);
+/// ```
+///
+/// Invalid assets fail to compile:
+/// ```compile_fail
+/// # use kudu::asset;
+/// let fee = asset!("1.2345 sys");
+/// ```
+#[macro_export]
+macro_rules! asset {
+    ($s:literal) => {
+        const { $crate::Asset::constant($s) }
+    };
+}
+
+/// Implement `TryFrom<&str>` and `TryFrom<String>` for types that implement `FromStr`,
+/// so that all the types that can be parsed from a string can be built in the same way,
+/// eg. by generic code using `s.try_into()`.
+macro_rules! impl_try_from_str {
+    ($($typ:ty),* $(,)?) => {
+        $(
+            impl TryFrom<&str> for $typ {
+                type Error = <$typ as ::std::str::FromStr>::Err;
+
+                fn try_from(s: &str) -> Result<Self, Self::Error> {
+                    <$typ as ::std::str::FromStr>::from_str(s)
+                }
+            }
+
+            impl TryFrom<String> for $typ {
+                type Error = <$typ as ::std::str::FromStr>::Err;
+
+                fn try_from(s: String) -> Result<Self, Self::Error> {
+                    <$typ as ::std::str::FromStr>::from_str(&s)
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_try_from_str;
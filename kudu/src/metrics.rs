@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Metrics of the long-running parts of kudu: the [SHiP client](crate::chain::ship),
+//! [`HistorySync`](crate::chain::history::HistorySync) and the caches.
+//!
+//! This module is only available with the `metrics` feature. Metrics are reported
+//! through the [`metrics`](https://docs.rs/metrics) facade crate, kudu does not depend
+//! on any exporter: install the recorder of your choice (eg: `metrics-exporter-prometheus`)
+//! in your application and the following metrics will be sent to it:
+//!
+//! | name                                     | type      | description |
+//! |------------------------------------------|-----------|-------------|
+//! | `kudu_ship_blocks_received_total`        | counter   | blocks received from SHiP endpoints |
+//! | `kudu_ship_decode_errors_total`          | counter   | SHiP results that could not be decoded |
+//! | `kudu_ship_head_lag_blocks`              | gauge     | blocks between the last block received from SHiP and the head block |
+//! | `kudu_history_blocks_handled_total`      | counter   | blocks handled by a `HistorySync` |
+//! | `kudu_history_blocks_per_second`         | gauge     | blocks handled per second (wall-clock) during the last batch |
+//! | `kudu_history_batch_duration_seconds`    | histogram | time spent by the handler on each batch |
+//! | `kudu_cache_hits_total`                  | counter   | cache lookups that found their value, labelled by `cache` |
+//! | `kudu_cache_misses_total`                | counter   | cache lookups that did not find their value, labelled by `cache` |
+//!
+//! The `cache` label is one of `abi_parsed` (see [`ABI::from_bin_abi_cached()`]),
+//! `abi_encode` (see [`ABI::with_encode_cache()`]) or `name` (see [`NameCache`]).
+//!
+//! Call [`describe()`] once after installing the recorder to also register the
+//! descriptions and units of these metrics.
+//!
+//! [`ABI::from_bin_abi_cached()`]: crate::ABI::from_bin_abi_cached
+//! [`ABI::with_encode_cache()`]: crate::ABI::with_encode_cache
+//! [`NameCache`]: crate::NameCache
+//!
+
+use ::metrics::{Unit, describe_counter, describe_gauge, describe_histogram, counter};
+
+
+pub const SHIP_BLOCKS_RECEIVED: &str = "kudu_ship_blocks_received_total";
+pub const SHIP_DECODE_ERRORS: &str = "kudu_ship_decode_errors_total";
+pub const SHIP_HEAD_LAG: &str = "kudu_ship_head_lag_blocks";
+pub const HISTORY_BLOCKS_HANDLED: &str = "kudu_history_blocks_handled_total";
+pub const HISTORY_BLOCKS_PER_SECOND: &str = "kudu_history_blocks_per_second";
+pub const HISTORY_BATCH_DURATION: &str = "kudu_history_batch_duration_seconds";
+pub const CACHE_HITS: &str = "kudu_cache_hits_total";
+pub const CACHE_MISSES: &str = "kudu_cache_misses_total";
+
+/// Register the descriptions and units of all the metrics reported by kudu with
+/// the installed recorder.
+pub fn describe() {
+    describe_counter!(SHIP_BLOCKS_RECEIVED, Unit::Count, "Blocks received from SHiP endpoints");
+    describe_counter!(SHIP_DECODE_ERRORS, Unit::Count, "SHiP results that could not be decoded");
+    describe_gauge!(SHIP_HEAD_LAG, Unit::Count, "Blocks between the last block received from SHiP and the head block");
+    describe_counter!(HISTORY_BLOCKS_HANDLED, Unit::Count, "Blocks handled by history sync");
+    describe_gauge!(HISTORY_BLOCKS_PER_SECOND, "Blocks handled per second by history sync during the last batch");
+    describe_histogram!(HISTORY_BATCH_DURATION, Unit::Seconds, "Time spent by the history sync handler on each batch");
+    describe_counter!(CACHE_HITS, Unit::Count, "Cache lookups that found their value");
+    describe_counter!(CACHE_MISSES, Unit::Count, "Cache lookups that did not find their value");
+}
+
+/// Record a lookup in one of the caches of kudu.
+pub(crate) fn cache_lookup(cache: &'static str, hit: bool) {
+    match hit {
+        true => counter!(CACHE_HITS, "cache" => cache).increment(1),
+        false => counter!(CACHE_MISSES, "cache" => cache).increment(1),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use metrics_util::{MetricKind, debugging::{DebugValue, DebuggingRecorder}};
+
+    use super::*;
+    use crate::{Name, NameCache};
+
+    #[test]
+    fn test_cache_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        ::metrics::with_local_recorder(&recorder, || {
+            let cache = NameCache::new(10);
+            cache.get(Name::constant("alice"));
+            cache.get(Name::constant("alice"));
+            cache.get(Name::constant("bob"));
+        });
+
+        let counters: Vec<_> = snapshotter.snapshot().into_vec().into_iter()
+            .filter(|(key, ..)| key.kind() == MetricKind::Counter)
+            .map(|(key, _, _, value)| {
+                let labels: Vec<_> = key.key().labels().map(|l| (l.key().to_owned(), l.value().to_owned())).collect();
+                (key.key().name().to_owned(), labels, value)
+            })
+            .collect();
+        let label = vec![("cache".to_owned(), "name".to_owned())];
+        assert!(counters.contains(&(CACHE_HITS.to_owned(), label.clone(), DebugValue::Counter(1))));
+        assert!(counters.contains(&(CACHE_MISSES.to_owned(), label, DebugValue::Counter(2))));
+    }
+}
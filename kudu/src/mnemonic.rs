@@ -0,0 +1,440 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Generate and recover K1 private keys from [BIP39] mnemonics (seed phrases), using
+//! [BIP32] derivation with the standard Antelope derivation path ([`ANTELOPE_DERIVATION_PATH`]).
+//!
+//! This module is only available with the `mnemonic` feature.
+//!
+//! Example:
+//! ```
+//! # use kudu::{PrivateKey, mnemonic::Mnemonic};
+//! // generate a new mnemonic for a wallet
+//! let mnemonic = Mnemonic::generate(12)?;
+//! assert_eq!(mnemonic.words().count(), 12);
+//!
+//! // recover a key from a mnemonic given by the user
+//! let mnemonic: Mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow".parse()?;
+//! let key: PrivateKey = mnemonic.private_key("")?;
+//! assert_eq!(key, mnemonic.derive_private_key("", "m/44'/194'/0'/0/0")?);
+//! # Ok::<(), color_eyre::Report>(())
+//! ```
+//!
+//! [BIP39]: <https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki>
+//! [BIP32]: <https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki>
+//!
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use secp256k1::{Scalar, SecretKey};
+use sha2::{Digest, Sha256, Sha512};
+use snafu::{ensure, OptionExt, Snafu};
+use zeroize::Zeroize;
+
+use kudu_macros::with_location;
+
+use crate::{KeyType, PrivateKey};
+
+
+/// Derivation path of the first account, as registered in [SLIP-44] for EOS (coin type 194).
+///
+/// [SLIP-44]: <https://github.com/satoshilabs/slips/blob/master/slip-0044.md>
+pub const ANTELOPE_DERIVATION_PATH: &str = "m/44'/194'/0'/0/0";
+
+/// Number of words allowed in a mnemonic.
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+const WORDLIST: &str = include_str!("mnemonic/bip39_english.txt");
+
+const PBKDF2_ROUNDS: u32 = 2048;
+
+const HARDENED: u32 = 1 << 31;
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum MnemonicError {
+    #[snafu(display("invalid number of words: {count}, needs to be one of {WORD_COUNTS:?}"))]
+    InvalidWordCount { count: usize },
+
+    #[snafu(display("invalid entropy size: {size} bytes, needs to be a multiple of 4 between 16 and 32"))]
+    InvalidEntropySize { size: usize },
+
+    #[snafu(display("unknown word in mnemonic: \"{word}\""))]
+    UnknownWord { word: String },
+
+    #[snafu(display("invalid mnemonic checksum"))]
+    InvalidChecksum,
+
+    #[snafu(display("invalid derivation path: \"{path}\""))]
+    InvalidPath { path: String },
+
+    #[snafu(display("derived key is invalid for path \"{path}\", use another index"))]
+    InvalidDerivedKey { path: String },
+
+    #[snafu(display("could not get random bytes from the OS: {message}"))]
+    RandomError { message: String },
+}
+
+
+// -----------------------------------------------------------------------------
+//     Mnemonic
+// -----------------------------------------------------------------------------
+
+/// A BIP39 mnemonic using the english wordlist.
+///
+/// Its `Display` representation is the seed phrase, with words separated by a space.
+/// Its `Debug` representation only shows the number of words, so that the seed phrase
+/// does not end up in logs, and its entropy is zeroed when it is dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Mnemonic {
+    entropy: Vec<u8>,
+}
+
+impl Mnemonic {
+    /// Generate a new mnemonic with the given number of words, using the random
+    /// number generator of the OS.
+    pub fn generate(word_count: usize) -> Result<Self, MnemonicError> {
+        ensure!(WORD_COUNTS.contains(&word_count), InvalidWordCountSnafu { count: word_count });
+        let mut entropy = vec![0u8; word_count / 3 * 4];
+        getrandom::fill(&mut entropy).map_err(|e| RandomSnafu { message: e.to_string() }.build())?;
+        Self::from_entropy(&entropy)
+    }
+
+    /// Build the mnemonic encoding the given entropy, which needs to be 16, 20, 24,
+    /// 28 or 32 bytes long.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, MnemonicError> {
+        ensure!(matches!(entropy.len(), 16..=32) && entropy.len().is_multiple_of(4),
+                InvalidEntropySizeSnafu { size: entropy.len() });
+        Ok(Mnemonic { entropy: entropy.to_vec() })
+    }
+
+    /// Parse a seed phrase, ignoring case and extra whitespace between words.
+    pub fn parse(phrase: &str) -> Result<Self, MnemonicError> {
+        let words: Vec<String> = phrase.split_whitespace().map(str::to_lowercase).collect();
+        ensure!(WORD_COUNTS.contains(&words.len()), InvalidWordCountSnafu { count: words.len() });
+
+        let mut bits = Vec::with_capacity(words.len() * 11);
+        for word in &words {
+            let index = wordlist().binary_search(&word.as_str())
+                .ok().context(UnknownWordSnafu { word })?;
+            bits.extend((0..11).rev().map(|i| (index >> i) & 1 == 1));
+        }
+
+        let checksum_len = words.len() / 3;
+        let (data, checksum) = bits.split_at(bits.len() - checksum_len);
+        let entropy: Vec<u8> = data.chunks(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+            .collect();
+
+        let mnemonic = Mnemonic { entropy };
+        ensure!(mnemonic.checksum_bits().eq(checksum.iter().copied()), InvalidChecksumSnafu);
+        Ok(mnemonic)
+    }
+
+    pub fn entropy(&self) -> &[u8] {
+        &self.entropy
+    }
+
+    /// Return the words of the seed phrase.
+    pub fn words(&self) -> impl Iterator<Item = &'static str> {
+        let bits: Vec<bool> = self.entropy.iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+            .chain(self.checksum_bits())
+            .collect();
+        let words = wordlist();
+        bits.chunks(11)
+            .map(|chunk| chunk.iter().fold(0usize, |acc, &bit| (acc << 1) | bit as usize))
+            .map(move |index| words[index])
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Return the 64-byte seed derived from this mnemonic and the given passphrase
+    /// (use an empty string for no passphrase).
+    ///
+    /// Note that the passphrase is not normalized: if it contains non-ASCII
+    /// characters, it needs to be given in Unicode NFKD form to be compatible
+    /// with other wallets.
+    pub fn to_seed(&self, passphrase: &str) -> [u8; 64] {
+        let salt = format!("mnemonic{passphrase}");
+        pbkdf2_hmac_sha512(self.to_string().as_bytes(), salt.as_bytes(), PBKDF2_ROUNDS)
+    }
+
+    /// Return the private key of the first account, derived with [`ANTELOPE_DERIVATION_PATH`].
+    pub fn private_key(&self, passphrase: &str) -> Result<PrivateKey, MnemonicError> {
+        self.derive_private_key(passphrase, ANTELOPE_DERIVATION_PATH)
+    }
+
+    /// Return the private key derived with the given BIP32 path, eg: `m/44'/194'/0'/0/1`
+    /// (hardened indices can be written as `44'` or `44h`).
+    pub fn derive_private_key(&self, passphrase: &str, path: &str) -> Result<PrivateKey, MnemonicError> {
+        derive_private_key(&self.to_seed(passphrase), path)
+    }
+
+    fn checksum_bits(&self) -> impl Iterator<Item = bool> {
+        let hash: [u8; 32] = Sha256::digest(&self.entropy).into();
+        let len = self.entropy.len() / 4;
+        (0..len).map(move |i| (hash[i / 8] >> (7 - i % 8)) & 1 == 1)
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.words().collect::<Vec<_>>().join(" "))
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mnemonic")
+            .field("word_count", &(self.entropy.len() / 4 * 3))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Mnemonic {
+    fn drop(&mut self) {
+        self.entropy.zeroize();
+    }
+}
+
+impl FromStr for Mnemonic {
+    type Err = MnemonicError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+fn wordlist() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORDLIST.lines().collect())
+}
+
+
+// -----------------------------------------------------------------------------
+//     Key derivation
+// -----------------------------------------------------------------------------
+
+/// Derive a private key from a BIP32 seed (eg: as returned by [`Mnemonic::to_seed()`])
+/// and the given derivation path.
+pub fn derive_private_key(seed: &[u8], path: &str) -> Result<PrivateKey, MnemonicError> {
+    let indices = parse_path(path)?;
+    let invalid = || InvalidDerivedKeySnafu { path }.build();
+
+    let master = hmac_sha512(b"Bitcoin seed", &[seed]);
+    let (mut key, mut chain_code) = split_key(&master);
+    let mut secret = SecretKey::from_byte_array(key).map_err(|_| invalid())?;
+
+    for index in indices {
+        let index_bytes = index.to_be_bytes();
+        let data = match index >= HARDENED {
+            true => hmac_sha512(&chain_code, &[&[0], &key, &index_bytes]),
+            false => {
+                let public = secp256k1::PublicKey::from_secret_key(secp256k1::global::SECP256K1, &secret);
+                hmac_sha512(&chain_code, &[&public.serialize(), &index_bytes])
+            },
+        };
+        let (tweak, child_chain_code) = split_key(&data);
+        let tweak = Scalar::from_be_bytes(tweak).map_err(|_| invalid())?;
+        secret = secret.add_tweak(&tweak).map_err(|_| invalid())?;
+        key = secret.secret_bytes();
+        chain_code = child_chain_code;
+    }
+
+    Ok(PrivateKey::with_key_type(KeyType::K1, key))
+}
+
+fn parse_path(path: &str) -> Result<Vec<u32>, MnemonicError> {
+    let mut components = path.split('/');
+    ensure!(components.next() == Some("m"), InvalidPathSnafu { path });
+    components.map(|c| {
+        let (index, hardened) = match c.strip_suffix(['\'', 'h', 'H']) {
+            Some(index) => (index, true),
+            None => (c, false),
+        };
+        let index: u32 = index.parse().ok()
+            .filter(|i| *i < HARDENED)
+            .context(InvalidPathSnafu { path })?;
+        Ok(if hardened { index | HARDENED } else { index })
+    }).collect()
+}
+
+fn split_key(data: &[u8; 64]) -> ([u8; 32], [u8; 32]) {
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&data[..32]);
+    right.copy_from_slice(&data[32..]);
+    (left, right)
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    const BLOCK_SIZE: usize = 128;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..64].copy_from_slice(&Sha512::digest(key));
+    }
+    else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha512::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    for d in data {
+        inner.update(d);
+    }
+    let mut outer = Sha512::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], rounds: u32) -> [u8; 64] {
+    // we only need the first block as the output has the same size as the hash
+    let mut u = hmac_sha512(password, &[salt, &1u32.to_be_bytes()]);
+    let mut result = u;
+    for _ in 1..rounds {
+        u = hmac_sha512(password, &[&u]);
+        result.iter_mut().zip(u).for_each(|(r, b)| *r ^= b);
+    }
+    result
+}
+
+
+// -----------------------------------------------------------------------------
+//     Legacy seeds
+// -----------------------------------------------------------------------------
+
+impl PrivateKey {
+    /// Return the private key derived from a seed string, as done by the legacy
+    /// `eosjs-ecc` library (`PrivateKey.fromSeed()`), ie: the SHA-256 hash of the seed.
+    ///
+    /// This is only provided to recover keys from old wallets, as a human-chosen seed
+    /// is usually easy to guess. Use a [`Mnemonic`] to generate new keys instead.
+    pub fn from_legacy_seed(seed: &str) -> PrivateKey {
+        PrivateKey::with_key_type(KeyType::K1, Sha256::digest(seed.as_bytes()).into())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use crate::{bytes_to_hex, hex_to_bytes, PublicKey};
+    use super::*;
+
+    #[test]
+    fn test_wordlist() {
+        let words = wordlist();
+        assert_eq!(words.len(), 2048);
+        assert!(words.is_sorted());
+        assert_eq!((words[0], words[2047]), ("abandon", "zoo"));
+    }
+
+    #[test]
+    fn test_bip39_vectors() -> Result<()> {
+        // test vectors from: https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+        let vectors = [
+            ("00000000000000000000000000000000",
+             "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+             "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"),
+            ("7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+             "legal winner thank year wave sausage worth useful legal winner thank yellow",
+             "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607"),
+            ("80808080808080808080808080808080",
+             "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+             "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8"),
+            ("ffffffffffffffffffffffffffffffff",
+             "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+             "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069"),
+        ];
+
+        for (entropy, phrase, seed) in vectors {
+            let mnemonic = Mnemonic::from_entropy(&hex_to_bytes(entropy)?)?;
+            assert_eq!(mnemonic.to_string(), phrase);
+            assert_eq!(Mnemonic::parse(phrase)?, mnemonic);
+            assert_eq!(bytes_to_hex(mnemonic.to_seed("TREZOR")), seed);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_mnemonics() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert!(matches!(Mnemonic::parse(phrase), Err(MnemonicError::InvalidWordCount { .. })));
+        assert_eq!(Mnemonic::parse(&format!("{phrase} abandon")).unwrap_err().to_string(), "invalid mnemonic checksum");
+        assert!(matches!(Mnemonic::parse(&format!("{phrase} kudu")), Err(MnemonicError::UnknownWord { .. })));
+        assert!(Mnemonic::parse(&format!("  {}\tABOUT\n", phrase.to_uppercase())).is_ok());
+        assert!(Mnemonic::from_entropy(&[0; 15]).is_err());
+        assert!(Mnemonic::generate(13).is_err());
+    }
+
+    #[test]
+    fn test_generate() -> Result<()> {
+        for count in WORD_COUNTS {
+            let mnemonic = Mnemonic::generate(count)?;
+            assert_eq!(mnemonic.words().count(), count);
+            assert_eq!(Mnemonic::parse(&mnemonic.to_string())?, mnemonic);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bip32_vectors() -> Result<()> {
+        // test vector 1 from: https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki
+        let seed = hex_to_bytes("000102030405060708090a0b0c0d0e0f")?;
+        let vectors = [
+            ("m", "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"),
+            ("m/0'", "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea"),
+            ("m/0'/1", "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368"),
+            ("m/0h/1/2h", "cbce0d719ecf7431d88e6a89fa1483e02e35092af60c042b1df2ff59fa424dca"),
+            ("m/0H/1/2H/2", "0f479245fb19a38a1954c5c7c0ebab2f9bdfd96a17563ef28a6a4b1a2a764ef4"),
+            ("m/0'/1/2'/2/1000000000", "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8"),
+        ];
+        for (path, key) in vectors {
+            assert_eq!(derive_private_key(&seed, path)?.to_hex(), key);
+        }
+
+        for path in ["", "0/1", "m/", "m/x", "m/2147483648", "m/0''"] {
+            assert!(matches!(derive_private_key(&seed, path), Err(MnemonicError::InvalidPath { .. })));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_private_key() -> Result<()> {
+        let mnemonic = Mnemonic::from_entropy(&[0; 16])?;
+        let key = mnemonic.private_key("")?;
+        assert_eq!(key, mnemonic.derive_private_key("", ANTELOPE_DERIVATION_PATH)?);
+        assert_ne!(key, mnemonic.private_key("passphrase")?);
+        assert_ne!(key, mnemonic.derive_private_key("", "m/44'/194'/0'/0/1")?);
+
+        // the key can be used to sign
+        let signature = key.sign_bytes(b"kudu");
+        assert!(PublicKey::from_private_key(&key).verify_signature(b"kudu", &signature));
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_hides_phrase() -> Result<()> {
+        let mnemonic: Mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow".parse()?;
+        let repr = format!("{mnemonic:?}");
+        assert_eq!(repr, "Mnemonic { word_count: 12, .. }");
+        assert!(!repr.contains("legal"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_legacy_seed() -> Result<()> {
+        let key = PrivateKey::from_legacy_seed("correct horse battery staple");
+        assert_eq!(key.to_hex(), "c4bbcb1fbec99d65bf59d85c8cb62ee2db963f0fe106f483d9afa73bd4e39a8a");
+        Ok(())
+    }
+}
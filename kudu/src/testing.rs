@@ -0,0 +1,355 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Helpers to unit-test code built around contract actions without running a node.
+//!
+//! [`MockChain`] applies actions (typically built from [`#[contract]`][contract]
+//! structs) by dispatching them to Rust handlers registered for each
+//! `(account, action)` pair. It is not a WASM VM: the contracts themselves are not
+//! run, but it replicates enough of the data model of a chain (accounts,
+//! authorizations, inline actions, rollback of failed actions) to test the
+//! off-chain logic interacting with them.
+//!
+//! [contract]: crate::contract
+//!
+
+use std::collections::{BTreeSet, HashMap};
+
+use snafu::{ensure, ResultExt, Snafu};
+
+use crate::{
+    AccountName, Action, ActionName, Contract, PermissionLevel, SerializeError,
+    from_bin, with_location,
+};
+
+/// Max depth of inline actions, same as the default of `nodeos`.
+pub const MAX_INLINE_ACTION_DEPTH: usize = 4;
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+pub enum MockChainError {
+    #[snafu(display("account `{account}` does not exist"))]
+    UnknownAccount { account: AccountName },
+
+    #[snafu(display("no handler for action `{account}::{name}`"))]
+    NoHandler { account: AccountName, name: ActionName },
+
+    #[snafu(display("cannot decode data of action `{account}::{name}`"))]
+    Decode { account: AccountName, name: ActionName, source: SerializeError },
+
+    #[snafu(display("missing authority of `{account}`"))]
+    MissingAuth { account: AccountName },
+
+    #[snafu(display("inline action sent by `{sender}` cannot be authorized by `{level}`"))]
+    InlineAuth { sender: AccountName, level: PermissionLevel },
+
+    #[snafu(display("max inline action depth reached ({MAX_INLINE_ACTION_DEPTH})"))]
+    MaxDepth,
+
+    #[snafu(display("assertion failure: {message}"))]
+    Assert { message: String },
+}
+
+
+type Handler<S> = Box<dyn Fn(&mut ActionContext<S>) -> Result<(), MockChainError>>;
+
+/// Context in which an action is applied, given to the handlers of a [`MockChain`].
+///
+/// It gives access to the state of the chain and provides the equivalent of the
+/// intrinsics used by contracts to check authorizations and send inline actions.
+pub struct ActionContext<'a, S> {
+    pub state: &'a mut S,
+    action: &'a Action,
+    inline_actions: Vec<Action>,
+}
+
+impl<S> ActionContext<'_, S> {
+    /// Return the action being applied.
+    pub fn action(&self) -> &Action {
+        self.action
+    }
+
+    /// Return the account of the contract receiving the action.
+    pub fn receiver(&self) -> AccountName {
+        self.action.account
+    }
+
+    /// Return whether the action has been authorized by `account`.
+    pub fn has_auth(&self, account: AccountName) -> bool {
+        self.action.authorization.iter().any(|level| level.actor == account)
+    }
+
+    /// Fail unless the action has been authorized by `account`.
+    pub fn require_auth(&self, account: AccountName) -> Result<(), MockChainError> {
+        ensure!(self.has_auth(account), MissingAuthSnafu { account });
+        Ok(())
+    }
+
+    /// Fail unless the action has been authorized by `level` exactly.
+    pub fn require_auth2(&self, level: PermissionLevel) -> Result<(), MockChainError> {
+        ensure!(self.action.authorization.contains(&level), MissingAuthSnafu { account: level.actor });
+        Ok(())
+    }
+
+    /// Fail with the given message unless `condition` holds, same as `eosio::check()`.
+    pub fn check(&self, condition: bool, message: &str) -> Result<(), MockChainError> {
+        ensure!(condition, AssertSnafu { message });
+        Ok(())
+    }
+
+    /// Send an inline action, which is applied after the current one.
+    ///
+    /// Inline actions can only be authorized by the contract sending them, as
+    /// with the `eosio.code` permission on a real chain.
+    pub fn send_inline(&mut self, action: Action) {
+        self.inline_actions.push(action);
+    }
+}
+
+
+/// An in-process stub of a chain applying actions using Rust handlers.
+///
+/// The state of the chain is a value of type `S` which handlers can read and
+/// modify; it is restored to its previous value when applying an action fails,
+/// including when one of the inline actions it sent fails.
+///
+/// Authorization follows a simple model:
+///  - the actors declaring the authorization of an action must be existing
+///    accounts, created with [`MockChain::create_account()`], and are considered
+///    to have signed the action
+///  - handlers check authorizations explicitly using [`ActionContext::require_auth()`]
+///  - inline actions can only be authorized by the contract sending them
+///
+/// # Example
+///
+/// ```
+/// # use kudu::{Action, ABISerializable, AccountName, contract};
+/// # use kudu::testing::{MockChain, MockChainError};
+/// #[contract(account="counter", name="incr")]
+/// #[derive(ABISerializable)]
+/// pub struct Incr {
+///     pub owner: AccountName,
+/// }
+///
+/// let mut chain = MockChain::new(0_u32).with_accounts(&["counter", "alice"]);
+/// chain.on(|ctx, incr: Incr| {
+///     ctx.require_auth(incr.owner)?;
+///     *ctx.state += 1;
+///     Ok(())
+/// });
+///
+/// let alice = AccountName::constant("alice");
+/// chain.push_action(Action::new(("alice", "active"), &Incr { owner: alice }))?;
+/// assert_eq!(*chain.state(), 1);
+///
+/// // bob did not authorize the action
+/// let bob = AccountName::constant("bob");
+/// assert!(chain.push_action(Action::new(("alice", "active"), &Incr { owner: bob })).is_err());
+/// assert_eq!(*chain.state(), 1);
+/// # Ok::<(), MockChainError>(())
+/// ```
+pub struct MockChain<S> {
+    state: S,
+    accounts: BTreeSet<AccountName>,
+    handlers: HashMap<(AccountName, ActionName), Handler<S>>,
+    allow_unhandled: bool,
+    applied: Vec<Action>,
+}
+
+impl<S: Clone> MockChain<S> {
+    pub fn new(state: S) -> Self {
+        MockChain {
+            state,
+            accounts: BTreeSet::new(),
+            handlers: HashMap::new(),
+            allow_unhandled: false,
+            applied: vec![],
+        }
+    }
+
+    /// Create the given accounts, see [`MockChain::create_account()`].
+    ///
+    /// Panics if one of the names is not a valid account name.
+    pub fn with_accounts(mut self, accounts: &[&str]) -> Self {
+        for account in accounts {
+            self.create_account(AccountName::constant(account));
+        }
+        self
+    }
+
+    /// Set whether actions for which no handler has been registered are accepted
+    /// (and simply recorded) instead of failing.
+    pub fn allow_unhandled(mut self, allow: bool) -> Self {
+        self.allow_unhandled = allow;
+        self
+    }
+
+    pub fn create_account(&mut self, account: AccountName) {
+        self.accounts.insert(account);
+    }
+
+    pub fn account_exists(&self, account: AccountName) -> bool {
+        self.accounts.contains(&account)
+    }
+
+    /// Register the handler applying the actions of type `T`, replacing the previous one.
+    pub fn on<T, F>(&mut self, handler: F)
+    where
+        T: Contract + 'static,
+        F: Fn(&mut ActionContext<S>, T) -> Result<(), MockChainError> + 'static,
+    {
+        let (account, name) = (T::account(), T::name());
+        self.handlers.insert((account, name), Box::new(move |ctx| {
+            let data: T = from_bin(&ctx.action.data).context(DecodeSnafu { account, name })?;
+            handler(ctx, data)
+        }));
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Return all the actions successfully applied so far, including inline actions,
+    /// in the order in which they have been applied.
+    pub fn applied_actions(&self) -> &[Action] {
+        &self.applied
+    }
+
+    /// Apply an action and all the inline actions it sends, and return them in the
+    /// order in which they have been applied.
+    ///
+    /// If any of them fails, the state of the chain is left untouched.
+    pub fn push_action(&mut self, action: Action) -> Result<Vec<Action>, MockChainError> {
+        self.push_actions(vec![action])
+    }
+
+    /// Apply several actions atomically, as in a transaction, see [`MockChain::push_action()`].
+    pub fn push_actions(&mut self, actions: Vec<Action>) -> Result<Vec<Action>, MockChainError> {
+        let snapshot = self.state.clone();
+        let mut applied = vec![];
+        let result = actions.into_iter()
+            .try_for_each(|action| self.apply(action, None, 0, &mut applied));
+
+        match result {
+            Ok(()) => {
+                self.applied.extend(applied.iter().cloned());
+                Ok(applied)
+            },
+            Err(e) => {
+                self.state = snapshot;
+                Err(e)
+            },
+        }
+    }
+
+    fn apply(&mut self, action: Action, sender: Option<AccountName>, depth: usize,
+             applied: &mut Vec<Action>) -> Result<(), MockChainError> {
+        ensure!(depth <= MAX_INLINE_ACTION_DEPTH, MaxDepthSnafu);
+        ensure!(self.account_exists(action.account), UnknownAccountSnafu { account: action.account });
+        for level in &action.authorization {
+            ensure!(self.account_exists(level.actor), UnknownAccountSnafu { account: level.actor });
+            if let Some(sender) = sender {
+                ensure!(level.actor == sender, InlineAuthSnafu { sender, level: *level });
+            }
+        }
+
+        let inline_actions = match self.handlers.get(&(action.account, action.name)) {
+            Some(handler) => {
+                let mut ctx = ActionContext { state: &mut self.state, action: &action, inline_actions: vec![] };
+                handler(&mut ctx)?;
+                ctx.inline_actions
+            },
+            None => {
+                ensure!(self.allow_unhandled, NoHandlerSnafu { account: action.account, name: action.name });
+                vec![]
+            },
+        };
+
+        let receiver = action.account;
+        applied.push(action);
+        for inline in inline_actions {
+            self.apply(inline, Some(receiver), depth + 1, applied)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ABISerializable, contract};
+
+    // needed by the `contract` and `ABISerializable` macros
+    extern crate self as kudu;
+
+    #[contract(account="bank", name="deposit")]
+    #[derive(ABISerializable)]
+    struct Deposit {
+        owner: AccountName,
+        amount: u64,
+    }
+
+    #[contract(account="bank", name="notify")]
+    #[derive(ABISerializable)]
+    struct Notify {
+        owner: AccountName,
+    }
+
+    fn bank() -> MockChain<HashMap<AccountName, u64>> {
+        let mut chain = MockChain::new(HashMap::new()).with_accounts(&["bank", "alice", "bob"]);
+        chain.on(|ctx, deposit: Deposit| {
+            ctx.require_auth(deposit.owner)?;
+            ctx.check(deposit.amount > 0, "amount must be positive")?;
+            *ctx.state.entry(deposit.owner).or_default() += deposit.amount;
+            ctx.send_inline(Action::new(("bank", "active"), &Notify { owner: deposit.owner }));
+            Ok(())
+        });
+        chain.on(|_ctx, _notify: Notify| Ok(()));
+        chain
+    }
+
+    #[test]
+    fn test_mock_chain() {
+        let mut chain = bank();
+        let alice = AccountName::constant("alice");
+
+        let applied = chain.push_action(Action::new(("alice", "active"), &Deposit { owner: alice, amount: 5 })).unwrap();
+        assert_eq!(applied.len(), 2);
+        assert_eq!(applied[1].name, ActionName::constant("notify"));
+        assert_eq!(chain.state()[&alice], 5);
+
+        // second action fails, the first one is rolled back
+        let result = chain.push_actions(vec![
+            Action::new(("alice", "active"), &Deposit { owner: alice, amount: 5 }),
+            Action::new(("alice", "active"), &Deposit { owner: alice, amount: 0 }),
+        ]);
+        assert!(matches!(result, Err(MockChainError::Assert { .. })));
+        assert_eq!(chain.state()[&alice], 5);
+        assert_eq!(chain.applied_actions().len(), 2);
+
+        let result = chain.push_action(Action::new(("bob", "active"), &Deposit { owner: alice, amount: 5 }));
+        assert!(matches!(result, Err(MockChainError::MissingAuth { .. })));
+
+        let result = chain.push_action(Action::new(("carol", "active"), &Deposit { owner: alice, amount: 5 }));
+        assert!(matches!(result, Err(MockChainError::UnknownAccount { .. })));
+    }
+
+    #[test]
+    fn test_mock_chain_inline_auth() {
+        let mut chain = bank();
+        chain.on(|ctx, deposit: Deposit| {
+            // inline action authorized by the user instead of the contract
+            ctx.send_inline(Action::new(("alice", "active"), &Notify { owner: deposit.owner }));
+            Ok(())
+        });
+        let result = chain.push_action(Action::new(("alice", "active"), &Deposit { owner: AccountName::constant("alice"), amount: 5 }));
+        assert!(matches!(result, Err(MockChainError::InlineAuth { .. })));
+    }
+}
@@ -20,6 +20,7 @@
 
 mod antelopevalue;
 mod asset;
+mod bls;
 mod crypto;
 mod float128;
 mod name;
@@ -30,7 +31,9 @@ mod varint;
 use std::str::FromStr;
 
 use hex::FromHexError;
-use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::macros::impl_try_from_str;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 
 // -----------------------------------------------------------------------------
@@ -55,13 +58,13 @@ pub use varint::{VarInt32, VarUint32};
 
 pub type Float32 = f32;
 pub type Float64 = f64;
-pub use float128::Float128;
+pub use float128::{Float128, f64_to_ordered_bits, f64_from_ordered_bits};
 
 // -----------------------------------------------------------------------------
 //     Bytes and String types
 // -----------------------------------------------------------------------------
 
-pub use crate::bytestream::Bytes;
+pub use crate::bytestream::{Bytes, HexDisplay};
 pub type String = std::string::String;
 
 
@@ -78,7 +81,9 @@ pub use crate::types::time::{TimePoint, TimePointSec, BlockTimestamp};
 
 macro_rules! impl_checksum {
     ($typ:ident, $size:literal) => {
-        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        /// Checksums are ordered as big-endian unsigned integers, which is the same
+        /// as the lexicographic order of their bytes.
+        #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
         pub struct $typ(pub [u8; $size]);
 
         impl $typ {
@@ -89,6 +94,53 @@ macro_rules! impl_checksum {
             pub fn to_hex(&self) -> String {
                 hex::encode(self.0)
             }
+
+            pub fn as_bytes(&self) -> &[u8; $size] {
+                &self.0
+            }
+
+            /// Return the bytes of this checksum in big-endian order, ie: as they
+            /// are serialized and shown in its hex representation.
+            pub fn to_be_bytes(&self) -> [u8; $size] {
+                self.0
+            }
+
+            /// Return the bytes of this checksum in little-endian order, ie: reversed
+            /// from their serialized order.
+            pub fn to_le_bytes(&self) -> [u8; $size] {
+                let mut result = self.0;
+                result.reverse();
+                result
+            }
+
+            pub fn from_be_bytes(bytes: [u8; $size]) -> Self {
+                Self(bytes)
+            }
+
+            pub fn from_le_bytes(mut bytes: [u8; $size]) -> Self {
+                bytes.reverse();
+                Self(bytes)
+            }
+
+            /// Return the value of bit `n` of this checksum seen as a big-endian
+            /// unsigned integer, where bit 0 is the least significant one.
+            ///
+            /// Panics if `n` is out of range.
+            pub fn bit(&self, n: usize) -> bool {
+                assert!(n < 8 * $size, "bit index out of range: {n}");
+                self.0[$size - 1 - n / 8] & (1 << (n % 8)) != 0
+            }
+
+            /// Return this checksum with bit `n` set to `value`, see [`Self::bit()`].
+            ///
+            /// Panics if `n` is out of range.
+            pub fn with_bit(mut self, n: usize, value: bool) -> Self {
+                assert!(n < 8 * $size, "bit index out of range: {n}");
+                let mask = 1 << (n % 8);
+                let byte = &mut self.0[$size - 1 - n / 8];
+                if value { *byte |= mask; } else { *byte &= !mask; }
+                self
+            }
         }
 
         impl From<[u8; $size]> for $typ {
@@ -97,13 +149,7 @@ macro_rules! impl_checksum {
             }
         }
 
-        impl TryFrom<&str> for $typ {
-            type Error = FromHexError;
-
-            fn try_from(s: &str) -> Result<Self, Self::Error> {
-                Self::from_hex(s)
-            }
-        }
+        impl_try_from_str!($typ);
 
         impl AsRef<[u8]> for $typ {
             fn as_ref(&self) -> &[u8] {
@@ -130,7 +176,10 @@ macro_rules! impl_checksum {
             where
                 S: Serializer
             {
-                self.to_hex().serialize(serializer)
+                match serializer.is_human_readable() {
+                    true => self.to_hex().serialize(serializer),
+                    false => crate::abiserializable::serialize_bin(self, serializer),
+                }
             }
         }
 
@@ -139,8 +188,10 @@ macro_rules! impl_checksum {
             where
                 D: Deserializer<'de>,
             {
-                let hex_repr: &str = <&str>::deserialize(deserializer)?;
-                Self::from_hex(hex_repr).map_err(|e| de::Error::custom(e.to_string()))
+                if !deserializer.is_human_readable() {
+                    return crate::abiserializable::deserialize_bin(deserializer);
+                }
+                crate::abiserializable::deserialize_str(deserializer, "a hex-encoded checksum", |s| Self::from_hex(s))
             }
         }
     }
@@ -150,18 +201,95 @@ impl_checksum!(Checksum160, 20);
 impl_checksum!(Checksum256, 32);
 impl_checksum!(Checksum512, 64);
 
+/// Helpers treating a `Checksum256` as a big-endian `u256`, which is how `nodeos`
+/// orders `i256` secondary indices. These are useful to compute the `lower_bound` /
+/// `upper_bound` values of a table query on such an index.
+///
+/// This does not apply to `sha256` indices: `nodeos` stores those keys as two 128-bit
+/// words with their halves swapped, so their order is not the lexicographic order
+/// of the checksum bytes.
+impl Checksum256 {
+    pub const MIN: Checksum256 = Checksum256([0; 32]);
+    pub const MAX: Checksum256 = Checksum256([0xff; 32]);
+
+    /// Build a checksum from the high and low 128 bits of a `u256`.
+    pub fn from_u128_pair(high: u128, low: u128) -> Self {
+        let mut result = [0u8; 32];
+        result[..16].copy_from_slice(&high.to_be_bytes());
+        result[16..].copy_from_slice(&low.to_be_bytes());
+        Self(result)
+    }
+
+    /// Return the high and low 128 bits of this checksum as a `u256`.
+    pub fn to_u128_pair(&self) -> (u128, u128) {
+        let high = u128::from_be_bytes(self.0[..16].try_into().unwrap());  // safe unwrap
+        let low = u128::from_be_bytes(self.0[16..].try_into().unwrap());  // safe unwrap
+        (high, low)
+    }
+
+    /// Return the next value, or `None` if this is already `Checksum256::MAX`.
+    pub fn checked_increment(&self) -> Option<Self> {
+        let mut result = self.0;
+        for b in result.iter_mut().rev() {
+            let (v, overflow) = b.overflowing_add(1);
+            *b = v;
+            if !overflow { return Some(Self(result)); }
+        }
+        None
+    }
+
+    /// Return the previous value, or `None` if this is already `Checksum256::MIN`.
+    pub fn checked_decrement(&self) -> Option<Self> {
+        let mut result = self.0;
+        for b in result.iter_mut().rev() {
+            let (v, overflow) = b.overflowing_sub(1);
+            *b = v;
+            if !overflow { return Some(Self(result)); }
+        }
+        None
+    }
+
+    /// Return the (inclusive) bounds of the range of all values starting with the given
+    /// bytes, ie: `(prefix000...000, prefixfff...fff)`.
+    ///
+    /// As `nodeos` treats both the `lower_bound` and the `upper_bound` of a table query
+    /// as inclusive, these can be used directly to query all the rows with a given prefix.
+    ///
+    /// Panics if `prefix` is longer than 32 bytes.
+    pub fn prefix_bounds(prefix: &[u8]) -> (Self, Self) {
+        assert!(prefix.len() <= 32, "prefix too long for a checksum256: {} bytes", prefix.len());
+        let (mut lower, mut upper) = (Self::MIN, Self::MAX);
+        lower.0[..prefix.len()].copy_from_slice(prefix);
+        upper.0[..prefix.len()].copy_from_slice(prefix);
+        (lower, upper)
+    }
+
+    /// Return the representation of this checksum to be used as a bound in a
+    /// `get_table_rows` query with `key_type = "i256"`, ie: a hex number prefixed with `0x`.
+    ///
+    /// Note that with `key_type = "sha256"` the checksum should be given as its
+    /// hex representation instead (see [`Checksum256::to_hex()`]).
+    pub fn to_i256_bound(&self) -> String {
+        format!("0x{}", self.to_hex())
+    }
+}
+
 
 pub use crate::types::crypto::{
     CryptoData, CryptoDataType, InvalidCryptoData,
     KeyType, PrivateKey, PublicKey, Signature,
 };
+pub use crate::types::bls::{BlsPublicKey, BlsSignature};
 
 
 // -----------------------------------------------------------------------------
 //     Other builtin Antelope types
 // -----------------------------------------------------------------------------
 
-pub use name::{Name, InvalidName};
+pub use name::{
+    Name, NameStr, NameCache, DEFAULT_NAME_CACHE_CAPACITY, InvalidName,
+    AccountName, ActionName, PermissionName, TableName, ScopeName,
+};
 pub use symbol::{Symbol, InvalidSymbol, SymbolCode};
 pub use asset::{Asset, InvalidAsset, ExtendedAsset};
 
@@ -173,12 +301,6 @@ pub use asset::{Asset, InvalidAsset, ExtendedAsset};
 pub use antelopevalue::{AntelopeType, AntelopeValue, InvalidValue};
 
 // from https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/types.hpp
-pub type ActionName = Name;
-pub type ScopeName = Name;
-pub type AccountName = Name;
-pub type PermissionName = Name;
-pub type TableName = Name;
-
 pub type ChainId = Checksum256;
 pub type BlockId = Checksum256;
 pub type Checksum = Checksum256;
@@ -193,3 +315,110 @@ pub type MicroSeconds = i64;
 /// Extensions are prefixed with type and are a buffer that can be
 /// interpreted by code that is aware and ignored by unaware code.
 pub type Extensions = Vec<(u16, Bytes)>;
+
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use super::*;
+
+    fn check_try_from_str<T>(s: &str)
+    where
+        T: for<'a> TryFrom<&'a str> + TryFrom<String> + FromStr + PartialEq + Debug,
+        for<'a> <T as TryFrom<&'a str>>::Error: Debug,
+        <T as TryFrom<String>>::Error: Debug,
+        <T as FromStr>::Err: Debug,
+    {
+        let expected: T = s.parse().unwrap();
+        assert_eq!(T::try_from(s).unwrap(), expected);
+        assert_eq!(T::try_from(s.to_string()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        check_try_from_str::<Name>("eosio.token");
+        check_try_from_str::<AccountName>("alice");
+        check_try_from_str::<PermissionName>("active");
+        check_try_from_str::<SymbolCode>("EOS");
+        check_try_from_str::<Symbol>("4,EOS");
+        check_try_from_str::<Asset>("1.0000 EOS");
+        check_try_from_str::<PublicKey>("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV");
+        check_try_from_str::<PrivateKey>("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3");
+        check_try_from_str::<Signature>("SIG_K1_JvyUh5EJU7xS3QJSszNKdxGTkQNoo1PUcaQUAjpGTa64Sihf7R6tyiiAjoiZVkoDcfFpEokJPMVqyKYUFmgSvW1MvcRhrM");
+        check_try_from_str::<Checksum160>(&"ab".repeat(20));
+        check_try_from_str::<Checksum256>(&"ab".repeat(32));
+        check_try_from_str::<Checksum512>(&"ab".repeat(64));
+        check_try_from_str::<TimePoint>("2018-06-27T20:33:54.000");
+        check_try_from_str::<TimePointSec>("2018-06-27T20:33:54");
+        check_try_from_str::<BlockTimestamp>("2018-06-27T20:33:54.000");
+
+        let name: Result<Name, _> = "Invalid".to_string().try_into();
+        assert!(name.is_err());
+    }
+
+    #[test]
+    fn test_checksum256_as_u256() {
+        let one = Checksum256::from_u128_pair(0, 1);
+        let big = Checksum256::from_u128_pair(1, 0);
+        assert!(Checksum256::MIN < one && one < big && big < Checksum256::MAX);
+        assert_eq!(big.to_u128_pair(), (1, 0));
+
+        assert_eq!(Checksum256::MIN.checked_increment(), Some(one.clone()));
+        assert_eq!(Checksum256::from_u128_pair(0, u128::MAX).checked_increment(), Some(big.clone()));
+        assert_eq!(big.checked_decrement(), Some(Checksum256::from_u128_pair(0, u128::MAX)));
+        assert_eq!(Checksum256::MAX.checked_increment(), None);
+        assert_eq!(Checksum256::MIN.checked_decrement(), None);
+
+        let (lower, upper) = Checksum256::prefix_bounds(&[0xab, 0xcd]);
+        assert_eq!(lower.to_hex(), format!("abcd{}", "0".repeat(60)));
+        assert_eq!(upper.to_hex(), format!("abcd{}", "f".repeat(60)));
+        assert_eq!(one.to_i256_bound(), format!("0x{}1", "0".repeat(63)));
+    }
+
+    #[test]
+    fn test_checksum_bytes() {
+        let c = Checksum160::from_hex(format!("01{}ff", "00".repeat(18))).unwrap();
+        assert_eq!(c.to_be_bytes()[0], 0x01);
+        assert_eq!(c.to_le_bytes()[0], 0xff);
+        assert_eq!(Checksum160::from_le_bytes(c.to_le_bytes()), c);
+        assert_eq!(Checksum160::from_be_bytes(c.to_be_bytes()), c);
+
+        assert!(c.bit(0) && c.bit(7) && !c.bit(8) && c.bit(152) && !c.bit(159));
+        let one = Checksum256::default().with_bit(0, true);
+        assert_eq!(one, Checksum256::from_u128_pair(0, 1));
+        assert_eq!(one.with_bit(0, false), Checksum256::MIN);
+    }
+
+    #[test]
+    fn test_float128_bytes() {
+        // 1.0 as an IEEE 754 quadruple precision float
+        let bits = 0x3fff_u128 << 112;
+        let x = Float128::from_bits(bits);
+        assert_eq!(x.to_bits(), bits);
+        assert_eq!(x.to_le_bytes()[15], 0x3f);
+        assert_eq!(x.to_be_bytes()[0], 0x3f);
+        assert_eq!(x.to_hex(), format!("{}ff3f", "00".repeat(14)));
+        assert_eq!(Float128::from_be_bytes(x.to_be_bytes()), x);
+        assert_eq!(Float128::from_le_bytes(x.to_le_bytes()), x);
+    }
+
+    #[test]
+    fn test_float_ordered_bits() {
+        let values = [f64::NEG_INFINITY, f64::MIN, -1.5, -f64::MIN_POSITIVE, -0.0,
+                      0.0, f64::MIN_POSITIVE, 1.0, 1.5, f64::MAX, f64::INFINITY];
+        for w in values.windows(2) {
+            assert!(f64_to_ordered_bits(w[0]) < f64_to_ordered_bits(w[1]), "{} < {}", w[0], w[1]);
+        }
+        for x in values {
+            assert_eq!(f64_from_ordered_bits(f64_to_ordered_bits(x)).to_bits(), x.to_bits());
+        }
+        assert_eq!(f64_from_ordered_bits(f64_to_ordered_bits(1.0) + 1), 1.0 + f64::EPSILON);
+
+        let one = Float128::from_bits(0x3fff_u128 << 112);
+        let minus_one = Float128::from_bits(0xbfff_u128 << 112);
+        assert!(minus_one.to_ordered_bits() < Float128::default().to_ordered_bits());
+        assert!(Float128::default().to_ordered_bits() < one.to_ordered_bits());
+        assert_eq!(Float128::from_ordered_bits(minus_one.to_ordered_bits()), minus_one);
+    }
+}
@@ -16,6 +16,7 @@ use tracing::instrument;
 use kudu_macros::with_location;
 
 use crate::{
+    config::{self, SerializationConfig},
     json, JsonError, JsonValue, ByteStream, SerializeError, ABISerializable,
     impl_auto_error_conversion,
 };
@@ -24,7 +25,7 @@ use crate::types::{self,
     VarInt32, VarUint32, Float128, Bytes,
     TimePoint, TimePointSec, BlockTimestamp,
     Asset, ExtendedAsset, InvalidAsset,
-    Name, InvalidName,
+    Name, InvalidName, AccountName,
     Symbol, SymbolCode, InvalidSymbol,
     Checksum160, Checksum256, Checksum512,
     PublicKey, PrivateKey, Signature, InvalidCryptoData,
@@ -138,36 +139,53 @@ impl AntelopeValue {
         })
     }
 
+    /// Return the JSON representation of this value, using the default
+    /// [`SerializationConfig`], see [`config::set_default_serialization()`].
     pub fn to_variant(&self) -> JsonValue {
+        self.to_variant_with(&config::default_serialization())
+    }
+
+    /// Return the JSON representation of this value, using the given [`SerializationConfig`].
+    ///
+    /// ```
+    /// # use kudu::{AntelopeValue, json};
+    /// # use kudu::config::{HexCase, SerializationConfig};
+    /// let config = SerializationConfig { hex_case: HexCase::Upper, quote_u64: true, ..Default::default() };
+    /// assert_eq!(AntelopeValue::Uint64(u64::MAX).to_variant_with(&config), json!("18446744073709551615"));
+    /// assert_eq!(AntelopeValue::Bytes(vec![0xab, 0xcd].into()).to_variant_with(&config), json!("ABCD"));
+    /// ```
+    pub fn to_variant_with(&self, config: &SerializationConfig) -> JsonValue {
         match self {
             Self::Bool(b) => json!(b),
             Self::Int8(n) => json!(n),
             Self::Int16(n) => json!(n),
             Self::Int32(n) => json!(n),
+            Self::Int64(n) if config.quote_u64 => json!(n.to_string()),
             Self::Int64(n) => json!(n),
             Self::Int128(n) => json!(n.to_string()),
             Self::Uint8(n) => json!(n),
             Self::Uint16(n) => json!(n),
             Self::Uint32(n) => json!(n),
+            Self::Uint64(n) if config.quote_u64 => json!(n.to_string()),
             Self::Uint64(n) => json!(n),
             Self::Uint128(n) => json!(n.to_string()),
             Self::VarInt32(n) => json!(i32::from(*n)),
             Self::VarUint32(n) => json!(u32::from(*n)),
             Self::Float32(x) => json!(x),
             Self::Float64(x) => json!(x),
-            Self::Float128(x) => json!(x.to_hex()),
-            Self::Bytes(b) => json!(b.to_hex()),
+            Self::Float128(x) => json!(config.hex(x.to_bin_repr())),
+            Self::Bytes(b) => json!(config.hex(b.as_bytes())),
             Self::String(s) => json!(s),
             Self::TimePoint(t) => t.to_json(),
             Self::TimePointSec(t) => t.to_json(),
             Self::BlockTimestamp(t) => t.to_json(),
-            Self::Checksum160(c) => json!(c.to_hex()),
-            Self::Checksum256(c) => json!(c.to_hex()),
-            Self::Checksum512(c) => json!(c.to_hex()),
+            Self::Checksum160(c) => json!(config.hex(c.as_bytes())),
+            Self::Checksum256(c) => json!(config.hex(c.as_bytes())),
+            Self::Checksum512(c) => json!(config.hex(c.as_bytes())),
             Self::PublicKey(sig) => json!(sig.to_string()),
             Self::PrivateKey(sig) => json!(sig.to_string()),
             Self::Signature(sig) => json!(sig.to_string()),
-            Self::Name(name) => json!(name.to_string()),
+            Self::Name(name) => json!(name),
             Self::SymbolCode(sym) => json!(sym.to_string()),
             Self::Symbol(sym) => json!(sym.to_string()),
             Self::Asset(asset) => json!(asset.to_string()),
@@ -244,7 +262,7 @@ impl AntelopeValue {
                 let qty = variant_to_str(&ea["quantity"])?;
                 Self::ExtendedAsset(Box::new(ExtendedAsset {
                     quantity: qty.parse().context(AssetSnafu { repr: qty })?,
-                    contract: Name::new(ea["contract"].as_str().with_context(incompatible_types)?).context(NameSnafu)?,
+                    contract: AccountName::new(ea["contract"].as_str().with_context(incompatible_types)?).context(NameSnafu)?,
                 }))
             },
         })
@@ -324,7 +342,7 @@ impl AntelopeValue {
             AntelopeType::ExtendedAsset => {
                 Self::ExtendedAsset(Box::new(ExtendedAsset {
                     quantity: Asset::from_bin(stream)?,
-                    contract: Name::from_bin(stream)?
+                    contract: AccountName::from_bin(stream)?
                 }))
             },
         })
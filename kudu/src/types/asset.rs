@@ -1,15 +1,17 @@
 // SPDX-FileCopyrightText: 2023-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::cmp::Ordering;
 use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
-use serde::{de, Serialize, Serializer, Deserialize, Deserializer};
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
 use snafu::{ensure, Snafu, OptionExt, ResultExt};
 
 use kudu_macros::with_location;
-use crate::{InvalidSymbol, Name, Symbol, impl_auto_error_conversion};
+use crate::{AccountName, InvalidSymbol, Symbol, impl_auto_error_conversion};
+use crate::macros::impl_try_from_str;
 
 
 #[with_location]
@@ -24,6 +26,12 @@ pub enum InvalidAsset {
     #[snafu(display("could not parse amount for asset"))]
     ParseAmount { source: ParseIntError },
 
+    #[snafu(display("invalid amount for asset: {amount}"))]
+    InvalidAmount { amount: String },
+
+    #[snafu(display("amount {amount} has more decimals than its symbol {symbol}"))]
+    TooManyDecimals { amount: String, symbol: Symbol },
+
     #[snafu(display("amount overflow for: {amount}"))]
     AmountOverflow { amount: String },
 
@@ -49,7 +57,22 @@ impl_auto_error_conversion!(InvalidSymbol, InvalidAsset, InvalidSymbolSnafu);
 /// assert_eq!(asset.symbol(), "4,CUR".parse::<Symbol>()?);
 /// # Ok::<(), InvalidAsset>(())
 /// ```
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+///
+/// Assets can also be built from a decimal amount and a symbol, from an amount
+/// in the smallest unit of the symbol, or at compile time with the [`asset!`] macro:
+/// ```
+/// # use kudu::{asset, Asset, InvalidAsset};
+/// let asset = Asset::from_decimal("1.5", "4,SYS")?;
+/// assert_eq!(asset, Asset::from_units(15000, "4,SYS".parse()?)?);
+/// assert_eq!(asset, asset!("1.5000 SYS"));
+/// # Ok::<(), InvalidAsset>(())
+/// ```
+///
+/// [`asset!`]: crate::asset
+///
+/// Assets are ordered by symbol first, then by amount. Note that on-chain, only
+/// assets with the same symbol can be compared.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
 pub struct Asset {
     amount: i64,
     symbol: Symbol,
@@ -59,12 +82,111 @@ impl Asset {
     const MAX_AMOUNT: i64 = (1 << 62) - 1;
 
     pub fn new(amount: i64, symbol: Symbol) -> Result<Asset, InvalidAsset> {
-        ensure!((-Self::MAX_AMOUNT..Self::MAX_AMOUNT).contains(&amount), AmountOutOfRangeSnafu);
+        ensure!(Self::is_amount_within_range(amount), AmountOutOfRangeSnafu);
         // no need to check for `symbol.is_valid()` as it has been successfully
         // constructed so must be valid already
         Ok(Asset { amount, symbol })
     }
 
+    /// Build an asset from an amount expressed in the smallest unit of the symbol,
+    /// eg: `12345` units of `4,SYS` is `1.2345 SYS`.
+    pub fn from_units(units: i64, symbol: Symbol) -> Result<Asset, InvalidAsset> {
+        Self::new(units, symbol)
+    }
+
+    /// Build an asset from a decimal amount and a symbol, eg: `("1.5", "4,SYS")`
+    /// is `1.5000 SYS`.
+    ///
+    /// The amount can have fewer decimals than the symbol, but not more as that
+    /// would lose precision.
+    pub fn from_decimal(amount: &str, symbol: &str) -> Result<Asset, InvalidAsset> {
+        let symbol = Symbol::new(symbol).context(InvalidSymbolSnafu)?;
+        let amount = amount.trim();
+
+        let (negative, abs_amount) = match amount.strip_prefix('-') {
+            Some(abs_amount) => (true, abs_amount),
+            None => (false, amount),
+        };
+        let (int_part, frac_part) = match abs_amount.split_once('.') {
+            Some((int_part, frac_part)) => {
+                ensure!(!frac_part.is_empty(), MissingDecimalSnafu);
+                (int_part, frac_part)
+            },
+            None => (abs_amount, ""),
+        };
+        ensure!(!int_part.is_empty() && int_part.bytes().chain(frac_part.bytes()).all(|c| c.is_ascii_digit()),
+                InvalidAmountSnafu { amount });
+        ensure!(frac_part.len() <= symbol.decimals() as usize, TooManyDecimalsSnafu { amount, symbol });
+
+        let width = symbol.decimals() as usize;
+        let units: i64 = format!("{int_part}{frac_part:0<width$}").parse().ok()
+            .with_context(|| AmountOverflowSnafu { amount })?;
+        Self::new(if negative { -units } else { units }, symbol)
+    }
+
+    /// Parse an asset from user input, eg: from the command-line. This is more
+    /// lenient than `FromStr`: surrounding whitespace is ignored, the amount and
+    /// the symbol can be separated by any amount of whitespace (or none at all)
+    /// and the symbol is converted to uppercase, eg: `" 1.5  sys"` is `1.5 SYS`.
+    ///
+    /// Use `FromStr` for consensus data.
+    pub fn parse_lenient(s: &str) -> Result<Asset, InvalidAsset> {
+        let s = s.trim();
+        let pos = s.find(|c: char| c.is_ascii_alphabetic()).context(MissingSpaceSnafu)?;
+        let (amount, symbol) = (s[..pos].trim_end(), s[pos..].to_ascii_uppercase());
+        format!("{amount} {symbol}").parse()
+    }
+
+    /// Build an asset from its string representation (const version).
+    ///
+    /// NOTE: this panics for invalid assets
+    pub const fn constant(s: &str) -> Self {
+        let s = s.as_bytes();
+        let mut pos = 0;
+        let negative = !s.is_empty() && s[0] == b'-';
+        if negative { pos += 1; }
+
+        let mut amount: i64 = 0;
+        let mut digits = 0;
+        let mut decimals: u8 = 0;
+        let mut has_dot = false;
+        while pos < s.len() && s[pos] != b' ' {
+            let c = s[pos];
+            if c == b'.' && !has_dot && digits > 0 {
+                has_dot = true;
+            }
+            else if c.is_ascii_digit() {
+                amount = match amount.checked_mul(10) {
+                    Some(a) => match a.checked_add((c - b'0') as i64) {
+                        Some(a) => a,
+                        None => panic!("Asset amount overflow"),
+                    },
+                    None => panic!("Asset amount overflow"),
+                };
+                digits += 1;
+                if has_dot { decimals += 1; }
+            }
+            else {
+                panic!("Invalid asset amount");
+            }
+            pos += 1;
+        }
+        if digits == 0 { panic!("Invalid asset amount"); }
+        if has_dot && decimals == 0 { panic!("Missing decimal fraction after decimal point"); }
+        if pos == s.len() { panic!("Asset amount and symbol should be separated with space"); }
+        while pos < s.len() && s[pos] == b' ' { pos += 1; }
+
+        let (_, name) = s.split_at(pos);
+        let symbol = Symbol::constant_from_parts(decimals, name);
+        let amount = if negative { -amount } else { amount };
+        if !Self::is_amount_within_range(amount) { panic!("Asset amount out of range, max is 2^62-1"); }
+        Asset { amount, symbol }
+    }
+
+    const fn is_amount_within_range(amount: i64) -> bool {
+        -Self::MAX_AMOUNT <= amount && amount < Self::MAX_AMOUNT
+    }
+
     pub fn amount(&self) -> i64 { self.amount }
     pub fn symbol(&self) -> Symbol { self.symbol }
     pub fn symbol_name(&self) -> String { self.symbol.name() }
@@ -77,10 +199,24 @@ impl Asset {
 
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// Extended assets are ordered by quantity first, then by contract.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ExtendedAsset {
     pub quantity: Asset,
-    pub contract: Name,
+    pub contract: AccountName,
+}
+
+
+impl PartialOrd for Asset {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Asset {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.symbol, self.amount).cmp(&(other.symbol, other.amount))
+    }
 }
 
 
@@ -88,13 +224,7 @@ pub struct ExtendedAsset {
 //     Conversion traits
 // -----------------------------------------------------------------------------
 
-impl TryFrom<&str> for Asset {
-    type Error = InvalidAsset;
-
-    fn try_from(s: &str) -> Result<Asset, InvalidAsset> {
-        Asset::from_str(s)
-    }
-}
+impl_try_from_str!(Asset);
 
 
 // -----------------------------------------------------------------------------
@@ -189,8 +319,7 @@ impl<'de> Deserialize<'de> for Asset {
     where
         D: Deserializer<'de>,
     {
-        let asset: &str = <&str>::deserialize(deserializer)?;
-        Asset::from_str(asset).map_err(|e| de::Error::custom(e.to_string()))
+        crate::abiserializable::deserialize_str(deserializer, "an asset", Asset::from_str)
     }
 }
 
@@ -239,4 +368,59 @@ mod tests {
         assert_eq!(serde_json::from_str::<Asset>(json).unwrap(), obj);
         assert_eq!(serde_json::to_string(&obj).unwrap(), json);
     }
+
+    #[test]
+    fn ordering() {
+        let mut assets: Vec<Asset> = ["2.0000 FOO", "1.0000 FOO", "3.00 BAR", "0.5000 FOO"]
+            .iter().map(|a| a.parse().unwrap()).collect();
+        assets.sort();
+        // assets are grouped by symbol (ordered by their raw value, not alphabetically),
+        // then ordered by amount
+        let sorted: Vec<_> = assets.iter().map(|a| a.to_string()).collect();
+        assert_eq!(sorted, ["0.5000 FOO", "1.0000 FOO", "2.0000 FOO", "3.00 BAR"]);
+
+        let unique: std::collections::HashSet<_> = assets.iter().chain(assets.iter()).collect();
+        assert_eq!(unique.len(), 4);
+    }
+
+    #[test]
+    fn constructors() {
+        let sys: Symbol = "4,SYS".parse().unwrap();
+        let expected: Asset = "1.2345 SYS".parse().unwrap();
+        assert_eq!(Asset::from_decimal("1.2345", "4,SYS").unwrap(), expected);
+        assert_eq!(Asset::from_units(12345, sys).unwrap(), expected);
+        assert_eq!(crate::asset!("1.2345 SYS"), expected);
+
+        // missing decimals are padded with zeros
+        assert_eq!(Asset::from_decimal("12", "4,SYS").unwrap().to_string(), "12.0000 SYS");
+        assert_eq!(Asset::from_decimal("-0.5", "4,SYS").unwrap().to_string(), "-0.5000 SYS");
+        assert_eq!(Asset::from_decimal("7", "0,SYS").unwrap().to_string(), "7 SYS");
+
+        for (amount, symbol) in [("1.23456", "4,SYS"), ("1.5", "0,SYS"), ("1.", "4,SYS"), (".5", "4,SYS"),
+                                 ("1.-5", "4,SYS"), ("", "4,SYS"), ("1", "SYS"), ("1", "4,sys"),
+                                 ("10000000000000000", "4,SYS")] {
+            assert!(Asset::from_decimal(amount, symbol).is_err(), "{amount} {symbol}");
+        }
+        assert!(matches!(Asset::from_decimal("1.23456", "4,SYS"), Err(InvalidAsset::TooManyDecimals { .. })));
+        assert!(Asset::from_units(1 << 62, sys).is_err());
+
+        // the const version agrees with `FromStr`
+        for s in ["0 SYS", "-1.0 A", "99.000000 ABCDEFG", "4611686018427387.902 SYS"] {
+            assert_eq!(Asset::constant(s), s.parse().unwrap());
+        }
+        assert_eq!(Symbol::constant("4,SYS"), sys);
+    }
+
+    #[test]
+    fn lenient_parsing() {
+        let expected: Asset = "1.5000 SYS".parse().unwrap();
+        for s in ["1.5000 sys", "  1.5000\tSys ", "1.5000SYS", "1.5000   SYS"] {
+            assert_eq!(Asset::parse_lenient(s).unwrap(), expected, "{s}");
+        }
+        assert!(Asset::from_str("1.5000 sys").is_err());
+
+        for s in ["", "1.5000", "sys", "1.5000 s y s", "1,5000 SYS"] {
+            assert!(Asset::parse_lenient(s).is_err(), "{s}");
+        }
+    }
 }
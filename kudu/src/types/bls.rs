@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! BLS12-381 keys and signatures, as used by the Savanna finality algorithm.
+//!
+//! These are only containers for the serialized data, they do not implement
+//! any cryptographic operations.
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ripemd::{Digest, Ripemd160};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use snafu::{ensure, ResultExt};
+
+use crate::macros::impl_try_from_str;
+use crate::types::crypto::{
+    Base64Snafu, InvalidCryptoData, InvalidDataSizeSnafu, InvalidHashSnafu, NotCryptoDataSnafu,
+};
+
+// from: https://github.com/AntelopeIO/spring/blob/main/libraries/libfc/include/fc/crypto/bls_public_key.hpp
+//       https://github.com/AntelopeIO/spring/blob/main/libraries/libfc/include/fc/crypto/bls_signature.hpp
+//
+// the string representation is the prefix followed by the base64url encoding of the
+// data (in affine non-montgomery little-endian form) with a 4-bytes ripemd160 checksum
+
+macro_rules! impl_bls_type {
+    ($typ:ident, $size:literal, $prefix:literal, $display_name:literal) => {
+        /// BLS data is ordered as the lexicographic order of its bytes.
+        #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $typ(pub [u8; $size]);
+
+        impl $typ {
+            pub const PREFIX: &'static str = $prefix;
+
+            pub fn new(s: &str) -> Result<Self, InvalidCryptoData> {
+                let enc_data = s.strip_prefix($prefix).ok_or_else(|| NotCryptoDataSnafu {
+                    message: format!("{} needs to start with `{}`: {}", $display_name, $prefix, s)
+                }.build())?;
+                let data = URL_SAFE_NO_PAD.decode(enc_data.trim_end_matches('=')).context(Base64Snafu)?;
+
+                ensure!(data.len() == $size + 4, InvalidDataSizeSnafu {
+                    message: format!("wrong size for {}, needs to be {} but is: {}",
+                                     $display_name, $size + 4, data.len())
+                });
+                let (data, expected) = data.split_at($size);
+                let actual = &checksum(data)[..];
+                ensure!(actual == expected, InvalidHashSnafu {
+                    hash: hex::encode(actual),
+                    expected: hex::encode(expected),
+                });
+
+                Ok(Self(data.try_into().unwrap()))  // safe unwrap
+            }
+
+            pub fn data(&self) -> &[u8; $size] { &self.0 }
+
+            pub fn to_hex(&self) -> String {
+                hex::encode(self.0)
+            }
+        }
+
+        impl From<[u8; $size]> for $typ {
+            fn from(v: [u8; $size]) -> Self {
+                Self(v)
+            }
+        }
+
+        impl Default for $typ {
+            fn default() -> Self {
+                Self([0; $size])
+            }
+        }
+
+        impl fmt::Display for $typ {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut data = self.0.to_vec();
+                data.extend_from_slice(&checksum(&self.0));
+                write!(f, "{}{}", $prefix, URL_SAFE_NO_PAD.encode(data))
+            }
+        }
+
+        impl FromStr for $typ {
+            type Err = InvalidCryptoData;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::new(s)
+            }
+        }
+
+        impl_try_from_str!($typ);
+
+        impl Serialize for $typ {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer
+            {
+                match serializer.is_human_readable() {
+                    true => self.to_string().serialize(serializer),
+                    false => crate::abiserializable::serialize_bin(self, serializer),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $typ {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                if !deserializer.is_human_readable() {
+                    return crate::abiserializable::deserialize_bin(deserializer);
+                }
+                crate::abiserializable::deserialize_str(deserializer, "a BLS key or signature", Self::new)
+            }
+        }
+    }
+}
+
+fn checksum(data: &[u8]) -> [u8; 4] {
+    Ripemd160::digest(data)[..4].try_into().unwrap()  // safe unwrap
+}
+
+impl_bls_type!(BlsPublicKey, 96, "PUB_BLS_", "BLS public key");
+impl_bls_type!(BlsSignature, 192, "SIG_BLS_", "BLS signature");
+
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_bls_string_round_trip() -> Result<()> {
+        let key = BlsPublicKey(std::array::from_fn(|i| i as u8));
+        let repr = key.to_string();
+        assert!(repr.starts_with("PUB_BLS_"));
+        assert_eq!(BlsPublicKey::new(&repr)?, key);
+
+        // corrupting the data invalidates the checksum
+        let mut corrupted = repr.into_bytes();
+        corrupted[10] = if corrupted[10] == b'A' { b'B' } else { b'A' };
+        assert!(BlsPublicKey::new(std::str::from_utf8(&corrupted)?).is_err());
+
+        assert!(BlsSignature::new(&key.to_string()).is_err());
+        let sig = BlsSignature::default();
+        assert_eq!(BlsSignature::new(&sig.to_string())?, sig);
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2023-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::cmp::Ordering;
 use std::fmt;
 use std::marker::PhantomData;
 use std::str::FromStr;
@@ -9,13 +10,15 @@ use bs58;
 use bytemuck::cast_ref;
 use ripemd::{Digest, Ripemd160};
 use secp256k1::{Message, SecretKey};
-use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sha2::Sha256;
 use snafu::{ensure, ResultExt, Snafu};
 use tracing::debug;
 
 use kudu_macros::with_location;
 
+use crate::config;
+
 // NOTE: as for which library to use for computing signatures, they are a few candidates
 //       - k256 + ecdsa: where the Rust crypto world seems to be going, however this doesn't offer
 //         passing a custom nonce when signing, which is required to find an "EOS-canonical" signature
@@ -31,10 +34,14 @@ use kudu_macros::with_location;
 
 #[with_location]
 #[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
 pub enum InvalidCryptoData {
     #[snafu(display("invalid key type index: {index}"))]
     KeyTypeIndex { index: u8 },
 
+    #[snafu(display("unsupported key type: {key_type:?}"))]
+    UnsupportedKeyType { key_type: KeyType },
+
     #[snafu(display("not crypto data: {message}"))]
     NotCryptoData { message: String },
 
@@ -52,10 +59,14 @@ pub enum InvalidCryptoData {
 
     #[snafu(display("error while decoding base58 data"))]
     Base58Error { source: bs58::decode::Error },
+
+    #[snafu(display("error while decoding base64 data"))]
+    Base64Error { source: base64::DecodeError },
 }
 
 
-#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+/// Key types are ordered by their index, as on-chain.
+#[derive(Eq, PartialEq, PartialOrd, Ord, Hash, Debug, Copy, Clone)]
 pub enum KeyType {
     K1,
     R1,
@@ -72,6 +83,14 @@ impl KeyType {
         })
     }
 
+    /// Same as [`KeyType::from_index()`], but fail for WebAuthn, whose keys and
+    /// signatures have a variable size which is not supported yet.
+    pub(crate) fn fixed_size_from_index(i: u8) -> Result<Self, InvalidCryptoData> {
+        let key_type = Self::from_index(i)?;
+        ensure!(key_type != Self::WebAuthn, UnsupportedKeyTypeSnafu { key_type });
+        Ok(key_type)
+    }
+
     pub fn index(&self) -> u8 {
         match self {
             Self::K1 => 0,
@@ -95,6 +114,9 @@ pub trait CryptoDataType {
     // const DATA_SIZE: usize;
 }
 
+/// Crypto data is ordered by key type first, then by the lexicographic order of
+/// its bytes. This matches the on-chain ordering of keys, which is for instance
+/// required for the keys of an [`Authority`](crate::Authority) to be valid.
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub struct CryptoData<T: CryptoDataType, const DATA_SIZE: usize> {
     key_type: KeyType,
@@ -112,10 +134,11 @@ impl<T: CryptoDataType, const DATA_SIZE: usize> CryptoData<T, DATA_SIZE> {
 
     pub fn new(s: &str) -> Result<Self, InvalidCryptoData> {
         // check legacy formats first
-        if T::PREFIX == "PUB" && s.starts_with("EOS") {
+        let legacy_pub = if T::PREFIX == "PUB" { strip_legacy_prefix(s) } else { None };
+        if let Some(enc_data) = legacy_pub {
             // legacy format public key
             let key_type = KeyType::K1;
-            let data = string_to_key_data(&s[3..], None)?;
+            let data = string_to_key_data(enc_data, None)?;
             Ok(Self { key_type, data: Self::vec_to_data(data)?, phantom: PhantomData })
         }
         else if T::PREFIX == "PVT" && !s.contains('_') {
@@ -137,13 +160,21 @@ impl<T: CryptoDataType, const DATA_SIZE: usize> CryptoData<T, DATA_SIZE> {
             // unimplemented!()
         }
         else if s.starts_with(&format!("{}_WA_", T::PREFIX)) {
-            unimplemented!()
+            UnsupportedKeyTypeSnafu { key_type: KeyType::WebAuthn }.fail()
         }
         else {
             NotCryptoDataSnafu { message: s.to_owned() }.fail()
         }
     }
 
+    /// Parse a key or signature from user input, ignoring surrounding whitespace,
+    /// eg: when pasted from another program.
+    ///
+    /// Use [`CryptoData::new()`] or `FromStr` for consensus data.
+    pub fn parse_lenient(s: &str) -> Result<Self, InvalidCryptoData> {
+        Self::new(s.trim())
+    }
+
     pub fn vec_to_data(v: Vec<u8>) -> Result<[u8; DATA_SIZE], InvalidCryptoData> {
         let input_len = v.len();
         let result = v.try_into();
@@ -159,6 +190,19 @@ impl<T: CryptoDataType, const DATA_SIZE: usize> CryptoData<T, DATA_SIZE> {
 }
 
 
+impl<T: CryptoDataType + Eq, const DATA_SIZE: usize> PartialOrd for CryptoData<T, DATA_SIZE> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: CryptoDataType + Eq, const DATA_SIZE: usize> Ord for CryptoData<T, DATA_SIZE> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.key_type, &self.data).cmp(&(other.key_type, &other.data))
+    }
+}
+
+
 // -----------------------------------------------------------------------------
 //     `TryFrom` implementation
 // -----------------------------------------------------------------------------
@@ -171,6 +215,14 @@ impl<T: CryptoDataType, const DATA_SIZE: usize> TryFrom<&str> for CryptoData<T,
     }
 }
 
+impl<T: CryptoDataType, const DATA_SIZE: usize> TryFrom<String> for CryptoData<T, DATA_SIZE> {
+    type Error = InvalidCryptoData;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::new(&s)
+    }
+}
+
 
 // -----------------------------------------------------------------------------
 //     `Display` implementation
@@ -205,7 +257,10 @@ impl<T: CryptoDataType, const DATA_SIZE: usize> Serialize for CryptoData<T, DATA
     where
         S: Serializer
     {
-        self.to_string().serialize(serializer)
+        match serializer.is_human_readable() {
+            true => self.to_string().serialize(serializer),
+            false => crate::abiserializable::serialize_bin(self, serializer),
+        }
     }
 }
 
@@ -214,8 +269,10 @@ impl<'de, T: CryptoDataType, const DATA_SIZE: usize> Deserialize<'de> for Crypto
     where
         D: Deserializer<'de>,
     {
-        let repr: &str = <&str>::deserialize(deserializer)?;
-        Self::new(repr).map_err(|e| de::Error::custom(e.to_string()))
+        if !deserializer.is_human_readable() {
+            return crate::abiserializable::deserialize_bin(deserializer);
+        }
+        crate::abiserializable::deserialize_str(deserializer, "a key or signature", Self::new)
     }
 }
 
@@ -279,6 +336,16 @@ fn string_to_key_data(enc_data: &str, prefix: Option<&str>) -> Result<Vec<u8>, I
     Ok(data[..data.len() - 4].to_owned())
 }
 
+/// Return the encoded data of a public key in the legacy format, ie: after the
+/// prefix set in [`config::legacy_key_prefix()`] or the default `EOS` prefix.
+fn strip_legacy_prefix(s: &str) -> Option<&str> {
+    if s.starts_with("PUB_") {
+        return None;
+    }
+    let prefix = config::legacy_key_prefix();
+    s.strip_prefix(prefix.as_str()).or_else(|| s.strip_prefix(config::DEFAULT_LEGACY_KEY_PREFIX))
+}
+
 fn from_wif(enc_data: &str) -> Result<Vec<u8>, InvalidCryptoData> {
     let data = bs58::decode(enc_data).into_vec().context(Base58Snafu)?;
 
@@ -322,15 +389,55 @@ fn key_data_to_string<const N: usize>(k: &[u8; N], prefix: &str) -> String {
 // - https://github.com/EOSIO/eos/issues/6699
 // - https://github.com/AntelopeIO/spring/issues/1106
 // - https://github.com/steemit/steem/issues/1944
+
+/// Half of the order of the secp256k1 curve, signatures with a higher `s` value
+/// have an equivalent signature with a lower one and are malleable.
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
 impl Signature {
-    /// Return whether this signature is an EOS-canonical signature
+    /// Return whether this signature is an EOS-canonical signature, ie: it has a low
+    /// `s` value and both its `r` and `s` values are exactly 32 bytes long when
+    /// DER-encoded. Nodeos refuses signatures which are not canonical.
     pub fn is_canonical(&self) -> bool {
         let s1 = (self.data[1] & 0x80) == 0;
         let s2 = self.data[1] != 0 || (self.data[2] & 0x80 != 0);
         let s3 = self.data[33] & 0x80 == 0;
         let s4 = self.data[33] != 0 || (self.data[34] & 0x80 != 0);
 
-        s1 && s2 && s3 && s4
+        s1 && s2 && s3 && s4 && self.is_low_s()
+    }
+
+    /// Return whether the `s` value of this signature is in the lower half of the
+    /// curve order. This is always true for signatures that are not K1 signatures.
+    pub fn is_low_s(&self) -> bool {
+        self.key_type != KeyType::K1 || self.data[33..] <= SECP256K1_HALF_ORDER[..]
+    }
+
+    /// Return the equivalent signature with a low `s` value, which is valid for the
+    /// same digest and recovers to the same public key.
+    ///
+    /// Note that this only fixes the `s` value, a signature with an `r` value which
+    /// is not canonical needs to be computed again with another nonce.
+    pub fn normalize_s(&self) -> Signature {
+        if self.is_low_s() {
+            return self.clone();
+        }
+        // `s` is lower than the curve order for valid signatures, so we can use
+        // `SecretKey` to compute `n - s`
+        let Ok(s) = SecretKey::from_byte_array(self.data[33..].try_into().unwrap()) else {  // safe unwrap
+            return self.clone();
+        };
+        let mut data = self.data;
+        data[33..].copy_from_slice(&s.negate().secret_bytes());
+
+        // negating `s` flips the parity of the recovery id
+        let recid = data[0].wrapping_sub(27) & 3;
+        data[0] = data[0] - recid + (recid ^ 1);
+
+        Signature::with_key_type(self.key_type, data)
     }
 }
 
@@ -350,7 +457,8 @@ impl TryFrom<&Signature> for secp256k1::ecdsa::RecoverableSignature {
     type Error = InvalidCryptoData;
 
     fn try_from(value: &Signature) -> Result<Self, Self::Error> {
-        let recid = secp256k1::ecdsa::RecoveryId::from_u8_masked(value.data[0]);
+        // first byte is `27 + 4 + recid` (the `4` denoting a compressed public key)
+        let recid = secp256k1::ecdsa::RecoveryId::from_u8_masked(value.data[0].wrapping_sub(27));
         Self::from_compact(&value.data[1..], recid).map_err(|e| {
             InvalidSignatureSnafu { message: e.to_string() }.build()
         })
@@ -374,7 +482,8 @@ impl PrivateKey {
             let message = Message::from_digest(digest.0);
 
             // iterate over a nonce to be added to the signatures until we find a good one
-            // (i.e.: EOS-canonical)
+            // (i.e.: EOS-canonical). libsecp256k1 always returns low-S signatures so only
+            // the length of `r` and `s` needs to be checked here
 
             let secp_sig = secp.sign_ecdsa_recoverable(message, &secret_key);
 
@@ -432,8 +541,28 @@ impl PublicKey {
         secp.verify_ecdsa(message, &sig, &public_key).is_ok()
     }
 
+    /// Recover the public key that produced the given signature over `digest`.
+    pub fn recover_from_digest(digest: &crate::Digest, signature: &Signature) -> Result<Self, InvalidCryptoData> {
+        ensure!(signature.key_type == KeyType::K1,
+                InvalidSignatureSnafu { message: "can only recover public keys from K1 signatures" });
+        let secp = secp256k1::global::SECP256K1;
+        let message = Message::from_digest(digest.0);
+        let sig = secp256k1::ecdsa::RecoverableSignature::try_from(signature)?;
+        let public_key = secp.recover_ecdsa(message, &sig).map_err(|e| {
+            InvalidSignatureSnafu { message: e.to_string() }.build()
+        })?;
+        Ok(public_key.into())
+    }
+
+    /// Return this key in the legacy format, using the prefix set in
+    /// [`config::legacy_key_prefix()`] (`EOS` by default).
     pub fn to_old_format(&self) -> String {
-        format!("EOS{}", &key_data_to_string(&self.data, "")[1..])
+        self.to_old_format_with_prefix(&config::legacy_key_prefix())
+    }
+
+    /// Return this key in the legacy format, using the given prefix.
+    pub fn to_old_format_with_prefix(&self, prefix: &str) -> String {
+        format!("{prefix}{}", &key_data_to_string(&self.data, "")[1..])
     }
 }
 
@@ -481,6 +610,11 @@ mod tests {
 
         assert_eq!(pub_key.to_string(), "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63");
         assert_eq!(pub_key.to_old_format(), "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV");
+        assert_eq!(pub_key.to_old_format_with_prefix("FIO"), "FIO6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV");
+
+        let pasted = format!(" {pub_key}\n");
+        assert_eq!(PublicKey::parse_lenient(&pasted)?, pub_key);
+        assert!(PublicKey::new(&pasted).is_err());
         Ok(())
     }
 
@@ -495,6 +629,37 @@ mod tests {
         let public_key = PublicKey::from_private_key(&key);
         assert!(public_key.verify_signature(input, &sig));
 
+        let digest: [u8; 32] = Sha256::digest(input).into();
+        assert_eq!(PublicKey::recover_from_digest(&digest.into(), &sig)?, public_key);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_signatures() -> Result<()> {
+        let key = PrivateKey::eosio_dev();
+        let public_key = PublicKey::from_private_key(&key);
+
+        for i in 0..100u32 {
+            let digest: [u8; 32] = Sha256::digest(i.to_le_bytes()).into();
+            let sig = key.sign_digest(digest.into());
+            assert!(sig.is_canonical() && sig.is_low_s());
+            assert_eq!(sig.normalize_s(), sig);
+
+            // build the malleated signature (r, n - s), which is valid but not canonical
+            let s = SecretKey::from_byte_array(sig.data[33..].try_into()?)?;
+            let mut data = sig.data;
+            data[33..].copy_from_slice(&s.negate().secret_bytes());
+            let recid = data[0] - 31;
+            data[0] = 31 + (recid ^ 1);
+            let high_s = Signature::with_key_type(KeyType::K1, data);
+
+            assert!(!high_s.is_low_s());
+            assert!(!high_s.is_canonical());
+            assert_eq!(PublicKey::recover_from_digest(&digest.into(), &high_s)?, public_key);
+            assert_eq!(high_s.normalize_s(), sig);
+        }
+
         Ok(())
     }
 
@@ -14,6 +14,94 @@ type Result<T, E = ConversionError> = std::result::Result<T, E>;
 
 pub use float128_impl::Float128;
 
+/// Explicit accessors to the binary representation of a `Float128`, which do not
+/// depend on whether the `float128` feature is enabled.
+///
+/// The binary representation is the IEEE 754 quadruple precision format, and it is
+/// serialized in little-endian byte order, as done by `nodeos`.
+impl Float128 {
+    /// Return the bytes of this float in little-endian order, ie: its serialized form.
+    pub fn to_le_bytes(&self) -> [u8; 16] {
+        self.to_bin_repr()
+    }
+
+    /// Return the bytes of this float in big-endian order.
+    pub fn to_be_bytes(&self) -> [u8; 16] {
+        self.to_bits().to_be_bytes()
+    }
+
+    pub fn from_le_bytes(bytes: [u8; 16]) -> Self {
+        Self::from_bin_repr(&bytes)
+    }
+
+    pub fn from_be_bytes(bytes: [u8; 16]) -> Self {
+        Self::from_bits(u128::from_be_bytes(bytes))
+    }
+
+    /// Return the raw IEEE 754 bits of this float, with the sign bit as the most
+    /// significant bit, same as [`f64::to_bits()`].
+    pub fn to_bits(&self) -> u128 {
+        u128::from_le_bytes(self.to_bin_repr())
+    }
+
+    pub fn from_bits(bits: u128) -> Self {
+        Self::from_bin_repr(&bits.to_le_bytes())
+    }
+
+    /// Return an unsigned integer which sorts in the same order as the floats do,
+    /// see [`f64_to_ordered_bits()`].
+    pub fn to_ordered_bits(&self) -> u128 {
+        ordered_bits!(self.to_bits(), u128)
+    }
+
+    pub fn from_ordered_bits(bits: u128) -> Self {
+        Self::from_bits(unordered_bits!(bits, u128))
+    }
+}
+
+
+// Floats are stored as sign-magnitude, so we flip the sign bit of positive numbers
+// to make them sort after negative ones, and flip all the bits of negative numbers
+// to reverse the order of their magnitudes.
+macro_rules! ordered_bits {
+    ($bits:expr, $typ:ty) => {{
+        let bits: $typ = $bits;
+        let sign = 1 << (<$typ>::BITS - 1);
+        if bits & sign == 0 { bits | sign } else { !bits }
+    }}
+}
+
+macro_rules! unordered_bits {
+    ($bits:expr, $typ:ty) => {{
+        let bits: $typ = $bits;
+        let sign = 1 << (<$typ>::BITS - 1);
+        if bits & sign != 0 { bits & !sign } else { !bits }
+    }}
+}
+
+use {ordered_bits, unordered_bits};
+
+/// Return an unsigned integer which sorts in the same order as the given float,
+/// ie: `x < y` implies `f64_to_ordered_bits(x) < f64_to_ordered_bits(y)`.
+///
+/// This is the order used by `nodeos` for `float64` (and `float128`) secondary
+/// indices, and it can be used to iterate over the float keys of a table, eg: to
+/// compute the value immediately following a bound (as bounds are inclusive in
+/// table queries).
+///
+/// Note that `-0.0` sorts just before `0.0` here while they compare equal in `nodeos`,
+/// and that NaNs (which cannot be stored in a secondary index) sort before `-inf`
+/// or after `inf` depending on their sign.
+pub fn f64_to_ordered_bits(x: f64) -> u64 {
+    ordered_bits!(x.to_bits(), u64)
+}
+
+/// Return the float corresponding to the given ordered bits, inverse of
+/// [`f64_to_ordered_bits()`].
+pub fn f64_from_ordered_bits(bits: u64) -> f64 {
+    f64::from_bits(unordered_bits!(bits, u64))
+}
+
 #[cfg(not(feature = "float128"))]
 mod float128_impl {
     use super::*;
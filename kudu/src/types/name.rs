@@ -1,14 +1,22 @@
 // SPDX-FileCopyrightText: 2023-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+#![deny(clippy::unwrap_used)]
+
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::ops::Deref;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use snafu::{Snafu, ensure};
 
 use kudu_macros::with_location;
 
+use crate::abi::CacheStats;
+use crate::macros::impl_try_from_str;
+
 
 #[with_location]
 #[derive(Debug, Snafu)]
@@ -21,9 +29,27 @@ pub enum InvalidName {
         given: String,
         normalized: String,
     },
+
+    #[snafu(display(r#"{kind} is longer than {max_len} characters: "{name}""#))]
+    TooLongFor { name: String, kind: &'static str, max_len: usize },
 }
 
 /// Represent an immutable name in the Antelope data model and is encoded as a `uint64`.
+///
+/// In human-readable serde formats, a `Name` is represented by its string form,
+/// also when used as a map key, so maps keyed by names (eg: table scopes) serialize
+/// to and from JSON objects. The same holds for the specialized name types such as
+/// [`AccountName`].
+///
+/// ## Example
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use kudu::AccountName;
+/// let balances: BTreeMap<AccountName, u64> = serde_json::from_str(r#"{"alice": 3, "bob": 5}"#)?;
+/// assert_eq!(balances[&AccountName::new("bob")?], 5);
+/// assert_eq!(serde_json::to_string(&balances)?, r#"{"alice":3,"bob":5}"#);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
 #[derive(Eq, Hash, PartialEq, Ord, PartialOrd, Copy, Clone, Default)]
 pub struct Name {
     value: u64,
@@ -86,10 +112,200 @@ impl Name {
     /// assert_eq!(Name::new("eosio")?.prefix(), Name::new("eosio")?);
     /// # Ok::<(), InvalidName>(())
     /// ```
+    #[allow(clippy::unwrap_used)]
     pub fn prefix(&self) -> Name {
         // note: antelope C++ has a more efficient implementation based on direct bit twiddling,
         //       but we're going for a simpler implementation here
-        Name::new(self.to_string().rsplitn(2, '.').last().unwrap()).unwrap()  // both unwrap are safe here
+        Name::new(self.to_name_str().rsplitn(2, '.').last().unwrap()).unwrap()  // both unwrap are safe here
+    }
+
+    /// Return the string representation of this name, without allocating.
+    ///
+    /// ## Example
+    /// ```
+    /// # use kudu::{Name, InvalidName};
+    /// let name = Name::new("eosio.token")?;
+    /// assert_eq!(name.to_name_str().as_str(), "eosio.token");
+    /// assert_eq!(name.to_name_str().len(), 11);  // `NameStr` derefs to `str`
+    /// # Ok::<(), InvalidName>(())
+    /// ```
+    #[inline]
+    pub fn to_name_str(&self) -> NameStr {
+        let mut buf = [0u8; 13];
+        let len = u64_to_buf(self.value, &mut buf) as u8;
+        NameStr { buf, len }
+    }
+
+    /// Return the string representation of this name, shared with all the other
+    /// calls for the same name through the global [`NameCache`].
+    ///
+    /// This is useful when holding a lot of names as strings, eg: in an indexer,
+    /// as the most frequent ones will only be allocated once.
+    ///
+    /// ## Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use kudu::{Name, InvalidName};
+    /// let name = Name::new("eosio.token")?;
+    /// assert_eq!(&*name.as_display_cache(), "eosio.token");
+    /// assert!(Arc::ptr_eq(&name.as_display_cache(), &name.as_display_cache()));
+    /// # Ok::<(), InvalidName>(())
+    /// ```
+    pub fn as_display_cache(&self) -> Arc<str> {
+        NameCache::global().get(*self)
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     `NameStr`: inline string representation
+// -----------------------------------------------------------------------------
+
+/// String representation of a [`Name`] stored inline, as returned by [`Name::to_name_str()`].
+///
+/// It derefs to `str` and can be used wherever a `&str` is expected.
+#[derive(Clone, Copy)]
+pub struct NameStr {
+    buf: [u8; 13],
+    len: u8,
+}
+
+impl NameStr {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // all chars in `CHARMAP` are ASCII, so this is always valid UTF-8
+        unsafe { str::from_utf8_unchecked(&self.buf[..self.len as usize]) }
+    }
+}
+
+impl Deref for NameStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for NameStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for NameStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for NameStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq for NameStr {
+    fn eq(&self, other: &NameStr) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for NameStr {}
+
+impl PartialEq<&str> for NameStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     `NameCache`: interning of the string representation of names
+// -----------------------------------------------------------------------------
+
+/// Default capacity of the global [`NameCache`], used by [`Name::as_display_cache()`].
+pub const DEFAULT_NAME_CACHE_CAPACITY: usize = 8192;
+
+#[derive(Default)]
+struct NameCacheInner {
+    entries: HashMap<Name, Arc<str>>,
+    order: VecDeque<Name>,
+    stats: CacheStats,
+}
+
+/// Bounded, thread-safe cache of the string representation of names.
+///
+/// Looking up a name which is in the cache returns a shared `Arc<str>` without
+/// allocating. Once the cache is full, the oldest entries are evicted in FIFO order.
+pub struct NameCache {
+    inner: Mutex<NameCacheInner>,
+}
+
+impl NameCache {
+    pub fn new(capacity: usize) -> Self {
+        let mut inner = NameCacheInner::default();
+        inner.stats.capacity = capacity;
+        NameCache { inner: Mutex::new(inner) }
+    }
+
+    /// Return the global cache used by [`Name::as_display_cache()`].
+    pub fn global() -> &'static NameCache {
+        static GLOBAL_NAME_CACHE: OnceLock<NameCache> = OnceLock::new();
+        GLOBAL_NAME_CACHE.get_or_init(|| NameCache::new(DEFAULT_NAME_CACHE_CAPACITY))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, NameCacheInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Return the string representation of `name`, inserting it in the cache if needed.
+    pub fn get(&self, name: impl Into<Name>) -> Arc<str> {
+        let name = name.into();
+        let mut inner = self.lock();
+        let found = inner.entries.get(&name).cloned();
+        #[cfg(feature = "metrics")]
+        crate::metrics::cache_lookup("name", found.is_some());
+        if let Some(s) = found {
+            inner.stats.hits += 1;
+            return s;
+        }
+        inner.stats.misses += 1;
+
+        let s: Arc<str> = Arc::from(name.to_name_str().as_str());
+        if inner.stats.capacity == 0 { return s; }
+
+        if inner.stats.entries == inner.stats.capacity {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.entries.remove(&oldest);
+                inner.stats.entries -= 1;
+                inner.stats.evictions += 1;
+            }
+        }
+        inner.entries.insert(name, s.clone());
+        inner.order.push_back(name);
+        inner.stats.entries += 1;
+        s
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Remove all entries from the cache and reset its statistics.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        let capacity = inner.stats.capacity;
+        *inner = NameCacheInner::default();
+        inner.stats.capacity = capacity;
+    }
+}
+
+impl fmt::Debug for NameCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NameCache")
+            .field("stats", &self.stats())
+            .finish()
     }
 }
 
@@ -130,11 +346,10 @@ const CHARMAP: &[u8] = b".12345abcdefghijklmnopqrstuvwxyz";
 
 
 fn _u64_to_bytes(n: u64) -> Vec<u8> {
-    let mut s: Vec<u8> = vec![b'.'; 13];
-    let end_pos = u64_to_buf(n, (&mut s[..]).try_into().unwrap());
+    let mut s = [b'.'; 13];
+    let end_pos = u64_to_buf(n, &mut s);
     // truncate string with unused trailing symbols
-    s.truncate(end_pos);
-    s
+    s[..end_pos].to_vec()
 }
 
 fn u64_to_buf(n: u64, s: &mut [u8; 13]) -> usize {
@@ -190,7 +405,8 @@ const fn is_normalized(s: &[u8], encoded: u64) -> bool {
 }
 
 fn _u64_to_string(n: u64) -> String {
-    String::from_utf8(_u64_to_bytes(n)).unwrap()  // safe unwrap
+    // all chars in `CHARMAP` are ASCII, so we can convert them directly
+    _u64_to_bytes(n).into_iter().map(char::from).collect()
 }
 
 
@@ -198,13 +414,7 @@ fn _u64_to_string(n: u64) -> String {
 //     Conversion traits
 // -----------------------------------------------------------------------------
 
-impl TryFrom<&str> for Name {
-    type Error = InvalidName;
-
-    fn try_from(s: &str) -> Result<Name, InvalidName> {
-        Name::new(s)
-    }
-}
+impl_try_from_str!(Name);
 
 impl From<u64> for Name {
     fn from(n: u64) -> Name {
@@ -219,9 +429,7 @@ impl From<u64> for Name {
 
 impl fmt::Debug for Name {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut buf: [u8; 13] = [0; 13];  // TODO: use MaybeUninit?
-        let n = u64_to_buf(self.value, &mut buf);
-        f.write_str(unsafe { str::from_utf8_unchecked(&buf[..n]) })
+        f.write_str(&self.to_name_str())
     }
 }
 
@@ -232,9 +440,7 @@ impl fmt::Debug for Name {
 
 impl fmt::Display for Name {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut buf: [u8; 13] = [0; 13];  // TODO: use MaybeUninit?
-        let n = u64_to_buf(self.value, &mut buf);
-        f.write_str(unsafe { str::from_utf8_unchecked(&buf[..n]) })
+        f.write_str(&self.to_name_str())
     }
 }
 
@@ -260,7 +466,30 @@ impl Serialize for Name {
     where
         S: Serializer,
     {
-        self.to_string().serialize(serializer)
+        // non human-readable formats store the `u64` value directly
+        match serializer.is_human_readable() {
+            true => serializer.serialize_str(&self.to_name_str()),
+            false => self.value.serialize(serializer),
+        }
+    }
+}
+
+struct NameVisitor;
+
+impl de::Visitor<'_> for NameVisitor {
+    type Value = Name;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an Antelope name")
+    }
+
+    // map keys are often borrowed from the input, parse them without allocating
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Name, E> {
+        Name::new(v).map_err(|e| E::custom(e.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Name, E> {
+        Ok(Name::from_u64(v))
     }
 }
 
@@ -269,8 +498,10 @@ impl<'de> Deserialize<'de> for Name {
     where
         D: Deserializer<'de>,
     {
-        let name: &str = <&str>::deserialize(deserializer)?;
-        Name::new(name).map_err(|e| de::Error::custom(e.to_string()))
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_str(NameVisitor),
+            false => deserializer.deserialize_u64(NameVisitor),
+        }
     }
 }
 
@@ -281,12 +512,9 @@ impl<'de> Deserialize<'de> for Name {
 
 // TODO: could we group all those impls with a single PartialEq<Deref<str>> or something similar?
 
-// TODO: this is not optimized!!
 impl PartialEq<&str> for Name {
     fn eq(&self, other: &&str) -> bool {
-        let mut s = [0u8; 13];
-        let end_pos = u64_to_buf(self.as_u64(), &mut s);
-        s[..end_pos] == *other.as_bytes()
+        self.to_name_str() == *other
     }
 }
 
@@ -297,6 +525,196 @@ impl PartialEq<String> for Name {
 }
 
 
+// -----------------------------------------------------------------------------
+//     Specialized name types
+// -----------------------------------------------------------------------------
+
+/// Return whether the name fits in `max_len` characters, ie: all the
+/// characters after the first `max_len` ones are `.`
+const fn fits_in(value: u64, max_len: usize) -> bool {
+    max_len >= 13 || value & ((1u64 << (64 - 5 * max_len)) - 1) == 0
+}
+
+/// Define a newtype around `Name` checking its specific rules when built from
+/// user input, with cheap conversion to and from a plain `Name`.
+///
+/// NOTE: as for `Name`, building one from its `u64` representation or decoding
+///       it (from binary data or serde) does not do any validation, so that data
+///       coming from the chain or other protocols (eg: the placeholder names used
+///       in signing requests) can always be read.
+macro_rules! impl_name_type {
+    ($(#[$attr:meta])* $typ:ident, $kind:literal, max_len = $max_len:literal) => {
+        $(#[$attr])*
+        #[derive(Eq, Hash, PartialEq, Ord, PartialOrd, Copy, Clone, Default)]
+        pub struct $typ(Name);
+
+        impl $typ {
+            /// Maximum number of characters allowed for this type of name.
+            pub const MAX_LEN: usize = $max_len;
+
+            /// Build a name from its string representation.
+            pub fn new(s: &str) -> Result<Self, InvalidName> {
+                Self::from_name(Name::new(s)?)
+            }
+
+            /// Build a name from its string representation (const version).
+            ///
+            /// NOTE: this panics for invalid names
+            pub const fn constant(s: &str) -> Self {
+                let name = Name::constant(s);
+                if !fits_in(name.value, $max_len) {
+                    panic!(concat!($kind, " too long! Max is ", $max_len, " chars"));
+                }
+                Self(name)
+            }
+
+            /// Build a name from a plain `Name`, checking that it is valid for this type.
+            pub fn from_name(name: Name) -> Result<Self, InvalidName> {
+                ensure!(fits_in(name.value, $max_len), TooLongForSnafu {
+                    name: name.to_string(), kind: $kind, max_len: $max_len as usize
+                });
+                Ok(Self(name))
+            }
+
+            /// Build a name from its `u64` representation.
+            #[inline]
+            pub const fn from_u64(n: u64) -> Self {
+                // NOTE: no validation here, see note on `impl_name_type`
+                Self(Name::from_u64(n))
+            }
+
+            /// Return this name as a plain `Name`.
+            #[inline]
+            pub const fn as_name(&self) -> Name { self.0 }
+
+            /// Return the name `u64` representation.
+            #[inline]
+            pub fn as_u64(&self) -> u64 { self.0.value }
+
+            /// Return the prefix.
+            pub fn prefix(&self) -> Name { self.0.prefix() }
+
+            /// Return the string representation of this name, without allocating.
+            #[inline]
+            pub fn to_name_str(&self) -> NameStr { self.0.to_name_str() }
+
+            /// Return the string representation of this name, see [`Name::as_display_cache()`].
+            pub fn as_display_cache(&self) -> Arc<str> { self.0.as_display_cache() }
+        }
+
+        impl From<$typ> for Name {
+            fn from(n: $typ) -> Name {
+                n.0
+            }
+        }
+
+        impl TryFrom<Name> for $typ {
+            type Error = InvalidName;
+
+            fn try_from(n: Name) -> Result<$typ, InvalidName> {
+                $typ::from_name(n)
+            }
+        }
+
+        impl_try_from_str!($typ);
+
+        impl FromStr for $typ {
+            type Err = InvalidName;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $typ::new(s)
+            }
+        }
+
+        impl fmt::Debug for $typ {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl fmt::Display for $typ {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Serialize for $typ {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                self.0.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $typ {
+            fn deserialize<D>(deserializer: D) -> Result<$typ, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Ok($typ(Name::deserialize(deserializer)?))
+            }
+        }
+
+        impl PartialEq<Name> for $typ {
+            fn eq(&self, other: &Name) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$typ> for Name {
+            fn eq(&self, other: &$typ) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialEq<&str> for $typ {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $typ {
+            fn eq(&self, other: &String) -> bool {
+                self.0 == *other
+            }
+        }
+    }
+}
+
+// from https://github.com/AntelopeIO/spring/blob/main/libraries/chain/include/eosio/chain/types.hpp
+// the name length limits are the ones enforced by nodeos and the CDT
+
+impl_name_type!(
+    /// Name of an account.
+    ///
+    /// Accounts can only be created with names of at most 12 characters.
+    AccountName, "Account name", max_len = 12
+);
+
+impl_name_type!(
+    /// Name of an action, can be any valid `Name`.
+    ActionName, "Action name", max_len = 13
+);
+
+impl_name_type!(
+    /// Name of a permission, can be any valid `Name`.
+    PermissionName, "Permission name", max_len = 13
+);
+
+impl_name_type!(
+    /// Name of a table.
+    ///
+    /// Table names are limited to 12 characters, as the last 4 bits of the name
+    /// are used by secondary indices to store their index number.
+    TableName, "Table name", max_len = 12
+);
+
+impl_name_type!(
+    /// Scope of a table, can be any valid `Name`.
+    ScopeName, "Scope name", max_len = 13
+);
+
+
 // =============================================================================
 //
 //     Unittests
@@ -347,15 +765,97 @@ mod tests {
     }
 
     #[test]
-    fn basic_functionality() {
-        let name = Name::new("foobar").unwrap();
+    fn basic_functionality() -> Result<()> {
+        let name = Name::new("foobar")?;
         let json = r#""foobar""#;
 
         assert_eq!(name, Name::from_u64(6712742083569909760));
         assert_eq!(name.as_u64(), 6712742083569909760);
 
-        assert_eq!(serde_json::from_str::<Name>(json).unwrap(), name);
-        assert_eq!(serde_json::to_string(&name).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Name>(json)?, name);
+        assert_eq!(serde_json::to_string(&name)?, json);
+
+        Ok(())
+    }
+
+    #[test]
+    fn specialized_names() -> Result<()> {
+        // 13 chars names are only valid for some of the specialized names
+        assert!(AccountName::new("eosio.token")?.as_name() == Name::new("eosio.token")?);
+        assert!(AccountName::new("aaaaaaaaaaaaa").is_err());
+        assert!(TableName::new("aaaaaaaaaaaaa").is_err());
+        assert!(ActionName::new("aaaaaaaaaaaaa").is_ok());
+        assert!(PermissionName::new("aaaaaaaaaaaaa").is_ok());
+        assert!(ScopeName::new("aaaaaaaaaaaaa").is_ok());
+
+        let name = Name::new("aaaaaaaaaaaa5")?;
+        assert!(AccountName::try_from(name).is_err());
+        assert_eq!(Name::from(ActionName::try_from(name)?), name);
+
+        // decoding does no validation, as for `Name`
+        let account = AccountName::from_u64(1);
+        assert_eq!(account, "............1");
+        assert_eq!(serde_json::from_str::<AccountName>(r#""............1""#)?, account);
+
+        assert_eq!(serde_json::to_string(&AccountName::constant("alice"))?, r#""alice""#);
+        Ok(())
     }
 
+    #[test]
+    fn name_str() -> Result<()> {
+        for s in ["", "a", "eosio.token", "a.b.c.d.e", "zzzzzzzzzzzzj"] {
+            let name = Name::new(s)?;
+            assert_eq!(name.to_name_str(), s);
+            assert_eq!(name.to_name_str().to_string(), name.to_string());
+            assert_eq!(format!("{:?}", name.to_name_str()), format!("{s:?}"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn name_cache() -> Result<()> {
+        let cache = NameCache::new(2);
+        let alice = Name::new("alice")?;
+        let bob = AccountName::new("bob")?;
+
+        let s = cache.get(alice);
+        assert_eq!(&*s, "alice");
+        assert!(Arc::ptr_eq(&s, &cache.get(alice)));
+        assert_eq!(&*cache.get(bob), "bob");
+        assert_eq!(&*cache.get(Name::new("carol")?), "carol");  // evicts `alice`
+        assert!(!Arc::ptr_eq(&s, &cache.get(alice)));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 4, 2));
+        assert_eq!((stats.entries, stats.capacity), (2, 2));
+
+        cache.clear();
+        assert_eq!(cache.stats(), CacheStats { capacity: 2, ..Default::default() });
+
+        // a cache with no capacity still works, it just does not store anything
+        let cache = NameCache::new(0);
+        assert_eq!(&*cache.get(alice), "alice");
+        assert_eq!(cache.stats().entries, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn names_as_map_keys() -> Result<()> {
+        use std::collections::BTreeMap;
+        use serde_json::json;
+
+        let scopes = HashMap::from([(Name::new("alice")?, 1), (Name::new("eosio.token")?, 2)]);
+        let value = serde_json::to_value(&scopes)?;
+        assert_eq!(value, json!({"alice": 1, "eosio.token": 2}));
+        assert_eq!(serde_json::from_value::<HashMap<Name, u32>>(value.clone())?, scopes);
+        assert_eq!(serde_json::from_str::<HashMap<Name, u32>>(&value.to_string())?, scopes);
+
+        let accounts: BTreeMap<AccountName, u32> = serde_json::from_value(value)?;
+        assert_eq!(accounts.keys().map(|a| a.to_string()).collect::<Vec<_>>(), ["alice", "eosio.token"]);
+
+        // keys need to be valid names
+        assert!(serde_json::from_str::<HashMap<Name, u32>>(r#"{"Alice": 1}"#).is_err());
+        assert!(serde_json::from_str::<HashMap<AccountName, u32>>(r#"{"eosio.tokenabc": 1}"#).is_err());
+        Ok(())
+    }
 }
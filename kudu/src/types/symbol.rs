@@ -5,10 +5,11 @@ use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
-use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use snafu::{ensure, Snafu, ResultExt, OptionExt};
 
 use crate::impl_auto_error_conversion;
+use crate::macros::impl_try_from_str;
 
 
 #[derive(Debug, Snafu)]
@@ -38,7 +39,8 @@ pub enum InvalidSymbol {
 impl_auto_error_conversion!(ParseIntError, InvalidSymbol, ParsePrecisionSnafu);
 
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// Symbol codes are ordered by their `u64` representation, as on-chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SymbolCode(u64);
 
 impl SymbolCode {
@@ -51,6 +53,35 @@ impl SymbolCode {
     pub fn new(s: &str) -> Result<SymbolCode, InvalidSymbol> {
         string_to_symbol_code(s).map(SymbolCode)
     }
+
+    /// Parse a symbol code from user input, ignoring surrounding whitespace and
+    /// converting it to uppercase, eg: `" sys "` is `SYS`.
+    ///
+    /// Use [`SymbolCode::new()`] or `FromStr` for consensus data.
+    pub fn parse_lenient(s: &str) -> Result<SymbolCode, InvalidSymbol> {
+        Self::new(&s.trim().to_ascii_uppercase())
+    }
+
+    /// Return an iterator over the characters of this symbol code.
+    ///
+    /// ## Example
+    /// ```
+    /// # use kudu::{SymbolCode, InvalidSymbol};
+    /// let code = SymbolCode::new("EOS")?;
+    /// assert_eq!(code.chars().collect::<Vec<_>>(), ['E', 'O', 'S']);
+    /// assert_eq!(code.chars().count(), 3);
+    /// # Ok::<(), InvalidSymbol>(())
+    /// ```
+    pub fn chars(&self) -> impl Iterator<Item = char> + use<> {
+        let mut v = self.0;
+        std::iter::from_fn(move || {
+            (v != 0).then(|| {
+                let c = (v & 0xFF) as u8;
+                v >>= 8;
+                c as char
+            })
+        })
+    }
 }
 
 /// `Symbol` represents a token and contains precision and name.
@@ -68,7 +99,9 @@ impl SymbolCode {
 /// assert_eq!(symbol.code(), SymbolCode::new("CUR")?);
 /// # Ok::<(), InvalidSymbol>(())
 /// ```
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///
+/// Symbols are ordered by their `u64` representation, as on-chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Symbol {
     value: u64,
 }
@@ -90,6 +123,58 @@ impl Symbol {
         Self::from_prec_and_str(precision, &s[pos + 1..])
     }
 
+    /// Parse a symbol from user input, ignoring whitespace around the precision
+    /// and the name and converting the name to uppercase, eg: `" 4, sys"` is `4,SYS`.
+    ///
+    /// Use [`Symbol::new()`] or `FromStr` for consensus data.
+    pub fn parse_lenient(s: &str) -> Result<Self, InvalidSymbol> {
+        let s = s.trim();
+        ensure!(!s.is_empty(), EmptySnafu);
+        let (precision, name) = s.split_once(',').context(MissingCommaSnafu)?;
+        let precision: u8 = precision.trim().parse().context(ParsePrecisionSnafu)?;
+        Self::from_prec_and_str(precision, &name.trim().to_ascii_uppercase())
+    }
+
+    /// Build a `Symbol` from its string representation (const version).
+    ///
+    /// NOTE: this panics for invalid symbols
+    pub const fn constant(s: &str) -> Self {
+        let s = s.as_bytes();
+        let mut pos = 0;
+        let mut precision: u8 = 0;
+        while pos < s.len() && s[pos].is_ascii_digit() {
+            precision = match precision.checked_mul(10) {
+                Some(p) => match p.checked_add(s[pos] - b'0') {
+                    Some(p) => p,
+                    None => panic!("Invalid symbol precision"),
+                },
+                None => panic!("Invalid symbol precision"),
+            };
+            pos += 1;
+        }
+        if pos == 0 { panic!("Invalid symbol precision"); }
+        if pos == s.len() || s[pos] != b',' { panic!("Missing comma in symbol"); }
+        let (_, name) = s.split_at(pos + 1);
+        Self::constant_from_parts(precision, name)
+    }
+
+    /// Build a `Symbol` from its precision and name (const version).
+    ///
+    /// NOTE: this panics for invalid symbols
+    pub(crate) const fn constant_from_parts(precision: u8, name: &[u8]) -> Self {
+        if precision > Self::MAX_PRECISION { panic!("Symbol precision too high! Max is 18"); }
+        if name.is_empty() { panic!("Empty symbol name"); }
+        if name.len() > 7 { panic!("Symbol name too long! Max is 7 chars"); }
+        let mut code: u64 = 0;
+        let mut i = 0;
+        while i < name.len() {
+            if !name[i].is_ascii_uppercase() { panic!("Invalid char in symbol name"); }
+            code |= (name[i] as u64) << (8 * i);
+            i += 1;
+        }
+        Self { value: (code << 8) | (precision as u64) }
+    }
+
     pub fn as_u64(&self) -> u64 { self.value }
 
     pub fn from_u64(n: u64) -> Result<Self, InvalidSymbol> {
@@ -98,6 +183,13 @@ impl Symbol {
         Ok(result)
     }
 
+    /// Build a `Symbol` from its number of decimals and its code.
+    pub fn from_code(decimals: u8, code: SymbolCode) -> Result<Self, InvalidSymbol> {
+        ensure!(decimals <= Self::MAX_PRECISION,
+                PrecisionSnafu { given: decimals, max: Self::MAX_PRECISION });
+        Self::from_u64((code.as_u64() << 8) | (decimals as u64))
+    }
+
     pub fn decimals(&self) -> u8 {
         (self.value & 0xFF) as u8
     }
@@ -153,14 +245,7 @@ fn string_to_symbol_code(s: &str) -> Result<u64, InvalidSymbol> {
 }
 
 fn symbol_code_to_string(value: u64) -> String {
-    let mut v: u64 = value;
-    let mut result = String::with_capacity(7);
-    while v != 0 {
-        let c = (v & 0xFF) as u8;
-        result.push(c as char);
-        v >>= 8;
-    }
-    result
+    SymbolCode(value).chars().collect()
 }
 
 fn string_to_symbol(precision: u8, s: &str) -> Result<u64, InvalidSymbol> {
@@ -179,13 +264,7 @@ fn is_valid_symbol_name(name: &str) -> bool {
 //     Conversion traits
 // -----------------------------------------------------------------------------
 
-impl TryFrom<&str> for Symbol {
-    type Error = InvalidSymbol;
-
-    fn try_from(s: &str) -> Result<Symbol, InvalidSymbol> {
-        Symbol::from_str(s)
-    }
-}
+impl_try_from_str!(SymbolCode, Symbol);
 
 
 // -----------------------------------------------------------------------------
@@ -242,8 +321,7 @@ impl<'de> Deserialize<'de> for SymbolCode {
     where
         D: Deserializer<'de>,
     {
-        let code: &str = <&str>::deserialize(deserializer)?;
-        SymbolCode::new(code).map_err(|e| de::Error::custom(e.to_string()))
+        crate::abiserializable::deserialize_str(deserializer, "a symbol code", SymbolCode::new)
     }
 }
 
@@ -261,8 +339,7 @@ impl<'de> Deserialize<'de> for Symbol {
     where
         D: Deserializer<'de>,
     {
-        let symbol: &str = <&str>::deserialize(deserializer)?;
-        Symbol::new(symbol).map_err(|e| de::Error::custom(e.to_string()))
+        crate::abiserializable::deserialize_str(deserializer, "a symbol", Symbol::new)
     }
 }
 
@@ -304,4 +381,19 @@ mod tests {
         assert_eq!(serde_json::from_str::<Symbol>(json).unwrap(), obj);
         assert_eq!(serde_json::to_string(&obj).unwrap(), json);
     }
+
+    #[test]
+    fn lenient_parsing() {
+        let sys = Symbol::new("4,SYS").unwrap();
+        for s in ["4,sys", " 4 , Sys\t", "4,SYS"] {
+            assert_eq!(Symbol::parse_lenient(s).unwrap(), sys);
+        }
+        assert!(Symbol::new("4,sys").is_err());
+        for s in ["", "sys", "4,", "4,s y", "x,SYS"] {
+            assert!(Symbol::parse_lenient(s).is_err(), "{s}");
+        }
+
+        assert_eq!(SymbolCode::parse_lenient(" sys\n").unwrap(), sys.code());
+        assert!(SymbolCode::new(" sys").is_err());
+    }
 }
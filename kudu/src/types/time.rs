@@ -6,13 +6,14 @@ use std::ops::Add;
 use std::str::FromStr;
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, ParseError as ChronoParseError, TimeZone, Utc};
-use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{json, Value as JsonValue};
 use snafu::{Snafu, OptionExt};
 
 use kudu_macros::with_location;
 
-use crate::config;
+use crate::{config, BlockNum};
+use crate::macros::impl_try_from_str;
 
 
 #[with_location]
@@ -61,9 +62,11 @@ fn parse_date(s: &str) -> Result<DateTime<Utc>, ChronoParseError> {
        .and_utc())
 }
 
+/// Return the block slot containing the given date, dates before the block timestamp
+/// epoch (2000-01-01) map to slot 0.
 fn timestamp_to_block_slot(dt: &DateTime<Utc>) -> u32 {
-    let ms_since_epoch = (dt.timestamp_micros() / 1000) as u64 - config::BLOCK_TIMESTAMP_EPOCH;
-    let result = ms_since_epoch / (config::BLOCK_INTERVAL_MS as u64);
+    let ms_since_epoch = dt.timestamp_millis() - config::BLOCK_TIMESTAMP_EPOCH as i64;
+    let result = ms_since_epoch.max(0) / (config::BLOCK_INTERVAL_MS as i64);
     result.try_into().expect("timestamp too far in the future to fit block slot in a `u32`")
 }
 
@@ -92,8 +95,7 @@ macro_rules! impl_serialize {
             where
                 D: Deserializer<'de>,
             {
-                let s: &str = <&str>::deserialize(deserializer)?;
-                Self::from_str(s).map_err(|e| de::Error::custom(e.to_string()))
+                crate::abiserializable::deserialize_str(deserializer, "a date/time", Self::from_str)
             }
         }
     }
@@ -113,12 +115,7 @@ macro_rules! impl_from {
             }
         }
 
-        impl TryFrom<&str> for $typ {
-            type Error = ChronoParseError;
-            fn try_from(value: &str) -> Result<Self, Self::Error> {
-                Self::from_str(value)
-            }
-        }
+        impl_try_from_str!($typ);
     }
 }
 
@@ -128,7 +125,9 @@ macro_rules! impl_from {
 // -----------------------------------------------------------------------------
 
 /// TimePoint with micro second precision
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+///
+/// Time points are ordered chronologically.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct TimePoint(i64);
 
 impl TimePoint {
@@ -147,8 +146,13 @@ impl TimePoint {
     pub fn from_datetime(dt: DateTime<Utc>) -> Self {
         TimePoint(dt.timestamp_micros())
     }
+    /// Return the corresponding `DateTime`, saturating to `DateTime::MIN_UTC` or
+    /// `DateTime::MAX_UTC` if the time point is outside of the range it supports.
     pub fn to_datetime(&self) -> DateTime<Utc> {
-        Utc.timestamp_micros(self.0).unwrap()  // safe unwrap
+        Utc.timestamp_micros(self.0).single().unwrap_or(match self.0 < 0 {
+            true => DateTime::<Utc>::MIN_UTC,
+            false => DateTime::<Utc>::MAX_UTC,
+        })
     }
     pub fn to_json(&self) -> JsonValue {
         json!(self.to_string())
@@ -173,7 +177,9 @@ impl FromStr for TimePoint {
 // -----------------------------------------------------------------------------
 
 /// TimePoint with second precision
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+///
+/// Time points are ordered chronologically.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct TimePointSec(u32);
 
 impl TimePointSec {
@@ -219,7 +225,11 @@ impl Add<u32> for TimePointSec {
 //     BlockTimestamp
 // -----------------------------------------------------------------------------
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+/// Timestamp of a block, represented as a block slot (500ms intervals since the
+/// `config::BLOCK_TIMESTAMP_EPOCH`).
+///
+/// Block timestamps are ordered chronologically.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct BlockTimestamp(u32);
 
 impl BlockTimestamp {
@@ -240,6 +250,53 @@ impl BlockTimestamp {
     pub fn to_json(&self) -> JsonValue {
         json!(format!("{}", self.to_datetime().format(DATE_FORMAT_MS)))
     }
+
+    pub const fn from_slot(slot: u32) -> Self { BlockTimestamp(slot) }
+
+    /// Return the block slot, ie: the number of block intervals since the epoch.
+    pub const fn slot(&self) -> u32 { self.0 }
+
+    /// Return the block timestamp containing the given time point, rounding down
+    /// to the start of its slot.
+    pub fn from_time_point(tp: TimePoint) -> Self {
+        BlockTimestamp::from_datetime(tp.to_datetime())
+    }
+
+    pub fn to_time_point(&self) -> TimePoint {
+        TimePoint::from_datetime(self.to_datetime())
+    }
+
+    /// Estimate the number of the block produced at this timestamp, given a
+    /// reference block and its timestamp.
+    ///
+    /// This assumes that a block is produced at every slot between the reference
+    /// block and this timestamp (ie: no missed blocks) so it is exact for nearby
+    /// blocks and an approximation otherwise. Return `None` if the result doesn't
+    /// fit in a [`BlockNum`].
+    ///
+    /// ## Example
+    /// ```
+    /// # use kudu::BlockTimestamp;
+    /// let head: BlockTimestamp = "2025-01-01T00:00:00.000".parse()?;
+    /// let later: BlockTimestamp = "2025-01-01T00:01:00.000".parse()?;
+    /// assert_eq!(later.block_num(1000, head), Some(1120));
+    /// assert_eq!(BlockTimestamp::from_block_num(1120, 1000, head), Some(later));
+    /// # Ok::<(), chrono::ParseError>(())
+    /// ```
+    pub fn block_num(&self, reference_num: BlockNum, reference_time: BlockTimestamp) -> Option<BlockNum> {
+        let slots = self.0 as i64 - reference_time.0 as i64;
+        (reference_num as i64 + slots).try_into().ok()
+    }
+
+    /// Estimate the timestamp of the given block, given a reference block and its
+    /// timestamp. See [`BlockTimestamp::block_num()`] for the assumptions made.
+    ///
+    /// Return `None` if the result is before the epoch or doesn't fit in a block slot.
+    pub fn from_block_num(block_num: BlockNum, reference_num: BlockNum, reference_time: BlockTimestamp)
+                          -> Option<BlockTimestamp> {
+        let blocks = block_num as i64 - reference_num as i64;
+        (reference_time.0 as i64 + blocks).try_into().ok().map(BlockTimestamp)
+    }
 }
 
 impl_time_display!(BlockTimestamp);
@@ -253,3 +310,33 @@ impl FromStr for BlockTimestamp {
         Ok(BlockTimestamp(timestamp_to_block_slot(&parse_date(s)?)))
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_timestamp_conversions() {
+        let epoch = BlockTimestamp::new(2000, 1, 1, 0, 0, 0, 0).unwrap();
+        assert_eq!(epoch.slot(), 0);
+        assert_eq!(BlockTimestamp::from_slot(3).to_string(), "2000-01-01T00:00:01.500");
+
+        // dates before the epoch saturate to slot 0
+        assert_eq!(BlockTimestamp::new(1999, 12, 31, 23, 59, 59, 0).unwrap(), epoch);
+        assert_eq!(BlockTimestamp::from_time_point(TimePoint::from(-1)), epoch);
+
+        // time points are rounded down to the start of their slot
+        let tp: TimePoint = "2024-06-01T12:00:00.999".parse().unwrap();
+        let bt = BlockTimestamp::from_time_point(tp);
+        assert_eq!(bt.to_string(), "2024-06-01T12:00:00.500");
+        assert_eq!(bt.to_time_point(), "2024-06-01T12:00:00.500".parse().unwrap());
+
+        let reference = BlockTimestamp::from_slot(100);
+        assert_eq!(BlockTimestamp::from_slot(90).block_num(50, reference), Some(40));
+        assert_eq!(BlockTimestamp::from_slot(10).block_num(50, reference), None);
+        assert_eq!(BlockTimestamp::from_block_num(40, 50, reference), Some(BlockTimestamp::from_slot(90)));
+        assert_eq!(BlockTimestamp::from_block_num(0, 200, reference), None);
+        assert_eq!(BlockTimestamp::from_block_num(BlockNum::MAX, 0, reference), None);
+    }
+}
@@ -8,7 +8,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 // -----------------------------------------------------------------------------
 
 /// Newtype wrapper around a `i32` that has a different serialization implementation
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct VarInt32(pub i32);
 
 impl From<i32> for VarInt32 {
@@ -44,7 +44,7 @@ impl<'de> Deserialize<'de> for VarInt32 {
 // -----------------------------------------------------------------------------
 
 /// Newtype wrapper around a `u32` that has a different serialization implementation
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct VarUint32(pub u32);
 
 impl From<u32> for VarUint32 {
@@ -12,13 +12,20 @@ use tracing::{trace, debug, info, instrument};
 
 use kudu::{
     abiserializable::{to_bin, from_bin, ABISerializable},
-    abi::data::{
-        PACKED_TRANSACTION_ABI, TEST_ABI, TOKEN_HEX_ABI, TRANSACTION_ABI
-    },
+    abi::data::{TEST_ABI, TOKEN_HEX_ABI, TRANSACTION_ABI},
     ABIDefinition, Asset, Bytes, ByteStream, ExtendedAsset, InvalidValue, JsonValue, Name,
     Symbol, SymbolCode, TimePoint, TimePointSec, TypeName, VarInt32, VarUint32, ABI,
     Checksum160, Checksum256, Checksum512, PublicKey, PrivateKey, Signature,
-    Transaction, Action, AccountName, Transfer, BlockTimestamp, PackedTransactionV0
+    Action, AccountName, ActionName, BlockTimestamp,
+};
+#[cfg(feature = "contracts-token")]
+use kudu::{
+    abi::data::PACKED_TRANSACTION_ABI, contracts::token::Transfer, PackedTransactionV0, Transaction,
+};
+#[cfg(feature = "contracts-system")]
+use kudu::{
+    abi::data::EOSIO_ABI, contracts::system::{RegProducer, RegProducer2},
+    BlockSigningAuthority, BlockSigningAuthorityV0, KeyWeight,
 };
 
 // just for the float128 test!!
@@ -414,6 +421,26 @@ fn roundtrip_i128() -> Result<()> {
     check_u128(340282366920938463463374607431768211455u128,
                r#""340282366920938463463374607431768211455""#, "ffffffffffffffffffffffffffffffff");
 
+    // unquoted numbers are accepted too, as long as serde_json can represent them
+    // exactly: 64-bit integers always, wider ones with the `arbitrary-precision` feature
+    check_round_trip(abi, "int128", "18446744073709551615", "ffffffffffffffff0000000000000000",
+                     r#""18446744073709551615""#)?;
+    #[cfg(feature = "arbitrary-precision")]
+    {
+        check_round_trip(abi, "int128", "-170141183460469231731687303715884105728", "00000000000000000000000000000080",
+                         r#""-170141183460469231731687303715884105728""#)?;
+        check_round_trip(abi, "uint128", "340282366920938463463374607431768211455", "ffffffffffffffffffffffffffffffff",
+                         r#""340282366920938463463374607431768211455""#)?;
+        check_error(|| try_encode(abi, "int128",  "170141183460469231731687303715884105728"),      "number too large");
+        check_error(|| try_encode(abi, "uint128", "340282366920938463463374607431768211456"),      "number too large");
+    }
+    // otherwise they are parsed as floats, which gives a clear error instead of a wrong value
+    #[cfg(not(feature = "arbitrary-precision"))]
+    {
+        check_error(|| try_encode(abi, "int128",  "-170141183460469231731687303715884105728"),     "must be quoted");
+        check_error(|| try_encode(abi, "uint128", "340282366920938463463374607431768211455"),      "must be quoted");
+    }
+
     check_error(|| try_encode(abi, "int128",  r#""170141183460469231731687303715884105728""#),  "number too large");
     check_error(|| try_encode(abi, "int128",  r#""-170141183460469231731687303715884105729""#), "number too small");
     check_error(|| try_encode(abi, "uint128", r#""-1""#),                                       "invalid integer");
@@ -782,10 +809,10 @@ fn roundtrip_asset() -> Result<()> {
     check_cross_conversion(abi, None::<Asset>, "asset?", "null", "00");
     check_cross_conversion(abi, Some(asset("0.123456 SIX")), "asset?", r#""0.123456 SIX""#, "0140e20100000000000653495800000000");
 
-    check_cross_conversion(abi, ExtendedAsset { quantity: "0 FOO".parse()?, contract: Name::new("bar")? },
+    check_cross_conversion(abi, ExtendedAsset { quantity: "0 FOO".parse()?, contract: AccountName::new("bar")? },
                            "extended_asset", r#"{"quantity":"0 FOO","contract":"bar"}"#,
                            "000000000000000000464f4f00000000000000000000ae39");
-    check_cross_conversion(abi, ExtendedAsset { quantity: "0.123456 SIX".parse()?, contract: Name::new("seven")? },
+    check_cross_conversion(abi, ExtendedAsset { quantity: "0.123456 SIX".parse()?, contract: AccountName::new("seven")? },
                            "extended_asset", r#"{"quantity":"0.123456 SIX","contract":"seven"}"#,
                            "40e201000000000006534958000000000000000080a9b6c2");
 
@@ -795,6 +822,7 @@ fn roundtrip_asset() -> Result<()> {
 }
 
 #[test]
+#[cfg(feature = "contracts-token")]
 fn roundtrip_transaction() -> Result<()> {
     init();
 
@@ -824,8 +852,8 @@ fn roundtrip_transaction() -> Result<()> {
     );
 
     let transfer = Transfer {
-        from: Name::constant("useraaaaaaaa"),
-        to: Name::constant("useraaaaaaab"),
+        from: AccountName::constant("useraaaaaaaa"),
+        to: AccountName::constant("useraaaaaaab"),
         quantity: Asset::from_str("0.0001 SYS")?,
         memo: "".into(),
     };
@@ -876,6 +904,46 @@ fn roundtrip_transaction() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "contracts-system")]
+fn roundtrip_producer_actions() -> Result<()> {
+    init();
+
+    let eosio_abi = &ABI::from_str(EOSIO_ABI)?;
+    let key = PublicKey::from_str("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV")?;
+
+    let regproducer = RegProducer {
+        producer: AccountName::constant("producer1"),
+        producer_key: key.clone(),
+        url: "https://example.com".into(),
+        location: 250,
+    };
+
+    check_cross_conversion(
+        eosio_abi, regproducer, "regproducer",
+        r#"{"producer":"producer1","producer_key":"PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63","url":"https://example.com","location":250}"#,
+        "00000857219de8ad0002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf1368747470733a2f2f6578616d706c652e636f6dfa00"
+    );
+
+    let regproducer2 = RegProducer2 {
+        producer: AccountName::constant("producer1"),
+        producer_authority: BlockSigningAuthority::V0(BlockSigningAuthorityV0 {
+            threshold: 2,
+            keys: vec![KeyWeight { key: key.clone(), weight: 1 }, KeyWeight { key, weight: 1 }],
+        }),
+        url: "https://example.com".into(),
+        location: 250,
+    };
+
+    check_cross_conversion(
+        eosio_abi, regproducer2, "regproducer2",
+        r#"{"producer":"producer1","producer_authority":["block_signing_authority_v0",{"threshold":2,"keys":[{"key":"PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63","weight":1},{"key":"PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63","weight":1}]}],"url":"https://example.com","location":250}"#,
+        "00000857219de8ad0002000000020002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf01000002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf01001368747470733a2f2f6578616d706c652e636f6dfa00"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn roundtrip_transaction_traces() -> Result<()> {
     use kudu::{TransactionTraceException, TransactionTraceMsg};
@@ -885,7 +953,7 @@ fn roundtrip_transaction_traces() -> Result<()> {
     let ship_abi_def = ABIDefinition::from_str(STATE_HISTORY_PLUGIN_ABI)?;
     let ship_abi = &ABI::from_definition(&ship_abi_def)?;
 
-    let eosio = Name::from_str("eosio")?;
+    let eosio = AccountName::from_str("eosio")?;
     let trace = TransactionTrace::V0(TransactionTraceV0 {
         id: "3098EA9476266BFA957C13FA73C26806D78753099CE8DEF2A650971F07595A69".try_into()?,
         status: 0,
@@ -909,14 +977,14 @@ fn roundtrip_transaction_traces() -> Result<()> {
             receiver: eosio,
             act: Action {
                 account: eosio,
-                name: Name::from_str("newaccount")?,
+                name: ActionName::from_str("newaccount")?,
                 authorization: vec![PermissionLevel { actor: eosio, permission: PermissionName::from_str("active")? }],
                 data: Bytes::from_hex("0000000000EA305500409406A888CCA501000000010002C0DED2BC1F1305FB0FAAC5E6C03EE3A1924234985427B6167CA569D13DF435CF0100000001000000010002C0DED2BC1F1305FB0FAAC5E6C03EE3A1924234985427B6167CA569D13DF435CF01000000")?,
             },
             context_free: false,
             elapsed: 83,
             console: "".into(),
-            account_ram_deltas: vec![AccountDelta { account: Name::from_str("oracle.aml")?, delta: 2724 }],
+            account_ram_deltas: vec![AccountDelta { account: AccountName::from_str("oracle.aml")?, delta: 2724 }],
             account_disk_deltas: vec![],
             except: None,
             error_code: None,
@@ -966,7 +1034,7 @@ fn roundtrip_transaction_traces() -> Result<()> {
             receiver: eosio,
             act: Action {
                 account: eosio,
-                name: Name::from_str("doit")?,
+                name: ActionName::from_str("doit")?,
                 authorization: vec![PermissionLevel { actor: eosio, permission: PermissionName::from_str("active")? }],
                 data: Bytes::from_hex("00")?,
             },
@@ -1033,3 +1101,24 @@ fn check_invalid_abis() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn check_malformed_input() -> Result<()> {
+    init();
+
+    // malformed input should return an error instead of panicking
+    let test_abi = ABI::from_str(TEST_ABI)?;
+    check_error(|| try_encode(&test_abi, "v1", r#"{"int8":1}"#), "expected input to be an array of 2 elements");
+    check_error(|| try_encode(&test_abi, "v1", r#"["int8"]"#), "expected input to be an array of 2 elements");
+    check_error(|| try_encode(&test_abi, "v1", "[8, 1]"), "expected variant typename to be a string");
+
+    check_error(|| Ok(ABI::from_str(r#"{"version":"eosio::abi/1.1","structs":[{"name":"a","base":"b","fields":[]}]}"#)?),
+                "integrity error: invalid type used in 'a::base'");
+
+    // time points outside of the range supported by `chrono` saturate instead of panicking
+    let abi = transaction_abi();
+    assert!(try_decode(abi, "time_point", "ffffffffffffff7f")?.is_string());
+    assert!(try_decode(abi, "time_point", "0000000000000080")?.is_string());
+
+    Ok(())
+}
@@ -0,0 +1,287 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Feed random, truncated and corrupted data to the decoders of all the public
+//! types and of the bundled ABIs, and check that they return errors instead of
+//! panicking.
+//!
+//! The number of inputs tried for each type and the random seed can be set with
+//! the `KUDU_FUZZ_ITERATIONS` and `KUDU_FUZZ_SEED` environment variables, eg. for
+//! a longer run on CI:
+//! ```sh
+//! KUDU_FUZZ_ITERATIONS=100000 cargo test --release --test decoding_robustness
+//! ```
+//!
+
+use std::any::type_name;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::cell::Cell;
+use std::panic::{self, catch_unwind, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::Once;
+
+use kudu::*;
+use kudu::abi::data::*;
+use kudu::chain::extensions::*;
+use kudu::chain::resources::{PowerupState, PowerupStateResource, RexPool};
+use strum::VariantNames;
+
+
+const DEFAULT_ITERATIONS: usize = 1000;
+const DEFAULT_SEED: u64 = 0x6b75_6475;
+
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    /// Whether we are currently decoding, in which case panics are expected to be
+    /// caught and should not be printed.
+    static DECODING: Cell<bool> = const { Cell::new(false) };
+}
+
+
+// -----------------------------------------------------------------------------
+//     Input generation
+// -----------------------------------------------------------------------------
+
+/// Small xorshift PRNG, so that runs are reproducible given a seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn byte(&mut self) -> u8 {
+        // favor values that are interesting as lengths, tags and varint bytes
+        match self.below(4) {
+            0 => [0x00, 0x01, 0x7f, 0x80, 0xff][self.below(5)],
+            _ => self.next() as u8,
+        }
+    }
+
+    fn bytes(&mut self, max_len: usize) -> Vec<u8> {
+        (0..self.below(max_len + 1)).map(|_| self.byte()).collect()
+    }
+}
+
+struct Harness {
+    rng: Rng,
+    iterations: usize,
+    failures: Vec<String>,
+}
+
+impl Harness {
+    fn from_env() -> Self {
+        let var = |name, default| env::var(name).ok()
+            .map(|v| v.parse().unwrap_or_else(|_| panic!("invalid value for {name}: {v}")))
+            .unwrap_or(default);
+        let seed = var("KUDU_FUZZ_SEED", DEFAULT_SEED);
+
+        PANIC_HOOK.call_once(|| {
+            let default_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if !DECODING.get() { default_hook(info) }
+            }));
+        });
+
+        Harness {
+            rng: Rng(seed.max(1)),
+            iterations: var("KUDU_FUZZ_ITERATIONS", DEFAULT_ITERATIONS as u64) as usize,
+            failures: vec![],
+        }
+    }
+
+    /// Return the inputs to try for a decoder: all the prefixes of the samples, then
+    /// random bytes and randomly corrupted samples.
+    fn inputs(&mut self, samples: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        // all-zero data decodes as a valid (empty) value for most types
+        let mut samples = samples.to_vec();
+        samples.push(vec![0; 64]);
+
+        let mut result: Vec<Vec<u8>> = samples.iter()
+            .flat_map(|s| (0..s.len()).map(|n| s[..n].to_vec()))
+            .collect();
+
+        for _ in 0..self.iterations {
+            let input = match self.rng.below(3) {
+                0 => self.rng.bytes(64),
+                _ => {
+                    let mut data = samples[self.rng.below(samples.len())].clone();
+                    for _ in 0..1 + self.rng.below(4) {
+                        let pos = self.rng.below(data.len());
+                        match self.rng.below(4) {
+                            0 if !data.is_empty() => { data.remove(pos); },
+                            1 => data.insert(pos, self.rng.byte()),
+                            _ if !data.is_empty() => data[pos] = self.rng.byte(),
+                            _ => {},
+                        }
+                    }
+                    data
+                },
+            };
+            result.push(input);
+        }
+        result
+    }
+
+    /// Call `decode` on all the inputs and record the ones which make it panic.
+    fn check(&mut self, name: &str, samples: &[Vec<u8>], decode: impl Fn(&[u8])) {
+        for input in self.inputs(samples) {
+            DECODING.set(true);
+            let result = catch_unwind(AssertUnwindSafe(|| decode(&input)));
+            DECODING.set(false);
+            if let Err(e) = result {
+                let msg = e.downcast_ref::<String>().map(String::as_str)
+                    .or_else(|| e.downcast_ref::<&str>().copied())
+                    .unwrap_or("<unknown>");
+                self.failures.push(format!("{name}: panicked on input `{}`: {msg}", bytes_to_hex(&input)));
+                return;  // only report the first failure for each type
+            }
+        }
+    }
+
+    fn check_type<T: ABISerializable>(&mut self, samples: &[Vec<u8>]) {
+        self.check(type_name::<T>(), samples, |input| {
+            let _ = T::from_bin(&mut ByteStream::from(input));
+        });
+    }
+
+    fn check_default<T: ABISerializable + Default>(&mut self) {
+        self.check_type::<T>(&[to_bin(&T::default()).into()]);
+    }
+
+    fn check_abi(&mut self, abi: &ABI) {
+        let mut typenames: BTreeSet<String> = AntelopeValue::VARIANTS.iter().map(|s| s.to_string()).collect();
+        typenames.extend(abi.structs().map(|s| s.name.clone()));
+        typenames.extend(abi.variants().map(|v| v.name.clone()));
+        typenames.extend(abi.typedefs().map(|(name, _)| name.to_string()));
+
+        // ABIs have a lot of types, try fewer inputs for each of them
+        let iterations = self.iterations;
+        self.iterations = iterations.div_ceil(10);
+        for typename in typenames {
+            self.check(&typename, &[], |input| {
+                let _ = abi.decode_variant(&mut ByteStream::from(input), typename.as_str());
+            });
+        }
+        self.iterations = iterations;
+    }
+
+    fn report(self) {
+        assert!(self.failures.is_empty(), "decoding panicked for {} type(s):\n{}",
+                self.failures.len(), self.failures.join("\n"));
+    }
+}
+
+
+// -----------------------------------------------------------------------------
+//     Tests
+// -----------------------------------------------------------------------------
+
+macro_rules! check_types {
+    ($h:ident, default: [$($def:ty),* $(,)?], other: [$($typ:ty),* $(,)?] $(,)?) => {
+        $( $h.check_default::<$def>(); )*
+        $( $h.check_type::<$typ>(&[]); )*
+    }
+}
+
+#[test]
+fn decode_builtin_types() {
+    let mut h = Harness::from_env();
+    check_types!(h,
+        default: [
+            bool, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64,
+            VarInt32, VarUint32, Float128, Bytes, String,
+            TimePoint, TimePointSec, BlockTimestamp,
+            Checksum160, Checksum256, Checksum512,
+            Name, AccountName, ActionName, PermissionName, TableName, ScopeName,
+            Extensions, Vec<String>, Option<Name>, Box<u32>, (u16, Bytes),
+            BTreeSet<Name>, BTreeMap<Name, Bytes>,
+        ],
+        other: [
+            &[u8], &str, [u8; 4], [Name; 2],
+            Symbol, SymbolCode, Asset, ExtendedAsset,
+            PublicKey, Signature, BlsPublicKey, BlsSignature,
+        ],
+    );
+
+    for name in AntelopeValue::VARIANTS {
+        let typename = AntelopeType::from_str(name).unwrap();
+        h.check(name, &[], |input| {
+            let _ = AntelopeValue::from_bin(typename, &mut ByteStream::from(input));
+        });
+    }
+    h.report();
+}
+
+#[test]
+fn decode_chain_types() {
+    let mut h = Harness::from_env();
+    check_types!(h,
+        default: [
+            PermissionLevel, Action, Transaction, Authority,
+            ProducerSchedule, BlockSigningAuthorityV0, ProducerAuthoritySchedule,
+            BlockHeader, PackedTransaction,
+            FinalizerPolicy, FinalizerPolicyDiff, ProposerPolicyDiff, QcClaim, QcSig, Qc,
+            AccountAuthSequence, ActionReceiptV0, AccountDelta, PackedTransactionV0,
+            TransactionTraceException, ActionTraceV0, ActionTraceV1, TransactionTraceV0,
+            DeferredTransactionGenerationContext, ResourcePayer, ProtocolFeatureActivation,
+            ProducerScheduleChangeExtension, FinalityExtension,
+            AdditionalBlockSignaturesExtension, QuorumCertificateExtension,
+        ],
+        other: [
+            SetCode, KeyWeight, PermissionLevelWeight, WaitWeight,
+            ProducerKey, BlockSigningAuthority, ProducerAuthority, SignedBlockHeader,
+            ReceiptTransaction, TransactionReceipt, SignedBlock,
+            FinalizerAuthority, OrderedDiff<FinalizerAuthority>,
+            ActionReceipt, ActionTrace, TransactionTrace, TransactionTraceMsg,
+            PowerupStateResource, PowerupState, RexPool,
+            ABIDefinition,
+        ],
+    );
+    h.report();
+}
+
+#[cfg(feature = "contracts-token")]
+#[test]
+fn decode_token_contract_types() {
+    use kudu::contracts::token::*;
+
+    let mut h = Harness::from_env();
+    check_types!(h,
+        default: [],
+        other: [Create, Issue, Retire, Transfer, Open, Close, Account, CurrencyStats],
+    );
+    h.report();
+}
+
+#[cfg(feature = "contracts-system")]
+#[test]
+fn decode_system_contract_types() {
+    use kudu::contracts::system::*;
+
+    let mut h = Harness::from_env();
+    check_types!(h,
+        default: [BlockchainParameters, GlobalState, GlobalState2, GlobalState3, GlobalState4],
+        other: [RegProducer, RegProducer2, ProducerInfo, VoterInfo, Connector, ExchangeState],
+    );
+    h.report();
+}
+
+#[test]
+fn decode_abi_types() {
+    let mut h = Harness::from_env();
+    for abi in [EOSIO_ABI, EOSIO_TOKEN_ABI, CORE_VAULTA_ABI, TEST_ABI, STATE_HISTORY_PLUGIN_ABI] {
+        h.check_abi(&ABI::from_str(abi).unwrap());
+    }
+    h.report();
+}
@@ -11,7 +11,7 @@ use serde::{Serialize, Deserialize};
 use serde_json::json;
 
 use kudu::{
-    ABI, ABISerializable,
+    ABI, ABISerializable, AccountName,
     AntelopeType, AntelopeValue, Asset, Bytes, BlockTimestamp, ExtendedAsset,
     Name, Symbol, SymbolCode, TimePoint, TimePointSec, VarInt32, VarUint32, PublicKey, PrivateKey, Signature,
     Checksum160, Checksum256, Checksum512,
@@ -691,9 +691,9 @@ fn test_asset() {
 #[test]
 fn test_extended_asset() -> Result<()> {
     let vals = [
-        ((Asset::from_str("0 FOO")?, Name::new("bar")?),
+        ((Asset::from_str("0 FOO")?, AccountName::new("bar")?),
          "000000000000000000464f4f00000000000000000000ae39"),
-        ((Asset::from_str("0.123456 SIX")?, Name::new("seven")?),
+        ((Asset::from_str("0.123456 SIX")?, AccountName::new("seven")?),
          "40e201000000000006534958000000000000000080a9b6c2"),
     ];
 
@@ -703,14 +703,12 @@ fn test_extended_asset() -> Result<()> {
     Ok(())
 }
 
-#[ignore]
 #[test]
 fn test_serde_timepoint() -> Result<()> {
     init();
 
-    // NOTE: this test fails due to the implementation of `Deserialize` for `TimePoint` expecting
-    //       a `&str` instead of a `String`
-    // we should find a way to make this work without having to allocate in the good case
+    // `from_value` can only provide owned strings to the `Deserialize` implementations,
+    // which used to fail for types expecting a `&str`
     // see: https://github.com/serde-rs/serde/issues/2065
     // see: https://github.com/serde-rs/serde/issues/1009
 
@@ -727,3 +725,413 @@ fn test_serde_timepoint() -> Result<()> {
 
     Ok(())
 }
+
+/// Check that native types can be deserialized by formats that do not lend borrowed
+/// data to the deserializer (`serde_json::from_value()`, `serde_json::from_reader()`),
+/// as is the case of most binary formats (MessagePack, CBOR, etc.)
+fn check_owned_round_trip<T>(value: T) -> Result<()>
+where
+    T: Debug + PartialEq + Serialize + for<'de> Deserialize<'de>
+{
+    let json_value = serde_json::to_value(&value)?;
+    assert_eq!(serde_json::from_value::<T>(json_value.clone())?, value);
+
+    let json_str = serde_json::to_vec(&json_value)?;
+    assert_eq!(serde_json::from_reader::<_, T>(json_str.as_slice())?, value);
+    Ok(())
+}
+
+#[test]
+fn test_serde_owned_deserialization() -> Result<()> {
+    init();
+
+    check_owned_round_trip(Name::new("eosio.token")?)?;
+    check_owned_round_trip(SymbolCode::from_str("EOS")?)?;
+    check_owned_round_trip(Symbol::from_str("4,EOS")?)?;
+    check_owned_round_trip(Asset::from_str("1.0000 EOS")?)?;
+    check_owned_round_trip(TimePoint::from_str("2024-10-14T12:34:56.789")?)?;
+    check_owned_round_trip(TimePointSec::from_str("2024-10-14T12:34:56")?)?;
+    check_owned_round_trip(Bytes::from_hex("0102fffe")?)?;
+    check_owned_round_trip(Checksum256::from_hex("d0a1e5b7a98fc1b49dd6d0b7e1f1e0d7ab4f1e8e0aa6727d855ea47a1ba6e90f")?)?;
+
+    let key = PrivateKey::new("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3")?;
+    check_owned_round_trip(key.clone())?;
+    check_owned_round_trip(PublicKey::from_private_key(&key))?;
+    check_owned_round_trip(Signature::from_str(
+        "SIG_K1_Kg2UKjXTX48gw2wWH4zmsZmWu3yarcfC21Bd9JPj7QoDURqiAacCHmtExPk3syPb2tFLsp1R4ttXLXgr7FYgDvKPC5RCkx"
+    )?)?;
+
+    // `SerializeEnum` types are deserialized using a sequence visitor
+    check_owned_round_trip(kudu::ReceiptTransaction::Id(
+        Checksum256::from_hex("d0a1e5b7a98fc1b49dd6d0b7e1f1e0d7ab4f1e8e0aa6727d855ea47a1ba6e90f")?
+    ))?;
+
+    // 128-bit integers are serialized as strings, so they can be parsed by any JSON parser
+    #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+    struct Int128 {
+        #[serde(with = "kudu::convert::serde_int128")]
+        unsigned: u128,
+        #[serde(with = "kudu::convert::serde_int128")]
+        signed: i128,
+    }
+    let ints = Int128 { unsigned: u128::MAX, signed: i128::MIN };
+    assert_eq!(serde_json::to_value(&ints)?, json!({
+        "unsigned": u128::MAX.to_string(),
+        "signed": i128::MIN.to_string(),
+    }));
+    check_owned_round_trip(ints)?;
+    let ints: Int128 = serde_json::from_value(json!({"unsigned": 42, "signed": -1}))?;
+    assert_eq!(ints, Int128 { unsigned: 42, signed: -1 });
+    #[cfg(feature = "arbitrary-precision")]
+    {
+        let ints: Int128 = serde_json::from_str(r#"{"unsigned": 42, "signed": -170141183460469231731687303715884105728}"#)?;
+        assert_eq!(ints, Int128 { unsigned: 42, signed: i128::MIN });
+    }
+
+    Ok(())
+}
+
+
+// =============================================================================
+//
+//     Minimal non human-readable serde format, used to check that types with a
+//     string representation are serialized as binary data in such formats
+//
+// =============================================================================
+
+mod compact {
+    use std::fmt;
+
+    use serde::{de, forward_to_deserialize_any, ser, Serialize};
+    use serde::ser::Impossible;
+
+    /// Value produced by the `Serializer`, only supports the kinds of values needed
+    /// by the types we want to test.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Compact {
+        U64(u64),
+        Bytes(Vec<u8>),
+        Str(String),
+    }
+
+    #[derive(Debug)]
+    pub struct Error(String);
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.0) }
+    }
+
+    impl std::error::Error for Error {}
+
+    impl ser::Error for Error {
+        fn custom<T: fmt::Display>(msg: T) -> Self { Error(msg.to_string()) }
+    }
+
+    impl de::Error for Error {
+        fn custom<T: fmt::Display>(msg: T) -> Self { Error(msg.to_string()) }
+    }
+
+    pub struct Serializer;
+
+    macro_rules! unsupported {
+        ($($method:ident($($arg:ty),*) -> $ret:ty;)*) => {
+            $(
+                fn $method(self, $(_: $arg),*) -> Result<$ret, Error> {
+                    Err(Error(format!("unsupported: {}", stringify!($method))))
+                }
+            )*
+        }
+    }
+
+    type Unsupported = Impossible<Compact, Error>;
+
+    impl ser::Serializer for Serializer {
+        type Ok = Compact;
+        type Error = Error;
+        type SerializeSeq = Unsupported;
+        type SerializeTuple = Unsupported;
+        type SerializeTupleStruct = Unsupported;
+        type SerializeTupleVariant = Unsupported;
+        type SerializeMap = Unsupported;
+        type SerializeStruct = Unsupported;
+        type SerializeStructVariant = Unsupported;
+
+        fn is_human_readable(&self) -> bool { false }
+
+        fn serialize_u64(self, v: u64) -> Result<Compact, Error> { Ok(Compact::U64(v)) }
+        fn serialize_bytes(self, v: &[u8]) -> Result<Compact, Error> { Ok(Compact::Bytes(v.to_vec())) }
+        fn serialize_str(self, v: &str) -> Result<Compact, Error> { Ok(Compact::Str(v.to_string())) }
+
+        unsupported! {
+            serialize_bool(bool) -> Compact;
+            serialize_i8(i8) -> Compact;
+            serialize_i16(i16) -> Compact;
+            serialize_i32(i32) -> Compact;
+            serialize_i64(i64) -> Compact;
+            serialize_u8(u8) -> Compact;
+            serialize_u16(u16) -> Compact;
+            serialize_u32(u32) -> Compact;
+            serialize_f32(f32) -> Compact;
+            serialize_f64(f64) -> Compact;
+            serialize_char(char) -> Compact;
+            serialize_none() -> Compact;
+            serialize_unit() -> Compact;
+            serialize_unit_struct(&'static str) -> Compact;
+            serialize_unit_variant(&'static str, u32, &'static str) -> Compact;
+            serialize_seq(Option<usize>) -> Unsupported;
+            serialize_tuple(usize) -> Unsupported;
+            serialize_tuple_struct(&'static str, usize) -> Unsupported;
+            serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Unsupported;
+            serialize_map(Option<usize>) -> Unsupported;
+            serialize_struct(&'static str, usize) -> Unsupported;
+            serialize_struct_variant(&'static str, u32, &'static str, usize) -> Unsupported;
+        }
+
+        fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Compact, Error> {
+            Err(Error("unsupported: serialize_some".to_string()))
+        }
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<Compact, Error> {
+            value.serialize(self)
+        }
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, _: u32, _: &'static str, _: &T)
+                                                            -> Result<Compact, Error> {
+            Err(Error("unsupported: serialize_newtype_variant".to_string()))
+        }
+    }
+
+    impl<'de> de::Deserializer<'de> for Compact {
+        type Error = Error;
+
+        fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self {
+                Compact::U64(n) => visitor.visit_u64(n),
+                Compact::Bytes(b) => visitor.visit_byte_buf(b),
+                Compact::Str(s) => visitor.visit_string(s),
+            }
+        }
+
+        fn is_human_readable(&self) -> bool { false }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+}
+
+fn check_compact_round_trip<T>(value: T, expected: compact::Compact) -> Result<()>
+where
+    T: Debug + PartialEq + Serialize + for<'de> Deserialize<'de>
+{
+    let serialized = value.serialize(compact::Serializer)?;
+    assert_eq!(serialized, expected);
+    assert_eq!(T::deserialize(serialized)?, value);
+    Ok(())
+}
+
+#[test]
+fn test_serde_non_human_readable() -> Result<()> {
+    init();
+    use compact::Compact;
+
+    let name = Name::new("eosio.token")?;
+    check_compact_round_trip(name, Compact::U64(name.as_u64()))?;
+
+    let checksum = Checksum256::from_hex("d0a1e5b7a98fc1b49dd6d0b7e1f1e0d7ab4f1e8e0aa6727d855ea47a1ba6e90f")?;
+    check_compact_round_trip(checksum.clone(), Compact::Bytes(checksum.0.to_vec()))?;
+
+    let key = PrivateKey::new("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3")?;
+    let pubkey = PublicKey::from_private_key(&key);
+    let sig = Signature::from_str(
+        "SIG_K1_Kg2UKjXTX48gw2wWH4zmsZmWu3yarcfC21Bd9JPj7QoDURqiAacCHmtExPk3syPb2tFLsp1R4ttXLXgr7FYgDvKPC5RCkx"
+    )?;
+
+    // crypto data is stored with its key type as first byte, as in its ABI representation
+    check_compact_round_trip(key.clone(), Compact::Bytes(kudu::to_bin(&key).to_vec()))?;
+    check_compact_round_trip(pubkey.clone(), Compact::Bytes(kudu::to_bin(&pubkey).to_vec()))?;
+    check_compact_round_trip(sig.clone(), Compact::Bytes(kudu::to_bin(&sig).to_vec()))?;
+    assert_eq!(kudu::to_bin(&pubkey).len(), 34);
+
+    // wrong sizes are rejected
+    assert!(Checksum256::deserialize(Compact::Bytes(vec![0; 31])).is_err());
+    assert!(PublicKey::deserialize(Compact::Bytes(vec![0; 35])).is_err());
+
+    // human-readable formats still use the string representation
+    assert_eq!(serde_json::to_value(&pubkey)?, json!(pubkey.to_string()));
+    assert_eq!(serde_json::to_value(name)?, json!("eosio.token"));
+
+    Ok(())
+}
+
+#[test]
+fn test_fixed_size_arrays() -> Result<()> {
+    init();
+
+    // mirrors a C++ struct with `std::array` fields
+    #[derive(Debug, PartialEq, Serialize, Deserialize, ABISerializable)]
+    struct Fixed {
+        hash: [u8; 4],
+        owners: [Name; 2],
+    }
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.2",
+        "structs": [{"name": "fixed", "base": "", "fields": [
+            {"name": "hash", "type": "uint8[4]"},
+            {"name": "owners", "type": "name[2]"}
+        ]}]
+    }"#)?;
+
+    let value = Fixed { hash: [1, 2, 3, 4], owners: [Name::new("alice")?, Name::new("bob")?] };
+    let bin = kudu::to_bin(&value);
+    assert_eq!(bin.to_hex(), "0401020304020000000000855c340000000000000e3d");
+    assert_eq!(kudu::from_bin::<Fixed>(&bin)?, value);
+
+    // the ABI serializer produces the same binary data
+    let json = serde_json::to_value(&value)?;
+    assert_eq!(json, json!({"hash": [1, 2, 3, 4], "owners": ["alice", "bob"]}));
+    assert_eq!(abi.variant_to_binary("fixed", &json)?, bin);
+    assert_eq!(abi.binary_to_variant("fixed", bin)?, json);
+
+    // the number of items needs to match the size of the array
+    assert!(abi.variant_to_binary("fixed", &json!({"hash": [1, 2, 3], "owners": ["alice", "bob"]})).is_err());
+    let wrong_size = Bytes::from_hex("03010203020000000000855c340000000000000e3d")?;
+    assert!(abi.binary_to_variant("fixed", wrong_size.clone()).is_err());
+    assert!(kudu::from_bin::<Fixed>(&wrong_size).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_optional_fields() -> Result<()> {
+    init();
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, ABISerializable)]
+    struct Offer {
+        owner: Name,
+        price: Option<Asset>,
+        bidders: Option<Vec<Name>>,
+        expires: Option<TimePointSec>,
+    }
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.2",
+        "structs": [{"name": "offer", "base": "", "fields": [
+            {"name": "owner", "type": "name"},
+            {"name": "price", "type": "asset?"},
+            {"name": "bidders", "type": "name[]?"},
+            {"name": "expires", "type": "time_point_sec?"}
+        ]}]
+    }"#)?;
+
+    let values = [
+        Offer { owner: Name::new("alice")?, price: None, bidders: None, expires: None },
+        Offer {
+            owner: Name::new("alice")?,
+            price: Some(Asset::from_str("1.0000 EOS")?),
+            bidders: Some(vec![Name::new("bob")?]),
+            expires: Some(TimePointSec::from_str("2025-01-01T00:00:00")?),
+        },
+    ];
+
+    let bin = kudu::to_bin(&values[0]);
+    assert_eq!(bin.to_hex(), "0000000000855c34000000");
+
+    for value in values {
+        let bin = kudu::to_bin(&value);
+        assert_eq!(kudu::from_bin::<Offer>(&bin)?, value);
+
+        // the ABI serializer produces the same binary data
+        let json = serde_json::to_value(&value)?;
+        assert_eq!(abi.variant_to_binary("offer", &json)?, bin);
+        assert_eq!(abi.binary_to_variant("offer", bin)?, json);
+    }
+
+    Ok(())
+}
+
+// the derive macros need to work when `kudu` is not in scope under that name, so
+// shadow it with an empty module here
+mod renamed_crate {
+    #![allow(dead_code)]
+    mod kudu {}
+
+    mod reexport {
+        pub use ::kudu as antelope;
+    }
+
+    use reexport::antelope::{ABISerializable, AccountName, SerializeEnum, contract};
+
+    #[contract(account = "eosio.token", name = "transfer", crate = "reexport::antelope")]
+    #[derive(Debug, PartialEq, ABISerializable)]
+    #[kudu(crate = "reexport::antelope")]
+    pub struct Transfer {
+        pub from: AccountName,
+        pub to: AccountName,
+    }
+
+    #[derive(Debug, PartialEq, SerializeEnum, ABISerializable)]
+    #[kudu(crate = "reexport::antelope")]
+    pub enum Value {
+        Int(u32),
+        Account(AccountName),
+    }
+
+    #[test]
+    fn test_renamed_crate() -> color_eyre::Result<()> {
+        use reexport::antelope::{Contract, from_bin, to_bin};
+
+        let transfer = Transfer { from: AccountName::new("alice")?, to: AccountName::new("bob")? };
+        assert_eq!(from_bin::<Transfer>(&to_bin(&transfer))?, transfer);
+        assert_eq!(Transfer::name().to_string(), "transfer");
+
+        let value = Value::Account(AccountName::new("alice")?);
+        assert_eq!(from_bin::<Value>(&to_bin(&value))?, value);
+        assert_eq!(serde_json::to_value(&value)?, serde_json::json!(["account", "alice"]));
+        assert_eq!(serde_json::from_value::<Value>(serde_json::json!(["int", 3]))?, Value::Int(3));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_serde_toml_round_trip() -> Result<()> {
+    init();
+
+    // TOML gives owned strings to the `Deserialize` implementations, check that we
+    // can go through a data format other than JSON
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct NativeTypes {
+        name: Name,
+        symbol: Symbol,
+        asset: Asset,
+        time: TimePoint,
+        time_sec: TimePointSec,
+        checksum: Checksum256,
+        public_key: PublicKey,
+        signature: Signature,
+        receipt_trx: kudu::ReceiptTransaction,
+    }
+
+    let checksum = Checksum256::from_hex("d0a1e5b7a98fc1b49dd6d0b7e1f1e0d7ab4f1e8e0aa6727d855ea47a1ba6e90f")?;
+    let key = PrivateKey::new("5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3")?;
+    let value = NativeTypes {
+        name: Name::new("eosio.token")?,
+        symbol: Symbol::from_str("4,EOS")?,
+        asset: Asset::from_str("1.0000 EOS")?,
+        time: TimePoint::from_str("2024-10-14T12:34:56.789")?,
+        time_sec: TimePointSec::from_str("2024-10-14T12:34:56")?,
+        checksum: checksum.clone(),
+        public_key: PublicKey::from_private_key(&key),
+        signature: Signature::from_str(
+            "SIG_K1_Kg2UKjXTX48gw2wWH4zmsZmWu3yarcfC21Bd9JPj7QoDURqiAacCHmtExPk3syPb2tFLsp1R4ttXLXgr7FYgDvKPC5RCkx"
+        )?,
+        receipt_trx: kudu::ReceiptTransaction::Id(checksum),
+    };
+
+    let repr = toml::to_string(&value)?;
+    assert!(repr.contains(r#"asset = "1.0000 EOS""#), "{repr}");
+    assert_eq!(toml::from_str::<NativeTypes>(&repr)?, value);
+
+    Ok(())
+}
@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::str::FromStr;
-use std::sync::Once;
+use std::sync::{Arc, Once};
 
 use color_eyre::eyre::Result;
+use hex::FromHexError;
 use serde_json::{json, Value as JsonValue};
 
 use kudu::*;
@@ -331,6 +332,30 @@ fn abi_type_loop() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn abi_typedef_depth() -> Result<()> {
+    init();
+
+    // `t0` -> `t1` -> ... -> `t{depth}` -> `uint8`
+    let typedef_chain = |depth: usize| {
+        let types: Vec<_> = (0..=depth).map(|i| match i {
+            i if i == depth => json!({"new_type_name": format!("t{i}"), "type": "uint8"}),
+            i => json!({"new_type_name": format!("t{i}"), "type": format!("t{}", i + 1)}),
+        }).collect();
+        json!({"version": "eosio::abi/1.1", "types": types}).to_string()
+    };
+
+    let abi = ABI::from_str(&typedef_chain(config::MAX_TYPEDEF_DEPTH - 1))?;
+    assert!(abi.is_type("t0".into()));
+    assert_eq!(abi.resolve_type("t0".into())?.as_str(), "uint8");
+    verify_byte_round_trip(&abi, "t0", &json!(42))?;
+
+    let result = ABI::from_str(&typedef_chain(config::MAX_TYPEDEF_DEPTH + 1));
+    assert!(matches!(result, Err(ABIError::TypedefDepthError { .. })), "{result:?}");
+
+    Ok(())
+}
+
 #[test]
 fn abi_std_optional() -> Result<()> {
     init();
@@ -879,3 +904,569 @@ fn serialize_optional_struct_type() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn flattened_struct() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(r#"
+    {
+        "version": "eosio::abi/1.0",
+        "types": [
+            {"new_type_name": "s3_alias", "type": "s3"}
+        ],
+        "structs": [
+            {"name": "s1", "base": "", "fields": [
+                {"name": "i0", "type": "int8"}
+            ]},
+            {"name": "s2", "base": "s1", "fields": [
+                {"name": "i1", "type": "int8"}
+            ]},
+            {"name": "s3", "base": "s2", "fields": [
+                {"name": "i2", "type": "int8"},
+                {"name": "i3", "type": "int8$"}
+            ]}
+        ]
+    }
+    "#)?;
+
+    let s3 = abi.flattened_struct("s3_alias")?;
+    assert_eq!(s3.name, "s3");
+    assert!(s3.base.is_empty());
+    let fields: Vec<_> = s3.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(fields, ["i0", "i1", "i2", "i3"]);
+
+    assert!(abi.flattened_struct("int8").is_err());
+
+    verify_round_trip(&abi, "s3", &json!({"i0": 1, "i1": 2, "i2": 3}), "010203")?;
+    verify_round_trip(&abi, "s3", &json!({"i0": 1, "i1": 2, "i2": 3, "i3": 4}), "01020304")?;
+
+    Ok(())
+}
+
+#[test]
+fn abi_contents() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(kudu::abi::data::EOSIO_TOKEN_ABI)?;
+
+    let actions: Vec<_> = abi.actions().map(|a| a.name.to_string()).collect();
+    assert_eq!(actions, ["close", "create", "issue", "issuefixed", "open", "retire", "setmaxsupply", "transfer"]);
+    let transfer = abi.actions().find(|a| a.name == Name::constant("transfer")).unwrap();
+    assert_eq!(transfer.type_, TypeName("transfer"));
+    assert_eq!(transfer.result_type, None);
+
+    let tables: Vec<_> = abi.tables().map(|t| (t.name.to_string(), t.type_.as_str())).collect();
+    assert_eq!(tables, [("accounts".to_string(), "account"), ("stat".to_string(), "currency_stats")]);
+
+    let structs: Vec<_> = abi.structs().map(|s| s.name.as_str()).collect();
+    assert_eq!(structs.len(), 10);
+    assert!(structs.is_sorted());
+
+    let abi = ABI::from_str(r#"
+    {
+        "version": "eosio::abi/1.2",
+        "types": [{"new_type_name": "v_alias", "type": "v"}],
+        "structs": [{"name": "s", "base": "", "fields": [{"name": "i0", "type": "int8"}]}],
+        "actions": [{"name": "act", "type": "s", "ricardian_contract": ""}],
+        "variants": [{"name": "v", "types": ["int8", "s"]}],
+        "action_results": [{"name": "act", "result_type": "v"}]
+    }
+    "#)?;
+
+    let act = abi.actions().next().unwrap();
+    assert_eq!(act.result_type, Some(TypeName("v")));
+    assert_eq!(abi.variants().map(|v| v.types.clone()).collect::<Vec<_>>(), [["int8", "s"]]);
+    assert_eq!(abi.typedefs().collect::<Vec<_>>(), [(TypeName("v_alias"), TypeName("v"))]);
+    assert_eq!(abi.tables().count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn diff_binary() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(kudu::abi::data::TRANSACTION_ABI)?;
+    let trx = |delay_sec: u32, actor: &str, signed_data: Option<&str>| {
+        let mut action = json!({
+            "account": "eosio.token",
+            "name": "transfer",
+            "authorization": [{"actor": actor, "permission": "active"}],
+            "data": "0000000000855c34",
+        });
+        if let Some(bytes) = signed_data { action["data"] = json!(bytes); }
+        json!({
+            "expiration": "2024-01-01T00:00:00",
+            "ref_block_num": 1,
+            "ref_block_prefix": 2,
+            "max_net_usage_words": 0,
+            "max_cpu_usage_ms": 0,
+            "delay_sec": delay_sec,
+            "context_free_actions": [],
+            "actions": [action],
+            "transaction_extensions": [],
+        })
+    };
+
+    let a = abi.variant_to_binary("transaction", &trx(0, "alice", None))?;
+    assert!(abi.diff_binary("transaction", &a, &a)?.is_empty());
+
+    let b = abi.variant_to_binary("transaction", &trx(10, "bob", Some("00")))?;
+    let diff = abi.diff_binary("transaction", &a, &b)?;
+    let paths: Vec<_> = diff.differences.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(paths, ["delay_sec", "actions[0].authorization[0].actor", "actions[0].data"]);
+    assert_eq!(diff.differences[0].to_string(), "delay_sec: 0 != 10");
+
+    // trailing data is reported as well
+    let mut c = a.to_vec();
+    c.push(0xff);
+    let diff = abi.diff_binary("transaction", &a, &c)?;
+    assert!(diff.differences.is_empty() && !diff.is_empty());
+    assert_eq!(diff.to_string(), "leftover data: \"\" != \"ff\"\n");
+
+    // arrays with different lengths
+    let abi = ABI::from_str(r#"{"version": "eosio::abi/1.1", "structs": [
+        {"name": "s", "base": "", "fields": [{"name": "v", "type": "int8[]"}]}
+    ]}"#)?;
+    let a = abi.variant_to_binary("s", &json!({"v": [1, 2]}))?;
+    let b = abi.variant_to_binary("s", &json!({"v": [1, 3, 4]}))?;
+    assert_eq!(abi.diff_binary("s", &a, &b)?.to_string(), "v[1]: 2 != 3\nv[2]: 4 (missing in a)\n");
+
+    Ok(())
+}
+
+#[test]
+fn describe_type() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.1",
+        "types": [{"new_type_name": "account", "type": "name"}],
+        "structs": [
+            {"name": "base", "base": "", "fields": [{"name": "owner", "type": "account"}]},
+            {"name": "node", "base": "base", "fields": [
+                {"name": "value", "type": "payload"},
+                {"name": "children", "type": "node[]"},
+                {"name": "memo", "type": "string?$"}
+            ]}
+        ],
+        "variants": [{"name": "payload", "types": ["uint64", "asset"]}]
+    }"#)?;
+
+    let description = abi.describe("node")?;
+    assert_eq!(description.to_string(), "\
+struct node
+  owner: name
+  value: variant payload
+    | uint64
+    | asset
+  children: array of node (recursive)
+  memo: optional string (binary extension)
+");
+
+    let abi::TypeDescription::Struct { fields, .. } = &description else { panic!("not a struct") };
+    assert_eq!(fields[0].type_, "account");
+    assert_eq!(serde_json::to_value(&fields[0])?,
+               json!({"name": "owner", "type": "account", "description": {"kind": "builtin", "name": "name"}}));
+
+    assert!(abi.describe("unknown").is_err());
+    Ok(())
+}
+
+#[test]
+fn encode_cache() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(abi::data::TRANSACTION_ABI)?;
+    let cached = abi.clone().with_encode_cache(1);
+    assert!(abi.encode_cache_stats().is_none());
+
+    let action = |actor| json!({
+        "account": "eosio.token",
+        "name": "transfer",
+        "authorization": [{"actor": actor, "permission": "active"}, {"permission": "active", "actor": actor}],
+        "data": "",
+    });
+
+    // only the permission levels are cached, not the action which contains an array.
+    // The second permission level is found in the cache, even with its keys in another order
+    let expected = abi.variant_to_binary("action", &action("alice"))?;
+    assert_eq!(cached.variant_to_binary("action", &action("alice"))?, expected);
+    let stats = cached.encode_cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+    assert_eq!(cached.variant_to_binary("action", &action("alice"))?, expected);
+    assert_eq!(cached.encode_cache_stats().unwrap().hits, 3);
+
+    // older entries are evicted once the cache is full
+    assert_eq!(cached.variant_to_binary("action", &action("bob"))?, abi.variant_to_binary("action", &action("bob"))?);
+    let stats = cached.encode_cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries, stats.evictions), (4, 2, 1, 1));
+    assert_eq!(stats.hit_ratio(), 4.0 / 6.0);
+
+    cached.clear_encode_cache();
+    assert_eq!(cached.encode_cache_stats().unwrap().entries, 0);
+    Ok(())
+}
+
+#[test]
+fn parsed_cache() -> Result<()> {
+    init();
+
+    // the eosio ABI is big and has ricardian clauses, make sure they round-trip
+    let definition = ABIDefinition::from_str(abi::data::EOSIO_ABI)?;
+    assert!(!definition.ricardian_clauses.is_empty());
+    let mut packed = Bytes::new();
+    definition.encode(&mut packed)?;
+    assert_eq!(ABIDefinition::decode(&mut ByteStream::from(&packed))?, definition);
+    let hex_abi = packed.to_hex();
+
+    // the hex and binary versions share the same cache entry
+    let abi = ABI::from_hex_abi_cached(&hex_abi)?;
+    assert!(Arc::ptr_eq(&abi, &ABI::from_hex_abi_cached(&hex_abi)?));
+    assert!(Arc::ptr_eq(&abi, &ABI::from_bin_abi_cached(&packed)?));
+    assert_eq!(abi.structs().count(), ABI::from_hex_abi(&hex_abi)?.structs().count());
+    assert!(ABI::parsed_cache_stats().hits >= 2);
+
+    // invalid characters are reported at their position in the whole input
+    let mut invalid = hex_abi.clone();
+    invalid.replace_range(20000..20001, "z");
+    assert!(matches!(ABI::from_hex_abi_cached(&invalid),
+                     Err(ABIError::HexABIError { source: FromHexError::InvalidHexCharacter { c: 'z', index: 20000 } })));
+    assert!(matches!(ABI::from_hex_abi_cached(&hex_abi[1..]),
+                     Err(ABIError::HexABIError { source: FromHexError::OddLength })));
+    Ok(())
+}
+
+#[test]
+fn builtin_types() -> Result<()> {
+    init();
+
+    let definition = ABIDefinition::from_str(r#"{
+        "version": "eosio::abi/1.1",
+        "types": [{"new_type_name": "rgb", "type": "uint24"}],
+        "structs": [{"name": "palette", "base": "", "fields": [
+            {"name": "main", "type": "rgb"},
+            {"name": "others", "type": "uint24[]"},
+            {"name": "background", "type": "uint24?"}
+        ]}]
+    }"#)?;
+
+    // unknown types are rejected when validating the ABI
+    assert!(matches!(ABI::from_definition(&definition), Err(ABIError::IntegrityError { .. })));
+
+    let abi = ABI::new()
+        .with_builtin_type("uint24",
+            |obj, ds| {
+                let n = obj.as_u64().filter(|&n| n < 1 << 24).ok_or("not a uint24")?;
+                ds.write_bytes(&n.to_le_bytes()[..3]);
+                Ok(())
+            },
+            |ds| {
+                let b = ds.read_bytes(3)?;
+                Ok(json!(u32::from_le_bytes([b[0], b[1], b[2], 0])))
+            })
+        .with_definition(&definition)?;
+    assert!(abi.has_builtin_type("uint24"));
+
+    let palette = json!({"main": 0x123456, "others": [1, 2], "background": null});
+    let encoded = abi.variant_to_binary("palette", &palette)?;
+    assert_eq!(encoded.to_hex(), "5634120201000002000000");
+    assert_eq!(abi.binary_to_variant("palette", encoded)?, palette);
+    assert_eq!(abi.describe("rgb")?.to_string(), "uint24\n");
+
+    // errors from the custom functions are reported with the name of the type
+    let invalid = json!({"main": 1 << 24, "others": []});
+    assert!(matches!(abi.variant_to_binary("palette", &invalid),
+                     Err(ABIError::BuiltinTypeError { typename, .. }) if typename == "uint24"));
+    assert!(matches!(abi.binary_to_variant("palette", Bytes::from(vec![1, 2])),
+                     Err(ABIError::BuiltinTypeError { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn encode_options() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.1",
+        "structs": [{"name": "s", "base": "", "fields": [
+            {"name": "a", "type": "uint8"},
+            {"name": "b", "type": "string?"}
+        ]}]
+    }"#)?;
+    assert_eq!(abi.encode_options(), abi::EncodeOptions::default());
+
+    let with_unknown = json!({"a": 1, "b": "x", "c": 2});
+    let missing_optional = json!({"a": 1});
+
+    // default behavior matches Spring: unknown fields are ignored and missing
+    // optionals are encoded as null
+    assert_eq!(abi.variant_to_binary("s", &with_unknown)?, abi.variant_to_binary("s", &json!({"a": 1, "b": "x"}))?);
+    assert_eq!(abi.variant_to_binary("s", &missing_optional)?.to_hex(), "0100");
+    assert!(abi.variant_to_binary("s", &json!([1, null, 3])).is_ok());
+
+    let strict = abi.clone().with_encode_options(abi::EncodeOptions {
+        deny_unknown_fields: true,
+        allow_missing_optionals: false,
+    });
+    assert!(matches!(strict.variant_to_binary("s", &with_unknown), Err(ABIError::EncodeError { message, .. })
+                     if message.contains("unknown field 'c'")));
+    assert!(matches!(strict.variant_to_binary("s", &missing_optional), Err(ABIError::EncodeError { message, .. })
+                     if message.contains("missing field 'b'")));
+    assert!(strict.variant_to_binary("s", &json!([1, null, 3])).is_err());
+    assert_eq!(strict.variant_to_binary("s", &json!({"a": 1, "b": null}))?.to_hex(), "0100");
+
+    Ok(())
+}
+
+#[test]
+fn decode_with_offsets() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {"name": "point", "base": "", "fields": [{"name": "x", "type": "uint8"}, {"name": "y", "type": "uint16"}]},
+            {"name": "s", "base": "", "fields": [
+                {"name": "ids", "type": "uint8[]"},
+                {"name": "shape", "type": "shape"},
+                {"name": "a/b", "type": "point?"},
+                {"name": "ext", "type": "uint8$"}
+            ]}
+        ],
+        "variants": [{"name": "shape", "types": ["uint8", "point"]}]
+    }"#)?;
+
+    let value = json!({"ids": [1, 2], "shape": ["point", {"x": 3, "y": 4}], "a/b": null});
+    let bin = abi.variant_to_binary("s", &value)?;
+    assert_eq!(bin.to_hex(), "0201020103040000");
+
+    let (decoded, offsets) = abi.decode_variant_with_offsets(&mut bin.view(), "s")?;
+    assert_eq!(decoded, value);
+
+    let offsets: Vec<_> = offsets.iter().map(|(pointer, span)| (pointer.as_str(), span.range())).collect();
+    assert_eq!(offsets, [
+        ("", 0..8),
+        ("/a~1b", 7..8),
+        ("/ids", 0..3),
+        ("/ids/0", 1..2),
+        ("/ids/1", 2..3),
+        ("/shape", 3..7),
+        ("/shape/0", 3..4),
+        ("/shape/1", 4..7),
+        ("/shape/1/x", 4..5),
+        ("/shape/1/y", 5..7),
+    ]);
+
+    // the offsets are relative to the start of the underlying data
+    let prefixed = [[0xff].as_slice(), &bin].concat();
+    let mut ds = ByteStream::from(&prefixed);
+    ds.read_byte()?;
+    let (_, offsets) = abi.decode_variant_with_offsets(&mut ds, "s")?;
+    assert_eq!(offsets["/shape/1/y"].range(), 6..8);
+
+    Ok(())
+}
+
+#[test]
+fn duplicate_fields() -> Result<()> {
+    init();
+
+    let duplicate_field_abi = r#"{
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {"name": "s", "base": "", "fields": [
+                {"name": "a", "type": "int8"},
+                {"name": "a", "type": "string"}
+            ]}
+        ]
+    }"#;
+    let result = ABI::from_str(duplicate_field_abi);
+    check_integrity_error!(result, "duplicate field name 's::a'");
+
+    let duplicate_base_field_abi = r#"{
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {"name": "base", "base": "", "fields": [{"name": "a", "type": "int8"}]},
+            {"name": "s", "base": "base", "fields": [{"name": "a", "type": "int8"}]}
+        ]
+    }"#;
+    let result = ABI::from_str(duplicate_base_field_abi);
+    check_integrity_error!(result, "duplicate field name 's::a'");
+
+    Ok(())
+}
+
+#[test]
+fn field_ordering() -> Result<()> {
+    init();
+
+    // use enough fields (in non-alphabetical order) so that the ordering of the
+    // JSON object cannot be preserved by chance
+    let names: Vec<String> = (0..20).rev().map(|i| format!("f{i}")).collect();
+    let fields: Vec<_> = names.iter().map(|name| json!({"name": name, "type": "uint8"})).collect();
+    let abi = ABI::from_str(&json!({
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {"name": "base", "base": "", "fields": [{"name": "zz", "type": "uint8"}]},
+            {"name": "s", "base": "base", "fields": fields},
+        ],
+    }).to_string())?;
+
+    // fields are given in alphabetical order, encoding follows the struct definition
+    let mut value = JsonMap::new();
+    value.insert("zz".to_string(), json!(100));
+    for (i, name) in names.iter().enumerate() {
+        value.insert(name.clone(), json!(i));
+    }
+    let value = JsonValue::Object(value);
+    let mut sorted = value.as_object().unwrap().clone();
+    sorted.sort_keys();
+    let bin = abi.variant_to_binary("s", &JsonValue::Object(sorted))?;
+    assert_eq!(bin.as_ref()[..3], [100, 0, 1]);
+
+    // decoding gives the fields in the order of the struct definition
+    let decoded = abi.binary_to_variant("s", bin)?;
+    let keys: Vec<_> = decoded.as_object().unwrap().keys().collect();
+    let expected: Vec<_> = value.as_object().unwrap().keys().collect();
+    assert_eq!(keys, expected);
+    assert_eq!(decoded.to_string(), value.to_string());
+
+    Ok(())
+}
+
+#[test]
+fn unknown_variant_tags() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {"name": "s", "base": "", "fields": [
+                {"name": "a", "type": "uint8"},
+                {"name": "v", "type": "v1"}
+            ]}
+        ],
+        "variants": [{"name": "v1", "types": ["int8", "string"]}]
+    }"#)?;
+
+    // by default, unknown tags are an error
+    let data = Bytes::from_hex("2a0301020304")?;
+    let result = abi.binary_to_variant("s", data.clone());
+    check_error!(result, ABIError::DecodeError { .. }, "deserialized invalid tag 3 for variant v1");
+
+    // in tolerant mode, they are annotated and the remaining data is kept as hex
+    let abi = abi.with_decode_options(abi::DecodeOptions { tolerate_unknown_variants: true, ..Default::default() });
+    assert_eq!(abi.binary_to_variant("s", data)?,
+               json!({"a": 42, "v": ["__unknown_3", "01020304"]}));
+    assert_eq!(abi.binary_to_variant("v1", Bytes::from_hex("05")?)?, json!(["__unknown_5", ""]));
+
+    // known tags are decoded as usual
+    assert_eq!(abi.binary_to_variant("s", Bytes::from_hex("2a01026869")?)?,
+               json!({"a": 42, "v": ["string", "hi"]}));
+
+    Ok(())
+}
+
+#[test]
+fn serialization_config() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.1",
+        "structs": [
+            {"name": "s", "base": "", "fields": [
+                {"name": "n", "type": "uint64"},
+                {"name": "i", "type": "int64"},
+                {"name": "b", "type": "bytes"},
+                {"name": "c", "type": "checksum160"}
+            ]}
+        ]
+    }"#)?;
+    let value = json!({
+        "n": 18446744073709551615u64,
+        "i": -5,
+        "b": "c0ffee",
+        "c": "00112233445566778899aabbccddeeff00112233",
+    });
+    let data = abi.variant_to_binary("s", &value)?;
+    assert_eq!(abi.binary_to_variant("s", data.clone())?, value);
+
+    let config = config::SerializationConfig {
+        hex_case: config::HexCase::Upper,
+        quote_u64: true,
+        ..Default::default()
+    };
+    let abi = abi.with_decode_options(abi::DecodeOptions { serialization: Some(config), ..Default::default() });
+    let decoded = abi.binary_to_variant("s", data.clone())?;
+    assert_eq!(decoded, json!({
+        "n": "18446744073709551615",
+        "i": "-5",
+        "b": "C0FFEE",
+        "c": "00112233445566778899AABBCCDDEEFF00112233",
+    }));
+
+    // the alternative representation can be encoded back to the same binary data
+    assert_eq!(abi.variant_to_binary("s", &decoded)?, data);
+
+    Ok(())
+}
+
+#[test]
+fn decode_hooks() -> Result<()> {
+    init();
+
+    let abi = ABI::from_str(r#"{
+        "version": "eosio::abi/1.1",
+        "types": [{"new_type_name": "created_at", "type": "time_point"}],
+        "structs": [
+            {"name": "token", "base": "", "fields": [{"name": "sym", "type": "symbol"}]},
+            {"name": "s", "base": "", "fields": [
+                {"name": "token", "type": "token"},
+                {"name": "others", "type": "symbol[]"},
+                {"name": "maybe", "type": "symbol?"},
+                {"name": "updated", "type": "time_point"},
+                {"name": "created", "type": "created_at"}
+            ]}
+        ]
+    }"#)?;
+    let value = json!({
+        "token": {"sym": "4,EOS"},
+        "others": ["8,WAX"],
+        "maybe": "0,X",
+        "updated": "1970-01-01T00:00:01.500",
+        "created": "1970-01-01T00:00:01.000",
+    });
+    let data = abi.variant_to_binary("s", &value)?;
+
+    let abi = abi
+        .with_decode_hook("symbol", |sym| {
+            let (precision, code) = sym.as_str().and_then(|s| s.split_once(',')).ok_or("invalid symbol")?;
+            Ok(json!({"precision": precision.parse::<u8>()?, "code": code}))
+        })
+        .with_decode_hook("time_point", |tp| {
+            let tp: TimePoint = tp.as_str().ok_or("invalid time_point")?.parse()?;
+            Ok(json!(tp.to_datetime().timestamp_millis()))
+        })
+        .with_decode_hook("created_at", |_| Ok(json!("hidden")));
+    assert!(abi.has_decode_hook("symbol"));
+
+    // hooks apply to nested values, array items and optionals, and aliases take precedence
+    assert_eq!(abi.binary_to_variant("s", data.clone())?, json!({
+        "token": {"sym": {"precision": 4, "code": "EOS"}},
+        "others": [{"precision": 8, "code": "WAX"}],
+        "maybe": {"precision": 0, "code": "X"},
+        "updated": 1500,
+        "created": "hidden",
+    }));
+
+    // errors from the hooks are reported with the name of the type
+    let abi = abi.with_decode_hook("token", |_| Err("no tokens allowed".into()));
+    assert!(matches!(abi.binary_to_variant("s", data),
+                     Err(ABIError::DecodeHookError { typename, .. }) if typename == "token"));
+
+    Ok(())
+}
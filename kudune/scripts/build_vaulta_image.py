@@ -12,6 +12,8 @@ from pyinfra.api import deploy
 from pyinfra import host, logger
 import math
 import re
+import urllib.error
+import urllib.request
 
 SPRING_VERSION = '1.2.2'
 CDT_VERSION = '4.1.1'
@@ -51,6 +53,38 @@ def nproc(required_gb_per_core=None):
     return cpus
 
 
+def spring_package_url(version):
+    spring_package = f'antelope-spring_{version}_{ARCH}.deb'
+    return f'https://github.com/AntelopeIO/spring/releases/download/v{version}/{spring_package}'
+
+
+def cdt_package_url(version):
+    if version.startswith('4.1'):
+        # FIXME: find a better way to do this...
+        cdt_package = f'cdt_{version}-1_{ARCH}.deb'
+    else:
+        cdt_package = f'cdt_{version}_{ARCH}.deb'
+    return f'https://github.com/AntelopeIO/cdt/releases/download/v{version}/{cdt_package}'
+
+
+def package_exists(url):
+    try:
+        urllib.request.urlopen(urllib.request.Request(url, method='HEAD'), timeout=30)
+        return True
+    except urllib.error.URLError:
+        return False
+
+
+# not all versions of Spring and CDT have packages for all archs (eg: arm64),
+# compile them from source when that is the case
+if not COMPILE_SPRING_CDT:
+    for url in [spring_package_url(SPRING_VERSION), cdt_package_url(CDT_VERSION)]:
+        if not package_exists(url):
+            logger.warning(f'No package found at {url}, compiling Spring and CDT instead')
+            COMPILE_SPRING_CDT = True
+            break
+
+
 spring_cdt_source = 'compiled' if COMPILE_SPRING_CDT else 'from package'
 
 logger.warning(f"Installing on: {DISTRO['PRETTY_NAME']} (arch: {ARCH})")
@@ -152,9 +186,7 @@ def compile_spring(git_ref=None):
 
 @deploy('Deploy Antelope Spring')
 def deploy_spring(version=None):
-    spring_package = f'antelope-spring_{version}_{ARCH}.deb'
-    spring_url = f'https://github.com/AntelopeIO/spring/releases/download/v{version}/{spring_package}'
-    apt.deb(src=spring_url)
+    apt.deb(src=spring_package_url(version))
 
 
 @deploy('Compile Antelope CDT')
@@ -187,13 +219,7 @@ def compile_cdt(git_ref=None):
 
 @deploy('Deploy Antelope CDT')
 def deploy_cdt(version=None):
-    if version.startswith('4.1'):
-        # FIXME: find a better way to do this...
-        cdt_package = f'cdt_{version}-1_{ARCH}.deb'
-    else:
-        cdt_package = f'cdt_{version}_{ARCH}.deb'
-    cdt_url = f'https://github.com/AntelopeIO/cdt/releases/download/v{version}/{cdt_package}'
-    apt.deb(src=cdt_url)
+    apt.deb(src=cdt_package_url(version))
 
 
 @deploy('Deploy system contracts')
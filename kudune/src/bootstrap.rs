@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Profiles describing how to bootstrap a chain: which system accounts and contracts
+//! are created, which protocol features are activated and how the system token is
+//! configured, see [`Dune::bootstrap_with_profile()`].
+//!
+//! Built-in profiles are stored as TOML files in the `src/data/profiles` folder.
+//! A user TOML file can override any of their values, using the `base` key to
+//! select the profile it is based on (default: `vaulta`).
+//!
+//! Example:
+//! ```
+//! # use kudune::BootstrapProfile;
+//! let vaulta = BootstrapProfile::builtin("vaulta")?;
+//! assert_eq!(vaulta.token.symbol, "EOS");
+//! # for name in BootstrapProfile::builtin_names() { BootstrapProfile::builtin(name)?; }
+//!
+//! let profile = BootstrapProfile::from_toml(r#"
+//!     base = "vanilla-eos"
+//!     [token]
+//!     symbol = "TST"
+//!     max_supply = "1000.0000"
+//!     initial_supply = "1000.0000"
+//! "#)?;
+//! assert_eq!(profile.token.symbol, "TST");
+//! assert_eq!(profile.token.precision, 4);  // not overridden
+//! assert!(profile.vaulta.is_none());
+//! # Ok::<(), color_eyre::Report>(())
+//! ```
+//!
+//! [`Dune::bootstrap_with_profile()`]: crate::Dune::bootstrap_with_profile
+//!
+
+use color_eyre::eyre::{eyre, OptionExt, Result, WrapErr};
+use serde::Deserialize;
+use toml::{Table, Value};
+
+use kudu::config::VAULTA_FEATURES;
+
+
+/// Name of the profile used when none is specified.
+pub const DEFAULT_PROFILE: &str = "vaulta";
+
+/// Names and contents of the built-in profiles.
+const BUILTIN_PROFILES: &[(&str, &str)] = &[
+    ("vaulta", include_str!("data/profiles/vaulta.toml")),
+    ("vanilla-eos", include_str!("data/profiles/vanilla-eos.toml")),
+    ("savanna-testing", include_str!("data/profiles/savanna-testing.toml")),
+];
+
+
+/// Configuration of the system token, created by `eosio.token` and issued to `eosio`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TokenConfig {
+    pub symbol: String,
+    pub precision: u8,
+    /// Max supply, as a decimal amount without symbol, eg: `"10000000000.0000"`
+    pub max_supply: String,
+    /// Amount issued to `eosio`, as a decimal amount without symbol
+    pub initial_supply: String,
+}
+
+/// Configuration of the Vaulta `core.vaulta` contract.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VaultaConfig {
+    pub account: String,
+    /// Max supply of the `A` token, eg: `"2100000000.0000 A"`
+    pub max_supply: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapProfile {
+    pub description: String,
+    /// Folder inside the container where the compiled system contracts are located
+    pub contracts_dir: String,
+    pub system_accounts: Vec<String>,
+    /// Names of the protocol features to activate, see [`VAULTA_FEATURES`]
+    pub features: Vec<String>,
+    pub token: TokenConfig,
+    /// If set, deploy and initialize the `core.vaulta` contract
+    pub vaulta: Option<VaultaConfig>,
+}
+
+impl BootstrapProfile {
+    /// Return the names of the built-in profiles.
+    pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+        BUILTIN_PROFILES.iter().map(|(name, _)| *name)
+    }
+
+    /// Return the built-in profile with the given name.
+    pub fn builtin(name: &str) -> Result<BootstrapProfile> {
+        Self::from_table(builtin_table(name)?)
+    }
+
+    /// Parse a user profile, whose values override those of the built-in profile
+    /// given by its `base` key (default: `vaulta`). Tables are merged recursively.
+    pub fn from_toml(s: &str) -> Result<BootstrapProfile> {
+        let mut overrides: Table = s.parse().wrap_err("invalid TOML for bootstrap profile")?;
+        let base = match overrides.remove("base") {
+            Some(Value::String(base)) => base,
+            Some(base) => return Err(eyre!("`base` should be a profile name, got: {base}")),
+            None => DEFAULT_PROFILE.to_string(),
+        };
+        let mut table = builtin_table(&base)?;
+        merge(&mut table, overrides);
+        Self::from_table(table)
+    }
+
+    /// Return the digests of the protocol features to activate.
+    pub fn feature_digests(&self) -> Result<Vec<&'static str>> {
+        self.features.iter().map(|name| {
+            VAULTA_FEATURES.iter()
+                .find(|(feature, _)| feature == name)
+                .map(|(_, digest)| *digest)
+                .ok_or_else(|| eyre!("unknown protocol feature: `{name}`"))
+        }).collect()
+    }
+
+    fn from_table(table: Table) -> Result<BootstrapProfile> {
+        let profile: BootstrapProfile = table.try_into().wrap_err("invalid bootstrap profile")?;
+        profile.feature_digests()?;  // make sure all features are known
+        Ok(profile)
+    }
+}
+
+fn builtin_table(name: &str) -> Result<Table> {
+    let (_, contents) = BUILTIN_PROFILES.iter().find(|(n, _)| *n == name)
+        .ok_or_eyre(format!("unknown bootstrap profile: `{name}` (available: {})",
+                            BootstrapProfile::builtin_names().collect::<Vec<_>>().join(", ")))?;
+    Ok(contents.parse()?)
+}
+
+/// Merge the values of `overrides` into `table`, recursing into tables.
+fn merge(table: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(value)) => merge(base, value),
+            (_, value) => { table.insert(key, value); },
+        }
+    }
+}
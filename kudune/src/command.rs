@@ -1,19 +1,94 @@
 // SPDX-FileCopyrightText: 2024-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::process::{self, Output};
+use std::io;
+use std::process::{ExitStatus, Output};
+use std::sync::LazyLock;
+use std::thread;
+use std::time::Duration;
 
 use duct::cmd;
-use serde_json::{Value, json};
-use tracing::{error, trace};
+use regex::Regex;
+use serde_json::Value;
+use snafu::{OptionExt, ResultExt, Snafu};
+use tracing::{debug, trace, warn};
+
+use kudu::with_location;
 
 use crate::{print_streams, util::join_quote};
 
+
+/// Number of times a command is retried by default when the Docker daemon
+/// cannot be reached.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Delay before the first retry, doubled after each attempt.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Messages printed by the Docker client when it cannot talk to the daemon, in
+/// which case the command has not been executed and can safely be retried.
+const TRANSIENT_ERRORS: &[&str] = &[
+    "Cannot connect to the Docker daemon",
+    "error during connect",
+    "TLS handshake timeout",
+];
+
+// safe unwraps, the regexes are valid
+#[allow(clippy::unwrap_used)]
+static NOT_RUNNING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:[Cc]ontainer (\S+) is not running|No such container: (\S+))").unwrap()
+});
+
+#[allow(clippy::unwrap_used)]
+static NO_IMAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:Unable to find image '([^']+)'|No such image: (\S+)|pull access denied for ([^,\s]+))").unwrap()
+});
+
+
+#[with_location]
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum DockerError {
+    #[snafu(display("could not run docker command: `{command}`"))]
+    Spawn { command: String, source: io::Error },
+
+    #[snafu(display("docker image not found: `{image}`"))]
+    ImageNotFound { image: String },
+
+    #[snafu(display("docker container is not running: `{container}`"))]
+    ContainerNotRunning { container: String },
+
+    #[snafu(display("docker container `{container}` is in an unknown state: {state}"))]
+    UnknownState { container: String, state: String },
+
+    #[snafu(display("docker daemon unavailable after {attempts} attempts: {stderr}"))]
+    DaemonUnavailable { attempts: u32, stderr: String },
+
+    #[snafu(display("command `{command}` failed with {status}\n{stderr}"))]
+    CommandFailed { command: String, status: ExitStatus, stdout: String, stderr: String },
+
+    #[snafu(display("invalid output for command `{command}`: {output}"))]
+    InvalidOutput { command: String, output: String },
+
+    #[snafu(display("I/O error on host while {action}"))]
+    HostIo { action: String, source: io::Error },
+}
+
+impl DockerError {
+    /// Return whether this error has been caused by the Docker daemon being
+    /// (temporarily) unreachable.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, DockerError::DaemonUnavailable { .. })
+    }
+}
+
+
 #[derive(Debug)]
 pub struct DockerCommand {
     args: Vec<String>,
     check_status: bool,
     capture_output: bool,
+    retries: u32,
 }
 
 
@@ -24,9 +99,15 @@ impl DockerCommand {
             args: args.iter().map(|x| x.to_string()).collect(),
             check_status: true,
             capture_output: true,
+            retries: DEFAULT_RETRIES,
         }
     }
 
+    /// If `true` (the default), a non-zero exit status of the command is returned
+    /// as a [`DockerError::CommandFailed`] error.
+    ///
+    /// Errors coming from Docker itself (eg: the container not running) are always
+    /// returned as errors.
     pub fn check_status(self, check_status: bool) -> Self {
         DockerCommand { check_status, ..self }
     }
@@ -35,7 +116,41 @@ impl DockerCommand {
         DockerCommand { capture_output, ..self }
     }
 
-    pub fn run(&self) -> Output {
+    /// Set the number of times the command is retried if the Docker daemon cannot
+    /// be reached, with an exponential backoff. Other errors are never retried.
+    pub fn retries(self, retries: u32) -> Self {
+        DockerCommand { retries, ..self }
+    }
+
+    pub fn run(&self) -> Result<Output, DockerError> {
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let output = self.run_once()?;
+            if output.status.success() {
+                return Ok(output);
+            }
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !TRANSIENT_ERRORS.iter().any(|msg| stderr.contains(msg)) {
+                return match self.docker_error(&output) {
+                    Some(err) => Err(err),
+                    None if self.check_status => Err(self.command_failed(&output)),
+                    None => Ok(output),
+                };
+            }
+
+            if attempt > self.retries {
+                return DaemonUnavailableSnafu { attempts: attempt, stderr: stderr.trim() }.fail();
+            }
+            warn!("Docker daemon unavailable, retrying in {delay:?} ({attempt}/{})", self.retries);
+            thread::sleep(delay);
+            delay *= 2;
+        }
+    }
+
+    fn run_once(&self) -> Result<Output, DockerError> {
         let expr = if self.capture_output {
             cmd("docker", &self.args).stdout_capture().stderr_capture()
         }
@@ -45,24 +160,41 @@ impl DockerCommand {
 
         trace!("run command: {:?}", &self.args);
 
-        let output = expr.unchecked().run().unwrap();
+        let output = expr.unchecked().run()
+            .context(SpawnSnafu { command: self.pretty_command() })?;
 
         print_streams!(trace, &output);
 
-        if self.check_status {
-            self.handle_error(&output);
-        }
-        output
+        Ok(output)
     }
 
-    pub fn handle_error(&self, output: &Output) {
-        if !output.status.success() {
-            error!("Error executing docker command:");
-            error!("{:?}", self.args);
-            error!("pretty -->  {}", &self.pretty_command());
-            print_streams!(error, output);
-            process::exit(1);
+    /// Return the error corresponding to a failure of Docker itself (as opposed to
+    /// the command it runs), if it can be recognized from the command output.
+    fn docker_error(&self, output: &Output) -> Option<DockerError> {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        let first_match = |caps: regex::Captures| {
+            caps.iter().skip(1).flatten().next().map(|m| m.as_str().to_string())
+        };
+
+        if let Some(container) = NOT_RUNNING_RE.captures(&stderr).and_then(first_match) {
+            debug!("docker command failed, container not running: {}", self.pretty_command());
+            return Some(ContainerNotRunningSnafu { container }.build());
+        }
+        if let Some(image) = NO_IMAGE_RE.captures(&stderr).and_then(first_match) {
+            debug!("docker command failed, image not found: {}", self.pretty_command());
+            return Some(ImageNotFoundSnafu { image }.build());
         }
+        None
+    }
+
+    fn command_failed(&self, output: &Output) -> DockerError {
+        CommandFailedSnafu {
+            command: self.pretty_command(),
+            status: output.status,
+            stdout: String::from_utf8_lossy(&output.stdout).trim(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim(),
+        }.build()
     }
 
     pub fn args_ref(&self) -> Vec<&str> {
@@ -102,20 +234,60 @@ impl DockerCommandJson {
                 args,
                 check_status: true,
                 capture_output: true,
+                retries: DEFAULT_RETRIES,
             }
         }
     }
 
-    pub fn run(&self) -> Vec<Value> {
-        let output = self.command.run();
-        let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    pub fn run(&self) -> Result<Vec<Value>, DockerError> {
+        let output = self.command.run()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
 
-        let result: Result<_, _> = stdout.lines()
+        stdout.lines()
             // first and last chars are single quotes, remove them before parsing json
-            .map(|l| serde_json::from_str(&l[1..l.len()-1]))
-            .collect();
-        result.unwrap_or_else(|err| vec![json!(
-            format!("ERROR: invalid command output: {:?}\nERROR: {err}", stdout.lines())
-        )])
+            .map(|l| l.strip_prefix('\'').and_then(|l| l.strip_suffix('\'')).unwrap_or(l))
+            .map(|l| serde_json::from_str(l).ok().context(InvalidOutputSnafu {
+                command: self.command.pretty_command(),
+                output: l,
+            }))
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::process::ExitStatusExt;
+
+    use super::*;
+
+    fn output(code: i32, stderr: &str) -> Output {
+        Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: vec![],
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_docker_errors() {
+        let cmd = DockerCommand::new(&["container", "exec", "-w", "/app", "vaulta_nodeos", "ls"]);
+
+        let err = cmd.docker_error(&output(1, "Error response from daemon: container 1a2b3c is not running"));
+        assert!(matches!(err, Some(DockerError::ContainerNotRunning { container, .. }) if container == "1a2b3c"));
+
+        let err = cmd.docker_error(&output(1, "Error response from daemon: No such container: vaulta_nodeos"));
+        assert!(matches!(err, Some(DockerError::ContainerNotRunning { container, .. }) if container == "vaulta_nodeos"));
+
+        let err = cmd.docker_error(&output(125, "Unable to find image 'vaulta:latest' locally\n"));
+        assert!(matches!(err, Some(DockerError::ImageNotFound { image, .. }) if image == "vaulta:latest"));
+
+        // errors of the command itself are not docker errors
+        assert!(cmd.docker_error(&output(2, "ls: cannot access 'foo': No such file or directory")).is_none());
+
+        let err = cmd.command_failed(&output(2, "ls: cannot access 'foo'\n"));
+        assert!(matches!(&err, DockerError::CommandFailed { command, stderr, .. }
+                         if command == "ls" && stderr == "ls: cannot access 'foo'"));
+        assert!(!err.is_transient());
     }
 }
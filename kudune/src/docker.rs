@@ -1,10 +1,16 @@
 // SPDX-FileCopyrightText: 2024-2026 DigiGaia SCCL
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+// the output of the Docker commands should not be trusted blindly, make sure we
+// don't panic when it doesn't look like what we expect
+#![deny(clippy::unwrap_used)]
+
+use std::fmt;
 use std::fs;
 use std::io::Write;
+use std::str::FromStr;
 
-use color_eyre::{Result, eyre::{eyre, WrapErr}};
+use color_eyre::{Result, eyre::eyre};
 use ratatui::layout::Alignment;
 use ratatui::{
     prelude::Modifier,
@@ -13,10 +19,12 @@ use ratatui::{
 use ratatui_macros::{line, span};
 use regex::Regex;
 use serde_json::Value;
+use snafu::ResultExt;
 use tempfile::NamedTempFile;
 use tracing::{info, debug, trace, warn};
 
-pub use crate::command::{DockerCommand, DockerCommandJson};
+pub use crate::command::{DockerCommand, DockerCommandJson, DockerError};
+use crate::command::{HostIoSnafu, UnknownStateSnafu};
 use crate::ratatui::{make_block, make_table, render, terminal_size};
 
 pub struct Docker {
@@ -38,6 +46,56 @@ pub struct Docker {
 
 const HOST_MOUNT_PATH: &str = "/host";
 
+
+/// CPU architecture of a Docker image or container.
+///
+/// Note that the `eos-vm-jit` WASM runtime of `nodeos` is only available on `amd64`,
+/// nodes running on `arm64` need to use the `eos-vm` interpreter instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platform {
+    Amd64,
+    Arm64,
+}
+
+impl Platform {
+    /// Return the platform matching the architecture of the host, defaulting
+    /// to `amd64` for architectures that are not supported.
+    pub fn host() -> Platform {
+        match std::env::consts::ARCH {
+            "aarch64" => Platform::Arm64,
+            _ => Platform::Amd64,
+        }
+    }
+
+    /// Return the name of the platform as given to `docker --platform`.
+    pub fn docker_name(&self) -> &'static str {
+        match self {
+            Platform::Amd64 => "linux/amd64",
+            Platform::Arm64 => "linux/arm64",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.docker_name())
+    }
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    /// Parse a platform given either as a Docker platform (eg: `linux/arm64`)
+    /// or as an architecture name (eg: `arm64`, `aarch64`, `x86_64`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("linux/").unwrap_or(s) {
+            "amd64" | "x86_64" => Ok(Platform::Amd64),
+            "arm64" | "aarch64" | "arm64/v8" => Ok(Platform::Arm64),
+            _ => Err(format!("unsupported platform: `{s}` (expected `amd64` or `arm64`)")),
+        }
+    }
+}
+
 impl Docker {
     /// the Docker constructor is pretty barebones and doesn't ensure
     /// anything is running. You have to call the `start()` method yourself
@@ -74,6 +132,24 @@ impl Docker {
         Self::docker_container_command(&self.container, args)
     }
 
+    /// Return a `DockerCommand` builder that you can later run inside the
+    /// docker container, in the given working directory (a path inside the container)
+    ///
+    /// If `interactive` is true, the command keeps its stdin open and is allocated
+    /// a TTY, as with `docker exec -it`, so that it can be used to run a shell.
+    pub fn exec_command(&self, args: &[&str], workdir: &str, interactive: bool) -> DockerCommand {
+        let mut docker_cmd = vec!["container", "exec"];
+
+        if interactive {
+            docker_cmd.extend_from_slice(&["-i", "-t", "-e", "TERM=xterm-256color"]);
+        }
+        docker_cmd.extend_from_slice(&["-w", workdir]);
+        docker_cmd.push(&self.container);
+        docker_cmd.extend_from_slice(args);
+
+        Self::docker_command(&docker_cmd)
+    }
+
     /// Return a `DockerCommand` builder that you can later run inside
     /// the docker container
     pub fn color_command(&self, args: &[&str]) -> DockerCommand {
@@ -88,51 +164,52 @@ impl Docker {
     }
 
 
-    pub fn list_running_containers() -> Vec<Value> {
+    pub fn list_running_containers() -> Result<Vec<Value>, DockerError> {
         Self::docker_command_json(&["container", "ls"]).run()
     }
 
-    pub fn list_all_containers() -> Vec<Value> {
+    pub fn list_all_containers() -> Result<Vec<Value>, DockerError> {
         Self::docker_command_json(&["container", "ls", "-a"]).run()
     }
 
-    pub fn list_images() -> Vec<Value> {
+    pub fn list_images() -> Result<Vec<Value>, DockerError> {
         Self::docker_command_json(&["images", "--all"]).run()
     }
 
-    pub fn is_running(container: &str) -> bool {
-        Docker::list_running_containers().into_iter()
-            .any(|c| c["Names"].as_str().unwrap() == container)
+    pub fn is_running(container: &str) -> Result<bool, DockerError> {
+        Ok(Docker::list_running_containers()?.into_iter()
+           .any(|c| c["Names"] == container))
     }
 
-    pub fn container_exists(container: &str) -> bool {
-        Docker::list_all_containers().into_iter()
-            .any(|c| c["Names"].as_str().unwrap() == container)
+    pub fn container_exists(container: &str) -> Result<bool, DockerError> {
+        Ok(Docker::list_all_containers()?.into_iter()
+           .any(|c| c["Names"] == container))
     }
 
     pub fn info(container: &str) -> Result<()> {
-        let get_apt_version = |package| -> String {
-            let pkg_info = String::from_utf8(
-                Self::docker_container_command(container, &["apt-cache", "show", package])
-                    .capture_output(true)
-                    .run()
-                    .stdout  // safe to not check exit status, will return "" if apt-cache errors (eg: package doesn't exist)
-            ).unwrap();  // safe unwrap, we are confident apt-cache will not give us non-utf8 strings
+        let get_apt_version = |package| -> Result<String> {
+            let output = Self::docker_container_command(container, &["apt-cache", "show", package])
+                .capture_output(true)
+                .check_status(false)
+                .run()?;  // safe to not check exit status, will return "" if apt-cache errors (eg: package doesn't exist)
+            let pkg_info = String::from_utf8_lossy(&output.stdout);
+            #[allow(clippy::unwrap_used)]  // safe unwrap, the regex is valid
             let version_re = Regex::new(r"Version: (.*)\n").unwrap();
-            version_re.captures(&pkg_info)
-                .and_then(|caps| caps.get(1))
-                .map(|v| v.as_str().to_string())
-                .unwrap_or_else(|| "unknown".to_string())
+            Ok(version_re.captures(&pkg_info)
+               .and_then(|caps| caps.get(1))
+               .map(|v| v.as_str().to_string())
+               .unwrap_or_else(|| "unknown".to_string()))
         };
-        let get_git_version = |folder| -> String {
+        let get_git_version = |folder| -> Result<String> {
             let output = Self::docker_container_command(container, &["git", "-C", folder, "describe", "--tags"])
                 .capture_output(true)
-                .run();
+                .check_status(false)
+                .run()?;
             if output.status.success() {
-                String::from_utf8_lossy(&output.stdout).trim().to_string()
+                Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
             }
             else {
-                "unknown".to_string()
+                Ok("unknown".to_string())
             }
         };
         let kudune_version = kudu::config::VERSION;
@@ -156,7 +233,7 @@ impl Docker {
         //     print vaulta images
         // -----------------------------------------------------------------------------
 
-        let images: Vec<_> = Self::list_images().into_iter()
+        let images: Vec<_> = Self::list_images()?.into_iter()
             .filter(|image| image["Repository"] == "vaulta")
             .collect();
 
@@ -172,7 +249,7 @@ impl Docker {
         //     print vaulta containers
         // -----------------------------------------------------------------------------
 
-        let containers = Self::list_running_containers();
+        let containers = Self::list_running_containers()?;
         let containers_output = render(width, (containers.len() as u16) + 6, |f| {
             let block = make_block("Running containers");
             let table = make_table(&containers, &["ID", "Image", "RunningFor", "Ports", "Names"]);
@@ -185,15 +262,15 @@ impl Docker {
         //     print version of the components inside the main container
         // -----------------------------------------------------------------------------
 
-        if !Self::is_running(container) {
+        if !Self::is_running(container)? {
             // only get info from container if it is running, otherwise exit here
             return Ok(());
         }
 
-        let spring_version = get_apt_version("antelope-spring");
-        let cdt_version = get_apt_version("cdt");
-        let system_contracts_version = get_git_version("/app/system_contracts/");
-        let vaulta_contract_version = get_git_version("/app/vaulta_system_contract/");
+        let spring_version = get_apt_version("antelope-spring")?;
+        let cdt_version = get_apt_version("cdt")?;
+        let system_contracts_version = get_git_version("/app/system_contracts/")?;
+        let vaulta_contract_version = get_git_version("/app/vaulta_system_contract/")?;
 
         let main_container_output = render(width, 8, |f| {
             // let title = format!("Container: {}", self.docker.container);
@@ -215,27 +292,27 @@ impl Docker {
     }
 
     /// Start the docker container if needed. Show log output if `log=true`.
-    pub fn start(&self, log: bool) {
+    pub fn start(&self, log: bool) -> Result<(), DockerError> {
         let name = &self.container;
 
         // check first if a container with the same name already exists
-        if let Some(c) = Docker::find_container(name) {
-            match c["State"].as_str().unwrap() {
+        if let Some(c) = Docker::find_container(name)? {
+            match c["State"].as_str().unwrap_or_default() {
                 "created" => {
                     // FIXME!! do we want this or to fall through out of the match?
                     if log { debug!("Container `{}` created but not running. Starting it", name); }
-                    Self::docker_command(&["container", "start", name]).run();
+                    Self::docker_command(&["container", "start", name]).run()?;
                 }
                 "running" => {
                     if log { debug!("Container `{}` already running, using it", name); }
                 },
                 "exited" => {
                     if log { debug!("Container `{}` existing but stopped. Restarting it", name); }
-                    Self::docker_command(&["container", "start", name]).run();
+                    Self::docker_command(&["container", "start", name]).run()?;
                 },
-                s => panic!("unknown state for container: {}", s),
+                state => return UnknownStateSnafu { container: name, state }.fail(),
             }
-            return;
+            return Ok(());
         }
 
         // we didn't find an already existing container,
@@ -261,21 +338,26 @@ impl Docker {
             &self.image,
             "/sbin/my_init",
         ]);
-        Self::docker_command(&args[..]).run();
+        Self::docker_command(&args[..]).run()?;
+        Ok(())
     }
 
-    fn find_container(name: &str) -> Option<Value> {
-        Docker::list_all_containers().into_iter()
-            .find(|c| c["Names"].as_str().unwrap() == name)
+    fn find_container(name: &str) -> Result<Option<Value>, DockerError> {
+        Ok(Docker::list_all_containers()?.into_iter()
+           .find(|c| c["Names"] == name))
     }
 
     /// Given a path to a file or dir on the host, return the equivalent path as
     /// seen from within the container.
     pub fn host_to_container_path(&self, path: &str) -> Result<String> {
+        use color_eyre::eyre::WrapErr;
+
         let path = fs::canonicalize(path).wrap_err_with(|| {
             format!("Could not get canonical path for: {}", path)
         })?;
-        let path = path.to_str().expect("Given path is not valid utf-8!...");
+        let path = path.to_str().ok_or_else(|| {
+            eyre!("Given path is not valid utf-8: {}", path.display())
+        })?;
         let path = path.strip_prefix(&self.host_mount).ok_or_else(|| {
             eyre!("Trying to map host path: \"{}\" in container but it is not part of the mount point: \"{}\"", path, self.host_mount)
         })?;
@@ -283,73 +365,81 @@ impl Docker {
         Ok(format!("{}{}", HOST_MOUNT_PATH, path))
     }
 
-    pub fn stop(container_name: &str) {
+    pub fn stop(container_name: &str) -> Result<(), DockerError> {
         info!("Stopping docker container `{}`...", container_name);
-        if !Docker::is_running(container_name) {
+        if !Docker::is_running(container_name)? {
             warn!("Container {} is not running", container_name);
-            return;
+            return Ok(());
         }
-        Docker::docker_command(&["container", "stop", container_name]).run();
+        Docker::docker_command(&["container", "stop", container_name]).run()?;
+        Ok(())
     }
 
-    pub fn destroy(container_name: &str) {
-        if !Docker::container_exists(container_name) {
+    pub fn destroy(container_name: &str) -> Result<(), DockerError> {
+        if !Docker::container_exists(container_name)? {
             warn!("Container {} does not exist...", container_name);
-            return;
+            return Ok(());
         }
-        Docker::stop(container_name);
+        Docker::stop(container_name)?;
         info!("Destroying docker container `{}`...", container_name);
-        Docker::docker_command(&["container", "rm", container_name]).run();
+        Docker::docker_command(&["container", "rm", container_name]).run()?;
         info!("Docker container `{}` destroyed successfully!", container_name);
+        Ok(())
+    }
+
+    /// Return the platform of the running container, or `None` if its architecture
+    /// could not be determined.
+    pub fn platform(&self) -> Result<Option<Platform>, DockerError> {
+        let output = self.command(&["uname", "-m"]).check_status(false).run()?;
+        if !output.status.success() { return Ok(None); }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
     }
 
     /// this is a very crude implementation
-    pub fn find_pid(&self, pattern: &str) -> Option<usize> {
-        let output = self.command(&["ps", "ax"]).run();
-        let stdout = std::str::from_utf8(&output.stdout).unwrap();
-        for line in stdout.lines().skip(1) {
-            if line.contains(pattern) {
-                return Some(line.split_whitespace().next().unwrap().parse().unwrap());
-            }
-        }
-        None
+    pub fn find_pid(&self, pattern: &str) -> Result<Option<usize>, DockerError> {
+        let output = self.command(&["ps", "ax"]).run()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().skip(1)
+           .find(|line| line.contains(pattern))
+           .and_then(|line| line.split_whitespace().next()?.parse().ok()))
     }
 
     // -----------------------------------------------------------------------------
     //     File management methods
     // -----------------------------------------------------------------------------
 
-    pub fn file_exists(&self, filename: &str) -> bool {
-        self.command(&["test", "-f", filename])
-            .check_status(false).run()
-            .status.success()
+    pub fn file_exists(&self, filename: &str) -> Result<bool, DockerError> {
+        Ok(self.command(&["test", "-f", filename])
+           .check_status(false).run()?
+           .status.success())
     }
 
-    pub fn cp_host_to_container(&self, host_file: &str, container_file: &str) {
+    pub fn cp_host_to_container(&self, host_file: &str, container_file: &str) -> Result<(), DockerError> {
         trace!("Copy file {} from host to container:{}", host_file, container_file);
         let dest = format!("{}:{}", &self.container, container_file);
-        Docker::docker_command(&["cp", host_file, &dest]).run();
+        Docker::docker_command(&["cp", host_file, &dest]).run()?;
+        Ok(())
     }
 
-    pub fn cp_container_to_host(&self, container_file: &str, host_file: &str) {
+    pub fn cp_container_to_host(&self, container_file: &str, host_file: &str) -> Result<(), DockerError> {
         trace!("Copy file {} from container to host:{}", container_file, host_file);
         let src = format!("{}:{}", &self.container, container_file);
-        Docker::docker_command(&["cp", &src, host_file]).run();
+        Docker::docker_command(&["cp", &src, host_file]).run()?;
+        Ok(())
     }
 
-    pub fn write_file(&self, filename: &str, content: &str) {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        let _ = temp_file.write(content.as_bytes()).unwrap();
+    pub fn write_file(&self, filename: &str, content: &str) -> Result<(), DockerError> {
+        let action = "writing temporary file";
+        let mut temp_file = NamedTempFile::new().context(HostIoSnafu { action })?;
+        temp_file.write_all(content.as_bytes()).context(HostIoSnafu { action })?;
 
-        self.cp_host_to_container(temp_file.path().to_str().unwrap(), filename);
+        self.cp_host_to_container(&temp_file.path().to_string_lossy(), filename)
     }
 
-    pub fn read_file(&self, filename: &str) -> String {
-        // FIXME: this impl sucks!
-        let temp_file = "/tmp/tempfile";
-        self.cp_container_to_host(filename, temp_file);
-        let result = std::fs::read_to_string(temp_file).unwrap();
-        std::fs::remove_file(temp_file).unwrap();
-        result
+    pub fn read_file(&self, filename: &str) -> Result<String, DockerError> {
+        let action = "reading temporary file";
+        let temp_file = NamedTempFile::new().context(HostIoSnafu { action })?;
+        self.cp_container_to_host(filename, &temp_file.path().to_string_lossy())?;
+        fs::read_to_string(temp_file.path()).context(HostIoSnafu { action })
     }
 }
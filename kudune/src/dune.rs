@@ -6,14 +6,17 @@ use std::path::Path;
 use std::time::Duration;
 use std::{process, thread};
 
-use color_eyre::eyre::{eyre, Result};
+use color_eyre::eyre::{bail, eyre, OptionExt, Result, WrapErr};
 use regex::Regex;
 use tracing::{debug, info, warn, trace};
 use serde_json::{json, Value};
 
-use kudu::config::VAULTA_FEATURES;
-use crate::docker::{Docker, DockerCommand};
+use kudu::{KeyType, PrivateKey, PublicKey};
+use crate::bootstrap::{BootstrapProfile, DEFAULT_PROFILE};
+use crate::docker::{Docker, DockerCommand, Platform};
 use crate::nodeconfig::NodeConfig;
+use crate::profile::{ActionProfile, ActionSample};
+use crate::progress::{ProgressHandler, ProgressTracker};
 use crate::util::eyre_from_output;
 
 
@@ -22,6 +25,16 @@ const DEFAULT_NODEOS_HTTP_PORT: u16 = 8888;
 const CONFIG_PATH: &str = "/app/config.ini";
 const TEMP_FOLDER: &str = "/tmp/scratch";
 
+/// Folders of the nodeos data dir holding the state of the blockchain itself, as
+/// opposed to the config, genesis and wallet which are kept outside of it.
+const CHAIN_DATA_DIRS: &[&str] = &[
+    "/app/datadir/blocks",
+    "/app/datadir/state",
+    "/app/datadir/state-history",
+    "/app/datadir/snapshots",
+    "/app/datadir/traces",
+];
+
 const SYS_TOKEN_SYMBOL: &str = "EOS";
 
 
@@ -35,10 +48,13 @@ fn unpack_scripts<P: AsRef<Path>>(scripts: P) -> Result<()> {
     Ok(())
 }
 
-fn replace_line<P: AsRef<Path>>(filename: P, line: &str, replace: &str) {
-    let contents = read_to_string(filename.as_ref()).unwrap();
-    let re = Regex::new(line).unwrap();
-    write_file(filename, re.replace(&contents, replace).as_ref()).unwrap();
+fn replace_line<P: AsRef<Path>>(filename: P, line: &str, replace: &str) -> Result<()> {
+    let filename = filename.as_ref();
+    let contents = read_to_string(filename)
+        .wrap_err_with(|| format!("could not read file: {}", filename.display()))?;
+    let re = Regex::new(line)?;
+    write_file(filename, re.replace(&contents, replace).as_ref())
+        .wrap_err_with(|| format!("could not write file: {}", filename.display()))
 }
 
 
@@ -52,6 +68,11 @@ pub struct BuildOpts {
     pub nproc: Option<i16>,
     pub cleanup: bool,
     pub verbose: bool,
+    /// Architecture of the image to build, defaults to the one of the host.
+    /// Spring and CDT are compiled from source when no package is available for it.
+    pub platform: Platform,
+    /// If set, receive progress events while the image is being built
+    pub progress: Option<ProgressHandler>,
 }
 
 impl Default for BuildOpts {
@@ -66,9 +87,19 @@ impl Default for BuildOpts {
             nproc: None,
             cleanup: true,
             verbose: false,
+            platform: Platform::host(),
+            progress: None,
         }
     }
 }
+
+/// Location of the compiled files of a contract, as seen from within the container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractArtifacts {
+    pub wasm: String,
+    pub abi: String,
+}
+
 /// A `Dune` instance manages a Docker container in which a `nodeos` instance is
 /// running a Vaulta blockchain.
 ///
@@ -86,6 +117,9 @@ pub struct Dune {
     /// the internal (to the container) http address where to connect to nodeos
     /// FIXME: this should be replaced just by the port, the addr inside the container will always be localhost:{port}
     http_addr: String,
+
+    /// if set, receive progress events for long-running operations
+    progress: Option<ProgressHandler>,
 }
 
 impl Dune {
@@ -96,29 +130,39 @@ impl Dune {
     pub fn new(container: String, image: String, port_mapping: Vec<(u16, u16)>, host_mount: String) -> Result<Dune> {
         // make sure we have a docker image ready in case we need one to build
         // a new container off of it2
-        let vaulta_image = duct::cmd!("docker", "images", "-q", &image).read().unwrap();
-        if vaulta_image.is_empty() {
+        let vaulta_image = Docker::docker_command(&["images", "-q", &image]).run()?;
+        if vaulta_image.stdout.trim_ascii().is_empty() {
             info!("No appropriate image found, building one before starting container");
             Self::build_image(&BuildOpts { name: image.clone(), ..Default::default() })?;
         }
 
         let docker = Docker::new(container, port_mapping, image, host_mount);
-        docker.start(true);
+        docker.start(true)?;
 
-        let mut result = Dune { docker, http_addr: format!("0.0.0.0:{DEFAULT_NODEOS_HTTP_PORT}") };
-        result.sync_config();
+        let mut result = Dune {
+            docker,
+            http_addr: format!("0.0.0.0:{DEFAULT_NODEOS_HTTP_PORT}"),
+            progress: None,
+        };
+        result.sync_config()?;
 
         Ok(result)
     }
 
+    /// Set the handler that receives progress events for long-running operations,
+    /// such as [`Dune::bootstrap_system()`].
+    pub fn set_progress_handler(&mut self, handler: Option<ProgressHandler>) {
+        self.progress = handler;
+    }
+
     /// Return a list of running Docker containers on this machine
-    pub fn list_running_containers(&self) -> Vec<Value> {
-        Docker::list_running_containers()
+    pub fn list_running_containers(&self) -> Result<Vec<Value>> {
+        Ok(Docker::list_running_containers()?)
     }
 
     /// Return a list of all Docker containers (running and stopped) on this machine.
-    pub fn list_all_containers(&self) -> Vec<Value> {
-        Docker::list_all_containers()
+    pub fn list_all_containers(&self) -> Result<Vec<Value>> {
+        Ok(Docker::list_all_containers()?)
     }
 
     /// Given a path to a file or dir on the host, return the equivalent path as
@@ -129,12 +173,17 @@ impl Dune {
 
     /// Build a Docker image starting from `base_image` that has Spring, the CDT and
     /// system contracts installed. It will be saved as `name`.
+    ///
+    /// Progress is reported to `opts.progress` if it is set.
     pub fn build_image(opts: &BuildOpts) -> Result<()> {
+        let mut progress = ProgressTracker::new(opts.progress.as_ref(), "Build image", 4);
+
         // first make sure we are able to run pyinfra
+        progress.step("Checking that pyinfra is available");
         let status = duct::cmd!("which", "pyinfra")
             .stdout_capture()
             .unchecked().run()
-            .unwrap()
+            .wrap_err("could not run `which pyinfra`")?
             .status;
 
         if !status.success() {
@@ -148,6 +197,7 @@ impl Dune {
         let scripts_folder = Path::new(TEMP_FOLDER).join("scripts");
 
         // unpack script files to a temporary location
+        progress.step("Preparing build scripts");
         unpack_scripts(&scripts_folder)?;
 
         // build image using pyinfra
@@ -156,33 +206,33 @@ impl Dune {
         if let Some(version) = &opts.spring {
             replace_line(scripts_folder.join("build_vaulta_image.py"),
                          r"SPRING_VERSION = .+",
-                         &format!("SPRING_VERSION = '{}'", version));
+                         &format!("SPRING_VERSION = '{}'", version))?;
         }
 
         if let Some(version) = &opts.cdt {
             replace_line(scripts_folder.join("build_vaulta_image.py"),
                          r"CDT_VERSION = .+",
-                         &format!("CDT_VERSION = '{}'", version));
+                         &format!("CDT_VERSION = '{}'", version))?;
         }
 
         if let Some(version) = &opts.system_contracts {
             replace_line(scripts_folder.join("build_vaulta_image.py"),
                          r"SYSTEM_CONTRACTS_VERSION = .+",
-                         &format!("SYSTEM_CONTRACTS_VERSION = '{}'", version));
+                         &format!("SYSTEM_CONTRACTS_VERSION = '{}'", version))?;
         }
 
         replace_line(scripts_folder.join("build_vaulta_image.py"),
                      r"COMPILE_SPRING_CDT = [A-Za-z]+",
-                     &format!("COMPILE_SPRING_CDT = {}", if opts.compile { "True" } else { "False" }));
+                     &format!("COMPILE_SPRING_CDT = {}", if opts.compile { "True" } else { "False" }))?;
 
         replace_line(scripts_folder.join("build_vaulta_image.py"),
                      r"CLEANUP = [A-Za-z]+",
-                     &format!("CLEANUP = {}", if opts.cleanup { "True" } else { "False" }));
+                     &format!("CLEANUP = {}", if opts.cleanup { "True" } else { "False" }))?;
 
         if let Some(nproc) = opts.nproc {
             replace_line(scripts_folder.join("build_vaulta_image.py"),
                          r"NPROC = [0-9None]+",
-                         &format!("NPROC = {}", nproc));
+                         &format!("NPROC = {}", nproc))?;
         }
 
 
@@ -194,14 +244,9 @@ impl Dune {
         }
         let command = duct::cmd("pyinfra", &args);
 
-        // if we're running on Apple silicon, we need to force the arch to be AMD64 because
-        // the EOS WASM JIT VM only runs on that architecture
-        let command = if std::env::consts::ARCH == "aarch64" {
-            command.env("DOCKER_DEFAULT_PLATFORM", "linux/amd64")
-        }
-        else {
-            command
-        };
+        // build the image for the requested arch, which is not necessarily the host one
+        // (eg: an `amd64` image on Apple silicon, running under emulation)
+        let command = command.env("DOCKER_DEFAULT_PLATFORM", opts.platform.docker_name());
 
         let command = if CAPTURE_OUTPUT {
             command.stdout_capture().stderr_capture()
@@ -210,7 +255,9 @@ impl Dune {
             command
         }.dir(TEMP_FOLDER);
 
-        let output = command.unchecked().run().unwrap();
+        progress.step(&format!("Building {} image from `{}` (this can take a while...)",
+                               opts.platform, opts.base_image));
+        let output = command.unchecked().run().wrap_err("could not run `pyinfra`")?;
 
         match output.status.success() {
             true => {
@@ -218,21 +265,26 @@ impl Dune {
                 let image_id = if CAPTURE_OUTPUT {
                     // we captured the output of the process, parse it to get the image ID
                     let stderr = std::str::from_utf8(&output.stderr)?;
-                    let re = Regex::new(r"image ID: ([0-9a-f]+)").unwrap();
-                    let m = re.captures(stderr).expect("could not parse image ID from stderr");
+                    let re = Regex::new(r"image ID: ([0-9a-f]+)")?;
+                    let m = re.captures(stderr).ok_or_eyre("could not parse image ID from stderr")?;
                     let image_id = &m[1];
                     image_id.to_string()
                 }
                 else {
                     // we didn't capture any output, get the image ID from the
                     // latest docker image and hope for the best
-                    Docker::list_images()[0]["ID"].as_str().unwrap().to_string()
+                    Docker::list_images()?.first()
+                        .and_then(|image| image["ID"].as_str())
+                        .ok_or_eyre("could not find ID of the latest docker image")?
+                        .to_string()
                 };
 
                 info!("Image built successfully with image ID: {:?}", &image_id);
-                Docker::docker_command(&["tag", &image_id, &opts.name]).run();
+                progress.step(&format!("Tagging image as `{}`", &opts.name));
+                Docker::docker_command(&["tag", &image_id, &opts.name]).run()?;
                 info!("Image tagged as: `{}`", &opts.name);
 
+                progress.finish();
                 Ok(())
             },
             false => {
@@ -257,12 +309,19 @@ impl Dune {
         self.docker.color_command(args).capture_output(false)
     }
 
-    pub fn cleos_cmd(&self, cmd: &[&str]) -> process::Output {
+    /// Return a command running `args` in the container, in `workdir` (a path inside
+    /// the container, `/app` by default), optionally with an interactive TTY.
+    pub fn exec_command(&self, args: &[&str], workdir: Option<&str>, interactive: bool) -> DockerCommand {
+        self.docker.exec_command(args, workdir.unwrap_or("/app"), interactive)
+            .capture_output(false)
+    }
+
+    pub fn cleos_cmd(&self, cmd: &[&str]) -> Result<process::Output> {
         trace!("Running cleos command: {:?}", cmd);
         let url = format!("http://{}", self.http_addr);
         let mut cleos_cmd = vec!["cleos", "--verbose", "-u", &url];
         cleos_cmd.extend_from_slice(cmd);
-        self.docker.command(&cleos_cmd).run()
+        Ok(self.docker.command(&cleos_cmd).run()?)
     }
 
 
@@ -272,39 +331,50 @@ impl Dune {
     //
     // =============================================================================
 
-    pub fn has_config(&self) -> bool {
-        self.docker.file_exists(CONFIG_PATH)
+    pub fn has_config(&self) -> Result<bool> {
+        Ok(self.docker.file_exists(CONFIG_PATH)?)
     }
 
-    pub fn rm_config(&self) {
-        self.docker.command(&["rm", CONFIG_PATH]).run();
+    pub fn rm_config(&self) -> Result<()> {
+        self.docker.command(&["rm", CONFIG_PATH]).run()?;
+        Ok(())
     }
 
-    fn sync_config(&mut self) {
-        self.http_addr = self.pull_config().http_addr().to_string();
+    fn sync_config(&mut self) -> Result<()> {
+        self.http_addr = self.pull_config()?.http_addr().to_string();
+        Ok(())
     }
 
     /// Push the given `NodeConfig` to the `config.ini` file inside the container
     ///
     /// this also updates the cached `dune.http_addr` value (and others) if necessary
-    pub fn push_config(&mut self, config: &NodeConfig) {
-        self.docker.write_file(CONFIG_PATH, &config.to_ini());
+    ///
+    /// On `arm64` containers, the `eos-vm-jit` WASM runtime is replaced with `eos-vm`
+    /// as it is not available on that architecture.
+    pub fn push_config(&mut self, config: &NodeConfig) -> Result<()> {
+        let mut config = config.clone();
+        let jit = config.params.get("wasm-runtime").is_some_and(|rt| rt == "eos-vm-jit");
+        if jit && self.docker.platform()? == Some(Platform::Arm64) {
+            warn!("`eos-vm-jit` is not available on arm64, using `eos-vm` instead");
+            config.params.insert("wasm-runtime".to_string(), "eos-vm".to_string());
+        }
+        self.docker.write_file(CONFIG_PATH, &config.to_ini())?;
         self.sync_config()
     }
 
     /// Pull the config from the `config.ini` file inside the container and return it
     /// as a `NodeConfig`. If it cannot be found, return a default config.
-    pub fn pull_config(&self) -> NodeConfig {
-        match self.docker.file_exists(CONFIG_PATH) {
-            true => NodeConfig::from_ini(&self.docker.read_file(CONFIG_PATH)),
+    pub fn pull_config(&self) -> Result<NodeConfig> {
+        Ok(match self.docker.file_exists(CONFIG_PATH)? {
+            true => NodeConfig::from_ini(&self.docker.read_file(CONFIG_PATH)?),
             false => NodeConfig::default(),
-        }
+        })
     }
 
     /// Write the given string as a genesis file inside the container. It will be
     /// used automatically when starting nodeos for the first time
-    pub fn push_genesis(&self, genesis_content: &str) {
-        self.docker.write_file("/app/genesis.json", genesis_content);
+    pub fn push_genesis(&self, genesis_content: &str) -> Result<()> {
+        Ok(self.docker.write_file("/app/genesis.json", genesis_content)?)
     }
 
 
@@ -315,8 +385,8 @@ impl Dune {
     // =============================================================================
 
     /// Return whether `nodeos` is running inside the container.
-    pub fn is_node_running(&self) -> bool {
-        self.docker.find_pid("nodeos").is_some()
+    pub fn is_node_running(&self) -> Result<bool> {
+        Ok(self.docker.find_pid("nodeos")?.is_some())
     }
 
     /// Start `nodeos` inside the container.
@@ -325,15 +395,15 @@ impl Dune {
     /// (useful after some crashes).
     ///
     /// If `clean == true`, delete the data dir and restart with a fresh one
-    pub fn start_node(&mut self, replay_blockchain: bool, clean: bool) {
-        if self.is_node_running() {
+    pub fn start_node(&mut self, replay_blockchain: bool, clean: bool) -> Result<()> {
+        if self.is_node_running()? {
             info!("Node is already running");
-            return;
+            return Ok(());
         }
 
         if clean {
-            self.docker.command(&["rm", "-fr", "/app/datadir"]).run();
-            self.docker.command(&["mkdir", "-p", "/app/datadir"]).run();
+            self.docker.command(&["rm", "-fr", "/app/datadir"]).run()?;
+            self.docker.command(&["mkdir", "-p", "/app/datadir"]).run()?;
         }
 
         let mut args = vec!["/app/launch_bg.sh", "nodeos", "--data-dir=/app/datadir"];
@@ -344,54 +414,71 @@ impl Dune {
         }
 
         info!("Starting nodeos...");
-        let output = self.docker.command(&args).run();
-
-        // print_streams(&output);
+        self.docker.command(&args).run()?;
 
-        if output.status.success() && self.is_node_running() {
-            self.wait_blockchain_ready();
-            info!("Node active!");
-        }
-        else {
-            info!("Could not start node");
+        if !self.is_node_running()? {
+            bail!("Could not start node");
         }
+        self.wait_blockchain_ready()?;
+        info!("Node active!");
+        Ok(())
     }
 
     /// Stop `nodeos` inside the container.
-    pub fn stop_node(&self) {
+    pub fn stop_node(&self) -> Result<()> {
         let max_wait_time_seconds = 30;
         let mut waited = 0;
 
-        match self.docker.find_pid("nodeos") {
+        match self.docker.find_pid("nodeos")? {
             None => { debug!("Trying to stop node but it is not running"); },
             Some(pid) => {
-                self.docker.command(&["kill", &pid.to_string()]).run();
+                self.docker.command(&["kill", &pid.to_string()]).run()?;
                 debug!("Waiting for node to shutdown, PID: {pid} (max wait: {max_wait_time_seconds}s)");
 
                 loop {
                     thread::sleep(Duration::from_secs(1));
-                    if !self.is_node_running() { break; }
+                    if !self.is_node_running()? { break; }
 
                     waited += 1;
                     if waited > max_wait_time_seconds {
-                        warn!("Cannot stop node with PID: {}", pid);
-                        process::exit(1);
+                        bail!("Cannot stop node with PID: {pid}, waited for {max_wait_time_seconds} seconds");
                     }
                 }
 
                 info!("Stopped node successfully!");
             }
         }
+        Ok(())
+    }
+
+    /// Delete the blockchain data (blocks, state, etc.) so that the next start of
+    /// `nodeos` begins a new chain from the genesis.
+    ///
+    /// As opposed to `start_node(false, true)`, this keeps the `config.ini` file and
+    /// the wallet with its keys. If the node was running, it is stopped and restarted.
+    pub fn reset_chain(&mut self) -> Result<()> {
+        let was_running = self.is_node_running()?;
+        self.stop_node()?;
+
+        info!("Deleting blockchain data...");
+        let mut args = vec!["rm", "-fr"];
+        args.extend_from_slice(CHAIN_DATA_DIRS);
+        self.docker.command(&args).run()?;
+
+        if was_running {
+            self.start_node(false, false)?;
+        }
+        Ok(())
     }
 
     /// Wait until `nodeos` is fully started and ready to accept connections.
-    fn wait_blockchain_ready(&self) {
+    fn wait_blockchain_ready(&self) -> Result<()> {
         let url = format!("{}/v1/chain/get_info", self.http_addr);
         let max_wait_time_seconds = 10;
         let mut waited = 0;
 
         loop {
-            let output = self.docker.command(&["curl", "--request", "POST", &url]).check_status(false).run();
+            let output = self.docker.command(&["curl", "--request", "POST", &url]).check_status(false).run()?;
             if output.status.success() { break; }
             debug!("blockchain not ready yet, waiting 1 second before retrying");
             thread::sleep(Duration::from_secs(1));
@@ -402,6 +489,7 @@ impl Dune {
                 break;
             }
         }
+        Ok(())
     }
 
 
@@ -414,42 +502,73 @@ impl Dune {
     /// Return a newly created (private, public) keypair
     /// TODO: use antelope types (or a tagged type), to avoid confusion between
     ///       private and public
-    fn create_key(&self) -> (String, String) {
-        let output = self.cleos_cmd(&["create", "key", "--to-console"]);
-        let mut stdout = std::str::from_utf8(&output.stdout).unwrap().lines();
-        let private = stdout.next().unwrap().split(": ").nth(1).unwrap().to_string();
-        let public = stdout.next().unwrap().split(": ").nth(1).unwrap().to_string();
-        (private, public)
+    fn create_key(&self) -> Result<(String, String)> {
+        let output = self.cleos_cmd(&["create", "key", "--to-console"])?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut keys = stdout.lines().filter_map(|line| line.split(": ").nth(1));
+        match (keys.next(), keys.next()) {
+            (Some(private), Some(public)) => Ok((private.to_string(), public.to_string())),
+            _ => bail!("could not parse keys from `cleos create key` output: {stdout}"),
+        }
+    }
+
+    fn import_key(&self, privkey: &str) -> Result<()> {
+        self.unlock_wallet()?;
+        self.cleos_cmd(&["wallet", "import", "--private-key", privkey])?;
+        Ok(())
+    }
+
+    /// Create a new keypair, import it in the wallet and return it.
+    pub fn wallet_create_key(&self) -> Result<(PrivateKey, PublicKey)> {
+        let (private, public) = self.create_key()?;
+        self.import_key(&private)?;
+        Ok((PrivateKey::new(&private)?, PublicKey::new(&public)?))
+    }
+
+    /// Import the given private key in the wallet if it is not already there,
+    /// and return its public key.
+    pub fn wallet_import_key(&self, private_key: &str) -> Result<PublicKey> {
+        let private_key = PrivateKey::new(private_key)?;
+        if private_key.key_type() != KeyType::K1 {
+            bail!("only K1 keys can be imported, got a {:?} key", private_key.key_type());
+        }
+        let public_key = PublicKey::from_private_key(&private_key);
+        if !self.wallet_keys()?.contains(&public_key) {
+            self.import_key(&private_key.to_string())?;
+        }
+        Ok(public_key)
     }
 
-    fn import_key(&self, privkey: &str) {
-        self.unlock_wallet();
-        self.cleos_cmd(&["wallet", "import", "--private-key", privkey]);
+    /// Return the public keys of all the keys stored in the wallet.
+    pub fn wallet_keys(&self) -> Result<Vec<PublicKey>> {
+        self.unlock_wallet()?;
+        let output = self.cleos_cmd(&["wallet", "keys"])?;
+        let keys: Vec<String> = serde_json::from_slice(&output.stdout)?;
+        Ok(keys.iter().map(|k| PublicKey::new(k)).collect::<Result<_, _>>()?)
     }
 
     /// Retrieve the wallet password.
-    pub fn get_wallet_password(&self) -> String {
-        let output = self.docker.command(&["cat", "/app/.wallet.pw"]).run();
-        String::from_utf8(output.stdout).unwrap()
+    pub fn get_wallet_password(&self) -> Result<String> {
+        let output = self.docker.command(&["cat", "/app/.wallet.pw"]).run()?;
+        Ok(String::from_utf8(output.stdout)?)
     }
 
     /// Unlock the wallet.
-    pub fn unlock_wallet(&self) {
-        let command = self.docker.command(&[
-            "cleos", "wallet", "unlock", "--password", &self.get_wallet_password()
-        ]).check_status(false);
-
-        let output = command.run();
+    pub fn unlock_wallet(&self) -> Result<()> {
+        let output = self.docker.command(&[
+            "cleos", "wallet", "unlock", "--password", &self.get_wallet_password()?
+        ]).check_status(false).run()?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             if stderr.contains("Already unlocked") {
                 // all good, we don't want to fail here
-                return;
+                return Ok(());
             }
 
-            command.handle_error(&output);
+            return Err(eyre_from_output("Could not unlock wallet", &output));
         }
+        Ok(())
     }
 
 
@@ -461,127 +580,144 @@ impl Dune {
     // =============================================================================
 
 
-    /// Bootstrap a running Vaulta system by executing the following steps:
-    ///  - deploy boot contract
-    ///  - pre-activate features
+    /// Bootstrap a running Vaulta system using the default profile, see
+    /// [`Dune::bootstrap_with_profile()`].
+    pub fn bootstrap_system(&self) -> Result<()> {
+        self.bootstrap_with_profile(&BootstrapProfile::builtin(DEFAULT_PROFILE)?)
+    }
+
+    /// Bootstrap a running system by executing the following steps:
     ///  - create system accounts
-    ///  - deploy system contracts
+    ///  - deploy `eosio.msig` and `eosio.token` contracts
     ///  - create system token
+    ///  - deploy boot contract and activate features
+    ///  - deploy and initialize system contract
+    ///  - deploy and initialize `core.vaulta` contract (if enabled in the profile)
+    ///
+    /// Progress is reported to the handler set with [`Dune::set_progress_handler()`].
     ///
     /// See reference at:
     /// <https://github.com/AntelopeIO/spring/blob/main/tutorials/bios-boot-tutorial/bios-boot-tutorial.py>
-    pub fn bootstrap_system(&self) {
+    pub fn bootstrap_with_profile(&self, profile: &BootstrapProfile) -> Result<()> {
         // TODO: check tests/eosio.system_tester.hpp in system-contracts
-        let currency = SYS_TOKEN_SYMBOL;
-        let max_value     = "10000000000.0000";
-        let initial_value =  "1000000000.0000";
+        let token = &profile.token;
+        let currency = &token.symbol;
+        let features = profile.feature_digests()?;
+        let contract = |name: &str| format!("{}/{name}", profile.contracts_dir);
 
         // -----------------------------------------------------------------------------
         //     create system accounts
         // -----------------------------------------------------------------------------
 
+        // one step per system account + 8 other steps (+ 2 for `core.vaulta`)
+        let steps = profile.system_accounts.len() + 8 + if profile.vaulta.is_some() { 2 } else { 0 };
+        let mut progress = ProgressTracker::new(self.progress.as_ref(), "Bootstrap", steps);
+
         info!("Creating accounts needed for system contracts");
-        self.create_account("eosio.bpay", Some("eosio"));
-        self.create_account("eosio.msig", Some("eosio"));
-        self.create_account("eosio.names", Some("eosio"));
-        self.create_account("eosio.ram", Some("eosio"));
-        self.create_account("eosio.ramfee", Some("eosio"));
-        self.create_account("eosio.saving", Some("eosio"));
-        self.create_account("eosio.stake", Some("eosio"));
-        self.create_account("eosio.token", Some("eosio"));
-        self.create_account("eosio.vpay", Some("eosio"));
-        self.create_account("eosio.wrap", Some("eosio"));
-        self.create_account("eosio.rex", Some("eosio"));
-        self.create_account("eosio.fees", Some("eosio"));
-        self.create_account("eosio.reward", Some("eosio"));
-        self.create_account("eosio.wram", Some("eosio"));
-        self.create_account("eosio.reserv", Some("eosio"));
-        self.create_account("eosio.powup", Some("eosio"));
-        self.create_account("core.vaulta", Some("eosio"));
+        for account in &profile.system_accounts {
+            progress.step(&format!("Creating account `{account}`"));
+            self.create_account(account, Some("eosio"))?;
+        }
 
         // -----------------------------------------------------------------------------
         //     install system contracts
         // -----------------------------------------------------------------------------
 
         info!("Deploying system contracts");
-        self.deploy_contract("/app/system_contracts/build/contracts/eosio.msig", "eosio.msig");
-        self.deploy_contract("/app/system_contracts/build/contracts/eosio.token", "eosio.token");
+        progress.step("Deploying `eosio.msig` contract");
+        self.deploy_contract(&contract("eosio.msig"), "eosio.msig")?;
+        progress.step("Deploying `eosio.token` contract");
+        self.deploy_contract(&contract("eosio.token"), "eosio.token")?;
         // TODO: not in bios tutorial, is it needed?
         // self.deploy_contract("/app/eosio.fees", "eosio.fees");
 
         // -----------------------------------------------------------------------------
-        //     install system token (SYS)
+        //     install system token
         // -----------------------------------------------------------------------------
 
         info!("Setting up `{currency}` token");
-        self.setup_token(currency, max_value, initial_value);
+        progress.step(&format!("Setting up `{currency}` token"));
+        self.setup_token(currency, &token.max_supply, &token.initial_supply)?;
 
         // -----------------------------------------------------------------------------
         //     set system contract
         // -----------------------------------------------------------------------------
 
-        self.preactivate_features(); // required for boot contract
+        progress.step("Preactivating features");
+        self.preactivate_features()?; // required for boot contract
 
         // wait a little bit for feature to be activated (one block should be enough?)
         // TODO: use a retry wrapper on `deploy_contract()` instead of actively waiting
         thread::sleep(Duration::from_millis(500));
 
         info!("Deploying boot contract");
-        self.deploy_contract("/app/system_contracts/build/contracts/eosio.boot", "eosio");
+        progress.step("Deploying boot contract");
+        self.deploy_contract(&contract("eosio.boot"), "eosio")?;
 
         info!("Activating features");
-        self.activate_features();
+        progress.step("Activating features");
+        self.activate_features(&features)?;
 
         info!("Deploying main system contracts");
+        progress.step("Deploying main system contracts");
         thread::sleep(Duration::from_millis(500));
-        self.deploy_contract("/app/system_contracts/build/contracts/eosio.system", "eosio");
-
-        self.send_action("eosio", "setpriv", json!(["eosio.msig", 1]),  "eosio@active");
-        self.send_action("eosio", "setpriv", json!(["core.vaulta", 1]), "eosio@active");
+        self.deploy_contract(&contract("eosio.system"), "eosio")?;
 
-        self.deploy_contract("/app/system_contracts/build/contracts/core.vaulta", "core.vaulta");
+        self.send_action("eosio", "setpriv", json!(["eosio.msig", 1]),  "eosio@active")?;
 
         // -----------------------------------------------------------------------------
         //     init system contract
         // -----------------------------------------------------------------------------
 
         info!("Initialize system contract");
+        progress.step("Initializing system contract");
         // Initialize the system account with code zero (needed at initialization time)
-        // and currency / token with precision 4
-        self.send_action("eosio", "init", json!(["0", format!("4,{currency}")]), "eosio@active");
+        // and currency / token with the given precision
+        self.send_action("eosio", "init", json!(["0", format!("{},{currency}", token.precision)]), "eosio@active")?;
 
         // -----------------------------------------------------------------------------
         //     issue `A` token
         // -----------------------------------------------------------------------------
 
-        info!("Initialize `core.vaulta` contract and issue `A` token");
-        // Initialize the core.vaulta account contract
-        // see: https://github.com/VaultaFoundation/vaulta-system-contract/blob/main/tests/eosio.system_tester.hpp#L330
-        self.send_action("core.vaulta", "init", json!(["2100000000.0000 A"]), "core.vaulta@active");
+        if let Some(vaulta) = &profile.vaulta {
+            let account = &vaulta.account;
+            self.send_action("eosio", "setpriv", json!([account, 1]), "eosio@active")?;
+
+            progress.step(&format!("Deploying `{account}` contract"));
+            self.deploy_contract(&contract("core.vaulta"), account)?;
+
+            info!("Initialize `{account}` contract and issue `A` token");
+            progress.step(&format!("Initializing `{account}` contract"));
+            // see: https://github.com/VaultaFoundation/vaulta-system-contract/blob/main/tests/eosio.system_tester.hpp#L330
+            self.send_action(account, "init", json!([vaulta.max_supply]), &format!("{account}@active"))?;
+        }
+
+        progress.finish();
+        Ok(())
     }
 
-    fn setup_token(&self, currency: &str, max_value: &str, initial_value: &str) {
+    fn setup_token(&self, currency: &str, max_value: &str, initial_value: &str) -> Result<()> {
         // Create the currency with a maximum value of max_value tokens
         self.send_action("eosio.token", "create", json!(["eosio", format!("{max_value} {currency}")]),
-                         "eosio.token@active");
+                         "eosio.token@active")?;
 
         // Issue initial_value tokens (Remaining tokens not in circulation can be
         // considered to be held in reserve.)
         self.send_action("eosio.token", "issue", json!(["eosio", format!("{initial_value} {currency}"), "memo"]),
-                         "eosio@active");
+                         "eosio@active")
     }
 
     /// TODO: use builder pattern like so:
     /// dune.new_account("name").with_creator("eosio").with_pubkey("...").create();
-    fn create_account(&self, name: &str, creator: Option<&str>) {
-        let (private, public) = self.create_key();
+    fn create_account(&self, name: &str, creator: Option<&str>) -> Result<()> {
+        let (private, public) = self.create_key()?;
         info!("Creating account `{name}` with public key: {public}");
         let creator = creator.unwrap_or("eosio");
-        self.cleos_cmd(&["create", "account", creator, name, &public]);
-        self.import_key(&private);
+        self.cleos_cmd(&["create", "account", creator, name, &public])?;
+        self.import_key(&private)
     }
 
-    fn preactivate_features(&self) {
+    fn preactivate_features(&self) -> Result<()> {
         let url = format!("{}/v1/producer/schedule_protocol_feature_activations",
                           self.http_addr);
         let feature = "0ec7e080177b2c02b278d5088611686b49d739925a92d9bfcacd7fc6b74053bd";
@@ -590,52 +726,174 @@ impl Dune {
         let args = &["curl", "--no-progress-meter", "--request", "POST", &url, "-d", &data];
 
         debug!("Preactivating features");
-        self.docker.command(args).run();
+        self.docker.command(args).run()?;
+        Ok(())
     }
 
-    fn activate_features(&self) {
-        for (feature, addr) in VAULTA_FEATURES.iter() {
-            debug!("Activating blockchain feature: {feature}");
-            self.send_action("eosio", "activate", json!([addr]), "eosio@active");
+    fn activate_features(&self, digests: &[&str]) -> Result<()> {
+        for digest in digests {
+            debug!("Activating blockchain feature: {digest}");
+            self.send_action("eosio", "activate", json!([digest]), "eosio@active")?;
         }
+        Ok(())
     }
 
-    fn send_action(&self, account: &str, action: &str, data: Value, permission: &str) {
+    fn send_action(&self, account: &str, action: &str, data: Value, permission: &str) -> Result<()> {
         // TODO: do not use an external 'cleos' subprocess to send it but our own kudu::APIClient
-        self.cleos_cmd(&["push", "action", account, action, &data.to_string(), "-p", permission]);
+        self.cleos_cmd(&["push", "action", account, action, &data.to_string(), "-p", permission])?;
+        Ok(())
+    }
+
+    /// Push the given action `iterations` times and return statistics about the
+    /// resources (CPU, NET, RAM) it used, as reported in the transaction traces.
+    ///
+    /// Each transaction is made unique by `cleos` (using `--force-unique`) so that
+    /// identical actions are not rejected as duplicates. This adds a context-free
+    /// action to each transaction which slightly increases its NET usage.
+    pub fn profile_action(&self, contract: &str, action: &str, data: &Value,
+                          permission: &str, iterations: usize) -> Result<ActionProfile> {
+        let data = data.to_string();
+        let mut samples = Vec::with_capacity(iterations);
+        for i in 0..iterations {
+            debug!("Pushing action `{contract}::{action}` ({}/{iterations})", i + 1);
+            let output = self.cleos_cmd(&["push", "action", contract, action, &data,
+                                          "-p", permission, "--json", "--force-unique"])?;
+            let trace: Value = serde_json::from_slice(&output.stdout)?;
+            samples.push(ActionSample::from_trace(&trace)?);
+        }
+        ActionProfile::from_samples(contract, action, &samples)
+            .ok_or_else(|| eyre!("need at least 1 iteration to profile an action"))
     }
 
     /// Deploy a (previously compiled) contract located in `container_dir` to
     /// the given `account`.
-    pub fn deploy_contract(&self, container_dir: &str, account: &str) {
-        debug!("Deploying `{account}` contract (from: {container_dir})");
-        self.cleos_cmd(&["set", "account", "permission", account, "active", "--add-code"]);
-        self.cleos_cmd(&["set", "contract", account, container_dir]);
+    ///
+    /// The `.wasm` and `.abi` files are looked for recursively inside `container_dir`,
+    /// see [`Dune::find_contract_artifacts()`] for details.
+    pub fn deploy_contract(&self, container_dir: &str, account: &str) -> Result<()> {
+        let artifacts = self.find_contract_artifacts(container_dir, None, None)?;
+        self.deploy_contract_artifacts(&artifacts, account)
+    }
+
+    /// Deploy the given contract `.wasm` and `.abi` files to the given `account`.
+    pub fn deploy_contract_artifacts(&self, artifacts: &ContractArtifacts, account: &str) -> Result<()> {
+        debug!("Deploying `{account}` contract (wasm: {}, abi: {})", artifacts.wasm, artifacts.abi);
+        self.cleos_cmd(&["set", "account", "permission", account, "active", "--add-code"])?;
+        let contract_dir = Path::new(&artifacts.wasm).parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/".to_string());
+        self.cleos_cmd(&["set", "contract", account, &contract_dir, &artifacts.wasm, &artifacts.abi])?;
+        Ok(())
+    }
+
+    /// Find the `.wasm` and `.abi` files of a contract located in `container_dir`.
+    ///
+    /// If `wasm` and/or `abi` are given, they are used as is and only the missing one
+    /// is looked for inside the same folder. Otherwise, `container_dir` is searched
+    /// recursively (which handles CMake build subdirectories) for `.wasm` and `.abi`
+    /// files with the same name in the same folder. If more than one pair is found, the
+    /// one named after `container_dir` is chosen, and if there is none an error listing
+    /// all the candidates is returned.
+    ///
+    /// All paths are given and returned as seen from within the container.
+    pub fn find_contract_artifacts(&self, container_dir: &str, wasm: Option<&str>, abi: Option<&str>)
+                                   -> Result<ContractArtifacts> {
+        let sibling = |path: &str, ext: &str| -> Result<String> {
+            let sibling = Path::new(path).with_extension(ext).to_string_lossy().to_string();
+            match self.docker.file_exists(&sibling)? {
+                true => Ok(sibling),
+                false => Err(eyre!("Could not find `.{ext}` file matching `{path}`, tried: {sibling}")),
+            }
+        };
+
+        match (wasm, abi) {
+            (Some(wasm), Some(abi)) => return Ok(ContractArtifacts { wasm: wasm.to_string(), abi: abi.to_string() }),
+            (Some(wasm), None) => return Ok(ContractArtifacts { wasm: wasm.to_string(), abi: sibling(wasm, "abi")? }),
+            (None, Some(abi)) => return Ok(ContractArtifacts { wasm: sibling(abi, "wasm")?, abi: abi.to_string() }),
+            (None, None) => {},
+        }
+
+        let output = self.docker.command(&[
+            "find", container_dir, "-type", "f",
+            "(", "-name", "*.wasm", "-o", "-name", "*.abi", ")",
+            "-not", "-path", "*/CMakeFiles/*",
+        ]).run()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let files: Vec<_> = stdout.lines().collect();
+
+        let mut candidates: Vec<ContractArtifacts> = files.iter()
+            .filter_map(|f| f.strip_suffix(".wasm"))
+            .filter(|stem| files.contains(&format!("{stem}.abi").as_str()))
+            .map(|stem| ContractArtifacts { wasm: format!("{stem}.wasm"), abi: format!("{stem}.abi") })
+            .collect();
+        candidates.sort_by(|a, b| a.wasm.cmp(&b.wasm));
+
+        if candidates.len() > 1 {
+            // follow the `cleos` convention: prefer `<dir>/<dir>.wasm` (or a file with
+            // the same name in a subfolder)
+            let dir_name = Path::new(container_dir).file_name().map(|n| n.to_string_lossy().to_string());
+            let named: Vec<_> = candidates.iter()
+                .filter(|c| Path::new(&c.wasm).file_stem().map(|n| n.to_string_lossy().to_string()) == dir_name)
+                .cloned()
+                .collect();
+            if named.len() == 1 {
+                candidates = named;
+            }
+        }
+
+        match candidates.len() {
+            0 if files.is_empty() => Err(eyre!("No `.wasm` or `.abi` file found in: {container_dir}")),
+            0 => Err(eyre!("No matching `.wasm` and `.abi` files found in: {container_dir}\nfound only:\n  {}",
+                           files.join("\n  "))),
+            1 => Ok(candidates.pop().unwrap()),  // safe unwrap
+            _ => {
+                let candidates: Vec<_> = candidates.iter()
+                    .map(|c| format!("{} + {}", c.wasm, c.abi))
+                    .collect();
+                Err(eyre!(concat!("Found more than one contract in: {}, use the `--wasm` and/or ",
+                                  "`--abi` arguments to specify which one to deploy. Candidates:\n  {}"),
+                          container_dir, candidates.join("\n  ")))
+            },
+        }
     }
 
     /// Build a smart contract located in `container_dir`.
-    pub fn cmake_build(&self, container_dir: &str) {
+    pub fn cmake_build(&self, container_dir: &str) -> Result<()> {
         debug!("Building cmake project in: {container_dir}");
         let build_dir = format!("{container_dir}/build");
-        self.docker.command(&["mkdir", "-p", &build_dir]).run();
+        self.docker.command(&["mkdir", "-p", &build_dir]).run()?;
         self.color_command(&[
             "cmake", "-S", container_dir, "-B", &build_dir,
-        ]).run();
-        self.color_command(&["cmake", "--build", &build_dir]).run();
+        ]).run()?;
+        self.color_command(&["cmake", "--build", &build_dir]).run()?;
+        Ok(())
+    }
+
+    /// Return the symbol of the core token of the chain (eg: `EOS`), as found in the
+    /// RAM market of the system contract, or `EOS` if it cannot be found.
+    pub fn core_symbol(&self) -> Result<String> {
+        let output = self.cleos_cmd(&["get", "table", "eosio", "eosio", "rammarket"])?;
+        let rammarket: Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+        Ok(rammarket["rows"][0]["quote"]["balance"].as_str()
+           .and_then(|balance| balance.split_once(' '))
+           .map(|(_, symbol)| symbol.to_string())
+           .unwrap_or_else(|| SYS_TOKEN_SYMBOL.to_string()))
     }
 
     /// Create a new account with a given creator.
-    pub fn system_newaccount(&self, account: &str, creator: &str) {
-        let (private, public) = self.create_key();
-        self.import_key(&private);
+    pub fn system_newaccount(&self, account: &str, creator: &str) -> Result<()> {
+        let (private, public) = self.create_key()?;
+        self.import_key(&private)?;
 
+        let stake = format!("1.0000 {}", self.core_symbol()?);
         self.cleos_cmd(&[
             "system", "newaccount",
             "--transfer",
-            "--stake-net", &format!("1.0000 {SYS_TOKEN_SYMBOL}"),
-            "--stake-cpu", &format!("1.0000 {SYS_TOKEN_SYMBOL}"),
+            "--stake-net", &stake,
+            "--stake-cpu", &stake,
             "--buy-ram-kbytes", "512",
             creator, account, &public,
-        ]);
+        ])?;
+        Ok(())
     }
 }
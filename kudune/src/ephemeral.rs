@@ -0,0 +1,117 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Throwaway nodes running in their own container, which is destroyed when they
+//! go out of scope. This allows running integration tests with a single command:
+//!
+//! ```sh
+//! kudune run --ephemeral -- cargo test --features integration-tests
+//! ```
+//!
+
+use std::net::TcpListener;
+use std::process::{Command, ExitStatus};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{OptionExt, Result, WrapErr};
+use tracing::{info, warn};
+
+use crate::{BootstrapProfile, Docker, Dune, NodeConfig, ProgressHandler};
+
+
+/// Name of the environment variable containing the URL of the node API, set for
+/// the commands run with [`EphemeralNode::run()`].
+pub const API_URL_ENV_VAR: &str = "KUDU_API_URL";
+
+const NODEOS_HTTP_PORT: u16 = 8888;
+
+
+/// A bootstrapped node running in a uniquely named container, which is destroyed
+/// when this is dropped, even if the program panics.
+pub struct EphemeralNode {
+    dune: Option<Dune>,
+    container: String,
+    api_url: String,
+}
+
+impl EphemeralNode {
+    /// Create a new container from the given image, start a node in it and bootstrap
+    /// the system with the given profile. The node API is exposed on a free port of
+    /// the host.
+    pub fn start(image: &str, host_mount: String, profile: &BootstrapProfile,
+                 progress: Option<ProgressHandler>) -> Result<EphemeralNode> {
+        let container = unique_container_name();
+        let host_port = free_port()?;
+        info!("Starting ephemeral container `{container}`");
+
+        // create the guard first so that the container is destroyed if anything fails
+        let mut node = EphemeralNode {
+            dune: None,
+            container: container.clone(),
+            api_url: format!("http://127.0.0.1:{host_port}"),
+        };
+
+        let mut dune = Dune::new(container, image.to_string(),
+                                 vec![(host_port, NODEOS_HTTP_PORT)], host_mount)?;
+        dune.set_progress_handler(progress);
+        dune.push_config(&NodeConfig::default())?;
+        dune.push_genesis(include_str!("data/genesis_dev.json"))?;
+        dune.start_node(false, true)?;
+        dune.unlock_wallet()?;
+        dune.bootstrap_with_profile(profile)?;
+
+        node.dune = Some(dune);
+        Ok(node)
+    }
+
+    pub fn dune(&self) -> &Dune {
+        self.dune.as_ref().expect("node has been started")
+    }
+
+    pub fn container(&self) -> &str {
+        &self.container
+    }
+
+    /// URL of the node API, as seen from the host.
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    /// Run the given command on the host, with the [`API_URL_ENV_VAR`] environment
+    /// variable pointing to the node API.
+    pub fn run(&self, cmd: &[String]) -> Result<ExitStatus> {
+        run_with_api_url(cmd, &self.api_url)
+    }
+}
+
+impl Drop for EphemeralNode {
+    fn drop(&mut self) {
+        if let Err(err) = Docker::destroy(&self.container) {
+            warn!("Could not destroy ephemeral container `{}`: {err}", self.container);
+        }
+    }
+}
+
+/// Run the given command on the host, with the [`API_URL_ENV_VAR`] environment
+/// variable set to `api_url`.
+pub fn run_with_api_url(cmd: &[String], api_url: &str) -> Result<ExitStatus> {
+    let (program, args) = cmd.split_first().ok_or_eyre("no command given")?;
+    info!("Running `{}` with {API_URL_ENV_VAR}={api_url}", cmd.join(" "));
+    Command::new(program)
+        .args(args)
+        .env(API_URL_ENV_VAR, api_url)
+        .status()
+        .wrap_err_with(|| format!("could not run command: `{program}`"))
+}
+
+fn unique_container_name() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!("kudune_ephemeral_{}_{}", std::process::id(), now.as_millis())
+}
+
+/// Return a TCP port of the host that is not currently in use.
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0").wrap_err("could not find a free port")?;
+    Ok(listener.local_addr()?.port())
+}
@@ -8,14 +8,22 @@
 
 #![doc = include_str!("../README.md")]
 
+pub mod bootstrap;
 pub mod command;
 pub mod docker;
 pub mod dune;
+pub mod ephemeral;
 pub mod nodeconfig;
+pub mod profile;
+pub mod progress;
 mod ratatui;
 pub mod util;
 
-pub use command::{DockerCommand, DockerCommandJson};
-pub use docker::Docker;
-pub use dune::{BuildOpts, Dune};
+pub use bootstrap::BootstrapProfile;
+pub use command::{DockerCommand, DockerCommandJson, DockerError};
+pub use docker::{Docker, Platform};
+pub use dune::{BuildOpts, ContractArtifacts, Dune};
+pub use ephemeral::EphemeralNode;
 pub use nodeconfig::NodeConfig;
+pub use profile::ActionProfile;
+pub use progress::{ProgressEvent, ProgressHandler};
@@ -3,12 +3,14 @@
 
 use std::{env, fs, io, process};
 
-use clap::{Parser, Subcommand, CommandFactory};
+use clap::{Args, Parser, Subcommand, CommandFactory};
+use serde_json::json;
 use color_eyre::eyre::{OptionExt, Result};
 use tracing::{error, info, trace, warn, Level};
 use tracing_subscriber::{EnvFilter, filter::LevelFilter};
 
-use kudune::{BuildOpts, Docker, Dune, NodeConfig};
+use kudune::{BootstrapProfile, BuildOpts, Docker, Dune, EphemeralNode, NodeConfig, Platform, ProgressHandler};
+use kudune::ephemeral::run_with_api_url;
 
 
 #[derive(Parser, Debug)]
@@ -89,17 +91,62 @@ enum Commands {
         /// do not cleanup image after finishing building it. This can be useful during dev
         #[arg(long, default_value_t = false)]
         no_cleanup: bool,
+
+        /// architecture of the image (`amd64` or `arm64`), defaults to the one of the host.
+        /// Spring and CDT are compiled when no package is available for it
+        #[arg(long)]
+        platform: Option<Platform>,
     },
 
     /// Pass-through that runs the given command in the container
+    ///
+    /// Example, to get a shell in the build folder of a contract:
+    /// `kudune exec -it -w ./mycontract/build bash`
     Exec {
+        /// Keep stdin open and allocate a TTY, for interactive commands such as a shell
+        #[arg(short, long, default_value_t = false)]
+        interactive: bool,
+
+        /// Same as `--interactive`, so that `-it` can be used as with `docker exec`
+        #[arg(short, long, default_value_t = false)]
+        tty: bool,
+
+        /// Working directory in which to run the command, as a path on the host
+        /// inside the folder mounted in the container
+        #[arg(short, long)]
+        workdir: Option<String>,
+
         /// The commands you want to execute and its arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
         cmd: Vec<String>,
     },
 
     /// Version of the installed components in the current container
     Info,
 
+    /// Run the given command on the host with the `KUDU_API_URL` environment
+    /// variable pointing to the node API, and exit with its exit code
+    ///
+    /// With `--ephemeral`, a uniquely named container is created, its node is
+    /// started and bootstrapped before running the command, and the container is
+    /// destroyed afterwards regardless of the outcome.
+    ///
+    /// Example:
+    /// `kudune run --ephemeral -- cargo test --features integration-tests`
+    Run {
+        /// Run the command against a new bootstrapped node in a throwaway container
+        #[arg(long, default_value_t = false)]
+        ephemeral: bool,
+
+        /// Profile used to bootstrap the node, with `--ephemeral`
+        #[command(flatten)]
+        profile: ProfileArgs,
+
+        /// The command you want to run and its arguments
+        #[arg(last = true, required = true)]
+        cmd: Vec<String>,
+    },
+
     // -----------------------------------------------------------------------------
     //     Commands operating on a docker container
     // -----------------------------------------------------------------------------
@@ -132,6 +179,17 @@ enum Commands {
         #[arg(long)]
         config: Option<String>,
 
+        /// Comma-separated list of plugins to enable, replacing the ones in the config
+        ///
+        /// Plugins can be given by their short name (`chain_api`, `db_size_api`, `http`,
+        /// `net_api`, `producer`, `producer_api`, `prometheus`, `ship`, `trace_api`),
+        /// by their full name (eg: `eosio::chain_api_plugin`) or using one of the
+        /// presets `producer`, `api` or `indexer`.
+        ///
+        /// Example: `kudune start-node --plugins indexer,trace_api`
+        #[arg(long)]
+        plugins: Option<String>,
+
         /// Whether to replay the blockchain from the beginning when starting
         #[arg(short, long, default_value_t=false)]
         replay_blockchain: bool,
@@ -144,6 +202,13 @@ enum Commands {
     /// Stop nodeos in the current container
     StopNode,
 
+    /// Delete the blocks and state of the chain, keeping the config and the wallet
+    ///
+    /// This is useful to start over from the genesis while keeping the same setup,
+    /// as opposed to `start-node --clean`. The node is restarted if it was running
+    /// and needs to be bootstrapped again.
+    ResetChain,
+
     /// Destroy the current Vaulta container
     Destroy,
 
@@ -152,8 +217,16 @@ enum Commands {
     // -----------------------------------------------------------------------------
 
 
-    /// Bootstrap a running system by installing the system contracts etc. FIXME desc
-    Bootstrap,
+    /// Bootstrap a running system by creating the system accounts, activating the
+    /// protocol features, installing the system contracts and creating the system token
+    ///
+    /// What gets installed is determined by the bootstrap profile, which can be one of
+    /// the built-in ones (`vaulta`, `vanilla-eos`, `savanna-testing`) or a TOML file
+    /// overriding the values of one of them (given by its `base` key).
+    Bootstrap {
+        #[command(flatten)]
+        profile: ProfileArgs,
+    },
 
     /// Create a new account on the blockchain with initial resources
     #[command(name="system-newaccount")]
@@ -171,6 +244,12 @@ enum Commands {
         location: String,
         /// The account name on which to deploy the contract
         account: String,
+        /// The `.wasm` file to deploy, if it cannot be found automatically in `location`
+        #[arg(long)]
+        wasm: Option<String>,
+        /// The `.abi` file to deploy, if it cannot be found automatically in `location`
+        #[arg(long)]
+        abi: Option<String>,
     },
 
     /// Build the cmake project in the given directory
@@ -189,9 +268,70 @@ enum Commands {
         table: String,
     },
 
+    /// Push an action repeatedly and report the resources (CPU, NET, RAM) it used, as JSON
+    ///
+    /// Example:
+    /// `kudune profile-action eosio.token transfer '["alice", "bob", "1.0000 SYS", ""]' --permission alice -n 100`
+    ProfileAction {
+        /// The account on which the contract is deployed
+        contract: String,
+        /// The name of the action to push
+        action: String,
+        /// The action data, as a JSON object or array
+        args: String,
+        /// The permission used to sign the action, defaults to `<contract>@active`
+        #[arg(long)]
+        permission: Option<String>,
+        /// The number of times the action is pushed
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: usize,
+    },
+
     /// Show the wallet password
     WalletPassword,
 
+    /// Manage the keys stored in the container's wallet, outputting JSON
+    Wallet {
+        #[command(subcommand)]
+        command: WalletCommands,
+    },
+
+}
+
+#[derive(Subcommand, Debug)]
+enum WalletCommands {
+    /// Import the given private key into the wallet and output its public key
+    ImportKey {
+        /// The private key to import, eg: `PVT_K1_...`
+        private_key: String,
+    },
+
+    /// Create a new keypair in the wallet and output it
+    CreateKey,
+
+    /// Output the public keys of all the keys stored in the wallet
+    ListKeys,
+}
+
+#[derive(Args, Debug)]
+struct ProfileArgs {
+    /// Name of the built-in bootstrap profile
+    #[arg(long, default_value = kudune::bootstrap::DEFAULT_PROFILE)]
+    profile: String,
+
+    /// TOML file overriding values of the bootstrap profile, using its `base` key
+    /// to select the built-in profile it is based on (default: `vaulta`)
+    #[arg(long, conflicts_with = "profile")]
+    profile_file: Option<String>,
+}
+
+impl ProfileArgs {
+    fn load(&self) -> Result<BootstrapProfile> {
+        match &self.profile_file {
+            Some(filename) => BootstrapProfile::from_toml(&fs::read_to_string(filename)?),
+            None => BootstrapProfile::builtin(&self.profile),
+        }
+    }
 }
 
 fn init_tracing(verbose_level: u8) {
@@ -237,16 +377,16 @@ fn main() -> Result<()> {
     // this avoids building and starting a container when it is not needed
     match cmd {
         Commands::ListContainers => {
-            for c in Docker::list_all_containers().iter() {
+            for c in Docker::list_all_containers()?.iter() {
                 let name = c["Names"].to_string();
-                let status = c["Status"].as_str().unwrap();
+                let status = c["Status"].as_str().unwrap_or_default();
                 println!("Container: {:20} ({})", name, status);
             }
         },
         Commands::Info => {
             Docker::info(&cli.container)?;
         }
-        Commands::BuildImage { base, spring, cdt, system_contracts, compile, nproc, no_cleanup } => {
+        Commands::BuildImage { base, spring, cdt, system_contracts, compile, nproc, no_cleanup, platform } => {
             let compile_info = match compile {
                 true => "(compiled)",
                 false => "(packaged)",
@@ -262,11 +402,24 @@ fn main() -> Result<()> {
                 nproc,
                 cleanup: !no_cleanup,
                 verbose: cli.verbose >= 1,
+                platform: platform.unwrap_or_else(Platform::host),
+                progress: (!cli.quiet).then(ProgressHandler::terminal),
             };
             Dune::build_image(&opts)?;
         },
         Commands::Destroy => {
-            Docker::destroy(cli.container.as_str());
+            Docker::destroy(cli.container.as_str())?;
+        },
+        Commands::Run { ephemeral: true, profile, cmd } => {
+            let home = env::var("HOME").expect("$HOME variable should be set");
+            let profile = profile.load()?;
+            let progress = (!cli.quiet).then(ProgressHandler::terminal);
+            let status = {
+                // the container is destroyed when the node goes out of scope
+                let node = EphemeralNode::start(&cli.image, home, &profile, progress)?;
+                node.run(&cmd)?
+            };
+            process::exit(status.code().unwrap_or(1));
         },
         // all the other commands need a `Dune` instance, get one now and keep matching
         _ => {
@@ -280,12 +433,31 @@ fn main() -> Result<()> {
             )?;
 
             // unlock our wallet pre-emptively to be able to perform operations
-            dune.unlock_wallet();
+            dune.unlock_wallet()?;
 
             match cmd {
                 Commands::WalletPassword => {
                     info!("Wallet password is:");
-                    println!("{}", &dune.get_wallet_password());
+                    println!("{}", &dune.get_wallet_password()?);
+                },
+                Commands::Wallet { command } => {
+                    let output = match command {
+                        WalletCommands::ImportKey { private_key } => {
+                            json!({ "public_key": dune.wallet_import_key(&private_key)? })
+                        },
+                        WalletCommands::CreateKey => {
+                            let (private_key, public_key) = dune.wallet_create_key()?;
+                            json!({ "private_key": private_key, "public_key": public_key })
+                        },
+                        WalletCommands::ListKeys => json!(dune.wallet_keys()?),
+                    };
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                },
+                Commands::ProfileAction { contract, action, args, permission, iterations } => {
+                    let args = kudu::json::parse_strict(&args)?;
+                    let permission = permission.unwrap_or_else(|| format!("{contract}@active"));
+                    let profile = dune.profile_action(&contract, &action, &args, &permission, iterations)?;
+                    println!("{}", serde_json::to_string_pretty(&profile.to_json())?);
                 },
                 Commands::SetConfig { args } => {
                     warn!("set config: {:?}", &args);
@@ -293,7 +465,7 @@ fn main() -> Result<()> {
                         NodeConfig::default()
                     }
                     else {
-                        let mut cfg = dune.pull_config();
+                        let mut cfg = dune.pull_config()?;
                         for arg in args {
                             cfg.add_param(&arg).unwrap_or_else(|msg| {
                                 error!("{}", msg);
@@ -302,58 +474,98 @@ fn main() -> Result<()> {
                         }
                         cfg
                     };
-                    dune.push_config(&cfg);
+                    dune.push_config(&cfg)?;
                 },
-                Commands::StartNode { config, replay_blockchain, clean } => {
+                Commands::StartNode { config, plugins, replay_blockchain, clean } => {
+                    if plugins.is_some() && config.as_deref() == Some("none") {
+                        error!("cannot set plugins when not using a config file (`--config none`)");
+                        process::exit(1);
+                    }
                     match config.as_deref() {
                         Some("none") => {
-                            if dune.has_config() {
-                                dune.rm_config();
+                            if dune.has_config()? {
+                                dune.rm_config()?;
                             }
                         },
                         Some("default") => {
-                            dune.push_config(&NodeConfig::default());
+                            dune.push_config(&NodeConfig::default())?;
                         },
                         Some(filename) => {
-                            let contents = fs::read_to_string(filename).unwrap();
-                            dune.push_config(&NodeConfig::from_ini(&contents));
+                            let contents = fs::read_to_string(filename)?;
+                            dune.push_config(&NodeConfig::from_ini(&contents))?;
                         },
                         None => {
                             // use the one already there, or create a default one
-                            if !dune.has_config() {
-                                dune.push_config(&NodeConfig::default());
+                            if !dune.has_config()? {
+                                dune.push_config(&NodeConfig::default())?;
                             }
                         }
                     }
-                    dune.push_genesis(include_str!("data/genesis_dev.json"));
-                    dune.start_node(replay_blockchain, clean);
+                    if let Some(plugins) = plugins {
+                        let mut cfg = dune.pull_config()?;
+                        cfg.set_plugins(&plugins).unwrap_or_else(|msg| {
+                            error!("{}", msg);
+                            process::exit(1);
+                        });
+                        dune.push_config(&cfg)?;
+                    }
+                    dune.push_genesis(include_str!("data/genesis_dev.json"))?;
+                    dune.start_node(replay_blockchain, clean)?;
                 },
                 Commands::StopNode => {
-                    dune.stop_node();
+                    dune.stop_node()?;
                 },
-                Commands::Bootstrap => {
-                    dune.bootstrap_system();
+                Commands::ResetChain => {
+                    dune.reset_chain()?;
+                },
+                Commands::Bootstrap { profile } => {
+                    let profile = profile.load()?;
+                    if !cli.quiet {
+                        dune.set_progress_handler(Some(ProgressHandler::terminal()));
+                    }
+                    dune.bootstrap_with_profile(&profile)?;
                 },
                 Commands::SystemNewAccount { account, creator } => {
                     dune.system_newaccount(&account, creator.as_deref()
-                                           .expect("has default value"));
+                                           .expect("has default value"))?;
                 },
-                Commands::DeployContract { location, account } => {
+                Commands::DeployContract { location, account, wasm, abi } => {
                     let location = dune.host_to_container_path(&location)?;
-                    dune.deploy_contract(&location, &account);
+                    let wasm = wasm.map(|f| dune.host_to_container_path(&f)).transpose()?;
+                    let abi = abi.map(|f| dune.host_to_container_path(&f)).transpose()?;
+                    let artifacts = dune.find_contract_artifacts(&location, wasm.as_deref(), abi.as_deref())?;
+                    dune.deploy_contract_artifacts(&artifacts, &account)?;
                 },
                 Commands::CmakeBuild { location } => {
                     let location = dune.host_to_container_path(&location)?;
-                    dune.cmake_build(&location);
+                    dune.cmake_build(&location)?;
                 },
                 Commands::GetTable { account, scope, table } => {
-                    let output = String::from_utf8(dune.cleos_cmd(&["get", "table", &account, &scope, &table]).stdout)?;
+                    let output = String::from_utf8(dune.cleos_cmd(&["get", "table", &account, &scope, &table])?.stdout)?;
                     info!("{}", &output);
 
                 },
-                Commands::Exec { cmd } => {
+                Commands::Run { ephemeral: false, cmd, .. } => {
+                    if !dune.is_node_running()? {
+                        dune.start_node(false, false)?;
+                    }
+                    let ports: Vec<_> = cli.ports.split(",").map(parse_mapping).collect::<Result<_>>()?;
+                    let (host_port, _) = ports.into_iter().find(|&(_, port)| port == 8888)
+                        .ok_or_eyre("no port mapping found for the nodeos HTTP port (8888)")?;
+                    let status = run_with_api_url(&cmd, &format!("http://127.0.0.1:{host_port}"))?;
+                    process::exit(status.code().unwrap_or(1));
+                },
+                Commands::Exec { interactive, tty, workdir, cmd } => {
                     let cmd: Vec<_> = cmd.iter().map(String::as_str).collect();
-                    dune.command(&cmd).capture_output(false).run();
+                    let workdir = workdir.map(|dir| dune.host_to_container_path(&dir)).transpose()?;
+                    if interactive || tty {
+                        // the exit status belongs to the user session, just forward it
+                        let output = dune.exec_command(&cmd, workdir.as_deref(), true)
+                            .check_status(false)
+                            .run()?;
+                        process::exit(output.status.code().unwrap_or(1));
+                    }
+                    dune.exec_command(&cmd, workdir.as_deref(), false).run()?;
                 }
                 _ => todo!(),
             }
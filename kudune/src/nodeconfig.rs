@@ -34,6 +34,29 @@ const DEFAULT_PLUGINS: &[&str] = &[
     "eosio::producer_api_plugin"
 ];
 
+/// Short names of the nodeos plugins that can be given to [`NodeConfig::set_plugins()`]
+pub const PLUGIN_NAMES: &[(&str, &str)] = &[
+    ("chain_api", "eosio::chain_api_plugin"),
+    ("db_size_api", "eosio::db_size_api_plugin"),
+    ("http", "eosio::http_plugin"),
+    ("net_api", "eosio::net_api_plugin"),
+    ("producer", "eosio::producer_plugin"),
+    ("producer_api", "eosio::producer_api_plugin"),
+    ("prometheus", "eosio::prometheus_plugin"),
+    ("ship", "eosio::state_history_plugin"),
+    ("trace_api", "eosio::trace_api_plugin"),
+];
+
+/// Named sets of plugins that can be given to [`NodeConfig::set_plugins()`]
+///
+/// All of them include the producer plugin, as the node in the container is the
+/// only one producing blocks.
+pub const PLUGIN_PRESETS: &[(&str, &[&str])] = &[
+    ("producer", &["producer", "producer_api", "chain_api", "http"]),
+    ("api", &["producer", "chain_api", "db_size_api", "net_api", "http"]),
+    ("indexer", &["producer", "chain_api", "ship", "http"]),
+];
+
 #[derive(Clone, Debug)]
 pub struct NodeConfig {
     pub params: HashMap<String, String>,
@@ -99,7 +122,61 @@ impl NodeConfig {
         Ok(())
     }
 
+    /// Replace the list of plugins with the ones given as a comma-separated list.
+    ///
+    /// Each item can be a short plugin name from [`PLUGIN_NAMES`], a preset from
+    /// [`PLUGIN_PRESETS`] or a fully qualified plugin name. Note that presets take
+    /// precedence over plugins with the same short name, eg: `producer` enables
+    /// the producer API as well.
+    ///
+    /// ```
+    /// # use kudune::NodeConfig;
+    /// let mut config = NodeConfig::default();
+    /// config.set_plugins("indexer,trace_api").unwrap();
+    /// assert_eq!(config.plugins, ["eosio::producer_plugin", "eosio::chain_api_plugin",
+    ///                             "eosio::state_history_plugin", "eosio::http_plugin",
+    ///                             "eosio::trace_api_plugin"]);
+    ///
+    /// assert!(config.set_plugins("chain_api,unknown").is_err());
+    /// ```
+    pub fn set_plugins(&mut self, plugins: &str) -> Result<(), String> {
+        let mut result: Vec<String> = vec![];
+        for item in plugins.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let names = match PLUGIN_PRESETS.iter().find(|(preset, _)| *preset == item) {
+                Some((_, names)) => names,
+                None => &[item][..],
+            };
+            for name in names {
+                let plugin = resolve_plugin(name)?;
+                if !result.contains(&plugin) {
+                    result.push(plugin);
+                }
+            }
+        }
+        if result.is_empty() {
+            return Err("no plugins given".to_string());
+        }
+        self.plugins = result;
+        Ok(())
+    }
+
     pub fn http_addr(&self) -> &str {
         self.params.get("http-server-address").map_or(DEFAULT_HTTP_ADDR, |x| x)
     }
 }
+
+/// Return the fully qualified name of a plugin given its short name.
+fn resolve_plugin(name: &str) -> Result<String, String> {
+    if name.starts_with("eosio::") {
+        return Ok(name.to_string());
+    }
+    match PLUGIN_NAMES.iter().find(|(short, _)| *short == name) {
+        Some((_, plugin)) => Ok(plugin.to_string()),
+        None => {
+            let valid: Vec<_> = PLUGIN_NAMES.iter().map(|(short, _)| *short)
+                .chain(PLUGIN_PRESETS.iter().map(|(preset, _)| *preset))
+                .collect();
+            Err(format!("unknown plugin: '{name}', valid values are: {}", valid.join(", ")))
+        },
+    }
+}
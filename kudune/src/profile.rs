@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Resource usage statistics computed from the traces of actions pushed
+//! repeatedly on a local chain, see [`Dune::profile_action()`].
+//!
+//! [`Dune::profile_action()`]: crate::Dune::profile_action
+//!
+
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::{OptionExt, Result};
+use serde_json::{json, Value};
+
+
+/// Resources used by a single execution of an action, as found in the trace
+/// of the transaction containing it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionSample {
+    pub cpu_us: u64,
+    pub net_bytes: u64,
+    /// RAM delta for each account whose RAM usage changed, in bytes
+    pub ram_deltas: BTreeMap<String, i64>,
+}
+
+impl ActionSample {
+    /// Extract the resources used from the JSON output of `cleos push action --json`.
+    pub fn from_trace(trace: &Value) -> Result<ActionSample> {
+        let processed = &trace["processed"];
+        let receipt = &processed["receipt"];
+        let mut sample = ActionSample {
+            cpu_us: receipt["cpu_usage_us"].as_u64().ok_or_eyre("no CPU usage in transaction receipt")?,
+            net_bytes: 8 * receipt["net_usage_words"].as_u64().ok_or_eyre("no NET usage in transaction receipt")?,
+            ram_deltas: BTreeMap::new(),
+        };
+
+        // inline actions and notifications have their own trace in `action_traces`
+        let action_traces = processed["action_traces"].as_array().ok_or_eyre("no action traces in transaction")?;
+        for delta in action_traces.iter().filter_map(|t| t["account_ram_deltas"].as_array()).flatten() {
+            let account = delta["account"].as_str().ok_or_eyre("no account in RAM delta")?;
+            let bytes = delta["delta"].as_i64().ok_or_eyre("no delta in RAM delta")?;
+            *sample.ram_deltas.entry(account.to_string()).or_default() += bytes;
+        }
+        Ok(sample)
+    }
+}
+
+/// Summary statistics of a resource measured over several iterations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub min: u64,
+    pub max: u64,
+    pub avg: f64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+impl Stats {
+    /// Compute the statistics for the given samples, return `None` if there are none.
+    pub fn from_samples(samples: impl IntoIterator<Item = u64>) -> Option<Stats> {
+        let mut sorted: Vec<u64> = samples.into_iter().collect();
+        sorted.sort_unstable();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        // nearest-rank method
+        let percentile = |p: usize| sorted[(p * sorted.len()).div_ceil(100).max(1) - 1];
+        Some(Stats {
+            min,
+            max,
+            avg: sorted.iter().sum::<u64>() as f64 / sorted.len() as f64,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "min": self.min,
+            "avg": self.avg,
+            "p50": self.p50,
+            "p90": self.p90,
+            "p99": self.p99,
+            "max": self.max,
+        })
+    }
+}
+
+/// Report of the resources used by an action over several iterations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionProfile {
+    pub contract: String,
+    pub action: String,
+    pub iterations: usize,
+    pub cpu_us: Stats,
+    pub net_bytes: Stats,
+    /// Total RAM delta for each account over all the iterations, in bytes
+    pub ram_deltas: BTreeMap<String, i64>,
+}
+
+impl ActionProfile {
+    /// Build the report from the samples of all the iterations, return `None` if
+    /// there are none.
+    pub fn from_samples(contract: &str, action: &str, samples: &[ActionSample]) -> Option<ActionProfile> {
+        let mut ram_deltas = BTreeMap::new();
+        for (account, delta) in samples.iter().flat_map(|s| &s.ram_deltas) {
+            *ram_deltas.entry(account.clone()).or_default() += delta;
+        }
+        Some(ActionProfile {
+            contract: contract.to_string(),
+            action: action.to_string(),
+            iterations: samples.len(),
+            cpu_us: Stats::from_samples(samples.iter().map(|s| s.cpu_us))?,
+            net_bytes: Stats::from_samples(samples.iter().map(|s| s.net_bytes))?,
+            ram_deltas,
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let ram: BTreeMap<_, _> = self.ram_deltas.iter().map(|(account, &total)| {
+            (account, json!({ "total": total, "avg": total as f64 / self.iterations as f64 }))
+        }).collect();
+        json!({
+            "contract": self.contract,
+            "action": self.action,
+            "iterations": self.iterations,
+            "cpu_us": self.cpu_us.to_json(),
+            "net_bytes": self.net_bytes.to_json(),
+            "ram_delta_bytes": ram,
+        })
+    }
+}
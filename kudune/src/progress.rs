@@ -0,0 +1,189 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//!
+//! Progress reporting for long-running operations, such as [`Dune::build_image()`]
+//! or [`Dune::bootstrap_system()`].
+//!
+//! Progress is reported as a sequence of [`ProgressEvent`]s sent to a [`ProgressHandler`],
+//! which can either be a callback or a channel from which the events can be received.
+//!
+//! [`Dune::build_image()`]: crate::Dune::build_image
+//! [`Dune::bootstrap_system()`]: crate::Dune::bootstrap_system
+//!
+
+use std::fmt;
+use std::sync::{Arc, Mutex, mpsc};
+
+use ratatui::{style::{Color, Style}, widgets::LineGauge};
+
+use crate::ratatui::render;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// A new operation starts, which will be executed in `total_steps` steps.
+    Started { operation: String, total_steps: usize },
+
+    /// The step with the given index (starting at 1) of the current operation starts.
+    Step { operation: String, step: usize, total_steps: usize, message: String },
+
+    /// The current operation has finished, successfully or not.
+    Finished { operation: String, success: bool },
+}
+
+impl ProgressEvent {
+    pub fn operation(&self) -> &str {
+        match self {
+            ProgressEvent::Started { operation, .. } => operation,
+            ProgressEvent::Step { operation, .. } => operation,
+            ProgressEvent::Finished { operation, .. } => operation,
+        }
+    }
+
+    /// Return the completion ratio of the current operation, between 0 and 1.
+    ///
+    /// Note that this is the ratio of completed steps, the current step being
+    /// considered as not completed yet.
+    pub fn ratio(&self) -> f64 {
+        match self {
+            ProgressEvent::Started { .. } => 0.0,
+            ProgressEvent::Step { step, total_steps, .. } => {
+                (step.saturating_sub(1) as f64 / (*total_steps).max(1) as f64).min(1.0)
+            },
+            ProgressEvent::Finished { .. } => 1.0,
+        }
+    }
+
+    pub fn percent(&self) -> u8 {
+        (self.ratio() * 100.0).round() as u8
+    }
+}
+
+
+/// Receive the [`ProgressEvent`]s of an operation, either through a callback or
+/// a channel. Cloning a `ProgressHandler` shares the underlying callback.
+#[derive(Clone)]
+pub struct ProgressHandler {
+    callback: Arc<dyn Fn(&ProgressEvent) + Send + Sync>,
+}
+
+impl ProgressHandler {
+    /// Return a handler that calls `callback` for each event.
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&ProgressEvent) + Send + Sync + 'static
+    {
+        ProgressHandler { callback: Arc::new(callback) }
+    }
+
+    /// Return a handler that forwards all events to the returned channel receiver.
+    ///
+    /// Events are silently dropped once the receiver has been dropped.
+    pub fn channel() -> (Self, mpsc::Receiver<ProgressEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let handler = ProgressHandler::new(move |event| {
+            let _ = sender.lock().unwrap().send(event.clone());
+        });
+        (handler, receiver)
+    }
+
+    /// Return a handler that prints a progress bar on `stderr` for each event.
+    pub fn terminal() -> Self {
+        ProgressHandler::new(|event| {
+            let width = crossterm::terminal::size().map(|(w, _)| w).unwrap_or(80).min(100);
+            eprintln!("{}", render_progress(event, width));
+        })
+    }
+
+    pub fn emit(&self, event: &ProgressEvent) {
+        (self.callback)(event)
+    }
+}
+
+impl fmt::Debug for ProgressHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
+
+
+/// Helper that keeps track of the current step of an operation and sends the
+/// corresponding events to an (optional) handler.
+///
+/// If it is dropped before [`ProgressTracker::finish()`] has been called, the
+/// operation is reported as failed.
+pub(crate) struct ProgressTracker<'a> {
+    handler: Option<&'a ProgressHandler>,
+    operation: String,
+    step: usize,
+    total_steps: usize,
+    finished: bool,
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub fn new(handler: Option<&'a ProgressHandler>, operation: &str, total_steps: usize) -> Self {
+        let result = ProgressTracker {
+            handler,
+            operation: operation.to_string(),
+            step: 0,
+            total_steps,
+            finished: false,
+        };
+        result.emit(ProgressEvent::Started { operation: result.operation.clone(), total_steps });
+        result
+    }
+
+    pub fn step(&mut self, message: &str) {
+        self.step += 1;
+        self.emit(ProgressEvent::Step {
+            operation: self.operation.clone(),
+            step: self.step,
+            total_steps: self.total_steps,
+            message: message.to_string(),
+        });
+    }
+
+    pub fn finish(mut self) {
+        self.finished = true;
+        self.emit(ProgressEvent::Finished { operation: self.operation.clone(), success: true });
+    }
+
+    fn emit(&self, event: ProgressEvent) {
+        if let Some(handler) = self.handler {
+            handler.emit(&event);
+        }
+    }
+}
+
+impl Drop for ProgressTracker<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.emit(ProgressEvent::Finished { operation: self.operation.clone(), success: false });
+        }
+    }
+}
+
+
+/// Render a progress event as a single line containing a progress bar, suitable
+/// to be printed on a terminal.
+pub fn render_progress(event: &ProgressEvent, width: u16) -> String {
+    let label = match event {
+        ProgressEvent::Started { operation, .. } => format!("{operation}: starting"),
+        ProgressEvent::Step { step, total_steps, message, .. } => format!("[{step}/{total_steps}] {message}"),
+        ProgressEvent::Finished { operation, success: true } => format!("{operation}: done"),
+        ProgressEvent::Finished { operation, success: false } => format!("{operation}: failed"),
+    };
+    let color = match event {
+        ProgressEvent::Finished { success: false, .. } => Color::Red,
+        _ => Color::Green,
+    };
+    render(width, 1, |f| {
+        let gauge = LineGauge::default()
+            .label(format!("{:>3}% {label}", event.percent()))
+            .filled_style(Style::new().fg(color))
+            .ratio(event.ratio());
+        f.render_widget(gauge, f.area());
+    }).trim_end().to_string()
+}
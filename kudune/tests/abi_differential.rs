@@ -0,0 +1,191 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+// =============================================================================
+//
+//     Differential tests checking that `kudu` and `nodeos` agree on how data is
+//     encoded and decoded, using a node running in a Docker container.
+//
+//     These tests need Docker and are quite slow (the first run will build the
+//     Docker image), so they are only enabled with the `container-tests` feature:
+//
+//       cargo test -p kudune --features container-tests
+//
+//     A `kudutest` contract (see `tests/data/kudutest`) that stores its argument
+//     in a table is deployed on a freshly bootstrapped chain, then:
+//      - action data packed by `kudu` is compared to the one packed by `cleos`
+//      - actions packed by `kudu` are pushed, and the table rows are read back
+//        both in binary (decoded by `kudu`) and in JSON (decoded by `nodeos`)
+//
+//     The container is left running after the tests, remove it with:
+//
+//       kudune --container kudune_tests destroy
+//
+// =============================================================================
+
+#![cfg(feature = "container-tests")]
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use color_eyre::eyre::{Result, eyre};
+use serde_json::{Value, json};
+
+use kudu::{ABI, ABIDefinition, APIClient, Bytes};
+use kudune::Dune;
+
+const CONTAINER: &str = "kudune_tests";
+const IMAGE: &str = "vaulta:latest";
+const HOST_PORT: u16 = 18888;
+const CONTRACT: &str = "kudutest";
+
+
+struct TestNode {
+    dune: Dune,
+    client: APIClient,
+    abi: ABI,
+}
+
+/// Return a node with the test contract deployed, setting it up on first call.
+fn node() -> &'static TestNode {
+    static NODE: OnceLock<TestNode> = OnceLock::new();
+    NODE.get_or_init(|| setup().expect("could not setup test node"))
+}
+
+fn setup() -> Result<TestNode> {
+    let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data").canonicalize()?;
+    let host_mount = data_dir.to_string_lossy().to_string();
+
+    let mut dune = Dune::new(CONTAINER.to_string(), IMAGE.to_string(),
+                             vec![(HOST_PORT, 8888)], host_mount.clone())?;
+
+    // always start from a clean chain so that bootstrapping succeeds
+    dune.stop_node()?;
+    dune.start_node(false, true)?;
+    dune.unlock_wallet()?;
+    dune.bootstrap_system()?;
+
+    let contract_dir = dune.host_to_container_path(&format!("{host_mount}/{CONTRACT}"))?;
+    dune.cmake_build(&contract_dir)?;
+    dune.system_newaccount(CONTRACT, "eosio")?;
+    dune.deploy_contract(&contract_dir, CONTRACT)?;
+
+    let client = APIClient::new(&format!("http://127.0.0.1:{HOST_PORT}"));
+    let abi = client.call("/v1/chain/get_abi", &json!({"account_name": CONTRACT}))?;
+    let abi = ABI::from_definition(&ABIDefinition::from_variant(&abi["abi"])?)?;
+
+    Ok(TestNode { dune, client, abi })
+}
+
+fn cleos(args: &[&str]) -> Result<String> {
+    let output = node().dune.cleos_cmd(args)?;
+    match output.status.success() {
+        true => Ok(String::from_utf8(output.stdout)?.trim().to_string()),
+        false => Err(eyre!("cleos command failed: {args:?}\n{}", String::from_utf8_lossy(&output.stderr))),
+    }
+}
+
+/// Return rows with different values for all the fields of the `alltypes` struct.
+fn test_rows() -> Vec<Value> {
+    let row = |id: u64, small: bool| {
+        json!({
+            "id": id,
+            "b": small,
+            "i8": if small { 0 } else { -128 },
+            "u8": if small { 0 } else { 255 },
+            "i16": if small { -1 } else { -32768 },
+            "u16": if small { 1 } else { 65535 },
+            "i32": if small { -1 } else { -2147483648i64 },
+            "u32": if small { 1 } else { 4294967295u64 },
+            "i64": if small { json!(-1) } else { json!("-9223372036854775808") },
+            "u64": if small { json!(1) } else { json!("18446744073709551615") },
+            "i128": if small { "-1" } else { "-170141183460469231731687303715884105728" },
+            "u128": if small { "1" } else { "340282366920938463463374607431768211455" },
+            "vi32": if small { -1 } else { -2147483648i64 },
+            "vu32": if small { 1 } else { 4294967295u64 },
+            "f32": if small { "0.5" } else { "-3.40282346638528859811704183484516925e+38" },
+            "f64": if small { "0.10000000000000001" } else { "1.79769313486231570814527423731704357e+308" },
+            "tp": if small { "1970-01-01T00:00:00.000" } else { "2106-02-07T06:28:15.000" },
+            "tps": if small { "1970-01-01T00:00:00" } else { "2106-02-07T06:28:15" },
+            "bts": if small { "2000-01-01T00:00:00.000" } else { "2024-10-14T12:34:56.500" },
+            "n": if small { "" } else { "zzzzzzzzzzzzj" },
+            "bytes": if small { "" } else { "00ff7f80" },
+            "s": if small { "" } else { "kudu ❤️ unicode" },
+            "c160": "0123456789abcdef0123456789abcdef01234567",
+            "c256": "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            "c512": concat!("0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+                            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"),
+            "pubkey": "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63",
+            "sig": "SIG_K1_Kg2UKjXTX48gw2wWH4zmsZmWu3yarcfC21Bd9JPj7QoDURqiAacCHmtExPk3syPb2tFLsp1R4ttXLXgr7FYgDvKPC5RCkx",
+            "sym": if small { "0,A" } else { "18,ABCDEFG" },
+            "symcode": if small { "A" } else { "ABCDEFG" },
+            "a": if small { "0 A" } else { "-1.2345 EOS" },
+            "ea": { "quantity": "1.0000 EOS", "contract": "eosio.token" },
+            "opt": if small { json!(null) } else { json!(42) },
+            "var": if small { json!(["uint64", 7]) } else { json!(["string", "variant"]) },
+            "names": if small { json!([]) } else { json!(["eosio", "eosio.token", "kudutest"]) },
+        })
+    };
+    vec![row(1, true), row(2, false)]
+}
+
+
+#[test]
+fn test_pack_action_data() -> Result<()> {
+    let node = node();
+
+    for row in test_rows() {
+        let args = json!({"row": &row});
+        let kudu_packed = node.abi.variant_to_binary("store", &args)?;
+        let nodeos_packed = cleos(&["convert", "pack_action_data", CONTRACT, "store", &args.to_string()])?;
+        assert_eq!(kudu_packed.to_hex(), nodeos_packed, "packed data differs for: {args}");
+
+        let nodeos_unpacked: Value = serde_json::from_str(&cleos(&[
+            "convert", "unpack_action_data", CONTRACT, "store", &kudu_packed.to_hex()
+        ])?)?;
+        let kudu_unpacked = node.abi.binary_to_variant("store", kudu_packed)?;
+        assert_eq!(kudu_unpacked, nodeos_unpacked, "unpacked data differs for: {args}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_table_rows() -> Result<()> {
+    let node = node();
+
+    // push actions packed by kudu, as raw hex data
+    for row in test_rows() {
+        let data = node.abi.variant_to_binary("store", &json!({"row": row}))?;
+        let trx = json!({
+            "actions": [{
+                "account": CONTRACT,
+                "name": "store",
+                "authorization": [{"actor": CONTRACT, "permission": "active"}],
+                "data": data.to_hex(),
+            }]
+        });
+        cleos(&["push", "transaction", &trx.to_string()])?;
+    }
+
+    let get_rows = |json: bool| -> Result<Vec<Value>> {
+        let result = node.client.call("/v1/chain/get_table_rows", &json!({
+            "code": CONTRACT,
+            "scope": CONTRACT,
+            "table": "rows",
+            "json": json,
+        }))?;
+        Ok(result["rows"].as_array().cloned().unwrap_or_default())
+    };
+
+    let nodeos_rows = get_rows(true)?;
+    let binary_rows = get_rows(false)?;
+    assert_eq!(nodeos_rows.len(), test_rows().len());
+    assert_eq!(binary_rows.len(), nodeos_rows.len());
+
+    for (bin, nodeos_row) in binary_rows.iter().zip(nodeos_rows) {
+        let hex = bin.as_str().ok_or_else(|| eyre!("binary row is not a hex string: {bin}"))?;
+        let kudu_row = node.abi.binary_to_variant("alltypes", Bytes::from_hex(hex)?)?;
+        assert_eq!(kudu_row, nodeos_row, "decoded row differs for: {hex}");
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+// SPDX-FileCopyrightText: 2026 DigiGaia SCCL
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+#include <eosio/eosio.hpp>
+#include <eosio/asset.hpp>
+#include <eosio/crypto.hpp>
+#include <eosio/time.hpp>
+#include <eosio/varint.hpp>
+
+#include <optional>
+#include <string>
+#include <variant>
+#include <vector>
+
+using namespace eosio;
+
+// Test contract used to check that `kudu` and `nodeos` agree on how data is serialized.
+// The `store` action saves its argument as is in the `rows` table, so that both its
+// binary and JSON representations can be retrieved using `get_table_rows`.
+class [[eosio::contract("kudutest")]] kudutest : public contract {
+public:
+    using contract::contract;
+
+    struct [[eosio::table]] alltypes {
+        uint64_t                            id;
+        bool                                b;
+        int8_t                              i8;
+        uint8_t                             u8;
+        int16_t                             i16;
+        uint16_t                            u16;
+        int32_t                             i32;
+        uint32_t                            u32;
+        int64_t                             i64;
+        uint64_t                            u64;
+        int128_t                            i128;
+        uint128_t                           u128;
+        signed_int                          vi32;
+        unsigned_int                        vu32;
+        float                               f32;
+        double                              f64;
+        time_point                          tp;
+        time_point_sec                      tps;
+        block_timestamp                     bts;
+        name                                n;
+        std::vector<char>                   bytes;
+        std::string                         s;
+        checksum160                         c160;
+        checksum256                         c256;
+        checksum512                         c512;
+        public_key                          pubkey;
+        signature                           sig;
+        symbol                              sym;
+        symbol_code                         symcode;
+        asset                               a;
+        extended_asset                      ea;
+        std::optional<uint32_t>             opt;
+        std::variant<uint64_t, std::string> var;
+        std::vector<name>                   names;
+
+        uint64_t primary_key() const { return id; }
+    };
+
+    using rows_table = multi_index<"rows"_n, alltypes>;
+
+    [[eosio::action]]
+    void store(const alltypes& row) {
+        require_auth(get_self());
+        rows_table rows(get_self(), get_self().value);
+        auto it = rows.find(row.id);
+        if (it == rows.end()) {
+            rows.emplace(get_self(), [&](auto& r) { r = row; });
+        }
+        else {
+            rows.modify(it, get_self(), [&](auto& r) { r = row; });
+        }
+    }
+};